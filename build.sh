@@ -12,11 +12,12 @@ NC='\033[0m'
 # Create bin directory if it doesn't exist
 mkdir -p bin
 
-# Build daemon
+# Build daemon, stamped with the release so the CLI can tell which one it talks to
 echo -e "${BLUE}Building Go daemon...${NC}"
+DAEMON_LDFLAGS="-X main.Version=$(cat version.txt 2>/dev/null || echo dev)"
 # Run go mod tidy first to ensure dependencies are up to date
 if cd daemon/src && go mod tidy >/dev/null 2>&1; then
-    if go build -o ../../bin/port42d .; then
+    if go build -ldflags "$DAEMON_LDFLAGS" -o ../../bin/port42d .; then
         cd ../..
         echo -e "${GREEN}✅ Daemon built successfully${NC}"
     else
@@ -26,7 +27,7 @@ if cd daemon/src && go mod tidy >/dev/null 2>&1; then
     fi
 else
     # Try to build anyway - go mod tidy might fail but build might work
-    if go build -o ../../bin/port42d .; then
+    if go build -ldflags "$DAEMON_LDFLAGS" -o ../../bin/port42d .; then
         cd ../..
         echo -e "${GREEN}✅ Daemon built successfully${NC}"
     else
@@ -120,9 +121,14 @@ if [ "$PACKAGE_BINARIES" = "true" ]; then
     
     if tar -czf "releases/${PACKAGE_NAME}" "${files_to_package[@]}" 2>/dev/null; then
         
-        # Create symlink to latest
+        # Create symlink to latest, and the checksum 'port42 upgrade' verifies
         cd releases
         ln -sf "${PACKAGE_NAME}" "${LATEST_NAME}"
+        if command -v sha256sum >/dev/null 2>&1; then
+            sha256sum "${PACKAGE_NAME}" > "${PACKAGE_NAME}.sha256"
+        else
+            shasum -a 256 "${PACKAGE_NAME}" > "${PACKAGE_NAME}.sha256"
+        fi
         cd ..
         
         echo -e "${GREEN}✅ Release package created: releases/${PACKAGE_NAME}${NC}"
@@ -160,7 +166,7 @@ if [ "$PACKAGE_BINARIES" = "true" ] && [ -f "releases/${PACKAGE_NAME}" ]; then
     echo "  ./install.sh --binaries releases/${LATEST_NAME}"
     echo
     echo -e "${BLUE}To create GitHub release:${NC}"
-    echo "  gh release create v${VERSION} releases/${PACKAGE_NAME} \\"
+    echo "  gh release create v${VERSION} releases/${PACKAGE_NAME} releases/${PACKAGE_NAME}.sha256 \\"
     echo "    --title \"Port42 v${VERSION}\" \\"
     echo "    --notes \"Release v${VERSION} with ${PLATFORM} binaries\""
     echo
@@ -0,0 +1,150 @@
+//! Locally defined agents and shareable agent packs
+//!
+//! Agent definitions (description, persona, guidance and default references)
+//! live in `~/.port42/agents.toml`. Model settings stay in `config.toml` under
+//! `[agents]`, so an agent pack is split across both files on import. Custom
+//! agents are also registered with the daemon when it supports it.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::{port42_dir, AgentDefaults};
+
+const AGENTS_FILE: &str = "agents.toml";
+
+/// The agents the daemon ships with
+pub const BUILTIN_AGENTS: &[&str] = &["@ai-engineer", "@ai-muse", "@ai-analyst", "@ai-founder"];
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AgentDefinition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Who the agent is. Custom agents need one; the daemon has its own for
+    /// the built-in agents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persona: Option<String>,
+
+    /// Extra instructions sent with every message to this agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guidance: Option<String>,
+
+    /// References loaded into every session, e.g. "p42:/commands/analyzer"
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub default_refs: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgentRegistry {
+    /// Definitions keyed by agent name (e.g. "@ai-reviewer")
+    pub agents: BTreeMap<String, AgentDefinition>,
+}
+
+impl AgentRegistry {
+    /// Load definitions from ~/.port42/agents.toml
+    pub fn load() -> Result<Self> {
+        let path = agents_path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid agent definitions in {}", path.display()))
+    }
+
+    /// Load definitions, warning and falling back to none on errors
+    pub fn load_or_default() -> Self {
+        match Self::load() {
+            Ok(registry) => registry,
+            Err(e) => {
+                eprintln!("⚠️  {:#}", e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = agents_path();
+        fs::create_dir_all(port42_dir())?;
+        fs::write(&path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn get(&self, agent: &str) -> Option<&AgentDefinition> {
+        self.agents.get(&normalize_agent_name(agent))
+    }
+
+    /// Built-in agents plus any defined locally
+    pub fn is_known(&self, agent: &str) -> bool {
+        is_builtin(agent) || self.get(agent).is_some()
+    }
+
+    /// Persona and guidance combined, as sent with each message
+    pub fn instructions(&self, agent: &str) -> Option<String> {
+        let definition = self.get(agent)?;
+        match (&definition.persona, &definition.guidance) {
+            (Some(persona), Some(guidance)) => Some(format!("{}\n\n{}", persona, guidance)),
+            (persona, guidance) => persona.clone().or_else(|| guidance.clone()),
+        }
+    }
+}
+
+pub fn is_builtin(agent: &str) -> bool {
+    BUILTIN_AGENTS.contains(&normalize_agent_name(agent).as_str())
+}
+
+/// Everything needed to recreate an agent on another machine
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AgentPack {
+    pub agent: PackAgent,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<AgentDefaults>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PackAgent {
+    pub name: String,
+
+    #[serde(flatten)]
+    pub definition: AgentDefinition,
+}
+
+impl AgentPack {
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let mut pack: Self = toml::from_str(content).context("Invalid agent pack")?;
+        pack.agent.name = normalize_agent_name(&pack.agent.name);
+        validate_agent_name(&pack.agent.name)?;
+        Ok(pack)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
+
+/// Agent names are always stored with their leading '@'
+pub fn normalize_agent_name(name: &str) -> String {
+    format!("@{}", name.trim().trim_start_matches('@'))
+}
+
+pub fn validate_agent_name(name: &str) -> Result<()> {
+    let bare = name.trim_start_matches('@');
+    let valid = !bare.is_empty()
+        && bare.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        bail!("Invalid agent name '{}'. Use letters, digits, '-' and '_'", name);
+    }
+    Ok(())
+}
+
+pub fn agents_path() -> PathBuf {
+    port42_dir().join(AGENTS_FILE)
+}
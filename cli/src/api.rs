@@ -0,0 +1,167 @@
+//! Port 42 for other Rust programs
+//!
+//! `Client` talks to the daemon over tokio and returns the same typed
+//! results the CLI works with, without printing anything or reading the
+//! CLI's config:
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! let mut client = port42::Client::new(4242);
+//! let reply = client.possess("@ai-engineer", "what tools do I have for logs?").await?;
+//! println!("{}", reply.message);
+//! for entry in client.ls("/tools").await?.entries {
+//!     println!("{}", entry.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Failures come back as `anyhow::Error`s wrapping `Port42Error`, so callers
+//! can downcast to tell a missing daemon from a missing file. The daemon
+//! answers one request per connection, so every call opens its own.
+
+use anyhow::Result;
+use serde_json::Value;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use crate::client::{RequestKind, Timeouts};
+use crate::common::{errors::Port42Error, generate_id};
+use crate::protocol::{
+    CatRequest, CatResponse, DaemonRequest, DeclareRelationRequest, DeclareRelationResponse, InfoRequest,
+    InfoResponse, LsRequest, LsResponse, Relation, RequestBuilder, ResponseParser, SearchRequest,
+    SearchResponse, StatusRequest, StatusResponse, SwimRequest, SwimResponse,
+};
+use crate::types::Response;
+
+pub struct Client {
+    port: u16,
+    timeouts: Timeouts,
+}
+
+impl Client {
+    /// A client for the daemon on `port`; nothing connects until the first call
+    pub fn new(port: u16) -> Self {
+        Self { port, timeouts: Timeouts::default() }
+    }
+
+    /// How long to wait for replies, by request kind
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub async fn status(&mut self) -> Result<StatusResponse> {
+        self.call::<StatusResponse>(&StatusRequest).await
+    }
+
+    /// Send `message` to an agent in a new session
+    pub async fn possess(&mut self, agent: &str, message: &str) -> Result<SwimResponse> {
+        self.swim(&SwimRequest {
+            agent: agent.to_string(),
+            message: message.to_string(),
+            memory_context: None,
+            references: None,
+            approval_response: None,
+            provider: None,
+            guidance: None,
+            workspace: None,
+        }).await
+    }
+
+    /// Send a fully specified swim request, e.g. with references or a provider
+    pub async fn swim(&mut self, request: &SwimRequest) -> Result<SwimResponse> {
+        self.call::<SwimResponse>(request).await
+    }
+
+    /// Declare a tool and let the daemon generate it
+    pub async fn declare_tool(&mut self, name: &str, transforms: &[&str]) -> Result<DeclareRelationResponse> {
+        self.declare(&DeclareRelationRequest {
+            relation: Relation::new_tool(name, transforms.iter().map(|t| t.to_string()).collect()),
+            references: None,
+            user_prompt: None,
+            provider: None,
+            progress: false,
+            preview: false,
+        }).await
+    }
+
+    pub async fn declare(&mut self, request: &DeclareRelationRequest) -> Result<DeclareRelationResponse> {
+        self.call::<DeclareRelationResponse>(request).await
+    }
+
+    pub async fn search(&mut self, query: &str) -> Result<SearchResponse> {
+        self.search_with(&SearchRequest::new(query.to_string())).await
+    }
+
+    /// Search with filters, e.g. `SearchRequest::new(q).with_filters(...)`
+    pub async fn search_with(&mut self, request: &SearchRequest) -> Result<SearchResponse> {
+        self.call::<SearchResponse>(request).await
+    }
+
+    pub async fn ls(&mut self, path: &str) -> Result<LsResponse> {
+        self.call::<LsResponse>(&LsRequest { path: path.to_string() }).await
+    }
+
+    pub async fn cat(&mut self, path: &str) -> Result<CatResponse> {
+        self.call::<CatResponse>(&CatRequest { path: path.to_string(), version: None }).await
+    }
+
+    pub async fn info(&mut self, path: &str) -> Result<InfoResponse> {
+        self.call::<InfoResponse>(&InfoRequest { path: path.to_string(), version: None }).await
+    }
+
+    /// Build, send and parse one request; a daemon error becomes an `Err`
+    pub async fn call<P: ResponseParser>(&mut self, request: &dyn RequestBuilder) -> Result<P::Output> {
+        let response = self.request(request.build_request(generate_id())?).await?;
+        if !response.success {
+            let error = response.error.unwrap_or_else(|| "Unknown daemon error".to_string());
+            return Err(Port42Error::from_daemon(&error).into());
+        }
+        P::parse_response(&response.data.unwrap_or(Value::Null))
+    }
+
+    /// Send any request and return the daemon's response as it came, errors included
+    pub async fn request(&mut self, request: DaemonRequest) -> Result<Response> {
+        let timeout = self.timeouts.for_kind(RequestKind::of(&request.request_type));
+        tokio::time::timeout(timeout, exchange(self.port, &request)).await
+            .unwrap_or_else(|_| Err(Port42Error::Timeout(format!(
+                "No reply within {}s to {}", timeout.as_secs(), request.request_type
+            )).into()))
+    }
+}
+
+/// One request on a fresh connection, which the daemon closes after replying
+async fn exchange(port: u16, request: &DaemonRequest) -> Result<Response> {
+    let stream = tokio::time::timeout(Duration::from_secs(2), TcpStream::connect(("127.0.0.1", port))).await
+        .map_err(|_| Port42Error::Connection(format!("Timed out connecting to the daemon on port {}", port)))?
+        .map_err(|e| Port42Error::Connection(format!("Can't reach the daemon on port {}: {}", port, e)))?;
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let mut json = serde_json::to_string(request)?;
+    json.push('\n');
+    writer.write_all(json.as_bytes()).await
+        .map_err(|e| Port42Error::Connection(format!("Lost the daemon on port {}: {}", port, e)))?;
+
+    // Stream chunks, status lines and events come without "success"
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).await
+            .map_err(|e| Port42Error::Connection(format!("Lost the daemon on port {}: {}", port, e)))?;
+        if read == 0 {
+            return Err(Port42Error::Connection(format!("The daemon on port {} hung up", port)).into());
+        }
+        let value: Value = serde_json::from_str(&line)
+            .map_err(|e| Port42Error::Daemon(format!("Invalid response from daemon: {}", e)))?;
+        if value.get("success").is_some() {
+            return Ok(serde_json::from_value(value)?);
+        }
+    }
+}
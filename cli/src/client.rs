@@ -1,11 +1,19 @@
 use anyhow::{anyhow, Result};
 use colored::*;
 use std::io::{BufRead, BufReader, Write};
-use std::net::{TcpStream, SocketAddr};
 use std::time::{Duration, Instant};
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::{debug, trace, warn};
 
-use crate::protocol::DaemonRequest;
+use crate::common::endpoint::{Endpoint, Transport};
+use crate::common::errors::Port42Error;
+use crate::common::{logging, recording};
+use crate::help_text::format_error_with_suggestion;
+use crate::protocol::{DaemonRequest, GenerationStatus, RequestBuilder, ResponseParser, StatusRequest, StatusResponse};
+use crate::protocol::hello::{HelloRequest, HelloResponse};
+use crate::protocol::events::DaemonEvent;
 use crate::types::Response; // Keep old Response for now
 
 // Track recursion depth to prevent stack overflow
@@ -24,22 +32,257 @@ impl Drop for RecursionGuard {
     }
 }
 
+/// How requests are resent when the connection drops or times out mid-exchange
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Resends after the first attempt; 0 disables retrying
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(4),
+        }
+    }
+}
+
+static RETRY_POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+
+impl RetryPolicy {
+    /// The policy new clients use: `[retry]` in config, with PORT42_RETRIES
+    /// (or `--retries`, via `set_retries`) overriding the retry count
+    pub fn current() -> Self {
+        *RETRY_POLICY.get_or_init(|| {
+            let mut policy = Self::from_config();
+            if let Some(retries) = std::env::var("PORT42_RETRIES").ok().and_then(|v| v.parse().ok()) {
+                policy.max_retries = retries;
+            }
+            policy
+        })
+    }
+
+    fn from_config() -> Self {
+        let config = crate::config::Config::load_or_default().retry.unwrap_or_default();
+        let defaults = Self::default();
+        Self {
+            max_retries: config.max_retries.unwrap_or(defaults.max_retries),
+            base_delay: config.base_delay_ms.map_or(defaults.base_delay, Duration::from_millis),
+            max_delay: config.max_delay_ms.map_or(defaults.max_delay, Duration::from_millis),
+        }
+    }
+
+    /// Exponential backoff before the given (zero-based) retry
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.base_delay.saturating_mul(1 << retry.min(16)).min(self.max_delay)
+    }
+}
+
+/// Override the retry count for every client created from now on. Call
+/// before the first client is made; later calls are ignored.
+pub fn set_retries(max_retries: u32) {
+    let _ = RETRY_POLICY.set(RetryPolicy { max_retries, ..RetryPolicy::from_config() });
+}
+
+/// What a request does, which decides how long to wait for its reply
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestKind {
+    /// Generation by an AI provider; can take minutes
+    Ai,
+    /// Reading and writing the virtual filesystem
+    Vfs,
+    Search,
+    Other,
+}
+
+impl RequestKind {
+    pub fn of(request_type: &str) -> Self {
+        match request_type {
+            "swim" | "possess" | "declare_relation" | "embed" => RequestKind::Ai,
+            "list_path" | "read_path" | "get_metadata" | "store_path" | "copy_path" | "move_path"
+            | "delete_path" | "trash_path" | "restore_path" | "import_object" => RequestKind::Vfs,
+            "search" => RequestKind::Search,
+            _ => RequestKind::Other,
+        }
+    }
+
+    /// Requests that only read, so sending one twice does no harm
+    pub fn is_read_only(request_type: &str) -> bool {
+        matches!(request_type,
+            "ping" | "hello" | "status" | "list_path" | "read_path" | "get_metadata" | "search" | "memory"
+            | "context" | "list_agents" | "list_jobs" | "job_status" | "list_notes" | "list_providers"
+            | "list_rules" | "list_versions" | "metrics" | "models" | "usage" | "session_metadata"
+            | "get_last_session" | "command_stats" | "resolve_references" | "embed")
+    }
+}
+
+/// How long to wait for a reply, by request kind
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timeouts {
+    pub ai: Duration,
+    pub vfs: Duration,
+    pub search: Duration,
+    pub other: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            ai: Duration::from_secs(300), // matches the daemon's own AI timeout
+            vfs: Duration::from_secs(30),
+            search: Duration::from_secs(60),
+            other: Duration::from_secs(30),
+        }
+    }
+}
+
+static TIMEOUTS: OnceLock<Timeouts> = OnceLock::new();
+
+/// Set by `--timeout`; wins over every other source, including timeouts
+/// commands pick for themselves
+static FORCED_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+impl Timeouts {
+    /// The timeouts new clients use: `[timeouts]` in config, then
+    /// PORT42_TIMEOUT for every kind, then PORT42_TIMEOUT_AI, _VFS and _SEARCH
+    pub fn current() -> Self {
+        *TIMEOUTS.get_or_init(|| {
+            let config = crate::config::Config::load_or_default().timeouts.unwrap_or_default();
+            Self::default().layered(&config, |name| std::env::var(name).ok())
+        })
+    }
+
+    /// Apply config, then environment overrides (looked up through `env`), on top of `self`
+    pub fn layered(self, config: &crate::config::TimeoutConfig, env: impl Fn(&str) -> Option<String>) -> Self {
+        let secs = |name: &str| env(name).and_then(|v| v.trim().parse::<u64>().ok()).map(Duration::from_secs);
+        let all = secs("PORT42_TIMEOUT");
+        let pick = |default: Duration, configured: Option<u64>, var: &str| {
+            secs(var).or(all).or(configured.map(Duration::from_secs)).unwrap_or(default)
+        };
+        Self {
+            ai: pick(self.ai, config.ai_secs, "PORT42_TIMEOUT_AI"),
+            vfs: pick(self.vfs, config.vfs_secs, "PORT42_TIMEOUT_VFS"),
+            search: pick(self.search, config.search_secs, "PORT42_TIMEOUT_SEARCH"),
+            other: pick(self.other, config.other_secs, "PORT42_TIMEOUT_OTHER"),
+        }
+    }
+
+    pub fn for_kind(&self, kind: RequestKind) -> Duration {
+        if let Some(forced) = FORCED_TIMEOUT.get() {
+            return *forced;
+        }
+        match kind {
+            RequestKind::Ai => self.ai,
+            RequestKind::Vfs => self.vfs,
+            RequestKind::Search => self.search,
+            RequestKind::Other => self.other,
+        }
+    }
+}
+
+/// Wait this long for every reply, whatever its kind. Call before the first
+/// client is made; later calls are ignored.
+pub fn set_timeout(timeout: Duration) {
+    let _ = FORCED_TIMEOUT.set(timeout);
+}
+
+/// Set by `--profile`: the daemon to talk to when it isn't on this machine
+static ENDPOINT: OnceLock<Endpoint> = OnceLock::new();
+
+/// Talk to the daemon at `endpoint` instead of on this machine. Call before
+/// the first client is made; later calls are ignored.
+pub fn set_endpoint(endpoint: Endpoint) {
+    let _ = ENDPOINT.set(endpoint);
+}
+
+/// Where clients connect: the profile's daemon, or this machine's
+pub fn endpoint() -> Endpoint {
+    ENDPOINT.get().cloned().unwrap_or_default()
+}
+
+/// What each daemon said to `hello`, by port; asked once per run
+static HANDSHAKES: OnceLock<Mutex<HashMap<u16, HelloResponse>>> = OnceLock::new();
+
+/// Set by `--strict`: refuse to send requests to a daemon we can't understand
+static STRICT: OnceLock<bool> = OnceLock::new();
+
+/// Refuse, rather than warn about, daemons whose protocol doesn't match.
+/// Call before the first client is made; later calls are ignored.
+pub fn set_strict(strict: bool) {
+    let _ = STRICT.set(strict);
+}
+
+/// What the daemon on `port` said when this run first connected, if it has
+pub fn handshake(port: u16) -> Option<HelloResponse> {
+    handshakes().get(&port).cloned()
+}
+
+fn handshakes() -> std::sync::MutexGuard<'static, HashMap<u16, HelloResponse>> {
+    HANDSHAKES.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Transport failures worth a fresh connection and another try, for
+/// requests that are safe to send twice
+fn is_transient(kind: std::io::ErrorKind) -> bool {
+    use std::io::ErrorKind::*;
+    matches!(kind, ConnectionReset | ConnectionAborted | BrokenPipe | TimedOut | WouldBlock | UnexpectedEof | Interrupted)
+}
+
+fn describe_transient(kind: std::io::ErrorKind) -> &'static str {
+    use std::io::ErrorKind::*;
+    match kind {
+        TimedOut | WouldBlock => "Daemon timed out",
+        Interrupted => "Request interrupted",
+        _ => "Connection dropped",
+    }
+}
+
+/// Why one attempt at a request failed
+enum Failure {
+    /// Nothing was listening, so the daemon never saw the request; any request may be retried
+    Refused(anyhow::Error),
+    /// The socket failed while `context`; read-only requests may be retried
+    Transport(std::io::Error, &'static str),
+    Other(anyhow::Error),
+}
+
 pub struct DaemonClient {
     port: u16,
-    stream: Option<TcpStream>,
-    reader: Option<BufReader<TcpStream>>,
+    endpoint: Endpoint,
+    stream: Option<Transport>,
+    reader: Option<BufReader<Transport>>,
     connection_timeout: Duration,
-    request_timeout: Duration,
+    timeouts: Timeouts,
+    /// Set for the duration of `request_timeout`
+    timeout_override: Option<Duration>,
+    /// What the request in flight is waiting for, for error messages
+    active_timeout: Duration,
+    retry: RetryPolicy,
+    /// Whether the last failed connect was refused outright
+    refused: bool,
+    /// Whether any of the last request reached the socket, so the daemon
+    /// may have acted on it even if no answer came back
+    wrote_request: bool,
 }
 
 impl DaemonClient {
     pub fn new(port: u16) -> Self {
         Self {
             port,
+            endpoint: endpoint(),
             stream: None,
             reader: None,
             connection_timeout: Duration::from_secs(2),
-            request_timeout: Duration::from_secs(300), // 5 minutes for AI requests - matches daemon timeout
+            timeouts: Timeouts::current(),
+            timeout_override: None,
+            active_timeout: Timeouts::current().other,
+            retry: RetryPolicy::current(),
+            refused: false,
+            wrote_request: false,
         }
     }
     
@@ -47,6 +290,16 @@ impl DaemonClient {
         self.port
     }
     
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+    
+    /// Drop the connection; the next request opens a fresh one
+    pub fn disconnect(&mut self) {
+        self.stream = None;
+        self.reader = None;
+    }
+    
     /// Ensure we have a valid connection to the daemon
     pub fn ensure_connected(&mut self) -> Result<()> {
         // Guard against recursion
@@ -55,9 +308,7 @@ impl DaemonClient {
         // Create guard immediately after incrementing
         let _guard = RecursionGuard;
         
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: ensure_connected: Recursion depth = {}", depth);
-        }
+        debug!("ensure_connected: Recursion depth = {}", depth);
         
         // Prevent stack overflow from recursive calls
         if depth > 3 {
@@ -67,31 +318,33 @@ impl DaemonClient {
         // Check if we already have a connection
         if self.stream.is_some() {
             // Test if still alive with a quick ping
-            if std::env::var("PORT42_DEBUG").is_ok() {
-                eprintln!("DEBUG: ensure_connected: Testing existing connection with ping");
-            }
+            debug!("ensure_connected: Testing existing connection with ping");
             if self.ping().is_ok() {
                 return Ok(());
             }
             // Connection is dead, reset
-            if std::env::var("PORT42_DEBUG").is_ok() {
-                eprintln!("DEBUG: ensure_connected: Connection dead, resetting");
-            }
+            debug!("ensure_connected: Connection dead, resetting");
             self.stream = None;
             self.reader = None;
         }
         
-        // Try to connect
-        let addr: SocketAddr = format!("127.0.0.1:{}", self.port).parse()?;
-        
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: ensure_connected: Creating NEW connection to {}", addr);
+        if handshake(self.port).is_none() {
+            self.greet()?;
         }
         
-        match TcpStream::connect_timeout(&addr, self.connection_timeout) {
+        // Try to connect
+        self.connect()
+    }
+    
+    fn connect(&mut self) -> Result<()> {
+        debug!("ensure_connected: Creating NEW connection to {}", self.endpoint.describe(self.port));
+        
+        let connected = self.endpoint.connect(self.port, self.connection_timeout);
+        self.refused = connected.as_ref().is_err_and(|e| e.kind() == std::io::ErrorKind::ConnectionRefused);
+        match connected {
             Ok(stream) => {
                 // Set timeouts on the stream
-                stream.set_read_timeout(Some(self.request_timeout))?;
+                stream.set_read_timeout(Some(self.active_timeout))?;
                 stream.set_write_timeout(Some(Duration::from_secs(5)))?;
                 
                 // Clone for the reader
@@ -107,111 +360,288 @@ impl DaemonClient {
         }
     }
     
+    /// Swap versions with a daemon we haven't met this run, warning once if
+    /// we can't be sure of understanding each other. The daemon answers one
+    /// request per connection, so `hello` gets a connection of its own.
+    fn greet(&mut self) -> Result<()> {
+        self.connect()?;
+        let hello = self.hello();
+        self.disconnect();
+        let hello = match hello {
+            Ok(Some(hello)) => hello,
+            // Without a handshake, the release the daemon reports is all there is to go on
+            Ok(None) => HelloResponse { version: self.legacy_version(), ..HelloResponse::legacy() },
+            Err(e) => {
+                debug!("hello failed, assuming a daemon from before the handshake: {:#}", e);
+                HelloResponse::legacy()
+            }
+        };
+        debug!("hello: daemon {:?} speaks protocol {}", hello.version, hello.protocol);
+        if let Some(problem) = hello.incompatibility() {
+            if !STRICT.get().copied().unwrap_or(false) {
+                eprintln!("{}", format!("⚠️  {}", problem.reason).yellow());
+                eprintln!("{}", format!("💡 {} (or pass --strict to stop here)", problem.fix).dimmed());
+            }
+        }
+        handshakes().insert(self.port, hello);
+        Ok(())
+    }
+    
+    /// Send `hello` straight down the greeting connection; it isn't traced or
+    /// recorded. None when the daemon answered that it doesn't know `hello`.
+    fn hello(&mut self) -> Result<Option<HelloResponse>> {
+        let response = self.untraced(HelloRequest::current().build_request("hello".to_string())?)?;
+        match (response.success, response.data) {
+            (true, Some(data)) => HelloResponse::parse_response(&data).map(Some),
+            (false, _) if response.error.as_deref().is_some_and(|e| e.contains("Unknown request type")) => Ok(None),
+            (_, data) => Err(anyhow!("Unexpected hello reply: {:?} {:?}", response.error, data)),
+        }
+    }
+    
+    /// The version a daemon from before `hello` gives in its status, on a
+    /// connection of its own
+    fn legacy_version(&mut self) -> Option<String> {
+        let status = self.connect()
+            .and_then(|_| self.untraced(StatusRequest.build_request("status".to_string())?));
+        self.disconnect();
+        let status = status.map_err(|e| debug!("status for the daemon's version failed: {:#}", e)).ok()?;
+        status.data.filter(|_| status.success)
+            .and_then(|data| StatusResponse::parse_response(&data).ok())
+            .and_then(|status| status.version)
+    }
+    
+    /// One request and its answer on the open connection, outside the retry
+    /// policy, tracing and recording
+    fn untraced(&mut self, request: DaemonRequest) -> Result<Response> {
+        let stream = self.stream.as_mut().ok_or_else(|| anyhow!("No stream for {}", request.request_type))?;
+        stream.set_read_timeout(Some(self.connection_timeout))?;
+        writeln!(stream, "{}", serde_json::to_string(&request)?)?;
+        stream.flush()?;
+        
+        let mut line = String::new();
+        if self.read_response_line(&mut line)? == 0 {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+    
+    /// Whether the last request got as far as being written to the daemon.
+    /// When it failed after that, the daemon may still have carried it out.
+    pub fn request_may_have_landed(&self) -> bool {
+        self.wrote_request
+    }
+    
+    /// Whether the daemon listed `feature` when we said hello, greeting it
+    /// first if this run hasn't yet
+    pub fn supports(&mut self, feature: &str) -> Result<bool> {
+        if handshake(self.port).is_none() {
+            self.greet()?;
+        }
+        Ok(handshake(self.port).is_some_and(|hello| hello.supports(feature)))
+    }
+    
+    /// Under `--strict`, stop before sending anything to a daemon whose protocol doesn't match ours
+    fn check_compatible(&mut self) -> Result<()> {
+        if !STRICT.get().copied().unwrap_or(false) {
+            return Ok(());
+        }
+        if let Some(problem) = handshake(self.port).and_then(|hello| hello.incompatibility()) {
+            self.disconnect();
+            return Err(Port42Error::Incompatible(format_error_with_suggestion(&problem.reason, problem.fix)).into());
+        }
+        Ok(())
+    }
+    
     /// Send a request and receive a response
     pub fn request(&mut self, request: DaemonRequest) -> Result<Response> {
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: request() called for type: {} (port {})", request.request_type, self.port);
+        self.exchange(request, &mut |_| {})
+    }
+    
+    /// Send a request whose reply may arrive as a stream: zero or more
+    /// `{"id": ..., "chunk": "text"}` lines, each passed to `on_chunk`,
+    /// followed by the usual response line. Daemons that don't stream
+    /// simply send the response line.
+    pub fn request_streaming(&mut self, request: DaemonRequest, on_chunk: &mut dyn FnMut(&str)) -> Result<Response> {
+        self.exchange(request, &mut |line| {
+            if let StreamLine::Chunk(chunk) = line {
+                on_chunk(&chunk);
+            }
+        })
+    }
+    
+    /// Send a request whose reply may be preceded by
+    /// `{"id": ..., "status": {"phase": ...}}` lines, each passed to
+    /// `on_status` as the daemon moves through the work
+    pub fn request_with_status(&mut self, request: DaemonRequest, on_status: &mut dyn FnMut(&GenerationStatus)) -> Result<Response> {
+        self.exchange(request, &mut |line| {
+            if let StreamLine::Status(status) = line {
+                on_status(&status);
+            }
+        })
+    }
+    
+    /// Send a `subscribe` request and pass each pushed event to `on_event`
+    /// until it returns false. The daemon acknowledges with a normal response
+    /// line, then sends `{"id": ..., "event": {...}}` lines for as long as the
+    /// connection stays open, so the connection is given over to the events
+    /// and dropped afterwards. A refused subscription is returned as the
+    /// daemon's error; a closed stream is a Connection error.
+    pub fn subscribe(&mut self, request: DaemonRequest, on_event: &mut dyn FnMut(DaemonEvent) -> bool) -> Result<()> {
+        let response = self.request(request)?;
+        if !response.success {
+            let error = response.error.unwrap_or_else(|| "Subscription refused".to_string());
+            return Err(Port42Error::from_daemon(&error).into());
+        }
+        
+        // Events come when they come; only a dead connection ends the wait
+        let result = self.read_events(on_event);
+        self.disconnect();
+        result
+    }
+    
+    fn read_events(&mut self, on_event: &mut dyn FnMut(DaemonEvent) -> bool) -> Result<()> {
+        self.stream.as_ref().unwrap().set_read_timeout(None)?;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let bytes_read = self.read_response_line(&mut line)
+                .map_err(|e| Port42Error::Connection(format!("Event stream failed: {}", e)))?;
+            if bytes_read == 0 {
+                return Err(Port42Error::Connection("The daemon closed the event stream".to_string()).into());
+            }
+            if let Some(event) = event_line(&line) {
+                if !on_event(event) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    
+    /// Run a request under the retry policy. A refused connection is always
+    /// tried again after a backoff. Other transport failures drop the
+    /// connection and resend only read-only requests, since the daemon may
+    /// already be working on anything else, and never once part of a
+    /// streamed reply was delivered.
+    fn exchange(&mut self, request: DaemonRequest, on_line: &mut dyn FnMut(StreamLine)) -> Result<Response> {
+        let resendable = RequestKind::is_read_only(&request.request_type);
+        self.wrote_request = false;
+        let mut retry = 0;
+        loop {
+            let mut streamed = false;
+            let reason = match self.exchange_once(&request, on_line, &mut streamed) {
+                Ok(response) => return Ok(response),
+                Err(Failure::Refused(_)) if retry < self.retry.max_retries => "Connection refused",
+                Err(Failure::Transport(err, _)) if is_transient(err.kind()) && resendable && retry < self.retry.max_retries && !streamed => {
+                    describe_transient(err.kind())
+                }
+                Err(Failure::Refused(err)) => return Err(err),
+                Err(Failure::Transport(err, context)) => return Err(self.enhance_io_error(err, context)),
+                Err(Failure::Other(err)) => return Err(err),
+            };
+            // A fresh connection, so a late reply to this attempt can't be read as the next one's
+            self.disconnect();
+            let delay = self.retry.delay(retry);
+            retry += 1;
+            eprintln!("{}", format!(
+                "↻ {} - retrying {} in {:.1}s ({} of {})",
+                reason, request.request_type, delay.as_secs_f32(), retry, self.retry.max_retries
+            ).dimmed());
+            std::thread::sleep(delay);
         }
-        self.ensure_connected()?;
+    }
+    
+    fn exchange_once(&mut self, request: &DaemonRequest, on_line: &mut dyn FnMut(StreamLine), streamed: &mut bool) -> std::result::Result<Response, Failure> {
+        debug!("request() called for type: {} (port {})", request.request_type, self.port);
+        self.refused = false;
+        self.ensure_connected().map_err(|e| if self.refused { Failure::Refused(e) } else { Failure::Other(e) })?;
+        self.check_compatible().map_err(Failure::Other)?;
         
         let start = Instant::now();
         
         // Send request
         let stream = self.stream.as_mut().unwrap();
-        let json = serde_json::to_string(&request)?;
-        
-        if std::env::var("PORT42_VERBOSE").is_ok() {
-            eprintln!("{} {}", "→ Request:".dimmed(), json.dimmed());
-        }
+        self.active_timeout = match (FORCED_TIMEOUT.get(), self.timeout_override) {
+            (None, Some(timeout)) => timeout,
+            _ => self.timeouts.for_kind(RequestKind::of(&request.request_type)),
+        };
+        stream.set_read_timeout(Some(self.active_timeout)).map_err(|e| Failure::Other(e.into()))?;
+        let json = serde_json::to_string(request).map_err(|e| Failure::Other(e.into()))?;
         
-        stream.write_all(json.as_bytes())?;
-        stream.write_all(b"\n")?;
-        stream.flush()?;
+        self.wrote_request = true;
+        stream.write_all(json.as_bytes())
+            .and_then(|_| stream.write_all(b"\n"))
+            .and_then(|_| stream.flush())
+            .map_err(|e| Failure::Transport(e, "sending request"))?;
         
-        // Read response (line-based protocol)
-        let reader = self.reader.as_mut().unwrap();
+        // Read response (line-based protocol), passing stream chunks and status lines along
         let mut line = String::new();
-        
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: About to read response line");
-        }
-        
-        // Retry on EAGAIN (Resource temporarily unavailable)
-        let mut retry_count = 0;
         let bytes_read = loop {
-            match reader.read_line(&mut line) {
-                Ok(bytes) => break bytes,
-                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock && retry_count < 3 => {
-                    if std::env::var("PORT42_DEBUG").is_ok() {
-                        eprintln!("DEBUG: Got EAGAIN, retry {} of 3", retry_count + 1);
-                    }
-                    retry_count += 1;
-                    std::thread::sleep(Duration::from_millis(10));
-                    continue;
+            line.clear();
+            let bytes_read = self.read_response_line(&mut line)
+                .map_err(|e| Failure::Transport(e, "reading response"))?;
+            match stream_line(&line) {
+                Some(StreamLine::Chunk(chunk)) => {
+                    *streamed = true;
+                    on_line(StreamLine::Chunk(chunk))
                 }
-                Err(e) => return Err(self.enhance_io_error(e, "reading response")),
+                Some(status) => on_line(status),
+                None => break bytes_read,
             }
         };
             
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: Read {} bytes, has_newline={}", bytes_read, line.ends_with('\n'));
-            if bytes_read == 0 {
-                eprintln!("DEBUG: Got 0 bytes - connection closed by daemon");
-            }
+        debug!("Read {} bytes, has_newline={}", bytes_read, line.ends_with('\n'));
+        if bytes_read == 0 {
+            debug!("Got 0 bytes - connection closed by daemon");
+            return Err(Failure::Transport(std::io::ErrorKind::UnexpectedEof.into(), "reading response"));
         }
         
         let elapsed = start.elapsed();
         
-        if std::env::var("PORT42_VERBOSE").is_ok() {
-            eprintln!("{} {} {:?}", "← Response:".dimmed(), 
-                     if line.len() > 200 { format!("{}...", &line[..200]) } else { line.clone() }.dimmed(),
-                     elapsed);
-        }
-        
-        // Debug: Check response size before parsing
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: Response line length: {} bytes", line.len());
-            if line.len() > 1000 {
-                eprintln!("DEBUG: Large response detected! First 200 chars: {}", &line[..200.min(line.len())]);
-            } else if line.len() < 100 && line.len() > 0 {
-                eprintln!("DEBUG: Small response: '{}'", line.trim());
+        trace!(
+            target: logging::REQUESTS_TARGET,
+            id = %request.id,
+            request_type = %request.request_type,
+            request = %json,
+            response = %line.trim_end(),
+            elapsed_ms = elapsed.as_millis() as u64,
+            "request"
+        );
+        debug!("Response line length: {} bytes", line.len());
+        if let Some(recorder) = recording::current() {
+            if let Err(e) = recorder.record(request, &line, elapsed) {
+                warn!("Couldn't record exchange: {:#}", e);
             }
         }
         
         // Parse response
         let response: Response = serde_json::from_str(&line)
-            .map_err(|e| anyhow!("Invalid response from daemon: {}\nRaw response: {}", e, 
-                               if line.len() > 200 { format!("{}...", &line[..200]) } else { line.clone() }))?;
+            .map_err(|e| Failure::Other(anyhow!("Invalid response from daemon: {}\nRaw response: {}", e, 
+                               if line.len() > 200 { format!("{}...", &line[..200]) } else { line.clone() })))?;
         
         Ok(response)
     }
     
-    /// Send a request with a custom timeout
-    pub fn request_timeout(&mut self, request: DaemonRequest, timeout: Duration) -> Result<Response> {
-        let old_timeout = self.request_timeout;
-        self.request_timeout = timeout;
+    fn read_response_line(&mut self, line: &mut String) -> std::io::Result<usize> {
+        let reader = self.reader.as_mut().unwrap();
         
-        // Update stream timeout if connected
-        if let Some(stream) = &self.stream {
-            stream.set_read_timeout(Some(timeout))?;
-        }
+        debug!("About to read response line");
         
+        // EAGAIN here is the read timeout expiring; the retry policy decides what happens next
+        reader.read_line(line)
+    }
+    
+    /// Send a request with a custom timeout instead of the one for its kind
+    /// (`--timeout` still wins)
+    pub fn request_timeout(&mut self, request: DaemonRequest, timeout: Duration) -> Result<Response> {
+        let old_timeout = self.timeout_override.replace(timeout);
         let result = self.request(request);
-        
-        // Restore timeout
-        self.request_timeout = old_timeout;
-        if let Some(stream) = &self.stream {
-            stream.set_read_timeout(Some(old_timeout))?;
-        }
-        
+        self.timeout_override = old_timeout;
         result
     }
     
     /// Test if the connection is still alive
     pub fn ping(&mut self) -> Result<()> {
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: ping() called");
-        }
+        debug!("ping() called");
         
         let req = DaemonRequest {
             request_type: "ping".to_string(),
@@ -220,6 +650,7 @@ impl DaemonClient {
             references: None,
             session_context: None,
             user_prompt: None,
+            provider: None,
         };
         
         // Don't use request_timeout as it might cause recursion
@@ -229,23 +660,17 @@ impl DaemonClient {
         
         // Try to write
         if let Err(e) = stream.write_all(json.as_bytes()) {
-            if std::env::var("PORT42_DEBUG").is_ok() {
-                eprintln!("DEBUG: ping write failed: {}", e);
-            }
+            debug!("ping write failed: {}", e);
             return Err(anyhow!("Ping write failed"));
         }
         
         if let Err(e) = stream.write_all(b"\n") {
-            if std::env::var("PORT42_DEBUG").is_ok() {
-                eprintln!("DEBUG: ping newline write failed: {}", e);
-            }
+            debug!("ping newline write failed: {}", e);
             return Err(anyhow!("Ping write failed"));
         }
         
         if let Err(e) = stream.flush() {
-            if std::env::var("PORT42_DEBUG").is_ok() {
-                eprintln!("DEBUG: ping flush failed: {}", e);
-            }
+            debug!("ping flush failed: {}", e);
             return Err(anyhow!("Ping flush failed"));
         }
         
@@ -255,15 +680,11 @@ impl DaemonClient {
         
         match reader.read_line(&mut line) {
             Ok(0) => {
-                if std::env::var("PORT42_DEBUG").is_ok() {
-                    eprintln!("DEBUG: ping read returned 0 bytes - connection closed");
-                }
+                debug!("ping read returned 0 bytes - connection closed");
                 Err(anyhow!("Connection closed"))
             }
             Ok(n) => {
-                if std::env::var("PORT42_DEBUG").is_ok() {
-                    eprintln!("DEBUG: ping read {} bytes: {}", n, line.trim());
-                }
+                debug!("ping read {} bytes: {}", n, line.trim());
                 // Just check if we got a response, don't parse it
                 if n > 0 {
                     Ok(())
@@ -272,9 +693,7 @@ impl DaemonClient {
                 }
             }
             Err(e) => {
-                if std::env::var("PORT42_DEBUG").is_ok() {
-                    eprintln!("DEBUG: ping read failed: {}", e);
-                }
+                debug!("ping read failed: {}", e);
                 Err(anyhow!("Ping read failed"))
             }
         }
@@ -294,6 +713,7 @@ impl DaemonClient {
             references: None,
             session_context: None,
             user_prompt: None,
+            provider: None,
         };
         
         let response = self.request(req)?;
@@ -318,14 +738,21 @@ impl DaemonClient {
         use std::io::ErrorKind;
         
         match err.kind() {
+            ErrorKind::ConnectionRefused if !self.endpoint.is_local() => {
+                Port42Error::Connection(format!(
+                    "{}\n\n{}",
+                    format!("🔌 Cannot reach the Port 42 daemon at {}", self.endpoint.describe(self.port)).red().bold(),
+                    "Check it's running there and listening beyond localhost".yellow()
+                )).into()
+            }
             ErrorKind::ConnectionRefused => {
-                anyhow!(
+                Port42Error::Connection(format!(
                     "{}\n\n{}\n\n{}\n  {}",
                     "🔌 Cannot connect to Port 42 daemon".red().bold(),
                     "The daemon is not running on port 42 or 4242".yellow(),
                     "To start the daemon:".bright_white(),
                     "port42 daemon start".bright_cyan()
-                )
+                )).into()
             }
             ErrorKind::PermissionDenied => {
                 anyhow!(
@@ -336,14 +763,17 @@ impl DaemonClient {
                 )
             }
             ErrorKind::TimedOut => {
-                anyhow!(
+                Port42Error::Timeout(format!(
                     "{}\n\n{}\n{}",
                     "⏱️  Connection timed out".red().bold(),
                     "The daemon might be busy or unresponsive.".yellow(),
                     "Try again in a moment.".dimmed()
-                )
+                )).into()
             }
-            _ => anyhow!("Connection failed: {}", err),
+            _ if !self.endpoint.is_local() => {
+                Port42Error::Connection(format!("Connection to {} failed: {}", self.endpoint.describe(self.port), err)).into()
+            }
+            _ => Port42Error::Connection(format!("Connection failed: {}", err)).into(),
         }
     }
     
@@ -352,19 +782,27 @@ impl DaemonClient {
         use std::io::ErrorKind;
         
         match err.kind() {
+            // A read timeout surfaces as EAGAIN on most unix systems
+            ErrorKind::TimedOut | ErrorKind::WouldBlock if context == "reading response" => {
+                Port42Error::Timeout(format!(
+                    "{}\n\n{}",
+                    format!("⏱️  No reply within {}s", self.active_timeout.as_secs()).red().bold(),
+                    "Allow longer with --timeout <secs>, PORT42_TIMEOUT or [timeouts] in ~/.port42/config.toml".yellow()
+                )).into()
+            }
             ErrorKind::UnexpectedEof => {
-                anyhow!(
+                Port42Error::Connection(format!(
                     "{}\n\n{}",
                     format!("🔌 Connection lost while {}", context).red().bold(),
                     "The daemon may have crashed or been stopped.".yellow()
-                )
+                )).into()
             }
             ErrorKind::TimedOut => {
-                anyhow!(
+                Port42Error::Timeout(format!(
                     "{}\n\n{}",
                     format!("⏱️  Timeout while {}", context).red().bold(),
                     "The operation took too long. The daemon might be processing another request.".yellow()
-                )
+                )).into()
             }
             ErrorKind::WouldBlock => {
                 anyhow!(
@@ -380,41 +818,121 @@ impl DaemonClient {
     
 }
 
+/// One connection shared by a long-running front end (the shell and its tab
+/// completion), so commands reuse it instead of reconnecting each time
+#[derive(Clone)]
+pub struct SharedClient {
+    client: Arc<Mutex<DaemonClient>>,
+    last_used: Arc<Mutex<Instant>>,
+}
+
+impl SharedClient {
+    pub fn new(port: u16) -> Self {
+        Self {
+            client: Arc::new(Mutex::new(DaemonClient::new(port))),
+            last_used: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Run `f` with the shared client
+    pub fn with<T>(&self, f: impl FnOnce(&mut DaemonClient) -> T) -> T {
+        let mut client = self.client.lock().unwrap_or_else(|e| e.into_inner());
+        let result = f(&mut client);
+        *self.last_used.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+        result
+    }
+
+    /// Ping the daemon whenever the connection has sat idle for `interval`.
+    /// A failed ping drops the connection, so the first command after a
+    /// daemon restart quietly connects to the new one. Stops when the
+    /// returned guard is dropped.
+    pub fn start_keepalive(&self, interval: Duration) -> KeepAlive {
+        let stop = Arc::new(AtomicBool::new(false));
+        let shared = self.clone();
+        let stopped = stop.clone();
+        std::thread::spawn(move || {
+            let tick = interval.min(Duration::from_secs(1));
+            while !stopped.load(Ordering::SeqCst) {
+                std::thread::sleep(tick);
+                let idle = shared.last_used.lock().map(|t| t.elapsed()).unwrap_or_default();
+                if idle < interval {
+                    continue;
+                }
+                // Never hold up a command that is using the connection
+                if let Ok(mut client) = shared.client.try_lock() {
+                    if client.is_connected() && client.ping().is_err() {
+                        debug!("keepalive ping failed, dropping connection");
+                        client.disconnect();
+                    }
+                }
+                if let Ok(mut last_used) = shared.last_used.lock() {
+                    *last_used = Instant::now();
+                }
+            }
+        });
+        KeepAlive { stop }
+    }
+}
+
+/// Stops a keepalive thread when dropped
+pub struct KeepAlive {
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for KeepAlive {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+/// A line sent ahead of the response
+enum StreamLine {
+    Chunk(String),
+    Status(GenerationStatus),
+}
+
+/// A stream chunk or status line, or None for a regular response line
+fn stream_line(line: &str) -> Option<StreamLine> {
+    if !line.contains("\"chunk\"") && !line.contains("\"status\"") {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    if value.get("success").is_some() {
+        return None;
+    }
+    if let Some(chunk) = value.get("chunk").and_then(|c| c.as_str()) {
+        return Some(StreamLine::Chunk(chunk.to_string()));
+    }
+    serde_json::from_value(value.get("status")?.clone()).ok().map(StreamLine::Status)
+}
+
+/// The event carried by a line of a subscription stream, if it is one
+fn event_line(line: &str) -> Option<DaemonEvent> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    serde_json::from_value(value.get("event")?.clone()).ok()
+}
+
 /// Helper function to detect which port the daemon is on using proper ping
 pub fn detect_daemon_port() -> Option<u16> {
-    if std::env::var("PORT42_DEBUG").is_ok() {
-        eprintln!("DEBUG: detect_daemon_port() called - starting port discovery");
-    }
+    debug!("detect_daemon_port() called - starting port discovery");
     
     // Try port 42 first - must actually test with ping, not just connect
-    if std::env::var("PORT42_DEBUG").is_ok() {
-        eprintln!("DEBUG: detect_daemon_port() - testing port 42");
-    }
+    debug!("detect_daemon_port() - testing port 42");
     let mut client_42 = DaemonClient::new(42);
     if client_42.ensure_connected().is_ok() && client_42.ping().is_ok() {
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: detect_daemon_port() - port 42 SUCCESS");
-        }
+        debug!("detect_daemon_port() - port 42 SUCCESS");
         return Some(42);
     }
-    if std::env::var("PORT42_DEBUG").is_ok() {
-        eprintln!("DEBUG: detect_daemon_port() - port 42 failed");
-    }
+    debug!("detect_daemon_port() - port 42 failed");
     
     // Try port 4242 - must actually test with ping, not just connect
-    if std::env::var("PORT42_DEBUG").is_ok() {
-        eprintln!("DEBUG: detect_daemon_port() - testing port 4242");
-    }
+    debug!("detect_daemon_port() - testing port 4242");
     let mut client_4242 = DaemonClient::new(4242);
     if client_4242.ensure_connected().is_ok() && client_4242.ping().is_ok() {
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: detect_daemon_port() - port 4242 SUCCESS");
-        }
+        debug!("detect_daemon_port() - port 4242 SUCCESS");
         return Some(4242);
     }
-    if std::env::var("PORT42_DEBUG").is_ok() {
-        eprintln!("DEBUG: detect_daemon_port() - port 4242 failed, returning None");
-    }
+    debug!("detect_daemon_port() - port 4242 failed, returning None");
     
     None
 }
\ No newline at end of file
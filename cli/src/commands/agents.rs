@@ -0,0 +1,245 @@
+use anyhow::{Context, Result, bail};
+use colored::*;
+use crate::AgentsAction;
+use crate::agents::{AgentDefinition, AgentPack, AgentRegistry, PackAgent, BUILTIN_AGENTS, is_builtin, normalize_agent_name, validate_agent_name};
+use crate::client::DaemonClient;
+use crate::common::{generate_id, errors::Port42Error, providers, references::parse_references};
+use crate::config::{self, AgentDefaults, Config};
+use crate::display::{Displayable, OutputFormat};
+use crate::protocol::agents::{
+    AgentDetail, AgentList, AgentSummary, ListAgentsRequest, ListAgentsResponse,
+    RegisterAgentRequest, UnregisterAgentRequest,
+};
+use crate::protocol::{RequestBuilder, ResponseParser};
+
+pub fn handle_agents(port: u16, action: AgentsAction, format: OutputFormat) -> Result<()> {
+    match action {
+        AgentsAction::List => list_agents(port, format),
+        AgentsAction::Show { agent } => show_agent(&agent, format),
+        AgentsAction::Add { name, description, persona, persona_file, guidance, refs, provider, model, force } => {
+            let persona = match persona_file {
+                Some(path) => Some(std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?),
+                None => persona,
+            };
+            let definition = AgentDefinition {
+                description,
+                persona: persona.map(|p| p.trim_end().to_string()).filter(|p| !p.is_empty()),
+                guidance,
+                default_refs: refs,
+            };
+            add_agent(port, &name, definition, AgentDefaults { provider, model, fallback: None }, force)
+        }
+        AgentsAction::Remove { agent } => remove_agent(port, &agent),
+        AgentsAction::Export { agent, output } => export_agent(&agent, output),
+        AgentsAction::Import { file, force } => import_agent(&file, force),
+    }
+}
+
+fn summary(name: &str, source: &str, description: Option<String>, config: &Config) -> AgentSummary {
+    let defaults = config.agent_defaults(name);
+    AgentSummary {
+        name: name.to_string(),
+        source: source.to_string(),
+        description,
+        provider: defaults.and_then(|d| d.provider.clone()),
+        model: defaults.and_then(|d| d.model.clone()),
+    }
+}
+
+fn list_agents(port: u16, format: OutputFormat) -> Result<()> {
+    let registry = AgentRegistry::load_or_default();
+    let config = Config::load_or_default();
+
+    let mut agents: Vec<AgentSummary> = BUILTIN_AGENTS.iter()
+        .map(|name| {
+            let description = registry.get(name).and_then(|d| d.description.clone());
+            summary(name, "built-in", description, &config)
+        })
+        .collect();
+    for (name, definition) in &registry.agents {
+        if !is_builtin(name) {
+            agents.push(summary(name, "custom", definition.description.clone(), &config));
+        }
+    }
+
+    // Agents registered from another machine only live in the daemon
+    for remote in daemon_agents(port) {
+        let name = normalize_agent_name(&remote.name);
+        if !agents.iter().any(|a| a.name == name) {
+            agents.push(summary(&name, "daemon", remote.description, &config));
+        }
+    }
+
+    AgentList(agents).display(format)
+}
+
+/// The daemon's registry, or nothing if it is down or predates agent registration
+fn daemon_agents(port: u16) -> Vec<crate::protocol::agents::RegisteredAgent> {
+    let mut client = DaemonClient::new(port);
+    let Ok(request) = ListAgentsRequest.build_request(generate_id()) else { return Vec::new() };
+    match client.request(request) {
+        Ok(response) if response.success => response.data
+            .and_then(|data| ListAgentsResponse::parse_response(&data).ok())
+            .map(|list| list.agents)
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn show_agent(agent: &str, format: OutputFormat) -> Result<()> {
+    let name = normalize_agent_name(agent);
+    let registry = AgentRegistry::load_or_default();
+    if !registry.is_known(&name) {
+        return Err(Port42Error::NotFound(format!("Unknown agent '{}'. See 'port42 agents list'", name)).into());
+    }
+    let definition = registry.get(&name).cloned().unwrap_or_default();
+    let source = if is_builtin(&name) { "built-in" } else { "custom" };
+    AgentDetail {
+        summary: summary(&name, source, definition.description, &Config::load_or_default()),
+        persona: definition.persona,
+        guidance: definition.guidance,
+        default_refs: definition.default_refs,
+    }.display(format)
+}
+
+fn add_agent(port: u16, agent: &str, definition: AgentDefinition, model: AgentDefaults, force: bool) -> Result<()> {
+    let name = normalize_agent_name(agent);
+    validate_agent_name(&name)?;
+    if is_builtin(&name) {
+        bail!("{} is a built-in agent. Use 'port42 agents import' to adjust its guidance", name);
+    }
+    if definition.persona.is_none() {
+        bail!("A custom agent needs a persona. Pass --persona or --persona-file");
+    }
+    if let Some(ref provider) = model.provider {
+        providers::validate_provider(provider)?;
+    }
+    if !definition.default_refs.is_empty() {
+        parse_references(definition.default_refs.clone(), false).context("Invalid --ref")?;
+    }
+
+    let mut registry = AgentRegistry::load()?;
+    if registry.agents.contains_key(&name) && !force {
+        bail!("Agent {} is already defined. Use --force to replace it", name);
+    }
+    let request = RegisterAgentRequest {
+        name: name.clone(),
+        description: definition.description.clone(),
+        persona: definition.persona.clone(),
+        guidance: definition.guidance.clone(),
+        default_refs: definition.default_refs.clone(),
+        provider: model.provider.clone(),
+        model: model.model.clone(),
+    };
+    registry.agents.insert(name.clone(), definition);
+    registry.save()?;
+
+    if model.provider.is_some() || model.model.is_some() {
+        config::set_agent_defaults(&name, &model)?;
+    } else if force {
+        config::remove_agent_defaults(&name)?;
+    }
+
+    println!("{}", format!("🤖 {} has joined the consciousness", name).green());
+    sync_with_daemon(port, request);
+    println!("{}", format!("Try it: port42 swim {} \"hello\"", name).dimmed());
+    Ok(())
+}
+
+fn remove_agent(port: u16, agent: &str) -> Result<()> {
+    let name = normalize_agent_name(agent);
+    if is_builtin(&name) {
+        bail!("{} is a built-in agent and cannot be removed", name);
+    }
+    let mut registry = AgentRegistry::load()?;
+    if registry.agents.remove(&name).is_none() {
+        bail!("No custom agent named {}", name);
+    }
+    registry.save()?;
+    config::remove_agent_defaults(&name)?;
+
+    println!("{}", format!("🌫️  {} has left the consciousness", name).green());
+    sync_with_daemon(port, UnregisterAgentRequest { name });
+    Ok(())
+}
+
+/// The local definition is the source of truth, so a daemon that can't be
+/// reached only earns a warning
+fn sync_with_daemon(port: u16, request: impl RequestBuilder) {
+    let result = request.build_request(generate_id())
+        .and_then(|req| DaemonClient::new(port).request(req));
+    match result {
+        Ok(response) if response.success => println!("  {}", "Synced with the daemon".dimmed()),
+        Ok(response) => eprintln!("  {} {}", "⚠️  Daemon registry not updated:".yellow(),
+            response.error.unwrap_or_else(|| "request rejected".to_string())),
+        Err(e) => eprintln!("  {} {}", "⚠️  Daemon registry not updated:".yellow(), e),
+    }
+}
+
+fn export_agent(agent: &str, output: Option<String>) -> Result<()> {
+    let name = normalize_agent_name(agent);
+    let registry = AgentRegistry::load_or_default();
+    if !registry.is_known(&name) {
+        return Err(Port42Error::NotFound(format!("Unknown agent '{}'. Nothing to export", name)).into());
+    }
+
+    let pack = AgentPack {
+        agent: PackAgent {
+            name: name.clone(),
+            definition: registry.get(&name).cloned().unwrap_or_default(),
+        },
+        model: Config::load_or_default().agent_defaults(&name).cloned(),
+    };
+    let content = pack.to_toml()?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path))?;
+            eprintln!("{}", format!("📦 Exported {} to {}", name, path).green());
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+fn import_agent(file: &str, force: bool) -> Result<()> {
+    let content = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file))?;
+    let pack = AgentPack::from_toml(&content)
+        .with_context(|| format!("Could not import {}", file))?;
+    let name = pack.agent.name.clone();
+
+    // Check everything before touching either file
+    if let Some(ref model) = pack.model {
+        if let Some(ref provider) = model.provider {
+            providers::validate_provider(provider)?;
+        }
+    }
+    if !pack.agent.definition.default_refs.is_empty() {
+        parse_references(pack.agent.definition.default_refs.clone(), false)
+            .with_context(|| format!("Invalid default_refs in {}", file))?;
+    }
+
+    let mut registry = AgentRegistry::load()?;
+    if registry.agents.contains_key(&name) && !force {
+        bail!("Agent {} is already defined. Use --force to replace it", name);
+    }
+    registry.agents.insert(name.clone(), pack.agent.definition);
+    registry.save()?;
+
+    if let Some(ref model) = pack.model {
+        config::set_agent_defaults(&name, model)?;
+    }
+
+    println!("{}", format!("📦 Imported {}", name).green());
+    if let Some(model) = pack.model {
+        let provider = model.provider.unwrap_or_else(|| "default provider".to_string());
+        match model.model {
+            Some(m) => println!("  Model: {} ({})", m.bright_cyan(), provider),
+            None => println!("  Provider: {}", provider.bright_cyan()),
+        }
+    }
+    println!("{}", format!("Try it: port42 swim {} \"hello\"", name).dimmed());
+    Ok(())
+}
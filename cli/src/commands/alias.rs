@@ -0,0 +1,146 @@
+use anyhow::{Context, Result};
+use clap::CommandFactory;
+use colored::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use crate::{AliasAction, Cli};
+use crate::common::aliases;
+use crate::common::errors::Port42Error;
+use crate::common::utils::split_words;
+use crate::config::{self, Config};
+use crate::display::{Displayable, OutputFormat, components::TableBuilder, print_yaml};
+
+pub fn handle_alias(action: AliasAction, format: OutputFormat) -> Result<()> {
+    match action {
+        AliasAction::List => list(format),
+        AliasAction::Add { name, command } => add(&name, &command.join(" ")),
+        AliasAction::Remove { name } => {
+            if !config::remove_alias(&name)? {
+                return Err(Port42Error::NotFound(format!("No alias named '{}'", name)).into());
+            }
+            println!("{}", format!("🗑️  Removed alias {}", name).yellow());
+            Ok(())
+        }
+    }
+}
+
+/// Every name an alias may not take: CLI subcommands, their aliases and shell commands
+pub fn builtins() -> Vec<String> {
+    let cli = Cli::command();
+    let mut names: Vec<String> = cli.get_subcommands()
+        .flat_map(|sub| std::iter::once(sub.get_name()).chain(sub.get_all_aliases()))
+        .chain(crate::shell::SHELL_COMMANDS.iter().copied())
+        .chain(["help"])
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Expand an alias standing in for the subcommand of a `port42 ...` command line
+pub fn expand_args(args: Vec<String>, aliases: &BTreeMap<String, String>) -> Result<Vec<String>> {
+    if aliases.is_empty() {
+        return Ok(args);
+    }
+    let Some(at) = subcommand_index(&args) else { return Ok(args) };
+    let mut expanded = args[..at].to_vec();
+    expanded.extend(aliases::expand(&args[at..], aliases, &builtins())?);
+    Ok(expanded)
+}
+
+/// Where the subcommand sits, past any global flags and their values
+fn subcommand_index(args: &[String]) -> Option<usize> {
+    let cli = Cli::command();
+    let takes_value = |flag: &str| cli.get_arguments().any(|arg| {
+        arg.get_action().takes_values() && (
+            arg.get_long().is_some_and(|long| flag == format!("--{}", long))
+            || arg.get_short().is_some_and(|short| flag == format!("-{}", short))
+        )
+    });
+    let mut i = 1;
+    while i < args.len() {
+        let arg = &args[i];
+        if !arg.starts_with('-') {
+            return Some(i);
+        }
+        i += if takes_value(arg) { 2 } else { 1 };
+    }
+    None
+}
+
+fn add(name: &str, command: &str) -> Result<()> {
+    aliases::check_name(name, &builtins())?;
+    let words = split_words(command).map_err(|e| Port42Error::Usage(e.to_string()))?;
+    if words.first().map(String::as_str) == Some(name) {
+        return Err(Port42Error::Usage(format!("Alias '{}' can't start with itself", name)).into());
+    }
+
+    let path = config::config_path();
+    let content = if path.exists() {
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let updated = config::set_in(&content, &format!("aliases.{}", name), toml_edit::Value::from(command))?;
+    std::fs::create_dir_all(config::port42_dir())?;
+    std::fs::write(&path, updated).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!("{}", format!("🔗 {} → {}", name, command).green());
+    Ok(())
+}
+
+/// One configured alias
+#[derive(Debug, Serialize)]
+pub struct AliasEntry {
+    pub name: String,
+    pub command: String,
+    /// A built-in command of the same name wins, so the alias never runs
+    pub shadowed: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct AliasListing(pub Vec<AliasEntry>);
+
+impl Displayable for AliasListing {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Table => {
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["Alias", "Command", "Shadowed"]);
+                for entry in &self.0 {
+                    let shadowed = if entry.shadowed { "yes" } else { "-" };
+                    table.add_row(vec![entry.name.clone(), entry.command.clone(), shadowed.to_string()]);
+                }
+                table.print();
+            }
+            OutputFormat::Plain => {
+                if self.0.is_empty() {
+                    println!("{}", "No aliases yet. Add one with 'port42 alias add sw swim @ai-engineer'".dimmed());
+                    return Ok(());
+                }
+                let width = self.0.iter().map(|e| e.name.len()).max().unwrap_or(0);
+                for entry in &self.0 {
+                    print!("{} {} {}", format!("{:<width$}", entry.name).bright_cyan(), "→".dimmed(), entry.command);
+                    if entry.shadowed {
+                        print!("  {}", "(shadowed by the built-in command)".yellow());
+                    }
+                    println!();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn list(format: OutputFormat) -> Result<()> {
+    let builtins = builtins();
+    let entries = Config::load_or_default().aliases
+        .into_iter()
+        .map(|(name, command)| AliasEntry { shadowed: builtins.contains(&name), name, command })
+        .collect();
+    AliasListing(entries).display(format)
+}
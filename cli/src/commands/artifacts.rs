@@ -0,0 +1,165 @@
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use crate::ArtifactsAction;
+use crate::client::DaemonClient;
+use crate::commands::tree::{self, TreeOptions};
+use crate::common::{generate_id, errors::Port42Error};
+use crate::common::archive::sha256_hex;
+use crate::common::utils::{create_private_dir_all, create_private_file};
+use crate::display::{Displayable, OutputFormat, highlight, markdown};
+use crate::help_text::*;
+use crate::protocol::{CatRequest, RequestBuilder, ResponseParser, TreeNode};
+use crate::protocol::artifacts::{ARTIFACTS_ROOT, ArtifactContent, ArtifactEntry, ArtifactList, artifact_path};
+use crate::protocol::file_ops::format_size;
+
+/// Programs that open a file in its default application: (program, args)
+#[cfg(target_os = "macos")]
+const OPENERS: &[(&str, &[&str])] = &[("open", &[])];
+#[cfg(windows)]
+const OPENERS: &[(&str, &[&str])] = &[("cmd", &["/C", "start", ""])];
+#[cfg(not(any(target_os = "macos", windows)))]
+const OPENERS: &[(&str, &[&str])] = &[("xdg-open", &[]), ("gio", &["open"])];
+
+pub fn handle_artifacts(port: u16, action: ArtifactsAction, format: OutputFormat) -> Result<()> {
+    let mut client = DaemonClient::new(port);
+    match action {
+        ArtifactsAction::List { filter, file_type } => list(&mut client, filter, file_type, format),
+        ArtifactsAction::Show { name, raw } => show(&mut client, &name, raw, format),
+        ArtifactsAction::Open { name } => open(&mut client, &name),
+    }
+}
+
+fn list(client: &mut DaemonClient, filter: Option<String>, file_type: Option<String>, format: OutputFormat) -> Result<()> {
+    let options = TreeOptions { level: None, types: Vec::new(), sizes: false };
+    let root = tree::load(client, ARTIFACTS_ROOT, &options)?;
+
+    let mut artifacts = Vec::new();
+    collect(&root, &mut artifacts);
+    let filter = filter.map(|f| f.to_lowercase());
+    let file_type = file_type.map(|t| t.trim_start_matches('.').to_lowercase());
+    artifacts.retain(|a: &ArtifactEntry| {
+        filter.as_ref().is_none_or(|f| a.path.to_lowercase().contains(f))
+            && file_type.as_ref().is_none_or(|t| a.file_type == *t || a.collection.to_lowercase() == *t)
+    });
+    artifacts.sort_by(|a, b| (&a.collection, &a.path).cmp(&(&b.collection, &b.path)));
+
+    ArtifactList { artifacts }.display(format)
+}
+
+fn collect(node: &TreeNode, out: &mut Vec<ArtifactEntry>) {
+    for child in &node.children {
+        if child.is_dir() {
+            collect(child, out);
+        } else {
+            out.push(ArtifactEntry::new(child.path.clone(), child.size));
+        }
+    }
+}
+
+fn fetch(client: &mut DaemonClient, name: &str) -> Result<ArtifactContent> {
+    let path = artifact_path(name);
+    let request = CatRequest { path: path.clone(), version: None }.build_request(generate_id())?;
+    let response = client.request(request).context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Err(Port42Error::from_lookup(
+            response.error.as_deref(),
+            ERR_PATH_NOT_FOUND,
+            &format!("No artifact at '{}'; see 'port42 artifacts list'", path),
+        ).into());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    let mut artifact = ArtifactContent::parse_response(&data)?;
+    if artifact.path.is_empty() {
+        artifact.path = path;
+    }
+    Ok(artifact)
+}
+
+/// Markdown rendered, source highlighted, binaries described
+fn show(client: &mut DaemonClient, name: &str, raw: bool, format: OutputFormat) -> Result<()> {
+    let artifact = fetch(client, name)?;
+    let text = artifact.text();
+
+    if format.is_structured() {
+        let output = serde_json::json!({
+            "path": artifact.path,
+            "size": artifact.bytes.len(),
+            "binary": text.is_none(),
+            "content": text,
+            "metadata": artifact.metadata,
+        });
+        if format == OutputFormat::Yaml {
+            crate::display::print_yaml(&output)?;
+        } else {
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        return Ok(());
+    }
+
+    let Some(text) = text else {
+        println!("{}", artifact.path.bright_blue().bold());
+        println!("{}", format!("Binary artifact, {}", format_size(artifact.bytes.len() as i64)).dimmed());
+        println!("{}", format!("Open it with: port42 artifacts open {}", name).dimmed());
+        return Ok(());
+    };
+    if raw {
+        print!("{}", text);
+        return Ok(());
+    }
+
+    println!("{}", artifact.path.bright_blue().bold());
+    if let Some(description) = artifact.metadata.as_ref().and_then(|m| m.description.as_deref()) {
+        println!("{}", description.dimmed());
+    }
+    println!();
+    if artifact.is_markdown() {
+        println!("{}", markdown::render(text));
+    } else {
+        let hint = artifact.metadata.as_ref().and_then(|m| m.language.as_deref());
+        println!("{}", highlight::highlight(&artifact.path, text, hint).unwrap_or_else(|| text.to_string()));
+    }
+    Ok(())
+}
+
+/// Write the artifact to a private cache file and hand it to the system's default application
+fn open(client: &mut DaemonClient, name: &str) -> Result<()> {
+    let artifact = fetch(client, name)?;
+    let file = materialize(&artifact)?;
+
+    for (program, args) in OPENERS {
+        let launched = Command::new(program)
+            .args(*args)
+            .arg(&file)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if launched.is_ok_and(|status| status.success()) {
+            println!("{}", format!("📂 Opened {} ({})", artifact.path, file.display()).bright_green());
+            return Ok(());
+        }
+    }
+    bail!("No program could open {}; it was saved to {}", artifact.path, file.display())
+}
+
+/// `~/.port42/cache/artifacts/<id>/<name>`, keyed by a hash of the content
+/// and keeping the name so the right application is chosen. The cache is
+/// private to this user and files are only ever created, never rewritten
+/// in place.
+fn materialize(artifact: &ArtifactContent) -> Result<PathBuf> {
+    let root = crate::config::port42_dir().join("cache").join("artifacts");
+    create_private_dir_all(&root)?;
+    let dir = root.join(sha256_hex(&artifact.bytes));
+    create_private_dir_all(&dir)?;
+    let name = artifact.path.rsplit('/').next().filter(|n| !n.is_empty()).unwrap_or("artifact");
+    let file = dir.join(name);
+
+    // Same id, same name: an earlier open left exactly these bytes here
+    if std::fs::read(&file).is_ok_and(|existing| existing == artifact.bytes) {
+        return Ok(file);
+    }
+    let _ = std::fs::remove_file(&file);
+    create_private_file(&file, &artifact.bytes, false)?;
+    Ok(file)
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+use colored::*;
+use crate::CacheAction;
+use crate::common::{cache, url_ref};
+use crate::config::Config;
+
+pub fn handle_cache(action: CacheAction) -> Result<()> {
+    match action {
+        CacheAction::Clear => {
+            let removed = cache::clear(&cache::cache_dir())?;
+            let pages = cache::clear(&url_ref::cache_dir())?;
+            println!("{}", format!("🧹 Cleared {} cached response{} and {} fetched page{}",
+                removed, if removed == 1 { "" } else { "s" }, pages, if pages == 1 { "" } else { "s" }).green());
+        }
+        CacheAction::Stats => {
+            let (count, bytes) = cache::stats(&cache::cache_dir());
+            let (pages, page_bytes) = cache::stats(&url_ref::cache_dir());
+            let enabled = Config::load_or_default().cache.map(|c| c.enabled).unwrap_or(false);
+            
+            println!("{}", "⚡ Response cache".bright_blue().bold());
+            println!("  Status:   {}", if enabled { "enabled".green() } else { "disabled".dimmed() });
+            println!("  Entries:  {}", count.to_string().bright_cyan());
+            println!("  Size:     {}", format!("{:.1} KB", bytes as f64 / 1024.0).bright_cyan());
+            println!("  Location: {}", cache::cache_dir().display().to_string().dimmed());
+            if !enabled {
+                println!("  {}", "Enable with [cache] enabled = true in ~/.port42/config.toml".dimmed());
+            }
+            println!();
+            println!("{}", "🌐 Fetched pages (url: references)".bright_blue().bold());
+            println!("  Entries:  {}", pages.to_string().bright_cyan());
+            println!("  Size:     {}", format!("{:.1} KB", page_bytes as f64 / 1024.0).bright_cyan());
+            println!("  Location: {}", url_ref::cache_dir().display().to_string().dimmed());
+        }
+    }
+    Ok(())
+}
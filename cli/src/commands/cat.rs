@@ -1,16 +1,20 @@
-use anyhow::{Result, Context, bail};
+use anyhow::{Result, Context};
+use colored::*;
+use crate::common::errors::Port42Error;
 use crate::client::DaemonClient;
 use crate::help_text::*;
-use crate::protocol::{CatRequest, CatResponse, RequestBuilder, ResponseParser};
+use crate::protocol::{CatRequest, CatResponse, RequestBuilder, ResponseParser, check_version};
 use crate::display::{Displayable, OutputFormat};
 
 pub fn handle_cat(client: &mut DaemonClient, path: String) -> Result<()> {
-    handle_cat_with_format(client, path, OutputFormat::Plain)
+    handle_cat_with_format(client, path, None, OutputFormat::Plain, false).map(|_| ())
 }
 
-pub fn handle_cat_with_format(client: &mut DaemonClient, path: String, format: OutputFormat) -> Result<()> {
+/// Display a path's content, returning it for callers that also copy it.
+/// `plain` prints it byte for byte, with no header or highlighting.
+pub fn handle_cat_with_format(client: &mut DaemonClient, path: String, version: Option<u32>, format: OutputFormat, plain: bool) -> Result<String> {
     // Create request
-    let request = CatRequest { path: path.clone() };
+    let request = CatRequest { path: path.clone(), version };
     let daemon_request = request.build_request(format!("cat-{}", chrono::Utc::now().timestamp()))?;
     
     // Send request and get response
@@ -18,23 +22,36 @@ pub fn handle_cat_with_format(client: &mut DaemonClient, path: String, format: O
         .context(ERR_CONNECTION_LOST)?;
     
     if !response.success {
-        bail!(format_error_with_suggestion(
+        return Err(Port42Error::from_lookup(
+            response.error.as_deref(),
             ERR_PATH_NOT_FOUND,
-            &format!("Reality fragment '{}' cannot be accessed", path)
-        ));
+            &match version {
+                Some(n) => format!("Version {} of '{}' cannot be accessed; see 'port42 history {}'", n, path, path),
+                None => format!("Reality fragment '{}' cannot be accessed", path),
+            }
+        ).into());
     }
     
     // Parse response
     let data = response.data.context(ERR_INVALID_RESPONSE)?;
     let mut cat_response = CatResponse::parse_response(&data)?;
+    check_version(&path, version, cat_response.version)?;
     
     // Set path if not provided by response
     if cat_response.path.is_empty() {
         cat_response.path = path;
     }
     
-    // Display using the displayable trait
-    cat_response.display(format)?;
+    if let Some(n) = version {
+        if !plain && !format.is_structured() {
+            println!("{}", format!("🕰️  Version {} of {}", n, cat_response.path).dimmed());
+        }
+    }
+    if plain && !format.is_structured() {
+        cat_response.print_raw();
+    } else {
+        cat_response.display(format)?;
+    }
     
-    Ok(())
+    Ok(cat_response.content)
 }
\ No newline at end of file
@@ -0,0 +1,267 @@
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Zsh};
+use clap_complete::{CompletionCandidate, Shell};
+use std::ffi::OsStr;
+use std::time::Duration;
+use crate::agents::{AgentRegistry, BUILTIN_AGENTS};
+use crate::client::{self, DaemonClient};
+use crate::common::generate_id;
+use crate::protocol::{LsRequest, LsResponse, RequestBuilder, ResponseParser};
+
+/// Environment variable the shell sets when asking us for candidates
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Completion runs on every <Tab>, so never wait long on the daemon
+const COMPLETION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// What a `port42 __complete` call is asking for
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum CompletionKind {
+    Agent,
+    Path,
+    Session,
+    Tool,
+    Template,
+}
+
+impl CompletionKind {
+    fn name(self) -> &'static str {
+        match self {
+            CompletionKind::Agent => "agent",
+            CompletionKind::Path => "path",
+            CompletionKind::Session => "session",
+            CompletionKind::Tool => "tool",
+            CompletionKind::Template => "template",
+        }
+    }
+
+    /// Paths can fill every positional; the rest only the first
+    fn every_position(self) -> bool {
+        self == CompletionKind::Path
+    }
+}
+
+/// Subcommands whose positional arguments the static scripts complete
+/// through `port42 __complete`
+const DYNAMIC_SUBCOMMANDS: &[(CompletionKind, &[&str])] = &[
+    (CompletionKind::Agent, &["swim"]),
+    (CompletionKind::Session, &["session"]),
+    (CompletionKind::Tool, &["run"]),
+    (CompletionKind::Path, &["ls", "tree", "cat", "info", "history", "rollback", "note", "diff", "cp", "mv", "rm", "restore"]),
+];
+
+pub fn handle_completions(shell: Shell, static_script: bool) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    if static_script {
+        // Subcommands and flags from the script itself; names come from
+        // `port42 __complete` where the shell can be hooked
+        clap_complete::generate(shell, &mut crate::Cli::command(), "port42", &mut stdout);
+        if let Some(hooks) = dynamic_hooks(shell) {
+            print!("{}", hooks);
+        }
+        return Ok(());
+    }
+
+    let completer: &dyn EnvCompleter = match shell {
+        Shell::Bash => &Bash,
+        Shell::Zsh => &Zsh,
+        Shell::Fish => &Fish,
+        Shell::PowerShell => &Powershell,
+        Shell::Elvish => &Elvish,
+        _ => anyhow::bail!("Unsupported shell '{}'", shell),
+    };
+    completer.write_registration(COMPLETE_VAR, "port42", "port42", "port42", &mut stdout)?;
+    Ok(())
+}
+
+/// Answer `port42 __complete <kind> [current]`, one candidate per line
+pub fn handle_complete(kind: CompletionKind, current: &str) -> Result<()> {
+    let current = OsStr::new(current);
+    let candidates = match kind {
+        CompletionKind::Agent => complete_agent(current),
+        CompletionKind::Path => complete_vfs_path(current),
+        CompletionKind::Session => complete_session(current),
+        CompletionKind::Tool => complete_tool(current),
+        CompletionKind::Template => complete_template(current),
+    };
+    for candidate in candidates {
+        println!("{}", candidate.get_value().to_string_lossy());
+    }
+    Ok(())
+}
+
+/// Shell code that routes positional arguments to `port42 __complete`,
+/// wrapping the function clap generated. None for shells without a hook.
+fn dynamic_hooks(shell: Shell) -> Option<String> {
+    let cases = |first_only: bool, fmt: &dyn Fn(&str, &str) -> String| -> String {
+        DYNAMIC_SUBCOMMANDS.iter()
+            .filter(|(kind, _)| kind.every_position() != first_only)
+            .map(|(kind, subcommands)| fmt(kind.name(), &subcommands.join("|")))
+            .collect()
+    };
+    match shell {
+        Shell::Bash => Some(format!(r#"
+_port42_dynamic() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" kind=""
+    if [[ $COMP_CWORD -ge 2 && "$cur" != -* ]]; then
+        case "${{COMP_WORDS[1]}}" in
+{every}        esac
+        if [[ -z "$kind" && $COMP_CWORD -eq 2 ]]; then
+            case "${{COMP_WORDS[1]}}" in
+{first}            esac
+        fi
+    fi
+    if [[ -n "$kind" ]]; then
+        COMPREPLY=( $(port42 __complete "$kind" "$cur" 2>/dev/null) )
+        if [[ ${{#COMPREPLY[@]}} -gt 0 ]]; then
+            [[ ${{#COMPREPLY[@]}} -eq 1 && "${{COMPREPLY[0]}}" == */ ]] && compopt -o nospace
+            return 0
+        fi
+    fi
+    _port42 "$@"
+}}
+if [[ "${{BASH_VERSINFO[0]}}" -eq 4 && "${{BASH_VERSINFO[1]}}" -ge 4 || "${{BASH_VERSINFO[0]}}" -gt 4 ]]; then
+    complete -F _port42_dynamic -o nosort -o bashdefault -o default port42
+else
+    complete -F _port42_dynamic -o bashdefault -o default port42
+fi
+"#,
+            every = cases(false, &|kind, subs| format!("            {}) kind={} ;;\n", subs, kind)),
+            first = cases(true, &|kind, subs| format!("                {}) kind={} ;;\n", subs, kind)),
+        )),
+        Shell::Zsh => Some(format!(r#"
+_port42_dynamic() {{
+    local kind=""
+    if (( CURRENT > 2 )) && [[ $PREFIX != -* ]]; then
+        case $words[2] in
+{every}        esac
+        if [[ -z $kind ]] && (( CURRENT == 3 )); then
+            case $words[2] in
+{first}            esac
+        fi
+    fi
+    if [[ -n $kind ]]; then
+        local -a candidates
+        candidates=(${{(f)"$(port42 __complete $kind "$PREFIX" 2>/dev/null)"}})
+        if (( $#candidates )); then
+            compadd -Q -- $candidates
+            return
+        fi
+    fi
+    _port42 "$@"
+}}
+compdef _port42_dynamic port42
+"#,
+            every = cases(false, &|kind, subs| format!("            {}) kind={} ;;\n", subs, kind)),
+            first = cases(true, &|kind, subs| format!("                {}) kind={} ;;\n", subs, kind)),
+        )),
+        Shell::Fish => Some(DYNAMIC_SUBCOMMANDS.iter()
+            .map(|(kind, subcommands)| format!(
+                "complete -c port42 -n '__fish_seen_subcommand_from {}' -f -a '(port42 __complete {} (commandline -ct))'\n",
+                subcommands.join(" "), kind.name()))
+            .collect()),
+        _ => None,
+    }
+}
+
+/// Built-in agents plus any defined in ~/.port42/agents.toml
+pub fn complete_agent(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let registry = AgentRegistry::load().unwrap_or_default();
+    let mut candidates: Vec<CompletionCandidate> = BUILTIN_AGENTS.iter()
+        .filter(|agent| agent.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect();
+    for (name, definition) in &registry.agents {
+        if name.starts_with(current.as_ref()) && !BUILTIN_AGENTS.contains(&name.as_str()) {
+            candidates.push(CompletionCandidate::new(name)
+                .help(definition.description.clone().map(Into::into)));
+        }
+    }
+    candidates
+}
+
+/// Paths in the virtual filesystem, one directory level at a time
+pub fn complete_vfs_path(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let current = if current.is_empty() { "/" } else { current.as_ref() };
+    let (dir, prefix) = match current.rfind('/') {
+        Some(i) => (&current[..=i], &current[i + 1..]),
+        None => ("/", current),
+    };
+
+    list_dir(dir.trim_end_matches('/'))
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(prefix))
+        .map(|(name, is_dir)| {
+            let suffix = if is_dir { "/" } else { "" };
+            CompletionCandidate::new(format!("{}{}{}", dir, name, suffix))
+        })
+        .collect()
+}
+
+/// Session IDs from conversation memory
+pub fn complete_session(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    std::iter::once("last".to_string())
+        .chain(list_dir("/memory").into_iter().map(|(name, _)| name))
+        .filter(|id| id.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Crystallized command names for `run`; full paths complete like any VFS path
+pub fn complete_tool(current: &OsStr) -> Vec<CompletionCandidate> {
+    if current.to_string_lossy().starts_with('/') {
+        return complete_vfs_path(current);
+    }
+    let current = current.to_string_lossy();
+    list_dir("/commands")
+        .into_iter()
+        .filter(|(name, is_dir)| !is_dir && name.starts_with(current.as_ref()))
+        .map(|(name, _)| CompletionCandidate::new(name))
+        .collect()
+}
+
+/// Names of saved prompt templates
+pub fn complete_template(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    list_dir(crate::commands::prompts::PROMPTS_ROOT)
+        .into_iter()
+        .filter_map(|(name, is_dir)| name.strip_suffix(".md").filter(|_| !is_dir).map(str::to_string))
+        .filter(|name| name.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Entries of a VFS directory as (name, is_directory); empty when the daemon is away
+fn list_dir(path: &str) -> Vec<(String, bool)> {
+    let port = std::env::var("PORT42_PORT").ok()
+        .and_then(|p| p.parse().ok())
+        .or_else(|| crate::config::Config::load_or_default().port)
+        .or_else(client::detect_daemon_port);
+    let Some(port) = port else { return Vec::new() };
+    list_dir_with(&mut DaemonClient::new(port), path)
+}
+
+/// Like `list_dir`, over an existing connection
+pub fn list_dir_with(client: &mut DaemonClient, path: &str) -> Vec<(String, bool)> {
+    let path = if path.is_empty() { "/" } else { path };
+    let Ok(request) = LsRequest { path: path.to_string() }.build_request(generate_id()) else {
+        return Vec::new();
+    };
+    let Ok(response) = client.request_timeout(request, COMPLETION_TIMEOUT) else {
+        return Vec::new();
+    };
+    response.data
+        .filter(|_| response.success)
+        .and_then(|data| LsResponse::parse_response(&data).ok())
+        .map(|ls| ls.entries.into_iter()
+            .map(|entry| {
+                let is_dir = entry.entry_type == "directory";
+                (entry.name, is_dir)
+            })
+            .collect())
+        .unwrap_or_default()
+}
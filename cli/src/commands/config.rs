@@ -0,0 +1,191 @@
+use anyhow::{Context, Result, bail};
+use colored::*;
+use serde::Serialize;
+use crate::ConfigAction;
+use crate::config::{self, Config, ENV_OVERRIDES};
+use crate::display::{Displayable, OutputFormat, components::TableBuilder, print_yaml};
+
+pub fn handle_config(action: ConfigAction, format: OutputFormat) -> Result<()> {
+    match action {
+        ConfigAction::Get { key } => get(&key, format),
+        ConfigAction::Set { key, value } => set(&key, &value),
+        ConfigAction::List => list(format),
+        ConfigAction::Edit => edit(),
+    }
+}
+
+/// One effective setting and where it came from
+#[derive(Debug, Serialize)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: serde_json::Value,
+    /// "config" or the environment variable that set it
+    pub source: String,
+}
+
+impl ConfigEntry {
+    fn value_text(&self) -> String {
+        match &self.value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct ConfigListing(pub Vec<ConfigEntry>);
+
+impl Displayable for ConfigListing {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Table => {
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["Key", "Value", "Source"]);
+                for entry in &self.0 {
+                    table.add_row(vec![entry.key.clone(), entry.value_text(), entry.source.clone()]);
+                }
+                table.print();
+            }
+            OutputFormat::Plain => {
+                if self.0.is_empty() {
+                    println!("{}", format!("Nothing configured. Settings go in {}", config::config_path().display()).dimmed());
+                }
+                for entry in &self.0 {
+                    let note = if entry.source == "config" { String::new() } else { format!("  # from {}", entry.source) };
+                    println!("{} = {}{}", entry.key.bright_cyan(), entry.value, note.dimmed());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn get(key: &str, format: OutputFormat) -> Result<()> {
+    let entry = match config::env_override(key) {
+        Some((var, value)) => env_entry(key, var, value),
+        None => {
+            let value = config::get_in(&Config::load()?, key)
+                .with_context(|| format!("{} is not set", key))?;
+            ConfigEntry { key: key.to_string(), value: serde_json::to_value(value)?, source: "config".to_string() }
+        }
+    };
+    match format {
+        OutputFormat::Plain => println!("{}", entry.value_text()),
+        _ => ConfigListing(vec![entry]).display(format)?,
+    }
+    Ok(())
+}
+
+/// Environment values are text; show numbers and booleans as what they mean
+fn env_entry(key: &str, var: &str, value: String) -> ConfigEntry {
+    let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+    ConfigEntry { key: key.to_string(), value, source: var.to_string() }
+}
+
+fn set(key: &str, raw: &str) -> Result<()> {
+    let path = config::config_path();
+    let content = if path.exists() {
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let updated = config::set_in(&content, key, config::parse_value(raw))?;
+    std::fs::create_dir_all(config::port42_dir())?;
+    std::fs::write(&path, updated).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!("{}", format!("⚙️  {} = {}", key, raw).green());
+    if let Some((var, value)) = config::env_override(key) {
+        println!("{}", format!("Note: {}={} is set and takes precedence", var, value).yellow());
+    }
+    Ok(())
+}
+
+fn list(format: OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+    let mut entries = Vec::new();
+    if let Ok(toml::Value::Table(table)) = toml::Value::try_from(&config) {
+        flatten("", &toml::Value::Table(table), &mut entries)?;
+    }
+    for (key, _) in ENV_OVERRIDES {
+        if let Some((var, value)) = config::env_override(key) {
+            let entry = env_entry(key, var, value);
+            match entries.iter_mut().find(|e| e.key == *key) {
+                Some(existing) => *existing = entry,
+                None => entries.push(entry),
+            }
+        }
+    }
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    ConfigListing(entries).display(format)
+}
+
+fn flatten(prefix: &str, value: &toml::Value, entries: &mut Vec<ConfigEntry>) -> Result<()> {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                // Quote keys like "@ai-muse" so they read back as one key
+                let key = if key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                    key.clone()
+                } else {
+                    format!("\"{}\"", key)
+                };
+                let key = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                flatten(&key, value, entries)?;
+            }
+        }
+        other => entries.push(ConfigEntry {
+            key: prefix.to_string(),
+            value: serde_json::to_value(other)?,
+            source: "config".to_string(),
+        }),
+    }
+    Ok(())
+}
+
+/// Edit a copy in $VISUAL/$EDITOR and only replace the real file if the
+/// result is valid
+fn edit() -> Result<()> {
+    let path = config::config_path();
+    let original = std::fs::read_to_string(&path).unwrap_or_default();
+    let scratch = std::env::temp_dir().join(format!("port42-config-{}.toml", std::process::id()));
+    std::fs::write(&scratch, &original)?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Editors are often set with flags, like "code --wait"
+    let mut words = editor.split_whitespace();
+    let status = std::process::Command::new(words.next().unwrap_or("vi"))
+        .args(words)
+        .arg(&scratch)
+        .status();
+    let status = match status {
+        Ok(status) => status,
+        Err(e) => {
+            let _ = std::fs::remove_file(&scratch);
+            return Err(e).with_context(|| format!("Failed to launch editor '{}'", editor));
+        }
+    };
+    if !status.success() {
+        let _ = std::fs::remove_file(&scratch);
+        bail!("Editor exited with an error, configuration not changed");
+    }
+
+    let edited = std::fs::read_to_string(&scratch)?;
+    if edited == original {
+        let _ = std::fs::remove_file(&scratch);
+        println!("{}", "No changes".dimmed());
+        return Ok(());
+    }
+    if let Err(e) = config::validate(&edited) {
+        bail!("{:#}\nConfiguration not changed. Your edit is kept in {}", e, scratch.display());
+    }
+    std::fs::create_dir_all(config::port42_dir())?;
+    std::fs::write(&path, edited).with_context(|| format!("Failed to write {}", path.display()))?;
+    let _ = std::fs::remove_file(&scratch);
+    println!("{}", format!("⚙️  Saved {}", path.display()).green());
+    Ok(())
+}
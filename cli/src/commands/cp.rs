@@ -0,0 +1,62 @@
+use anyhow::{Result, Context, bail};
+use crate::client::DaemonClient;
+use crate::help_text::*;
+use crate::protocol::{CopyPathRequest, TransferResponse, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat};
+
+pub fn handle_cp(client: &mut DaemonClient, source: String, destination: String, force: bool) -> Result<()> {
+    handle_cp_with_format(client, source, destination, force, OutputFormat::Plain)
+}
+
+pub fn handle_cp_with_format(client: &mut DaemonClient, source: String, destination: String, force: bool, format: OutputFormat) -> Result<()> {
+    let destination = resolve_destination(&source, &destination)?;
+    let request = CopyPathRequest { source: source.clone(), destination: destination.clone(), overwrite: force };
+    let daemon_request = request.build_request(format!("cp-{}", chrono::Utc::now().timestamp()))?;
+
+    let response = client.request(daemon_request)
+        .context(ERR_CONNECTION_LOST)?;
+
+    if !response.success {
+        bail!(format_error_with_suggestion(
+            ERR_TRANSFER_FAILED,
+            &response.error.unwrap_or_else(|| format!("Cannot replicate '{}' to '{}'", source, destination))
+        ));
+    }
+
+    let data = response.data.unwrap_or_default();
+    let transfer = complete(TransferResponse::parse_response(&data)?, "copy", source, destination);
+    transfer.display(format)?;
+
+    Ok(())
+}
+
+/// A destination ending in '/' names a directory: keep the source's own name
+pub fn resolve_destination(source: &str, destination: &str) -> Result<String> {
+    let name = source.trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+    if name.is_empty() {
+        bail!(format_error_with_suggestion(ERR_TRANSFER_FAILED, &format!("'{}' is not an object path", source)));
+    }
+    let destination = if destination.ends_with('/') {
+        format!("{}{}", destination, name)
+    } else {
+        destination.to_string()
+    };
+    if destination.trim_end_matches('/') == source.trim_end_matches('/') {
+        bail!(format_error_with_suggestion(ERR_TRANSFER_FAILED, "Source and destination are the same path"));
+    }
+    Ok(destination)
+}
+
+/// Fill in whatever the daemon left out of its reply
+pub(crate) fn complete(mut transfer: TransferResponse, operation: &str, source: String, destination: String) -> TransferResponse {
+    if transfer.operation.is_empty() {
+        transfer.operation = operation.to_string();
+    }
+    if transfer.source.is_empty() {
+        transfer.source = source;
+    }
+    if transfer.destination.is_empty() {
+        transfer.destination = destination;
+    }
+    transfer
+}
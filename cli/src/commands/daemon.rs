@@ -2,60 +2,113 @@ use anyhow::{Result, Context, bail};
 use colored::*;
 use std::process::{Command, Stdio};
 use std::io::{BufRead, BufReader, Write};
+use regex::Regex;
 use std::fs;
 use std::env;
-use std::path::PathBuf;
 use crate::DaemonAction;
 use crate::help_text::*;
+use crate::common::{keychain, platform, providers, notify::{self, NotifyEvent}};
+use crate::common::errors::Port42Error;
+use crate::common::daemon_log::{self, LogFilter};
+use crate::common::utils::parse_since;
+use crate::common::service::{self, Manager, Scope, Service, ServiceSpec, Step};
+use crate::config::Config;
 
-const DAEMON_BINARY: &str = "port42d";
-const PID_FILE: &str = "/tmp/port42d.pid";
-const LOG_FILE: &str = ".port42/daemon.log";
+pub const DAEMON_BINARY: &str = "port42d";
 
-fn get_log_path() -> PathBuf {
-    let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    PathBuf::from(home).join(LOG_FILE)
-}
-
-fn is_daemon_running() -> bool {
+pub fn is_daemon_running() -> bool {
     // Check if PID file exists and process is running
-    if let Ok(pid_str) = fs::read_to_string(PID_FILE) {
+    if let Ok(pid_str) = fs::read_to_string(platform::pid_file()) {
         if let Ok(pid) = pid_str.trim().parse::<u32>() {
-            // Check if process exists (signal 0)
-            unsafe {
-                libc::kill(pid as i32, 0) == 0
-            }
+            platform::is_alive(pid)
         } else {
             false
         }
     } else {
         // Also check by process name
-        Command::new("pgrep")
-            .arg("-f")
-            .arg(DAEMON_BINARY)
-            .output()
-            .map(|output| output.status.success())
-            .unwrap_or(false)
+        platform::is_running(DAEMON_BINARY)
+    }
+}
+
+/// Warn about missing API keys for the configured provider before starting
+fn check_provider_keys() {
+    let config = Config::load_or_default();
+    let provider = config.provider.as_deref().unwrap_or(providers::DEFAULT_PROVIDER);
+    
+    if provider == providers::DEFAULT_PROVIDER {
+        // Check for API key - PORT42_ANTHROPIC_API_KEY first, then ANTHROPIC_API_KEY
+        if providers::find_api_key(provider).is_none() {
+            println!("{}", ERR_NO_API_KEY.yellow());
+            println!("{}", "To channel consciousness:".yellow());
+            println!("  export PORT42_ANTHROPIC_API_KEY='your-key-here'");
+            println!("  # or");
+            println!("  export ANTHROPIC_API_KEY='your-key-here'");
+            println!("  # or store it in the OS keychain");
+            println!("  port42 keys set anthropic");
+            println!("  port42 daemon restart\n");
+        }
+        return;
+    }
+    
+    println!("{}", format!("🔌 Default provider: {}", provider).dimmed());
+    if provider == providers::LOCAL_PROVIDER {
+        // Local models need no key - just say where we expect Ollama
+        println!("{}", format!("   Ollama endpoint: {}", providers::local_base_url(&config)).dimmed());
+        return;
+    }
+    
+    let key_vars = providers::api_key_vars(provider);
+    if providers::requires_api_key(provider) && providers::find_api_key(provider).is_none() {
+        println!("{}", format_missing_provider_key(provider).yellow());
+        println!("{}", "To channel consciousness:".yellow());
+        println!("  export {}='your-key-here'", key_vars[0]);
+        println!("  # or store it in the OS keychain");
+        println!("  port42 keys set {}", provider);
+        println!("  port42 daemon restart\n");
+    }
+    
+    if let Some(var) = providers::base_url_var(provider) {
+        if let Ok(url) = env::var(var) {
+            println!("{}", format!("   Base URL: {} (from {})", url, var).dimmed());
+        }
     }
 }
 
+/// Keys from the OS keychain for providers with no key in the environment.
+/// These are handed to the daemon process only, never exported to our own env.
+fn keychain_env() -> Vec<(&'static str, String)> {
+    providers::KNOWN_PROVIDERS
+        .iter()
+        .filter(|p| providers::requires_api_key(p) && providers::find_env_key(p).is_none())
+        .filter_map(|p| {
+            let key = keychain::get_key(p).ok().flatten()?;
+            Some((providers::api_key_vars(p)[0], key))
+        })
+        .collect()
+}
+
 fn start_daemon(background: bool) -> Result<()> {
+    if let Some(service) = service::installed() {
+        return start_service(service);
+    }
+    
     if is_daemon_running() {
         println!("{}", ERR_DAEMON_ALREADY_RUNNING.green());
         return Ok(());
     }
     
-    // Check for API key - PORT42_ANTHROPIC_API_KEY first, then ANTHROPIC_API_KEY
-    let api_key = env::var("PORT42_ANTHROPIC_API_KEY")
-        .or_else(|_| env::var("ANTHROPIC_API_KEY"))
-        .ok();
-    if api_key.is_none() {
-        println!("{}", ERR_NO_API_KEY.yellow());
-        println!("{}", "To channel consciousness:".yellow());
-        println!("  export PORT42_ANTHROPIC_API_KEY='your-key-here'");
-        println!("  # or");
-        println!("  export ANTHROPIC_API_KEY='your-key-here'");
-        println!("  port42 daemon restart\n");
+    // A PID file without a process means the last daemon died without 'daemon stop'
+    if let Ok(pid) = fs::read_to_string(platform::pid_file()) {
+        notify::notify(NotifyEvent::DaemonCrashed, &[
+            ("reason", format!("process {} exited without being stopped", pid.trim())),
+        ]);
+        fs::remove_file(platform::pid_file()).ok();
+    }
+    
+    check_provider_keys();
+    let keychain_keys = keychain_env();
+    if !keychain_keys.is_empty() {
+        println!("{}", format!("🔐 Loaded {} API key(s) from the OS keychain", keychain_keys.len()).dimmed());
     }
     
     // Check if daemon binary exists
@@ -71,28 +124,33 @@ fn start_daemon(background: bool) -> Result<()> {
     println!();
     
     if background {
-        // Start in background using nohup
-        let log_path = get_log_path();
+        // Start in background, detached from this terminal
+        let log_path = platform::daemon_log_path();
         
         // Create log directory if needed
         if let Some(parent) = log_path.parent() {
             fs::create_dir_all(parent)?;
         }
         
-        let mut cmd = Command::new("nohup");
-        cmd.arg(&daemon_path)
-            .stdout(Stdio::from(fs::File::create(&log_path)?))
+        let mut cmd = Command::new(&daemon_path);
+        platform::detach(&mut cmd);
+        cmd.stdout(Stdio::from(fs::File::create(&log_path)?))
             .stderr(Stdio::from(fs::File::create(&log_path)?))
             .stdin(Stdio::null());
         
-        // The daemon should inherit all environment variables by default
-        // No need to explicitly set them unless we want to override
+        // The daemon should inherit all environment variables by default,
+        // plus any keys that only live in the keychain
+        cmd.envs(keychain_keys.iter().map(|(var, key)| (*var, key)));
         
         let child = cmd.spawn()
             .context(ERR_DAEMON_START_FAILED)?;
         
         // Save PID
-        fs::write(PID_FILE, child.id().to_string())?;
+        let pid_file = platform::pid_file();
+        if let Some(parent) = pid_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&pid_file, child.id().to_string())?;
         
         // Wait a moment to check if it started successfully
         std::thread::sleep(std::time::Duration::from_secs(2));
@@ -101,6 +159,10 @@ fn start_daemon(background: bool) -> Result<()> {
             println!("{}", MSG_DAEMON_SUCCESS.green());
             println!("{}", format!("📋 Log file: {}", log_path.display()).dimmed());
         } else {
+            fs::remove_file(platform::pid_file()).ok();
+            notify::notify(NotifyEvent::DaemonCrashed, &[
+                ("reason", format!("exited during startup, see {}", log_path.display())),
+            ]);
             bail!(format_error_with_suggestion(
                 ERR_DAEMON_START_FAILED,
                 &format!("Check the log file: {}", log_path.display())
@@ -108,7 +170,7 @@ fn start_daemon(background: bool) -> Result<()> {
         }
     } else {
         // Start in foreground - but still log to file
-        let log_path = get_log_path();
+        let log_path = platform::daemon_log_path();
         
         // Create log directory if needed
         if let Some(parent) = log_path.parent() {
@@ -124,7 +186,9 @@ fn start_daemon(background: bool) -> Result<()> {
         // Start daemon directly, capturing output to both terminal and file
         let mut cmd = Command::new(&daemon_path);
         
-        // The daemon should inherit all environment variables by default
+        // The daemon should inherit all environment variables by default,
+        // plus any keys that only live in the keychain
+        cmd.envs(keychain_keys.iter().map(|(var, key)| (*var, key)));
         
         // Spawn the process with piped stdout/stderr
         let mut child = cmd
@@ -169,6 +233,9 @@ fn start_daemon(background: bool) -> Result<()> {
         let status = child.wait()?;
         
         if !status.success() {
+            notify::notify(NotifyEvent::DaemonCrashed, &[
+                ("reason", format!("exited with {}", status)),
+            ]);
             bail!(format_error_with_suggestion(
                 ERR_DAEMON_START_FAILED,
                 &format!("Process exited with status: {}", status)
@@ -180,6 +247,13 @@ fn start_daemon(background: bool) -> Result<()> {
 }
 
 fn stop_daemon() -> Result<()> {
+    if let Some(service) = service::installed() {
+        println!("{}", MSG_DAEMON_STOPPING.red().bold());
+        run_steps(service, Step::Stop).context(ERR_FAILED_TO_STOP)?;
+        println!("{}", MSG_DAEMON_STOPPED.green());
+        return Ok(());
+    }
+    
     if !is_daemon_running() {
         println!("{}", format_daemon_connection_error(42));
         return Ok(());
@@ -188,43 +262,173 @@ fn stop_daemon() -> Result<()> {
     println!("{}", MSG_DAEMON_STOPPING.red().bold());
     
     // Try to read PID and kill gracefully
-    if let Ok(pid_str) = fs::read_to_string(PID_FILE) {
+    if let Ok(pid_str) = fs::read_to_string(platform::pid_file()) {
         if let Ok(pid) = pid_str.trim().parse::<u32>() {
-            unsafe {
-                // Send SIGTERM
-                if libc::kill(pid as i32, libc::SIGTERM) == 0 {
-                    // Wait for process to stop
-                    for _ in 0..10 {
-                        std::thread::sleep(std::time::Duration::from_millis(500));
-                        if !is_daemon_running() {
-                            println!("{}", MSG_DAEMON_STOPPED.green());
-                            fs::remove_file(PID_FILE).ok();
-                            return Ok(());
-                        }
+            if platform::terminate(pid) {
+                // Wait for process to stop
+                for _ in 0..10 {
+                    std::thread::sleep(std::time::Duration::from_millis(500));
+                    if !is_daemon_running() {
+                        println!("{}", MSG_DAEMON_STOPPED.green());
+                        fs::remove_file(platform::pid_file()).ok();
+                        return Ok(());
                     }
-                    
-                    // Force kill if still running
-                    libc::kill(pid as i32, libc::SIGKILL);
                 }
+                
+                // Force kill if still running
+                platform::kill(pid);
             }
         }
     }
     
     // Fallback: kill by name
-    Command::new("pkill")
-        .arg("-f")
-        .arg(DAEMON_BINARY)
-        .status()
-        .context(ERR_FAILED_TO_STOP)?;
+    platform::kill_by_name(DAEMON_BINARY).context(ERR_FAILED_TO_STOP)?;
     
-    fs::remove_file(PID_FILE).ok();
+    fs::remove_file(platform::pid_file()).ok();
     println!("{}", MSG_DAEMON_STOPPED.green());
     
     Ok(())
 }
 
-fn show_logs(lines: usize, follow: bool) -> Result<()> {
-    let log_path = get_log_path();
+/// What an installed service is handed: PATH for the tools the daemon runs,
+/// provider keys and base URLs from this shell, and keychain keys
+fn service_env() -> Vec<(String, String)> {
+    let mut vars = vec!["PATH"];
+    for provider in providers::KNOWN_PROVIDERS {
+        vars.extend(providers::api_key_vars(provider));
+        vars.extend(providers::base_url_var(provider));
+    }
+    
+    let mut env: Vec<(String, String)> = vars.into_iter()
+        .filter_map(|var| env::var(var).ok().filter(|value| !value.is_empty()).map(|value| (var.to_string(), value)))
+        .collect();
+    env.extend(keychain_env().into_iter().map(|(var, key)| (var.to_string(), key)));
+    env
+}
+
+fn is_key_var(var: &str) -> bool {
+    var.ends_with("_API_KEY")
+}
+
+fn run_steps(service: Service, step: Step) -> Result<()> {
+    for command in service.commands(step) {
+        service::run(&command)?;
+    }
+    Ok(())
+}
+
+fn start_service(service: Service) -> Result<()> {
+    if service.is_active() {
+        println!("{}", ERR_DAEMON_ALREADY_RUNNING.green());
+        return Ok(());
+    }
+    
+    println!("{}", MSG_DAEMON_STARTING.blue().bold());
+    run_steps(service, Step::Start).context(ERR_DAEMON_START_FAILED)?;
+    println!("{}", MSG_DAEMON_SUCCESS.green());
+    println!("{}", format!("⚓ Managed by {} ({} service)", service.manager.name(), service.scope.name()).dimmed());
+    println!("{}", format!("📋 Log file: {}", platform::daemon_log_path().display()).dimmed());
+    Ok(())
+}
+
+fn install_service(system: bool, dry_run: bool) -> Result<()> {
+    let manager = Manager::current()
+        .ok_or_else(|| Port42Error::Usage(ERR_NO_SERVICE_MANAGER.to_string()))?;
+    let scope = if system { Scope::System } else { Scope::User };
+    let service = Service::new(manager, scope);
+    
+    let daemon_path = which::which(DAEMON_BINARY)
+        .context(format!("{}
+💡 Install Port 42 to manifest the daemon", ERR_BINARY_NOT_FOUND))?;
+    let spec = ServiceSpec {
+        binary: daemon_path,
+        log: platform::daemon_log_path(),
+        home: dirs::home_dir().unwrap_or_default(),
+        // Under sudo, the service should still run as whoever asked for it
+        user: match scope {
+            Scope::System => env::var("SUDO_USER").or_else(|_| env::var("USER")).ok(),
+            Scope::User => None,
+        },
+        env: service_env(),
+    };
+    
+    if dry_run {
+        let shown = ServiceSpec {
+            env: spec.env.iter()
+                .map(|(var, value)| (var.clone(), if is_key_var(var) { "********".to_string() } else { value.clone() }))
+                .collect(),
+            ..spec
+        };
+        for file in service.render(&shown) {
+            println!("{}", format!("# {}", file.path.display()).dimmed());
+            println!("{}", file.contents);
+        }
+        for command in service.commands(Step::Load) {
+            println!("$ {}", command.join(" "));
+        }
+        return Ok(());
+    }
+    
+    // Reinstalling replaces the old service; a daemon started by hand would hold the port
+    if service.is_installed() {
+        run_steps(service, Step::Unload).ok();
+    } else if service::installed().is_none() && is_daemon_running() {
+        stop_daemon()?;
+    }
+    
+    check_provider_keys();
+    if let Some(parent) = spec.log.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    for file in service.render(&spec) {
+        service::write(&file).map_err(|e| match scope {
+            Scope::System => e.context("💡 System services need root: sudo -E port42 daemon install --system"),
+            Scope::User => e,
+        })?;
+        println!("{}", format!("📝 Wrote {}", file.path.display()).dimmed());
+    }
+    run_steps(service, Step::Load).context(ERR_DAEMON_START_FAILED)?;
+    
+    let keys = spec.env.iter().filter(|(var, _)| is_key_var(var)).count();
+    if keys > 0 {
+        println!("{}", format!("🔐 {} API key(s) passed to the service, readable only by its owner", keys).dimmed());
+    }
+    println!("{}", MSG_SERVICE_INSTALLED.green());
+    println!("{}", format!("⚓ Managed by {} ({} service), starting at {}",
+        manager.name(), scope.name(), if system { "boot" } else { "login" }).dimmed());
+    println!("{}", format!("📋 Log file: {}", spec.log.display()).dimmed());
+    println!("{}", "💡 Changed a key? Run port42 daemon install again to pass it on".dimmed());
+    Ok(())
+}
+
+fn uninstall_service(user: bool, system: bool) -> Result<()> {
+    let service = match (user, system, Manager::current()) {
+        (true, _, Some(manager)) => Some(Service::new(manager, Scope::User)),
+        (_, true, Some(manager)) => Some(Service::new(manager, Scope::System)),
+        _ => service::installed(),
+    };
+    let Some(service) = service.filter(Service::is_installed) else {
+        println!("{}", MSG_SERVICE_NOT_INSTALLED.dimmed());
+        return Ok(());
+    };
+    
+    // Already stopped or never loaded is fine; the files still go
+    if let Err(e) = run_steps(service, Step::Unload) {
+        println!("{}", format!("⚠️  {}", e).yellow());
+    }
+    fs::remove_file(service.path())
+        .with_context(|| format!("Failed to remove {}", service.path().display()))?;
+    if let Some(env_file) = service.env_file() {
+        fs::remove_file(env_file).ok();
+    }
+    
+    println!("{}", MSG_SERVICE_UNINSTALLED.green());
+    println!("{}", "💡 Start it by hand from now on: port42 daemon start -b".dimmed());
+    Ok(())
+}
+
+fn show_logs(lines: usize, follow: bool, filter: LogFilter) -> Result<()> {
+    let log_path = platform::daemon_log_path();
     
     if !log_path.exists() {
         bail!(format_error_with_suggestion(
@@ -237,36 +441,30 @@ fn show_logs(lines: usize, follow: bool) -> Result<()> {
     println!("{}", format!("File: {}", log_path.display()).dimmed());
     println!("{}", "─".repeat(50).dimmed());
     
+    for line in daemon_log::tail(&log_path, lines, &filter).context(ERR_LOG_NOT_FOUND)? {
+        println!("{}", line.render());
+    }
+    
     if follow {
-        // Follow logs using tail -f
-        let mut child = Command::new("tail")
-            .arg("-f")
-            .arg(&log_path)
-            .stdout(Stdio::piped())
-            .spawn()
-            .context("Failed to follow log stream")?;
-        
-        if let Some(stdout) = child.stdout.take() {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                println!("{}", line?);
-            }
-        }
-    } else {
-        // Show last N lines
-        let output = Command::new("tail")
-            .arg(format!("-{}", lines))
-            .arg(&log_path)
-            .output()
-            .context(ERR_LOG_NOT_FOUND)?;
-        
-        print!("{}", String::from_utf8_lossy(&output.stdout));
+        daemon_log::follow(&log_path, &filter, |line| {
+            println!("{}", line.render());
+            true
+        }).context("Failed to follow log stream")?;
     }
     
     Ok(())
 }
 
 pub fn handle_daemon(action: DaemonAction, port: u16) -> Result<()> {
+    // Everything here works on this machine's processes and services
+    let endpoint = crate::client::endpoint();
+    if !endpoint.is_local() {
+        bail!(Port42Error::Usage(format_error_with_suggestion(
+            &format!("Profile {} points at {}; 'port42 daemon' only manages the daemon on this machine",
+                endpoint.profile.as_deref().unwrap_or("?"), endpoint.describe(port)),
+            "Run it on that machine, or drop --profile"
+        )));
+    }
     match action {
         DaemonAction::Start { background } => {
             start_daemon(background)?;
@@ -277,6 +475,11 @@ pub fn handle_daemon(action: DaemonAction, port: u16) -> Result<()> {
         }
 
         DaemonAction::Status => {
+            if let Some(service) = service::installed() {
+                let state = if service.is_active() { "running" } else { "not running" };
+                println!("{}", format!("⚓ {} {} service, {}: {}",
+                    service.manager.name(), service.scope.name(), state, service.path().display()).dimmed());
+            }
             // Call the same status handler as the main status command
             crate::commands::status::handle_status(port, false)?;
         }
@@ -284,6 +487,12 @@ pub fn handle_daemon(action: DaemonAction, port: u16) -> Result<()> {
         DaemonAction::Restart => {
             println!("{}", MSG_DAEMON_RESTARTING.yellow().bold());
 
+            if let Some(service) = service::installed() {
+                run_steps(service, Step::Restart).context(ERR_DAEMON_START_FAILED)?;
+                println!("{}", MSG_DAEMON_SUCCESS.green());
+                return Ok(());
+            }
+
             // Stop if running
             if is_daemon_running() {
                 stop_daemon()?;
@@ -294,8 +503,23 @@ pub fn handle_daemon(action: DaemonAction, port: u16) -> Result<()> {
             start_daemon(true)?;
         }
         
-        DaemonAction::Logs { lines, follow } => {
-            show_logs(lines, follow)?;
+        DaemonAction::Logs { lines, follow, grep, level, since } => {
+            let filter = LogFilter {
+                level,
+                grep: grep.map(|pattern| Regex::new(&pattern))
+                    .transpose()
+                    .map_err(|e| Port42Error::Usage(format!("Invalid --grep pattern: {}", e)))?,
+                since: since.map(|s| parse_since(&s)).transpose()?,
+            };
+            show_logs(lines, follow, filter)?;
+        }
+        
+        DaemonAction::Install { user: _, system, dry_run } => {
+            install_service(system, dry_run)?;
+        }
+        
+        DaemonAction::Uninstall { user, system } => {
+            uninstall_service(user, system)?;
         }
     }
     
@@ -1,83 +1,340 @@
-use anyhow::Result;
+use anyhow::{Result, Context, bail};
 use colored::*;
-use std::time::Duration;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::debug;
 
-use crate::client::DaemonClient;
+use crate::client::{DaemonClient, RequestKind, Timeouts};
+use crate::protocol::hello::FEATURE_PREVIEW;
+use crate::ui::PhaseProgress;
+use crate::types::Response;
 use crate::protocol::{
     DeclareRelationRequest, DeclareRelationResponse, 
-    Relation, RequestBuilder, ResponseParser
+    ProviderSelection, Relation, RequestBuilder, ResponseParser
 };
 use crate::display::{Displayable, OutputFormat};
-use crate::common::{generate_id, references::parse_references};
+use crate::common::{generate_id, cache::ResponseCache, errors::Port42Error, references::parse_references, tool_manifest::{self, ToolSpec}, rate_limit::RateLimitQueue, notify::{self, NotifyEvent}, providers::{self, ProviderArgs, ensure_reachable, resolve_fallbacks, resolve_provider}};
+use crate::help_text::{ERR_INVALID_RESPONSE, MSG_CACHED_RESPONSE, format_error_with_suggestion, format_provider_fallback};
+use crate::swim::is_provider_outage_error;
+use crate::config::Config;
+use crate::project::Project;
+use crate::agents::{AgentRegistry, normalize_agent_name};
+use crate::commands::swim::validate_agent;
+use crate::commands::jobs::{can_detach, submit_detached};
+use crate::commands::outbox;
+use crate::common::template;
+use crate::commands::prompts::render_prompt;
+
+/// Per-invocation flags for declare tool/artifact
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct DeclareArgs {
+    /// AI provider and model selection (overrides config defaults)
+    #[command(flatten)]
+    pub provider: ProviderArgs,
+    
+    /// Submit as a background job and return its ID straight away
+    #[arg(long)]
+    pub detach: bool,
+    
+    /// Generate and show what would be written, without writing anything;
+    /// needs a daemon that can preview
+    #[arg(long, conflicts_with = "detach")]
+    pub dry_run: bool,
+    
+    /// If the daemon can't be reached, save the declaration to send later
+    #[arg(long, conflicts_with = "dry_run")]
+    pub queue: bool,
+    
+    /// Generate again even if an identical declaration is cached
+    #[arg(long)]
+    pub no_cache: bool,
+    
+    /// Build the prompt from a saved prompt template (see 'port42 prompts list');
+    /// any --prompt text is added after it
+    #[arg(long, value_name = "NAME", add = clap_complete::ArgValueCompleter::new(crate::commands::completions::complete_template))]
+    pub template: Option<String>,
+    
+    /// Template variable as key=value (can be used multiple times)
+    #[arg(long = "var", value_name = "KEY=VALUE", action = clap::ArgAction::Append, requires = "template")]
+    pub vars: Vec<String>,
+    
+    /// Report only the outcome, for declarations running side by side
+    #[arg(skip)]
+    pub quiet: bool,
+}
+
+impl DeclareArgs {
+    /// The prompt to send: the rendered template, if any, then `prompt`
+    fn prompt(&self, port: u16, prompt: Option<String>) -> Result<Option<String>> {
+        match self.template {
+            Some(ref name) => {
+                let rendered = render_prompt(&mut DaemonClient::new(port), name, &self.vars)?;
+                Ok(Some(template::append_extra(rendered, prompt)))
+            }
+            None => Ok(prompt),
+        }
+    }
+}
 
 /// Handle declaring a new tool relation
-pub fn handle_declare_tool(port: u16, name: &str, transforms: Vec<String>, references: Option<Vec<String>>, prompt: Option<String>) -> Result<()> {
-    println!("{}", format!("🌟 Declaring tool: {}", name).bright_blue());
+pub fn handle_declare_tool(port: u16, name: &str, transforms: Vec<String>, references: Option<Vec<String>>, prompt: Option<String>, agent: Option<String>, args: DeclareArgs) -> Result<()> {
+    declare_tool(port, name, transforms, references, prompt, agent, args)
+}
+
+/// Declare every tool in a manifest, up to `max_concurrent` at a time
+pub fn handle_declare_batch(port: u16, file: &Path, dry_run: bool, max_concurrent: usize, provider: ProviderArgs) -> Result<()> {
+    let tools = tool_manifest::load(file)?;
+    let total = tools.len();
+    println!("{}", format!("📜 {} tools in {}", total, file.display()).bright_blue());
+
+    if dry_run {
+        for (n, spec) in tools.iter().enumerate() {
+            print_planned(n + 1, total, spec);
+        }
+        println!("\n{}", "Dry run: nothing was declared".dimmed());
+        return Ok(());
+    }
+
+    let failed = if max_concurrent > 1 && total > 1 {
+        declare_concurrently(port, tools, &provider, max_concurrent)
+    } else {
+        let mut failed = Vec::new();
+        for (n, spec) in tools.into_iter().enumerate() {
+            println!("\n{}", format!("[{}/{}]", n + 1, total).bright_white().bold());
+            // The manifest was the review; --dry-run on the batch shows it
+            let args = DeclareArgs { provider: tool_provider(&spec, &provider), ..Default::default() };
+            if let Err(e) = declare_tool(port, &spec.name, spec.transforms, Some(spec.refs), spec.prompt, None, args) {
+                eprintln!("{} {:#}", "❌".red(), e);
+                failed.push(spec.name);
+            }
+        }
+        failed
+    };
+
+    let declared = total - failed.len();
+    println!();
+    if failed.is_empty() {
+        println!("{}", format!("✨ Batch complete: {} of {} tools declared", declared, total).green().bold());
+        return Ok(());
+    }
+    println!("{}", format!("Batch finished: {} declared, {} failed", declared, failed.len()).yellow().bold());
+    for name in &failed {
+        println!("  {} {}", "✗".red(), name);
+    }
+    bail!("{} of {} tools could not be declared", failed.len(), total)
+}
+
+/// Workers take tools off a shared queue, so a slow generation never holds
+/// up the rest. Returns the tools that failed, in manifest order.
+fn declare_concurrently(port: u16, tools: Vec<ToolSpec>, provider: &ProviderArgs, max_concurrent: usize) -> Vec<String> {
+    let total = tools.len();
+    let queue = Mutex::new(tools.into_iter().enumerate());
+    let failed = Mutex::new(Vec::new());
+    println!("{}", format!("Declaring up to {} at a time", max_concurrent.min(total)).dimmed());
+
+    std::thread::scope(|scope| {
+        for _ in 0..max_concurrent.min(total) {
+            scope.spawn(|| loop {
+                let Some((n, spec)) = queue.lock().unwrap().next() else { break };
+                let progress = format!("[{}/{}]", n + 1, total);
+                println!("{} {}", progress.bright_white().bold(), format!("🌟 Declaring {}", spec.name).bright_blue());
+                let args = DeclareArgs { provider: tool_provider(&spec, provider), quiet: true, ..Default::default() };
+                match declare_tool(port, &spec.name, spec.transforms, Some(spec.refs), spec.prompt, None, args) {
+                    Ok(()) => println!("{} {} {}", progress.bright_white().bold(), "✅".green(), spec.name.bright_green()),
+                    Err(e) => {
+                        eprintln!("{} {} {}: {:#}", progress.bright_white().bold(), "❌".red(), spec.name, e);
+                        failed.lock().unwrap().push((n, spec.name));
+                    }
+                }
+            });
+        }
+    });
+
+    let mut failed = failed.into_inner().unwrap();
+    failed.sort();
+    failed.into_iter().map(|(_, name)| name).collect()
+}
+
+/// A tool that names its own provider doesn't inherit the command line's model
+fn tool_provider(spec: &ToolSpec, provider: &ProviderArgs) -> ProviderArgs {
+    ProviderArgs {
+        model: if spec.provider.is_some() { spec.model.clone() } else { spec.model.clone().or_else(|| provider.model.clone()) },
+        provider: spec.provider.clone().or_else(|| provider.provider.clone()),
+        no_fallback: provider.no_fallback,
+    }
+}
+
+fn print_planned(n: usize, total: usize, spec: &ToolSpec) {
+    println!("\n{} {}", format!("[{}/{}]", n, total).bright_white().bold(), spec.name.bright_green());
+    if !spec.transforms.is_empty() {
+        println!("  {}: {}", "Transforms".bright_cyan(), spec.transforms.join(", "));
+    }
+    if let Some(ref prompt) = spec.prompt {
+        let first = prompt.lines().next().unwrap_or_default();
+        let more = if prompt.trim_end().contains('\n') { " …" } else { "" };
+        println!("  {}: {}{}", "Prompt".bright_cyan(), first, more.dimmed());
+    }
+    for reference in &spec.refs {
+        // Local files can be checked now rather than failing mid-batch
+        let missing = reference.strip_prefix("file:").is_some_and(|f| !Path::new(f).exists());
+        let note = if missing { " (missing)".red().to_string() } else { String::new() };
+        println!("  {}: {}{}", "Reference".bright_cyan(), reference, note);
+    }
+    if let Some(ref name) = spec.provider {
+        println!("  {}: {}", "Provider".bright_cyan(), name);
+    }
+    if let Some(ref model) = spec.model {
+        println!("  {}: {}", "Model".bright_cyan(), model);
+    }
+}
+
+/// Declare one tool, returning an error rather than exiting so batches can carry on
+fn declare_tool(port: u16, name: &str, transforms: Vec<String>, references: Option<Vec<String>>, prompt: Option<String>, agent: Option<String>, args: DeclareArgs) -> Result<()> {
+    let prompt = args.prompt(port, prompt)?;
+    let DeclareArgs { provider, detach, dry_run, queue, no_cache, quiet, .. } = args;
+    if !quiet {
+        println!("{}", format!("🌟 Declaring tool: {}", name).bright_blue());
+    }
     
-    if !transforms.is_empty() {
+    if !transforms.is_empty() && !quiet {
         println!("  {}: {}", "Transforms".bright_cyan(), transforms.join(", ").bright_green());
     }
     
-    // Parse references if provided using common logic
-    let parsed_refs = if let Some(ref_strings) = references {
-        match parse_references(ref_strings, true) {
-            Ok(refs) => Some(refs),
-            Err(e) => {
-                eprintln!("{} {}", "❌ Invalid reference:".red(), e);
-                std::process::exit(1);
-            }
+    let agent = agent.map(|a| normalize_agent_name(&a));
+    let registry = AgentRegistry::load_or_default();
+    if let Some(ref agent) = agent {
+        validate_agent(agent, &registry)?;
+        if !quiet {
+            println!("  {}: {}", "Agent".bright_cyan(), agent.bright_green());
         }
+    }
+    
+    // Project references come before any given on the command line
+    let project = Project::discover();
+    let mut ref_strings = project.as_ref().map(Project::references).unwrap_or_default();
+    ref_strings.extend(references.unwrap_or_default());
+    
+    // Parse references if provided using common logic
+    let parsed_refs = if !ref_strings.is_empty() {
+        Some(parse_references(ref_strings, !quiet).context("Invalid reference")?)
     } else {
         None
     };
     
+    let config = Config::load_or_default();
+    let no_fallback = provider.no_fallback;
+    let provider = resolve_provider(provider, agent.as_deref(), &config)?;
+    if !quiet {
+        print_provider(&provider);
+    }
+    ensure_reachable(&provider)?;
+    let fallbacks = if no_fallback { Vec::new() } else { resolve_fallbacks(agent.as_deref(), &provider, &config)? };
+    
     // Create tool relation
-    let relation = Relation::new_tool(name, transforms);
+    let transforms_label = transforms.join(", ");
+    let mut relation = Relation::new_tool(name, transforms);
+    if let Some(ref project) = project {
+        relation = relation.with_project(&project.config.name);
+    }
+    if let Some(ref agent) = agent {
+        relation = relation.with_agent(agent);
+    }
+    // Project notes reach generation the same way they reach swim
+    let instructions = agent.as_deref().and_then(|agent| registry.instructions(agent));
+    relation = relation.with_guidance(Project::guidance_with(project.as_ref(), instructions));
     
     // Create request
-    let request = DeclareRelationRequest { relation, references: parsed_refs, user_prompt: prompt };
+    let description = prompt.clone().unwrap_or_else(|| format!("transforms {}", transforms_label));
+    let request = DeclareRelationRequest { relation, references: parsed_refs, user_prompt: prompt, provider, progress: false, preview: false };
     
-    // Send to daemon with extended timeout for AI generation
+    if queue && !outbox::daemon_reachable(port) {
+        return outbox::enqueue(&format!("declare tool {}", name), request.build_request(generate_id())?);
+    }
     let mut client = DaemonClient::new(port);
-    let daemon_request = request.build_request(generate_id())?;
-    let response = client.request_timeout(daemon_request, Duration::from_secs(300))?; // 5 minutes for AI - matches daemon timeout
+    if detach && can_detach(&mut client)? {
+        submit_detached(&mut client, request.build_request(generate_id())?)?;
+        return Ok(());
+    }
+    
+    let cache = ResponseCache::from_config(&config, no_cache);
+    let Some(request) = review(&mut client, request, &fallbacks, &format!("tool {}", name), dry_run, cache.as_ref())? else {
+        return Ok(());
+    };
+    
+    // Send to daemon with extended timeout for AI generation
+    let response = send_with_fallback(&mut client, request, fallbacks, quiet)?;
     
     if !response.success {
         let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
-        eprintln!("{} {}", "❌ Failed to declare tool:".red(), error);
-        std::process::exit(1);
+        bail!("Failed to declare tool {}: {}", name, error);
     }
     
     // Parse and display response
     if let Some(data) = response.data {
         let declare_response = DeclareRelationResponse::parse_response(&data)?;
-        declare_response.display(OutputFormat::Plain)?;
+        if !quiet {
+            declare_response.display(OutputFormat::Plain)?;
+        }
+        notify::notify(NotifyEvent::ToolCrystallized, &[
+            ("name", name.to_string()),
+            ("description", description),
+            ("path", declare_response.physical_path.clone()),
+        ]);
     }
     
     Ok(())
 }
 
 /// Handle declaring a new artifact relation
-pub fn handle_declare_artifact(port: u16, name: &str, artifact_type: &str, file_type: &str, prompt: Option<String>) -> Result<()> {
+pub fn handle_declare_artifact(port: u16, name: &str, artifact_type: &str, file_type: &str, prompt: Option<String>, args: DeclareArgs) -> Result<()> {
+    let prompt = args.prompt(port, prompt)?;
+    let DeclareArgs { provider, detach, dry_run, queue, no_cache, .. } = args;
     println!("{}", format!("🌟 Declaring artifact: {}", name).bright_blue());
     println!("  {}: {}", "Type".bright_cyan(), artifact_type.bright_green());
     println!("  {}: {}", "File Type".bright_cyan(), file_type.bright_green());
     
+    let config = Config::load_or_default();
+    let no_fallback = provider.no_fallback;
+    let provider = resolve_provider(provider, None, &config)?;
+    print_provider(&provider);
+    ensure_reachable(&provider)?;
+    let fallbacks = if no_fallback { Vec::new() } else { resolve_fallbacks(None, &provider, &config)? };
+    
     // Create artifact relation
-    let relation = Relation::new_artifact(name, artifact_type, file_type);
+    let mut relation = Relation::new_artifact(name, artifact_type, file_type);
+    let mut references = None;
+    if let Some(project) = Project::discover() {
+        relation = relation.with_project(&project.config.name);
+        let project_refs = project.references();
+        if !project_refs.is_empty() {
+            references = Some(parse_references(project_refs, true)?);
+        }
+    }
     
     // Create request
-    let request = DeclareRelationRequest { relation, references: None, user_prompt: prompt };
+    let request = DeclareRelationRequest { relation, references, user_prompt: prompt, provider, progress: false, preview: false };
     
-    // Send to daemon with extended timeout for AI generation
+    if queue && !outbox::daemon_reachable(port) {
+        return outbox::enqueue(&format!("declare artifact {}", name), request.build_request(generate_id())?);
+    }
     let mut client = DaemonClient::new(port);
-    let daemon_request = request.build_request(generate_id())?;
-    let response = client.request_timeout(daemon_request, Duration::from_secs(300))?; // 5 minutes for AI - matches daemon timeout
+    if detach && can_detach(&mut client)? {
+        submit_detached(&mut client, request.build_request(generate_id())?)?;
+        return Ok(());
+    }
+    
+    let cache = ResponseCache::from_config(&config, no_cache);
+    let Some(request) = review(&mut client, request, &fallbacks, &format!("artifact {}", name), dry_run, cache.as_ref())? else {
+        return Ok(());
+    };
+    
+    // Send to daemon with extended timeout for AI generation
+    let response = send_with_fallback(&mut client, request, fallbacks, false)?;
     
     if !response.success {
         let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
-        eprintln!("{} {}", "❌ Failed to declare artifact:".red(), error);
-        std::process::exit(1);
+        return Err(Port42Error::from_daemon(&error)).context("❌ Failed to declare artifact");
     }
     
     // Parse and display response
@@ -87,4 +344,155 @@ pub fn handle_declare_artifact(port: u16, name: &str, artifact_type: &str, file_
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// With --dry-run, generate without writing and show the result, returning
+/// None. Otherwise return the request to send: one that writes what an
+/// earlier --dry-run showed, if `cache` kept it, or the request as it was.
+/// A daemon that doesn't list FEATURE_PREVIEW would write a preview for
+/// real, so nothing is sent to it.
+fn review(
+    client: &mut DaemonClient,
+    request: DeclareRelationRequest,
+    fallbacks: &[ProviderSelection],
+    what: &str,
+    dry_run: bool,
+    cache: Option<&ResponseCache>,
+) -> Result<Option<DeclareRelationRequest>> {
+    let key = cache.map(|_| cache_key(&request));
+    let cached = cache.zip(key.as_deref())
+        .and_then(|(cache, key)| cache.get(key))
+        .and_then(|data| DeclareRelationResponse::parse_response(&data).ok())
+        .filter(|preview| preview.is_preview() && preview.content.is_some());
+    if cached.is_some() {
+        eprintln!("{}", MSG_CACHED_RESPONSE.dimmed());
+    }
+
+    if !dry_run {
+        return Ok(Some(match cached.and_then(|preview| preview.content) {
+            Some(content) => with_content(request, &content),
+            None => request,
+        }));
+    }
+
+    let preview = match cached {
+        Some(preview) => preview,
+        None => {
+            if !client.supports(FEATURE_PREVIEW)? {
+                return Err(Port42Error::Usage(format_error_with_suggestion(
+                    &format!("The daemon can't preview a {} without writing it, so nothing was sent", what),
+                    "Restart the daemon from a release that supports --dry-run, or declare without it",
+                )).into());
+            }
+            let mut preview = request.clone();
+            preview.preview = true;
+            let response = send_with_fallback(client, preview, fallbacks.to_vec(), false)?;
+            if !response.success {
+                let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+                return Err(Port42Error::from_daemon(&error)).context(format!("❌ Failed to preview {}", what));
+            }
+            let data = response.data.context(ERR_INVALID_RESPONSE)?;
+            let preview = DeclareRelationResponse::parse_response(&data)?;
+            if !preview.is_preview() {
+                bail!("The daemon wrote the {} to {} instead of previewing it", what, preview.physical_path);
+            }
+            if let (Some(cache), Some(key), Some(_)) = (cache, &key, &preview.content) {
+                if let Err(e) = cache.put(key, &data) {
+                    debug!("failed to cache preview: {}", e);
+                }
+            }
+            preview
+        }
+    };
+    if preview.content.is_none() {
+        bail!("The daemon sent no preview of the {}", what);
+    }
+    println!();
+    preview.display_preview();
+    println!("{}", "Dry run: nothing was written".dimmed());
+    Ok(None)
+}
+
+/// The request that writes `content` as generated, without asking the AI again
+fn with_content(mut request: DeclareRelationRequest, content: &str) -> DeclareRelationRequest {
+    request.relation = request.relation.with_generated(content);
+    request
+}
+
+/// Stable key over everything that shapes a declaration's generation
+fn cache_key(request: &DeclareRelationRequest) -> String {
+    // Properties are a HashMap; sorted, so the same declaration always hashes the same
+    let properties: BTreeMap<_, _> = request.relation.properties.iter().collect();
+    ResponseCache::key(&[
+        "declare",
+        &request.relation.relation_type,
+        &serde_json::to_string(&properties).unwrap_or_default(),
+        request.user_prompt.as_deref().unwrap_or_default(),
+        &serde_json::to_string(&request.provider).unwrap_or_default(),
+        &ResponseCache::references_key(request.references.as_deref()),
+    ])
+}
+
+/// Send a declaration, moving down the fallback chain while providers are unavailable
+pub fn send_with_fallback(
+    client: &mut DaemonClient,
+    mut request: DeclareRelationRequest,
+    fallbacks: Vec<ProviderSelection>,
+    quiet: bool,
+) -> Result<Response> {
+    let mut fallbacks = fallbacks.into_iter();
+    let mut queue = RateLimitQueue::from_config(&Config::load_or_default());
+    if quiet {
+        queue = queue.quiet();
+    }
+    // Phases are only worth asking for when someone is watching them
+    request.progress = !quiet;
+    let timeout = Timeouts::current().for_kind(RequestKind::of("declare_relation"));
+    loop {
+        let daemon_request = request.build_request(generate_id())?;
+        let response = if quiet {
+            client.request(daemon_request)?
+        } else {
+            let mut progress = PhaseProgress::new(timeout);
+            let response = client.request_with_status(daemon_request, &mut |status| progress.update(status));
+            progress.finish(response.as_ref().is_ok_and(|r| r.success));
+            response?
+        };
+        
+        if let Some(ref error) = response.error {
+            let provider = request.provider.as_ref()
+                .and_then(|p| p.name.as_deref())
+                .unwrap_or(providers::DEFAULT_PROVIDER);
+            if !response.success && queue.wait_if_limited(providers::display_name(provider), error) {
+                continue;
+            }
+        }
+        
+        let outage = !response.success
+            && response.error.as_deref().is_some_and(is_provider_outage_error);
+        if !outage {
+            return Ok(response);
+        }
+        let Some(next) = fallbacks.next() else {
+            return Ok(response);
+        };
+        
+        let failed = request.provider.as_ref()
+            .and_then(|p| p.name.as_deref())
+            .unwrap_or(providers::DEFAULT_PROVIDER);
+        let next_name = next.name.as_deref().unwrap_or(providers::DEFAULT_PROVIDER);
+        eprintln!("{}", format_provider_fallback(failed, next_name).yellow());
+        request.provider = Some(next);
+    }
+}
+
+fn print_provider(provider: &Option<ProviderSelection>) {
+    if let Some(selection) = provider {
+        if let Some(ref name) = selection.name {
+            println!("  {}: {}", "Provider".bright_cyan(), name.bright_green());
+        }
+        if let Some(ref model) = selection.model {
+            println!("  {}: {}", "Model".bright_cyan(), model.bright_green());
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use anyhow::{Context, Result};
+use crate::client::DaemonClient;
+use crate::common::errors::Port42Error;
+use crate::common::generate_id;
+use crate::help_text::*;
+use crate::protocol::{CatRequest, CatResponse, RequestBuilder, ResponseParser, check_version};
+use crate::display::{Displayable, OutputFormat, diff::TextDiff};
+
+pub fn handle_diff(client: &mut DaemonClient, left: String, right: String, context: usize, format: OutputFormat) -> Result<()> {
+    let old = fetch(client, &left, None)?;
+    let new = fetch(client, &right, None)?;
+    TextDiff::new(&left, &old, &right, &new, context).display(format)
+}
+
+/// The content of a path, or of one of its earlier versions
+pub fn fetch(client: &mut DaemonClient, path: &str, version: Option<u32>) -> Result<String> {
+    let request = CatRequest { path: path.to_string(), version };
+    let response = client.request(request.build_request(generate_id())?)
+        .context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Err(Port42Error::from_lookup(
+            response.error.as_deref(),
+            ERR_PATH_NOT_FOUND,
+            &match version {
+                Some(n) => format!("Version {} of '{}' cannot be accessed", n, path),
+                None => format!("Reality fragment '{}' cannot be accessed", path),
+            }
+        ).into());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    let read = CatResponse::parse_response(&data)?;
+    check_version(path, version, read.version)?;
+    Ok(read.content)
+}
@@ -0,0 +1,187 @@
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use colored::*;
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use crate::client::DaemonClient;
+use crate::common::digest::{self, Digest, DigestSession};
+use crate::common::{generate_id, errors::Port42Error};
+use crate::common::notify::{Notifier, NotifyEvent};
+use crate::config::{self, Config};
+use crate::protocol::{LsRequest, LsResponse, MemoryListRequest, MemoryListResponse, RequestBuilder, ResponseParser};
+use crate::protocol::hooks::{CommandCount, CommandStatsRequest, CommandStatsResponse};
+use crate::protocol::swim::{SwimRequest, SwimResponse};
+use crate::swim::determine_session_id;
+
+const DEFAULT_HOURS: u64 = 24;
+const DEFAULT_AGENT: &str = "@ai-analyst";
+const TOP_COMMANDS: usize = 10;
+
+/// Writing the narrative is one short completion
+const NARRATIVE_TIMEOUT: Duration = Duration::from_secs(120);
+
+const NARRATIVE_PROMPT: &str = "Below is a record of my recent Port 42 activity. Write a short narrative digest of it (3 to 5 sentences): what I worked on, what got built, and any pattern worth noticing. Plain prose, no headings or bullet lists.";
+
+pub struct DigestOptions {
+    pub hours: Option<u64>,
+    pub agent: Option<String>,
+    pub no_ai: bool,
+    pub notify: bool,
+    pub email: Option<String>,
+    pub quiet: bool,
+}
+
+pub fn handle_digest(port: u16, options: DigestOptions) -> Result<()> {
+    let config = Config::load_or_default();
+    let settings = config.digest.clone().unwrap_or_default();
+    let hours = options.hours.or(settings.hours).unwrap_or(DEFAULT_HOURS);
+    let agent = options.agent.or(settings.agent).unwrap_or_else(|| DEFAULT_AGENT.to_string());
+
+    let until = Utc::now();
+    let since = until - ChronoDuration::hours(hours as i64);
+    let mut client = DaemonClient::new(port);
+
+    if !options.quiet {
+        println!("{}", format!("📰 Gathering the last {} hours...", hours).bright_cyan());
+    }
+    let mut digest = Digest {
+        since,
+        until,
+        sessions: sessions(&mut client, since, until)?,
+        tools: tools(&mut client, since, until)?,
+        top_commands: top_commands(&mut client, since),
+        narrative: None,
+    };
+
+    if !options.no_ai {
+        match narrate(&mut client, &agent, &digest) {
+            Ok(narrative) => digest.narrative = Some(narrative),
+            Err(e) => eprintln!("{}", format!("⚠️  No narrative ({}), keeping the facts only", e).yellow()),
+        }
+    }
+
+    let markdown = digest.to_markdown();
+    let path = save(&digest, &markdown)?;
+    if !options.quiet {
+        println!();
+        println!("{}", markdown);
+        println!("{}", format!("Saved to {}", path.display()).dimmed());
+    }
+
+    if options.notify || settings.notify.unwrap_or(false) {
+        let vars = HashMap::from([
+            ("hours".to_string(), hours.to_string()),
+            ("headline".to_string(), digest.headline()),
+            ("summary".to_string(), digest.narrative.clone().unwrap_or_else(|| digest.facts())),
+        ]);
+        let notifier = Notifier::from_config(&config);
+        if notifier.is_empty() {
+            eprintln!("{}", "⚠️  No notification sinks configured, see 'port42 notify test'".yellow());
+        }
+        notifier.notify(NotifyEvent::Digest, &vars);
+    }
+
+    if let Some(address) = options.email.or(settings.email) {
+        let subject = format!("Port 42 digest: {}", digest.headline());
+        send_email(&address, &subject, &markdown)?;
+        if !options.quiet {
+            println!("{}", format!("✉️  Mailed to {}", address).dimmed());
+        }
+    }
+    Ok(())
+}
+
+fn sessions(client: &mut DaemonClient, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<DigestSession>> {
+    let response = client.request(MemoryListRequest.build_request(generate_id())?)?;
+    if !response.success {
+        return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| "Failed to retrieve memory".to_string())).into());
+    }
+    let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+    let memory = MemoryListResponse::parse_response(&data)?;
+
+    Ok(memory.active_sessions.into_iter()
+        .chain(memory.recent_sessions)
+        .filter(|s| {
+            let last = s.last_activity.as_deref().or(s.created_at.as_deref()).unwrap_or(&s.date);
+            digest::within(last, since, until)
+        })
+        .map(|s| DigestSession {
+            id: s.id,
+            agent: s.agent,
+            messages: s.message_count,
+            created_tool: s.command_generated,
+        })
+        .collect())
+}
+
+fn tools(client: &mut DaemonClient, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<String>> {
+    let request = LsRequest { path: "/commands".to_string() }.build_request(generate_id())?;
+    let response = client.request(request)?;
+    let Some(data) = response.data.filter(|_| response.success) else { return Ok(Vec::new()) };
+    Ok(LsResponse::parse_response(&data)?.entries.into_iter()
+        .filter(|entry| entry.created.as_deref().is_some_and(|c| digest::within(c, since, until)))
+        .map(|entry| entry.name)
+        .collect())
+}
+
+/// Commands reported by the shell hook; None if the daemon has no history
+fn top_commands(client: &mut DaemonClient, since: DateTime<Utc>) -> Option<Vec<CommandCount>> {
+    let request = CommandStatsRequest { since: since.to_rfc3339(), limit: TOP_COMMANDS }
+        .build_request(generate_id()).ok()?;
+    let response = client.request(request).ok()?;
+    let data = response.data.filter(|_| response.success)?;
+    CommandStatsResponse::parse_response(&data).ok().map(|stats| stats.commands)
+}
+
+fn narrate(client: &mut DaemonClient, agent: &str, digest: &Digest) -> Result<String> {
+    let request = SwimRequest {
+        agent: agent.to_string(),
+        message: format!("{}\n\n{}", NARRATIVE_PROMPT, digest.facts()),
+        memory_context: None,
+        references: None,
+        approval_response: None,
+        provider: None,
+        guidance: None,
+        workspace: None,
+    };
+    let (session_id, _) = determine_session_id(None);
+    let mut daemon_request = request.build_request(generate_id())?;
+    if let Some(obj) = daemon_request.payload.as_object_mut() {
+        obj.insert("session_id".to_string(), serde_json::Value::String(session_id));
+    }
+
+    let response = client.request_timeout(daemon_request, NARRATIVE_TIMEOUT)?;
+    if !response.success {
+        return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| "Unknown error".to_string())).into());
+    }
+    let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+    Ok(SwimResponse::parse_response(&data)?.message.trim().to_string())
+}
+
+/// Keep every digest under ~/.port42/digests, named by the end of its window
+fn save(digest: &Digest, markdown: &str) -> Result<std::path::PathBuf> {
+    let dir = config::port42_dir().join("digests");
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("{}.md", digest.until.format("%Y-%m-%d-%H%M")));
+    std::fs::write(&path, markdown)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+fn send_email(address: &str, subject: &str, body: &str) -> Result<()> {
+    let mut child = Command::new("sendmail")
+        .arg("-t")
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("Could not run sendmail; install a mail transfer agent to email digests")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        write!(stdin, "To: {}\nSubject: {}\nContent-Type: text/plain; charset=utf-8\n\n{}\n", address, subject, body)?;
+    }
+    if !child.wait()?.success() {
+        bail!("sendmail failed to send the digest to {}", address);
+    }
+    Ok(())
+}
@@ -0,0 +1,327 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::Serialize;
+use std::time::Duration;
+use crate::client::{self, DaemonClient};
+use crate::common::{generate_id, errors::{Port42Error, exit_code}};
+use crate::common::providers::{self, KeySource};
+use crate::config::{self, Config};
+use crate::protocol::{ProviderSelection, RequestBuilder, ResponseParser, StatusRequest, StatusResponse};
+use crate::protocol::hello::{HelloResponse, PROTOCOL_REVISION, versions_compatible};
+use crate::protocol::models::{ModelsRequest, ModelsResponse};
+
+/// Key validation asks each provider for its models, which can be slow
+const KEY_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Clock difference from the daemon worth mentioning
+const MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+const MIN_COLUMNS: u16 = 80;
+const MIN_ROWS: u16 = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &str, detail: impl Into<String>) -> Self {
+        Self { name: name.to_string(), status: CheckStatus::Pass, detail: detail.into(), fix: None }
+    }
+
+    fn warn(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name: name.to_string(), status: CheckStatus::Warn, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name: name.to_string(), status: CheckStatus::Fail, detail: detail.into(), fix: Some(fix.into()) }
+    }
+}
+
+pub fn handle_doctor(port: u16, json: bool) -> Result<()> {
+    if !json {
+        println!("{}", "🩺 Examining your Port 42 reality...".bright_blue().bold());
+        println!();
+    }
+
+    let config = Config::load_or_default();
+    let mut client = DaemonClient::new(port);
+    let status = daemon_status(&mut client);
+
+    let mut checks = vec![check_daemon(port, &status)];
+    if let Ok(ref status) = status {
+        checks.push(check_version(status));
+    }
+    if let Some(hello) = client::handshake(port) {
+        checks.push(check_protocol(&hello));
+    }
+    checks.extend(check_keys(&mut client, &config, status.is_ok()));
+    checks.push(check_path());
+    checks.push(check_terminal());
+    checks.push(check_storage());
+    checks.push(check_clock(status.as_ref().ok()));
+
+    let failures = checks.iter().filter(|c| c.status == CheckStatus::Fail).count();
+    let warnings = checks.iter().filter(|c| c.status == CheckStatus::Warn).count();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+            "healthy": failures == 0,
+            "checks": checks,
+        }))?);
+    } else {
+        for check in &checks {
+            print_check(check);
+        }
+        println!();
+        if failures == 0 && warnings == 0 {
+            println!("{}", "✨ All checks passed".green().bold());
+        } else {
+            println!("{}", format!("{} failed, {} warnings", failures, warnings).yellow());
+        }
+    }
+
+    if failures > 0 {
+        std::process::exit(exit_code::FAILURE);
+    }
+    Ok(())
+}
+
+fn print_check(check: &Check) {
+    let (icon, name) = match check.status {
+        CheckStatus::Pass => ("✅", check.name.green()),
+        CheckStatus::Warn => ("⚠️ ", check.name.yellow()),
+        CheckStatus::Fail => ("❌", check.name.red()),
+    };
+    println!("{} {:<12} {}", icon, name, check.detail);
+    if let Some(ref fix) = check.fix {
+        println!("   {} {}", "→".dimmed(), fix.dimmed());
+    }
+}
+
+fn daemon_status(client: &mut DaemonClient) -> Result<StatusResponse> {
+    let response = client.request(StatusRequest.build_request(generate_id())?)?;
+    if !response.success {
+        return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| "Unknown error".to_string())).into());
+    }
+    let data = response.data.ok_or_else(|| anyhow::anyhow!("No data in response"))?;
+    StatusResponse::parse_response(&data)
+}
+
+fn check_daemon(port: u16, status: &Result<StatusResponse>) -> Check {
+    let endpoint = client::endpoint();
+    if !endpoint.is_local() {
+        return match status {
+            Ok(status) => Check::pass("daemon", format!("Gateway answering at {} (up {})", endpoint.describe(port), status.uptime)),
+            Err(e) => Check::fail(
+                "daemon",
+                format!("No gateway answering at {}: {}", endpoint.describe(port), e.to_string().lines().next().unwrap_or_default()),
+                "Check the daemon is running on that machine and the profile's host, port and TLS settings",
+            ),
+        };
+    }
+    match status {
+        Ok(status) => Check::pass("daemon", format!("Gateway answering on port {} (up {})", port, status.uptime)),
+        Err(_) => Check::fail(
+            "daemon",
+            format!("No gateway answering on port {}", port),
+            format!("Start it with: port42 daemon start{}", if port == 42 { " (requires sudo)" } else { "" }),
+        ),
+    }
+}
+
+fn check_version(status: &StatusResponse) -> Check {
+    let cli_version = env!("PORT42_VERSION");
+    match status.version.as_deref() {
+        None => Check::warn(
+            "version",
+            "Daemon did not report its version",
+            "Restart the daemon from the same release as this CLI: port42 daemon restart",
+        ),
+        Some(daemon_version) if versions_compatible(cli_version, daemon_version) => {
+            Check::pass("version", format!("CLI {} / daemon {}", cli_version, daemon_version))
+        }
+        Some(daemon_version) => Check::fail(
+            "version",
+            format!("CLI {} does not match daemon {}", cli_version, daemon_version),
+            "Install matching releases, then: port42 daemon restart",
+        ),
+    }
+}
+
+fn check_protocol(hello: &HelloResponse) -> Check {
+    if let Some(problem) = hello.incompatibility() {
+        return Check::fail("protocol", problem.reason, problem.fix);
+    }
+    if hello.is_legacy() {
+        Check::pass("protocol", "Daemon predates the protocol handshake; assuming it understands us")
+    } else {
+        Check::pass("protocol", format!("CLI speaks {} / daemon speaks {}", PROTOCOL_REVISION, hello.protocol))
+    }
+}
+
+fn check_keys(client: &mut DaemonClient, config: &Config, daemon_up: bool) -> Vec<Check> {
+    let default_provider = config.provider.clone()
+        .unwrap_or_else(|| providers::DEFAULT_PROVIDER.to_string());
+    let mut checks = Vec::new();
+
+    if default_provider == providers::LOCAL_PROVIDER {
+        let base_url = providers::local_base_url(config);
+        let selection = Some(ProviderSelection {
+            name: Some(providers::LOCAL_PROVIDER.to_string()),
+            model: None,
+            base_url: Some(base_url.clone()),
+        });
+        checks.push(match providers::ensure_reachable(&selection) {
+            Ok(()) => Check::pass("ollama", format!("Reachable at {}", base_url)),
+            Err(_) => Check::fail("ollama", format!("Nothing answering at {}", base_url), "Start it with: ollama serve"),
+        });
+    }
+
+    for provider in providers::KNOWN_PROVIDERS.iter().filter(|p| providers::requires_api_key(p)) {
+        let name = format!("{} key", provider);
+        let source = match providers::find_api_key_with_source(provider) {
+            Some((_, KeySource::Env(var))) => var.to_string(),
+            Some((_, KeySource::Keychain)) => "OS keychain".to_string(),
+            None if *provider == default_provider => {
+                checks.push(Check::fail(
+                    &name,
+                    format!("No API key for the default provider {}", providers::display_name(provider)),
+                    format!("Store one with: port42 keys set {}", provider),
+                ));
+                continue;
+            }
+            None => continue,
+        };
+
+        if !daemon_up {
+            checks.push(Check::warn(&name, format!("Found in {}, not validated", source), "Start the daemon to validate keys"));
+            continue;
+        }
+        checks.push(match validate_key(client, provider) {
+            Ok(()) => Check::pass(&name, format!("Found in {}, accepted by {}", source, providers::display_name(provider))),
+            Err(e) => Check::fail(
+                &name,
+                format!("Found in {} but rejected: {}", source, e),
+                format!("Replace it with: port42 keys set {}", provider),
+            ),
+        });
+    }
+    checks
+}
+
+/// Ask the daemon to list a provider's models, which needs a working key
+fn validate_key(client: &mut DaemonClient, provider: &str) -> Result<()> {
+    let request = ModelsRequest { provider: Some(provider.to_string()) }.build_request(generate_id())?;
+    let response = client.request_timeout(request, KEY_CHECK_TIMEOUT)?;
+    if !response.success {
+        return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| "Unknown error".to_string())).into());
+    }
+    let data = response.data.ok_or_else(|| anyhow::anyhow!("No data in response"))?;
+    let models = ModelsResponse::parse_response(&data)?;
+    match models.providers.into_iter().find(|p| p.name == provider).and_then(|p| p.error) {
+        Some(error) => anyhow::bail!(error),
+        None => Ok(()),
+    }
+}
+
+fn check_path() -> Check {
+    let commands_dir = config::port42_dir().join("commands");
+    let on_path = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|p| p == commands_dir))
+        .unwrap_or(false);
+    if on_path {
+        Check::pass("PATH", format!("{} is on PATH", commands_dir.display()))
+    } else {
+        Check::fail(
+            "PATH",
+            format!("{} is not on PATH, generated tools won't run by name", commands_dir.display()),
+            "Add to your shell rc: export PATH=\"$HOME/.port42/commands:$PATH\"",
+        )
+    }
+}
+
+fn check_terminal() -> Check {
+    if !atty::is(atty::Stream::Stdout) {
+        return Check::warn("terminal", "Output is not a terminal, interactive views are disabled", "Run port42 directly in a terminal for TUIs");
+    }
+    let term = std::env::var("TERM").unwrap_or_default();
+    if term.is_empty() || term == "dumb" {
+        return Check::warn("terminal", format!("TERM is '{}', TUIs may not render", term), "Set TERM, e.g. export TERM=xterm-256color");
+    }
+    match crossterm::terminal::size() {
+        Ok((cols, rows)) if cols < MIN_COLUMNS || rows < MIN_ROWS => Check::warn(
+            "terminal",
+            format!("{} ({}x{}) is smaller than {}x{}", term, cols, rows, MIN_COLUMNS, MIN_ROWS),
+            "Enlarge the window for watch and memory views",
+        ),
+        Ok((cols, rows)) => Check::pass("terminal", format!("{} ({}x{})", term, cols, rows)),
+        Err(e) => Check::warn("terminal", format!("Could not read terminal size: {}", e), "Use --text modes if TUIs misbehave"),
+    }
+}
+
+fn check_storage() -> Check {
+    let dir = config::port42_dir();
+    if !dir.exists() {
+        return Check::warn("storage", format!("{} does not exist yet", dir.display()), "It is created when the daemon first starts: port42 daemon start");
+    }
+    let probe = dir.join(format!(".doctor-{}", std::process::id()));
+    match std::fs::write(&probe, b"ok") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            Check::pass("storage", format!("{} is writable", dir.display()))
+        }
+        Err(e) => Check::fail(
+            "storage",
+            format!("Cannot write to {}: {}", dir.display(), e),
+            format!("Fix ownership with: sudo chown -R $USER {}", dir.display()),
+        ),
+    }
+}
+
+fn check_clock(status: Option<&StatusResponse>) -> Check {
+    let now = Utc::now();
+
+    // A binary from the future means the system clock is behind
+    let built = std::env::current_exe().ok()
+        .and_then(|exe| std::fs::metadata(exe).ok())
+        .and_then(|meta| meta.modified().ok())
+        .map(DateTime::<Utc>::from);
+    if let Some(built) = built {
+        if built > now + chrono::Duration::seconds(MAX_CLOCK_SKEW_SECS) {
+            return Check::fail(
+                "clock",
+                format!("System time {} is earlier than this binary ({})", now.format("%Y-%m-%d %H:%M"), built.format("%Y-%m-%d %H:%M")),
+                "Enable network time sync (e.g. timedatectl set-ntp true)",
+            );
+        }
+    }
+
+    let daemon_time = status
+        .and_then(|s| s.time.as_deref())
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+    match daemon_time {
+        Some(daemon_time) => {
+            let skew = (now - daemon_time.with_timezone(&Utc)).num_seconds().abs();
+            if skew > MAX_CLOCK_SKEW_SECS {
+                Check::warn("clock", format!("{}s apart from the daemon's clock", skew), "Enable network time sync on both machines")
+            } else {
+                Check::pass("clock", format!("In sync with the daemon ({}s)", skew))
+            }
+        }
+        None => Check::pass("clock", format!("System time {}", now.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M %Z"))),
+    }
+}
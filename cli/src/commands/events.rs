@@ -0,0 +1,54 @@
+use anyhow::Result;
+use colored::*;
+use std::io::Write;
+use std::time::Duration;
+use crate::EventsAction;
+use crate::client::RetryPolicy;
+use crate::common::{events, errors::Port42Error};
+use crate::common::webhook::Forwarder;
+use crate::protocol::events::{DaemonEvent, EventKind};
+
+pub fn handle_events(port: u16, action: EventsAction, json: bool) -> Result<()> {
+    match action {
+        EventsAction::Tail { types, refresh } => tail(port, &types, Duration::from_millis(refresh), json),
+        EventsAction::Forward { url, secret, types, retries, refresh } => {
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                return Err(Port42Error::Usage(format!("--url needs an http(s) address, not '{}'", url)).into());
+            }
+            let retry = RetryPolicy { max_retries: retries, base_delay: Duration::from_secs(1), max_delay: Duration::from_secs(30) };
+            forward(port, &Forwarder { url, secret: secret.filter(|s| !s.is_empty()), retry }, &types, Duration::from_millis(refresh))
+        }
+    }
+}
+
+/// Print events until interrupted, or until whatever reads them goes away
+fn tail(port: u16, types: &[EventKind], refresh: Duration, json: bool) -> Result<()> {
+    if !json {
+        eprintln!("{}", "👂 Listening for daemon events. Press Ctrl+C to stop.".cyan());
+    }
+    let stdout = std::io::stdout();
+    events::follow(port, types, refresh, &mut |event| {
+        let mut out = stdout.lock();
+        let line = if json { serde_json::to_string(event).unwrap_or_default() } else { describe(event) };
+        writeln!(out, "{}", line).and_then(|_| out.flush()).is_ok()
+    })
+}
+
+/// `10:42:07 tool_created      git-haiku`
+fn describe(event: &DaemonEvent) -> String {
+    let time = event.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S");
+    format!("{} {:<16} {}", time.to_string().dimmed(), event.kind.label().yellow(), event.subject)
+}
+
+fn forward(port: u16, forwarder: &Forwarder, types: &[EventKind], refresh: Duration) -> Result<()> {
+    let signing = if forwarder.secret.is_some() { "signed" } else { "unsigned" };
+    println!("{}", format!("📡 Forwarding daemon events to {} ({}). Press Ctrl+C to stop.", forwarder.url, signing).cyan());
+    events::follow(port, types, refresh, &mut |event| {
+        match forwarder.deliver(event) {
+            Ok(attempts) if attempts > 1 => println!("{} (after {} attempts)", describe(event), attempts),
+            Ok(_) => println!("{}", describe(event)),
+            Err(e) => eprintln!("{}", format!("❌ {} {} not delivered: {:#}", event.kind.label(), event.subject, e).red()),
+        }
+        true
+    })
+}
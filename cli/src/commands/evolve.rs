@@ -1,17 +1,136 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use colored::*;
+use crate::agents::{AgentRegistry, normalize_agent_name};
+use crate::client::DaemonClient;
+use crate::protocol::hello::FEATURE_PREVIEW;
+use crate::commands::declare::send_with_fallback;
+use crate::commands::diff::fetch;
+use crate::commands::swim::validate_agent;
+use crate::common::errors::Port42Error;
+use crate::common::providers::{ProviderArgs, ensure_reachable, resolve_fallbacks, resolve_provider};
+use crate::common::{generate_id, references::parse_references};
+use crate::config::Config;
+use crate::display::{Displayable, OutputFormat, diff::{DEFAULT_CONTEXT, TextDiff}};
 use crate::help_text::*;
+use crate::project::Project;
+use crate::protocol::{DeclareRelationRequest, DeclareRelationResponse, Relation, RequestBuilder, ResponseParser, UpdatePathRequest};
 
-pub fn handle_evolve(_port: u16, command: String, message: Option<String>) -> Result<()> {
+/// Everything `port42 evolve` was asked to do
+pub struct EvolveArgs {
+    pub command: String,
+    pub prompt: Option<String>,
+    pub references: Vec<String>,
+    pub agent: Option<String>,
+    pub provider: ProviderArgs,
+    pub dry_run: bool,
+}
+
+/// Regenerate an existing tool with the AI, record the result as the tool's
+/// next version and show what changed. A daemon that can preview generates
+/// first and the result is applied as an update; one that can't writes the
+/// tool as it generates, and --dry-run stops before asking it to.
+pub fn handle_evolve(port: u16, args: EvolveArgs) -> Result<()> {
+    let EvolveArgs { command, prompt, references, agent, provider, dry_run } = args;
+    let path = format!("/commands/{}", command);
+    let mut client = DaemonClient::new(port);
+    let preview = client.supports(FEATURE_PREVIEW)?;
+    if dry_run && !preview {
+        return Err(Port42Error::Usage(format_error_with_suggestion(
+            &format!("The daemon can't preview an evolution of {} without writing it, so nothing was sent", command),
+            "Restart the daemon from a release that supports --dry-run, or evolve without it",
+        )).into());
+    }
+
+    // Evolution needs something to evolve; new tools are declared
+    let current = fetch(&mut client, &path, None).map_err(|_| Port42Error::NotFound(format_error_with_suggestion(
+        ERR_PATH_NOT_FOUND,
+        &format!("There is no tool '{}' to evolve; create it with 'port42 declare tool {}'", command, command),
+    )))?;
     println!("{}", format_evolving(&command).blue().bold());
-    
-    println!("{}", ERR_EVOLVE_NOT_READY.yellow());
-    println!("{}", "The evolution chamber awaits activation".dimmed());
-    
-    if let Some(msg) = message {
-        println!("\n{}", "Changes requested:".bright_white());
-        println!("  {}", msg.dimmed());
-    }
-    
+
+    let agent = agent.map(|a| normalize_agent_name(&a));
+    let registry = AgentRegistry::load_or_default();
+    if let Some(ref agent) = agent {
+        validate_agent(agent, &registry)?;
+    }
+
+    // The tool's own source goes first, so the AI revises it instead of starting over
+    let project = Project::discover();
+    let mut ref_strings = vec![format!("tool:{}", command)];
+    ref_strings.extend(project.as_ref().map(Project::references).unwrap_or_default());
+    ref_strings.extend(references);
+    let parsed_refs = parse_references(ref_strings, true).context("Invalid reference")?;
+
+    let config = Config::load_or_default();
+    let no_fallback = provider.no_fallback;
+    let provider = resolve_provider(provider, agent.as_deref(), &config)?;
+    ensure_reachable(&provider)?;
+    let fallbacks = if no_fallback { Vec::new() } else { resolve_fallbacks(agent.as_deref(), &provider, &config)? };
+
+    let mut relation = Relation::new_tool(&command, Vec::new()).evolving(&path);
+    if let Some(ref project) = project {
+        relation = relation.with_project(&project.config.name);
+    }
+    if let Some(ref agent) = agent {
+        relation = relation.with_agent(agent);
+    }
+    // Project notes reach generation the same way they reach swim
+    let instructions = agent.as_deref().and_then(|agent| registry.instructions(agent));
+    relation = relation.with_guidance(Project::guidance_with(project.as_ref(), instructions));
+    let request = DeclareRelationRequest {
+        relation,
+        references: Some(parsed_refs),
+        user_prompt: prompt,
+        provider,
+        progress: false,
+        preview,
+    };
+
+    let response = send_with_fallback(&mut client, request, fallbacks, false)?;
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(Port42Error::from_daemon(&error)).context(format!("❌ Failed to evolve {}", command));
+    }
+    let declared = DeclareRelationResponse::parse_response(&response.data.context(ERR_INVALID_RESPONSE)?)?;
+    if preview && !declared.is_preview() {
+        bail!("The daemon wrote {} instead of previewing it; see 'port42 history {}'", declared.physical_path, path);
+    }
+    if declared.action.as_deref() == Some("create") {
+        if preview {
+            bail!("The daemon treated '{}' as a new tool rather than an update; nothing was changed", command);
+        }
+        println!("{}", format!("⚠️  The daemon declared {} as a new tool rather than an update", declared.physical_path).yellow());
+    }
+    let evolved = match declared.content {
+        Some(content) if preview => content,
+        None if preview => bail!("The daemon sent no preview of the evolved tool"),
+        // Already written; what the daemon now holds is the evolution
+        _ => fetch(&mut client, &path, None)?,
+    };
+    if evolved == current {
+        println!("{}", format!("{} came back unchanged; nothing to evolve", command).dimmed());
+        return Ok(());
+    }
+
+    println!();
+    TextDiff::new(&format!("{} (current)", path), &current, &format!("{} (evolved)", path), &evolved, DEFAULT_CONTEXT)
+        .display(OutputFormat::Plain)?;
+    println!();
+    if dry_run {
+        println!("{}", "Dry run: nothing was changed".dimmed());
+        return Ok(());
+    }
+
+    if preview {
+        // Applied as an update, so metadata, relations and history carry over
+        let request = UpdatePathRequest { path: path.clone(), content: evolved.into_bytes() };
+        let response = client.request(request.build_request(generate_id())?)
+            .context(ERR_CONNECTION_LOST)?;
+        if !response.success {
+            return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| format!("Cannot update '{}'", path))).into());
+        }
+    }
+    println!("{}", format_evolved(&command).bright_green());
+    println!("{}", format!("Compare or undo with 'port42 history {}' and 'port42 rollback {}'", path, path).dimmed());
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,325 @@
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use clap::ValueEnum;
+use colored::*;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::ExportAction;
+use crate::client::DaemonClient;
+use crate::common::archive::{self, ArchiveFilters, Manifest, ManifestEntry};
+use crate::common::generate_id;
+use crate::common::site::{self, escape_html, SiteEntry};
+use crate::protocol::{CatRequest, CatResponse, InfoRequest, InfoResponse, LsRequest, LsResponse, RequestBuilder, ResponseParser, parse_date};
+
+/// How deep to follow directories under /artifacts
+const MAX_ARTIFACT_DEPTH: usize = 6;
+
+/// Metadata keys that point at where an object came from
+const LINEAGE_KEYS: &[(&str, &str)] = &[
+    ("session_id", "Created in session"),
+    ("session", "Created in session"),
+    ("memory_id", "Created in session"),
+    ("parent", "Evolved from"),
+    ("parents", "Evolved from"),
+    ("evolved_from", "Evolved from"),
+    ("spawned_by", "Spawned by"),
+    ("relation_id", "Relation"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Kind {
+    Tool,
+    Memory,
+    Artifact,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 3] = [Kind::Tool, Kind::Memory, Kind::Artifact];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Tool => "tool",
+            Kind::Memory => "memory",
+            Kind::Artifact => "artifact",
+        }
+    }
+
+    /// Where objects of this kind live in the VFS, and how deep to look
+    fn root(self) -> (&'static str, usize) {
+        match self {
+            Kind::Tool => ("/commands", 0),
+            Kind::Memory => ("/memory", 0),
+            Kind::Artifact => ("/artifacts", MAX_ARTIFACT_DEPTH),
+        }
+    }
+
+    fn dir(self) -> &'static str {
+        match self {
+            Kind::Tool => "tools",
+            Kind::Memory => "memory",
+            Kind::Artifact => "artifacts",
+        }
+    }
+}
+
+/// One VFS object and everything the daemon told us about it
+struct Item {
+    kind: Kind,
+    path: String,
+    name: String,
+    content: String,
+    description: String,
+    created: String,
+    agent: String,
+    info: Value,
+}
+
+impl Item {
+    fn href(&self) -> String {
+        format!("{}/{}", self.kind.dir(), site::page_name(&self.path))
+    }
+}
+
+pub fn handle_export(action: ExportAction, port: u16) -> Result<()> {
+    match action {
+        ExportAction::Site { out } => export_site(port, &out),
+        ExportAction::Archive { out, types, agent, after } => export_archive(port, &out, types, agent, after),
+    }
+}
+
+fn export_site(port: u16, out: &Path) -> Result<()> {
+    let mut client = DaemonClient::new(port);
+
+    println!("{}", "📦 Gathering memories, tools and artifacts...".bright_cyan());
+    let mut items = Vec::new();
+    for kind in Kind::ALL {
+        let (root, depth) = kind.root();
+        collect(&mut client, root, kind, depth, &mut items)?;
+    }
+
+    for kind in [Kind::Tool, Kind::Memory, Kind::Artifact] {
+        fs::create_dir_all(out.join(kind.dir()))
+            .with_context(|| format!("Failed to create {}", out.display()))?;
+    }
+    fs::write(out.join("style.css"), site::STYLESHEET)?;
+
+    // Lineage values that name an exported object become links
+    let mut links: HashMap<String, String> = HashMap::new();
+    for item in &items {
+        links.insert(item.name.clone(), item.href());
+        links.insert(item.path.clone(), item.href());
+    }
+
+    for item in &items {
+        let body = match item.kind {
+            Kind::Memory => memory_body(item),
+            Kind::Tool | Kind::Artifact => object_body(item, &links),
+        };
+        fs::write(out.join(item.href()), site::page(&item.name, "../", &body))?;
+    }
+
+    let section = |kind: Kind| -> Vec<SiteEntry> {
+        items.iter()
+            .filter(|item| item.kind == kind)
+            .map(|item| SiteEntry {
+                title: item.name.clone(),
+                href: item.href(),
+                description: item.description.clone(),
+                keywords: format!("{} {}", item.agent, item.content.chars().take(2000).collect::<String>()),
+            })
+            .collect()
+    };
+    let generated = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let index = site::index_page(
+        &[("Tools", section(Kind::Tool)), ("Memories", section(Kind::Memory)), ("Artifacts", section(Kind::Artifact))],
+        &generated,
+    );
+    fs::write(out.join("index.html"), index)?;
+
+    println!("{}", format!("✅ Exported {} objects to {}", items.len(), out.display()).green());
+    println!("{}", format!("Open {} in a browser", out.join("index.html").display()).dimmed());
+    Ok(())
+}
+
+fn export_archive(port: u16, out: &Path, types: Vec<Kind>, agent: Option<String>, after: Option<String>) -> Result<()> {
+    let since = after.as_deref().map(parse_date).transpose()?
+        .and_then(|date| DateTime::parse_from_rfc3339(&date).ok());
+    let agent = agent.map(|a| if a.starts_with('@') { a } else { format!("@{}", a) });
+    let types = if types.is_empty() { Kind::ALL.to_vec() } else { types };
+    let mut client = DaemonClient::new(port);
+
+    println!("{}", "📦 Gathering objects for the archive...".bright_cyan());
+    let mut items = Vec::new();
+    for kind in &types {
+        let (root, depth) = kind.root();
+        collect(&mut client, root, *kind, depth, &mut items)?;
+    }
+    items.retain(|item| {
+        let agent_matches = agent.as_ref().is_none_or(|a| item.agent.trim_start_matches('@') == a.trim_start_matches('@'));
+        // Objects without a creation time can't be shown to be recent enough
+        let recent = since.is_none_or(|since| {
+            DateTime::parse_from_rfc3339(&item.created).is_ok_and(|created| created >= since)
+        });
+        agent_matches && recent
+    });
+
+    let tarball = archive::is_tarball(out);
+    let root = if tarball { archive::scratch_dir("export") } else { out.to_path_buf() };
+    fs::create_dir_all(&root).with_context(|| format!("Failed to create {}", root.display()))?;
+
+    let mut manifest = Manifest::new(ArchiveFilters {
+        types: types.iter().map(|k| k.name().to_string()).collect(),
+        agent,
+        after,
+    });
+    for item in &items {
+        let file = archive::file_for(&item.path)?;
+        let target = root.join(&file);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, &item.content).with_context(|| format!("Failed to write {}", target.display()))?;
+        let optional = |s: &str| Some(s.to_string()).filter(|s| !s.is_empty());
+        manifest.objects.push(ManifestEntry {
+            path: item.path.clone(),
+            kind: item.kind.name().to_string(),
+            file,
+            size: item.content.len() as u64,
+            sha256: archive::sha256_hex(item.content.as_bytes()),
+            agent: optional(&item.agent),
+            created: optional(&item.created),
+            metadata: item.info.clone(),
+        });
+    }
+    fs::write(root.join(archive::MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?)?;
+
+    if tarball {
+        let packed = archive::pack(&root, out);
+        let _ = fs::remove_dir_all(&root);
+        packed?;
+    }
+
+    let count = |kind: Kind| items.iter().filter(|item| item.kind == kind).count();
+    println!("{}", format!(
+        "✅ Archived {} tools, {} memories and {} artifacts to {}",
+        count(Kind::Tool), count(Kind::Memory), count(Kind::Artifact), out.display()
+    ).green());
+    Ok(())
+}
+
+/// Read every object under `path`, descending into directories up to `depth` levels
+fn collect(client: &mut DaemonClient, path: &str, kind: Kind, depth: usize, items: &mut Vec<Item>) -> Result<()> {
+    let request = LsRequest { path: path.to_string() }.build_request(generate_id())?;
+    let response = client.request(request)?;
+    let Some(data) = response.data.filter(|_| response.success) else { return Ok(()) };
+
+    for entry in LsResponse::parse_response(&data)?.entries {
+        let child = format!("{}/{}", path.trim_end_matches('/'), entry.name);
+        if entry.entry_type == "directory" {
+            if depth > 0 {
+                collect(client, &child, kind, depth - 1, items)?;
+            }
+            continue;
+        }
+
+        let request = CatRequest { path: child.clone(), version: None }.build_request(generate_id())?;
+        let response = client.request(request)?;
+        let Some(data) = response.data.filter(|_| response.success) else { continue };
+        let Ok(cat) = CatResponse::parse_response(&data) else {
+            eprintln!("{}", format!("⚠️  Skipping {} (not text)", child).yellow());
+            continue;
+        };
+
+        let request = InfoRequest { path: child.clone(), version: None }.build_request(generate_id())?;
+        let info = client.request(request).ok()
+            .and_then(|r| r.data.filter(|_| r.success))
+            .and_then(|data| InfoResponse::parse_response(&data).ok())
+            .map(|info| info.metadata)
+            .unwrap_or(Value::Null);
+
+        let metadata = cat.metadata;
+        let text = |key: &str| info[key].as_str().unwrap_or_default().to_string();
+        items.push(Item {
+            kind,
+            name: if kind == Kind::Artifact { child.trim_start_matches("/artifacts/").to_string() } else { entry.name },
+            description: metadata.as_ref().and_then(|m| m.description.clone()).unwrap_or_else(|| text("description")),
+            created: metadata.as_ref().and_then(|m| m.created.clone()).or(entry.created).unwrap_or_else(|| text("created")),
+            agent: metadata.as_ref().and_then(|m| m.agent.clone()).unwrap_or_else(|| text("agent")),
+            content: cat.content,
+            path: child,
+            info,
+        });
+    }
+    Ok(())
+}
+
+fn summary(item: &Item) -> String {
+    let mut html = String::new();
+    if !item.description.is_empty() {
+        html.push_str(&format!("<p>{}</p>\n", escape_html(&item.description)));
+    }
+    html.push_str(&site::meta_list(&[
+        ("Path", escape_html(&item.path)),
+        ("Created", escape_html(&item.created)),
+        ("Agent", escape_html(&item.agent)),
+    ]));
+    html
+}
+
+/// Tool and artifact pages: metadata, lineage, then the source
+fn object_body(item: &Item, links: &HashMap<String, String>) -> String {
+    let mut html = summary(item);
+
+    let mut lineage = Vec::new();
+    for (key, label) in LINEAGE_KEYS {
+        let values: Vec<String> = match &item.info[*key] {
+            Value::String(s) if !s.is_empty() => vec![s.clone()],
+            Value::Array(values) => values.iter().filter_map(|v| v.as_str().map(String::from)).collect(),
+            _ => continue,
+        };
+        for value in values {
+            let rendered = match links.get(&value) {
+                Some(href) => format!("<a href=\"../{}\">{}</a>", escape_html(href), escape_html(&value)),
+                None => escape_html(&value),
+            };
+            lineage.push((*label, rendered));
+        }
+    }
+    if let Some(tags) = item.info["tags"].as_array() {
+        let tags: Vec<&str> = tags.iter().filter_map(|t| t.as_str()).collect();
+        lineage.push(("Tags", escape_html(&tags.join(", "))));
+    }
+    if !lineage.is_empty() {
+        html.push_str("<h2>Lineage</h2>\n");
+        html.push_str(&site::meta_list(&lineage));
+    }
+
+    let heading = if item.kind == Kind::Tool { "Source" } else { "Content" };
+    html.push_str(&format!("<h2>{}</h2>\n<pre><code>{}</code></pre>\n", heading, escape_html(&item.content)));
+    html
+}
+
+/// Memory pages: the conversation as messages when it parses, raw otherwise
+fn memory_body(item: &Item) -> String {
+    let mut html = summary(item);
+    html.push_str("<h2>Conversation</h2>\n");
+
+    let messages = serde_json::from_str::<Value>(&item.content).ok()
+        .and_then(|v| v["messages"].as_array().cloned());
+    match messages {
+        Some(messages) => {
+            for message in messages {
+                let role = message["role"].as_str().unwrap_or("message");
+                let content = message["content"].as_str().unwrap_or_default();
+                html.push_str(&format!(
+                    "<div class=\"message {}\"><span class=\"role\">{}</span>{}</div>\n",
+                    escape_html(role), escape_html(role), escape_html(content)
+                ));
+            }
+        }
+        None => html.push_str(&format!("<pre>{}</pre>\n", escape_html(&item.content))),
+    }
+    html
+}
@@ -0,0 +1,245 @@
+use anyhow::{Context, Result, anyhow, bail};
+use colored::*;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use crate::GitAction;
+use crate::client::DaemonClient;
+use crate::common::{generate_id, errors::Port42Error, utils::make_executable};
+use crate::config::{Config, GitHookConfig};
+use crate::protocol::{RequestBuilder, ResponseParser};
+use crate::protocol::swim::{SwimRequest, SwimResponse};
+use crate::swim::determine_session_id;
+
+/// Hooks Port 42 knows how to drive
+pub const SUPPORTED_HOOKS: &[&str] = &["prepare-commit-msg", "pre-push"];
+
+/// Marks hook scripts we wrote, so uninstall never touches anyone else's
+const MANAGED_MARKER: &str = "# port42-managed-hook";
+
+const DEFAULT_AGENT: &str = "@ai-engineer";
+
+/// Diffs beyond this are truncated before being sent to an agent
+const MAX_DIFF_CHARS: usize = 60_000;
+
+const COMMIT_MSG_PROMPT: &str = "Write a git commit message for the staged diff below. Use a concise imperative subject line under 72 characters, a blank line, then a short body explaining what changed and why. Reply with the commit message only, no code fences or commentary.";
+
+const PRE_PUSH_PROMPT: &str = "Review the diff below that is about to be pushed. List only concrete problems worth fixing before pushing (bugs, leaked secrets, debug leftovers, missing tests). Reply 'Looks good.' if there are none. Be brief.";
+
+pub fn handle_git(action: GitAction, port: u16) -> Result<()> {
+    match action {
+        GitAction::InstallHooks { hooks, force } => install_hooks(hooks, force),
+        GitAction::UninstallHooks => uninstall_hooks(),
+        GitAction::RunHook { hook, args } => {
+            // A hook failure must never cost the user their commit
+            if let Err(e) = run_hook(port, &hook, &args) {
+                eprintln!("{}", format!("⚠️  port42 {} hook skipped: {:#}", hook, e).yellow());
+            }
+            Ok(())
+        }
+    }
+}
+
+fn install_hooks(hooks: Vec<String>, force: bool) -> Result<()> {
+    let hooks = if hooks.is_empty() {
+        SUPPORTED_HOOKS.iter().map(|h| h.to_string()).collect()
+    } else {
+        hooks
+    };
+    let dir = hooks_dir()?;
+    std::fs::create_dir_all(&dir)?;
+
+    for hook in &hooks {
+        if !SUPPORTED_HOOKS.contains(&hook.as_str()) {
+            bail!("Unsupported hook '{}'. Choose from: {}", hook, SUPPORTED_HOOKS.join(", "));
+        }
+        let path = dir.join(hook);
+        if let Ok(existing) = std::fs::read_to_string(&path) {
+            if !existing.contains(MANAGED_MARKER) {
+                if !force {
+                    bail!("{} already has a {} hook. Use --force to replace it (a backup is kept)", dir.display(), hook);
+                }
+                std::fs::rename(&path, path.with_extension("port42-backup"))?;
+            }
+        }
+
+        let script = format!(
+            "#!/bin/sh\n{}\n# Set PORT42_SKIP_HOOKS=1 to bypass.\n[ -n \"$PORT42_SKIP_HOOKS\" ] && exit 0\ncommand -v port42 >/dev/null 2>&1 || exit 0\nexec port42 git run-hook {} \"$@\"\n",
+            MANAGED_MARKER, hook
+        );
+        std::fs::write(&path, script)?;
+        make_executable(&path)?;
+        println!("{}", format!("🪝 Installed {} hook", hook).green());
+    }
+
+    println!("{}", "Configure handlers under [git.hooks.<name>] in ~/.port42/config.toml".dimmed());
+    Ok(())
+}
+
+fn uninstall_hooks() -> Result<()> {
+    let dir = hooks_dir()?;
+    let mut removed = 0;
+    for hook in SUPPORTED_HOOKS {
+        let path = dir.join(hook);
+        let managed = std::fs::read_to_string(&path)
+            .map(|content| content.contains(MANAGED_MARKER))
+            .unwrap_or(false);
+        if !managed {
+            continue;
+        }
+        std::fs::remove_file(&path)?;
+        // Put back whatever --force replaced
+        let backup = path.with_extension("port42-backup");
+        if backup.exists() {
+            std::fs::rename(&backup, &path)?;
+            println!("{}", format!("↩️  Restored previous {} hook", hook).dimmed());
+        }
+        println!("{}", format!("🪝 Removed {} hook", hook).green());
+        removed += 1;
+    }
+    if removed == 0 {
+        println!("{}", "No Port 42 git hooks installed in this repository".dimmed());
+    }
+    Ok(())
+}
+
+fn run_hook(port: u16, hook: &str, args: &[String]) -> Result<()> {
+    let settings = Config::load_or_default().git_hook(hook);
+    if settings.enabled == Some(false) {
+        return Ok(());
+    }
+
+    match hook {
+        "prepare-commit-msg" => prepare_commit_msg(port, &settings, args),
+        "pre-push" => pre_push(port, &settings),
+        _ => bail!("Unsupported hook '{}'", hook),
+    }
+}
+
+fn prepare_commit_msg(port: u16, settings: &GitHookConfig, args: &[String]) -> Result<()> {
+    let msg_file = args.first().ok_or_else(|| anyhow!("git did not pass a message file"))?;
+    // Leave messages from -m, merges, squashes and amends alone
+    if matches!(args.get(1).map(String::as_str), Some("message" | "merge" | "squash" | "commit")) {
+        return Ok(());
+    }
+
+    let diff = git(&["diff", "--cached"])?;
+    if diff.trim().is_empty() {
+        return Ok(());
+    }
+
+    eprintln!("{}", "✍️  Drafting commit message with Port 42...".dimmed());
+    let message = handle_diff(port, settings, COMMIT_MSG_PROMPT, &diff)?;
+    let message = message.trim();
+    if message.is_empty() {
+        return Ok(());
+    }
+
+    // Keep git's own comment lines below the draft
+    let existing = std::fs::read_to_string(msg_file).unwrap_or_default();
+    std::fs::write(msg_file, format!("{}\n{}", message, existing))?;
+    Ok(())
+}
+
+fn pre_push(port: u16, settings: &GitHookConfig) -> Result<()> {
+    // git sends "<local ref> <local sha> <remote ref> <remote sha>" per ref
+    let mut refs = String::new();
+    std::io::stdin().read_to_string(&mut refs)?;
+
+    let mut diff = String::new();
+    for line in refs.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let [_, local_sha, _, remote_sha] = parts[..] else { continue };
+        if local_sha.chars().all(|c| c == '0') {
+            continue; // deleting a branch
+        }
+        let range = if remote_sha.chars().all(|c| c == '0') {
+            // New branch: compare against the upstream default if we can
+            let base = git(&["merge-base", local_sha, "HEAD@{upstream}"]).unwrap_or_default();
+            if base.trim().is_empty() { format!("{}~1..{}", local_sha, local_sha) } else { format!("{}..{}", base.trim(), local_sha) }
+        } else {
+            format!("{}..{}", remote_sha, local_sha)
+        };
+        diff.push_str(&git(&["diff", &range]).unwrap_or_default());
+    }
+    if diff.trim().is_empty() {
+        return Ok(());
+    }
+
+    eprintln!("{}", "🔎 Port 42 pre-push review...".dimmed());
+    let review = handle_diff(port, settings, PRE_PUSH_PROMPT, &diff)?;
+    eprintln!("{}", review.trim());
+    Ok(())
+}
+
+/// Hand a diff to the configured tool, or else to an agent
+fn handle_diff(port: u16, settings: &GitHookConfig, default_prompt: &str, diff: &str) -> Result<String> {
+    if let Some(ref tool) = settings.tool {
+        return run_tool(tool, diff);
+    }
+
+    let agent = settings.agent.clone()
+        .or_else(|| Config::load_or_default().default_agent)
+        .unwrap_or_else(|| DEFAULT_AGENT.to_string());
+    let prompt = settings.prompt.as_deref().unwrap_or(default_prompt);
+    let diff: String = diff.chars().take(MAX_DIFF_CHARS).collect();
+    let request = SwimRequest {
+        agent,
+        message: format!("{}\n\n```diff\n{}\n```", prompt, diff),
+        memory_context: None,
+        references: None,
+        approval_response: None,
+        provider: None,
+        guidance: None,
+        workspace: None,
+    };
+
+    let (session_id, _) = determine_session_id(None);
+    let mut daemon_request = request.build_request(generate_id())?;
+    if let Some(obj) = daemon_request.payload.as_object_mut() {
+        obj.insert("session_id".to_string(), serde_json::Value::String(session_id));
+    }
+
+    let mut client = DaemonClient::new(port);
+    let response = client.request_timeout(daemon_request, Duration::from_secs(120))?;
+    if !response.success {
+        return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| "Unknown error".to_string())).into());
+    }
+    let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+    Ok(SwimResponse::parse_response(&data)?.message)
+}
+
+fn run_tool(tool: &str, input: &str) -> Result<String> {
+    let path = crate::config::port42_dir().join("commands").join(tool);
+    let mut child = Command::new(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run tool '{}'", tool))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("Tool '{}' exited with {}", tool, output.status);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn hooks_dir() -> Result<PathBuf> {
+    let path = git(&["rev-parse", "--git-path", "hooks"])
+        .context("Not inside a git repository")?;
+    Ok(PathBuf::from(path.trim()))
+}
@@ -0,0 +1,27 @@
+use anyhow::{Context, Result};
+use crate::client::DaemonClient;
+use crate::common::errors::Port42Error;
+use crate::common::generate_id;
+use crate::help_text::*;
+use crate::protocol::{HistoryRequest, VersionHistory, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat};
+
+pub fn handle_history(client: &mut DaemonClient, path: String, format: OutputFormat) -> Result<()> {
+    fetch_history(client, &path)?.display(format)
+}
+
+pub fn fetch_history(client: &mut DaemonClient, path: &str) -> Result<VersionHistory> {
+    let request = HistoryRequest { path: path.to_string() };
+    let response = client.request(request.build_request(generate_id())?)
+        .context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| format!("Cannot trace the history of '{}'", path))).into());
+    }
+
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    let mut history = VersionHistory::parse_response(&data)?;
+    if history.path.is_empty() {
+        history.path = path.to_string();
+    }
+    Ok(history)
+}
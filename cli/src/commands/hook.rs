@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use colored::*;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::debug;
+use crate::HookAction;
+use crate::client::DaemonClient;
+use crate::common::generate_id;
+use crate::protocol::RequestBuilder;
+use crate::protocol::hooks::TrackCommandRequest;
+
+const BEGIN_MARKER: &str = "# >>> port42 hook >>>";
+const END_MARKER: &str = "# <<< port42 hook <<<";
+
+/// Reporting must never slow the prompt down
+pub const RECORD_TIMEOUT: Duration = Duration::from_millis(500);
+
+const ZSH_HOOK: &str = r#"_port42_preexec() { _port42_last_cmd="$1"; }
+_port42_precmd() {
+  local exit_code=$?
+  [[ -n "$_port42_last_cmd" ]] || return
+  command port42 hook record --exit-code "$exit_code" --cwd "$PWD" -- "$_port42_last_cmd" >/dev/null 2>&1 &!
+  unset _port42_last_cmd
+}
+autoload -Uz add-zsh-hook
+add-zsh-hook preexec _port42_preexec
+add-zsh-hook precmd _port42_precmd"#;
+
+const BASH_HOOK: &str = r#"_port42_last_num=$(HISTTIMEFORMAT= builtin history 1 | awk '{print $1}')
+_port42_precmd() {
+  local exit_code=$? entry
+  entry=$(HISTTIMEFORMAT= builtin history 1)
+  if [[ $entry =~ ^\ *([0-9]+)\ +(.*)$ ]] && [[ ${BASH_REMATCH[1]} != "$_port42_last_num" ]]; then
+    _port42_last_num=${BASH_REMATCH[1]}
+    (command port42 hook record --exit-code "$exit_code" --cwd "$PWD" -- "${BASH_REMATCH[2]}" >/dev/null 2>&1 &)
+  fi
+  return $exit_code
+}
+PROMPT_COMMAND="_port42_precmd${PROMPT_COMMAND:+;$PROMPT_COMMAND}""#;
+
+const FISH_HOOK: &str = r#"function _port42_postexec --on-event fish_postexec
+    set -l exit_code $status
+    test -n "$argv[1]"; or return
+    command port42 hook record --exit-code $exit_code --cwd $PWD -- $argv[1] >/dev/null 2>&1 &
+    disown 2>/dev/null
+end"#;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum HookShell {
+    Zsh,
+    Bash,
+    Fish,
+}
+
+impl HookShell {
+    fn script(self) -> &'static str {
+        match self {
+            HookShell::Zsh => ZSH_HOOK,
+            HookShell::Bash => BASH_HOOK,
+            HookShell::Fish => FISH_HOOK,
+        }
+    }
+
+    fn rc_file(self) -> PathBuf {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        match self {
+            HookShell::Zsh => std::env::var("ZDOTDIR")
+                .map(PathBuf::from)
+                .unwrap_or(home)
+                .join(".zshrc"),
+            HookShell::Bash => home.join(".bashrc"),
+            HookShell::Fish => home.join(".config").join("fish").join("config.fish"),
+        }
+    }
+}
+
+pub fn handle_hook(action: HookAction, port: u16) -> Result<()> {
+    match action {
+        HookAction::Install { shell, print } => {
+            let block = hook_block(shell);
+            if print {
+                println!("{}", block);
+                return Ok(());
+            }
+            let rc = shell.rc_file();
+            let existing = std::fs::read_to_string(&rc).unwrap_or_default();
+            let updated = format!("{}\n{}\n", strip_hook_block(&existing).trim_end(), block);
+            if let Some(parent) = rc.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&rc, updated.trim_start())
+                .with_context(|| format!("Failed to write {}", rc.display()))?;
+            println!("{}", format!("🪝 Installed command capture hook in {}", rc.display()).green());
+            println!("{}", "Open a new shell (or source the file) to start reporting commands".dimmed());
+        }
+        HookAction::Uninstall { shell } => {
+            let rc = shell.rc_file();
+            let existing = std::fs::read_to_string(&rc).unwrap_or_default();
+            let stripped = strip_hook_block(&existing);
+            if stripped == existing {
+                println!("{}", format!("No port42 hook found in {}", rc.display()).dimmed());
+                return Ok(());
+            }
+            std::fs::write(&rc, stripped)
+                .with_context(|| format!("Failed to write {}", rc.display()))?;
+            println!("{}", format!("🪝 Removed command capture hook from {}", rc.display()).green());
+        }
+        HookAction::Record { exit_code, cwd, command } => {
+            let command = command.join(" ");
+            if command.trim().is_empty() {
+                return Ok(());
+            }
+            // Best effort: a stopped daemon must not break the user's shell
+            let request = TrackCommandRequest { command, exit_code, cwd, duration_ms: None, tool: None }.build_request(generate_id())?;
+            let mut client = DaemonClient::new(port);
+            if let Err(e) = client.request_timeout(request, RECORD_TIMEOUT) {
+                debug!("failed to record command: {}", e);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn hook_block(shell: HookShell) -> String {
+    format!("{}\n{}\n{}", BEGIN_MARKER, shell.script(), END_MARKER)
+}
+
+/// Remove a previously installed hook block, leaving everything else intact
+fn strip_hook_block(content: &str) -> String {
+    let (Some(start), Some(end)) = (content.find(BEGIN_MARKER), content.find(END_MARKER)) else {
+        return content.to_string();
+    };
+    if end < start {
+        return content.to_string();
+    }
+    let after = &content[end + END_MARKER.len()..];
+    format!("{}{}", content[..start].trim_end_matches('\n'), after)
+}
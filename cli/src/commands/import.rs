@@ -0,0 +1,103 @@
+use anyhow::{Result, bail};
+use colored::*;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::client::DaemonClient;
+use crate::common::archive::{self, ConflictPolicy, Manifest};
+use crate::common::generate_id;
+use crate::protocol::{ImportObjectRequest, ImportObjectResponse, RequestBuilder, ResponseParser};
+
+pub fn handle_import(port: u16, source: &Path, policy: ConflictPolicy, json: bool) -> Result<()> {
+    if !source.exists() {
+        bail!("No archive at {}", source.display());
+    }
+    // Tarballs are unpacked to a scratch directory first
+    let scratch = source.is_file().then(|| archive::scratch_dir("import"));
+    if let Some(ref dir) = scratch {
+        archive::unpack(source, dir)?;
+    }
+    let result = import_dir(port, scratch.as_deref().unwrap_or(source), policy, json);
+    if let Some(dir) = scratch {
+        let _ = fs::remove_dir_all(dir);
+    }
+    result
+}
+
+fn import_dir(port: u16, root: &Path, policy: ConflictPolicy, json: bool) -> Result<()> {
+    let manifest = Manifest::load(root)?;
+    let contents = manifest.verify(root)?;
+    let mut client = DaemonClient::new(port);
+
+    if !json {
+        println!("{}", format!(
+            "📥 Importing {} objects exported {} ({} on conflict)...",
+            manifest.objects.len(), manifest.created, policy.name()
+        ).bright_cyan());
+    }
+
+    let mut results = Vec::new();
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for (entry, content) in manifest.objects.iter().zip(contents) {
+        let request = ImportObjectRequest {
+            path: entry.path.clone(),
+            kind: entry.kind.clone(),
+            content,
+            metadata: entry.metadata.clone(),
+            on_conflict: policy.name().to_string(),
+        };
+        let result = match import_one(&mut client, request) {
+            Ok(imported) => {
+                if !json {
+                    let renamed = if imported.path != entry.path { format!(" → {}", imported.path) } else { String::new() };
+                    println!("  {} {}{}", status_label(&imported.status), entry.path, renamed.dimmed());
+                }
+                json!({ "source": entry.path, "path": imported.path, "status": imported.status })
+            }
+            Err(e) => {
+                if !json {
+                    println!("  {} {} {}", status_label("failed"), entry.path, format!("({})", e).dimmed());
+                }
+                json!({ "source": entry.path, "status": "failed", "error": e.to_string() })
+            }
+        };
+        *counts.entry(result["status"].as_str().unwrap_or_default().to_string()).or_default() += 1;
+        results.push(result);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&json!({ "objects": results, "counts": counts }))?);
+    } else {
+        let summary: Vec<String> = counts.iter().map(|(status, n)| format!("{} {}", n, status)).collect();
+        println!("{}", format!("✅ Import finished: {}", summary.join(", ")).green());
+    }
+
+    if let Some(failed) = counts.get("failed") {
+        bail!("{} of {} objects could not be imported", failed, manifest.objects.len());
+    }
+    Ok(())
+}
+
+fn import_one(client: &mut DaemonClient, request: ImportObjectRequest) -> Result<ImportObjectResponse> {
+    let path = request.path.clone();
+    let response = client.request(request.build_request(generate_id())?)?;
+    if !response.success {
+        bail!(response.error.unwrap_or_else(|| "import rejected".to_string()));
+    }
+    let mut imported = ImportObjectResponse::parse_response(&response.data.unwrap_or_default())?;
+    if imported.path.is_empty() {
+        imported.path = path;
+    }
+    Ok(imported)
+}
+
+fn status_label(status: &str) -> ColoredString {
+    match status {
+        "created" => "created    ".green(),
+        "overwritten" => "overwritten".yellow(),
+        "renamed" => "renamed    ".cyan(),
+        "skipped" => "skipped    ".dimmed(),
+        _ => "failed     ".red(),
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::{Result, anyhow};
+use colored::*;
+use crate::IndexAction;
+use crate::client::DaemonClient;
+use crate::common::{generate_id, errors::Port42Error, providers::{self, ProviderArgs}};
+use crate::common::cache::ResponseCache;
+use crate::common::semantic::{self, IndexEntry, SemanticIndex};
+use crate::config::Config;
+use crate::protocol::{CatRequest, CatResponse, LsRequest, LsResponse, ProviderSelection, RequestBuilder, ResponseParser};
+use crate::protocol::embeddings::{EmbedRequest, EmbedResponse};
+
+/// Roots whose contents are embedded: tools and conversation memory
+const INDEXED_ROOTS: &[(&str, &str)] = &[("/commands", "command"), ("/memory", "memory")];
+
+/// Texts sent per embed request
+const EMBED_BATCH: usize = 32;
+
+/// Characters of each document that go into its embedding
+const MAX_EMBED_CHARS: usize = 4000;
+
+struct Document {
+    path: String,
+    entry_type: String,
+    title: String,
+    snippet: String,
+    text: String,
+}
+
+pub fn handle_index(action: IndexAction, port: u16) -> Result<()> {
+    let mut client = DaemonClient::new(port);
+    match action {
+        IndexAction::Build => build_index(&mut client, true),
+        IndexAction::Update => build_index(&mut client, false),
+        IndexAction::Stats => {
+            let index = SemanticIndex::load()?;
+            println!("{}", "🧭 Semantic index".bright_blue().bold());
+            println!("  Entries:  {}", index.entries.len().to_string().bright_cyan());
+            println!("  Model:    {}", index.model.as_deref().unwrap_or("none").bright_cyan());
+            if let Some(updated) = index.updated_at {
+                println!("  Updated:  {}", updated.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string().bright_cyan());
+            }
+            println!("  Location: {}", semantic::index_path().display().to_string().dimmed());
+            if index.is_empty() {
+                println!("\n{}", "Build it with 'port42 index build'".dimmed());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Provider used for embeddings: the configured default, with the provider's
+/// own embedding model rather than the chat model
+pub fn embedding_provider() -> Result<Option<ProviderSelection>> {
+    let selection = providers::resolve_provider(ProviderArgs::default(), None, &Config::load_or_default())?;
+    Ok(selection.map(|s| ProviderSelection { model: None, ..s }))
+}
+
+/// Embed texts through the daemon, returning the vectors and model name
+pub fn embed(client: &mut DaemonClient, texts: Vec<String>, provider: &Option<ProviderSelection>) -> Result<EmbedResponse> {
+    let expected = texts.len();
+    let request = EmbedRequest { texts, provider: provider.clone() }.build_request(generate_id())?;
+    let response = client.request(request)?;
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(Port42Error::Daemon(format!("Embedding failed: {}", error)).into());
+    }
+    let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+    let embedded = EmbedResponse::parse_response(&data)?;
+    if embedded.vectors.len() != expected {
+        return Err(anyhow!("Daemon returned {} embeddings for {} texts", embedded.vectors.len(), expected));
+    }
+    Ok(embedded)
+}
+
+fn build_index(client: &mut DaemonClient, rebuild: bool) -> Result<()> {
+    let provider = embedding_provider()?;
+    let mut index = if rebuild { SemanticIndex::default() } else { SemanticIndex::load()? };
+
+    println!("{}", "🧭 Gathering memories and tools...".bright_cyan());
+    let documents = collect_documents(client)?;
+
+    // Keep vectors whose source text is unchanged, embed the rest
+    let mut previous: std::collections::HashMap<String, IndexEntry> = index.entries.drain(..)
+        .map(|e| (e.path.clone(), e))
+        .collect();
+    let mut kept = Vec::new();
+    let mut pending = Vec::new();
+    for doc in documents {
+        let hash = ResponseCache::key(&[&doc.text]);
+        match previous.remove(&doc.path) {
+            Some(entry) if entry.content_hash == hash => kept.push(entry),
+            _ => pending.push((doc, hash)),
+        }
+    }
+    let removed = previous.len();
+
+    let total = pending.len();
+    let mut fresh = Vec::with_capacity(total);
+    for batch in pending.chunks(EMBED_BATCH) {
+        let texts = batch.iter().map(|(doc, _)| doc.text.clone()).collect();
+        let embedded = embed(client, texts, &provider)?;
+        if index.model.is_some() && embedded.model.is_some() && index.model != embedded.model && !kept.is_empty() {
+            // Vectors from different models don't compare, start over
+            eprintln!("{}", "⚠️  Embedding model changed, rebuilding the whole index".yellow());
+            return build_index(client, true);
+        }
+        index.model = embedded.model.or(index.model.take());
+        for ((doc, hash), vector) in batch.iter().zip(embedded.vectors) {
+            fresh.push(IndexEntry {
+                path: doc.path.clone(),
+                entry_type: doc.entry_type.clone(),
+                title: doc.title.clone(),
+                snippet: doc.snippet.clone(),
+                content_hash: hash.clone(),
+                vector,
+            });
+        }
+        eprint!("\r  Embedded {}/{}", fresh.len(), total);
+    }
+    if total > 0 {
+        eprintln!();
+    }
+
+    let unchanged = kept.len();
+    index.entries = kept;
+    index.entries.extend(fresh);
+    index.save()?;
+
+    println!("{}", format!("✅ Indexed {} items ({} embedded, {} unchanged, {} removed)",
+        index.entries.len(), total, unchanged, removed).green());
+    Ok(())
+}
+
+fn collect_documents(client: &mut DaemonClient) -> Result<Vec<Document>> {
+    let mut documents = Vec::new();
+    for (root, entry_type) in INDEXED_ROOTS {
+        let request = LsRequest { path: root.to_string() }.build_request(generate_id())?;
+        let response = client.request(request)?;
+        let Some(data) = response.data.filter(|_| response.success) else { continue };
+
+        for entry in LsResponse::parse_response(&data)?.entries {
+            let path = format!("{}/{}", root, entry.name);
+            let request = CatRequest { path: path.clone(), version: None }.build_request(generate_id())?;
+            let response = client.request(request)?;
+            let Some(data) = response.data.filter(|_| response.success) else { continue };
+            let Ok(cat) = CatResponse::parse_response(&data) else { continue };
+
+            let description = cat.metadata.and_then(|m| m.description).unwrap_or_default();
+            let body: String = cat.content.chars().take(MAX_EMBED_CHARS).collect();
+            let snippet = if description.is_empty() {
+                body.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(160).collect()
+            } else {
+                description.clone()
+            };
+            documents.push(Document {
+                text: format!("{}\n{}\n{}", entry.name, description, body),
+                path,
+                entry_type: entry_type.to_string(),
+                title: entry.name,
+                snippet,
+            });
+        }
+    }
+    Ok(documents)
+}
@@ -1,16 +1,17 @@
-use anyhow::{Result, Context, bail};
+use anyhow::{Result, Context};
+use crate::common::errors::Port42Error;
 use crate::client::DaemonClient;
 use crate::help_text::*;
-use crate::protocol::{InfoRequest, InfoResponse, RequestBuilder, ResponseParser};
+use crate::protocol::{InfoRequest, InfoResponse, RequestBuilder, ResponseParser, check_version};
 use crate::display::{Displayable, OutputFormat};
 
 pub fn handle_info(client: &mut DaemonClient, path: String) -> Result<()> {
-    handle_info_with_format(client, path, OutputFormat::Plain)
+    handle_info_with_format(client, path, None, OutputFormat::Plain)
 }
 
-pub fn handle_info_with_format(client: &mut DaemonClient, path: String, format: OutputFormat) -> Result<()> {
+pub fn handle_info_with_format(client: &mut DaemonClient, path: String, version: Option<u32>, format: OutputFormat) -> Result<()> {
     // Create request
-    let request = InfoRequest { path: path.clone() };
+    let request = InfoRequest { path: path.clone(), version };
     let daemon_request = request.build_request(format!("info-{}", chrono::Utc::now().timestamp()))?;
     
     // Send request and get response
@@ -18,15 +19,20 @@ pub fn handle_info_with_format(client: &mut DaemonClient, path: String, format:
         .context(ERR_CONNECTION_LOST)?;
     
     if !response.success {
-        bail!(format_error_with_suggestion(
+        return Err(Port42Error::from_lookup(
+            response.error.as_deref(),
             ERR_PATH_NOT_FOUND,
-            &format!("Cannot inspect essence of '{}'", path)
-        ));
+            &match version {
+                Some(n) => format!("Cannot inspect version {} of '{}'; see 'port42 history {}'", n, path, path),
+                None => format!("Cannot inspect essence of '{}'", path),
+            }
+        ).into());
     }
     
     // Parse response
     let data = response.data.context(ERR_INVALID_RESPONSE)?;
     let mut info_response = InfoResponse::parse_response(&data)?;
+    check_version(&path, version, info_response.version())?;
     
     // Set path if not provided by response
     if info_response.path.is_empty() {
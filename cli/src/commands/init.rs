@@ -0,0 +1,17 @@
+use anyhow::Result;
+use colored::*;
+use crate::project::{Project, PROJECT_DIR};
+
+pub fn handle_init(name: Option<String>, force: bool) -> Result<()> {
+    let root = std::env::current_dir()?;
+    let project = Project::init(&root, name, force)?;
+    let dir = root.join(PROJECT_DIR);
+
+    println!("{}", format!("🌱 Initialized Port 42 project '{}'", project.config.name).green());
+    println!("  {} {}", "Settings:".dimmed(), dir.join("project.toml").display());
+    println!("  {} {}", "Notes:   ".dimmed(), dir.join("context.md").display());
+    println!();
+    println!("{}", "Add default refs and agent preferences to project.toml, and anything agents".dimmed());
+    println!("{}", "should know about the project to context.md.".dimmed());
+    Ok(())
+}
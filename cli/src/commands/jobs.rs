@@ -0,0 +1,156 @@
+use anyhow::{Result, Context};
+use colored::*;
+use std::io::Write;
+use std::time::Duration;
+use crate::JobsAction;
+use crate::client::DaemonClient;
+use crate::common::{generate_id, errors::Port42Error};
+use crate::display::{Displayable, OutputFormat};
+use crate::help_text::*;
+use crate::protocol::{DaemonRequest, DeclareRelationResponse, RequestBuilder, ResponseParser};
+use crate::protocol::hello::FEATURE_DETACH;
+use crate::protocol::jobs::{self, Job, JobCancelRequest, JobList, JobListRequest, JobState, JobStatusRequest, JobSubmitted};
+
+/// How often `attach` asks the daemon for progress
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn handle_jobs(port: u16, action: JobsAction, format: OutputFormat) -> Result<()> {
+    let mut client = DaemonClient::new(port);
+    match action {
+        JobsAction::List { active } => {
+            let mut list = fetch_list(&mut client)?;
+            if active {
+                list.jobs.retain(|job| !job.status.is_finished());
+            }
+            list.display(format)
+        }
+        JobsAction::Status { job_id } => fetch_job(&mut client, &job_id)?.display(format),
+        JobsAction::Attach { job_id } => attach(&mut client, &job_id, format),
+        JobsAction::Cancel { job_id } => {
+            let request = JobCancelRequest { job_id: job_id.clone() }.build_request(generate_id())?;
+            let response = client.request(request).context(ERR_CONNECTION_LOST)?;
+            if !response.success {
+                let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+                return Err(Port42Error::from_daemon(&error).into());
+            }
+            println!("{}", format!("🛑 Cancelled job {}", job_id).yellow());
+            Ok(())
+        }
+    }
+}
+
+/// Whether `--detach` can be honoured. A daemon that can't run requests in
+/// the background would quietly run them in the foreground anyway, so say so
+/// and let the caller do that itself.
+pub fn can_detach(client: &mut DaemonClient) -> Result<bool> {
+    if client.supports(FEATURE_DETACH)? {
+        return Ok(true);
+    }
+    eprintln!("ℹ️  This daemon can't run requests in the background; running this one here instead");
+    Ok(false)
+}
+
+/// Send an AI request to run in the background and report its job ID
+pub fn submit_detached(client: &mut DaemonClient, mut request: DaemonRequest) -> Result<JobSubmitted> {
+    jobs::detach(&mut request);
+    let response = client.request(request).context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(Port42Error::from_daemon(&error).into());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    let submitted = JobSubmitted::parse_response(&data)?;
+    println!("{}", format!("🚀 Submitted job {}", submitted.job_id).bright_blue());
+    println!("{}", format!("Follow it with 'port42 jobs attach {}'", submitted.job_id).dimmed());
+    Ok(submitted)
+}
+
+fn fetch_list(client: &mut DaemonClient) -> Result<JobList> {
+    let response = client.request(JobListRequest.build_request(generate_id())?)
+        .context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(Port42Error::from_daemon(&error).into());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    JobList::parse_response(&data)
+}
+
+fn fetch_job(client: &mut DaemonClient, job_id: &str) -> Result<Job> {
+    let request = JobStatusRequest { job_id: job_id.to_string() }.build_request(generate_id())?;
+    let response = client.request(request).context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| format!("job {} not found", job_id));
+        return Err(Port42Error::from_daemon(&error).into());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    Job::parse_response(&data)
+}
+
+/// Follow a job until it finishes, then show what it produced.
+/// Interrupting only stops watching; the daemon carries on.
+fn attach(client: &mut DaemonClient, job_id: &str, format: OutputFormat) -> Result<()> {
+    let live = !format.is_structured() && atty::is(atty::Stream::Stdout);
+    let mut last_line = String::new();
+    let job = loop {
+        let job = fetch_job(client, job_id)?;
+        if !format.is_structured() {
+            let line = job.progress_line();
+            if live {
+                // Pad so a shorter line fully covers the previous one
+                print!("\r{} {:<width$}", "⏳".bright_blue(), line, width = last_line.len());
+                let _ = std::io::stdout().flush();
+            } else if line != last_line {
+                println!("{}", line);
+            }
+            last_line = line;
+        }
+        if job.status.is_finished() {
+            break job;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+    if live {
+        println!();
+    }
+
+    if format.is_structured() {
+        job.display(format)?;
+    } else {
+        show_result(&job)?;
+    }
+    match job.status {
+        JobState::Failed => {
+            let error = job.error.unwrap_or_else(|| "Unknown error".to_string());
+            Err(Port42Error::from_daemon(&error)).with_context(|| format!("Job {} failed", job.id))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn show_result(job: &Job) -> Result<()> {
+    match job.status {
+        JobState::Cancelled => println!("{}", format!("🛑 Job {} was cancelled", job.id).yellow()),
+        JobState::Completed => {
+            let Some(ref result) = job.result else {
+                println!("{}", format!("✅ Job {} completed", job.id).green());
+                return Ok(());
+            };
+            match job.kind.as_str() {
+                "swim" => {
+                    if let Some(message) = result.get("message").and_then(|m| m.as_str()) {
+                        println!("{}", message);
+                    }
+                    if let Some(session) = result.get("session_id").and_then(|s| s.as_str()) {
+                        println!();
+                        println!("{}", format!("Use 'port42 memory {}' to review this thread", session).dimmed());
+                    }
+                }
+                "declare" => DeclareRelationResponse::parse_response(result)?.display(OutputFormat::Plain)?,
+                _ => println!("{}", serde_json::to_string_pretty(result)?),
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use anyhow::{Result, bail};
+use colored::*;
+use std::io::{self, BufRead, Write};
+use crate::KeysAction;
+use crate::help_text::*;
+use crate::common::{errors::Port42Error, keychain};
+use crate::common::providers::{self, KeySource};
+
+pub fn handle_keys(action: KeysAction) -> Result<()> {
+    match action {
+        KeysAction::Set { provider, stdin } => set_key(&provider, stdin),
+        KeysAction::Get { provider, reveal } => get_key(&provider, reveal),
+        KeysAction::Remove { provider } => remove_key(&provider),
+        KeysAction::List => list_keys(),
+    }
+}
+
+fn validate_key_provider(provider: &str) -> Result<()> {
+    providers::validate_provider(provider)?;
+    if !providers::requires_api_key(provider) {
+        bail!(format_error_with_suggestion(
+            &format!("🔑 Provider '{}' does not use an API key", provider),
+            "Local models run without credentials"
+        ));
+    }
+    Ok(())
+}
+
+fn set_key(provider: &str, from_stdin: bool) -> Result<()> {
+    validate_key_provider(provider)?;
+
+    let secret = if from_stdin || !atty::is(atty::Stream::Stdin) {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line)?;
+        line
+    } else {
+        read_hidden(&format!("🔑 API key for {}: ", provider))?
+    };
+    let secret = secret.trim();
+    if secret.is_empty() {
+        bail!("No key entered - nothing stored");
+    }
+
+    keychain::set_key(provider, secret)?;
+    println!("{}", format!("🔐 Key for '{}' stored in the OS keychain", provider).green());
+    println!("{}", MSG_KEYS_RESTART_HINT.dimmed());
+    Ok(())
+}
+
+fn get_key(provider: &str, reveal: bool) -> Result<()> {
+    validate_key_provider(provider)?;
+
+    match keychain::get_key(provider)? {
+        Some(secret) if reveal => println!("{}", secret),
+        Some(secret) => println!("{}: {}", provider.bright_cyan(), keychain::mask_key(&secret)),
+        None => {
+            return Err(Port42Error::NotFound(format!("🔑 No key stored for '{}'", provider)).into());
+        }
+    }
+    Ok(())
+}
+
+fn remove_key(provider: &str) -> Result<()> {
+    validate_key_provider(provider)?;
+
+    if keychain::remove_key(provider)? {
+        println!("{}", format!("🗑️  Key for '{}' removed from the OS keychain", provider).green());
+    } else {
+        println!("{}", format!("🔑 No key stored for '{}'", provider).dimmed());
+    }
+    Ok(())
+}
+
+fn list_keys() -> Result<()> {
+    println!("{}", "🔑 Provider API keys".bright_blue().bold());
+    println!();
+
+    for provider in providers::KNOWN_PROVIDERS.iter().filter(|p| providers::requires_api_key(p)) {
+        let status = match providers::find_api_key_with_source(provider) {
+            Some((secret, KeySource::Env(var))) => {
+                format!("{}  {}", keychain::mask_key(&secret), format!("(env: {})", var).dimmed())
+            }
+            Some((secret, KeySource::Keychain)) => {
+                format!("{}  {}", keychain::mask_key(&secret), "(keychain)".dimmed())
+            }
+            None => "not set".yellow().to_string(),
+        };
+        println!("  {:<10} {}", provider.bright_cyan(), status);
+    }
+
+    println!();
+    println!("{}", "Environment variables take precedence over keychain entries.".dimmed());
+    Ok(())
+}
+
+/// Prompt for a secret without echoing it to the terminal
+fn read_hidden(prompt: &str) -> Result<String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+    use crossterm::terminal;
+
+    print!("{}", prompt);
+    io::stdout().flush()?;
+
+    terminal::enable_raw_mode()?;
+    let mut secret = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(KeyEvent { code, modifiers, kind: KeyEventKind::Press, .. })) => match code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(anyhow::anyhow!("Cancelled"));
+                }
+                KeyCode::Backspace => {
+                    secret.pop();
+                }
+                KeyCode::Char(c) => secret.push(c),
+                _ => {}
+            },
+            Ok(Event::Paste(text)) => secret.push_str(&text),
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    println!();
+
+    result.map(|_| secret)
+}
@@ -1,4 +1,5 @@
 use anyhow::{Result, Context};
+use crate::common::errors::Port42Error;
 use crate::client::DaemonClient;
 use crate::help_text::*;
 use crate::protocol::{LsRequest, LsResponse, RequestBuilder, ResponseParser};
@@ -21,10 +22,11 @@ pub fn handle_ls_with_format(client: &mut DaemonClient, path: Option<String>, fo
         .context(ERR_CONNECTION_LOST)?;
     
     if !response.success {
-        anyhow::bail!(format_error_with_suggestion(
+        return Err(Port42Error::from_lookup(
+            response.error.as_deref(),
             ERR_PATH_NOT_FOUND,
             &format!("Path '{}' does not exist in reality", path)
-        ));
+        ).into());
     }
     
     // Parse response
@@ -0,0 +1,32 @@
+use anyhow::{Context, Result};
+use colored::*;
+use std::path::{Path, PathBuf};
+use crate::help_handler::reference_command;
+
+/// Write port42.1 and a page per subcommand (port42-swim.1, port42-memory-search.1, ...)
+pub fn handle_manpages(out_dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(out_dir)
+        .with_context(|| format!("Cannot create {}", out_dir.display()))?;
+
+    // Prose help is coloured for the terminal; roff wants it plain
+    let colorize = colored::control::SHOULD_COLORIZE.should_colorize();
+    colored::control::set_override(false);
+    let cmd = reference_command();
+    colored::control::set_override(colorize);
+
+    let mut written = Vec::new();
+    write_pages(&cmd, out_dir, &mut written)
+        .with_context(|| format!("Cannot write man pages to {}", out_dir.display()))?;
+
+    println!("{}", format!("📜 Wrote {} man pages to {}", written.len(), out_dir.display()).bright_green());
+    println!("{}", format!("Read them with: man -l {}", out_dir.join("port42.1").display()).dimmed());
+    Ok(())
+}
+
+fn write_pages(cmd: &clap::Command, out_dir: &Path, written: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set() && s.get_name() != "help") {
+        write_pages(sub, out_dir, written)?;
+    }
+    written.push(clap_mangen::Man::new(cmd.clone()).generate_to(out_dir)?);
+    Ok(())
+}
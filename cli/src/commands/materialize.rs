@@ -0,0 +1,190 @@
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crate::client::DaemonClient;
+use crate::commands::tree::{self, TreeOptions};
+use crate::common::archive::sha256_hex;
+use crate::common::materialize::{self, Change, Links};
+use crate::common::generate_id;
+use crate::help_text::*;
+use crate::protocol::{CatRequest, RequestBuilder, ResponseParser, TreeNode, UpdatePathRequest};
+use crate::protocol::artifacts::ArtifactContent;
+
+/// What was asked for and where it goes
+struct Target {
+    vfs_path: String,
+    dest: PathBuf,
+}
+
+pub fn handle_materialize(port: u16, path: String, dest: Option<PathBuf>, watch: bool, interval_ms: u64, force: bool) -> Result<()> {
+    let mut client = DaemonClient::new(port);
+    let vfs_path = if path.starts_with('/') { path } else { format!("/{}", path) };
+    let vfs_path = vfs_path.trim_end_matches('/').to_string();
+    let objects = list_objects(&mut client, &vfs_path)?;
+
+    let name = vfs_path.rsplit('/').next().filter(|n| !n.is_empty()).unwrap_or("vfs");
+    let dest = match dest {
+        // A single object copied into an existing directory keeps its name
+        Some(dest) if dest.is_dir() && objects.len() == 1 && objects[0] == vfs_path => dest.join(name),
+        Some(dest) => dest,
+        None => PathBuf::from(name),
+    };
+    let dest = std::path::absolute(&dest).with_context(|| format!("Invalid destination {}", dest.display()))?;
+    let target = Target { vfs_path, dest };
+
+    let mut links = Links::load();
+    let mut conflicts = HashMap::new();
+    let (synced, held) = sync(&mut client, &target, &objects, &mut links, &mut conflicts, watch, force)?;
+    links.save()?;
+
+    println!("{}", format_materialized(synced, &target.vfs_path, &target.dest).bright_green());
+    if !watch {
+        if held > 0 {
+            bail!("{} local file{} kept; send edits with --watch or 'port42 update <path> --from <file>', or overwrite them with --force", held, if held == 1 { " was" } else { "s were" });
+        }
+        return Ok(());
+    }
+
+    println!("{}", format!("👁  Keeping {} in sync with {} (Ctrl+C to stop)", target.dest.display(), target.vfs_path).bright_cyan());
+    loop {
+        std::thread::sleep(Duration::from_millis(interval_ms));
+        // New objects under a directory are picked up as they appear
+        let result = list_objects(&mut client, &target.vfs_path)
+            .and_then(|objects| sync(&mut client, &target, &objects, &mut links, &mut conflicts, true, false));
+        if let Err(e) = result {
+            eprintln!("{}", format!("⚠️  Sync failed, retrying: {}", e).yellow());
+        }
+        links.save()?;
+    }
+}
+
+/// The object at `path`, or every object beneath it
+fn list_objects(client: &mut DaemonClient, path: &str) -> Result<Vec<String>> {
+    if read(client, path)?.is_some() {
+        return Ok(vec![path.to_string()]);
+    }
+    let options = TreeOptions { level: None, types: Vec::new(), sizes: false };
+    let root = tree::load(client, path, &options)?;
+    let mut files = Vec::new();
+    collect(&root, &mut files);
+    Ok(files)
+}
+
+fn collect(node: &TreeNode, out: &mut Vec<String>) {
+    for child in &node.children {
+        if child.is_dir() {
+            collect(child, out);
+        } else {
+            out.push(child.path.clone());
+        }
+    }
+}
+
+/// An object's bytes, or None when there is nothing at the path
+fn read(client: &mut DaemonClient, path: &str) -> Result<Option<ArtifactContent>> {
+    let request = CatRequest { path: path.to_string(), version: None }.build_request(generate_id())?;
+    let response = client.request(request).context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Ok(None);
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    Ok(Some(ArtifactContent::parse_response(&data)?))
+}
+
+/// Reconcile each object with its local file. Returns how many files match
+/// their object, and how many were left alone holding edits that weren't sent
+fn sync(
+    client: &mut DaemonClient,
+    target: &Target,
+    objects: &[String],
+    links: &mut Links,
+    conflicts: &mut HashMap<PathBuf, String>,
+    push: bool,
+    force: bool,
+) -> Result<(usize, usize)> {
+    let (mut synced, mut held) = (0, 0);
+    for vfs_path in objects {
+        let Some(remote) = read(client, vfs_path)? else { continue };
+        let file = materialize::local_path(&target.vfs_path, vfs_path, &target.dest);
+        let remote_hash = sha256_hex(&remote.bytes);
+
+        let Ok(local) = fs::read(&file) else {
+            write(&file, &remote.bytes)?;
+            links.record(&file, vfs_path, &remote.bytes);
+            synced += 1;
+            continue;
+        };
+        let base = links.files.get(&file)
+            .filter(|link| link.vfs_path == *vfs_path)
+            .map(|link| link.sha256.clone())
+            .unwrap_or_default();
+
+        // Deleting the conflict file says the local copy is the merge
+        let conflict_file = materialize::conflict_path(&file);
+        if conflicts.contains_key(&file) && !conflict_file.exists() {
+            conflicts.remove(&file);
+            links.record(&file, vfs_path, &remote.bytes);
+            held += 1;
+            continue;
+        }
+
+        match materialize::compare(&base, &sha256_hex(&local), &remote_hash) {
+            Change::Unchanged => {}
+            Change::Converged => links.record(&file, vfs_path, &local),
+            Change::Remote => {
+                write(&file, &remote.bytes)?;
+                links.record(&file, vfs_path, &remote.bytes);
+                if push {
+                    println!("{}", format!("⬇️  {} changed in the VFS; updated {}", vfs_path, file.display()).bright_cyan());
+                }
+            }
+            _ if force => {
+                write(&file, &remote.bytes)?;
+                links.record(&file, vfs_path, &remote.bytes);
+            }
+            Change::Local if push => {
+                store(client, vfs_path, local.clone())?;
+                links.record(&file, vfs_path, &local);
+                println!("{}", format!("⬆️  Sent {} to {}", file.display(), vfs_path).bright_green());
+            }
+            Change::Conflict if push => {
+                held += 1;
+                if conflicts.get(&file) == Some(&remote_hash) {
+                    continue;
+                }
+                write(&conflict_file, &remote.bytes)?;
+                conflicts.insert(file.clone(), remote_hash);
+                eprintln!("{}", format_materialize_conflict(vfs_path, &file, &conflict_file).yellow());
+                continue;
+            }
+            Change::Local | Change::Conflict => {
+                held += 1;
+                eprintln!("{}", format!("⚠️  {} has local edits; left as it is", file.display()).yellow());
+                continue;
+            }
+        }
+        synced += 1;
+    }
+    Ok((synced, held))
+}
+
+fn write(file: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    fs::write(file, bytes).with_context(|| format!("Failed to write {}", file.display()))
+}
+
+/// Send a local edit as a new version of the object, keeping its metadata
+fn store(client: &mut DaemonClient, vfs_path: &str, content: Vec<u8>) -> Result<()> {
+    let request = UpdatePathRequest { path: vfs_path.to_string(), content }
+        .build_request(generate_id())?;
+    let response = client.request(request).context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        bail!("Could not store {}: {}", vfs_path, response.error.unwrap_or_else(|| "unknown error".to_string()));
+    }
+    Ok(())
+}
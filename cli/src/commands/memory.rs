@@ -2,16 +2,50 @@ use anyhow::{Result, anyhow};
 use colored::*;
 use crate::MemoryAction;
 use crate::client::DaemonClient;
-use crate::protocol::{MemoryListRequest, MemoryDetailRequest, MemoryListResponse, MemoryDetailResponse, RequestBuilder, ResponseParser};
+use crate::protocol::{MemoryListRequest, MemoryDetailRequest, MemoryForkRequest, MemoryMergeRequest, MemoryRenameRequest, MemoryListResponse, MemoryDetailResponse, MemoryForkResponse, MemoryMergeResponse, RequestBuilder, ResponseParser};
 use crate::display::{Displayable, OutputFormat};
 use crate::common::{generate_id, errors::Port42Error};
 use crate::help_text;
 
+/// Parse `fork <session_id> [--at <message>]`, given the words after "fork"
+pub fn parse_fork<S: AsRef<str>>(args: &[S]) -> Result<MemoryAction> {
+    let usage = || Port42Error::Usage(help_text::ERR_MEMORY_FORK_USAGE.to_string());
+    let args: Vec<&str> = args.iter().map(AsRef::as_ref).collect();
+    let (session_id, at) = match args.as_slice() {
+        [session_id] => (session_id, None),
+        [session_id, "--at", at] => (session_id, Some(at.parse::<usize>().ok().filter(|n| *n > 0).ok_or_else(usage)?)),
+        _ => return Err(usage().into()),
+    };
+    Ok(MemoryAction::Fork { session_id: session_id.to_string(), at })
+}
+
+/// Parse `merge <id> <id>... [--name <name>] [--interleave]`, given the words after "merge"
+pub fn parse_merge<S: AsRef<str>>(args: &[S]) -> Result<MemoryAction> {
+    let usage = || Port42Error::Usage(help_text::ERR_MEMORY_MERGE_USAGE.to_string());
+    let mut session_ids = Vec::new();
+    let mut name = None;
+    let mut interleave = false;
+    let mut args = args.iter().map(AsRef::as_ref);
+    while let Some(arg) = args.next() {
+        match arg {
+            "--name" => name = Some(args.next().ok_or_else(usage)?.to_string()),
+            "--interleave" => interleave = true,
+            id if !id.starts_with("--") => session_ids.push(id.to_string()),
+            _ => return Err(usage().into()),
+        }
+    }
+    if session_ids.len() < 2 {
+        return Err(usage().into());
+    }
+    Ok(MemoryAction::Merge { session_ids, name, interleave })
+}
+
 pub fn handle_memory(port: u16, action: Option<MemoryAction>) -> Result<()> {
-    handle_memory_with_format(port, action, OutputFormat::Plain)
+    handle_memory_with_format(port, action, OutputFormat::Plain).map(|_| ())
 }
 
-pub fn handle_memory_with_format(port: u16, action: Option<MemoryAction>, format: OutputFormat) -> Result<()> {
+/// Run a memory action, returning the session transcript when one was shown
+pub fn handle_memory_with_format(port: u16, action: Option<MemoryAction>, format: OutputFormat) -> Result<Option<String>> {
     let mut client = DaemonClient::new(port);
     
     match action {
@@ -34,7 +68,7 @@ pub fn handle_memory_with_format(port: u16, action: Option<MemoryAction>, format
         }
         
         Some(MemoryAction::Search { query, limit: _ }) => {
-            if format != OutputFormat::Json {
+            if !format.is_structured() {
                 println!("{}", help_text::format_searching(&query).blue().bold());
             }
             println!("{}", help_text::ERR_EVOLVE_NOT_READY.yellow());
@@ -58,33 +92,59 @@ pub fn handle_memory_with_format(port: u16, action: Option<MemoryAction>, format
                 if let Some(error) = response.error {
                     println!("  {}", error.dimmed());
                 }
-                return Ok(());
+                return Ok(None);
             }
             
             let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
             let memory_detail = MemoryDetailResponse::parse_response(&data)?;
             
             memory_detail.display(format)?;
+            return Ok(Some(memory_detail.transcript()));
+        }
+        
+        Some(MemoryAction::Fork { session_id, at }) => {
+            let request = MemoryForkRequest { session_id: session_id.clone(), at }.build_request(generate_id())?;
+            let response = client.request(request)?;
+            if !response.success {
+                let error = response.error.unwrap_or_else(|| format!("Failed to fork {}", session_id));
+                return Err(Port42Error::from_daemon(&error).into());
+            }
+            let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+            MemoryForkResponse::parse_response(&data)?.display(format)?;
+        }
+        
+        Some(MemoryAction::Merge { session_ids, name, interleave }) => {
+            let request = MemoryMergeRequest { session_ids, name, interleave }.build_request(generate_id())?;
+            let response = client.request(request)?;
+            if !response.success {
+                let error = response.error.unwrap_or_else(|| "Failed to merge sessions".to_string());
+                return Err(Port42Error::from_daemon(&error).into());
+            }
+            let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+            MemoryMergeResponse::parse_response(&data)?.display(format)?;
         }
         
         Some(MemoryAction::Rename { session_id, new_name }) => {
-            // Rename memory/session
-            if format != OutputFormat::Json {
-                println!("{}", format!("Renaming memory {} to '{}'...", session_id.bright_cyan(), new_name.bright_white()).blue());
+            rename_session(&mut client, &session_id, &new_name)?;
+            if !format.is_structured() {
+                println!("{}", format!("✏️  Renamed {} to '{}'", session_id.bright_cyan(), new_name.bright_white()).green());
             }
-            
-            // For now, show that this feature is coming soon
-            println!("{}", "Memory renaming feature is crystallizing...".yellow());
-            println!("{}", "This sacred power will soon be manifested.".dimmed());
-            
-            // TODO: Implement actual renaming via daemon protocol
-            // This would require:
-            // 1. Adding a MemoryRenameRequest to protocol
-            // 2. Implementing rename endpoint in daemon
-            // 3. Updating storage layer to support metadata changes
         }
     }
     
-    Ok(())
+    Ok(None)
 }
 
+
+fn rename_session(client: &mut DaemonClient, session_id: &str, name: &str) -> Result<()> {
+    let request = MemoryRenameRequest {
+        session_id: session_id.to_string(),
+        name: name.to_string(),
+    }.build_request(generate_id())?;
+    let response = client.request(request)?;
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| format!("Failed to rename {}", session_id));
+        return Err(Port42Error::from_daemon(&error).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anyhow::{Context, Result};
+use crate::client::DaemonClient;
+use crate::common::{generate_id, errors::Port42Error};
+use crate::display::{Displayable, OutputFormat};
+use crate::help_text::*;
+use crate::protocol::{RequestBuilder, ResponseParser};
+use crate::protocol::metrics::{MetricsRequest, MetricsSnapshot};
+
+/// Show the daemon's counters and histograms; `prometheus` prints the text
+/// exposition format whatever the output format
+pub fn handle_metrics(port: u16, prometheus: bool, format: OutputFormat) -> Result<()> {
+    let mut client = DaemonClient::new(port);
+    let response = client.request(MetricsRequest.build_request(generate_id())?)
+        .context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| "Failed to read metrics".to_string())).into());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    let snapshot = MetricsSnapshot::parse_response(&data)?;
+
+    if prometheus {
+        print!("{}", snapshot.to_prometheus());
+        return Ok(());
+    }
+    snapshot.display(format)
+}
@@ -1,4 +1,5 @@
 pub mod daemon;
+pub mod keys;
 pub mod evolve;
 pub mod reality;
 pub mod memory;
@@ -10,4 +11,44 @@ pub mod cat;
 pub mod info;
 pub mod search;
 pub mod declare;
-pub mod watch;
\ No newline at end of file
+pub mod watch;
+pub mod usage;
+pub mod models;
+pub mod cache;
+pub mod prompts;
+pub mod agents;
+pub mod index;
+pub mod hook;
+pub mod git;
+pub mod completions;
+pub mod doctor;
+pub mod init;
+pub mod export;
+pub mod notify;
+pub mod digest;
+pub mod providers;
+pub mod cp;
+pub mod mv;
+pub mod import;
+pub mod rm;
+pub mod restore;
+pub mod config;
+pub mod tree;
+pub mod run;
+pub mod jobs;
+pub mod note;
+pub mod rules;
+pub mod alias;
+pub mod diff;
+pub mod history;
+pub mod rollback;
+pub mod metrics;
+pub mod manpages;
+pub mod refs;
+pub mod artifacts;
+pub mod materialize;
+pub mod update;
+pub mod events;
+pub mod replay;
+pub mod upgrade;
+pub mod outbox;
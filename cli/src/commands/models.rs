@@ -0,0 +1,62 @@
+use anyhow::{Result, anyhow};
+
+use crate::client::DaemonClient;
+use crate::protocol::{RequestBuilder, ResponseParser};
+use crate::protocol::models::{ModelsReport, ModelsRequest, ModelsResponse};
+use crate::display::{Displayable, OutputFormat};
+use crate::common::{generate_id, errors::{Port42Error, exit_code}, providers};
+use crate::config::Config;
+
+pub fn handle_models_with_format(port: u16, provider: Option<String>, validate: bool, format: OutputFormat) -> Result<()> {
+    if let Some(ref p) = provider {
+        providers::validate_provider(p)?;
+    }
+
+    let mut client = DaemonClient::new(port);
+    let request = ModelsRequest { provider: provider.clone() }.build_request(generate_id())?;
+    let response = client.request(request)?;
+
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(Port42Error::Daemon(error).into());
+    }
+
+    let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+    let models = ModelsResponse::parse_response(&data)?;
+
+    let mut configured = configured_models(&Config::load_or_default());
+    if let Some(ref p) = provider {
+        configured.retain(|(_, configured_provider, _)| configured_provider == p);
+    }
+
+    let report = ModelsReport::new(models.providers, configured);
+    report.display(format)?;
+
+    if validate && report.has_invalid() {
+        // The report above already names the offending models
+        std::process::exit(exit_code::FAILURE);
+    }
+    Ok(())
+}
+
+/// Every (scope, provider, model) named in config
+fn configured_models(config: &Config) -> Vec<(String, String, String)> {
+    let default_provider = config.provider.clone()
+        .unwrap_or_else(|| providers::DEFAULT_PROVIDER.to_string());
+    let mut configured = Vec::new();
+
+    if let Some(ref model) = config.model {
+        configured.push(("default".to_string(), default_provider.clone(), model.clone()));
+    }
+    if let Some(model) = config.local.as_ref().and_then(|l| l.model.clone()) {
+        configured.push(("local".to_string(), providers::LOCAL_PROVIDER.to_string(), model));
+    }
+    for (agent, defaults) in &config.agents {
+        if let Some(ref model) = defaults.model {
+            let provider = defaults.provider.clone().unwrap_or_else(|| default_provider.clone());
+            configured.push((agent.clone(), provider, model.clone()));
+        }
+    }
+
+    configured
+}
@@ -0,0 +1,32 @@
+use anyhow::{Result, Context, bail};
+use crate::client::DaemonClient;
+use crate::help_text::*;
+use crate::protocol::{MovePathRequest, TransferResponse, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat};
+use super::cp::{complete, resolve_destination};
+
+pub fn handle_mv(client: &mut DaemonClient, source: String, destination: String, force: bool) -> Result<()> {
+    handle_mv_with_format(client, source, destination, force, OutputFormat::Plain)
+}
+
+pub fn handle_mv_with_format(client: &mut DaemonClient, source: String, destination: String, force: bool, format: OutputFormat) -> Result<()> {
+    let destination = resolve_destination(&source, &destination)?;
+    let request = MovePathRequest { source: source.clone(), destination: destination.clone(), overwrite: force };
+    let daemon_request = request.build_request(format!("mv-{}", chrono::Utc::now().timestamp()))?;
+
+    let response = client.request(daemon_request)
+        .context(ERR_CONNECTION_LOST)?;
+
+    if !response.success {
+        bail!(format_error_with_suggestion(
+            ERR_TRANSFER_FAILED,
+            &response.error.unwrap_or_else(|| format!("Cannot relocate '{}' to '{}'", source, destination))
+        ));
+    }
+
+    let data = response.data.unwrap_or_default();
+    let transfer = complete(TransferResponse::parse_response(&data)?, "move", source, destination);
+    transfer.display(format)?;
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anyhow::{Result, Context};
+use colored::*;
+use crate::client::DaemonClient;
+use crate::common::{generate_id, errors::Port42Error};
+use crate::help_text::*;
+use crate::protocol::{AddNoteRequest, ListNotesRequest, NoteList, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat};
+
+/// Attach a note to `path`, or show its notes when `text` is None
+pub fn handle_note(client: &mut DaemonClient, path: String, text: Option<String>, format: OutputFormat) -> Result<()> {
+    if text.as_deref().is_some_and(|t| t.trim().is_empty()) {
+        return Err(Port42Error::Usage("A note needs some text: port42 note <path> \"text\"".to_string()).into());
+    }
+    let request = match text {
+        Some(ref text) => AddNoteRequest { path: path.clone(), text: text.clone() }.build_request(generate_id())?,
+        None => ListNotesRequest { path: path.clone() }.build_request(generate_id())?,
+    };
+    let response = client.request(request)
+        .context(ERR_CONNECTION_LOST)?;
+
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(match Port42Error::from_daemon(&error) {
+            Port42Error::NotFound(_) => Port42Error::NotFound(format_error_with_suggestion(
+                ERR_PATH_NOT_FOUND,
+                &format!("Nothing at '{}' to annotate", path)
+            )),
+            other => other,
+        }.into());
+    }
+
+    if text.is_some() && !format.is_structured() {
+        println!("{}", format!("📝 Noted on {}", path).green());
+        return Ok(());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    let mut notes = NoteList::parse_response(&data)?;
+    if notes.path.is_empty() {
+        notes.path = path;
+    }
+    notes.display(format)
+}
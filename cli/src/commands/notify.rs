@@ -0,0 +1,116 @@
+use anyhow::{Result, bail};
+use colored::*;
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::NotifyAction;
+use crate::common::errors::{exit_code, Port42Error};
+use crate::common::{events, platform};
+use crate::common::notify::{self, Notifier, NotifyEvent};
+use crate::config::{self, Config, NotifyHook};
+use crate::protocol::events::{DaemonEvent, EventKind};
+
+pub fn handle_notify(port: u16, action: NotifyAction) -> Result<()> {
+    match action {
+        NotifyAction::Test { sink } => test_sinks(sink),
+        NotifyAction::Add { on, exec } => {
+            let hook = NotifyHook { on: on.label().to_string(), exec };
+            config::add_notify_hook(&hook)?;
+            println!("{} {}", "🔔".green(), format!("On {} run: {}", hook.on, hook.exec).green());
+            println!("{}", "Hooks run while 'port42 notify watch' is running".dimmed());
+            Ok(())
+        }
+        NotifyAction::List => {
+            let hooks = configured_hooks();
+            if hooks.is_empty() {
+                println!("{}", "No hooks. Add one with: port42 notify add --on tool_created --exec '...'".dimmed());
+            }
+            for (i, hook) in hooks.iter().enumerate() {
+                println!("{:>3}. {} {}", i + 1, hook.on.yellow(), hook.exec);
+            }
+            Ok(())
+        }
+        NotifyAction::Remove { number } => match config::remove_notify_hook(number as usize - 1)? {
+            Some(hook) => {
+                println!("{}", format!("🗑️  Removed hook on {}: {}", hook.on, hook.exec).green());
+                Ok(())
+            }
+            None => Err(Port42Error::NotFound(format!("No hook {} (see 'port42 notify list')", number)).into()),
+        },
+        NotifyAction::Watch { refresh } => watch(port, refresh),
+    }
+}
+
+fn configured_hooks() -> Vec<NotifyHook> {
+    Config::load_or_default().notify.map(|n| n.hooks).unwrap_or_default()
+}
+
+/// Run hooks as daemon events arrive; only the kinds hooks are set for are asked for
+fn watch(port: u16, refresh: u64) -> Result<()> {
+    let hooks = configured_hooks();
+    if hooks.is_empty() {
+        bail!("No hooks configured. Add one with: port42 notify add --on tool_created --exec '...'");
+    }
+    for hook in hooks.iter().filter(|h| EventKind::from_label(&h.on).is_none()) {
+        eprintln!("⚠️  Hook on unknown event '{}' will never run", hook.on);
+    }
+    let mut kinds = Vec::new();
+    for kind in hooks.iter().filter_map(|h| EventKind::from_label(&h.on)) {
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    if kinds.is_empty() {
+        bail!("None of the hooks is on a known event: {}", EventKind::ALL.iter().map(|k| k.label()).collect::<Vec<_>>().join(", "));
+    }
+    println!("{}", format!("👂 Watching daemon events for {} hook(s). Press Ctrl+C to stop.", hooks.len()).cyan());
+
+    events::follow(port, &kinds, Duration::from_millis(refresh), &mut |event| {
+        dispatch(&hooks, event);
+        true
+    })
+}
+
+fn dispatch(hooks: &[NotifyHook], event: &DaemonEvent) {
+    for hook in hooks.iter().filter(|h| h.on == event.kind.label()) {
+        let time = event.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S");
+        println!("{} [{}] {} {} → {}", "⚡".yellow(), time, event.kind.label(), event.subject, hook.exec.dimmed());
+        if let Err(e) = notify::run_hook(hook, event) {
+            eprintln!("{}", format!("❌ Hook '{}' failed: {:#}", hook.exec, e).red());
+        }
+    }
+}
+
+fn test_sinks(only: Option<String>) -> Result<()> {
+    let notifier = Notifier::from_config(&Config::load_or_default());
+    if notifier.is_empty() {
+        bail!("No notification sinks configured. Add one under [[notify.sinks]] in ~/.port42/config.toml");
+    }
+    if let Some(ref name) = only {
+        if !notifier.sinks().iter().any(|s| &s.name == name) {
+            bail!("No sink named '{}'", name);
+        }
+    }
+    for sink in notifier.sinks() {
+        notify::validate_sink(sink)?;
+    }
+
+    let vars = HashMap::from([("host".to_string(), hostname())]);
+    let mut failed = 0;
+    for (sink, result) in notifier.send(NotifyEvent::Test, &vars, only.as_deref()) {
+        match result {
+            Ok(()) => println!("{} {}", "✅".green(), format!("Delivered to '{}'", sink).green()),
+            Err(e) => {
+                failed += 1;
+                println!("{} {}", "❌".red(), format!("'{}' failed: {:#}", sink, e).red());
+            }
+        }
+    }
+    if failed > 0 {
+        std::process::exit(exit_code::FAILURE);
+    }
+    Ok(())
+}
+
+fn hostname() -> String {
+    platform::hostname().unwrap_or_else(|| "this machine".to_string())
+}
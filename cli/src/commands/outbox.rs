@@ -0,0 +1,252 @@
+use anyhow::{bail, Result};
+use colored::*;
+use serde_json::Value;
+use tracing::debug;
+use crate::OutboxAction;
+use crate::client::DaemonClient;
+use crate::common::errors::Port42Error;
+use crate::common::outbox::{Outbox, QueuedRequest};
+use crate::help_text::*;
+use crate::protocol::DaemonRequest;
+use crate::protocol::hello::FEATURE_DETACH;
+use crate::protocol::jobs;
+
+pub fn handle_outbox(port: u16, action: OutboxAction) -> Result<()> {
+    let outbox = Outbox::open();
+    match action {
+        OutboxAction::List => list(&outbox),
+        OutboxAction::Flush => flush(port, &outbox),
+        OutboxAction::Retry { name } => {
+            outbox.retry(&name)?;
+            println!("{}", format!("📮 {} is back in the outbox", name).green());
+            println!("{}", "💡 Send it with: port42 outbox flush".dimmed());
+            Ok(())
+        }
+    }
+}
+
+/// Whether the daemon on `port` would take a request right now
+pub fn daemon_reachable(port: u16) -> bool {
+    DaemonClient::new(port).ensure_connected().is_ok()
+}
+
+/// Save `request` for when the daemon is back
+pub fn enqueue(summary: &str, request: DaemonRequest) -> Result<()> {
+    let item = Outbox::open().push(summary, request)?;
+    println!("{}", MSG_QUEUED.yellow());
+    println!("   {} {}", item.name().dimmed(), summary);
+    println!("{}", "💡 Send it once the daemon is back with: port42 outbox flush".dimmed());
+    Ok(())
+}
+
+fn list(outbox: &Outbox) -> Result<()> {
+    let pending = outbox.pending()?;
+    let sending = outbox.sending()?;
+    let failed = outbox.failed()?;
+    let unknown = outbox.unknown()?;
+    if pending.is_empty() && sending.is_empty() && failed.is_empty() && unknown.is_empty() {
+        println!("{}", MSG_OUTBOX_EMPTY.dimmed());
+        return Ok(());
+    }
+    for item in &pending {
+        println!("{} {}  {}", "⏳".yellow(), item.name().dimmed(), item.summary);
+    }
+    for item in &sending {
+        println!("{} {}  {} {}", "📤".cyan(), item.name().dimmed(), item.summary, "(being delivered)".dimmed());
+    }
+    for item in &failed {
+        println!("{} {}  {}", "✗".red(), item.name().dimmed(), item.summary);
+        if let Some(ref error) = item.error {
+            println!("     {}", error.red());
+        }
+    }
+    for item in &unknown {
+        println!("{} {}  {} {}", "?".yellow(), item.name().dimmed(), item.summary, "(sent, but no answer came back)".dimmed());
+        if let Some(ref error) = item.error {
+            println!("     {}", error.dimmed());
+        }
+    }
+    if !failed.is_empty() {
+        println!("{}", "💡 Turned-down requests stay aside until: port42 outbox retry <name>".dimmed());
+    }
+    if !unknown.is_empty() {
+        println!("{}", "💡 The daemon may have carried these out; check before sending one again with: port42 outbox retry <name>".dimmed());
+    }
+    if !sending.is_empty() {
+        println!("{}", "💡 If no port42 is still delivering one, put it back with: port42 outbox retry <name>".dimmed());
+    }
+    Ok(())
+}
+
+/// Send every waiting request and wait for each answer
+fn flush(port: u16, outbox: &Outbox) -> Result<()> {
+    let pending = outbox.pending()?;
+    if pending.is_empty() {
+        println!("{}", MSG_OUTBOX_EMPTY.dimmed());
+        return Ok(());
+    }
+    let mut client = DaemonClient::new(port);
+    client.ensure_connected()?;
+
+    let total = pending.len();
+    println!("{}", format!("📮 Delivering {} queued request{}", total, if total == 1 { "" } else { "s" }).bright_blue().bold());
+    let mut turned_down = 0;
+    let mut items = pending.into_iter();
+    while let Some(item) = items.next() {
+        match deliver(&mut client, outbox, item, false) {
+            Delivery::Done(summary, detail) => println!("  {} {} {}", "✓".green(), summary, detail.dimmed()),
+            Delivery::Elsewhere(summary) => println!("  {} {} {}", "↷".dimmed(), summary, "(another port42 is delivering it)".dimmed()),
+            Delivery::TurnedDown(summary, error) => {
+                turned_down += 1;
+                println!("  {} {}: {}", "✗".red(), summary, error.red());
+            }
+            Delivery::Unknown(summary, error) => {
+                println!("  {} {}: {}", "?".yellow(), summary, error);
+                let waiting = items.len();
+                bail!(Port42Error::Connection(format_error_with_suggestion(
+                    &format!("No answer came for '{}'; {} queued request{} still waiting", summary, waiting, if waiting == 1 { "" } else { "s" }),
+                    "It may have gone through; see 'port42 outbox list' before retrying it",
+                )));
+            }
+            Delivery::NotSent(summary, error) => {
+                println!("  {} {}: {}", "⏸".yellow(), summary, error);
+                let waiting = items.len() + 1;
+                bail!(Port42Error::Connection(format!(
+                    "The daemon went away; {} queued request{} still waiting", waiting, if waiting == 1 { "" } else { "s" }
+                )));
+            }
+        }
+    }
+    if turned_down > 0 {
+        bail!(format_error_with_suggestion(
+            &format!("The daemon turned down {} of {} queued requests", turned_down, total),
+            "See why with: port42 outbox list"
+        ));
+    }
+    Ok(())
+}
+
+/// Hand waiting requests to the daemon as jobs, if it's there to take them
+/// and says it can run them detached. Each answer is only a job ID, so the
+/// command this rides on isn't held up by generation; from the first request
+/// the daemon can't detach on, or with a daemon that can't detach at all,
+/// they are left for `port42 outbox flush` with a mention that they wait. Reports go to
+/// stderr, leaving the command's own output alone.
+pub fn hand_off_as_jobs(port: u16) {
+    let outbox = Outbox::open();
+    let mut pending = match outbox.pending() {
+        Ok(pending) if !pending.is_empty() => pending,
+        Ok(_) => return,
+        Err(e) => {
+            debug!("Couldn't read the outbox: {:#}", e);
+            return;
+        }
+    };
+    let mut client = DaemonClient::new(port);
+    if client.ensure_connected().is_err() {
+        return;
+    }
+    let plural = |n: usize| format!("{} queued request{}", n, if n == 1 { "" } else { "s" });
+    // Stop at the first one that can't be detached, so nothing jumps the queue
+    let detachable = if client.supports(FEATURE_DETACH).unwrap_or(false) {
+        pending.iter().take_while(|item| jobs::detachable(&item.request)).count()
+    } else {
+        0
+    };
+    let held = pending.split_off(detachable);
+    if !held.is_empty() {
+        eprintln!("{}", format!("📮 {} waiting; send them with: port42 outbox flush", plural(held.len())).dimmed());
+    }
+    if pending.is_empty() {
+        return;
+    }
+    let count = plural(pending.len());
+
+    eprintln!("{}", format!("📮 Handing {} to the daemon", count).bright_blue());
+    for item in pending {
+        match deliver(&mut client, &outbox, item, true) {
+            Delivery::Done(summary, detail) => eprintln!("  {} {} {}", "✓".green(), summary, detail.dimmed()),
+            Delivery::Elsewhere(summary) => debug!("Another port42 is delivering {}", summary),
+            Delivery::TurnedDown(summary, error) => eprintln!("  {} {}: {}", "✗".red(), summary, error.red()),
+            Delivery::Unknown(summary, error) => {
+                eprintln!("  {} {}: {} {}", "?".yellow(), summary, error, "(see: port42 outbox list)".dimmed());
+                return;
+            }
+            Delivery::NotSent(summary, error) => {
+                debug!("Stopped delivering the outbox at {}: {}", summary, error);
+                return;
+            }
+        }
+    }
+    eprintln!("{}", "💡 Follow them with: port42 jobs list".dimmed());
+}
+
+enum Delivery {
+    /// Delivered and answered, with what came of it
+    Done(String, String),
+    /// The daemon answered with an error; set aside
+    TurnedDown(String, String),
+    /// Never reached the daemon; left where it was
+    NotSent(String, String),
+    /// Sent, but no answer came back; set aside rather than risk sending it twice
+    Unknown(String, String),
+    /// Claimed by another port42 first
+    Elsewhere(String),
+}
+
+fn deliver(client: &mut DaemonClient, outbox: &Outbox, item: QueuedRequest, detach: bool) -> Delivery {
+    let summary = item.summary.clone();
+    // Only one port42 sends each request, however many start at once
+    let item = match outbox.claim(item) {
+        Ok(Some(item)) => item,
+        Ok(None) => return Delivery::Elsewhere(summary),
+        Err(e) => return Delivery::NotSent(summary, format!("{:#}", e)),
+    };
+    let mut request = item.request.clone();
+    if detach {
+        jobs::detach(&mut request);
+    }
+    let response = match client.request(request) {
+        Ok(response) => response,
+        Err(e) if client.request_may_have_landed() => {
+            let error = format!("{:#}", e);
+            if let Err(e) = outbox.mark_unknown(item, &error) {
+                debug!("Couldn't set an unanswered request aside: {:#}", e);
+            }
+            return Delivery::Unknown(summary, error);
+        }
+        Err(e) => {
+            if let Err(e) = outbox.release(&item) {
+                debug!("Couldn't put an undelivered request back: {:#}", e);
+            }
+            return Delivery::NotSent(summary, format!("{:#}", e));
+        }
+    };
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        if let Err(e) = outbox.fail(item, &error) {
+            debug!("Couldn't set a turned-down request aside: {:#}", e);
+        }
+        return Delivery::TurnedDown(summary, error);
+    }
+    let mut detail = describe(response.data.as_ref());
+    if let Err(e) = outbox.remove(&item) {
+        // Better said now than found out when it is sent twice
+        detail = format!("{} (but it's still in the outbox: {:#})", detail, e);
+    }
+    Delivery::Done(summary, detail)
+}
+
+/// The part of an answer worth a glance: where a tool landed, a session or a job
+fn describe(data: Option<&Value>) -> String {
+    let field = |key: &str| data.and_then(|d| d.get(key)).and_then(Value::as_str);
+    if let Some(job) = field("job_id") {
+        format!("→ job {}", job)
+    } else if let Some(path) = field("physical_path") {
+        format!("→ {}", path)
+    } else if let Some(session) = field("session_id") {
+        format!("→ port42 memory {}", session)
+    } else {
+        String::new()
+    }
+}
@@ -0,0 +1,154 @@
+use anyhow::{Result, Context, anyhow, bail};
+use colored::*;
+use std::io::Read;
+use crate::PromptsAction;
+use crate::client::DaemonClient;
+use crate::common::{generate_id, errors::Port42Error, template};
+use crate::protocol::{CatRequest, CatResponse, LsRequest, LsResponse, RequestBuilder, ResponseParser};
+use crate::protocol::file_ops::{DeletePathRequest, StorePathRequest};
+
+/// Prompt templates live alongside other artifacts so search and sharing just work
+pub const PROMPTS_ROOT: &str = "/artifacts/prompts";
+
+pub fn prompt_path(name: &str) -> String {
+    format!("{}/{}.md", PROMPTS_ROOT, name)
+}
+
+pub fn handle_prompts(action: PromptsAction, port: u16) -> Result<()> {
+    let mut client = DaemonClient::new(port);
+    match action {
+        PromptsAction::Add { name, file, description } => add_prompt(&mut client, &name, file, description),
+        PromptsAction::List => list_prompts(&mut client),
+        PromptsAction::Show { name } => {
+            let content = load_prompt(&mut client, &name)?;
+            println!("{}", format!("📝 {}", name).bright_blue().bold());
+            let vars = template::placeholders(&content);
+            if !vars.is_empty() {
+                println!("{} {}", "Variables:".dimmed(), vars.join(", ").bright_cyan());
+            }
+            println!();
+            println!("{}", content);
+            Ok(())
+        }
+        PromptsAction::Remove { name } => {
+            validate_name(&name)?;
+            let request = DeletePathRequest { path: prompt_path(&name) }.build_request(generate_id())?;
+            let response = client.request(request)?;
+            if !response.success {
+                let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+                return Err(Port42Error::Daemon(error).into());
+            }
+            println!("{}", format!("🗑️  Removed prompt '{}'", name).green());
+            Ok(())
+        }
+    }
+}
+
+/// Fetch a stored prompt template's raw text
+pub fn load_prompt(client: &mut DaemonClient, name: &str) -> Result<String> {
+    validate_name(name)?;
+    let request = CatRequest { path: prompt_path(name), version: None }.build_request(generate_id())?;
+    let response = client.request(request)?;
+    if !response.success {
+        return Err(Port42Error::NotFound(format!("Prompt template '{}' not found. See 'port42 prompts list'", name)).into());
+    }
+    let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+    Ok(CatResponse::parse_response(&data)?.content)
+}
+
+/// Load a prompt template and fill in its `--var` values
+pub fn render_prompt(client: &mut DaemonClient, name: &str, vars: &[String]) -> Result<String> {
+    let content = load_prompt(client, name)?;
+    template::render(&content, &template::parse_vars(vars)?)
+}
+
+fn add_prompt(client: &mut DaemonClient, name: &str, file: Option<String>, description: Option<String>) -> Result<()> {
+    validate_name(name)?;
+
+    let content = if let Some(path) = file {
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path))?
+    } else if !atty::is(atty::Stream::Stdin) {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        buf
+    } else {
+        edit_in_editor(name)?
+    };
+
+    if content.trim().is_empty() {
+        bail!("Prompt '{}' is empty, nothing saved", name);
+    }
+
+    let vars = template::placeholders(&content);
+    let metadata = serde_json::json!({
+        "type": "prompt",
+        "title": name,
+        "description": description.unwrap_or_else(|| format!("Prompt template {}", name)),
+        "tags": ["prompt", "template"],
+    });
+    let request = StorePathRequest { path: prompt_path(name), content, metadata: Some(metadata) }
+        .build_request(generate_id())?;
+    let response = client.request(request)?;
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(Port42Error::Daemon(error).into());
+    }
+
+    println!("{}", format!("📝 Saved prompt '{}' to {}", name, prompt_path(name)).green());
+    if !vars.is_empty() {
+        println!("{} {}", "Variables:".dimmed(), vars.join(", ").bright_cyan());
+    }
+    Ok(())
+}
+
+fn list_prompts(client: &mut DaemonClient) -> Result<()> {
+    let request = LsRequest { path: PROMPTS_ROOT.to_string() }.build_request(generate_id())?;
+    let response = client.request(request)?;
+    let entries = if response.success {
+        let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+        LsResponse::parse_response(&data)?.entries
+    } else {
+        Vec::new()
+    };
+
+    if entries.is_empty() {
+        println!("{}", "No prompt templates yet. Add one with 'port42 prompts add <name>'".dimmed());
+        return Ok(());
+    }
+
+    println!("{}", "📝 Prompt templates".bright_blue().bold());
+    for entry in entries {
+        let name = entry.name.trim_end_matches(".md");
+        println!("  {}", name.bright_white());
+    }
+    Ok(())
+}
+
+pub fn edit_in_editor(name: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let path = std::env::temp_dir().join(format!("port42-prompt-{}.md", name));
+    std::fs::write(&path, "")?;
+
+    let status = std::process::Command::new(&editor)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{}'", editor))?;
+    let content = std::fs::read_to_string(&path)?;
+    let _ = std::fs::remove_file(&path);
+
+    if !status.success() {
+        bail!("Editor exited with an error, prompt not saved");
+    }
+    Ok(content)
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        bail!("Invalid prompt name '{}'. Use letters, digits, '-' and '_'", name);
+    }
+    Ok(())
+}
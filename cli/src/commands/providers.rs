@@ -0,0 +1,42 @@
+use anyhow::{Result, anyhow};
+
+use crate::ProvidersAction;
+use crate::client::DaemonClient;
+use crate::protocol::{RequestBuilder, ResponseParser};
+use crate::protocol::models::{ProvidersRequest, ProvidersResponse};
+use crate::display::{Displayable, OutputFormat};
+use crate::common::{generate_id, errors::Port42Error, providers};
+use crate::config::Config;
+
+pub fn handle_providers(port: u16, action: ProvidersAction, format: OutputFormat) -> Result<()> {
+    match action {
+        ProvidersAction::List { table } => {
+            let format = if table && format == OutputFormat::Plain { OutputFormat::Table } else { format };
+            list_providers(port, format)
+        }
+    }
+}
+
+fn list_providers(port: u16, format: OutputFormat) -> Result<()> {
+    let mut client = DaemonClient::new(port);
+    let response = client.request(ProvidersRequest.build_request(generate_id())?)?;
+
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(Port42Error::Daemon(error).into());
+    }
+
+    let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+    let mut listing = ProvidersResponse::parse_response(&data)?;
+
+    // Mark what a request without --provider would use here
+    let default = std::env::var("PORT42_PROVIDER").ok()
+        .filter(|p| !p.is_empty())
+        .or_else(|| Config::load_or_default().provider)
+        .unwrap_or_else(|| providers::DEFAULT_PROVIDER.to_string());
+    for provider in &mut listing.providers {
+        provider.is_default = provider.name == default;
+    }
+
+    listing.display(format)
+}
@@ -1,8 +1,10 @@
 use anyhow::{Context, Result};
 use colored::*;
 use std::fs;
-use std::path::PathBuf;
-use crate::protocol::{RealityData, CommandInfo};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+use crate::common::archive::sha256_hex;
+use crate::protocol::{RealityData, RealitySnapshot, SnapshotEntry, CommandInfo};
 use crate::display::{Displayable, OutputFormat};
 use crate::help_text;
 
@@ -11,31 +13,107 @@ pub fn handle_reality(port: u16, verbose: bool, agent: Option<String>) -> Result
 }
 
 pub fn handle_reality_with_format(_port: u16, verbose: bool, agent: Option<String>, format: OutputFormat) -> Result<()> {
-    if format != OutputFormat::Json {
+    if !format.is_structured() {
         println!("{}", help_text::MSG_COMMANDS_HEADER.blue().bold());
         println!();
     }
     
-    let commands_dir = dirs::home_dir()
-        .context("Could not find home directory")?  
-        .join(".port42")
-        .join("commands");
+    let commands_dir = commands_dir()?;
+    let mut command_infos = scan_commands(&commands_dir)?;
+    record_snapshot(&command_infos);
+    
+    // Filter by agent if specified
+    if let Some(ref agent_filter) = agent {
+        command_infos.retain(|c| c.agent.as_deref() == Some(agent_filter.as_str()));
+    }
+    
+    // Create structured data for display
+    let reality_data = RealityData {
+        total: command_infos.len(),
+        commands: command_infos,
+        commands_dir,
+    };
+    
+    // Display using the framework
+    let display_format = if format.is_structured() {
+        format
+    } else if verbose {
+        OutputFormat::Table
+    } else {
+        OutputFormat::Plain
+    };
+    
+    reality_data.display(display_format)?;
+    
+    Ok(())
+}
+
+/// Show which commands appeared, vanished or changed since the previous run
+pub fn handle_reality_diff(agent: Option<String>, format: OutputFormat) -> Result<()> {
+    if !format.is_structured() {
+        println!("{}", help_text::MSG_REALITY_DIFF_HEADER.blue().bold());
+    }
     
+    let commands = scan_commands(&commands_dir()?)?;
+    let previous = load_snapshot();
+    let current = record_snapshot(&commands);
+    
+    let mut diff = current.diff_from(previous.as_ref());
+    if let Some(ref agent_filter) = agent {
+        diff.retain_agent(agent_filter);
+    }
+    diff.display(format)
+}
+
+fn commands_dir() -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .context("Could not find home directory")?
+        .join(".port42")
+        .join("commands"))
+}
+
+fn snapshot_path() -> PathBuf {
+    crate::config::port42_dir().join("state").join("reality.json")
+}
+
+fn load_snapshot() -> Option<RealitySnapshot> {
+    let content = fs::read_to_string(snapshot_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Remember the current command list for the next `--diff`. Best effort:
+/// failing to save shouldn't stop reality from being shown.
+fn record_snapshot(commands: &[CommandInfo]) -> RealitySnapshot {
+    let snapshot = RealitySnapshot {
+        taken: chrono::Utc::now().to_rfc3339(),
+        tools: commands.iter()
+            .map(|c| (c.name.clone(), SnapshotEntry {
+                sha256: fs::read(&c.path).map(|bytes| sha256_hex(&bytes)).unwrap_or_default(),
+                language: c.language.clone(),
+                description: c.description.clone(),
+                agent: c.agent.clone(),
+            }))
+            .collect(),
+    };
+    let path = snapshot_path();
+    let saved = path.parent().map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, serde_json::to_string_pretty(&snapshot).unwrap_or_default()));
+    if let Err(e) = saved {
+        debug!("could not save reality snapshot: {}", e);
+    }
+    snapshot
+}
+
+/// Every executable in the commands directory, sorted by name
+fn scan_commands(commands_dir: &Path) -> Result<Vec<CommandInfo>> {
     if !commands_dir.exists() {
-        // No commands directory - display empty state
-        let reality_data = RealityData {
-            commands: vec![],
-            total: 0,
-            commands_dir,
-        };
-        
-        return reality_data.display(format);
+        return Ok(Vec::new());
     }
     
     let mut commands = Vec::new();
     
     // Read all files in commands directory
-    for entry in fs::read_dir(&commands_dir)? {
+    for entry in fs::read_dir(commands_dir)? {
         let entry = entry?;
         let path = entry.path();
         
@@ -66,46 +144,12 @@ pub fn handle_reality_with_format(_port: u16, verbose: bool, agent: Option<Strin
     commands.sort_by(|a, b| a.0.cmp(&b.0));
     
     // Convert to CommandInfo structures
-    let mut command_infos = Vec::new();
-    
-    for (name, path) in commands {
-        let (language, description, agent_name) = extract_metadata(&path)?;
-        
-        // Filter by agent if specified
-        if let Some(ref agent_filter) = agent {
-            if agent_name.as_deref() != Some(agent_filter) {
-                continue;
-            }
-        }
-        
-        command_infos.push(CommandInfo {
-            name,
-            path,
-            language,
-            description,
-            agent: agent_name,
-        });
-    }
-    
-    // Create structured data for display
-    let reality_data = RealityData {
-        total: command_infos.len(),
-        commands: command_infos,
-        commands_dir,
-    };
-    
-    // Display using the framework
-    let display_format = if format == OutputFormat::Json {
-        OutputFormat::Json
-    } else if verbose {
-        OutputFormat::Table
-    } else {
-        OutputFormat::Plain
-    };
-    
-    reality_data.display(display_format)?;
-    
-    Ok(())
+    commands.into_iter()
+        .map(|(name, path)| {
+            let (language, description, agent) = extract_metadata(&path)?;
+            Ok(CommandInfo { name, path, language, description, agent })
+        })
+        .collect()
 }
 
 fn extract_metadata(path: &PathBuf) -> Result<(String, Option<String>, Option<String>)> {
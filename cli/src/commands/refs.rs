@@ -0,0 +1,43 @@
+use anyhow::{Context, Result, bail};
+use crate::RefsAction;
+use crate::client::DaemonClient;
+use crate::common::{generate_id, errors::Port42Error, references::parse_references};
+use crate::display::{Displayable, OutputFormat};
+use crate::help_text::*;
+use crate::project::Project;
+use crate::protocol::{RequestBuilder, ResponseParser};
+use crate::protocol::refs::{ResolveReferencesRequest, ResolvedReferences};
+
+pub fn handle_refs(port: u16, action: RefsAction, format: OutputFormat) -> Result<()> {
+    match action {
+        RefsAction::Resolve { references, full, no_project } => resolve(port, references, full, no_project, format),
+    }
+}
+
+/// Resolve references through the same path declare takes: project
+/// references first, stdin and git read here, the rest by the daemon
+fn resolve(port: u16, references: Vec<String>, full: bool, no_project: bool, format: OutputFormat) -> Result<()> {
+    let mut ref_strings = if no_project {
+        Vec::new()
+    } else {
+        Project::discover().as_ref().map(Project::references).unwrap_or_default()
+    };
+    ref_strings.extend(references);
+    // The one-line summaries note what was cached or truncated on the way
+    let references = parse_references(ref_strings, format == OutputFormat::Plain).context("Invalid reference")?;
+
+    let mut client = DaemonClient::new(port);
+    let response = client.request(ResolveReferencesRequest { references }.build_request(generate_id())?)
+        .context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| "Failed to resolve references".to_string())).into());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    let resolved = ResolvedReferences { full, ..ResolvedReferences::parse_response(&data)? };
+    resolved.display(format)?;
+
+    if resolved.failed() > 0 {
+        bail!("{} of {} references could not be resolved", resolved.failed(), resolved.references.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use anyhow::{Context, Result};
+use colored::*;
+use serde_json::{json, Value};
+use std::path::Path;
+use crate::client::{DaemonClient, RequestKind};
+use crate::common::errors::Port42Error;
+use crate::common::recording::{Exchange, Recording};
+use crate::protocol::DaemonRequest;
+use crate::testing::{MockDaemon, Reply};
+
+pub fn handle_replay(port: u16, dir: &Path, serve: Option<u16>, types: &[String], all: bool, json: bool) -> Result<()> {
+    let recording = Recording::load(dir)?;
+    match serve {
+        Some(listen) => serve_recording(recording, listen),
+        None => resend(port, &recording, types, all, json),
+    }
+}
+
+/// Whether to send `kind` again: reads always; anything that changes
+/// things (declares, writes, deletes, AI generations) only when named with
+/// --type or when `all` says so
+fn should_resend(kind: &str, types: &[String], all: bool) -> bool {
+    if !types.is_empty() {
+        return types.iter().any(|t| t == kind);
+    }
+    all || RequestKind::is_read_only(kind)
+}
+
+enum Outcome {
+    Same,
+    Different(String),
+    Failed(String),
+}
+
+/// Send each recorded request again and say whether the answer changed
+fn resend(port: u16, recording: &Recording, types: &[String], all: bool, json: bool) -> Result<()> {
+    let mut client = DaemonClient::new(port);
+    let mut results = Vec::new();
+    let mut skipped: Vec<&str> = Vec::new();
+    for (name, exchange) in &recording.exchanges {
+        if !should_resend(exchange.request_type(), types, all) {
+            if types.is_empty() && !skipped.contains(&exchange.request_type()) {
+                skipped.push(exchange.request_type());
+            }
+            continue;
+        }
+        let request: DaemonRequest = serde_json::from_value(exchange.request.clone())
+            .with_context(|| format!("{} holds a request this CLI can't send", name))?;
+        let outcome = match client.request(request) {
+            Ok(response) => compare(exchange, &serde_json::to_value(&response)?),
+            Err(e) => Outcome::Failed(format!("{:#}", e)),
+        };
+        if !json {
+            print_outcome(name, exchange.request_type(), &outcome);
+        }
+        results.push((name, exchange.request_type(), outcome));
+    }
+
+    if json {
+        let results: Vec<Value> = results.iter().map(|(name, kind, outcome)| {
+            let (outcome, detail) = match outcome {
+                Outcome::Same => ("same", None),
+                Outcome::Different(detail) => ("different", Some(detail)),
+                Outcome::Failed(detail) => ("failed", Some(detail)),
+            };
+            json!({ "file": name, "type": kind, "outcome": outcome, "detail": detail })
+        }).collect();
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
+    }
+    if !skipped.is_empty() {
+        println!("{}", format!(
+            "Not resent, since they change things: {}. Name them with --type, or pass --yes to resend everything",
+            skipped.join(", ")
+        ).dimmed());
+    }
+    if results.is_empty() {
+        if types.is_empty() {
+            return Err(Port42Error::NotFound("No recorded requests that only read; nothing was resent".to_string()).into());
+        }
+        return Err(Port42Error::NotFound(format!("No recorded {} requests", types.join(" or "))).into());
+    }
+    let same = results.iter().filter(|(_, _, o)| matches!(o, Outcome::Same)).count();
+    println!("\n{} replayed: {} same, {} different", results.len(), same, results.len() - same);
+    Ok(())
+}
+
+/// Ids differ on every run, so only what the daemon said is compared
+fn compare(exchange: &Exchange, now: &Value) -> Outcome {
+    let then = &exchange.response;
+    let field = |value: &Value, key: &str| value.get(key).cloned().unwrap_or(Value::Null);
+    match (field(then, "success").as_bool(), field(now, "success").as_bool()) {
+        (Some(true), Some(false)) => Outcome::Different(format!("now fails: {}", field(now, "error").as_str().unwrap_or("no error given"))),
+        (Some(false), Some(true)) => Outcome::Different("now succeeds".to_string()),
+        _ if field(then, "data") != field(now, "data") => Outcome::Different("data differs".to_string()),
+        _ if field(then, "error") != field(now, "error") => Outcome::Different("error differs".to_string()),
+        _ => Outcome::Same,
+    }
+}
+
+fn print_outcome(name: &str, kind: &str, outcome: &Outcome) {
+    let (mark, detail) = match outcome {
+        Outcome::Same => ("✓".green(), "same".dimmed()),
+        Outcome::Different(detail) => ("≠".yellow(), detail.yellow()),
+        Outcome::Failed(detail) => ("✗".red(), detail.red()),
+    };
+    println!("  {} {:<24} {:<16} {}", mark, name, kind, detail);
+}
+
+/// Answer requests from the recording until interrupted
+fn serve_recording(mut recording: Recording, listen: u16) -> Result<()> {
+    let count = recording.exchanges.len();
+    let daemon = MockDaemon::serve(listen, Box::new(move |request| {
+        let kind = request.get("type").and_then(Value::as_str).unwrap_or("?");
+        match recording.answer(request) {
+            Some((source, response)) => {
+                println!("  {} {:<16} {}", "←".green(), kind, source.dimmed());
+                Reply::lines(vec![response.clone()])
+            }
+            None => {
+                println!("  {} {:<16} {}", "✗".red(), kind, "nothing recorded".red());
+                Reply::error(&format!("No recorded response for {}", kind))
+            }
+        }
+    })).map_err(|e| Port42Error::Usage(format!("Can't listen on port {}: {}", listen, e)))?;
+    println!("{}", format!(
+        "🎞️  Serving {} recorded exchanges on port {}. Point commands at it with --port {}; Ctrl+C stops.",
+        count, listen, listen
+    ).cyan());
+    daemon.wait();
+    Ok(())
+}
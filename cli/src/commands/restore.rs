@@ -0,0 +1,31 @@
+use anyhow::{Result, Context};
+use crate::common::errors::Port42Error;
+use crate::client::DaemonClient;
+use crate::help_text::*;
+use crate::protocol::{RestorePathRequest, TransferResponse, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat};
+use super::cp::complete;
+
+pub fn handle_restore(client: &mut DaemonClient, path: String, destination: Option<String>, format: OutputFormat) -> Result<()> {
+    let request = RestorePathRequest { path: path.clone(), destination: destination.clone() };
+    let daemon_request = request.build_request(format!("restore-{}", chrono::Utc::now().timestamp()))?;
+
+    let response = client.request(daemon_request)
+        .context(ERR_CONNECTION_LOST)?;
+
+    if !response.success {
+        return Err(Port42Error::from_lookup(
+            response.error.as_deref(),
+            ERR_PATH_NOT_FOUND,
+            &response.error.clone().unwrap_or_else(|| format!("Nothing in the trash answers to '{}'. Try: port42 ls /trash", path))
+        ).into());
+    }
+
+    let data = response.data.unwrap_or_default();
+    // Without a reply naming it, the object returns to the path it was trashed from
+    let fallback = destination.unwrap_or_else(|| path.strip_prefix(super::rm::TRASH_ROOT).unwrap_or(&path).to_string());
+    let restored = complete(TransferResponse::parse_response(&data)?, "restore", path, fallback);
+    restored.display(format)?;
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use anyhow::{Result, Context, bail};
+use crate::common::errors::Port42Error;
+use colored::*;
+use std::io::{self, Write};
+use crate::client::DaemonClient;
+use crate::help_text::*;
+use crate::protocol::{DeletePathRequest, TrashPathRequest, TransferResponse, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat, print_yaml};
+use crate::common::generate_id;
+use crate::common::utils::normalize_vfs_path;
+use super::cp::complete;
+
+/// Top-level directories that are never removed wholesale
+const PROTECTED_PATHS: &[&str] = &["/", "/commands", "/memory", "/artifacts", "/trash"];
+
+pub const TRASH_ROOT: &str = "/trash";
+
+pub fn handle_rm(client: &mut DaemonClient, paths: Vec<String>, force: bool, yes: bool, format: OutputFormat) -> Result<()> {
+    // Check the canonical form, so "//commands" or "/tools/../commands" can't slip past
+    let paths: Vec<String> = paths.iter().map(|p| normalize_vfs_path(p)).collect();
+    for path in &paths {
+        if PROTECTED_PATHS.contains(&path.as_str()) {
+            bail!(format_error_with_suggestion(ERR_RM_PROTECTED, &format!("'{}' holds a whole realm; remove what is inside instead", path)));
+        }
+    }
+
+    // Anything already in the trash can only go for good
+    let (permanent, soft): (Vec<String>, Vec<String>) = paths.into_iter()
+        .partition(|p| force || p.starts_with(&format!("{}/", TRASH_ROOT)));
+    if !permanent.is_empty() && !yes && !confirm_permanent(&permanent, format)? {
+        println!("{}", "Nothing was removed".dimmed());
+        return Ok(());
+    }
+
+    // Keep going past a failure, so the report says exactly what is gone and what isn't
+    let mut results = Vec::new();
+    let mut failures: Vec<(String, anyhow::Error)> = Vec::new();
+    let batch = soft.into_iter().map(|p| ("trash", p)).chain(permanent.into_iter().map(|p| ("delete", p)));
+    for (operation, path) in batch {
+        match remove(client, operation, path.clone()) {
+            Ok(result) => results.push(result),
+            Err(e) => failures.push((path, e)),
+        }
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+        OutputFormat::Yaml => print_yaml(&results)?,
+        _ => {
+            for result in &results {
+                result.display(format)?;
+            }
+            if results.iter().any(|r| r.operation == "trash") {
+                println!("{}", MSG_RM_RESTORE_HINT.dimmed());
+            }
+        }
+    }
+    if !format.is_structured() {
+        for (path, e) in &failures {
+            eprintln!("{} {}: {:#}", "✗".red(), path.bright_white(), e);
+        }
+    }
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let failed: Vec<String> = failures.iter().map(|(path, _)| path.clone()).collect();
+    let summary = format!("Removed {} of {} paths; failed: {}", results.len(), results.len() + failed.len(), failed.join(", "));
+    let (_, first) = failures.remove(0);
+    Err(first.context(summary))
+}
+
+fn remove(client: &mut DaemonClient, operation: &str, path: String) -> Result<TransferResponse> {
+    let id = generate_id();
+    let request = if operation == "trash" {
+        TrashPathRequest { path: path.clone() }.build_request(id)?
+    } else {
+        DeletePathRequest { path: path.clone() }.build_request(id)?
+    };
+    send(client, request, operation, path)
+}
+
+fn send(client: &mut DaemonClient, request: crate::protocol::DaemonRequest, operation: &str, path: String) -> Result<TransferResponse> {
+    let response = client.request(request)
+        .context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Err(Port42Error::from_lookup(
+            response.error.as_deref(),
+            ERR_PATH_NOT_FOUND,
+            &response.error.clone().unwrap_or_else(|| format!("Cannot remove '{}'", path))
+        ).into());
+    }
+    let data = response.data.unwrap_or_default();
+    // A permanent delete has nowhere to point to
+    let destination = if operation == "trash" { format!("{}{}", TRASH_ROOT, path) } else { String::new() };
+    Ok(complete(TransferResponse::parse_response(&data)?, operation, path, destination))
+}
+
+/// Ask before anything is destroyed for good; scripts must pass --yes
+fn confirm_permanent(paths: &[String], format: OutputFormat) -> Result<bool> {
+    if format.is_structured() || !atty::is(atty::Stream::Stdin) {
+        bail!(format_error_with_suggestion(ERR_RM_NEEDS_CONFIRMATION, "Pass --yes to remove permanently without a prompt"));
+    }
+    println!("{}", "These will be removed permanently and cannot be restored:".red().bold());
+    for path in paths {
+        println!("  {}", path.bright_white());
+    }
+    print!("Remove permanently? [y/N]: ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,100 @@
+use anyhow::{Context, Result};
+use colored::*;
+use std::io::{self, Write};
+use crate::client::DaemonClient;
+use crate::common::errors::Port42Error;
+use crate::common::generate_id;
+use crate::help_text::*;
+use crate::protocol::{ObjectVersion, RollbackRequest, RollbackResponse, RequestBuilder, ResponseParser, VersionHistory};
+use crate::display::{Displayable, OutputFormat, diff::{DEFAULT_CONTEXT, TextDiff}};
+use super::diff::fetch;
+use super::history::fetch_history;
+
+pub fn handle_rollback(client: &mut DaemonClient, path: String, version: Option<u32>, yes: bool, format: OutputFormat) -> Result<()> {
+    let interactive = !format.is_structured() && atty::is(atty::Stream::Stdin);
+    let history = fetch_history(client, &path)?;
+    let current = history.versions.iter().find(|v| v.current).map(|v| v.version);
+
+    let target = match version {
+        Some(n) => n,
+        None if interactive => match pick_version(&history, current)? {
+            Some(n) => n,
+            None => {
+                println!("{}", "Nothing was rolled back".dimmed());
+                return Ok(());
+            }
+        },
+        None => return Err(Port42Error::Usage("Pass --version N to choose what to roll back to; see 'port42 history'".to_string()).into()),
+    };
+    if !history.versions.iter().any(|v| v.version == target) {
+        return Err(Port42Error::NotFound(format!("'{}' has no version {}; see 'port42 history {}'", path, target, path)).into());
+    }
+    if current == Some(target) {
+        return Err(Port42Error::Usage(format!("Version {} of '{}' is already current", target, path)).into());
+    }
+
+    if !yes {
+        if !interactive {
+            return Err(Port42Error::Usage("Pass --yes to roll back without a prompt".to_string()).into());
+        }
+        // Show what the rollback undoes before asking
+        let now = fetch(client, &path, None)?;
+        let then = fetch(client, &path, Some(target))?;
+        TextDiff::new(&format!("{} (current)", path), &now, &format!("{} (v{})", path, target), &then, DEFAULT_CONTEXT)
+            .display(OutputFormat::Plain)?;
+        println!();
+        print!("Roll {} back to v{}? [y/N]: ", path, target);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("{}", "Nothing was rolled back".dimmed());
+            return Ok(());
+        }
+    }
+
+    let request = RollbackRequest { path: path.clone(), version: target };
+    let response = client.request(request.build_request(generate_id())?)
+        .context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| format!("Cannot roll back '{}'", path))).into());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    let mut result = RollbackResponse::parse_response(&data)?;
+    if result.path.is_empty() {
+        result.path = path;
+    }
+    result.display(format)
+}
+
+/// Ask which earlier version to restore; None when the user backs out
+fn pick_version(history: &VersionHistory, current: Option<u32>) -> Result<Option<u32>> {
+    let earlier: Vec<&ObjectVersion> = history.versions.iter().filter(|v| Some(v.version) != current).collect();
+    let Some(newest) = earlier.first() else {
+        return Err(Port42Error::NotFound(format!("'{}' has no earlier versions to roll back to", history.path)).into());
+    };
+
+    println!("{}", format!("🕰️  Versions of {}", history.path).bright_blue().bold());
+    for v in &history.versions {
+        let mut line = format!("  v{:<3} {}", v.version, v.created_local());
+        if let Some(ref agent) = v.agent {
+            line.push_str(&format!("  {}", agent));
+        }
+        if v.current {
+            println!("{}  {}", line.dimmed(), "← current".green());
+        } else {
+            println!("{}", line);
+        }
+    }
+    print!("Roll back to which version? [{}]: ", newest.version);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().trim_start_matches('v');
+    match input {
+        "" => Ok(Some(newest.version)),
+        "q" | "n" | "no" => Ok(None),
+        _ => input.parse().map(Some)
+            .map_err(|_| Port42Error::Usage(format!("'{}' is not a version number", input)).into()),
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::{Result, Context};
+use colored::*;
+use crate::RulesAction;
+use crate::client::DaemonClient;
+use crate::common::{generate_id, errors::Port42Error};
+use crate::display::{Displayable, OutputFormat};
+use crate::help_text::*;
+use crate::protocol::{DaemonRequest, RequestBuilder, ResponseParser};
+use crate::protocol::rules::{AddRuleRequest, DeleteRuleRequest, ListRulesRequest, Rule, RuleAction, RuleList, RuleTrigger, SetRuleEnabledRequest};
+
+pub fn handle_rules(port: u16, action: RulesAction, format: OutputFormat) -> Result<()> {
+    let mut client = DaemonClient::new(port);
+    match action {
+        RulesAction::List => {
+            let data = send(&mut client, ListRulesRequest.build_request(generate_id())?)?;
+            RuleList::parse_response(&data)?.display(format)
+        }
+        RulesAction::Add { name, when, matching, run, description } => {
+            let rule = Rule {
+                id: String::new(),
+                name,
+                description,
+                enabled: true,
+                trigger: RuleTrigger { event: when, pattern: matching },
+                action: RuleAction { command: run },
+                fire_count: 0,
+                last_fired: None,
+                created_at: None,
+            };
+            let data = send(&mut client, AddRuleRequest { rule }.build_request(generate_id())?)?;
+            let rule = Rule::parse_response(&data)?;
+            if format.is_structured() {
+                return RuleList { rules: vec![rule] }.display(format);
+            }
+            println!("{}", format!("⚡ Added rule {} [{}]", rule.name, rule.id).green());
+            println!("  {} {} {} {}", "when".dimmed(), rule.when(), "run".dimmed(), rule.action.command);
+            Ok(())
+        }
+        RulesAction::Enable { rule_id } => set_enabled(&mut client, rule_id, true),
+        RulesAction::Disable { rule_id } => set_enabled(&mut client, rule_id, false),
+        RulesAction::Delete { rule_id } => {
+            send(&mut client, DeleteRuleRequest { rule_id: rule_id.clone() }.build_request(generate_id())?)?;
+            println!("{}", format!("🗑️  Deleted rule {}", rule_id).green());
+            Ok(())
+        }
+    }
+}
+
+fn set_enabled(client: &mut DaemonClient, rule_id: String, enabled: bool) -> Result<()> {
+    let request = SetRuleEnabledRequest { rule_id: rule_id.clone(), enabled };
+    send(client, request.build_request(generate_id())?)?;
+    let state = if enabled { "Enabled".green() } else { "Disabled".yellow() };
+    println!("{} rule {}", state, rule_id);
+    Ok(())
+}
+
+/// Send a rules request, returning its data (or null when the daemon sends none)
+fn send(client: &mut DaemonClient, request: DaemonRequest) -> Result<serde_json::Value> {
+    let response = client.request(request).context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(Port42Error::from_daemon(&error).into());
+    }
+    Ok(response.data.unwrap_or_default())
+}
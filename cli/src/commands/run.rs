@@ -0,0 +1,114 @@
+use anyhow::{Result, Context};
+use colored::*;
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus};
+use std::time::Instant;
+use tracing::debug;
+use crate::client::DaemonClient;
+use crate::common::errors::Port42Error;
+use crate::common::generate_id;
+use crate::common::utils::{create_private_file, private_dir};
+use crate::commands::hook::RECORD_TIMEOUT;
+use crate::config;
+use crate::help_text::*;
+use crate::protocol::{CatRequest, CatResponse, RequestBuilder, ResponseParser};
+use crate::protocol::hooks::TrackCommandRequest;
+
+/// `name` as a VFS path: bare names live under /commands
+pub fn tool_path(name: &str) -> String {
+    if name.starts_with('/') {
+        name.to_string()
+    } else {
+        format!("/commands/{}", name)
+    }
+}
+
+/// Run a crystallized command and report how it went; returns its exit code
+pub fn handle_run(client: &mut DaemonClient, name: String, args: Vec<String>, verbose: bool) -> Result<i32> {
+    let path = tool_path(&name);
+    let content = fetch(client, &path)?;
+    let tool_name = path.rsplit('/').next().unwrap_or(&name).to_string();
+
+    // The local copy is used only while it matches what the daemon holds
+    let local = config::port42_dir().join("commands").join(&tool_name);
+    let scratch = match std::fs::read_to_string(&local) {
+        Ok(existing) if existing == content => None,
+        _ => Some(write_scratch(&tool_name, &content)?),
+    };
+    let program = scratch.as_ref().map(|(_, file)| file).unwrap_or(&local);
+
+    let cwd = std::env::current_dir().ok();
+    let started = Instant::now();
+    let status = Command::new(program)
+        .args(&args)
+        .status()
+        .with_context(|| format!("Failed to start {}", path));
+    let duration = started.elapsed();
+    if let Some((dir, _)) = &scratch {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    let code = exit_code(status?);
+
+    // Best effort, like the shell hook: the tool already ran
+    let command = std::iter::once(tool_name.clone()).chain(args).collect::<Vec<_>>().join(" ");
+    let request = TrackCommandRequest {
+        command,
+        exit_code: code,
+        cwd: cwd.map(|p| p.display().to_string()),
+        duration_ms: Some(duration.as_millis() as u64),
+        tool: Some(path),
+    }.build_request(generate_id())?;
+    if let Err(e) = client.request_timeout(request, RECORD_TIMEOUT) {
+        debug!("failed to record run: {}", e);
+    }
+
+    if verbose {
+        let summary = format!("{} exited {} in {:.2}s", tool_name, code, duration.as_secs_f64());
+        if code == 0 {
+            eprintln!("{}", summary.dimmed());
+        } else {
+            eprintln!("{}", summary.yellow());
+        }
+    }
+    Ok(code)
+}
+
+/// The tool's source as the daemon knows it
+fn fetch(client: &mut DaemonClient, path: &str) -> Result<String> {
+    let request = CatRequest { path: path.to_string(), version: None };
+    let response = client.request(request.build_request(generate_id())?)
+        .context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Err(Port42Error::from_lookup(
+            response.error.as_deref(),
+            ERR_PATH_NOT_FOUND,
+            &format!("No crystallized command at '{}'", path),
+        ).into());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    Ok(CatResponse::parse_response(&data)?.content)
+}
+
+/// A private copy of the tool to execute: the directory that holds it
+/// (removed after the run) and the file itself
+fn write_scratch(tool_name: &str, content: &str) -> Result<(PathBuf, PathBuf)> {
+    let dir = private_dir("run")?;
+    let scratch = dir.join(tool_name);
+    if let Err(e) = create_private_file(&scratch, content.as_bytes(), true) {
+        let _ = std::fs::remove_dir_all(&dir);
+        return Err(e);
+    }
+    Ok((dir, scratch))
+}
+
+/// Shell convention: killed by a signal reads as 128 + signal
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
@@ -1,7 +1,9 @@
 use anyhow::{Result, Context};
 use crate::client::DaemonClient;
 use crate::help_text::*;
-use crate::protocol::{SearchRequest, SearchFilters, SearchResponse, RequestBuilder, ResponseParser, parse_date};
+use crate::protocol::{SearchRequest, SearchFilters, SearchResponse, SearchResult, SearchMetadata, RequestBuilder, ResponseParser, parse_date};
+use colored::*;
+use crate::common::semantic::{self, SemanticIndex};
 use crate::display::{Displayable, OutputFormat};
 
 pub fn handle_search(
@@ -28,7 +30,7 @@ pub fn handle_search(
         tags,
         limit,
         OutputFormat::Plain,
-    )
+    ).map(|_| ())
 }
 
 pub fn handle_search_with_format(
@@ -43,7 +45,7 @@ pub fn handle_search_with_format(
     tags: Vec<String>,
     limit: Option<usize>,
     format: OutputFormat,
-) -> Result<()> {
+) -> Result<Option<String>> {
     // Build filters
     let mut filters = SearchFilters::default();
     
@@ -66,6 +68,21 @@ pub fn handle_search_with_format(
     
     filters.limit = limit.or(Some(20));
     
+    search_with_filters(client, query, mode, filters, format)
+}
+
+/// Run a search whose filters are already assembled (and dates normalized)
+pub fn search_with_filters(
+    client: &mut DaemonClient,
+    query: String,
+    mode: &str,
+    filters: SearchFilters,
+    format: OutputFormat,
+) -> Result<Option<String>> {
+    if mode == "semantic" {
+        return handle_semantic_search(client, query, filters, format);
+    }
+    
     // Create request with mode
     let mut request = SearchRequest::new(query.clone());
     request.mode = Some(mode.to_string());
@@ -82,7 +99,7 @@ pub fn handle_search_with_format(
             ERR_CONNECTION_LOST,
             error
         ));
-        return Ok(());
+        return Ok(None);
     }
     
     // Parse response
@@ -98,5 +115,95 @@ pub fn handle_search_with_format(
     // Display using the displayable trait
     search_response.display(format)?;
     
-    Ok(())
-}
\ No newline at end of file
+    Ok(search_response.results.first().map(|r| r.path.clone()))
+}
+
+/// Hybrid search: rank by embedding similarity and by keywords, then fuse
+fn handle_semantic_search(
+    client: &mut DaemonClient,
+    query: String,
+    filters: SearchFilters,
+    format: OutputFormat,
+) -> Result<Option<String>> {
+    // Index entries carry no dates, agents or tags to filter on
+    if filters.after.is_some() || filters.before.is_some() || filters.agent.is_some() || filters.tags.is_some() {
+        anyhow::bail!(format_error_with_suggestion(ERR_SEMANTIC_FILTERS,
+            "Drop --semantic to filter by --agent, --after, --before or --tag"));
+    }
+    let index = SemanticIndex::load()?;
+    if index.is_empty() {
+        anyhow::bail!(format_error_with_suggestion(ERR_NO_SEMANTIC_INDEX, "Build one with: port42 index build"));
+    }
+    let limit = filters.limit.unwrap_or(20);
+    
+    let provider = crate::commands::index::embedding_provider()?;
+    let embedded = crate::commands::index::embed(client, vec![query.clone()], &provider)?;
+    if index.model.is_some() && embedded.model.is_some() && index.model != embedded.model {
+        eprintln!("{}", format!("⚠️  Index was built with {}, rebuild with 'port42 index build'",
+            index.model.as_deref().unwrap_or("another model")).yellow());
+    }
+    let query_vector = embedded.vectors.into_iter().next().unwrap_or_default();
+    
+    // Semantic candidates, honoring the filters the index can check locally
+    let semantic_hits: Vec<_> = index.nearest(&query_vector, index.entries.len())
+        .into_iter()
+        .filter(|(entry, _)| filters.path.as_ref().is_none_or(|p| entry.path.starts_with(p.as_str())))
+        .filter(|(entry, _)| filters.type_filter.as_ref().is_none_or(|t| &entry.entry_type == t))
+        .take(limit)
+        .collect();
+    
+    // Keyword candidates from the daemon's regular search
+    let request = SearchRequest::new(query.clone()).with_filters(filters);
+    let response = client.request(request.build_request(format!("search-{}", chrono::Utc::now().timestamp_millis()))?)
+        .context(ERR_CONNECTION_LOST)?;
+    let keyword = match response.data.filter(|_| response.success) {
+        Some(data) => SearchResponse::parse_response(&data)?,
+        None => SearchResponse { query: query.clone(), count: 0, results: Vec::new(), filters: None },
+    };
+    
+    let rankings = vec![
+        semantic_hits.iter().map(|(entry, _)| entry.path.clone()).collect(),
+        keyword.results.iter().map(|r| r.path.clone()).collect(),
+    ];
+    let mut keyword_results: std::collections::HashMap<String, SearchResult> = keyword.results.into_iter()
+        .map(|r| (r.path.clone(), r))
+        .collect();
+    
+    let mut results = Vec::new();
+    for (path, score) in semantic::fuse_rankings(&rankings).into_iter().take(limit) {
+        let semantic_entry = semantic_hits.iter().find(|(entry, _)| entry.path == path).map(|(entry, _)| *entry);
+        let result = match (keyword_results.remove(&path), semantic_entry) {
+            (Some(mut result), found) => {
+                if found.is_some() {
+                    result.match_fields.push("semantic".to_string());
+                }
+                result.score = score;
+                result
+            }
+            (None, Some(entry)) => SearchResult {
+                path: entry.path.clone(),
+                result_type: entry.entry_type.clone(),
+                score,
+                snippet: Some(entry.snippet.clone()),
+                match_fields: vec!["semantic".to_string()],
+                metadata: Some(SearchMetadata {
+                    created: None,
+                    agent: None,
+                    title: Some(entry.title.clone()),
+                    description: None,
+                }),
+            },
+            (None, None) => continue,
+        };
+        results.push(result);
+    }
+    
+    let search_response = SearchResponse {
+        query,
+        count: results.len() as u64,
+        results,
+        filters: keyword.filters,
+    };
+    search_response.display(format)?;
+    Ok(search_response.results.first().map(|r| r.path.clone()))
+}
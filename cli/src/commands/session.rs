@@ -3,6 +3,7 @@ use colored::*;
 use serde_json::Value;
 use crate::client::DaemonClient;
 use crate::protocol::{LsRequest, InfoRequest, CatRequest, RequestBuilder, ResponseParser, LsResponse, InfoResponse, CatResponse};
+use crate::common::errors::Port42Error;
 use crate::help_text::*;
 use chrono::{DateTime, Local};
 
@@ -41,7 +42,7 @@ pub fn handle_session(port: u16, id_prefix: String) -> Result<()> {
     // Handle matches
     match matching_sessions.len() {
         0 => {
-            bail!("No session found matching prefix '{}'", id_prefix);
+            Err(Port42Error::NotFound(format!("No session found matching prefix '{}'", id_prefix)).into())
         }
         1 => {
             let session_name = &matching_sessions[0];
@@ -53,7 +54,7 @@ pub fn handle_session(port: u16, id_prefix: String) -> Result<()> {
             println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".bright_blue());
 
             // Get metadata
-            let info_request = InfoRequest { path: full_path.clone() };
+            let info_request = InfoRequest { path: full_path.clone(), version: None };
             let daemon_request = info_request.build_request(format!("info-session-{}", chrono::Utc::now().timestamp()))?;
             let response = client.request(daemon_request)
                 .context(ERR_CONNECTION_LOST)?;
@@ -98,7 +99,7 @@ pub fn handle_session(port: u16, id_prefix: String) -> Result<()> {
             println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".bright_blue());
 
             // Get content
-            let cat_request = CatRequest { path: full_path };
+            let cat_request = CatRequest { path: full_path, version: None };
             let daemon_request = cat_request.build_request(format!("cat-session-{}", chrono::Utc::now().timestamp()))?;
             let response = client.request(daemon_request)
                 .context(ERR_CONNECTION_LOST)?;
@@ -159,8 +160,7 @@ pub fn handle_session(port: u16, id_prefix: String) -> Result<()> {
             for session in &matching_sessions {
                 println!("  • {}", session.bright_cyan());
             }
-            println!("\nPlease provide a more specific prefix.");
-            std::process::exit(1);
+            Err(Port42Error::Usage("Please provide a more specific prefix.".to_string()).into())
         }
     }
 }
\ No newline at end of file
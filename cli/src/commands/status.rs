@@ -2,7 +2,7 @@ use anyhow::{Result, anyhow};
 use colored::*;
 
 use crate::client::DaemonClient;
-use crate::protocol::{StatusRequest, StatusResponse, RequestBuilder, ResponseParser};
+use crate::protocol::{DetailedStatusRequest, StatusRequest, StatusResponse, RequestBuilder, ResponseParser};
 use crate::display::{Displayable, OutputFormat};
 use crate::common::{generate_id, errors::Port42Error};
 use crate::help_text;
@@ -12,13 +12,17 @@ pub fn handle_status(port: u16, detailed: bool) -> Result<()> {
     handle_status_with_format(&mut client, detailed, OutputFormat::Plain)
 }
 
-pub fn handle_status_with_format(client: &mut DaemonClient, _detailed: bool, format: OutputFormat) -> Result<()> {
-    if format != OutputFormat::Json {
+pub fn handle_status_with_format(client: &mut DaemonClient, detailed: bool, format: OutputFormat) -> Result<()> {
+    if !format.is_structured() {
         println!("{}", help_text::MSG_CHECKING_STATUS.blue().bold());
     }
     
     // Build request using protocol types
-    let request = StatusRequest.build_request(generate_id())?;
+    let request = if detailed {
+        DetailedStatusRequest.build_request(generate_id())?
+    } else {
+        StatusRequest.build_request(generate_id())?
+    };
     
     // Send to daemon
     match client.request(request) {
@@ -34,11 +38,23 @@ pub fn handle_status_with_format(client: &mut DaemonClient, _detailed: bool, for
             
             // Display using framework
             status_response.display(format)?;
+            
+            // The table already carries the health checks
+            if detailed && format == OutputFormat::Plain {
+                status_response.display_details();
+            }
         }
+        // It answered; we just can't trust what it would say
+        Err(e) if matches!(e.downcast_ref(), Some(Port42Error::Incompatible(_))) => return Err(e),
         Err(e) => {
-            if format == OutputFormat::Json {
-                // For JSON, output an offline status
-                println!(r#"{{"status":"offline","port":{},"error":"Connection failed"}}"#, client.port());
+            if format.is_structured() {
+                // For JSON or YAML, output an offline status
+                let offline = serde_json::json!({"status": "offline", "port": client.port(), "error": "Connection failed"});
+                if format == OutputFormat::Yaml {
+                    crate::display::print_yaml(&offline)?;
+                } else {
+                    println!("{}", offline);
+                }
             } else {
                 // Connection failed - show offline message
                 println!("{}", help_text::format_daemon_connection_error(client.port()));
@@ -5,7 +5,74 @@ use crate::interactive::InteractiveSession;
 use crate::boot::{show_boot_sequence, show_connection_progress};
 use crate::help_text;
 use crate::swim::{SessionHandler, determine_session_id};
-use crate::common::{errors::Port42Error, references::parse_references};
+use crate::common::{generate_id, errors::Port42Error, references::parse_references, providers::{ProviderArgs, ensure_reachable, resolve_fallbacks, resolve_provider}};
+use crate::common::cache::ResponseCache;
+use crate::common::budget::TokenBudget;
+use crate::common::approval::{ApprovalPolicy, BashApproval};
+use crate::common::template;
+use crate::common::workspace;
+use crate::config::Config;
+use crate::agents::AgentRegistry;
+use crate::project::Project;
+use crate::protocol::{RequestBuilder, SwimRequest};
+use crate::commands::jobs::{can_detach, submit_detached};
+use crate::commands::outbox;
+
+/// Per-invocation flags for swim/possess
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct SwimArgs {
+    /// AI provider and model selection (overrides config defaults)
+    #[command(flatten)]
+    pub provider: ProviderArgs,
+    
+    /// Ask the AI again even if an identical prompt is cached
+    #[arg(long)]
+    pub no_cache: bool,
+    
+    /// Most tokens this session may spend before asking to continue
+    #[arg(long, value_name = "TOKENS")]
+    pub token_budget: Option<u64>,
+    
+    /// Start from a saved prompt template (see 'port42 prompts list')
+    #[arg(long, visible_alias = "prompt-template", value_name = "NAME", add = clap_complete::ArgValueCompleter::new(crate::commands::completions::complete_template))]
+    pub template: Option<String>,
+    
+    /// Template variable as key=value (can be used multiple times)
+    #[arg(long = "var", value_name = "KEY=VALUE", action = clap::ArgAction::Append, requires = "template")]
+    pub vars: Vec<String>,
+    
+    /// Wait for the whole reply instead of showing it as it's written
+    #[arg(long)]
+    pub no_stream: bool,
+    
+    /// Whether the AI may run bash commands that aren't on the allowlist
+    #[arg(long, value_enum, value_name = "POLICY", env = "PORT42_APPROVE_BASH")]
+    pub approve_bash: Option<BashApproval>,
+    
+    /// Submit the message as a background job and return its ID straight away
+    #[arg(long)]
+    pub detach: bool,
+    
+    /// If the daemon can't be reached, save the message to send later
+    #[arg(long)]
+    pub queue: bool,
+    
+    /// Print tokens and estimated cost under each reply
+    #[arg(long)]
+    pub show_usage: bool,
+    
+    /// Don't tell the AI about the working directory, even if the config says to
+    #[arg(long)]
+    pub no_workspace_context: bool,
+}
+
+/// Conversation context and routing gathered from CLI flags
+#[derive(Default)]
+struct SwimOptions {
+    memory_context: Vec<String>,
+    references: Option<Vec<crate::protocol::relations::Reference>>,
+    args: SwimArgs,
+}
 
 pub fn handle_swim_with_references(
     port: u16, 
@@ -13,6 +80,7 @@ pub fn handle_swim_with_references(
     message: Option<String>, 
     session: Option<String>,
     references: Option<Vec<String>>,
+    args: SwimArgs,
     show_boot: bool
 ) -> Result<()> {
     // Parse references if provided - daemon will resolve them server-side
@@ -24,8 +92,7 @@ pub fn handle_swim_with_references(
                 Some(refs)
             },
             Err(e) => {
-                eprintln!("{} {}", "❌ Invalid reference:".red(), e);
-                std::process::exit(1);
+                return Err(Port42Error::Usage(format!("❌ Invalid reference: {}", e)).into());
             }
         }
     } else {
@@ -33,7 +100,8 @@ pub fn handle_swim_with_references(
     };
     
     // Use unified flow with references - no manual memory context loading
-    handle_swim_with_boot_and_context(port, agent, message, session, show_boot, Vec::new(), parsed_refs)
+    let options = SwimOptions { references: parsed_refs, args, ..Default::default() };
+    handle_swim_with_boot_and_context(port, agent, message, session, show_boot, options)
 }
 
 
@@ -53,7 +121,7 @@ fn handle_swim_with_boot(
     session: Option<String>,
     show_boot: bool
 ) -> Result<()> {
-    handle_swim_with_boot_and_context(port, agent, message, session, show_boot, Vec::new(), None)
+    handle_swim_with_boot_and_context(port, agent, message, session, show_boot, SwimOptions::default())
 }
 
 fn handle_swim_with_boot_and_context(
@@ -62,11 +130,90 @@ fn handle_swim_with_boot_and_context(
     message: Option<String>, 
     session: Option<String>,
     show_boot: bool,
-    memory_context: Vec<String>,
-    references: Option<Vec<crate::protocol::relations::Reference>>
+    options: SwimOptions
 ) -> Result<()> {
+    let SwimOptions { memory_context, references, args } = options;
+    let SwimArgs { provider, no_cache, token_budget, template, vars, no_stream, approve_bash, detach, queue, show_usage, no_workspace_context } = args;
+    
     // Validate agent
-    validate_agent(&agent)?;
+    let registry = AgentRegistry::load_or_default();
+    validate_agent(&agent, &registry)?;
+    let instructions = registry.instructions(&agent);
+    let definition = registry.get(&agent).cloned().unwrap_or_default();
+    
+    // Project and agent default references come before any given on the command line
+    let project = Project::discover();
+    let mut default_refs = project.as_ref().map(Project::references).unwrap_or_default();
+    default_refs.extend(definition.default_refs);
+    let references = if default_refs.is_empty() {
+        references
+    } else {
+        let mut defaults = parse_references(default_refs, false)
+            .map_err(|e| anyhow::anyhow!("Invalid default reference for {}: {}", agent, e))?;
+        defaults.extend(references.unwrap_or_default());
+        Some(defaults)
+    };
+    
+    // Project notes ride along with the agent's own persona and guidance
+    let guidance = Project::guidance_with(project.as_ref(), instructions);
+    
+    // A prompt template becomes the message, with any typed text appended
+    let message = match template {
+        Some(name) => {
+            let mut client = DaemonClient::new(port);
+            let rendered = crate::commands::prompts::render_prompt(&mut client, &name, &vars)?;
+            Some(template::append_extra(rendered, message))
+        }
+        None => message,
+    };
+    
+    // Explicit --provider/--model win over agent and global config defaults
+    let config = Config::load_or_default();
+    let no_fallback = provider.no_fallback;
+    let provider = resolve_provider(provider, Some(&agent), &config)?;
+    ensure_reachable(&provider)?;
+    let fallbacks = if no_fallback {
+        Vec::new()
+    } else {
+        resolve_fallbacks(Some(&agent), &provider, &config)?
+    };
+    let budget = TokenBudget::from_config(&config, token_budget);
+    let approval = ApprovalPolicy::from_config(&config, approve_bash);
+    let workspace = if !no_workspace_context && workspace::is_enabled(&config) {
+        std::env::current_dir().ok().map(|dir| workspace::collect(&dir))
+    } else {
+        None
+    };
+    
+    let queued = queue && !outbox::daemon_reachable(port);
+    let detach = detach && !queued && can_detach(&mut DaemonClient::new(port))?;
+    if detach || queued {
+        let flag = if queued { "--queue" } else { "--detach" };
+        let Some(message) = message else {
+            return Err(Port42Error::Usage(format!("{} needs a message to send", flag)).into());
+        };
+        let (session_id, _) = determine_session_id(session);
+        let summary = format!("possess {}: {}", agent, message);
+        let request = SwimRequest {
+            agent,
+            message,
+            memory_context: if memory_context.is_empty() { None } else { Some(memory_context) },
+            references,
+            approval_response: None,
+            provider,
+            guidance,
+            workspace,
+        };
+        let mut request = request.build_request(generate_id())?;
+        if let Some(obj) = request.payload.as_object_mut() {
+            obj.insert("session_id".to_string(), serde_json::Value::String(session_id));
+        }
+        if queued {
+            return outbox::enqueue(&summary, request);
+        }
+        submit_detached(&mut DaemonClient::new(port), request)?;
+        return Ok(());
+    }
     
     // Show boot sequence only if requested
     if show_boot {
@@ -86,6 +233,18 @@ fn handle_swim_with_boot_and_context(
     if let Some(msg) = message {
         // Single message mode - use shared handler
         let mut handler = SessionHandler::new(client, false);
+        handler.set_provider(provider);
+        handler.set_fallbacks(fallbacks);
+        handler.set_guidance(guidance.clone());
+        handler.set_workspace(workspace);
+        handler.set_budget(budget);
+        handler.set_streaming(!no_stream);
+        handler.set_approval_policy(approval);
+        handler.set_show_usage(show_usage);
+        // Resumed sessions carry history, so only fresh one-shots are cacheable
+        if is_new {
+            handler.set_cache(ResponseCache::from_config(&config, no_cache));
+        }
         
         // Show minimal connection info for CLI mode, full session info for interactive
         if !show_boot {
@@ -134,10 +293,18 @@ fn handle_swim_with_boot_and_context(
         let memory_ctx = if memory_context.is_empty() { None } else { Some(memory_context) };
         let response = handler.send_message_with_context(&session_id, &agent, &msg, memory_ctx, references)?;
         
-        // Show session completion with actual daemon session ID
         println!();
-        handler.display_session_complete(&response.session_id);
-        println!("{}", "Use 'memory' to review this thread".dimmed());
+        if handler.replayed() {
+            // The session in a cached answer is the one that first asked; none was made now
+            println!("{}", help_text::MSG_REPLAYED_NO_SESSION.dimmed());
+        } else {
+            // Show session completion with actual daemon session ID
+            handler.display_session_complete(&response.session_id);
+            if let Some(budget) = handler.budget() {
+                println!("{}", format!("Token budget: {}", budget.status()).dimmed());
+            }
+            println!("{}", "Use 'memory' to review this thread".dimmed());
+        }
     } else {
         // Interactive mode (no need to repeat "Channeling" message if boot was shown)
         if !show_boot {
@@ -151,7 +318,15 @@ fn handle_swim_with_boot_and_context(
         if is_tty && has_term {
             // Full immersive interactive mode
             let memory_ctx = if memory_context.is_empty() { None } else { Some(memory_context) };
-            let mut session = InteractiveSession::with_context(client, agent, session_id.clone(), memory_ctx, references);
+            let mut session = InteractiveSession::with_context(client, agent, session_id.clone(), memory_ctx, references)
+                .with_provider(provider)
+                .with_fallbacks(fallbacks)
+                .with_guidance(guidance.clone())
+                .with_workspace(workspace)
+                .with_budget(budget)
+                .with_streaming(!no_stream)
+                .with_approval_policy(approval)
+                .with_show_usage(show_usage);
             session.run()?;
         } else {
             // Fallback to simple interactive mode
@@ -164,6 +339,14 @@ fn handle_swim_with_boot_and_context(
             
             // Use shared handler for simple mode
             let mut handler = SessionHandler::new(client, false);
+            handler.set_provider(provider);
+            handler.set_fallbacks(fallbacks);
+            handler.set_guidance(guidance.clone());
+            handler.set_workspace(workspace);
+            handler.set_budget(budget);
+            handler.set_streaming(!no_stream);
+            handler.set_approval_policy(approval);
+            handler.set_show_usage(show_usage);
             handler.display_session_info(&session_id, is_new);
             println!();
             
@@ -208,6 +391,12 @@ fn simple_interactive_mode_with_context(
             break;
         }
         
+        // Stop rather than error out if the budget is spent and not extended
+        if !handler.confirm_budget()? {
+            println!("{}", help_text::ERR_BUDGET_DECLINED.dimmed());
+            break;
+        }
+        
         // Send message with session context
         let response = handler.send_message_with_context(session_id, agent, input, memory_ctx.clone(), references.clone())?;
         
@@ -218,6 +407,9 @@ fn simple_interactive_mode_with_context(
     // Show session completion with actual session ID
     println!();
     handler.display_session_complete(&actual_session_id);
+    if let Some(budget) = handler.budget() {
+        println!("{}", format!("Token budget: {}", budget.status()).dimmed());
+    }
     println!("{}", "Use 'memory' to review this thread".dimmed());
     
     Ok(())
@@ -236,6 +428,7 @@ fn end_session(port: u16, session_id: &str) -> Result<()> {
         references: None,
         session_context: None,
         user_prompt: None,
+        provider: None,
     };
     
     if let Err(e) = client.request(request) {
@@ -248,13 +441,13 @@ fn end_session(port: u16, session_id: &str) -> Result<()> {
     Ok(())
 }
 
-fn validate_agent(agent: &str) -> Result<()> {
-    const VALID_AGENTS: &[&str] = &["@ai-engineer", "@ai-muse", "@ai-analyst", "@ai-founder"];
-    
-    if !VALID_AGENTS.contains(&agent) {
+pub(crate) fn validate_agent(agent: &str, registry: &AgentRegistry) -> Result<()> {
+    if !registry.is_known(agent) {
+        let mut known: Vec<&str> = crate::agents::BUILTIN_AGENTS.to_vec();
+        known.extend(registry.agents.keys().map(String::as_str));
         let error_msg = format!("👻 Unknown consciousness '{}'. Choose from: {}", 
             agent, 
-            VALID_AGENTS.join(", ")
+            known.join(", ")
         );
         bail!(Port42Error::Daemon(error_msg));
     }
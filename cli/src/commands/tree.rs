@@ -0,0 +1,117 @@
+use anyhow::{Result, Context};
+use clap::ValueEnum;
+use crate::client::DaemonClient;
+use crate::common::errors::Port42Error;
+use crate::help_text::*;
+use crate::protocol::{LsRequest, LsResponse, RequestBuilder, ResponseParser, TreeNode, VfsTree};
+use crate::display::{Displayable, OutputFormat};
+
+/// How far `tree` descends without -L; the virtual views nest deep
+pub const MAX_DEPTH: u32 = 16;
+
+/// What `tree --type` can keep
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum EntryKind {
+    File,
+    Directory,
+    /// Executable files, wherever they live
+    Command,
+}
+
+impl EntryKind {
+    fn matches(self, node: &TreeNode) -> bool {
+        match self {
+            EntryKind::File => !node.is_dir(),
+            EntryKind::Directory => node.is_dir(),
+            EntryKind::Command => !node.is_dir() && (node.executable.unwrap_or(false) || node.path.starts_with("/commands/")),
+        }
+    }
+}
+
+pub struct TreeOptions {
+    /// Levels below the starting path to show; None means MAX_DEPTH
+    pub level: Option<u32>,
+    pub types: Vec<EntryKind>,
+    pub sizes: bool,
+}
+
+pub fn handle_tree(client: &mut DaemonClient, path: Option<String>, options: &TreeOptions, format: OutputFormat) -> Result<()> {
+    let path = match path.as_deref().map(|p| p.trim_end_matches('/')) {
+        None | Some("") => "/".to_string(),
+        Some(p) => p.to_string(),
+    };
+    let root = load(client, &path, options)?;
+    VfsTree::new(root, options.sizes).display(format)
+}
+
+/// Everything under `path`, as far down as `options` allow
+pub fn load(client: &mut DaemonClient, path: &str, options: &TreeOptions) -> Result<TreeNode> {
+    let path = path.to_string();
+
+    // The starting point has to exist; anything deeper that fails is noted in place
+    let listing = list(client, &path)?.map_err(|_| Port42Error::NotFound(format_error_with_suggestion(
+        ERR_PATH_NOT_FOUND,
+        &format!("Path '{}' does not exist in reality", path)
+    )))?;
+    let mut root = TreeNode {
+        name: path.rsplit('/').find(|s| !s.is_empty()).unwrap_or("/").to_string(),
+        path: path.clone(),
+        entry_type: "directory".to_string(),
+        size: None,
+        executable: None,
+        children: Vec::new(),
+        truncated: false,
+        error: None,
+    };
+    root.children = expand(client, &path, listing, 1, options)?;
+    Ok(root)
+}
+
+/// List one directory: Err for a broken connection, Ok(Err) when the daemon refuses
+fn list(client: &mut DaemonClient, path: &str) -> Result<std::result::Result<LsResponse, String>> {
+    let request = LsRequest { path: path.to_string() };
+    let daemon_request = request.build_request(format!("tree-{}", chrono::Utc::now().timestamp_millis()))?;
+    let response = client.request(daemon_request)
+        .context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Ok(Err(response.error.unwrap_or_else(|| "cannot be listed".to_string())));
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    Ok(Ok(LsResponse::parse_response(&data)?))
+}
+
+fn expand(client: &mut DaemonClient, parent: &str, listing: LsResponse, depth: u32, options: &TreeOptions) -> Result<Vec<TreeNode>> {
+    let max_depth = options.level.unwrap_or(MAX_DEPTH);
+    let mut nodes = Vec::new();
+    for entry in listing.entries {
+        let path = format!("{}/{}", parent.trim_end_matches('/'), entry.name);
+        let mut node = TreeNode {
+            name: entry.name,
+            path,
+            entry_type: entry.entry_type,
+            size: entry.size,
+            executable: entry.executable,
+            children: Vec::new(),
+            truncated: false,
+            error: None,
+        };
+        if node.is_dir() {
+            if depth >= max_depth {
+                node.truncated = true;
+            } else {
+                match list(client, &node.path)? {
+                    Ok(listing) => node.children = expand(client, &node.path, listing, depth + 1, options)?,
+                    Err(error) => node.error = Some(error),
+                }
+            }
+        }
+        // Directories stay when they hold something that matched
+        let keep = options.types.is_empty()
+            || options.types.iter().any(|kind| kind.matches(&node))
+            || !node.children.is_empty();
+        if keep {
+            nodes.push(node);
+        }
+    }
+    Ok(nodes)
+}
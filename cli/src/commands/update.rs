@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use colored::*;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use crate::client::DaemonClient;
+use crate::common::errors::Port42Error;
+use crate::common::generate_id;
+use crate::common::materialize::Links;
+use crate::help_text::*;
+use crate::protocol::{RequestBuilder, ResponseParser, UpdatePathRequest, UpdatePathResponse};
+use crate::display::{Displayable, OutputFormat, diff::{DEFAULT_CONTEXT, TextDiff}};
+use super::diff::fetch;
+
+pub fn handle_update(client: &mut DaemonClient, path: String, from: &Path, yes: bool, format: OutputFormat) -> Result<()> {
+    let content = read_source(from)?;
+    if content.is_empty() {
+        return Err(Port42Error::Usage(format!("{} is empty; remove the object with 'port42 rm {}' instead", from.display(), path)).into());
+    }
+    // Only existing objects are updated; anything new is declared
+    let current = fetch(client, &path, None)?;
+    if current.as_bytes() == content.as_slice() {
+        println!("{}", format!("{} already matches {}; nothing to update", path, from.display()).dimmed());
+        return Ok(());
+    }
+
+    if !yes {
+        if format.is_structured() || !atty::is(atty::Stream::Stdin) || is_stdin(from) {
+            return Err(Port42Error::Usage("Pass --yes to update without a prompt".to_string()).into());
+        }
+        let new = String::from_utf8_lossy(&content);
+        TextDiff::new(&format!("{} (current)", path), &current, &from.display().to_string(), &new, DEFAULT_CONTEXT)
+            .display(OutputFormat::Plain)?;
+        println!();
+        print!("Update {} with these changes? [y/N]: ", path);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("{}", "Nothing was updated".dimmed());
+            return Ok(());
+        }
+    }
+
+    let request = UpdatePathRequest { path: path.clone(), content: content.clone() };
+    let response = client.request(request.build_request(generate_id())?)
+        .context(ERR_CONNECTION_LOST)?;
+    if !response.success {
+        return Err(Port42Error::from_daemon(&response.error.unwrap_or_else(|| format!("Cannot update '{}'", path))).into());
+    }
+    let data = response.data.context(ERR_INVALID_RESPONSE)?;
+    let mut result = UpdatePathResponse::parse_response(&data)?;
+    result.path = path.clone();
+    result.size = content.len();
+
+    // A materialized copy that was just sent is in sync again
+    if let Ok(file) = std::path::absolute(from) {
+        let mut links = Links::load();
+        if links.files.get(&file).is_some_and(|link| link.vfs_path == path) {
+            links.record(&file, &path, &content);
+            links.save()?;
+        }
+    }
+    result.display(format)
+}
+
+fn is_stdin(from: &Path) -> bool {
+    from.as_os_str() == "-"
+}
+
+/// The new content: a file, or standard input for `-`
+fn read_source(from: &Path) -> Result<Vec<u8>> {
+    if is_stdin(from) {
+        let mut content = Vec::new();
+        io::stdin().read_to_end(&mut content)?;
+        return Ok(content);
+    }
+    std::fs::read(from).map_err(|e| Port42Error::NotFound(format!("Cannot read {}: {}", from.display(), e)).into())
+}
@@ -0,0 +1,105 @@
+use anyhow::{bail, Context, Result};
+use colored::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use crate::common::errors::Port42Error;
+use crate::common::upgrade::{self, Release};
+use crate::common::utils::private_dir;
+use crate::config::port42_dir;
+use crate::help_text::*;
+use super::daemon::{is_daemon_running, DAEMON_BINARY};
+
+pub fn handle_upgrade(check: bool, to: Option<String>, force: bool, finish: bool) -> Result<()> {
+    if finish {
+        return finish_upgrade();
+    }
+
+    let current = env!("CARGO_PKG_VERSION");
+    let platform = upgrade::platform().ok_or_else(|| Port42Error::Usage(format!(
+        "No Port 42 releases are built for {} on {}", std::env::consts::OS, std::env::consts::ARCH
+    )))?;
+    let base = upgrade::releases_url();
+    let release = match &to {
+        Some(version) => Release::new(&base, version, &platform),
+        None => Release::latest(&base, &platform)?,
+    };
+
+    if !force && !upgrade::is_newer(&release.version, current) {
+        if to.is_some() {
+            println!("{}", format!("v{} isn't newer than this v{}", release.version, current).yellow());
+            println!("{}", "💡 Pass --force to install it anyway".dimmed());
+        } else {
+            println!("{}", format!("{} (v{})", MSG_UPGRADE_CURRENT, current).green());
+        }
+        return Ok(());
+    }
+
+    if check {
+        println!("{}", format!("⬆️  v{} → v{} is available", current, release.version).bright_white().bold());
+        println!("{}", format!("   {}", release.archive_url).dimmed());
+        println!("{}", "💡 Install it with: port42 upgrade".dimmed());
+        return Ok(());
+    }
+
+    let work = private_dir("upgrade")?;
+    let result = install(&release, &work);
+    fs::remove_dir_all(&work).ok();
+    result
+}
+
+fn install(release: &Release, work: &Path) -> Result<()> {
+    println!("{}", format!("🐬 Fetching v{} for {}...", release.version, release.platform).blue().bold());
+    let archive = release.fetch()?;
+    println!("{}", "🔏 Checksum verified".dimmed());
+
+    let unpacked = upgrade::unpack(&archive, work)?;
+    let reported = upgrade::binary_version(&unpacked.cli)?;
+    if reported != release.version {
+        bail!("The downloaded port42 reports v{}, not v{}; nothing was installed", reported, release.version);
+    }
+
+    let cli = std::env::current_exe().context("Can't tell where this port42 is installed")?;
+    let daemon = which::which(DAEMON_BINARY).unwrap_or_else(|_| {
+        cli.with_file_name(format!("{}{}", DAEMON_BINARY, std::env::consts::EXE_SUFFIX))
+    });
+    let was_running = is_daemon_running();
+
+    let backup = port42_dir().join("backup");
+    let installed = upgrade::swap(&[(&unpacked.cli, &cli), (&unpacked.daemon, &daemon)], &backup)?;
+    println!("{}", format!("📦 Replaced {} and {}", cli.display(), daemon.display()).dimmed());
+    for (path, digest) in &installed {
+        println!("{}", format!("   sha256 {}  {}", digest, path.display()).dimmed());
+    }
+    println!("{}", format!("   Previous binaries kept in {}", backup.display()).dimmed());
+    for file in &unpacked.data {
+        if let Some(name) = file.file_name() {
+            fs::copy(file, port42_dir().join(name))?;
+        }
+    }
+
+    // Only the new binary knows what the new layout looks like
+    let migrated = Command::new(&cli).args(["upgrade", "--finish"]).status()
+        .context("Failed to run the new port42")?;
+    if !migrated.success() {
+        bail!(format_error_with_suggestion(
+            "The new binaries are in place, but ~/.port42 couldn't be brought up to date",
+            &format!("Run 'port42 upgrade --finish' to try again, or restore from {}", backup.display())
+        ));
+    }
+
+    if was_running {
+        Command::new(&cli).args(["daemon", "restart"]).status()
+            .context("Failed to restart the daemon")?;
+    }
+
+    println!("{}", format!("{} (v{})", MSG_UPGRADE_DONE, release.version).green());
+    Ok(())
+}
+
+fn finish_upgrade() -> Result<()> {
+    for step in upgrade::migrate(&port42_dir())? {
+        println!("{}", format!("🧳 {}", step).dimmed());
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anyhow::{Result, anyhow};
+
+use crate::client::DaemonClient;
+use crate::protocol::{RequestBuilder, ResponseParser};
+use crate::protocol::usage::{BudgetStatus, UsageReport, UsageRequest, UsageResponse};
+use crate::display::{Displayable, OutputFormat};
+use crate::common::{generate_id, errors::Port42Error, utils::{parse_since, start_of_month}};
+use crate::config::Config;
+
+pub fn handle_usage_with_format(port: u16, since: Option<String>, until: Option<String>, format: OutputFormat) -> Result<()> {
+    let since = since.map(|s| parse_since(&s)).transpose()?;
+    let until = until.map(|s| parse_since(&s)).transpose()?;
+    if let (Some(since), Some(until)) = (since, until) {
+        if since > until {
+            return Err(Port42Error::Usage("--since must be earlier than --until".to_string()).into());
+        }
+    }
+    let budget = Config::load_or_default().usage.and_then(|u| u.monthly_budget);
+
+    // The budget check needs the whole month even when the report window is shorter
+    let month_start = start_of_month();
+    let fetch_since = match (since, budget) {
+        (Some(s), Some(_)) => Some(s.min(month_start)),
+        (s, _) => s,
+    };
+    // Usage is recorded per day, so the last day counts in full
+    let until_day = until.map(|u| u.format("%Y-%m-%d").to_string());
+    let fetch_until = until
+        .filter(|_| budget.is_none())
+        .and_then(|u| u.date_naive().succ_opt())
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .map(|midnight| midnight.and_utc().to_rfc3339());
+
+    let mut client = DaemonClient::new(port);
+    let request = UsageRequest { since: fetch_since.map(|s| s.to_rfc3339()), until: fetch_until }
+        .build_request(generate_id())?;
+    let response = client.request(request)?;
+
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(Port42Error::from_daemon(&error).into());
+    }
+
+    let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
+    let usage = UsageResponse::parse_response(&data)?;
+
+    let since_day = since.map(|s| s.format("%Y-%m-%d").to_string());
+    let in_window = usage.records.iter()
+        .filter(|r| since.is_none_or(|since| r.is_since(since)))
+        .filter(|r| until_day.as_ref().is_none_or(|day| r.date.as_str() <= day.as_str()));
+    let mut report = UsageReport::from_records(in_window, since_day.clone());
+    report.until = until_day;
+
+    if let Some(monthly_budget) = budget {
+        let month_to_date = usage.records.iter()
+            .filter(|r| r.is_since(month_start))
+            .map(|r| r.cost)
+            .sum();
+        report.budget = Some(BudgetStatus { monthly_budget, month_to_date });
+    }
+
+    report.display(format)
+}
@@ -1,5 +1,8 @@
 use anyhow::Result;
+use crate::client::DaemonClient;
+use crate::display::{Displayable, OutputFormat};
 use crate::protocol::status::send_watch_request;
+use crate::protocol::{MemoryListRequest, MemoryListResponse, RequestBuilder, ResponseParser};
 
 pub fn watch_rules(port: u16) -> Result<()> {
     println!("🔍 Watching rule engine activity...");
@@ -40,6 +43,35 @@ pub fn watch_rules(port: u16) -> Result<()> {
     Ok(())
 }
 
+pub fn watch_sessions(port: u16, refresh_ms: u64) -> Result<()> {
+    use crate::context::sessions_tui;
+
+    if let Err(e) = sessions_tui::run_sessions_watch(DaemonClient::new(port), refresh_ms) {
+        eprintln!("⚠️  TUI mode not available ({}), using text mode...", e);
+        watch_sessions_text(port, refresh_ms)?;
+    }
+    Ok(())
+}
+
+/// Plain redraw loop for terminals the TUI can't drive
+fn watch_sessions_text(port: u16, refresh_ms: u64) -> Result<()> {
+    use std::io::Write;
+
+    let mut client = DaemonClient::new(port);
+    loop {
+        print!("\x1B[2J\x1B[H");
+        std::io::stdout().flush().unwrap_or(());
+        println!("🐬 Port42 Sessions (text mode) - Press Ctrl+C to stop\n");
+
+        let response = client.request(MemoryListRequest.build_request(crate::common::generate_id())?)?;
+        match response.data.filter(|_| response.success) {
+            Some(data) => MemoryListResponse::parse_response(&data)?.display(OutputFormat::Plain)?,
+            None => eprintln!("❌ {}", response.error.unwrap_or_else(|| "Failed to retrieve memory".to_string())),
+        }
+        std::thread::sleep(std::time::Duration::from_millis(refresh_ms));
+    }
+}
+
 fn format_timestamp(timestamp: &str) -> String {
     // For now, just show time part
     if let Some(time_part) = timestamp.split('T').nth(1) {
@@ -0,0 +1,64 @@
+//! User-defined command aliases
+//!
+//! `[aliases]` in config.toml (or `port42 alias add`) maps a word to the
+//! command it stands for, e.g. `sw = "swim @ai-engineer"`. The first word of
+//! a command line is expanded, on the command line and in the shell alike,
+//! and an alias may start with another alias. Built-in commands always win
+//! over an alias of the same name.
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+use crate::common::errors::Port42Error;
+use crate::common::utils::split_words;
+
+/// Check a new alias name: one plain word that isn't already a command
+pub fn check_name(name: &str, builtins: &[String]) -> Result<()> {
+    let plain = !name.is_empty()
+        && !name.starts_with('-')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !plain {
+        return Err(Port42Error::Usage(format!("Alias names are letters, digits, '-' and '_', not '{}'", name)).into());
+    }
+    if builtins.iter().any(|b| b == name) {
+        return Err(Port42Error::Usage(format!("'{}' is a built-in command; pick another name for the alias", name)).into());
+    }
+    Ok(())
+}
+
+/// The alias text `name` stands for and its words, or None when it isn't an alias in use
+fn lookup<'a>(name: &str, aliases: &'a BTreeMap<String, String>, builtins: &[String], seen: &mut Vec<String>) -> Result<Option<(&'a str, Vec<String>)>> {
+    if builtins.iter().any(|b| b == name) {
+        return Ok(None);
+    }
+    let Some(command) = aliases.get(name) else { return Ok(None) };
+    if seen.iter().any(|s| s == name) {
+        seen.push(name.to_string());
+        return Err(Port42Error::Usage(format!("Alias loop: {}", seen.join(" → "))).into());
+    }
+    seen.push(name.to_string());
+    let words = split_words(command).map_err(|e| Port42Error::Usage(format!("Alias '{}': {}", name, e)))?;
+    if words.is_empty() {
+        return Err(Port42Error::Usage(format!("Alias '{}' is empty", name)).into());
+    }
+    Ok(Some((command, words)))
+}
+
+/// Expand an alias at the start of `words`; anything else comes back unchanged
+pub fn expand(words: &[String], aliases: &BTreeMap<String, String>, builtins: &[String]) -> Result<Vec<String>> {
+    let mut words = words.to_vec();
+    let mut seen = Vec::new();
+    while let Some((_, expansion)) = match words.first() {
+        Some(first) => lookup(first, aliases, builtins, &mut seen)?,
+        None => None,
+    } {
+        words.splice(..1, expansion);
+    }
+    Ok(words)
+}
+
+/// Split a shell line into words, "quoted phrases" kept whole, and expand an alias at the start
+pub fn expand_line(line: &str, aliases: &BTreeMap<String, String>, builtins: &[String]) -> Result<Vec<String>> {
+    let words = split_words(line).map_err(|e| Port42Error::Usage(e.to_string()))?;
+    expand(&words, aliases, builtins)
+}
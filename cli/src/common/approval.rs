@@ -0,0 +1,160 @@
+//! Deciding what happens when an AI asks to run a bash command
+//!
+//! `--approve-bash` (or `[approval] bash` in config.toml) sets the mode, and
+//! `[approval] allow` lists command patterns that run without asking, so
+//! scripts and pipelines never block on the prompt. Every decision made
+//! without the user is appended to `~/.port42/state/approvals.jsonl`, which
+//! `port42 context` shows alongside the daemon's activity.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::common::utils::split_words;
+use crate::config::Config;
+
+/// What to do with bash requests that aren't on the allowlist
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum BashApproval {
+    /// Deny without asking
+    Never,
+    /// Prompt, or deny when there is no terminal to prompt on
+    #[default]
+    Ask,
+    /// Approve without asking
+    Always,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    /// Decided without the user; the reason is logged
+    Approve(&'static str),
+    Deny(&'static str),
+    Ask,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ApprovalPolicy {
+    pub mode: BashApproval,
+    /// Patterns matched word by word against the command, see
+    /// `pattern_matches`. Commands that chain, pipe, redirect, substitute or
+    /// expand never match, so `git log` can't carry `; rm -rf ~` along.
+    /// Patterns should spell out the options they allow: a bare `*` word
+    /// stands for further arguments but never for options.
+    pub allow: Vec<String>,
+}
+
+impl ApprovalPolicy {
+    /// `[approval]` from config, with the flag (or PORT42_APPROVE_BASH) winning
+    pub fn from_config(config: &Config, flag: Option<BashApproval>) -> Self {
+        let approval = config.approval.clone().unwrap_or_default();
+        Self {
+            mode: flag.or(approval.bash).unwrap_or_default(),
+            allow: approval.allow,
+        }
+    }
+
+    /// `interactive` says whether there is a terminal to ask on
+    pub fn decide(&self, command: &str, interactive: bool) -> Decision {
+        if self.mode == BashApproval::Always {
+            return Decision::Approve("--approve-bash always");
+        }
+        if !has_shell_operators(command) && !has_expansions(command) && self.allow.iter().any(|pattern| pattern_matches(pattern, command)) {
+            return Decision::Approve("allowlist");
+        }
+        match self.mode {
+            BashApproval::Never => Decision::Deny("--approve-bash never"),
+            _ if !interactive => Decision::Deny("no terminal to ask on"),
+            _ => Decision::Ask,
+        }
+    }
+}
+
+/// Match `command` against `pattern` one argv word at a time. Each pattern
+/// word is a glob for one command word (`*` any characters, `?` one), so
+/// `git log --format=*` allows any format and nothing else. A pattern word
+/// that is only `*` matches any number of further words that aren't options:
+/// `git log *` allows `git log main` but not `git log --output=~/.bashrc`.
+pub fn pattern_matches(pattern: &str, command: &str) -> bool {
+    match (split_words(pattern), split_words(command)) {
+        (Ok(pattern), Ok(command)) => words_match(&pattern, &command),
+        _ => false,
+    }
+}
+
+fn words_match(pattern: &[String], command: &[String]) -> bool {
+    match pattern.split_first() {
+        None => command.is_empty(),
+        Some((word, rest)) if word == "*" => (0..=command.len())
+            .take_while(|&n| n == 0 || !command[n - 1].starts_with('-'))
+            .any(|n| words_match(rest, &command[n..])),
+        Some((word, rest)) => command.first().is_some_and(|first| glob_matches(word, first))
+            && words_match(rest, &command[1..]),
+    }
+}
+
+fn glob_matches(pattern: &str, word: &str) -> bool {
+    fn matches(p: &[char], c: &[char]) -> bool {
+        match p.split_first() {
+            None => c.is_empty(),
+            Some(('*', rest)) => (0..=c.len()).any(|i| matches(rest, &c[i..])),
+            Some(('?', rest)) => !c.is_empty() && matches(rest, &c[1..]),
+            Some((ch, rest)) => c.first() == Some(ch) && matches(rest, &c[1..]),
+        }
+    }
+    let p: Vec<char> = pattern.chars().collect();
+    let c: Vec<char> = word.chars().collect();
+    matches(&p, &c)
+}
+
+/// Whether `command` does more than run one program: `;`, `&`, `|`,
+/// redirections, backticks, `$(` or a line break
+pub fn has_shell_operators(command: &str) -> bool {
+    command.trim().contains([';', '&', '|', '<', '>', '`', '\n', '\r']) || command.contains("$(")
+}
+
+/// Whether bash would rewrite the words of `command` before running it:
+/// variables (`a${IFS}--output=x` is two words to git), escapes and brace
+/// expansion. What runs then isn't what the allowlist matched.
+pub fn has_expansions(command: &str) -> bool {
+    command.contains(['$', '\\', '{'])
+}
+
+/// A bash request decided by policy rather than by the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRecord {
+    pub timestamp: DateTime<Utc>,
+    pub session_id: String,
+    pub agent: String,
+    pub command: String,
+    pub approved: bool,
+    pub reason: String,
+}
+
+pub fn log_path() -> PathBuf {
+    crate::config::port42_dir().join("state").join("approvals.jsonl")
+}
+
+/// Append to the approval log
+pub fn record(entry: &ApprovalRecord) -> Result<()> {
+    let path = log_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// The most recent policy decisions, newest first
+pub fn recent(limit: usize) -> Vec<ApprovalRecord> {
+    let content = std::fs::read_to_string(log_path()).unwrap_or_default();
+    content.lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect()
+}
@@ -0,0 +1,166 @@
+//! Portable snapshots of Port 42 state
+//!
+//! An archive is a directory (optionally packed as a .tar.gz) holding one file
+//! per VFS object plus `manifest.json`, which records where each object lived,
+//! what kind it is, and the metadata the daemon reported for it. Files keep
+//! their VFS layout: `/commands/foo` is stored as `commands/foo`.
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Bumped when the manifest changes in a way older importers can't read
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub created: String,
+    /// Filters the export was made with, for the record
+    #[serde(default)]
+    pub filters: ArchiveFilters,
+    pub objects: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveFilters {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Where the object lived in the VFS
+    pub path: String,
+    /// tool, memory or artifact
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Location of the content, relative to the archive root
+    pub file: String,
+    pub size: u64,
+    pub sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
+impl Manifest {
+    pub fn new(filters: ArchiveFilters) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            created: chrono::Utc::now().to_rfc3339(),
+            filters,
+            objects: Vec::new(),
+        }
+    }
+
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(MANIFEST_FILE);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("No {} in {}: {}", MANIFEST_FILE, root.display(), e))?;
+        let manifest: Manifest = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("{} is not a Port 42 manifest: {}", path.display(), e))?;
+        if manifest.version > FORMAT_VERSION {
+            bail!("Archive format {} is newer than this CLI understands ({})", manifest.version, FORMAT_VERSION);
+        }
+        Ok(manifest)
+    }
+
+    /// Read every object's content, checking it against the manifest
+    pub fn verify(&self, root: &Path) -> Result<Vec<Vec<u8>>> {
+        self.objects.iter()
+            .map(|entry| {
+                if file_for(&entry.path).is_err() {
+                    bail!("Manifest entry has an unsafe path '{}'", entry.path);
+                }
+                file_for(&entry.file)?;
+                let content = std::fs::read(root.join(&entry.file))
+                    .map_err(|e| anyhow::anyhow!("{} is listed but missing: {}", entry.file, e))?;
+                if sha256_hex(&content) != entry.sha256 {
+                    bail!("{} does not match its checksum; the archive may be corrupt", entry.file);
+                }
+                Ok(content)
+            })
+            .collect()
+    }
+}
+
+/// What to do when an imported object's path is already taken
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+impl ConflictPolicy {
+    pub fn name(self) -> &'static str {
+        match self {
+            ConflictPolicy::Skip => "skip",
+            ConflictPolicy::Overwrite => "overwrite",
+            ConflictPolicy::Rename => "rename",
+        }
+    }
+}
+
+/// Relative file for a VFS path, refusing anything that would escape the archive
+pub fn file_for(vfs_path: &str) -> Result<String> {
+    let relative = vfs_path.trim_start_matches('/');
+    let safe = Path::new(relative).components().all(|c| matches!(c, Component::Normal(_)));
+    if relative.is_empty() || !safe {
+        bail!("Refusing to archive unsafe path '{}'", vfs_path);
+    }
+    Ok(relative.to_string())
+}
+
+pub fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Whether the output should be packed rather than left as a directory
+pub fn is_tarball(path: &Path) -> bool {
+    let name = path.to_string_lossy();
+    name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// A scratch directory for packing or unpacking
+pub fn scratch_dir(purpose: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("port42-{}-{}", purpose, uuid::Uuid::new_v4()))
+}
+
+pub fn pack(dir: &Path, tarball: &Path) -> Result<()> {
+    let status = Command::new("tar")
+        .arg("-czf").arg(tarball)
+        .arg("-C").arg(dir)
+        .arg(".")
+        .status()
+        .map_err(|e| anyhow::anyhow!("Could not run tar: {}", e))?;
+    if !status.success() {
+        bail!("tar failed to write {}", tarball.display());
+    }
+    Ok(())
+}
+
+pub fn unpack(tarball: &Path, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let status = Command::new("tar")
+        .arg("-xzf").arg(tarball)
+        .arg("-C").arg(dir)
+        .status()
+        .map_err(|e| anyhow::anyhow!("Could not run tar: {}", e))?;
+    if !status.success() {
+        bail!("tar failed to read {}", tarball.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,92 @@
+//! Per-session token budget
+//!
+//! Counts tokens spent by this CLI session against a configured ceiling so a
+//! long-running conversation can't quietly run up a bill.
+
+use crate::config::Config;
+
+/// Fraction of the budget at which the user is warned
+const WARN_AT: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetEvent {
+    /// Nothing worth telling the user
+    Within,
+    /// Just crossed the warning threshold
+    Warning,
+    /// Just went over the limit
+    Exceeded,
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenBudget {
+    limit: u64,
+    used: u64,
+    overrun_approved: bool,
+}
+
+impl TokenBudget {
+    pub fn new(limit: u64) -> Self {
+        Self { limit, used: 0, overrun_approved: false }
+    }
+
+    /// Budget from a --token-budget flag, else `[session] max_tokens`
+    pub fn from_config(config: &Config, limit: Option<u64>) -> Option<Self> {
+        limit
+            .or_else(|| config.session.as_ref().and_then(|s| s.max_tokens))
+            .filter(|limit| *limit > 0)
+            .map(Self::new)
+    }
+
+    /// Add spent tokens, reporting any threshold crossed along the way
+    pub fn record(&mut self, tokens: u64) -> BudgetEvent {
+        let before = self.fraction();
+        self.used += tokens;
+        let after = self.fraction();
+
+        if before < 1.0 && after >= 1.0 {
+            BudgetEvent::Exceeded
+        } else if before < WARN_AT && after >= WARN_AT {
+            BudgetEvent::Warning
+        } else {
+            BudgetEvent::Within
+        }
+    }
+
+    /// Over the limit and the user hasn't agreed to keep going
+    pub fn needs_confirmation(&self) -> bool {
+        self.used >= self.limit && !self.overrun_approved
+    }
+
+    pub fn approve_overrun(&mut self) {
+        self.overrun_approved = true;
+    }
+
+    pub fn fraction(&self) -> f64 {
+        self.used as f64 / self.limit as f64
+    }
+
+    pub fn is_near_limit(&self) -> bool {
+        self.fraction() >= WARN_AT
+    }
+
+    /// Compact form for prompts, e.g. "12.4k/50k tokens (25%)"
+    pub fn status(&self) -> String {
+        format!("{}/{} tokens ({:.0}%)", compact(self.used), compact(self.limit), self.fraction() * 100.0)
+    }
+}
+
+/// Rough token count for text the daemon didn't report usage for
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+fn compact(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0).replace(".0M", "M")
+    } else if n >= 1_000 {
+        format!("{:.1}k", n as f64 / 1_000.0).replace(".0k", "k")
+    } else {
+        n.to_string()
+    }
+}
@@ -0,0 +1,126 @@
+//! Opt-in cache of AI responses for identical one-shot prompts and declarations
+//!
+//! Entries live in `~/.port42/cache/responses/<sha256>.json` and hold the
+//! daemon's response data verbatim, so a hit replays exactly what was shown
+//! the first time.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::protocol::relations::Reference;
+
+const DEFAULT_TTL_HOURS: i64 = 24;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    created_at: DateTime<Utc>,
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    pub fn new(ttl_hours: Option<i64>) -> Self {
+        Self {
+            dir: cache_dir(),
+            ttl: Duration::hours(ttl_hours.unwrap_or(DEFAULT_TTL_HOURS)),
+        }
+    }
+
+    /// The cache if enabled in config, unless the caller opted out
+    pub fn from_config(config: &crate::config::Config, no_cache: bool) -> Option<Self> {
+        let settings = config.cache.as_ref()?;
+        if no_cache || !settings.enabled {
+            return None;
+        }
+        Some(Self::new(settings.ttl_hours))
+    }
+
+    /// Stable key over everything that shapes the response
+    pub fn key(parts: &[&str]) -> String {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update(part.as_bytes());
+            // Separator so ["ab", "c"] and ["a", "bc"] differ
+            hasher.update([0u8]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Key part for references, by what they point at now: a file's
+    /// contents can change under the same path
+    pub fn references_key(references: Option<&[Reference]>) -> String {
+        references.unwrap_or_default()
+            .iter()
+            .map(|r| {
+                let content = if r.ref_type == "file" {
+                    fs::read_to_string(&r.target).unwrap_or_default()
+                } else {
+                    r.context.clone().unwrap_or_default()
+                };
+                format!("{}:{}:{}", r.ref_type, r.target, Self::key(&[&content]))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn get(&self, key: &str) -> Option<serde_json::Value> {
+        let content = fs::read_to_string(self.entry_path(key)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        if Utc::now() - entry.created_at > self.ttl {
+            return None;
+        }
+        Some(entry.data)
+    }
+
+    pub fn put(&self, key: &str, data: &serde_json::Value) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let entry = CacheEntry { created_at: Utc::now(), data: data.clone() };
+        fs::write(self.entry_path(key), serde_json::to_string(&entry)?)
+            .context("Failed to write cache entry")
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+pub fn cache_dir() -> PathBuf {
+    crate::config::port42_dir().join("cache").join("responses")
+}
+
+/// Number of entries and total bytes on disk in a cache directory, e.g.
+/// [`cache_dir`] or the `url:` page cache
+pub fn stats(dir: &Path) -> (usize, u64) {
+    let Ok(entries) = fs::read_dir(dir) else { return (0, 0) };
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .fold((0, 0), |(count, bytes), m| (count + 1, bytes + m.len()))
+}
+
+/// Remove every entry in a cache directory, returning how many were deleted
+pub fn clear(dir: &Path) -> Result<usize> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let mut removed = 0;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
@@ -0,0 +1,71 @@
+//! Copying command output to the system clipboard
+//!
+//! Uses whichever platform tool is installed (pbcopy, wl-copy, xclip, xsel,
+//! clip.exe). Without one, falls back to the OSC 52 escape sequence, which
+//! most modern terminals (and tmux with `set-clipboard on`) honor, including
+//! over SSH.
+
+use anyhow::{Result, bail};
+use colored::*;
+use base64::{Engine as _, engine::general_purpose};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Clipboard tools in order of preference: (program, args)
+const CLIPBOARD_TOOLS: &[(&str, &[&str])] = &[
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("clip.exe", &[]),
+];
+
+/// Place `text` on the clipboard, returning how it got there
+pub fn copy(text: &str) -> Result<&'static str> {
+    for (program, args) in CLIPBOARD_TOOLS {
+        if pipe_to(program, args, text).is_ok() {
+            return Ok(program);
+        }
+    }
+    osc52(text)?;
+    Ok("terminal")
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    if !child.wait()?.success() {
+        bail!("{} exited with an error", program);
+    }
+    Ok(())
+}
+
+/// Ask the terminal itself to set the clipboard
+fn osc52(text: &str) -> Result<()> {
+    if !atty::is(atty::Stream::Stdout) {
+        bail!("No clipboard tool found (install xclip, xsel or wl-copy)");
+    }
+    let mut stdout = std::io::stdout();
+    write!(stdout, "{}", osc52_sequence(text))?;
+    stdout.flush()?;
+    Ok(())
+}
+
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", general_purpose::STANDARD.encode(text))
+}
+
+/// Copy and tell the user on stderr, so piped stdout stays clean
+pub fn copy_and_report(text: &str, what: &str) {
+    match copy(text) {
+        Ok(via) => eprintln!("{}", format!("📋 Copied {} to the clipboard (via {})", what, via).dimmed()),
+        Err(e) => eprintln!("{}", format!("⚠️  Could not copy {}: {}", what, e).yellow()),
+    }
+}
@@ -0,0 +1,272 @@
+//! Reading the daemon's log
+//!
+//! The daemon writes Go `log` lines, `2024/06/01 12:34:56 ⚠️ message`, with
+//! the level only hinted at by an emoji or a word like "error". JSON lines,
+//! as structured loggers (and `port42 --log-json`) write them, are read for
+//! their own time, level and message. Lines with neither, like the rest of a
+//! multi-line message, belong to the entry above them.
+//!
+//! Everything here is plain file reading, so `daemon logs` behaves the same
+//! on systems without `tail`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use colored::*;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+/// How often `follow` looks for new lines
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Most to least severe, so `level <= wanted` keeps what is at least as bad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl Level {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "error" | "err" | "fatal" | "panic" | "critical" => Some(Level::Error),
+            "warn" | "warning" => Some(Level::Warn),
+            "info" | "notice" => Some(Level::Info),
+            "debug" | "trace" => Some(Level::Debug),
+            _ => None,
+        }
+    }
+
+    /// Guess from a plain line's wording
+    fn infer(message: &str) -> Self {
+        if message.contains('❌') || message.contains('🚨') {
+            return Level::Error;
+        }
+        if message.contains('⚠') {
+            return Level::Warn;
+        }
+        if message.contains("[DEBUG]") || message.starts_with('🔍') {
+            return Level::Debug;
+        }
+        let lower = message.to_lowercase();
+        if ["error", "fatal", "panic"].iter().any(|word| lower.starts_with(word)) || lower.contains(" error") {
+            Level::Error
+        } else if lower.starts_with("warn") || lower.contains(" warning") {
+            Level::Warn
+        } else {
+            Level::Info
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub time: Option<DateTime<Local>>,
+    pub level: Level,
+    /// The line without its timestamp; for JSON, the message and any other fields
+    pub message: String,
+    /// Carries on the entry above rather than starting one
+    pub continued: bool,
+}
+
+impl LogLine {
+    /// Read one line, given the entry it may be continuing
+    pub fn parse(line: &str, previous: Option<&LogLine>) -> Self {
+        if let Some(parsed) = parse_json(line) {
+            return parsed;
+        }
+        if let Some((time, message)) = split_go_timestamp(line) {
+            return LogLine { time: Some(time), level: Level::infer(message), message: message.to_string(), continued: false };
+        }
+        LogLine {
+            time: previous.and_then(|p| p.time),
+            level: previous.map(|p| p.level).unwrap_or_else(|| Level::infer(line)),
+            message: line.to_string(),
+            continued: true,
+        }
+    }
+
+    /// For the terminal, colored by level
+    pub fn render(&self) -> String {
+        if self.continued {
+            return paint(self.level, &self.message);
+        }
+        let time = self.time
+            .map(|t| t.format("%Y/%m/%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| " ".repeat(19));
+        let label = match self.level {
+            Level::Error => self.level.label().red().bold(),
+            Level::Warn => self.level.label().yellow().bold(),
+            Level::Info => self.level.label().green(),
+            Level::Debug => self.level.label().dimmed(),
+        };
+        format!("{} {:<5} {}", time.dimmed(), label, paint(self.level, &self.message))
+    }
+}
+
+fn paint(level: Level, text: &str) -> String {
+    match level {
+        Level::Error => text.red().to_string(),
+        Level::Warn => text.yellow().to_string(),
+        Level::Info => text.to_string(),
+        Level::Debug => text.dimmed().to_string(),
+    }
+}
+
+/// `2024/06/01 12:34:56 rest`, optionally with microseconds, in local time
+fn split_go_timestamp(line: &str) -> Option<(DateTime<Local>, &str)> {
+    let stamp_end = line.char_indices().filter(|(_, c)| *c == ' ').nth(1).map(|(i, _)| i)?;
+    let stamp = &line[..stamp_end];
+    let naive = NaiveDateTime::parse_from_str(stamp, "%Y/%m/%d %H:%M:%S%.f").ok()?;
+    let time = Local.from_local_datetime(&naive).earliest()?;
+    Some((time, line[stamp_end..].trim_start()))
+}
+
+fn parse_json(line: &str) -> Option<LogLine> {
+    if !line.trim_start().starts_with('{') {
+        return None;
+    }
+    let mut object: Map<String, Value> = serde_json::from_str(line).ok()?;
+
+    let time = ["time", "timestamp", "ts"].iter()
+        .find_map(|key| object.remove(*key))
+        .and_then(|value| match value {
+            Value::String(s) => DateTime::parse_from_rfc3339(&s).ok().map(|t| t.with_timezone(&Local)),
+            Value::Number(n) => n.as_f64().and_then(|secs| Local.timestamp_opt(secs as i64, 0).single()),
+            _ => None,
+        });
+    let level_name = ["level", "lvl", "severity"].iter()
+        .find_map(|key| object.remove(*key))
+        .and_then(|value| value.as_str().map(str::to_string));
+
+    // tracing puts the message and its fields under "fields"
+    if let Some(Value::Object(fields)) = object.remove("fields") {
+        object.extend(fields);
+    }
+    let message = ["msg", "message"].iter()
+        .find_map(|key| object.remove(*key))
+        .map(|value| value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string()))
+        .unwrap_or_default();
+    let level = level_name.as_deref().and_then(Level::parse).unwrap_or_else(|| Level::infer(&message));
+
+    let mut text = message;
+    for (key, value) in object {
+        let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&format!("{}={}", key, value));
+    }
+    Some(LogLine { time, level, message: text, continued: false })
+}
+
+/// Which lines to show
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    /// At least this severe
+    pub level: Option<Level>,
+    pub grep: Option<Regex>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl LogFilter {
+    pub fn matches(&self, line: &LogLine) -> bool {
+        if self.level.is_some_and(|wanted| line.level > wanted) {
+            return false;
+        }
+        if let Some(since) = self.since {
+            // Lines with no time of their own or their entry's can't be placed, so they stay
+            if line.time.is_some_and(|time| time < since) {
+                return false;
+            }
+        }
+        self.grep.as_ref().is_none_or(|grep| grep.is_match(&line.message))
+    }
+}
+
+/// Turns raw lines into parsed ones, remembering the entry being continued
+#[derive(Debug, Default)]
+pub struct LogReader {
+    previous: Option<LogLine>,
+}
+
+impl LogReader {
+    pub fn read(&mut self, line: &str) -> LogLine {
+        let parsed = LogLine::parse(line, self.previous.as_ref());
+        self.previous = Some(parsed.clone());
+        parsed
+    }
+}
+
+/// The last `count` lines of `path` that pass `filter`
+pub fn tail(path: &Path, count: usize, filter: &LogFilter) -> Result<Vec<LogLine>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut file = BufReader::new(file);
+    let mut reader = LogReader::default();
+    let mut kept = VecDeque::with_capacity(count.min(4096));
+    let mut raw = Vec::new();
+    while file.read_until(b'\n', &mut raw)? > 0 {
+        let line = reader.read(String::from_utf8_lossy(&raw).trim_end_matches(['\n', '\r']));
+        raw.clear();
+        if filter.matches(&line) {
+            if kept.len() == count {
+                kept.pop_front();
+            }
+            if count > 0 {
+                kept.push_back(line);
+            }
+        }
+    }
+    Ok(kept.into())
+}
+
+/// Call `each` with every line added to `path` from now on, until it returns false.
+/// A file that shrinks was truncated, e.g. by a daemon restart, and is read again
+/// from the top.
+pub fn follow(path: &Path, filter: &LogFilter, mut each: impl FnMut(&LogLine) -> bool) -> Result<()> {
+    let mut file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut position = file.seek(SeekFrom::End(0))?;
+    let mut reader = LogReader::default();
+    let mut partial = Vec::new();
+
+    loop {
+        let length = std::fs::metadata(path).map(|m| m.len()).unwrap_or(position);
+        if length < position {
+            file = File::open(path)?;
+            position = 0;
+            partial.clear();
+        }
+
+        let mut chunk = Vec::new();
+        file.seek(SeekFrom::Start(position))?;
+        position += file.read_to_end(&mut chunk)? as u64;
+        partial.extend_from_slice(&chunk);
+
+        // Only whole lines; the rest waits for its newline
+        while let Some(end) = partial.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = partial.drain(..=end).collect();
+            let text = String::from_utf8_lossy(&raw);
+            let line = reader.read(text.trim_end_matches(['\n', '\r']));
+            if filter.matches(&line) && !each(&line) {
+                return Ok(());
+            }
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,119 @@
+//! The periodic summary produced by `port42 digest`
+//!
+//! A digest collects what happened in a time window (sessions held, tools
+//! crystallized, commands run) and renders it as Markdown. An agent may add
+//! a short narrative on top; without one the facts stand on their own.
+
+use chrono::{DateTime, Utc};
+
+use crate::protocol::hooks::CommandCount;
+
+#[derive(Debug, Clone)]
+pub struct DigestSession {
+    pub id: String,
+    pub agent: String,
+    pub messages: u64,
+    pub created_tool: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Digest {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub sessions: Vec<DigestSession>,
+    /// Names of tools created in the window
+    pub tools: Vec<String>,
+    /// None when the daemon has no command history to offer
+    pub top_commands: Option<Vec<CommandCount>>,
+    pub narrative: Option<String>,
+}
+
+impl Digest {
+    pub fn hours(&self) -> i64 {
+        (self.until - self.since).num_hours()
+    }
+
+    /// One line for notifications and subjects
+    pub fn headline(&self) -> String {
+        format!(
+            "{} sessions, {} tools created, {} commands run",
+            self.sessions.len(),
+            self.tools.len(),
+            self.top_commands.as_ref().map(|c| c.iter().map(|c| c.count).sum::<u64>()).unwrap_or(0),
+        )
+    }
+
+    /// The raw activity as plain bullet points, also what the agent is shown
+    pub fn facts(&self) -> String {
+        let mut lines = vec![format!("Window: last {} hours, ending {}", self.hours(), self.until.format("%Y-%m-%d %H:%M UTC"))];
+
+        lines.push(format!("Sessions held: {}", self.sessions.len()));
+        for session in &self.sessions {
+            let tool = if session.created_tool { ", created a tool" } else { "" };
+            lines.push(format!("- {} with {} ({} messages{})", session.id, session.agent, session.messages, tool));
+        }
+
+        lines.push(format!("Tools created: {}", self.tools.len()));
+        for tool in &self.tools {
+            lines.push(format!("- {}", tool));
+        }
+
+        if let Some(ref commands) = self.top_commands {
+            lines.push("Top commands run:".to_string());
+            for command in commands {
+                lines.push(format!("- {} ({}x)", command.command, command.count));
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "# Port 42 digest: {} to {}\n\n",
+            self.since.format("%Y-%m-%d %H:%M"),
+            self.until.format("%Y-%m-%d %H:%M UTC"),
+        );
+        if let Some(ref narrative) = self.narrative {
+            md.push_str(narrative.trim());
+            md.push_str("\n\n");
+        }
+
+        md.push_str(&format!("## Sessions ({})\n\n", self.sessions.len()));
+        if self.sessions.is_empty() {
+            md.push_str("None.\n");
+        }
+        for session in &self.sessions {
+            let tool = if session.created_tool { " · created a tool" } else { "" };
+            md.push_str(&format!("- `{}` with {}, {} messages{}\n", session.id, session.agent, session.messages, tool));
+        }
+
+        md.push_str(&format!("\n## Tools created ({})\n\n", self.tools.len()));
+        if self.tools.is_empty() {
+            md.push_str("None.\n");
+        }
+        for tool in &self.tools {
+            md.push_str(&format!("- `{}`\n", tool));
+        }
+
+        if let Some(ref commands) = self.top_commands {
+            md.push_str("\n## Top commands\n\n");
+            if commands.is_empty() {
+                md.push_str("None recorded.\n");
+            }
+            for command in commands {
+                md.push_str(&format!("- `{}` ({}x)\n", command.command, command.count));
+            }
+        }
+        md
+    }
+}
+
+/// Whether an RFC 3339 timestamp falls inside [since, until]
+pub fn within(timestamp: &str, since: DateTime<Utc>, until: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| {
+            let t = t.with_timezone(&Utc);
+            t >= since && t <= until
+        })
+        .unwrap_or(false)
+}
@@ -0,0 +1,250 @@
+//! Where the daemon is, and how to reach it
+//!
+//! Without a profile the daemon is on this machine and spoken to in plain
+//! TCP. A profile (see `[profiles]` in config.toml) can name another host
+//! and ask for TLS, checked against the system's roots, a `ca_file`, or a
+//! pinned certificate fingerprint. Either way the client gets a
+//! [`Transport`] that reads and writes like a `TcpStream`.
+//!
+//! OpenSSL is vendored, so its idea of where the roots live is wherever they
+//! were on the machine that built it. [`SystemRoots`] looks for them on this
+//! one instead.
+
+use anyhow::Result;
+use openssl::hash::{DigestBytes, MessageDigest};
+use openssl::ssl::{SslConnector, SslConnectorBuilder, SslMethod, SslStream, SslVerifyMode};
+use openssl::x509::store::X509StoreBuilder;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::common::errors::Port42Error;
+use crate::config::{ProfileConfig, TlsConfig};
+
+pub const LOCAL_HOST: &str = "127.0.0.1";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoint {
+    pub host: String,
+    pub tls: Option<TlsConfig>,
+    /// The profile this came from, for messages
+    pub profile: Option<String>,
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Self { host: LOCAL_HOST.to_string(), tls: None, profile: None }
+    }
+}
+
+impl Endpoint {
+    pub fn from_profile(name: &str, profile: &ProfileConfig) -> Self {
+        Self {
+            host: profile.host.clone().filter(|h| !h.is_empty()).unwrap_or_else(|| LOCAL_HOST.to_string()),
+            tls: profile.tls.clone(),
+            profile: Some(name.to_string()),
+        }
+    }
+
+    /// Whether the daemon is on this machine, where it can be started and stopped
+    pub fn is_local(&self) -> bool {
+        matches!(self.host.as_str(), "127.0.0.1" | "localhost" | "::1" | "[::1]")
+    }
+
+    /// `host:port`, as shown to people
+    pub fn describe(&self, port: u16) -> String {
+        let host = if self.host.contains(':') && !self.host.starts_with('[') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        match self.tls {
+            Some(_) => format!("{}:{} (TLS)", host, port),
+            None => format!("{}:{}", host, port),
+        }
+    }
+
+    /// Open a connection, with the TLS handshake done if the profile asks for it
+    pub fn connect(&self, port: u16, timeout: Duration) -> io::Result<Transport> {
+        self.connect_trusting(port, timeout, &SystemRoots::probe())
+    }
+
+    /// Like `connect`, with `roots` standing in for the system's
+    pub fn connect_trusting(&self, port: u16, timeout: Duration, roots: &SystemRoots) -> io::Result<Transport> {
+        let host = self.host.trim_start_matches('[').trim_end_matches(']');
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, format!("{} has no address", host));
+        let mut stream = None;
+        for addr in (host, port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, timeout) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                }
+                Err(e) => last_error = e,
+            }
+        }
+        let stream = stream.ok_or(last_error)?;
+        match self.tls {
+            Some(ref tls) => {
+                // The handshake gets the same patience as the connection
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                let tls_stream = handshake(host, tls, roots, stream.try_clone()?)
+                    .map_err(|e| io::Error::other(format!("{:#}", e)))?;
+                Ok(Transport::Tls { socket: stream, tls: Arc::new(Mutex::new(tls_stream)) })
+            }
+            None => Ok(Transport::Plain(stream)),
+        }
+    }
+}
+
+/// Where this machine keeps the certificates it trusts
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemRoots {
+    /// A bundle of PEM certificates
+    pub file: Option<PathBuf>,
+    /// Directories of PEM certificates named by subject hash, as `c_rehash` leaves them
+    pub dirs: Vec<PathBuf>,
+}
+
+impl SystemRoots {
+    /// The usual places for this platform, or `SSL_CERT_FILE` and `SSL_CERT_DIR` if they're set
+    pub fn probe() -> Self {
+        let found = openssl_probe::probe();
+        Self { file: found.cert_file, dirs: found.cert_dir }
+    }
+
+    fn trust(&self, builder: &mut SslConnectorBuilder) -> Result<()> {
+        // In place of the vendored defaults, not as well as them: they point
+        // into the build machine, and shadow directories added after them
+        builder.set_cert_store(X509StoreBuilder::new()?.build());
+        if let Some(ref file) = self.file {
+            builder.set_ca_file(file)
+                .map_err(|e| anyhow::anyhow!("Can't load the system's certificates from {}: {}", file.display(), e))?;
+        }
+        for dir in &self.dirs {
+            builder.load_verify_locations(None, Some(dir))
+                .map_err(|e| anyhow::anyhow!("Can't load the system's certificates from {}: {}", dir.display(), e))?;
+        }
+        Ok(())
+    }
+}
+
+fn handshake(host: &str, tls: &TlsConfig, roots: &SystemRoots, stream: TcpStream) -> Result<SslStream<TcpStream>> {
+    let mut builder = SslConnector::builder(SslMethod::tls_client())?;
+    match tls.ca_file {
+        Some(ref ca_file) => {
+            builder.set_ca_file(ca_file)
+                .map_err(|e| Port42Error::Usage(format!("Can't load certificates from {}: {}", ca_file.display(), e)))?;
+        }
+        None => roots.trust(&mut builder)?,
+    }
+
+    // A pinned certificate stands in for the chain and the name
+    let pin = match tls.pin_sha256 {
+        Some(ref pin) => Some(normalize_fingerprint(pin)
+            .ok_or_else(|| Port42Error::Usage(format!("'{}' isn't a SHA-256 fingerprint", pin)))?),
+        None => None,
+    };
+    let seen = Arc::new(Mutex::new(None));
+    if let Some(ref pin) = pin {
+        let pin = pin.clone();
+        let seen = Arc::clone(&seen);
+        builder.set_verify_callback(SslVerifyMode::PEER, move |_, context| {
+            if context.error_depth() != 0 {
+                return true;
+            }
+            let fingerprint = context.current_cert()
+                .and_then(|cert| cert.digest(MessageDigest::sha256()).ok())
+                .map(|digest: DigestBytes| hex(&digest));
+            let matches = fingerprint.as_deref() == Some(pin.as_str());
+            *seen.lock().unwrap_or_else(|e| e.into_inner()) = fingerprint;
+            matches
+        });
+    }
+
+    let mut config = builder.build().configure()?;
+    config.set_verify_hostname(pin.is_none());
+    let name = tls.server_name.as_deref().unwrap_or(host);
+    config.connect(name, stream).map_err(|e| {
+        let seen = seen.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        match (pin, seen) {
+            (Some(pin), Some(seen)) if pin != seen => Port42Error::Connection(format!(
+                "{}'s certificate has fingerprint {}, but the profile pins {}", host, seen, pin
+            )).into(),
+            _ => anyhow::anyhow!("TLS handshake with {} failed: {}", host, e),
+        }
+    })
+}
+
+/// Lowercase hex without separators, if `fingerprint` is a SHA-256 digest
+pub fn normalize_fingerprint(fingerprint: &str) -> Option<String> {
+    let digits: String = fingerprint.trim().chars().filter(|c| *c != ':').collect::<String>().to_ascii_lowercase();
+    (digits.len() == 64 && digits.chars().all(|c| c.is_ascii_hexdigit())).then_some(digits)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A connection to the daemon. Clones share it, so one can read while
+/// another writes, as with `TcpStream::try_clone`.
+pub enum Transport {
+    Plain(TcpStream),
+    Tls {
+        /// The socket under the TLS session, for timeouts
+        socket: TcpStream,
+        tls: Arc<Mutex<SslStream<TcpStream>>>,
+    },
+}
+
+impl Transport {
+    pub fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Transport::Plain(stream) => Ok(Transport::Plain(stream.try_clone()?)),
+            Transport::Tls { socket, tls } => Ok(Transport::Tls { socket: socket.try_clone()?, tls: Arc::clone(tls) }),
+        }
+    }
+
+    fn socket(&self) -> &TcpStream {
+        match self {
+            Transport::Plain(stream) => stream,
+            Transport::Tls { socket, .. } => socket,
+        }
+    }
+
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.socket().set_read_timeout(timeout)
+    }
+
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.socket().set_write_timeout(timeout)
+    }
+}
+
+impl Read for Transport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Transport::Plain(stream) => stream.read(buf),
+            Transport::Tls { tls, .. } => tls.lock().unwrap_or_else(|e| e.into_inner()).read(buf),
+        }
+    }
+}
+
+impl Write for Transport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Transport::Plain(stream) => stream.write(buf),
+            Transport::Tls { tls, .. } => tls.lock().unwrap_or_else(|e| e.into_inner()).write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Transport::Plain(stream) => stream.flush(),
+            Transport::Tls { tls, .. } => tls.lock().unwrap_or_else(|e| e.into_inner()).flush(),
+        }
+    }
+}
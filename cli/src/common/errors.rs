@@ -1,20 +1,190 @@
+use serde::Serialize;
+use std::sync::OnceLock;
 use thiserror::Error;
 
+/// Process exit codes, one per kind of failure, so scripts can branch on them
+pub mod exit_code {
+    pub const FAILURE: i32 = 1;
+    pub const USAGE: i32 = 2;
+    pub const CONNECTION: i32 = 3;
+    pub const API_KEY: i32 = 4;
+    pub const NOT_FOUND: i32 = 5;
+    pub const PROVIDER: i32 = 6;
+    pub const TIMEOUT: i32 = 7;
+    pub const DAEMON: i32 = 8;
+    pub const INCOMPATIBLE: i32 = 9;
+}
+
 #[derive(Error, Debug)]
 pub enum Port42Error {
     #[error("Daemon error: {0}")]
     Daemon(String),
-    
+
     #[error("Claude API error: {0}")]
     ClaudeApi(String),
-    
+
+    /// Upstream failure from a non-Anthropic provider: (provider, message)
+    #[error("{0} API error: {1}")]
+    ProviderApi(String, String),
+
     #[error("API key error: {0}")]
     ApiKey(String),
-    
+
     #[error("Network error: {0}")]
     Network(String),
-    
+
     #[error("External service error: {0}")]
     ExternalService(String),
+
+    /// The daemon could not be reached at all
+    #[error("{0}")]
+    Connection(String),
+
+    /// A request went unanswered within its timeout
+    #[error("{0}")]
+    Timeout(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    /// Arguments that parsed but make no sense together
+    #[error("{0}")]
+    Usage(String),
+
+    /// The CLI and daemon speak protocol revisions neither can bridge
+    #[error("{0}")]
+    Incompatible(String),
+
+    /// The daemon has no handler for this request type
+    #[error("The daemon does not support {0} requests")]
+    Unsupported(String),
+}
+
+impl Port42Error {
+    /// Classify a daemon error string by the source prefix it carries
+    pub fn from_daemon(error: &str) -> Self {
+        let prefixed = |prefix: &str| error.strip_prefix(prefix).map(|msg| msg.trim().to_string());
+        if let Some(msg) = prefixed("CLAUDE_API_ERROR:") {
+            Port42Error::ClaudeApi(msg)
+        } else if let Some(msg) = prefixed("OPENAI_API_ERROR:") {
+            Port42Error::ProviderApi("openai".to_string(), msg)
+        } else if let Some(msg) = prefixed("GOOGLE_API_ERROR:") {
+            Port42Error::ProviderApi("google".to_string(), msg)
+        } else if let Some(msg) = prefixed("API_KEY_ERROR:") {
+            Port42Error::ApiKey(msg)
+        } else if let Some(msg) = prefixed("NETWORK_ERROR:") {
+            Port42Error::Network(msg)
+        } else if let Some(msg) = prefixed("AI_CONNECTION_ERROR:") {
+            Port42Error::ExternalService(msg)
+        } else if let Some(request_type) = prefixed("Unknown request type:") {
+            Port42Error::Unsupported(request_type)
+        } else if is_not_found(error) {
+            Port42Error::NotFound(error.to_string())
+        } else {
+            // Fallback to daemon error for unclassified errors
+            Port42Error::Daemon(error.to_string())
+        }
+    }
+
+    /// A failed read of a path. Not-found errors (or none at all) get the
+    /// friendly `headline` and `hint`; timeouts, database errors and the
+    /// rest keep the daemon's own classification.
+    pub fn from_lookup(error: Option<&str>, headline: &str, hint: &str) -> Self {
+        match error.map(Self::from_daemon) {
+            None | Some(Port42Error::NotFound(_)) => {
+                Port42Error::NotFound(crate::help_text::format_error_with_suggestion(headline, hint))
+            }
+            Some(other) => other,
+        }
+    }
+
+    /// Stable name for the kind of failure, as reported in error JSON
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Port42Error::Daemon(_) => "daemon",
+            Port42Error::ClaudeApi(_) | Port42Error::ProviderApi(_, _) | Port42Error::ExternalService(_) => "provider",
+            Port42Error::ApiKey(_) => "api_key",
+            Port42Error::Network(_) => "network",
+            Port42Error::Connection(_) => "connection",
+            Port42Error::Timeout(_) => "timeout",
+            Port42Error::NotFound(_) => "not_found",
+            Port42Error::Usage(_) => "usage",
+            Port42Error::Incompatible(_) => "incompatible",
+            Port42Error::Unsupported(_) => "unsupported",
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Port42Error::Daemon(_) => exit_code::DAEMON,
+            Port42Error::ClaudeApi(_) | Port42Error::ProviderApi(_, _) | Port42Error::ExternalService(_) | Port42Error::Network(_) => exit_code::PROVIDER,
+            Port42Error::ApiKey(_) => exit_code::API_KEY,
+            Port42Error::Connection(_) => exit_code::CONNECTION,
+            Port42Error::Timeout(_) => exit_code::TIMEOUT,
+            Port42Error::NotFound(_) => exit_code::NOT_FOUND,
+            Port42Error::Usage(_) => exit_code::USAGE,
+            Port42Error::Incompatible(_) | Port42Error::Unsupported(_) => exit_code::INCOMPATIBLE,
+        }
+    }
+}
+
+/// Whether `error` is the daemon refusing a request type it doesn't have,
+/// for commands that can manage without it
+pub fn is_unsupported(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<Port42Error>(), Some(Port42Error::Unsupported(_)))
+}
+
+fn is_not_found(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    lower.contains("not found") || lower.contains("no such") || lower.contains("does not exist")
 }
 
+/// What `--json` callers get on stderr when a command fails
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ErrorReport {
+    pub code: i32,
+    pub kind: String,
+    pub message: String,
+}
+
+impl ErrorReport {
+    /// The innermost typed error decides the kind; untyped ones fall back to
+    /// what the underlying I/O error or message says
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        let message = format!("{:#}", err);
+        if let Some(typed) = err.chain().find_map(|e| e.downcast_ref::<Port42Error>()) {
+            return Self { code: typed.exit_code(), kind: typed.kind().to_string(), message };
+        }
+        let (code, kind) = if let Some(io) = err.chain().find_map(|e| e.downcast_ref::<std::io::Error>()) {
+            match io.kind() {
+                std::io::ErrorKind::NotFound => (exit_code::NOT_FOUND, "not_found"),
+                std::io::ErrorKind::ConnectionRefused | std::io::ErrorKind::ConnectionReset => (exit_code::CONNECTION, "connection"),
+                std::io::ErrorKind::TimedOut => (exit_code::TIMEOUT, "timeout"),
+                _ => (exit_code::FAILURE, "error"),
+            }
+        } else if is_not_found(&message) {
+            (exit_code::NOT_FOUND, "not_found")
+        } else {
+            (exit_code::FAILURE, "error")
+        };
+        Self { code, kind: kind.to_string(), message }
+    }
+}
+
+static JSON_ERRORS: OnceLock<bool> = OnceLock::new();
+
+/// Report failures as JSON rather than prose; set once from `--json`/`--output json`
+pub fn set_json(enabled: bool) {
+    let _ = JSON_ERRORS.set(enabled);
+}
+
+/// Print a command's failure the way the caller asked for and return its exit code
+pub fn report(err: &anyhow::Error) -> i32 {
+    let report = ErrorReport::from_error(err);
+    if JSON_ERRORS.get().copied().unwrap_or(false) {
+        eprintln!("{}", serde_json::json!({ "error": report }));
+    } else {
+        eprintln!("Error: {:?}", err);
+    }
+    report.code
+}
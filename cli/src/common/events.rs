@@ -0,0 +1,79 @@
+//! Following daemon events as they happen
+//!
+//! Daemons that know `subscribe` push events over the connection. Older ones
+//! refuse it; for those the context snapshot is fetched every `poll` and
+//! compared with the last one, which sees fewer kinds of event (no rule
+//! firings) and only sees them a little late. Either way the daemon going
+//! away is waited out rather than ending the follow.
+
+use anyhow::Result;
+use std::time::Duration;
+
+use crate::client::DaemonClient;
+use crate::common::{errors::is_unsupported, generate_id};
+use crate::context::{ContextData, suggestions};
+use crate::protocol::RequestBuilder;
+use crate::protocol::events::{DaemonEvent, EventKind, SubscribeRequest};
+
+/// Pass events of `types` (every kind when empty) to `on_event` until it
+/// returns false
+pub fn follow(port: u16, types: &[EventKind], poll: Duration, on_event: &mut dyn FnMut(&DaemonEvent) -> bool) -> Result<()> {
+    let wanted = |event: &DaemonEvent| types.is_empty() || types.contains(&event.kind);
+    let mut client = DaemonClient::new(port);
+    let mut down = false;
+    loop {
+        let request = SubscribeRequest { types: types.to_vec() }.build_request(generate_id())?;
+        let result = client.subscribe(request, &mut |event| {
+            down = false;
+            !wanted(&event) || on_event(&event)
+        });
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if is_unsupported(&e) => {
+                eprintln!("ℹ️  This daemon can't push events; checking for them every {:.1}s instead", poll.as_secs_f32());
+                return poll_snapshots(&mut client, &wanted, poll, on_event);
+            }
+            Err(e) => {
+                if !down {
+                    eprintln!("⚠️  {:#} - reconnecting", e);
+                    down = true;
+                }
+            }
+        }
+        std::thread::sleep(poll);
+    }
+}
+
+/// Derive events from successive snapshots; the first only sets the baseline
+fn poll_snapshots(
+    client: &mut DaemonClient,
+    wanted: &dyn Fn(&DaemonEvent) -> bool,
+    poll: Duration,
+    on_event: &mut dyn FnMut(&DaemonEvent) -> bool,
+) -> Result<()> {
+    let mut previous: Option<ContextData> = None;
+    let mut down = false;
+    loop {
+        match suggestions::fetch(client) {
+            Ok(current) => {
+                down = false;
+                if let Some(ref previous) = previous {
+                    for event in current.events_since(previous).iter().filter(|e| wanted(e)) {
+                        if !on_event(event) {
+                            return Ok(());
+                        }
+                    }
+                }
+                previous = Some(current);
+            }
+            Err(e) => {
+                if !down {
+                    eprintln!("⚠️  {:#} - still watching", e);
+                    down = true;
+                }
+                client.disconnect();
+            }
+        }
+        std::thread::sleep(poll);
+    }
+}
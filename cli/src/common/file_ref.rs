@@ -0,0 +1,222 @@
+//! `file:` references to directories and globs
+//!
+//! `file:./src` and `file:./src/**/*.rs` expand into one reference per file,
+//! read here and sent as context. `*` and `?` match within a path segment,
+//! `**` matches any number of segments. Inside a git work tree the files
+//! are those git would track, so `.gitignore` is honoured exactly; elsewhere
+//! the directory is walked, skipping hidden entries and whatever the
+//! top-level `.gitignore` names.
+//!
+//! Each file is cut to `[refs] file_max_bytes` and files stop being added
+//! once `[refs] files_max_bytes` is reached. Binary files are skipped. A
+//! plain file path is left for the daemon to read, as before.
+
+use anyhow::{Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::common::references::truncate_context;
+use crate::config::Config;
+
+/// Bytes of any one expanded file sent, unless the config says otherwise
+pub const DEFAULT_FILE_MAX_BYTES: usize = 32 * 1024;
+/// Bytes of all the files of one expanded reference together
+pub const DEFAULT_FILES_MAX_BYTES: usize = 200 * 1024;
+
+/// Bytes looked at to tell text from binary
+const SNIFF_BYTES: usize = 8000;
+
+/// Whether a `file:` target names more than one file
+pub fn is_expandable(target: &str) -> bool {
+    has_glob(target) || Path::new(target).is_dir()
+}
+
+fn has_glob(path: &str) -> bool {
+    path.contains(['*', '?'])
+}
+
+/// A file that made it in; `size` is its length before any cut
+#[derive(Debug, Clone)]
+pub struct IncludedFile {
+    pub path: String,
+    pub content: String,
+    pub size: usize,
+    pub truncated: bool,
+}
+
+/// A file that matched but was left out, and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Expansion {
+    pub included: Vec<IncludedFile>,
+    pub skipped: Vec<SkippedFile>,
+}
+
+impl Expansion {
+    /// Bytes that will be sent
+    pub fn bytes(&self) -> usize {
+        self.included.iter().map(|f| f.content.len()).sum()
+    }
+}
+
+/// Size limits for one expanded reference
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    pub per_file: usize,
+    pub total: usize,
+}
+
+impl Budget {
+    pub fn from_config(config: &Config) -> Self {
+        let refs = config.refs.clone().unwrap_or_default();
+        Budget {
+            per_file: refs.file_max_bytes.unwrap_or(DEFAULT_FILE_MAX_BYTES),
+            total: refs.files_max_bytes.unwrap_or(DEFAULT_FILES_MAX_BYTES),
+        }
+    }
+}
+
+/// Expand a directory or glob into the files it names, read and budgeted
+pub fn expand(target: &str, budget: Budget) -> Result<Expansion> {
+    let paths = matching_files(target)?;
+    if paths.is_empty() {
+        bail!("file:{} matched no files", target);
+    }
+
+    let mut expansion = Expansion::default();
+    let mut total = 0;
+    for path in paths {
+        let shown = path.display().to_string();
+        if total >= budget.total {
+            expansion.skipped.push(SkippedFile { path: shown, reason: "over the total budget".to_string() });
+            continue;
+        }
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                expansion.skipped.push(SkippedFile { path: shown, reason: e.to_string() });
+                continue;
+            }
+        };
+        if bytes[..bytes.len().min(SNIFF_BYTES)].contains(&0) {
+            expansion.skipped.push(SkippedFile { path: shown, reason: "binary".to_string() });
+            continue;
+        }
+        let size = bytes.len();
+        let cap = budget.per_file.min(budget.total - total);
+        let (content, truncated) = truncate_context(String::from_utf8_lossy(&bytes).into_owned(), cap);
+        total += content.len();
+        expansion.included.push(IncludedFile { path: shown, content, size, truncated });
+    }
+    Ok(expansion)
+}
+
+/// Files under a directory, or matching a glob, in path order
+pub fn matching_files(target: &str) -> Result<Vec<PathBuf>> {
+    let target = target.trim_end_matches('/');
+    let (base, pattern) = if has_glob(target) {
+        // Walk from the deepest directory named before the first wildcard
+        let segments: Vec<&str> = target.split('/').collect();
+        let fixed = segments.iter().take_while(|s| !has_glob(s)).count();
+        let base = if fixed == 0 { ".".to_string() } else { segments[..fixed].join("/") };
+        (base, Some(target.to_string()))
+    } else {
+        (target.to_string(), None)
+    };
+    let base = if base.is_empty() { "/".to_string() } else { base };
+    if !Path::new(&base).is_dir() {
+        bail!("file:{}: {} is not a directory", target, base);
+    }
+
+    let mut files: Vec<PathBuf> = list_files(Path::new(&base))?
+        .into_iter()
+        .map(|relative| Path::new(&base).join(relative))
+        .filter(|path| match pattern {
+            Some(ref pattern) => glob_match(pattern, &path.to_string_lossy()),
+            None => true,
+        })
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Paths of the files under `dir`, relative to it
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let tracked = Command::new("git")
+        .arg("-C").arg(dir)
+        .args(["ls-files", "-z", "--cached", "--others", "--exclude-standard"])
+        .output();
+    if let Ok(output) = tracked {
+        if output.status.success() {
+            return Ok(output.stdout
+                .split(|b| *b == 0)
+                .filter(|p| !p.is_empty())
+                .map(|p| PathBuf::from(String::from_utf8_lossy(p).into_owned()))
+                .collect());
+        }
+    }
+
+    let ignored: Vec<String> = fs::read_to_string(dir.join(".gitignore"))
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+        .map(|line| line.trim_start_matches('/').trim_end_matches('/').to_string())
+        .collect();
+    let mut files = Vec::new();
+    walk(dir, Path::new(""), &ignored, &mut files)?;
+    Ok(files)
+}
+
+fn walk(root: &Path, relative: &Path, ignored: &[String], files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(root.join(relative))? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = relative.join(&name);
+        let shown = path.to_string_lossy();
+        if name.starts_with('.') || ignored.iter().any(|pattern| glob_match(pattern, &name) || glob_match(pattern, &shown)) {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            walk(root, &path, ignored, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Match a path against a glob, segment by segment: `*` and `?` stay within
+/// a segment, `**` spans any number of them
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty() && *s != ".").collect();
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty() && *s != ".").collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((name, remaining)) => match_segment(segment.as_bytes(), name.as_bytes()) && match_segments(rest, remaining),
+            None => false,
+        },
+    }
+}
+
+fn match_segment(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| match_segment(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && match_segment(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && match_segment(rest, &name[1..]),
+    }
+}
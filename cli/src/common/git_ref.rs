@@ -0,0 +1,87 @@
+//! `git:` references
+//!
+//! The daemon can't see the user's working tree, so git references are
+//! resolved here by shelling out to git and sent along as context:
+//!
+//! - `git:diff`      uncommitted changes (staged and unstaged) against HEAD
+//! - `git:staged`    only what is staged
+//! - `git:A..B`      log with patches for a commit range
+//! - `git:<rev>`     a single commit, as `git show` prints it
+
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::process::Command;
+
+use crate::common::references::truncate_context;
+use crate::help_text;
+
+/// Diffs beyond this are truncated before being sent to the daemon
+pub const MAX_GIT_BYTES: usize = 100 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GitSpec {
+    Diff,
+    Staged,
+    Range(String),
+    Commit(String),
+}
+
+impl GitSpec {
+    pub fn parse(target: &str) -> Result<Self> {
+        let target = target.trim();
+        if target.is_empty() {
+            bail!("git: needs a target, e.g. git:diff, git:staged or git:HEAD~3..HEAD");
+        }
+        // Anything git would read as an option is refused outright
+        if target.starts_with('-') || target.chars().any(char::is_whitespace) {
+            bail!("'{}' is not a revision or range", target);
+        }
+        Ok(match target {
+            "diff" => GitSpec::Diff,
+            "staged" => GitSpec::Staged,
+            t if t.contains("..") => GitSpec::Range(t.to_string()),
+            t => GitSpec::Commit(t.to_string()),
+        })
+    }
+
+    /// Arguments for the git invocation that produces this reference
+    pub fn args(&self) -> Vec<&str> {
+        match self {
+            GitSpec::Diff => vec!["diff", "--no-color", "HEAD"],
+            GitSpec::Staged => vec!["diff", "--no-color", "--cached"],
+            GitSpec::Range(range) => vec!["log", "--no-color", "--patch", "--stat", range, "--"],
+            GitSpec::Commit(rev) => vec!["show", "--no-color", "--stat", "--patch", rev, "--"],
+        }
+    }
+}
+
+/// Run git for a `git:` target and return what it printed, capped at
+/// [`MAX_GIT_BYTES`]
+pub fn resolve(target: &str) -> Result<String> {
+    let spec = GitSpec::parse(target)?;
+    let inside = Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+        .context("Failed to run git. Is it installed?")?;
+    if !inside.status.success() {
+        bail!(help_text::ERR_GIT_REF_NOT_REPO);
+    }
+
+    let output = Command::new("git")
+        .args(spec.args())
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!("git {} failed: {}", spec.args().join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    let total = output.stdout.len();
+    let (content, truncated) = truncate_context(String::from_utf8_lossy(&output.stdout).into_owned(), MAX_GIT_BYTES);
+    if truncated {
+        eprintln!("{}", help_text::format_git_ref_truncated(target, total, MAX_GIT_BYTES).yellow());
+    }
+    if content.trim().is_empty() {
+        eprintln!("{}", help_text::format_git_ref_empty(target).yellow());
+    }
+    Ok(content)
+}
@@ -0,0 +1,51 @@
+//! Provider API keys kept in the OS credential store
+//!
+//! macOS Keychain, the freedesktop Secret Service on Linux and the Windows
+//! Credential Manager all sit behind the `keyring` crate. Entries are stored
+//! under the `port42` service with the provider name as the account.
+
+use anyhow::{Context, Result};
+use keyring::Entry;
+
+const SERVICE: &str = "port42";
+
+fn entry(provider: &str) -> Result<Entry> {
+    Entry::new(SERVICE, provider)
+        .with_context(|| format!("Failed to open keychain entry for '{}'", provider))
+}
+
+/// Store (or replace) the API key for a provider
+pub fn set_key(provider: &str, secret: &str) -> Result<()> {
+    entry(provider)?
+        .set_password(secret)
+        .with_context(|| format!("Failed to store key for '{}' in the keychain", provider))
+}
+
+/// Fetch the API key for a provider, `None` if nothing is stored
+pub fn get_key(provider: &str) -> Result<Option<String>> {
+    match entry(provider)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read key for '{}' from the keychain", provider)),
+    }
+}
+
+/// Delete the stored key for a provider; returns false if there was none
+pub fn remove_key(provider: &str) -> Result<bool> {
+    match entry(provider)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("Failed to remove key for '{}' from the keychain", provider)),
+    }
+}
+
+/// Show just enough of a key to recognise it
+pub fn mask_key(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
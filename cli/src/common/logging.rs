@@ -0,0 +1,129 @@
+//! Diagnostics for the CLI itself
+//!
+//! Everything the CLI logs goes through `tracing`. What is shown comes from
+//! `--log-level`, then `PORT42_LOG` (full filter directives, e.g.
+//! `warn,port42::client=trace`), then the older switches: PORT42_DEBUG means
+//! debug, PORT42_VERBOSE adds each request and response, PORT42_DEBUG_KEYS
+//! adds key events in the interactive session. Logs go to stderr unless
+//! `--log-file` names a file, as text or, with `--log-json`, one JSON object
+//! per line.
+//!
+//! `--trace-requests <file>` separately appends every request and response
+//! to a file as JSON lines, whatever the log level.
+
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing_subscriber::filter::{EnvFilter, LevelFilter, Targets};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, Layer, Registry};
+
+/// Target of the request/response records `--trace-requests` captures
+pub const REQUESTS_TARGET: &str = "port42::requests";
+
+/// Target of interactive key events
+pub const KEYS_TARGET: &str = "port42::keys";
+
+const DEFAULT_DIRECTIVES: &str = "warn";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn directive(self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct LogOptions {
+    pub level: Option<LogLevel>,
+    pub file: Option<PathBuf>,
+    pub json: bool,
+    pub trace_requests: Option<PathBuf>,
+}
+
+/// The filter directives in effect, with `env` looking up variables
+pub fn directives(level: Option<LogLevel>, env: impl Fn(&str) -> Option<String>) -> String {
+    if let Some(level) = level {
+        return level.directive().to_string();
+    }
+    if let Some(directives) = env("PORT42_LOG").filter(|d| !d.trim().is_empty()) {
+        return directives;
+    }
+    let mut directives = vec![if env("PORT42_DEBUG").is_some() { "debug" } else { DEFAULT_DIRECTIVES }.to_string()];
+    if env("PORT42_VERBOSE").is_some() {
+        directives.push(format!("{}=trace", REQUESTS_TARGET));
+    }
+    if env("PORT42_DEBUG_KEYS").is_some() {
+        directives.push(format!("{}=trace", KEYS_TARGET));
+    }
+    directives.join(",")
+}
+
+/// Install the global subscriber; call once, before anything logs
+pub fn init(options: &LogOptions) -> Result<()> {
+    let directives = directives(options.level, |name| std::env::var(name).ok());
+    let filter = EnvFilter::try_new(&directives)
+        .with_context(|| format!("Invalid log filter '{}'", directives))?;
+
+    let main = match options.file {
+        Some(ref path) => output_layer(Mutex::new(append(path)?), false, options.json),
+        None => output_layer(std::io::stderr, atty::is(atty::Stream::Stderr), options.json),
+    }
+    .with_filter(filter);
+
+    let requests = match options.trace_requests {
+        Some(ref path) => Some(
+            fmt::layer()
+                .json()
+                .with_current_span(false)
+                .with_writer(Mutex::new(append(path)?))
+                .with_filter(Targets::new().with_target(REQUESTS_TARGET, LevelFilter::TRACE)),
+        ),
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(main)
+        .with(requests)
+        .try_init()
+        .context("Logging was already set up")
+}
+
+fn output_layer<W>(writer: W, ansi: bool, json: bool) -> Box<dyn Layer<Registry> + Send + Sync>
+where
+    W: for<'a> fmt::MakeWriter<'a> + Send + Sync + 'static,
+{
+    if json {
+        fmt::layer().json().with_current_span(false).with_writer(writer).boxed()
+    } else {
+        fmt::layer().with_ansi(ansi).with_writer(writer).boxed()
+    }
+}
+
+fn append(path: &Path) -> Result<File> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Can't open log file {}", path.display()))
+}
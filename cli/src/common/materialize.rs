@@ -0,0 +1,111 @@
+//! Local copies of VFS objects
+//!
+//! `port42 materialize` writes objects to ordinary files so they can be
+//! edited with any editor. Each copy remembers the VFS path it came from and
+//! the SHA-256 of the content both sides last agreed on, in
+//! `~/.port42/state/materialized.json`. Comparing the local file and the VFS
+//! object against that base tells an edit from a change made underneath,
+//! and both from a conflict.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::common::archive::sha256_hex;
+
+/// Suffix of the file holding the VFS side of a conflict
+pub const CONFLICT_SUFFIX: &str = "vfs";
+
+/// One local file and the object it mirrors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub vfs_path: String,
+    /// SHA-256 of the content at the last sync
+    pub sha256: String,
+    pub synced: DateTime<Utc>,
+}
+
+/// Every materialized file, keyed by its absolute local path
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Links {
+    #[serde(default)]
+    pub files: BTreeMap<PathBuf, Link>,
+}
+
+impl Links {
+    pub fn path() -> PathBuf {
+        crate::config::port42_dir().join("state").join("materialized.json")
+    }
+
+    /// The recorded links; a missing or unreadable file means none yet
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Record that `file` and `vfs_path` now both hold `content`
+    pub fn record(&mut self, file: &Path, vfs_path: &str, content: &[u8]) {
+        self.files.insert(file.to_path_buf(), Link {
+            vfs_path: vfs_path.to_string(),
+            sha256: sha256_hex(content),
+            synced: Utc::now(),
+        });
+    }
+}
+
+/// How a local copy and its object have moved since they last agreed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Unchanged,
+    /// Edited locally: send it to the VFS
+    Local,
+    /// Changed in the VFS: bring it down
+    Remote,
+    /// Both sides now hold the same new content
+    Converged,
+    /// Both sides changed, differently
+    Conflict,
+}
+
+/// Compare the hashes of each side against the last agreed one
+pub fn compare(base: &str, local: &str, remote: &str) -> Change {
+    match (local == base, remote == base) {
+        (true, true) => Change::Unchanged,
+        (false, true) => Change::Local,
+        (true, false) => Change::Remote,
+        (false, false) if local == remote => Change::Converged,
+        (false, false) => Change::Conflict,
+    }
+}
+
+/// `notes.md` -> `notes.md.vfs`, next to the file
+pub fn conflict_path(file: &Path) -> PathBuf {
+    let mut name = file.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", CONFLICT_SUFFIX));
+    file.with_file_name(name)
+}
+
+/// Where a VFS object lands locally. `root` is the path that was asked for;
+/// objects under a directory keep their layout beneath `dest`
+pub fn local_path(root: &str, vfs_path: &str, dest: &Path) -> PathBuf {
+    let relative = vfs_path.strip_prefix(root.trim_end_matches('/')).unwrap_or(vfs_path).trim_start_matches('/');
+    if relative.is_empty() {
+        dest.to_path_buf()
+    } else {
+        relative.split('/').filter(|s| !s.is_empty() && *s != "..").fold(dest.to_path_buf(), |path, s| path.join(s))
+    }
+}
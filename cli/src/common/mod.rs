@@ -1,6 +1,40 @@
 pub mod errors;
 pub mod utils;
 pub mod references;
+pub mod providers;
+pub mod keychain;
+pub mod pricing;
+pub mod cache;
+pub mod template;
+pub mod semantic;
+pub mod rate_limit;
+pub mod budget;
+pub mod site;
+pub mod notify;
+pub mod clipboard;
+pub mod digest;
+pub mod archive;
+pub mod tool_manifest;
+pub mod transcript;
+pub mod git_ref;
+pub mod url_ref;
+pub mod file_ref;
+pub mod approval;
+pub mod aliases;
+pub mod shell_history;
+pub mod shell_input;
+pub mod materialize;
+pub mod workspace;
+pub mod events;
+pub mod webhook;
+pub mod logging;
+pub mod recording;
+pub mod platform;
+pub mod service;
+pub mod daemon_log;
+pub mod upgrade;
+pub mod outbox;
+pub mod endpoint;
 
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -21,4 +55,4 @@ pub fn generate_session_id() -> String {
         .unwrap()
         .as_millis();
     format!("cli-session-{}", timestamp)
-}
\ No newline at end of file
+}
@@ -0,0 +1,210 @@
+//! Event notifications posted to Slack, Discord or any webhook
+//!
+//! Sinks are configured under `[[notify.sinks]]` in config.toml. Each event
+//! renders a short message from its template (overridable under
+//! `[notify.templates]`) and is posted with `curl`, so HTTPS works without
+//! pulling a TLS stack into the CLI. Failures never interrupt the command
+//! that raised the event.
+//!
+//! Hooks under `[[notify.hooks]]` are local commands instead, run by
+//! `port42 notify watch` as daemon events happen.
+
+use anyhow::{Result, bail};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::common::{platform, template};
+use crate::config::{Config, NotifyHook, NotifySink};
+use crate::protocol::events::DaemonEvent;
+
+pub const SINK_KINDS: &[&str] = &["slack", "discord", "webhook"];
+
+const POST_TIMEOUT_SECS: &str = "10";
+
+pub const DEFAULT_LONG_SESSION_MINUTES: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotifyEvent {
+    ToolCrystallized,
+    SessionCompleted,
+    DaemonCrashed,
+    Digest,
+    Test,
+}
+
+impl NotifyEvent {
+    pub const ALL: &'static [NotifyEvent] = &[
+        NotifyEvent::ToolCrystallized,
+        NotifyEvent::SessionCompleted,
+        NotifyEvent::DaemonCrashed,
+        NotifyEvent::Digest,
+        NotifyEvent::Test,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NotifyEvent::ToolCrystallized => "tool_crystallized",
+            NotifyEvent::SessionCompleted => "session_completed",
+            NotifyEvent::DaemonCrashed => "daemon_crashed",
+            NotifyEvent::Digest => "digest",
+            NotifyEvent::Test => "test",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|e| e.name() == name)
+    }
+
+    pub fn default_template(self) -> &'static str {
+        match self {
+            NotifyEvent::ToolCrystallized => "🛠️ Tool {{name}} crystallized: {{description}}",
+            NotifyEvent::SessionCompleted => "🐬 {{agent}} session {{session}} ended after {{minutes}} minutes ({{tools}} tools created)",
+            NotifyEvent::DaemonCrashed => "🚨 Port 42 daemon stopped unexpectedly: {{reason}}",
+            NotifyEvent::Digest => "📰 Port 42 digest for the last {{hours}} hours: {{headline}}\n\n{{summary}}",
+            NotifyEvent::Test => "👋 Test notification from Port 42 on {{host}}",
+        }
+    }
+}
+
+pub struct Notifier {
+    sinks: Vec<NotifySink>,
+    templates: HashMap<String, String>,
+}
+
+impl Notifier {
+    pub fn from_config(config: &Config) -> Self {
+        let notify = config.notify.clone().unwrap_or_default();
+        Self {
+            sinks: notify.sinks,
+            templates: notify.templates.into_iter().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    pub fn sinks(&self) -> &[NotifySink] {
+        &self.sinks
+    }
+
+    /// The event's message: the configured template, or the built-in one if
+    /// there is none or it names a variable the event doesn't provide
+    pub fn render(&self, event: NotifyEvent, vars: &HashMap<String, String>) -> String {
+        let mut vars = vars.clone();
+        vars.entry("event".to_string()).or_insert_with(|| event.name().to_string());
+        self.templates.get(event.name())
+            .and_then(|t| template::render(t, &vars).ok())
+            .or_else(|| template::render(event.default_template(), &vars).ok())
+            .unwrap_or_else(|| event.default_template().to_string())
+    }
+
+    /// Post to every sink subscribed to the event, warning on failures
+    pub fn notify(&self, event: NotifyEvent, vars: &HashMap<String, String>) {
+        for (sink, result) in self.send(event, vars, None) {
+            if let Err(e) = result {
+                eprintln!("⚠️  Notification to '{}' failed: {:#}", sink, e);
+            }
+        }
+    }
+
+    /// Post to subscribed sinks (or just `only`), returning each outcome.
+    /// Test events go to every sink regardless of subscriptions.
+    pub fn send(&self, event: NotifyEvent, vars: &HashMap<String, String>, only: Option<&str>) -> Vec<(String, Result<()>)> {
+        let message = self.render(event, vars);
+        self.sinks.iter()
+            .filter(|sink| only.is_none_or(|name| sink.name == name))
+            .filter(|sink| event == NotifyEvent::Test || subscribed(sink, event))
+            .map(|sink| (sink.name.clone(), post(&sink.url, &payload(sink, event, &message, vars).to_string(), &[])))
+            .collect()
+    }
+}
+
+/// Send an event using the user's config; a no-op when nothing is configured
+pub fn notify(event: NotifyEvent, vars: &[(&str, String)]) {
+    let notifier = Notifier::from_config(&Config::load_or_default());
+    if notifier.is_empty() {
+        return;
+    }
+    let vars = vars.iter().map(|(k, v)| (k.to_string(), v.clone())).collect();
+    notifier.notify(event, &vars);
+}
+
+fn subscribed(sink: &NotifySink, event: NotifyEvent) -> bool {
+    sink.events.is_empty() || sink.events.iter().any(|e| e == event.name())
+}
+
+/// JSON body in the shape each kind of sink expects
+pub fn payload(sink: &NotifySink, event: NotifyEvent, message: &str, vars: &HashMap<String, String>) -> Value {
+    match sink.kind.as_str() {
+        "slack" => json!({ "text": message }),
+        "discord" => json!({ "content": message }),
+        _ => json!({
+            "event": event.name(),
+            "message": message,
+            "data": vars,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        }),
+    }
+}
+
+pub fn validate_sink(sink: &NotifySink) -> Result<()> {
+    if !SINK_KINDS.contains(&sink.kind.as_str()) {
+        bail!("Sink '{}' has unknown kind '{}'. Choose from: {}", sink.name, sink.kind, SINK_KINDS.join(", "));
+    }
+    if !(sink.url.starts_with("https://") || sink.url.starts_with("http://")) {
+        bail!("Sink '{}' needs an http(s) url", sink.name);
+    }
+    for event in &sink.events {
+        if NotifyEvent::from_name(event).is_none() {
+            bail!("Sink '{}' subscribes to unknown event '{}'", sink.name, event);
+        }
+    }
+    Ok(())
+}
+
+/// POST a JSON body, with any extra `Name: value` headers
+pub fn post(url: &str, body: &str, headers: &[String]) -> Result<()> {
+    let mut child = Command::new("curl")
+        .args(["-sS", "-f", "-X", "POST", "--max-time", POST_TIMEOUT_SECS])
+        .args(["-H", "Content-Type: application/json", "--data-binary", "@-", "-o", "/dev/null"])
+        .args(headers.iter().flat_map(|h| ["-H", h.as_str()]))
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("could not run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Run a hook for an event through the shell (`cmd /C` on Windows), waiting for it to finish. The
+/// event is in PORT42_EVENT, PORT42_SUBJECT and PORT42_TIMESTAMP, and on
+/// stdin as JSON.
+pub fn run_hook(hook: &NotifyHook, event: &DaemonEvent) -> Result<()> {
+    let mut child = platform::shell(&hook.exec)
+        .env("PORT42_EVENT", event.kind.label())
+        .env("PORT42_SUBJECT", &event.subject)
+        .env("PORT42_TIMESTAMP", event.timestamp.to_rfc3339())
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("could not start the shell: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores stdin may exit before reading it
+        let _ = stdin.write_all(serde_json::to_string(event)?.as_bytes());
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("exited with {}", status.code().map_or_else(|| "a signal".to_string(), |c| format!("code {}", c)));
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! Requests saved while the daemon was out of reach
+//!
+//! `possess --queue` and `declare --queue` write the request they would have
+//! sent to `~/.port42/outbox/<millis>-<id>.json`, so the file names sort in
+//! the order things were asked for. Whoever delivers a request first renames
+//! it to `<name>.sending`, so two port42s starting together can't both send
+//! it. Delivered requests are deleted; ones the daemon turned down move to
+//! `outbox/failed/` with its answer, so they aren't sent again on every
+//! connection. A request that was sent but never answered may well have been
+//! carried out, so rather than go back in line to be generated twice it moves
+//! to `outbox/unknown/` until someone has checked and retries it.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::protocol::DaemonRequest;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueuedRequest {
+    pub queued_at: DateTime<Utc>,
+    /// What was asked for, as the user would recognise it
+    pub summary: String,
+    pub request: DaemonRequest,
+    /// The daemon's reason for turning it down, once it has
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl QueuedRequest {
+    /// The file name without `.json`, which `outbox` commands accept
+    pub fn name(&self) -> String {
+        self.path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    dir: PathBuf,
+}
+
+impl Outbox {
+    pub fn open() -> Self {
+        Self::at(crate::config::port42_dir().join("outbox"))
+    }
+
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Save `request` to be sent later
+    pub fn push(&self, summary: &str, request: DaemonRequest) -> Result<QueuedRequest> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let queued_at = Utc::now();
+        let path = self.dir.join(format!("{}-{}.json", queued_at.timestamp_millis(), request.id));
+        let item = QueuedRequest { queued_at, summary: summary.to_string(), request, error: None, path };
+        write(&item.path, &item)?;
+        Ok(item)
+    }
+
+    /// Waiting requests, oldest first
+    pub fn pending(&self) -> Result<Vec<QueuedRequest>> {
+        load_all(&self.dir, "json")
+    }
+
+    /// Requests being delivered, or left claimed by a port42 that died
+    pub fn sending(&self) -> Result<Vec<QueuedRequest>> {
+        load_all(&self.dir, "sending")
+    }
+
+    /// Requests the daemon turned down, oldest first
+    pub fn failed(&self) -> Result<Vec<QueuedRequest>> {
+        load_all(&self.dir.join("failed"), "json")
+    }
+
+    /// Requests sent without an answer, which the daemon may have carried out
+    pub fn unknown(&self) -> Result<Vec<QueuedRequest>> {
+        load_all(&self.dir.join("unknown"), "json")
+    }
+
+    /// Take a waiting request to deliver. None if another port42 took it first.
+    pub fn claim(&self, mut item: QueuedRequest) -> Result<Option<QueuedRequest>> {
+        let claimed = item.path.with_extension("sending");
+        match fs::rename(&item.path, &claimed) {
+            Ok(()) => {
+                item.path = claimed;
+                Ok(Some(item))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to claim {}", item.path.display())),
+        }
+    }
+
+    /// Put a claimed request back in line, undelivered
+    pub fn release(&self, item: &QueuedRequest) -> Result<()> {
+        fs::rename(&item.path, item.path.with_extension("json"))
+            .with_context(|| format!("Failed to return {} to the outbox", item.path.display()))
+    }
+
+    /// Forget a delivered request
+    pub fn remove(&self, item: &QueuedRequest) -> Result<()> {
+        fs::remove_file(&item.path).with_context(|| format!("Failed to remove {}", item.path.display()))
+    }
+
+    /// Set a request the daemon turned down aside with its reason
+    pub fn fail(&self, item: QueuedRequest, error: &str) -> Result<()> {
+        self.set_aside(item, "failed", error)
+    }
+
+    /// Set aside a request that was sent but never answered
+    pub fn mark_unknown(&self, item: QueuedRequest, error: &str) -> Result<()> {
+        self.set_aside(item, "unknown", error)
+    }
+
+    fn set_aside(&self, mut item: QueuedRequest, subdir: &str, error: &str) -> Result<()> {
+        let aside = self.dir.join(subdir);
+        fs::create_dir_all(&aside)?;
+        // Claimed requests are `.sending`; set aside they're `.json` again
+        let to = aside.join(format!("{}.json", item.name()));
+        let from = std::mem::replace(&mut item.path, to);
+        item.error = Some(error.to_string());
+        write(&item.path, &item)?;
+        fs::remove_file(&from).with_context(|| format!("Failed to remove {}", from.display()))
+    }
+
+    /// Put a failed or unknown request, or one left claimed by a port42 that
+    /// died, back in line
+    pub fn retry(&self, name: &str) -> Result<()> {
+        let file = format!("{}.json", name);
+        let from = [
+            self.dir.join(format!("{}.sending", name)),
+            self.dir.join("unknown").join(&file),
+        ]
+        .into_iter()
+        .find(|path| path.exists())
+        .unwrap_or_else(|| self.dir.join("failed").join(&file));
+        let mut item = load(&from)?;
+        item.error = None;
+        item.path = self.dir.join(format!("{}.json", name));
+        write(&item.path, &item)?;
+        fs::remove_file(&from).with_context(|| format!("Failed to remove {}", from.display()))
+    }
+}
+
+fn load(path: &Path) -> Result<QueuedRequest> {
+    let text = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut item: QueuedRequest = serde_json::from_str(&text)
+        .with_context(|| format!("{} isn't a queued request", path.display()))?;
+    item.path = path.to_path_buf();
+    Ok(item)
+}
+
+fn load_all(dir: &Path, extension: &str) -> Result<Vec<QueuedRequest>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+        .collect();
+    paths.sort();
+    paths.iter()
+        // Another port42 may claim or deliver one between listing and reading
+        .filter_map(|path| match load(path) {
+            Err(_) if !path.exists() => None,
+            loaded => Some(loaded),
+        })
+        .collect()
+}
+
+/// Written whole or not at all, so a crash never leaves half a request to send
+fn write(path: &Path, item: &QueuedRequest) -> Result<()> {
+    let partial = path.with_extension("json.partial");
+    fs::write(&partial, serde_json::to_string_pretty(item)?)
+        .with_context(|| format!("Failed to write {}", partial.display()))?;
+    fs::rename(&partial, path).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,167 @@
+//! What running the daemon looks like on each OS
+//!
+//! Unix finds and signals processes with kill(2), pgrep and pkill, and keeps
+//! the PID file in /tmp. Windows uses tasklist and taskkill, starts the
+//! daemon as a detached process and keeps the PID file under
+//! %LOCALAPPDATA%\port42. The daemon is reached over loopback TCP
+//! everywhere, so the client needs nothing from here.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+pub use imp::{detach, hostname, is_alive, is_running, kill, kill_by_name, runtime_dir, shell, terminate};
+
+/// Where a running daemon's PID is kept
+pub fn pid_file() -> PathBuf {
+    runtime_dir().join("port42d.pid")
+}
+
+/// The daemon's output, wherever it was started from
+pub fn daemon_log_path() -> PathBuf {
+    crate::config::port42_dir().join("daemon.log")
+}
+
+#[cfg(unix)]
+mod imp {
+    use super::*;
+    use std::os::unix::process::CommandExt;
+
+    /// Short-lived state: /tmp, where earlier versions put the PID file too
+    pub fn runtime_dir() -> PathBuf {
+        PathBuf::from("/tmp")
+    }
+
+    pub fn is_alive(pid: u32) -> bool {
+        // SAFETY: signal 0 only checks the process exists
+        unsafe { libc::kill(pid as i32, 0) == 0 }
+    }
+
+    /// Whether any process was started from an executable called `name`
+    pub fn is_running(name: &str) -> bool {
+        Command::new("pgrep")
+            .args(["-f", name])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Ask a process to exit; false if it couldn't be signalled
+    pub fn terminate(pid: u32) -> bool {
+        unsafe { libc::kill(pid as i32, libc::SIGTERM) == 0 }
+    }
+
+    pub fn kill(pid: u32) {
+        unsafe {
+            libc::kill(pid as i32, libc::SIGKILL);
+        }
+    }
+
+    pub fn kill_by_name(name: &str) -> std::io::Result<()> {
+        Command::new("pkill").args(["-f", name]).status().map(|_| ())
+    }
+
+    /// Let `command` outlive this process and the terminal it runs in
+    pub fn detach(command: &mut Command) {
+        // SAFETY: setsid is async-signal-safe
+        unsafe {
+            command.pre_exec(|| {
+                libc::setsid();
+                Ok(())
+            });
+        }
+    }
+
+    pub fn hostname() -> Option<String> {
+        let mut buf = [0u8; 256];
+        let ok = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) } == 0;
+        let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+        (ok && len > 0).then(|| String::from_utf8_lossy(&buf[..len]).into_owned())
+    }
+
+    /// A command running `script` through the shell
+    pub fn shell(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use super::*;
+    use std::os::windows::process::CommandExt;
+
+    const DETACHED_PROCESS: u32 = 0x0000_0008;
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    /// %LOCALAPPDATA%\port42, falling back to the temp directory
+    pub fn runtime_dir() -> PathBuf {
+        dirs::data_local_dir().unwrap_or_else(std::env::temp_dir).join("port42")
+    }
+
+    pub fn is_alive(pid: u32) -> bool {
+        tasklist(&format!("PID eq {}", pid))
+    }
+
+    pub fn is_running(name: &str) -> bool {
+        tasklist(&format!("IMAGENAME eq {}", executable(name)))
+    }
+
+    /// `name` as a file on disk, e.g. port42d.exe
+    fn executable(name: &str) -> String {
+        format!("{}{}", name, std::env::consts::EXE_SUFFIX)
+    }
+
+    /// Whether `tasklist` lists anything matching `filter`
+    fn tasklist(filter: &str) -> bool {
+        Command::new("tasklist")
+            .args(["/FI", filter, "/NH", "/FO", "CSV"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            // No match prints an INFO line instead of a CSV row
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim_start().starts_with('"'))
+            .unwrap_or(false)
+    }
+
+    /// Ask a process to close; false if taskkill couldn't
+    pub fn terminate(pid: u32) -> bool {
+        taskkill(&["/PID", &pid.to_string()])
+    }
+
+    pub fn kill(pid: u32) {
+        taskkill(&["/F", "/T", "/PID", &pid.to_string()]);
+    }
+
+    pub fn kill_by_name(name: &str) -> std::io::Result<()> {
+        Command::new("taskkill")
+            .args(["/F", "/IM", &executable(name)])
+            .creation_flags(CREATE_NO_WINDOW)
+            .status()
+            .map(|_| ())
+    }
+
+    fn taskkill(args: &[&str]) -> bool {
+        Command::new("taskkill")
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Start `command` with no console, outside this process's Ctrl+C group
+    pub fn detach(command: &mut Command) {
+        command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+    }
+
+    pub fn hostname() -> Option<String> {
+        std::env::var("COMPUTERNAME").ok().filter(|name| !name.is_empty())
+    }
+
+    pub fn shell(script: &str) -> Command {
+        let mut command = Command::new("cmd");
+        command.args(["/C", script]);
+        command
+    }
+}
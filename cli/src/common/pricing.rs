@@ -0,0 +1,47 @@
+//! Rough per-model pricing used to estimate spend from token counts
+//!
+//! Prices are USD per million tokens and only need to be close enough for
+//! budgeting - the provider's invoice is the source of truth.
+
+/// (model prefix, input $/Mtok, output $/Mtok), most specific prefix first
+const MODEL_PRICES: &[(&str, f64, f64)] = &[
+    ("claude-opus", 15.0, 75.0),
+    ("claude-sonnet", 3.0, 15.0),
+    ("claude-3-7-sonnet", 3.0, 15.0),
+    ("claude-3-5-haiku", 0.8, 4.0),
+    ("claude-haiku", 0.8, 4.0),
+    ("gpt-4o-mini", 0.15, 0.6),
+    ("gpt-4o", 2.5, 10.0),
+    ("gpt-4.1-mini", 0.4, 1.6),
+    ("gpt-4.1", 2.0, 8.0),
+    ("o3-mini", 1.1, 4.4),
+    ("gemini-1.5-flash", 0.075, 0.3),
+    ("gemini-1.5-pro", 1.25, 5.0),
+    ("gemini-2.0-flash", 0.1, 0.4),
+    ("gemini-2.5-pro", 1.25, 10.0),
+];
+
+/// Fallback per provider when the model is unknown
+fn provider_price(provider: &str) -> (f64, f64) {
+    match provider {
+        "anthropic" => (3.0, 15.0),
+        "openai" => (2.5, 10.0),
+        "google" => (1.25, 5.0),
+        // Local models cost nothing per token
+        _ => (0.0, 0.0),
+    }
+}
+
+/// Estimated cost in USD for a request
+pub fn estimate_cost(provider: &str, model: Option<&str>, input_tokens: u64, output_tokens: u64) -> f64 {
+    let (input_price, output_price) = model
+        .and_then(|m| {
+            MODEL_PRICES
+                .iter()
+                .find(|(prefix, _, _)| m.starts_with(prefix))
+                .map(|(_, i, o)| (*i, *o))
+        })
+        .unwrap_or_else(|| provider_price(provider));
+
+    (input_tokens as f64 * input_price + output_tokens as f64 * output_price) / 1_000_000.0
+}
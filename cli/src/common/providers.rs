@@ -0,0 +1,233 @@
+use anyhow::{Result, bail};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use crate::config::Config;
+use crate::common::{errors::Port42Error, keychain};
+use crate::protocol::ProviderSelection;
+
+/// AI backends the daemon's provider factory knows how to construct
+pub const KNOWN_PROVIDERS: &[&str] = &["anthropic", "openai", "google", "local"];
+
+pub const DEFAULT_PROVIDER: &str = "anthropic";
+
+/// Provider backed by a local Ollama endpoint - no API key, works offline
+pub const LOCAL_PROVIDER: &str = "local";
+
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Environment variables holding API keys for a provider, in lookup order
+pub fn api_key_vars(provider: &str) -> &'static [&'static str] {
+    match provider {
+        "anthropic" => &["PORT42_ANTHROPIC_API_KEY", "ANTHROPIC_API_KEY"],
+        "openai" => &["PORT42_OPENAI_API_KEY", "OPENAI_API_KEY"],
+        "google" => &["PORT42_GOOGLE_API_KEY", "GOOGLE_API_KEY", "GEMINI_API_KEY"],
+        _ => &[],
+    }
+}
+
+/// Environment variable overriding the provider's API base URL
+pub fn base_url_var(provider: &str) -> Option<&'static str> {
+    match provider {
+        "openai" => Some("PORT42_OPENAI_BASE_URL"),
+        "local" => Some("PORT42_OLLAMA_BASE_URL"),
+        _ => None,
+    }
+}
+
+/// Whether the daemon needs an API key to talk to this provider
+pub fn requires_api_key(provider: &str) -> bool {
+    !api_key_vars(provider).is_empty()
+}
+
+/// Check that a provider name is one we can route to
+pub fn validate_provider(provider: &str) -> Result<()> {
+    if !KNOWN_PROVIDERS.contains(&provider) {
+        bail!("Unknown provider '{}'. Choose from: {}", provider, KNOWN_PROVIDERS.join(", "));
+    }
+    Ok(())
+}
+
+/// Human-readable provider name for messages
+pub fn display_name(provider: &str) -> &str {
+    match provider {
+        "anthropic" => "Claude",
+        "openai" => "OpenAI",
+        "google" => "Gemini",
+        "local" => "Ollama",
+        other => other,
+    }
+}
+
+/// `--provider` / `--model` flags shared by commands that talk to an AI.
+/// PORT42_PROVIDER / PORT42_MODEL stand in for flags that aren't given.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ProviderArgs {
+    #[arg(long, env = "PORT42_PROVIDER", help = crate::help_text::PROVIDER_ARG_HELP)]
+    pub provider: Option<String>,
+
+    #[arg(long, env = "PORT42_MODEL", help = crate::help_text::MODEL_ARG_HELP)]
+    pub model: Option<String>,
+
+    /// Don't retry on fallback providers when the primary is unavailable
+    #[arg(long)]
+    pub no_fallback: bool,
+}
+
+/// Resolve provider and model for a request. Precedence is explicit flags, then the
+/// agent's defaults from config, then the global config defaults. The global model
+/// only applies to the global provider; any other provider uses its own default.
+/// The model string is passed through untouched - the daemon's provider decides what it means.
+pub fn resolve_provider(args: ProviderArgs, agent: Option<&str>, config: &Config) -> Result<Option<ProviderSelection>> {
+    let agent_defaults = agent.and_then(|a| config.agent_defaults(a)).cloned().unwrap_or_default();
+
+    // An agent's model was chosen for the agent's provider, or the global one
+    // when it names none, so drop it when --provider points somewhere else
+    let agent_model_provider = agent_defaults.provider.clone()
+        .or_else(|| config.provider.clone())
+        .unwrap_or_else(|| DEFAULT_PROVIDER.to_string());
+    let agent_model = match args.provider {
+        Some(ref flag) if *flag != agent_model_provider => None,
+        _ => agent_defaults.model,
+    };
+
+    let name = args.provider
+        .or(agent_defaults.provider)
+        .or_else(|| config.provider.clone());
+    if let Some(ref p) = name {
+        validate_provider(p)?;
+    }
+    let global_provider = config.provider.as_deref().unwrap_or(DEFAULT_PROVIDER);
+    let global_model = config.model.clone()
+        .filter(|_| name.as_deref().unwrap_or(DEFAULT_PROVIDER) == global_provider);
+    let mut model = args.model.or(agent_model);
+    let mut base_url = None;
+
+    // For Ollama, the [local] model comes before the global one
+    if name.as_deref() == Some(LOCAL_PROVIDER) {
+        let local = config.local.clone().unwrap_or_default();
+        model = model.or(local.model);
+        base_url = Some(local_base_url(config));
+    }
+    let model = model.or(global_model);
+
+    if name.is_none() && model.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(ProviderSelection { name, model, base_url }))
+}
+
+/// Fallback providers for a request, skipping the primary. Entries come from the
+/// agent's `fallback` list if it has one, otherwise the global list.
+pub fn resolve_fallbacks(
+    agent: Option<&str>,
+    primary: &Option<ProviderSelection>,
+    config: &Config,
+) -> Result<Vec<ProviderSelection>> {
+    let chain = agent
+        .and_then(|a| config.agent_defaults(a))
+        .and_then(|d| d.fallback.clone())
+        .unwrap_or_else(|| config.fallback.clone());
+    let primary_name = primary.as_ref()
+        .and_then(|p| p.name.as_deref())
+        .unwrap_or(DEFAULT_PROVIDER);
+
+    let mut fallbacks = Vec::new();
+    for entry in chain {
+        let (name, model) = match entry.split_once('/') {
+            Some((name, model)) => (name.to_string(), Some(model.to_string())),
+            None => (entry.clone(), None),
+        };
+        validate_provider(&name)?;
+        if name == primary_name {
+            continue;
+        }
+
+        let mut selection = ProviderSelection { name: Some(name), model, base_url: None };
+        if selection.name.as_deref() == Some(LOCAL_PROVIDER) {
+            let local = config.local.clone().unwrap_or_default();
+            selection.model = selection.model.or(local.model);
+            selection.base_url = Some(local_base_url(config));
+        }
+        fallbacks.push(selection);
+    }
+    Ok(fallbacks)
+}
+
+/// Ollama endpoint: PORT42_OLLAMA_BASE_URL, then config, then the Ollama default
+pub fn local_base_url(config: &Config) -> String {
+    base_url_var(LOCAL_PROVIDER)
+        .and_then(|var| std::env::var(var).ok())
+        .filter(|v| !v.is_empty())
+        .or_else(|| config.local.as_ref().and_then(|l| l.base_url.clone()))
+        .unwrap_or_else(|| DEFAULT_OLLAMA_URL.to_string())
+}
+
+/// Fail fast when a local provider is selected but its endpoint is down
+pub fn ensure_reachable(selection: &Option<ProviderSelection>) -> Result<()> {
+    let Some(selection) = selection else { return Ok(()) };
+    if selection.name.as_deref() != Some(LOCAL_PROVIDER) {
+        return Ok(());
+    }
+
+    let base_url = selection.base_url.as_deref().unwrap_or(DEFAULT_OLLAMA_URL);
+    if let Err(e) = probe_ollama(base_url) {
+        bail!(Port42Error::Network(crate::help_text::format_local_unreachable(base_url, &e.to_string())));
+    }
+    Ok(())
+}
+
+/// Ask Ollama for its model list - any HTTP 200 means the server is up
+fn probe_ollama(base_url: &str) -> Result<()> {
+    let rest = base_url
+        .strip_prefix("http://")
+        .ok_or_else(|| anyhow::anyhow!("only http:// endpoints can be probed"))?;
+    let host = rest.split('/').next().unwrap_or(rest);
+    let addr_str = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+
+    let addr = addr_str
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("could not resolve {}", host))?;
+    let mut stream = TcpStream::connect_timeout(&addr, REACHABILITY_TIMEOUT)?;
+    stream.set_read_timeout(Some(REACHABILITY_TIMEOUT))?;
+    stream.set_write_timeout(Some(REACHABILITY_TIMEOUT))?;
+
+    write!(stream, "GET /api/tags HTTP/1.0\r\nHost: {}\r\n\r\n", host)?;
+    let mut status = [0u8; 12];
+    stream.read_exact(&mut status)?;
+    let status = String::from_utf8_lossy(&status);
+    if !status.ends_with("200") {
+        bail!("unexpected response '{}'", status.trim());
+    }
+    Ok(())
+}
+
+/// Where a provider's API key was found
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeySource {
+    Env(&'static str),
+    Keychain,
+}
+
+/// Find the first API key variable that is set for a provider
+pub fn find_env_key(provider: &str) -> Option<(&'static str, String)> {
+    api_key_vars(provider)
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()).map(|v| (*var, v)))
+}
+
+/// Find a provider's API key: environment first, then the OS keychain
+pub fn find_api_key_with_source(provider: &str) -> Option<(String, KeySource)> {
+    if let Some((var, key)) = find_env_key(provider) {
+        return Some((key, KeySource::Env(var)));
+    }
+    // Keychain errors (e.g. no Secret Service running) just mean "not found"
+    keychain::get_key(provider).ok().flatten().map(|key| (key, KeySource::Keychain))
+}
+
+pub fn find_api_key(provider: &str) -> Option<String> {
+    find_api_key_with_source(provider).map(|(key, _)| key)
+}
@@ -0,0 +1,119 @@
+//! Client-side queueing for rate-limited provider requests
+//!
+//! A 429 answer means "not now", not "never". Instead of failing, the
+//! request waits out a visible countdown and is sent again. A 529 or
+//! "overloaded" answer is an outage, not a limit, and goes to the provider
+//! fallback chain rather than waiting.
+//! Each wait is stretched by a random fraction so that requests limited at
+//! the same moment (a batch of declarations, say) don't all retry at once.
+
+use colored::*;
+use regex::Regex;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::help_text;
+use crate::ui::WaveSpinner;
+
+const DEFAULT_MAX_WAITS: u32 = 5;
+const DEFAULT_MAX_DELAY_SECS: u64 = 120;
+const BASE_DELAY_SECS: u64 = 5;
+/// Largest random stretch, as a fraction of the delay
+const MAX_JITTER: f64 = 0.2;
+
+/// Does this daemon error mean the provider wants us to slow down?
+pub fn is_rate_limited(raw: &str) -> bool {
+    // The status on its own, not the digits inside an id, a port or a token count
+    static STATUS: OnceLock<Regex> = OnceLock::new();
+    let status = STATUS.get_or_init(|| Regex::new(r"\b429\b").expect("valid pattern"));
+    let lower = raw.to_lowercase();
+    status.is_match(raw)
+        || lower.contains("rate limit")
+        || lower.contains("rate_limit")
+        || lower.contains("too many requests")
+}
+
+/// A "retry after N seconds" hint from the provider, if it gave one
+pub fn retry_after(raw: &str) -> Option<Duration> {
+    let re = Regex::new(r"(?i)retry[-_ ]after\D{0,3}(\d+)").ok()?;
+    let secs: u64 = re.captures(raw)?.get(1)?.as_str().parse().ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+/// Delay before the given (zero-based) retry: the provider's hint if any,
+/// otherwise exponential backoff, capped either way
+pub fn backoff_delay(attempt: u32, hint: Option<Duration>, max: Duration) -> Duration {
+    let delay = hint.unwrap_or_else(|| Duration::from_secs(BASE_DELAY_SECS.saturating_mul(1 << attempt.min(10))));
+    delay.min(max)
+}
+
+/// `delay` stretched by `unit` (0.0 to 1.0) of the maximum jitter.
+/// Never shorter than `delay`, so a provider's retry-after is still honoured.
+pub fn with_jitter(delay: Duration, unit: f64) -> Duration {
+    delay + delay.mul_f64(MAX_JITTER * unit.clamp(0.0, 1.0))
+}
+
+/// A random number in 0.0..=1.0, from the std hasher's random keys
+fn random_unit() -> f64 {
+    RandomState::new().hash_one(std::time::Instant::now()) as f64 / u64::MAX as f64
+}
+
+pub struct RateLimitQueue {
+    waits: u32,
+    max_waits: u32,
+    max_delay: Duration,
+    animated: bool,
+}
+
+impl RateLimitQueue {
+    pub fn new(max_waits: u32, max_delay: Duration) -> Self {
+        Self { waits: 0, max_waits, max_delay, animated: true }
+    }
+    
+    /// Announce each wait on one line instead of animating it, for requests
+    /// running side by side
+    pub fn quiet(mut self) -> Self {
+        self.animated = false;
+        self
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let settings = config.rate_limit.clone().unwrap_or_default();
+        Self::new(
+            settings.max_waits.unwrap_or(DEFAULT_MAX_WAITS),
+            Duration::from_secs(settings.max_delay_secs.unwrap_or(DEFAULT_MAX_DELAY_SECS)),
+        )
+    }
+
+    /// If `error` is a rate limit and waits remain, count down and return true
+    /// so the caller resends. Returns false when the caller should give up
+    /// (or move on to a fallback provider).
+    pub fn wait_if_limited(&mut self, provider: &str, error: &str) -> bool {
+        if !is_rate_limited(error) || self.waits >= self.max_waits {
+            return false;
+        }
+        let delay = with_jitter(backoff_delay(self.waits, retry_after(error), self.max_delay), random_unit());
+        self.waits += 1;
+        if self.animated {
+            countdown(provider, delay, self.waits, self.max_waits);
+        } else {
+            eprintln!("{}", help_text::format_rate_limit_countdown(provider, delay.as_secs().max(1), self.waits, self.max_waits).yellow());
+            std::thread::sleep(delay);
+        }
+        true
+    }
+}
+
+fn countdown(provider: &str, delay: Duration, attempt: u32, max: u32) {
+    let provider_name = provider.to_string();
+    let mut spinner = WaveSpinner::with_status(delay, move |left| {
+        // Round up so the last second reads "1s", not "0s"
+        let secs = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+        help_text::format_rate_limit_countdown(&provider_name, secs.max(1), attempt, max).yellow().to_string()
+    });
+    std::thread::sleep(delay);
+    spinner.stop();
+    eprintln!("{}", help_text::format_rate_limit_resumed(provider).dimmed());
+}
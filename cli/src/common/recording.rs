@@ -0,0 +1,158 @@
+//! Recording daemon traffic to reproduce bugs
+//!
+//! With `--record <dir>` every request the client sends and the response it
+//! gets back are saved as `<dir>/0001-<type>.json`, numbered on from whatever
+//! is already there, so several commands can add to one recording. Streamed
+//! chunks aren't kept; the final response carries the whole reply anyway.
+//!
+//! `port42 replay <dir>` sends the requests again and compares the answers,
+//! or with `--serve` runs a `MockDaemon` that answers from the recording.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::common::errors::Port42Error;
+use crate::protocol::DaemonRequest;
+
+/// One request and the response it got
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub recorded_at: DateTime<Utc>,
+    pub elapsed_ms: u64,
+    pub request: Value,
+    /// As received; a line that wasn't JSON is kept as a string
+    pub response: Value,
+}
+
+impl Exchange {
+    pub fn request_type(&self) -> &str {
+        self.request.get("type").and_then(Value::as_str).unwrap_or("unknown")
+    }
+}
+
+pub struct Recorder {
+    dir: PathBuf,
+    next: Mutex<u32>,
+}
+
+static RECORDER: OnceLock<Recorder> = OnceLock::new();
+
+/// Record every exchange from now on into `dir`; later calls are ignored
+pub fn start(dir: &Path) -> Result<()> {
+    let recorder = Recorder::create(dir)?;
+    let _ = RECORDER.set(recorder);
+    Ok(())
+}
+
+/// The recorder `--record` started, if any
+pub fn current() -> Option<&'static Recorder> {
+    RECORDER.get()
+}
+
+impl Recorder {
+    pub fn create(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("Can't create {}", dir.display()))?;
+        let last = entries(dir)?.iter().filter_map(|path| sequence(path)).max().unwrap_or(0);
+        Ok(Self { dir: dir.to_path_buf(), next: Mutex::new(last + 1) })
+    }
+
+    /// Save one exchange, returning the file it went to
+    pub fn record(&self, request: &DaemonRequest, response_line: &str, elapsed: Duration) -> Result<PathBuf> {
+        let line = response_line.trim_end();
+        let exchange = Exchange {
+            recorded_at: Utc::now(),
+            elapsed_ms: elapsed.as_millis() as u64,
+            request: serde_json::to_value(request)?,
+            response: serde_json::from_str(line).unwrap_or_else(|_| Value::String(line.to_string())),
+        };
+        let body = serde_json::to_string_pretty(&exchange)?;
+        let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+        // Another process may be recording into the same directory
+        loop {
+            let path = self.dir.join(format!("{:04}-{}.json", *next, file_safe(&request.request_type)));
+            *next += 1;
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(body.as_bytes())?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e).with_context(|| format!("Can't write {}", path.display())),
+            }
+        }
+    }
+}
+
+/// A recording read back, in the order it was made
+pub struct Recording {
+    pub exchanges: Vec<(String, Exchange)>,
+    served: Vec<bool>,
+}
+
+impl Recording {
+    pub fn load(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            return Err(Port42Error::NotFound(format!("No recording at {}", dir.display())).into());
+        }
+        let mut exchanges = Vec::new();
+        for path in entries(dir)? {
+            let text = fs::read_to_string(&path)?;
+            let exchange: Exchange = serde_json::from_str(&text)
+                .with_context(|| format!("{} isn't a recorded exchange", path.display()))?;
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            exchanges.push((name, exchange));
+        }
+        if exchanges.is_empty() {
+            return Err(Port42Error::NotFound(format!("Nothing recorded in {}", dir.display())).into());
+        }
+        let served = vec![false; exchanges.len()];
+        Ok(Self { exchanges, served })
+    }
+
+    /// The file and recorded response to play back for `request`. Prefers
+    /// the first unserved exchange with the same type and payload, then the
+    /// first unserved one of the same type, then the last one of that type
+    /// served again.
+    pub fn answer(&mut self, request: &Value) -> Option<(&str, &Value)> {
+        let kind = request.get("type").and_then(Value::as_str).unwrap_or_default();
+        let same_type = |e: &Exchange| e.request_type() == kind;
+        let unserved = |i: usize| !self.served[i];
+        let found = self.position(|i, e| unserved(i) && same_type(e) && e.request.get("payload") == request.get("payload"))
+            .or_else(|| self.position(|i, e| unserved(i) && same_type(e)))
+            .or_else(|| self.exchanges.iter().rposition(|(_, e)| same_type(e)));
+        let i = found?;
+        self.served[i] = true;
+        let (ref name, ref exchange) = self.exchanges[i];
+        Some((name.as_str(), &exchange.response))
+    }
+
+    fn position(&self, matches: impl Fn(usize, &Exchange) -> bool) -> Option<usize> {
+        self.exchanges.iter().enumerate().position(|(i, (_, e))| matches(i, e))
+    }
+}
+
+/// The recorded exchanges in `dir`, by file name
+fn entries(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| sequence(path).is_some() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// `12` for `0012-swim.json`
+fn sequence(path: &Path) -> Option<u32> {
+    path.file_name()?.to_str()?.split('-').next()?.parse().ok()
+}
+
+fn file_safe(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' }).collect()
+}
@@ -1,6 +1,18 @@
 use crate::protocol::relations::Reference;
+use crate::common::{file_ref::{self, Expansion}, git_ref, url_ref};
+use crate::config::Config;
+use crate::protocol::file_ops::format_size;
+use crate::help_text;
 use anyhow::{Result, bail};
 use colored::*;
+use std::io::Read;
+use std::sync::OnceLock;
+
+/// Piped input beyond this is cut off so one `cat` can't swamp the context window
+pub const MAX_STDIN_BYTES: usize = 100 * 1024;
+
+/// stdin can only be read once, so every `stdin:` reference shares the first read
+static PIPED: OnceLock<String> = OnceLock::new();
 
 /// Parse reference strings into Reference structs
 /// Common logic used by both declare and swim modes
@@ -9,6 +21,52 @@ pub fn parse_references(ref_strings: Vec<String>, show_output: bool) -> Result<V
     
     for ref_str in ref_strings {
         match Reference::from_string(&ref_str) {
+            // stdin and git only exist on this machine, and pages are cached
+            // on it, so these are resolved here and sent as context rather
+            // than left to the daemon
+            Ok(mut reference) if matches!(reference.ref_type.as_str(), "stdin" | "git" | "url") => {
+                let (content, summary) = match reference.ref_type.as_str() {
+                    "stdin" => {
+                        let content = read_piped()?;
+                        let summary = format!("{} bytes piped", content.len());
+                        (content, summary)
+                    }
+                    "git" => {
+                        let content = git_ref::resolve(&reference.target)?;
+                        let summary = format!("{} ({} lines)", reference.target, content.lines().count());
+                        (content, summary)
+                    }
+                    _ => {
+                        let page = url_ref::resolve(&reference.target)?;
+                        let kind = if page.extracted { "article" } else { "raw" };
+                        let cached = if page.cached { ", cached" } else { "" };
+                        let summary = format!("{} ({} bytes {}{})", reference.target, page.content.len(), kind, cached);
+                        (page.content, summary)
+                    }
+                };
+                if show_output {
+                    println!("  {}: {} → {}",
+                           "Reference".bright_cyan(),
+                           reference.ref_type.bright_yellow(),
+                           summary.bright_white());
+                }
+                reference.context = Some(content);
+                refs.push(reference);
+            }
+            // Directories and globs become a reference per file, read here
+            // so the budgets can be applied and the choice shown
+            Ok(reference) if reference.ref_type == "file" && file_ref::is_expandable(&reference.target) => {
+                let budget = file_ref::Budget::from_config(&Config::load_or_default());
+                let expansion = file_ref::expand(&reference.target, budget)?;
+                if show_output {
+                    print_expansion(&reference.target, &expansion);
+                }
+                refs.extend(expansion.included.into_iter().map(|file| Reference {
+                    ref_type: "file".to_string(),
+                    target: file.path,
+                    context: Some(file.content),
+                }));
+            }
             Ok(reference) => {
                 if show_output {
                     println!("  {}: {} → {}", 
@@ -25,4 +83,63 @@ pub fn parse_references(ref_strings: Vec<String>, show_output: bool) -> Result<V
     }
     
     Ok(refs)
-}
\ No newline at end of file
+}
+
+/// Files listed under an expanded reference before the rest are counted
+const LISTED_FILES: usize = 20;
+
+/// What a directory or glob expanded to, and what was left out
+fn print_expansion(target: &str, expansion: &Expansion) {
+    let count = expansion.included.len();
+    let mut summary = format!("{} ({} file{}, {}", target, count, if count == 1 { "" } else { "s" }, format_size(expansion.bytes() as i64));
+    if !expansion.skipped.is_empty() {
+        summary.push_str(&format!(", {} skipped", expansion.skipped.len()));
+    }
+    summary.push(')');
+    println!("  {}: {} → {}", "Reference".bright_cyan(), "file".bright_yellow(), summary.bright_white());
+
+    for file in expansion.included.iter().take(LISTED_FILES) {
+        let note = if file.truncated { format!(" (cut from {})", format_size(file.size as i64)) } else { String::new() };
+        println!("    {} {} {}{}", "+".green(), file.path, format_size(file.content.len() as i64).dimmed(), note.yellow());
+    }
+    if expansion.included.len() > LISTED_FILES {
+        println!("    {}", format!("… {} more", expansion.included.len() - LISTED_FILES).dimmed());
+    }
+    for file in expansion.skipped.iter().take(LISTED_FILES) {
+        println!("    {} {} {}", "-".red(), file.path, format!("({})", file.reason).dimmed());
+    }
+    if expansion.skipped.len() > LISTED_FILES {
+        println!("    {}", format!("… {} more skipped", expansion.skipped.len() - LISTED_FILES).dimmed());
+    }
+}
+
+fn read_piped() -> Result<String> {
+    if let Some(content) = PIPED.get() {
+        return Ok(content.clone());
+    }
+    if atty::is(atty::Stream::Stdin) {
+        bail!(help_text::ERR_STDIN_NOT_PIPED);
+    }
+    let mut bytes = Vec::new();
+    std::io::stdin().read_to_end(&mut bytes)?;
+    let total = bytes.len();
+    let (content, truncated) = truncate_context(String::from_utf8_lossy(&bytes).into_owned(), MAX_STDIN_BYTES);
+    if truncated {
+        eprintln!("{}", help_text::format_stdin_truncated(total, MAX_STDIN_BYTES).yellow());
+    }
+    Ok(PIPED.get_or_init(|| content).clone())
+}
+
+/// Cut `content` to at most `cap` bytes without splitting a character.
+/// Returns whether anything was dropped
+pub fn truncate_context(mut content: String, cap: usize) -> (String, bool) {
+    if content.len() <= cap {
+        return (content, false);
+    }
+    let mut end = cap;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    content.truncate(end);
+    (content, true)
+}
@@ -0,0 +1,107 @@
+//! Embedding index for semantic search over memories and tools
+//!
+//! Vectors are computed by the daemon (`embed` requests) and kept in
+//! `~/.port42/index/semantic.json`. Each entry remembers a hash of the text
+//! it was built from, so `index update` only re-embeds what changed.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Reciprocal rank fusion constant; 60 is the usual choice
+const RRF_K: f64 = 60.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub path: String,
+    pub entry_type: String,
+    pub title: String,
+    pub snippet: String,
+    pub content_hash: String,
+    pub vector: Vec<f32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SemanticIndex {
+    /// Embedding model the vectors came from; queries must use the same one
+    pub model: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub entries: Vec<IndexEntry>,
+}
+
+impl SemanticIndex {
+    pub fn load() -> Result<Self> {
+        let path = index_path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Corrupt search index at {}, rebuild with 'port42 index build'", path.display()))
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        let path = index_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        self.updated_at = Some(Utc::now());
+        fs::write(&path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries most similar to the query vector, best first
+    pub fn nearest(&self, query: &[f32], limit: usize) -> Vec<(&IndexEntry, f64)> {
+        let mut scored: Vec<(&IndexEntry, f64)> = self.entries.iter()
+            .map(|entry| (entry, cosine_similarity(query, &entry.vector)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+        scored
+    }
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (*x as f64, *y as f64);
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Merge ranked path lists with reciprocal rank fusion, best first.
+/// Paths found by several rankings rise above those found by one.
+pub fn fuse_rankings(rankings: &[Vec<String>]) -> Vec<(String, f64)> {
+    let mut scores: HashMap<&str, f64> = HashMap::new();
+    for ranking in rankings {
+        for (rank, path) in ranking.iter().enumerate() {
+            *scores.entry(path.as_str()).or_default() += 1.0 / (RRF_K + rank as f64 + 1.0);
+        }
+    }
+    let mut fused: Vec<(String, f64)> = scores.into_iter()
+        .map(|(path, score)| (path.to_string(), score))
+        .collect();
+    fused.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    fused
+}
+
+pub fn index_path() -> PathBuf {
+    crate::config::port42_dir().join("index").join("semantic.json")
+}
@@ -0,0 +1,362 @@
+//! The daemon as an OS service
+//!
+//! `port42 daemon install` hands the daemon to launchd on macOS or systemd on
+//! Linux, which start it at login (or at boot with `--system`) and restart it
+//! if it crashes. The daemon only learns API keys from its environment, so
+//! they are written out for the service manager: into the plist for launchd,
+//! or into an environment file next to the config for systemd. Both are
+//! readable only by their owner.
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// launchd label, also the plist's file name
+pub const LABEL: &str = "com.port42.daemon";
+
+/// systemd unit name
+pub const UNIT_NAME: &str = "port42d.service";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Manager {
+    Launchd,
+    Systemd,
+}
+
+impl Manager {
+    /// The service manager on this OS, if there is one we know how to drive
+    pub fn current() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            Some(Manager::Launchd)
+        } else if cfg!(target_os = "linux") && which::which("systemctl").is_ok() {
+            Some(Manager::Systemd)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Manager::Launchd => "launchd",
+            Manager::Systemd => "systemd",
+        }
+    }
+}
+
+/// Started at login for the current user, or at boot for the whole machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    User,
+    System,
+}
+
+impl Scope {
+    pub fn name(self) -> &'static str {
+        match self {
+            Scope::User => "user",
+            Scope::System => "system",
+        }
+    }
+}
+
+/// Things to ask the service manager to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Register the written files and start the daemon, now and from now on
+    Load,
+    /// Stop the daemon and forget about it
+    Unload,
+    Start,
+    Stop,
+    Restart,
+}
+
+/// What goes into the unit or plist
+#[derive(Debug, Clone)]
+pub struct ServiceSpec {
+    pub binary: PathBuf,
+    pub log: PathBuf,
+    pub home: PathBuf,
+    /// Account a system service runs as; user services run as their owner
+    pub user: Option<String>,
+    pub env: Vec<(String, String)>,
+}
+
+/// A file to write when installing
+#[derive(Debug, Clone)]
+pub struct ServiceFile {
+    pub path: PathBuf,
+    pub contents: String,
+    /// Holds API keys, so only the owner may read it
+    pub private: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Service {
+    pub manager: Manager,
+    pub scope: Scope,
+}
+
+impl Service {
+    pub fn new(manager: Manager, scope: Scope) -> Self {
+        Self { manager, scope }
+    }
+
+    /// Where the plist or unit lives
+    pub fn path(&self) -> PathBuf {
+        let home = dirs::home_dir().unwrap_or_default();
+        match (self.manager, self.scope) {
+            (Manager::Launchd, Scope::User) => home.join("Library/LaunchAgents").join(format!("{}.plist", LABEL)),
+            (Manager::Launchd, Scope::System) => PathBuf::from("/Library/LaunchDaemons").join(format!("{}.plist", LABEL)),
+            (Manager::Systemd, Scope::User) => dirs::config_dir()
+                .unwrap_or_else(|| home.join(".config"))
+                .join("systemd/user")
+                .join(UNIT_NAME),
+            (Manager::Systemd, Scope::System) => PathBuf::from("/etc/systemd/system").join(UNIT_NAME),
+        }
+    }
+
+    /// Where a systemd unit reads its environment from; launchd keeps it in the plist
+    pub fn env_file(&self) -> Option<PathBuf> {
+        match (self.manager, self.scope) {
+            (Manager::Launchd, _) => None,
+            (Manager::Systemd, Scope::User) => Some(crate::config::port42_dir().join("daemon.env")),
+            (Manager::Systemd, Scope::System) => Some(PathBuf::from("/etc/port42/daemon.env")),
+        }
+    }
+
+    pub fn is_installed(&self) -> bool {
+        self.path().exists()
+    }
+
+    /// The files `install` writes
+    pub fn render(&self, spec: &ServiceSpec) -> Vec<ServiceFile> {
+        match self.manager {
+            Manager::Launchd => vec![ServiceFile {
+                path: self.path(),
+                contents: self.plist(spec),
+                private: true,
+            }],
+            Manager::Systemd => {
+                let env_file = self.env_file().expect("systemd services have an environment file");
+                vec![
+                    ServiceFile { path: self.path(), contents: self.unit(spec, &env_file), private: false },
+                    ServiceFile { path: env_file, contents: env_file_contents(&spec.env), private: true },
+                ]
+            }
+        }
+    }
+
+    fn plist(&self, spec: &ServiceSpec) -> String {
+        let mut env = vec![("HOME".to_string(), spec.home.display().to_string())];
+        env.extend(spec.env.iter().filter(|(var, _)| var != "HOME").cloned());
+        let env: String = env.iter()
+            .map(|(var, value)| format!("        <key>{}</key>\n        <string>{}</string>\n", xml_escape(var), xml_escape(value)))
+            .collect();
+        let user = match (&spec.user, self.scope) {
+            (Some(user), Scope::System) => format!("    <key>UserName</key>\n    <string>{}</string>\n", xml_escape(user)),
+            _ => String::new(),
+        };
+        let log = xml_escape(&spec.log.display().to_string());
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{binary}</string>
+    </array>
+{user}    <key>EnvironmentVariables</key>
+    <dict>
+{env}    </dict>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>StandardOutPath</key>
+    <string>{log}</string>
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+</dict>
+</plist>
+"#,
+            label = LABEL,
+            binary = xml_escape(&spec.binary.display().to_string()),
+            user = user,
+            env = env,
+            log = log,
+        )
+    }
+
+    fn unit(&self, spec: &ServiceSpec, env_file: &Path) -> String {
+        let log = spec.log.display();
+        let mut service = format!(
+            "ExecStart={}\nEnvironmentFile=-{}\nRestart=on-failure\nRestartSec=5\nStandardOutput=append:{}\nStandardError=append:{}\n",
+            spec.binary.display(), env_file.display(), log, log
+        );
+        let wanted_by = match self.scope {
+            Scope::User => "default.target",
+            Scope::System => {
+                if let Some(user) = &spec.user {
+                    service.push_str(&format!("User={}\n", user));
+                }
+                service.push_str(&format!("Environment=HOME={}\n", spec.home.display()));
+                // Lets the daemon open port 42 without running as root
+                service.push_str("AmbientCapabilities=CAP_NET_BIND_SERVICE\n");
+                "multi-user.target"
+            }
+        };
+
+        format!(
+            "[Unit]\nDescription=Port 42 daemon\nAfter=network-online.target\nWants=network-online.target\n\n\
+             [Service]\n{}\n[Install]\nWantedBy={}\n",
+            service, wanted_by
+        )
+    }
+
+    /// The commands that carry out `step`, in order
+    pub fn commands(&self, step: Step) -> Vec<Vec<String>> {
+        let command = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        match self.manager {
+            Manager::Launchd => {
+                let domain = self.launchd_domain();
+                let target = format!("{}/{}", domain, LABEL);
+                let path = self.path().display().to_string();
+                match step {
+                    Step::Load => vec![command(&["launchctl", "bootstrap", &domain, &path])],
+                    Step::Unload => vec![command(&["launchctl", "bootout", &target])],
+                    Step::Start => vec![command(&["launchctl", "kickstart", &target])],
+                    // The daemon exits cleanly on SIGTERM, which KeepAlive leaves alone
+                    Step::Stop => vec![command(&["launchctl", "kill", "SIGTERM", &target])],
+                    Step::Restart => vec![command(&["launchctl", "kickstart", "-k", &target])],
+                }
+            }
+            Manager::Systemd => {
+                let systemctl = |args: &[&str]| {
+                    let mut full = command(&["systemctl"]);
+                    if self.scope == Scope::User {
+                        full.push("--user".to_string());
+                    }
+                    full.extend(command(args));
+                    full
+                };
+                match step {
+                    Step::Load => vec![systemctl(&["daemon-reload"]), systemctl(&["enable", "--now", UNIT_NAME])],
+                    Step::Unload => vec![systemctl(&["disable", "--now", UNIT_NAME])],
+                    Step::Start => vec![systemctl(&["start", UNIT_NAME])],
+                    Step::Stop => vec![systemctl(&["stop", UNIT_NAME])],
+                    Step::Restart => vec![systemctl(&["restart", UNIT_NAME])],
+                }
+            }
+        }
+    }
+
+    /// Whether the service manager has the daemon running right now
+    pub fn is_active(&self) -> bool {
+        match self.manager {
+            // `print` succeeds for loaded jobs whether or not they are running
+            Manager::Launchd => Command::new("launchctl")
+                .args(["print", &format!("{}/{}", self.launchd_domain(), LABEL)])
+                .output()
+                .map(|output| output.status.success() && String::from_utf8_lossy(&output.stdout).contains("state = running"))
+                .unwrap_or(false),
+            Manager::Systemd => {
+                let mut command = Command::new("systemctl");
+                if self.scope == Scope::User {
+                    command.arg("--user");
+                }
+                command.args(["is-active", "--quiet", UNIT_NAME])
+                    .status()
+                    .map(|status| status.success())
+                    .unwrap_or(false)
+            }
+        }
+    }
+
+    fn launchd_domain(&self) -> String {
+        match self.scope {
+            Scope::User => format!("gui/{}", current_uid()),
+            Scope::System => "system".to_string(),
+        }
+    }
+}
+
+/// The installed service, if any; a user install wins over a system one
+pub fn installed() -> Option<Service> {
+    let manager = Manager::current()?;
+    [Scope::User, Scope::System]
+        .into_iter()
+        .map(|scope| Service::new(manager, scope))
+        .find(Service::is_installed)
+}
+
+/// Write `file`, creating its directory and keeping it private if it holds keys
+pub fn write(file: &ServiceFile) -> Result<()> {
+    if let Some(parent) = file.path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    // Remove first so a fresh file gets the mode below before any key is written
+    fs::remove_file(&file.path).ok();
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(if file.private { 0o600 } else { 0o644 });
+    }
+    let mut handle = options.open(&file.path)
+        .with_context(|| format!("Failed to write {}", file.path.display()))?;
+    std::io::Write::write_all(&mut handle, file.contents.as_bytes())?;
+    Ok(())
+}
+
+/// Run one command from `Service::commands`, failing with its output
+pub fn run(command: &[String]) -> Result<()> {
+    let output = Command::new(&command[0])
+        .args(&command[1..])
+        .output()
+        .with_context(|| format!("Failed to run {}", command[0]))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{} failed: {}", command.join(" "), stderr.trim());
+    }
+    Ok(())
+}
+
+/// `KEY="value"` lines, as systemd's EnvironmentFile= reads them
+fn env_file_contents(env: &[(String, String)]) -> String {
+    let mut contents = String::from("# Written by port42 daemon install\n");
+    for (var, value) in env {
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+        contents.push_str(&format!("{}=\"{}\"\n", var, value));
+    }
+    contents
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(unix)]
+fn current_uid() -> u32 {
+    // SAFETY: getuid has no failure modes
+    unsafe { libc::getuid() }
+}
+
+#[cfg(not(unix))]
+fn current_uid() -> u32 {
+    0
+}
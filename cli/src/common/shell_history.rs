@@ -0,0 +1,46 @@
+//! Command history for the interactive shell
+//!
+//! Each command is kept once: running it again moves it to the end rather
+//! than adding a copy, so Ctrl+R and the arrow keys never wade through
+//! repeats. The oldest entries fall off past the configured size. Entries
+//! are numbered from 1, oldest first, for `history` and `history <N>`.
+
+use anyhow::Result;
+
+use crate::common::errors::Port42Error;
+
+/// Entries kept when `[shell] history_size` isn't set
+pub const DEFAULT_HISTORY_SIZE: usize = 1000;
+
+/// Keep only the latest occurrence of each entry, then the newest `max`
+pub fn dedup(entries: &[String], max: usize) -> Vec<String> {
+    let mut kept: Vec<String> = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        if !entries[i + 1..].contains(entry) {
+            kept.push(entry.clone());
+        }
+    }
+    let excess = kept.len().saturating_sub(max);
+    kept.split_off(excess)
+}
+
+/// The history after `line` runs: any earlier copy removed, `line` last
+pub fn remember(entries: &[String], line: &str, max: usize) -> Vec<String> {
+    let mut updated: Vec<String> = entries.iter().filter(|e| *e != line).cloned().collect();
+    updated.push(line.to_string());
+    let excess = updated.len().saturating_sub(max);
+    updated.split_off(excess)
+}
+
+/// The command a `history <N>` line asks to run again, or None for any other line
+pub fn recall(entries: &[String], line: &str) -> Result<Option<String>> {
+    let mut words = line.split_whitespace();
+    let (Some("history"), Some(index), None) = (words.next(), words.next(), words.next()) else {
+        return Ok(None);
+    };
+    let Ok(n) = index.parse::<usize>() else { return Ok(None) };
+    match n.checked_sub(1).and_then(|i| entries.get(i)) {
+        Some(entry) => Ok(Some(entry.clone())),
+        None => Err(Port42Error::Usage(format!("No history entry {}; 'history' lists 1 to {}", n, entries.len())).into()),
+    }
+}
@@ -0,0 +1,58 @@
+//! Multi-line input for the interactive shell
+//!
+//! A line ending in `\` or leaving a quote open continues on the next one,
+//! and a paste arrives as a single block, however many lines it has. The
+//! block is then cut into commands at newlines outside quotes. A quote only
+//! opens at the start of a word, so the apostrophe in `what's` never leaves
+//! the shell waiting for a closing one.
+
+/// Commands in a block of input, and whether it still needs more lines
+#[derive(Debug, Default, PartialEq)]
+pub struct ShellInput {
+    pub commands: Vec<String>,
+    pub incomplete: bool,
+}
+
+/// Cut a block of input into commands, joining `\` continuations.
+/// Blank lines and `#` comments are dropped.
+pub fn split_commands(input: &str) -> ShellInput {
+    let mut commands = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut incomplete = false;
+    let mut prev: Option<char> = None;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                current.push(c);
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '\\' && chars.peek() == Some(&'\n') => {
+                chars.next();
+                prev = Some('\n');
+                continue;
+            }
+            None if c == '\\' && chars.peek().is_none() => incomplete = true,
+            None if c == '\n' => commands.push(std::mem::take(&mut current)),
+            None if (c == '"' || c == '\'') && prev.is_none_or(char::is_whitespace) => {
+                quote = Some(c);
+                current.push(c);
+            }
+            None => current.push(c),
+        }
+        prev = Some(c);
+    }
+    commands.push(current);
+
+    ShellInput {
+        commands: commands.into_iter()
+            .map(|command| command.trim().to_string())
+            .filter(|command| !command.is_empty() && !command.starts_with('#'))
+            .collect(),
+        incomplete: incomplete || quote.is_some(),
+    }
+}
@@ -0,0 +1,124 @@
+//! Building blocks for the static HTML site written by `port42 export site`
+//!
+//! Pages are plain HTML with one shared stylesheet. Search runs entirely in
+//! the browser against an index embedded in `index.html`, so the site works
+//! when opened straight from disk.
+
+pub const STYLESHEET: &str = r#"body { font: 15px/1.5 -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 0; background: #0b1020; color: #d8dee9; }
+a { color: #88c0d0; text-decoration: none; }
+a:hover { text-decoration: underline; }
+header { padding: 1.2em 2em; background: #111833; border-bottom: 1px solid #2e3a5c; }
+header h1 { margin: 0; font-size: 1.3em; }
+main { max-width: 960px; margin: 0 auto; padding: 1.5em 2em 4em; }
+h2 { border-bottom: 1px solid #2e3a5c; padding-bottom: .3em; }
+.meta { color: #8a94ad; font-size: .9em; }
+.meta dt { float: left; clear: left; width: 8em; color: #6c7796; }
+.meta dd { margin-left: 8em; }
+pre { background: #111833; border: 1px solid #2e3a5c; padding: 1em; overflow-x: auto; font: 13px/1.45 ui-monospace, Menlo, monospace; }
+ul.items { list-style: none; padding: 0; }
+ul.items li { padding: .45em 0; border-bottom: 1px solid #1a2240; }
+ul.items .desc { color: #8a94ad; margin-left: .6em; }
+.message { margin: 1em 0; padding: .8em 1em; border-left: 3px solid #4c566a; background: #111833; white-space: pre-wrap; }
+.message.user { border-color: #a3be8c; }
+.message.assistant { border-color: #88c0d0; }
+.message .role { display: block; font-size: .8em; color: #6c7796; text-transform: uppercase; margin-bottom: .3em; }
+#search { width: 100%; padding: .6em .8em; font-size: 1em; background: #111833; color: inherit; border: 1px solid #2e3a5c; border-radius: 4px; }
+"#;
+
+/// Filters the index lists as you type; each `li` carries `data-search`
+const SEARCH_SCRIPT: &str = r#"<script>
+document.getElementById('search').addEventListener('input', function (e) {
+  var terms = e.target.value.toLowerCase().split(/\s+/).filter(Boolean);
+  document.querySelectorAll('li[data-search]').forEach(function (li) {
+    var text = li.getAttribute('data-search');
+    li.style.display = terms.every(function (t) { return text.indexOf(t) !== -1; }) ? '' : 'none';
+  });
+});
+</script>"#;
+
+/// One object on the index page
+#[derive(Debug, Clone)]
+pub struct SiteEntry {
+    pub title: String,
+    pub href: String,
+    pub description: String,
+    /// Extra text matched by search but not shown, e.g. the source
+    pub keywords: String,
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// File name for a VFS path: "/artifacts/docs/api.md" -> "docs-api-md.html"
+pub fn page_name(path: &str) -> String {
+    let mut slug = String::new();
+    for c in path.trim_matches('/').chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    format!("{}.html", if slug.is_empty() { "index" } else { slug })
+}
+
+/// Wrap a body in the shared layout. `root` is the relative path back to the
+/// site root ("" for top-level pages, "../" one level down).
+pub fn page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title} · Port 42</title>\n<link rel=\"stylesheet\" href=\"{root}style.css\">\n</head>\n<body>\n<header><h1><a href=\"{root}index.html\">🐬 Port 42</a> · {title}</h1></header>\n<main>\n{body}\n</main>\n</body>\n</html>\n",
+        title = escape_html(title),
+        root = root,
+        body = body,
+    )
+}
+
+/// The index page: a search box over every section's entries
+pub fn index_page(sections: &[(&str, Vec<SiteEntry>)], generated: &str) -> String {
+    let mut body = String::from("<input id=\"search\" type=\"search\" placeholder=\"Search memories, tools and artifacts...\" autofocus>\n");
+    for (heading, entries) in sections {
+        body.push_str(&format!("<h2>{} <span class=\"meta\">({})</span></h2>\n", escape_html(heading), entries.len()));
+        if entries.is_empty() {
+            body.push_str("<p class=\"meta\">Nothing here yet.</p>\n");
+            continue;
+        }
+        body.push_str("<ul class=\"items\">\n");
+        for entry in entries {
+            let search = format!("{} {} {}", entry.title, entry.description, entry.keywords).to_lowercase();
+            body.push_str(&format!(
+                "<li data-search=\"{}\"><a href=\"{}\">{}</a><span class=\"desc\">{}</span></li>\n",
+                escape_html(&search),
+                escape_html(&entry.href),
+                escape_html(&entry.title),
+                escape_html(&entry.description),
+            ));
+        }
+        body.push_str("</ul>\n");
+    }
+    body.push_str(&format!("<p class=\"meta\">Exported {}</p>\n{}", escape_html(generated), SEARCH_SCRIPT));
+    page("Archive", "", &body)
+}
+
+/// Metadata rows as a definition list, skipping empty values.
+/// Values are HTML, so callers escape plain text themselves.
+pub fn meta_list(rows: &[(&str, String)]) -> String {
+    let mut html = String::from("<dl class=\"meta\">\n");
+    for (label, value) in rows.iter().filter(|(_, v)| !v.is_empty()) {
+        html.push_str(&format!("<dt>{}</dt><dd>{}</dd>\n", escape_html(label), value));
+    }
+    html.push_str("</dl>\n");
+    html
+}
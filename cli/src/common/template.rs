@@ -0,0 +1,62 @@
+//! `{{variable}}` substitution for prompt templates and notification messages
+
+use anyhow::{Result, bail};
+use regex::Regex;
+use std::collections::HashMap;
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_-]*)\s*\}\}").expect("valid placeholder regex")
+}
+
+/// Parse repeated `--var key=value` flags
+pub fn parse_vars(vars: &[String]) -> Result<HashMap<String, String>> {
+    let mut parsed = HashMap::new();
+    for var in vars {
+        match var.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                parsed.insert(key.trim().to_string(), value.to_string());
+            }
+            _ => bail!("Invalid --var '{}'. Use key=value", var),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Variable names used in a template, in order of first appearance
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for cap in placeholder_regex().captures_iter(template) {
+        let name = cap[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Fill in every placeholder; unknown variables are an error rather than left blank
+pub fn render(template: &str, vars: &HashMap<String, String>) -> Result<String> {
+    let missing: Vec<String> = placeholders(template)
+        .into_iter()
+        .filter(|name| !vars.contains_key(name))
+        .collect();
+    if !missing.is_empty() {
+        bail!("Missing template variable{}: {} (pass --var {}=...)",
+            if missing.len() == 1 { "" } else { "s" },
+            missing.join(", "),
+            missing[0]);
+    }
+
+    let rendered = placeholder_regex().replace_all(template, |cap: &regex::Captures| {
+        vars[&cap[1]].clone()
+    });
+    Ok(rendered.into_owned())
+}
+
+/// Put text typed alongside a template after the rendered template
+pub fn append_extra(rendered: String, extra: Option<String>) -> String {
+    match extra {
+        Some(extra) if !extra.trim().is_empty() => format!("{}\n\n{}", rendered.trim_end(), extra),
+        _ => rendered,
+    }
+}
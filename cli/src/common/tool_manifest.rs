@@ -0,0 +1,112 @@
+//! Manifests for declaring many tools at once
+//!
+//! A manifest lists tool specs under `tools:` (or as a bare list). YAML is the
+//! usual format; `.json` and `.toml` files are read with their own parsers.
+//!
+//! ```yaml
+//! tools:
+//!   - name: git-haiku
+//!     transforms: [git, log, haiku]
+//!     prompt: Turn recent commits into haiku
+//!     refs:
+//!       - file:./README.md
+//! ```
+
+use anyhow::{Result, Context, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolSpec {
+    pub name: String,
+    /// A list, or a comma-separated string as `--transforms` takes
+    #[serde(default, deserialize_with = "list_or_csv")]
+    pub transforms: Vec<String>,
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default, alias = "references")]
+    pub refs: Vec<String>,
+    /// Overrides the provider given on the command line for this tool
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Read and check a manifest, returning its tools in declaration order
+pub fn load(path: &Path) -> Result<Vec<ToolSpec>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Could not read {}", path.display()))?;
+    parse(&content, path.extension().and_then(|e| e.to_str()).unwrap_or("yaml"))
+        .with_context(|| format!("Invalid tool manifest {}", path.display()))
+}
+
+/// Parse manifest text; `format` is a file extension
+pub fn parse(content: &str, format: &str) -> Result<Vec<ToolSpec>> {
+    let tools = match format {
+        "json" => from_value(serde_json::from_str(content)?)?,
+        "toml" => from_value(serde_json::to_value(toml::from_str::<toml::Value>(content)?)?)?,
+        // Typed straight from the text, so `name: 1.0` stays the string it was written as
+        _ => match serde_yaml::from_str::<serde_yaml::Value>(content)? {
+            serde_yaml::Value::Sequence(_) => serde_yaml::from_str(content)?,
+            serde_yaml::Value::Mapping(map) if map.contains_key("tools") => serde_yaml::from_str::<Manifest>(content)?.tools,
+            _ => bail!("Expected a list of tools under 'tools:'"),
+        },
+    };
+    validate(&tools)?;
+    Ok(tools)
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    tools: Vec<ToolSpec>,
+}
+
+fn from_value(value: Value) -> Result<Vec<ToolSpec>> {
+    let list = match value {
+        Value::Array(_) => value,
+        Value::Object(mut map) if map.contains_key("tools") => map.remove("tools").unwrap_or_default(),
+        _ => bail!("Expected a list of tools under 'tools:'"),
+    };
+    Ok(serde_json::from_value(list)?)
+}
+
+fn validate(tools: &[ToolSpec]) -> Result<()> {
+    if tools.is_empty() {
+        bail!("The manifest declares no tools");
+    }
+    let mut seen = HashSet::new();
+    for (n, tool) in tools.iter().enumerate() {
+        let valid = !tool.name.is_empty()
+            && tool.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            bail!("Tool #{} has an invalid name '{}'. Use letters, digits, '-' and '_'", n + 1, tool.name);
+        }
+        if !seen.insert(tool.name.as_str()) {
+            bail!("Tool '{}' is declared more than once", tool.name);
+        }
+        if tool.transforms.is_empty() && tool.prompt.is_none() {
+            bail!("Tool '{}' needs transforms or a prompt", tool.name);
+        }
+        if let Some(bad) = tool.refs.iter().find(|r| !r.contains(':')) {
+            bail!("Tool '{}' has an invalid reference '{}'. Use type:target", tool.name, bad);
+        }
+    }
+    Ok(())
+}
+
+fn list_or_csv<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ListOrCsv {
+        List(Vec<String>),
+        Csv(String),
+    }
+    Ok(match Option::<ListOrCsv>::deserialize(deserializer)? {
+        Some(ListOrCsv::List(items)) => items,
+        Some(ListOrCsv::Csv(csv)) => csv.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect(),
+        None => Vec::new(),
+    })
+}
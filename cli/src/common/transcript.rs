@@ -0,0 +1,88 @@
+//! Local transcripts of interactive sessions, written by `/save`
+
+use serde::Serialize;
+use std::path::Path;
+
+use crate::protocol::swim::{ArtifactSpec, CommandSpec};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Transcript {
+    pub session_id: String,
+    pub agent: String,
+    pub started: String,
+    pub saved: String,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptEntry {
+    /// "user" or "assistant"
+    pub role: String,
+    pub content: String,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command_spec: Option<CommandSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact_spec: Option<ArtifactSpec>,
+}
+
+impl TranscriptEntry {
+    pub fn new(role: &str, content: &str) -> Self {
+        Self {
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: chrono::Local::now().to_rfc3339(),
+            command_spec: None,
+            artifact_spec: None,
+        }
+    }
+}
+
+/// JSON for `.json` paths, Markdown for anything else
+pub fn is_json_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+impl Transcript {
+    pub fn render(&self, json: bool) -> String {
+        if json {
+            serde_json::to_string_pretty(self).unwrap_or_default()
+        } else {
+            self.to_markdown()
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Port 42 session {}\n\n", self.session_id);
+        out.push_str(&format!("- **Agent:** {}\n", self.agent));
+        out.push_str(&format!("- **Started:** {}\n", readable(&self.started)));
+        out.push_str(&format!("- **Saved:** {}\n", readable(&self.saved)));
+        let generated: Vec<String> = self.entries.iter()
+            .flat_map(|e| {
+                e.command_spec.iter().map(|c| format!("`{}` (command)", c.name))
+                    .chain(e.artifact_spec.iter().map(|a| format!("`{}` (artifact)", a.name)))
+            })
+            .collect();
+        if !generated.is_empty() {
+            out.push_str(&format!("- **Generated:** {}\n", generated.join(", ")));
+        }
+
+        for entry in &self.entries {
+            let speaker = if entry.role == "user" { "You" } else { self.agent.as_str() };
+            out.push_str(&format!("\n---\n\n### {} · {}\n\n{}\n", speaker, readable(&entry.timestamp), entry.content.trim_end()));
+            if let Some(ref spec) = entry.command_spec {
+                out.push_str(&format!("\n> 🔨 Crystallized command `{}` ({}): {}\n", spec.name, spec.language, spec.description));
+            }
+            if let Some(ref spec) = entry.artifact_spec {
+                out.push_str(&format!("\n> 📄 Created artifact `{}` ({}) at {}: {}\n", spec.name, spec.artifact_type, spec.path, spec.description));
+            }
+        }
+        out
+    }
+}
+
+fn readable(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
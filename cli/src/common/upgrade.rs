@@ -0,0 +1,322 @@
+//! Replacing this installation with a newer release
+//!
+//! Releases are published the way install.sh reads them: `version.txt` names
+//! the latest version and `releases/port42-<platform>-v<version>.tar.gz` holds
+//! `bin/port42`, `bin/port42d` and the agent files. Each archive has a
+//! `.sha256` next to it, and nothing is installed unless it matches. That
+//! checksum comes from the same place as the archive, so it catches a
+//! corrupt or truncated download but not a tampered release host; releases
+//! are not signed. Downloads and unpacking go through curl (or wget) and
+//! tar, as the installer's do, and tar reads the very bytes that were
+//! checked.
+//!
+//! `~/.port42` carries a layout number so a release that moves things around
+//! can bring older directories up to date; the new binary runs those steps,
+//! since only it knows the new layout.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::common::archive::sha256_hex;
+use crate::common::errors::Port42Error;
+use crate::common::utils::{create_private_file, make_executable};
+
+/// Where version.txt and releases/ are served from
+pub const DEFAULT_RELEASES_URL: &str = "https://raw.githubusercontent.com/gordonmattey/port42/main";
+
+/// Overrides DEFAULT_RELEASES_URL, e.g. for a mirror or a file:// directory
+pub const RELEASES_URL_VAR: &str = "PORT42_RELEASES_URL";
+
+/// The ~/.port42 layout this build expects
+pub const LAYOUT_VERSION: u32 = 1;
+
+const LAYOUT_FILE: &str = "layout";
+
+/// A step bringing ~/.port42 from `to - 1` up to `to`
+struct Migration {
+    to: u32,
+    summary: &'static str,
+    run: fn(&Path) -> Result<()>,
+}
+
+/// Add a step here, and bump LAYOUT_VERSION, when a release moves files under ~/.port42
+const MIGRATIONS: &[Migration] = &[];
+
+pub fn releases_url() -> String {
+    std::env::var(RELEASES_URL_VAR)
+        .ok()
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| DEFAULT_RELEASES_URL.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// This machine in release names, e.g. darwin-aarch64 or linux-x86_64
+pub fn platform() -> Option<String> {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os @ ("linux" | "windows") => os,
+        _ => return None,
+    };
+    let arch = match std::env::consts::ARCH {
+        arch @ ("x86_64" | "aarch64") => arch,
+        _ => return None,
+    };
+    Some(format!("{}-{}", os, arch))
+}
+
+/// Whether `candidate` is a later version than `current`, e.g. 0.1.10 > 0.1.9
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        version.trim().trim_start_matches('v')
+            .split(['.', '-'])
+            .map_while(|part| part.parse().ok())
+            .collect()
+    }
+    parts(candidate) > parts(current)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub version: String,
+    pub platform: String,
+    pub archive_url: String,
+}
+
+impl Release {
+    pub fn new(base: &str, version: &str, platform: &str) -> Self {
+        let version = version.trim().trim_start_matches('v').to_string();
+        let archive_url = format!("{}/releases/port42-{}-v{}.tar.gz", base, platform, version);
+        Self { version, platform: platform.to_string(), archive_url }
+    }
+
+    /// The release version.txt points at
+    pub fn latest(base: &str, platform: &str) -> Result<Self> {
+        let version = String::from_utf8(download(&format!("{}/version.txt", base))?)
+            .map_err(|_| Port42Error::Daemon("version.txt isn't text".to_string()))?;
+        if version.trim().is_empty() {
+            bail!(Port42Error::NotFound(format!("No version published at {}/version.txt", base)));
+        }
+        Ok(Self::new(base, &version, platform))
+    }
+
+    pub fn checksum_url(&self) -> String {
+        format!("{}.sha256", self.archive_url)
+    }
+
+    /// Download the archive and check it against its published checksum
+    pub fn fetch(&self) -> Result<Vec<u8>> {
+        let checksum = download(&self.checksum_url()).map_err(|e| Port42Error::NotFound(format!(
+            "No checksum published for v{} ({}), so it can't be verified: {}", self.version, self.platform, e
+        )))?;
+        let archive = download(&self.archive_url)?;
+        verify_checksum(&archive, &String::from_utf8_lossy(&checksum))?;
+        Ok(archive)
+    }
+}
+
+/// Fetch `url` with curl, or wget where there is no curl
+pub fn download(url: &str) -> Result<Vec<u8>> {
+    let agent = concat!("port42/", env!("CARGO_PKG_VERSION"));
+    let output = if which::which("curl").is_ok() {
+        Command::new("curl").args(["-sS", "-fL", "--max-time", "300", "-A", agent]).arg(url).output()
+    } else if which::which("wget").is_ok() {
+        Command::new("wget").args(["-q", "-O", "-", "-U", agent]).arg(url).output()
+    } else {
+        bail!("Downloading needs curl or wget");
+    }
+    .map_err(|e| anyhow!("could not run the downloader: {}", e))?;
+    if !output.status.success() {
+        bail!(Port42Error::Connection(format!(
+            "Could not fetch {}: {}", url, String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// `checksum` as sha256sum writes it: the hex digest, optionally followed by a file name
+pub fn verify_checksum(data: &[u8], checksum: &str) -> Result<()> {
+    let expected = checksum.split_whitespace().next().unwrap_or_default().to_ascii_lowercase();
+    if expected.len() != 64 || !expected.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("The published checksum isn't a SHA-256 digest");
+    }
+    let actual = sha256_hex(data);
+    if actual != expected {
+        bail!("Checksum mismatch: expected {}, got {}. Nothing was installed", expected, actual);
+    }
+    Ok(())
+}
+
+/// The binaries and agent files found in an unpacked release
+#[derive(Debug, Clone)]
+pub struct Unpacked {
+    pub cli: PathBuf,
+    pub daemon: PathBuf,
+    /// Files that go straight into ~/.port42, like agents.json
+    pub data: Vec<PathBuf>,
+}
+
+/// Unpack the gzipped tarball `archive` into `dir`, feeding tar the bytes
+/// themselves so nothing can change between checking and unpacking them
+pub fn unpack(archive: &[u8], dir: &Path) -> Result<Unpacked> {
+    let mut tar = Command::new("tar")
+        .arg("-xzf").arg("-")
+        .arg("-C").arg(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run tar")?;
+    // tar may stop reading early on a bad archive; its exit status says why
+    let _ = tar.stdin.take().expect("piped stdin").write_all(archive);
+    let output = tar.wait_with_output().context("Failed to run tar")?;
+    if !output.status.success() {
+        bail!("Failed to unpack the release: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    let binary = |name: &str| {
+        let path = dir.join("bin").join(format!("{}{}", name, std::env::consts::EXE_SUFFIX));
+        if path.is_file() { Ok(path) } else { Err(anyhow!("The release has no bin/{}", name)) }
+    };
+    let data = ["agents.json", "agent_guidance.md"].iter()
+        .map(|name| dir.join("daemon").join(name))
+        .filter(|path| path.is_file())
+        .collect();
+    Ok(Unpacked { cli: binary("port42")?, daemon: binary("port42d")?, data })
+}
+
+/// The version a binary reports with --version
+pub fn binary_version(binary: &Path) -> Result<String> {
+    let output = Command::new(binary).arg("--version").output()
+        .with_context(|| format!("Failed to run {}", binary.display()))?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    text.split_whitespace().last()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("{} didn't report a version", binary.display()))
+}
+
+/// Put each `(new, target)` binary in place, keeping the old ones in `backup`,
+/// and return each installed path with the SHA-256 of what was written there.
+/// Every new file is written next to its target first, so each swap is a
+/// rename; if staging fails nothing is left behind, and if a swap fails those
+/// already swapped are put back.
+pub fn swap(binaries: &[(&Path, &Path)], backup: &Path) -> Result<Vec<(PathBuf, String)>> {
+    fs::create_dir_all(backup)?;
+    let mut staged = Vec::new();
+    for (new, target) in binaries {
+        match stage(new, target, backup) {
+            Ok(entry) => staged.push(entry),
+            Err(e) => {
+                for (stage, _, _, _) in &staged {
+                    let _ = fs::remove_file(stage);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    let mut done: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for (stage, target, saved, _) in &staged {
+        if let Err(e) = replace(stage, target) {
+            // The backup may be on another filesystem, so it is copied back rather than moved
+            for (target, saved) in done.iter().rev() {
+                let _ = fs::copy(saved, target);
+            }
+            for (stage, _, _, _) in &staged {
+                let _ = fs::remove_file(stage);
+            }
+            return Err(e).with_context(|| format!("Failed to replace {}; nothing was changed", target.display()));
+        }
+        done.push((target.clone(), saved.clone()));
+    }
+    Ok(staged.into_iter().map(|(_, target, _, digest)| (target, digest)).collect())
+}
+
+/// Write `new` to a fresh `.<name>.new` beside `target`, check the copy hashes
+/// the same as the bytes read, and back up the current `target`.
+/// Returns (stage, target, backup, sha256).
+fn stage(new: &Path, target: &Path, backup: &Path) -> Result<(PathBuf, PathBuf, PathBuf, String)> {
+    let name = target.file_name().ok_or_else(|| anyhow!("{} isn't a file", target.display()))?.to_string_lossy();
+    let stage = target.with_file_name(format!(".{}.new", name));
+    let bytes = fs::read(new).with_context(|| format!("Failed to read {}", new.display()))?;
+    let digest = sha256_hex(&bytes);
+
+    // A stage left by an interrupted upgrade is ours to replace
+    let _ = fs::remove_file(&stage);
+    let written = create_private_file(&stage, &bytes, true)
+        .and_then(|_| make_executable(&stage))
+        .and_then(|_| {
+            let on_disk = sha256_hex(&fs::read(&stage)?);
+            if on_disk != digest {
+                bail!("{} changed while it was being written", stage.display());
+            }
+            Ok(())
+        });
+    let saved = backup.join(format!("{}.bak", name));
+    let backed_up = written.and_then(|_| {
+        if target.exists() {
+            fs::copy(target, &saved).with_context(|| format!("Failed to back up {}", target.display()))?;
+        }
+        Ok(())
+    });
+    if let Err(e) = backed_up {
+        let _ = fs::remove_file(&stage);
+        return Err(e).with_context(|| format!("Failed to stage {}", target.display()));
+    }
+    Ok((stage, target.to_path_buf(), saved, digest))
+}
+
+#[cfg(unix)]
+fn replace(new: &Path, target: &Path) -> Result<()> {
+    fs::rename(new, target)?;
+    Ok(())
+}
+
+/// A running .exe can't be overwritten, but it can be moved out of the way
+#[cfg(windows)]
+fn replace(new: &Path, target: &Path) -> Result<()> {
+    let old = target.with_extension("old");
+    let _ = fs::remove_file(&old);
+    if target.exists() {
+        fs::rename(target, &old)?;
+    }
+    if let Err(e) = fs::rename(new, target) {
+        let _ = fs::rename(&old, target);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// The layout `dir` was last brought up to; directories from before layouts were numbered are 1
+pub fn layout_version(dir: &Path) -> u32 {
+    fs::read_to_string(dir.join(LAYOUT_FILE))
+        .ok()
+        .and_then(|text| text.trim().parse().ok())
+        .unwrap_or(1)
+}
+
+/// Bring `dir` up to LAYOUT_VERSION, returning what was done
+pub fn migrate(dir: &Path) -> Result<Vec<&'static str>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let from = layout_version(dir);
+    if from > LAYOUT_VERSION {
+        bail!(Port42Error::Usage(format!(
+            "{} was laid out by a newer Port 42 (layout {}, this one knows {}); upgrade instead of going back",
+            dir.display(), from, LAYOUT_VERSION
+        )));
+    }
+
+    let mut done = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.to > from) {
+        (migration.run)(dir).with_context(|| format!("Layout migration failed: {}", migration.summary))?;
+        fs::write(dir.join(LAYOUT_FILE), migration.to.to_string())?;
+        done.push(migration.summary);
+    }
+    fs::write(dir.join(LAYOUT_FILE), LAYOUT_VERSION.to_string())?;
+    Ok(done)
+}
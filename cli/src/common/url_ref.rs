@@ -0,0 +1,257 @@
+//! `url:` references
+//!
+//! Pages are fetched here rather than by the daemon so they can be cached
+//! and trimmed before they reach the prompt. Options follow the address,
+//! each after a `!`:
+//!
+//! - `!raw`       send the page as fetched instead of its article text
+//! - `!max=20k`   cap the bytes sent (default `[refs] url_max_bytes`, 100 KB)
+//! - `!fresh`     fetch again even if the cache holds the page
+//!
+//! HTML is reduced to its readable text: scripts, navigation and other
+//! chrome are dropped and the `<article>` or `<main>` element is preferred
+//! to the whole body. Pages are kept as fetched in
+//! `~/.port42/cache/url/<sha256>.json` for `[refs] url_ttl_hours` (24 by
+//! default), so a later `!raw` or a different `!max` needs no refetch.
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{DateTime, Duration, Utc};
+use colored::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::common::cache::ResponseCache;
+use crate::common::references::truncate_context;
+use crate::config::Config;
+use crate::help_text;
+
+/// Bytes of a page sent when neither `!max=` nor the config says otherwise
+pub const DEFAULT_MAX_URL_BYTES: usize = 100 * 1024;
+
+const DEFAULT_TTL_HOURS: i64 = 24;
+const FETCH_TIMEOUT_SECS: &str = "30";
+/// Pages larger than this aren't downloaded at all
+const MAX_DOWNLOAD: &str = "10M";
+
+/// A `url:` target split into the address and its options
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrlSpec {
+    pub url: String,
+    pub raw: bool,
+    pub max_bytes: Option<usize>,
+    pub fresh: bool,
+}
+
+impl UrlSpec {
+    /// Options are peeled off the end while they parse, so a `!` that is
+    /// part of the address stays with it
+    pub fn parse(target: &str) -> Result<Self> {
+        let mut spec = UrlSpec { url: target.trim().to_string(), raw: false, max_bytes: None, fresh: false };
+        while let Some((rest, option)) = spec.url.rsplit_once('!') {
+            match option {
+                "raw" => spec.raw = true,
+                "fresh" => spec.fresh = true,
+                _ => match option.strip_prefix("max=") {
+                    Some(size) => spec.max_bytes = Some(parse_size(size)?),
+                    None => break,
+                },
+            }
+            spec.url = rest.to_string();
+        }
+        if !(spec.url.starts_with("https://") || spec.url.starts_with("http://")) {
+            bail!("url: needs an http(s) address, e.g. url:https://example.com/docs");
+        }
+        Ok(spec)
+    }
+}
+
+/// `20000`, `20k` or `1m`
+pub fn parse_size(size: &str) -> Result<usize> {
+    let lower = size.trim().to_ascii_lowercase();
+    let (digits, unit) = match lower.strip_suffix('k') {
+        Some(digits) => (digits, 1024),
+        None => match lower.strip_suffix('m') {
+            Some(digits) => (digits, 1024 * 1024),
+            None => (lower.as_str(), 1),
+        },
+    };
+    match digits.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n * unit),
+        _ => bail!("'{}' is not a size; use bytes or a k/m suffix, e.g. max=20k", size),
+    }
+}
+
+/// A page as the server sent it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchedPage {
+    pub url: String,
+    pub content_type: String,
+    pub body: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl FetchedPage {
+    pub fn is_html(&self) -> bool {
+        self.content_type.contains("html")
+            || self.body.trim_start().get(..15).is_some_and(|start| start.to_ascii_lowercase().starts_with("<!doctype html"))
+    }
+}
+
+/// What a `url:` reference puts into the prompt
+#[derive(Debug, Clone)]
+pub struct UrlContent {
+    pub content: String,
+    /// Article text was extracted from HTML
+    pub extracted: bool,
+    /// Served from the local cache
+    pub cached: bool,
+}
+
+/// Fetch (or recall) the page for a `url:` target and reduce it to what the
+/// AI should see
+pub fn resolve(target: &str) -> Result<UrlContent> {
+    let spec = UrlSpec::parse(target)?;
+    let settings = Config::load_or_default().refs.unwrap_or_default();
+    let ttl = Duration::hours(settings.url_ttl_hours.unwrap_or(DEFAULT_TTL_HOURS));
+
+    let cached = if spec.fresh { None } else { cached_page(&spec.url, ttl) };
+    let (page, from_cache) = match cached {
+        Some(page) => (page, true),
+        None => {
+            let page = fetch(&spec.url)?;
+            // A cache that can't be written only costs a refetch next time
+            let _ = store(&page);
+            (page, false)
+        }
+    };
+
+    let extracted = !spec.raw && page.is_html();
+    let text = if extracted { extract_article(&page.body) } else { page.body };
+    let cap = spec.max_bytes.or(settings.url_max_bytes).unwrap_or(DEFAULT_MAX_URL_BYTES);
+    let total = text.len();
+    let (content, truncated) = truncate_context(text, cap);
+    if truncated {
+        eprintln!("{}", help_text::format_url_ref_truncated(&spec.url, total, cap).yellow());
+    }
+    Ok(UrlContent { content, extracted, cached: from_cache })
+}
+
+fn fetch(url: &str) -> Result<FetchedPage> {
+    // The content type follows the body on its own line
+    let output = Command::new("curl")
+        .args(["-sS", "-fL", "--max-time", FETCH_TIMEOUT_SECS, "--max-filesize", MAX_DOWNLOAD])
+        .args(["-A", concat!("port42/", env!("CARGO_PKG_VERSION")), "-w", "\n%{content_type}"])
+        .arg(url)
+        .output()
+        .map_err(|e| anyhow!("could not run curl: {}", e))?;
+    if !output.status.success() {
+        bail!("Could not fetch {}: {}", url, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (body, content_type) = text.rsplit_once('\n').unwrap_or((&text, ""));
+    Ok(FetchedPage {
+        url: url.to_string(),
+        content_type: content_type.trim().to_ascii_lowercase(),
+        body: body.to_string(),
+        fetched_at: Utc::now(),
+    })
+}
+
+pub fn cache_dir() -> PathBuf {
+    crate::config::port42_dir().join("cache").join("url")
+}
+
+fn entry_path(url: &str) -> PathBuf {
+    cache_dir().join(format!("{}.json", ResponseCache::key(&[url])))
+}
+
+fn cached_page(url: &str, ttl: Duration) -> Option<FetchedPage> {
+    let content = fs::read_to_string(entry_path(url)).ok()?;
+    let page: FetchedPage = serde_json::from_str(&content).ok()?;
+    (Utc::now() - page.fetched_at <= ttl).then_some(page)
+}
+
+fn store(page: &FetchedPage) -> Result<()> {
+    fs::create_dir_all(cache_dir())?;
+    fs::write(entry_path(&page.url), serde_json::to_string(page)?)?;
+    Ok(())
+}
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid pattern"))
+}
+
+/// The readable text of an HTML page: its title, then the article, main
+/// element or body with markup, scripts and page chrome removed
+pub fn extract_article(html: &str) -> String {
+    static TITLE: OnceLock<Regex> = OnceLock::new();
+    static COMMENT: OnceLock<Regex> = OnceLock::new();
+    static HEADING: OnceLock<Regex> = OnceLock::new();
+    static ITEM: OnceLock<Regex> = OnceLock::new();
+    static BREAK: OnceLock<Regex> = OnceLock::new();
+    static BLOCK: OnceLock<Regex> = OnceLock::new();
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    static BLANK_LINES: OnceLock<Regex> = OnceLock::new();
+
+    let title = regex(&TITLE, r"(?is)<title[^>]*>(.*?)</title>")
+        .captures(html)
+        .map(|c| decode_entities(c[1].trim()));
+
+    let mut page = regex(&COMMENT, r"(?s)<!--.*?-->").replace_all(html, "").into_owned();
+    for tag in ["head", "script", "style", "noscript", "svg", "template", "iframe", "nav", "header", "footer", "aside", "form"] {
+        let chrome = Regex::new(&format!(r"(?is)<{0}\b.*?</{0}\s*>", tag)).expect("valid pattern");
+        page = chrome.replace_all(&page, "").into_owned();
+    }
+    let body = ["article", "main", "body"]
+        .iter()
+        .find_map(|tag| inner(&page, tag))
+        .unwrap_or(&page);
+
+    let text = regex(&HEADING, r"(?i)<h[1-6][^>]*>").replace_all(body, "\n\n# ");
+    let text = regex(&ITEM, r"(?i)<li[^>]*>").replace_all(&text, "\n- ");
+    let text = regex(&BREAK, r"(?i)<br\s*/?>|</tr\s*>").replace_all(&text, "\n");
+    let text = regex(&BLOCK, r"(?i)</(p|div|h[1-6]|pre|blockquote|section|table|ul|ol)\s*>").replace_all(&text, "\n\n");
+    let text = decode_entities(&regex(&TAG, r"(?s)<[^>]+>").replace_all(&text, ""));
+
+    let lines: Vec<String> = text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    let text = regex(&BLANK_LINES, r"\n{3,}").replace_all(lines.join("\n").trim(), "\n\n").into_owned();
+    match title {
+        Some(title) if !title.is_empty() && !text.starts_with(&format!("# {}", title)) => format!("# {}\n\n{}", title, text),
+        _ => text,
+    }
+}
+
+/// Between the first `<tag ...>` and the last `</tag>`
+fn inner<'a>(html: &'a str, tag: &str) -> Option<&'a str> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find(&format!("<{}", tag))?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = lower.rfind(&format!("</{}", tag))?;
+    (end >= start).then(|| &html[start..end])
+}
+
+fn decode_entities(text: &str) -> String {
+    static ENTITY: OnceLock<Regex> = OnceLock::new();
+    regex(&ENTITY, r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").replace_all(text, |c: &regex::Captures| {
+        let name = &c[1];
+        let decoded = match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => name.strip_prefix("#x").or_else(|| name.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16).ok())
+                .unwrap_or_else(|| name.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        decoded.map(String::from).unwrap_or_else(|| c[0].to_string())
+    }).into_owned()
+}
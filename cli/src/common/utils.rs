@@ -1 +1,155 @@
-// Currently no utilities - keeping module for future use
\ No newline at end of file
+use anyhow::{Result, bail};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use std::path::{Path, PathBuf};
+
+/// Parse a `--since` value: a relative span (`30m`, `12h`, `7d`, `4w`)
+/// or an absolute date (`YYYY-MM-DD`) or RFC 3339 timestamp.
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is valid");
+        return Ok(Utc.from_utc_datetime(&midnight));
+    }
+
+    if value.len() >= 2 {
+        let (amount, unit) = value.split_at(value.len() - 1);
+        if let Ok(amount) = amount.parse::<i64>() {
+            let span = match unit {
+                "m" => Some(Duration::minutes(amount)),
+                "h" => Some(Duration::hours(amount)),
+                "d" => Some(Duration::days(amount)),
+                "w" => Some(Duration::weeks(amount)),
+                _ => None,
+            };
+            if let Some(span) = span {
+                return Ok(Utc::now() - span);
+            }
+        }
+    }
+
+    bail!("Invalid time '{}'. Use a span like 7d or 12h, or a date like 2024-06-01", value)
+}
+
+/// Midnight UTC on the first day of the current month
+pub fn start_of_month() -> DateTime<Utc> {
+    let first = Utc::now().date_naive().with_day(1).expect("first of month is valid");
+    Utc.from_utc_datetime(&first.and_hms_opt(0, 0, 0).expect("midnight is valid"))
+}
+
+/// Split on whitespace, keeping "quoted phrases" together
+pub fn split_words(input: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_word = false;
+    for c in input.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_word = true;
+            }
+            None if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        bail!("Unclosed quote in '{}'", input);
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+/// A VFS path in canonical form: rooted, no empty, `.` or `..` segments,
+/// no trailing slash. `//commands/`, `commands` and `/tools/../commands`
+/// all come out as `/commands`.
+pub fn normalize_vfs_path(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    format!("/{}", segments.join("/"))
+}
+
+/// Let everyone run `path` (0755); nothing to do where files have no such bit
+#[cfg(unix)]
+pub fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// A fresh directory under the system temp dir that only this user can
+/// enter (0700). Fails rather than reuse a path that already exists, so
+/// nobody can plant files in it ahead of time.
+pub fn private_dir(purpose: &str) -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("port42-{}-{}", purpose, uuid::Uuid::new_v4()));
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(&dir)
+        .map_err(|e| anyhow::anyhow!("Could not create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// Create `dir` and any missing parents, and make `dir` itself 0700 even
+/// if it was already there
+pub fn create_private_dir_all(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| anyhow::anyhow!("Could not create {}: {}", dir.display(), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// Write `content` to a file that must not exist yet, readable only by
+/// this user; `executable` adds the user's execute bit (0700 instead of 0600)
+pub fn create_private_file(path: &Path, content: &[u8], executable: bool) -> Result<()> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(if executable { 0o700 } else { 0o600 });
+    }
+    #[cfg(not(unix))]
+    let _ = executable;
+    let mut file = options.open(path)
+        .map_err(|e| anyhow::anyhow!("Could not create {}: {}", path.display(), e))?;
+    file.write_all(content)
+        .map_err(|e| anyhow::anyhow!("Could not write {}: {}", path.display(), e))?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! Forwarding daemon events to a webhook
+//!
+//! `port42 events forward` POSTs each event as the same JSON object
+//! `events tail --json` prints. With a secret, the body is signed the way
+//! GitHub signs its webhooks: `X-Port42-Signature: sha256=<hex HMAC-SHA256
+//! of the body>`, so a receiver can check it came from someone holding the
+//! secret. Failed deliveries are retried with backoff, then reported and
+//! skipped; one bad delivery never stops the forwarding.
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+
+use crate::client::RetryPolicy;
+use crate::common::notify;
+use crate::protocol::events::DaemonEvent;
+
+pub const SIGNATURE_HEADER: &str = "X-Port42-Signature";
+
+pub struct Forwarder {
+    pub url: String,
+    pub secret: Option<String>,
+    pub retry: RetryPolicy,
+}
+
+impl Forwarder {
+    /// Deliver one event, retrying failures; returns how many attempts it took
+    pub fn deliver(&self, event: &DaemonEvent) -> Result<u32> {
+        let body = serde_json::to_string(event)?;
+        let headers = self.headers(event, &body);
+        let mut retry = 0;
+        loop {
+            match notify::post(&self.url, &body, &headers) {
+                Ok(()) => return Ok(retry + 1),
+                Err(_) if retry < self.retry.max_retries => {
+                    std::thread::sleep(self.retry.delay(retry));
+                    retry += 1;
+                }
+                Err(e) => return Err(e.context(format!("gave up after {} attempts", retry + 1))),
+            }
+        }
+    }
+
+    /// The same delivery id on every attempt, so receivers can drop repeats
+    pub fn headers(&self, event: &DaemonEvent, body: &str) -> Vec<String> {
+        let mut headers = vec![
+            format!("X-Port42-Event: {}", event.kind.label()),
+            format!("X-Port42-Delivery: {}", uuid::Uuid::new_v4()),
+        ];
+        if let Some(ref secret) = self.secret {
+            headers.push(format!("{}: sha256={}", SIGNATURE_HEADER, sign(secret.as_bytes(), body.as_bytes())));
+        }
+        headers
+    }
+}
+
+/// Hex HMAC-SHA256 of `body` under `secret` (RFC 2104)
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    const BLOCK: usize = 64;
+    let mut key = if secret.len() > BLOCK { Sha256::digest(secret).to_vec() } else { secret.to_vec() };
+    key.resize(BLOCK, 0);
+    let pad = |byte: u8| key.iter().map(|k| k ^ byte).collect::<Vec<u8>>();
+    let inner = Sha256::new().chain_update(pad(0x36)).chain_update(body).finalize();
+    let outer = Sha256::new().chain_update(pad(0x5c)).chain_update(inner).finalize();
+    format!("{:x}", outer)
+}
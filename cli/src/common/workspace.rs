@@ -0,0 +1,79 @@
+//! What the AI is told about the directory a session runs in
+//!
+//! With `[session] workspace_context = true`, swim messages carry the
+//! working directory, its git branch and the names at its top level, so the
+//! AI knows which project it is in without being handed a reference. Only
+//! names go out, never contents; hidden entries are left out and the
+//! listing stops at `MAX_ENTRIES`.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::Config;
+
+/// Top-level names listed before the rest are only counted
+pub const MAX_ENTRIES: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceContext {
+    pub cwd: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    /// Sorted names; directories end in `/`
+    pub files: Vec<String>,
+    /// Entries beyond `MAX_ENTRIES` that were left out
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub omitted: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// Whether sessions carry workspace context unless told not to
+pub fn is_enabled(config: &Config) -> bool {
+    config.session.as_ref().and_then(|s| s.workspace_context).unwrap_or(false)
+}
+
+/// Describe `dir`. Anything that can't be read is simply left out
+pub fn collect(dir: &Path) -> WorkspaceContext {
+    let mut files: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    if name.starts_with('.') {
+                        return None;
+                    }
+                    let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+                    Some(if is_dir { format!("{}/", name) } else { name })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    let omitted = files.len().saturating_sub(MAX_ENTRIES);
+    files.truncate(MAX_ENTRIES);
+
+    WorkspaceContext {
+        cwd: dir.display().to_string(),
+        git_branch: git_branch(dir),
+        files,
+        omitted,
+    }
+}
+
+/// The checked-out branch, `HEAD` when detached, or None outside a work tree
+fn git_branch(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()
+        .ok()?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !branch.is_empty()).then_some(branch)
+}
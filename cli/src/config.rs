@@ -0,0 +1,779 @@
+//! User configuration for the Port 42 CLI
+//!
+//! Settings live in `~/.port42/config.toml`. A missing file is not an
+//! error - every setting has a sensible default. Inside a project created
+//! with `port42 init`, the project's settings are layered on top, and
+//! environment variables (see [`ENV_OVERRIDES`]) and flags win over both.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::common::errors::Port42Error;
+use crate::project::{Project, ProjectConfig};
+
+const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Daemon port used when no --port flag is given (default: detected)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    /// Agent `swim` talks to when its first argument isn't an agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_agent: Option<String>,
+
+    /// Output format when no --output or --json flag is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+
+    /// When to color output: auto (default, only on a terminal), always or never
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+
+    /// Syntax highlighting theme for `cat`: default, ocean, ember or mono
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+
+    /// Default AI provider used when no --provider flag is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+
+    /// Default model passed to the provider when no --model flag is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Settings for the `local` (Ollama) provider
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local: Option<LocalConfig>,
+
+    /// Providers to retry, in order, when the primary is unavailable.
+    /// Entries are "provider" or "provider/model".
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fallback: Vec<String>,
+
+    /// Per-agent overrides, keyed by agent name (e.g. "@ai-muse")
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub agents: BTreeMap<String, AgentDefaults>,
+
+    /// Command aliases, e.g. `sw = "swim @ai-engineer"`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+
+    /// Response cache settings (off unless enabled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheConfig>,
+
+    /// Usage reporting settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageConfig>,
+
+    /// How `url:` and directory `file:` references are fetched and trimmed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refs: Option<RefsConfig>,
+
+    /// Limits applied to each swim/possess session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<SessionConfig>,
+
+    /// Which agent or tool handles each installed git hook
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitConfig>,
+
+    /// How long to queue requests when a provider is rate limiting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitConfig>,
+
+    /// How daemon requests are resent after a dropped connection or timeout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryConfig>,
+
+    /// How long to wait for the daemon, by request kind
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeouts: Option<TimeoutConfig>,
+
+    /// Which bash commands an AI may run without asking
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval: Option<ApprovalConfig>,
+
+    /// Webhooks posted to when notable things happen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify: Option<NotifyConfig>,
+
+    /// Defaults for `port42 digest`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<DigestConfig>,
+
+    /// Interactive shell settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<ShellConfig>,
+
+    /// Profile used when no --profile flag is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+
+    /// Daemons to talk to by name with --profile, e.g. `[profiles.work]`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Replay identical one-shot prompts and declarations from ~/.port42/cache
+    pub enabled: bool,
+
+    /// How long a cached response stays valid (default 24)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl_hours: Option<i64>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefsConfig {
+    /// How long a fetched page is reused from ~/.port42/cache/url (default 24)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url_ttl_hours: Option<i64>,
+
+    /// Most bytes of a page sent to the AI unless `!max=` says otherwise (default 102400)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url_max_bytes: Option<usize>,
+
+    /// Most bytes sent of any one file a directory or glob expands to (default 32768)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_max_bytes: Option<usize>,
+
+    /// Most bytes sent for all the files of one directory or glob (default 204800)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_max_bytes: Option<usize>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageConfig {
+    /// Warn in `port42 usage` when month-to-date spend nears this (USD)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_budget: Option<f64>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Token budget per session; warns at 80% and asks before going over
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+
+    /// Send the working directory, git branch and top-level file names with
+    /// swim messages (off unless set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_context: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// Host name or address of the daemon (default 127.0.0.1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+
+    /// Daemon port (default: detected, as without a profile)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    /// Connect over TLS; an empty `[profiles.<name>.tls]` table is enough
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM certificates to trust instead of the system's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_file: Option<PathBuf>,
+
+    /// SHA-256 fingerprint of the daemon's certificate, hex with or without
+    /// colons; a pinned certificate is trusted whoever signed it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin_sha256: Option<String>,
+
+    /// Name the certificate should carry, when it isn't the host
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShellConfig {
+    /// Commands kept in ~/.port42/shell_history (default 1000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history_size: Option<usize>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitConfig {
+    /// Handlers keyed by hook name, e.g. "prepare-commit-msg"
+    pub hooks: BTreeMap<String, GitHookConfig>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitHookConfig {
+    /// Agent asked to handle the hook (default @ai-engineer)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+
+    /// Port 42 command run instead of an agent; gets the diff on stdin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+
+    /// Replaces the built-in instructions sent to the agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+
+    /// Set to false to keep the hook installed but idle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Times to wait and resend before giving up (default 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_waits: Option<u32>,
+
+    /// Longest single wait in seconds (default 120)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_delay_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    /// Seconds to wait for AI generation: swim and declare (default 300)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ai_secs: Option<u64>,
+
+    /// Seconds to wait for filesystem operations like ls and cat (default 30)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vfs_secs: Option<u64>,
+
+    /// Seconds to wait for search (default 60)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_secs: Option<u64>,
+
+    /// Seconds to wait for everything else (default 30)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApprovalConfig {
+    /// never, ask (default) or always, for commands not on the allowlist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bash: Option<crate::common::approval::BashApproval>,
+
+    /// Command patterns approved without asking, matched word by word, e.g.
+    /// "git status", "git log --oneline -n ?" or "ls *". Spell out the
+    /// options you allow; a bare `*` word never matches an option.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Resends after the first attempt (default 2, 0 disables)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+
+    /// Wait before the first resend in milliseconds, doubled each time (default 250)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_delay_ms: Option<u64>,
+
+    /// Longest single wait in milliseconds (default 4000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_delay_ms: Option<u64>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Where notifications go; each sink picks its own events
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sinks: Vec<NotifySink>,
+
+    /// Message templates keyed by event name, using {{variable}} placeholders
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, String>,
+
+    /// Sessions at least this long count as "long" (default 30)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_session_minutes: Option<u64>,
+
+    /// Commands `port42 notify watch` runs when daemon events happen
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<NotifyHook>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestConfig {
+    /// Hours covered by each digest (default 24)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hours: Option<u64>,
+
+    /// Agent that writes the narrative (default @ai-analyst)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+
+    /// Address the digest is mailed to via sendmail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+
+    /// Post the digest to notification sinks subscribed to "digest"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifySink {
+    pub name: String,
+
+    /// "slack", "discord" or "webhook" (generic JSON)
+    pub kind: String,
+
+    pub url: String,
+
+    /// Events to send, e.g. ["tool_crystallized"]; empty means all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+}
+
+/// `[[notify.hooks]]`: run `exec` through the shell whenever `on` happens
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NotifyHook {
+    /// Event name, e.g. "tool_created"
+    pub on: String,
+
+    pub exec: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AgentDefaults {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Fallback chain for this agent; replaces the global one (empty disables it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalConfig {
+    /// Ollama endpoint, e.g. http://localhost:11434
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+
+    /// Model to run when none is given explicitly
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl Config {
+    /// Load configuration from ~/.port42/config.toml, plus the current project's
+    pub fn load() -> Result<Self> {
+        let mut config = Self::load_user()?;
+        if let Some(project) = Project::discover() {
+            config.apply_project(&project.config);
+        }
+        Ok(config)
+    }
+
+    /// Load only ~/.port42/config.toml
+    pub fn load_user() -> Result<Self> {
+        let path = config_path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid configuration in {}", path.display()))
+    }
+
+    /// Layer a project's provider, model and agent settings over these
+    pub fn apply_project(&mut self, project: &ProjectConfig) {
+        if project.provider.is_some() {
+            self.provider = project.provider.clone();
+        }
+        if project.model.is_some() {
+            self.model = project.model.clone();
+        }
+        for (agent, overrides) in &project.agents {
+            let key = self.agents.keys()
+                .find(|k| k.trim_start_matches('@') == agent.trim_start_matches('@'))
+                .cloned()
+                .unwrap_or_else(|| agent.clone());
+            let defaults = self.agents.entry(key).or_default();
+            if overrides.provider.is_some() {
+                defaults.provider = overrides.provider.clone();
+            }
+            if overrides.model.is_some() {
+                defaults.model = overrides.model.clone();
+            }
+            if overrides.fallback.is_some() {
+                defaults.fallback = overrides.fallback.clone();
+            }
+        }
+    }
+
+    /// Defaults configured for an agent; the leading '@' is optional in the file
+    pub fn agent_defaults(&self, agent: &str) -> Option<&AgentDefaults> {
+        let bare = agent.trim_start_matches('@');
+        self.agents
+            .get(agent)
+            .or_else(|| self.agents.get(bare))
+            .or_else(|| self.agents.get(&format!("@{}", bare)))
+    }
+
+    /// Handler settings for a git hook, defaulted if not configured
+    pub fn git_hook(&self, hook: &str) -> GitHookConfig {
+        self.git.as_ref()
+            .and_then(|git| git.hooks.get(hook))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The profile called `name`
+    pub fn profile(&self, name: &str) -> Result<&ProfileConfig> {
+        self.profiles.get(name).ok_or_else(|| {
+            let known = if self.profiles.is_empty() {
+                "none are defined".to_string()
+            } else {
+                format!("try {}", self.profiles.keys().cloned().collect::<Vec<_>>().join(", "))
+            };
+            Port42Error::Usage(format!("No profile named '{}' in {} ({})", name, config_path().display(), known)).into()
+        })
+    }
+
+    /// Load configuration, warning and falling back to defaults on errors
+    pub fn load_or_default() -> Self {
+        match Self::load() {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("⚠️  {:#}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Settings an environment variable overrides, and the variable that does
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("port", "PORT42_PORT"),
+    ("profile", "PORT42_PROFILE"),
+    ("provider", "PORT42_PROVIDER"),
+    ("model", "PORT42_MODEL"),
+    ("output", "PORT42_OUTPUT"),
+    ("color", "PORT42_COLOR"),
+    ("theme", "PORT42_THEME"),
+    ("retry.max_retries", "PORT42_RETRIES"),
+    ("approval.bash", "PORT42_APPROVE_BASH"),
+    ("timeouts.ai_secs", "PORT42_TIMEOUT_AI"),
+    ("timeouts.vfs_secs", "PORT42_TIMEOUT_VFS"),
+    ("timeouts.search_secs", "PORT42_TIMEOUT_SEARCH"),
+    ("timeouts.other_secs", "PORT42_TIMEOUT_OTHER"),
+];
+
+pub const COLOR_CHOICES: &[&str] = &["always", "auto", "never"];
+
+pub const OUTPUT_CHOICES: &[&str] = &["plain", "json", "table", "yaml"];
+
+/// The environment variable overriding `key`, if one is set
+pub fn env_override(key: &str) -> Option<(&'static str, String)> {
+    let (_, var) = ENV_OVERRIDES.iter().find(|(k, _)| *k == key)?;
+    std::env::var(var).ok().filter(|v| !v.is_empty()).map(|v| (*var, v))
+}
+
+/// Parse a value typed on the command line: numbers, booleans and arrays
+/// keep their TOML type, anything else is a string
+pub fn parse_value(raw: &str) -> toml_edit::Value {
+    match raw.parse::<toml_edit::Value>() {
+        Ok(value) if !matches!(value, toml_edit::Value::Datetime(_)) => value,
+        _ => toml_edit::Value::from(raw),
+    }
+}
+
+/// Check settings with a fixed set of values, beyond what parsing catches
+fn check_choices(config: &Config) -> Result<()> {
+    if let Some(ref color) = config.color {
+        if !COLOR_CHOICES.contains(&color.as_str()) {
+            anyhow::bail!("color must be one of {}, not '{}'", COLOR_CHOICES.join(", "), color);
+        }
+    }
+    if let Some(ref theme) = config.theme {
+        let themes = crate::display::highlight::THEME_NAMES;
+        if !themes.contains(&theme.as_str()) {
+            anyhow::bail!("theme must be one of {}, not '{}'", themes.join(", "), theme);
+        }
+    }
+    if let Some(ref output) = config.output {
+        if !OUTPUT_CHOICES.contains(&output.as_str()) {
+            anyhow::bail!("output must be one of {}, not '{}'", OUTPUT_CHOICES.join(", "), output);
+        }
+    }
+    if let Some(ref provider) = config.provider {
+        crate::common::providers::validate_provider(provider)?;
+    }
+    for (name, profile) in &config.profiles {
+        if let Some(pin) = profile.tls.as_ref().and_then(|tls| tls.pin_sha256.as_deref()) {
+            if crate::common::endpoint::normalize_fingerprint(pin).is_none() {
+                anyhow::bail!("profiles.{}.tls.pin_sha256 must be a SHA-256 fingerprint (64 hex digits), not '{}'", name, pin);
+            }
+        }
+    }
+    if let Some(ref profile) = config.profile {
+        config.profile(profile)?;
+    }
+    Ok(())
+}
+
+/// Parse and check config.toml content without writing it
+pub fn validate(content: &str) -> Result<Config> {
+    let config: Config = toml::from_str(content)?;
+    check_choices(&config)?;
+    Ok(config)
+}
+
+/// Set a dotted key (e.g. `timeouts.ai_secs`) in `content`, returning the new
+/// file. Comments and formatting elsewhere are kept.
+pub fn set_in(content: &str, key: &str, value: toml_edit::Value) -> Result<String> {
+    use toml_edit::{DocumentMut, Item, Table};
+
+    let mut doc: DocumentMut = content.parse().context("Invalid configuration")?;
+    let parts = split_key(key)?;
+    let (last, tables) = parts.split_last().unwrap();
+    let mut table = doc.as_table_mut();
+    for part in tables {
+        let item = table.entry(part.as_str()).or_insert_with(|| {
+            let mut t = Table::new();
+            t.set_implicit(true);
+            Item::Table(t)
+        });
+        table = item.as_table_mut()
+            .with_context(|| format!("'{}' is not a table", part))?;
+    }
+    let empty = matches!(value, toml_edit::Value::Array(ref a) if a.is_empty());
+    table.insert(last.as_str(), Item::Value(value));
+
+    let updated = doc.to_string();
+    let config = validate(&updated).with_context(|| format!("Can't set {}", key))?;
+    // Unknown keys parse fine and are then dropped, so look for the value
+    if get_in(&config, key).is_none() && !empty {
+        return Err(Port42Error::Usage(format!("Unknown setting '{}'", key)).into());
+    }
+    Ok(updated)
+}
+
+/// Look up a dotted key in loaded settings
+pub fn get_in(config: &Config, key: &str) -> Option<toml::Value> {
+    let mut value = toml::Value::try_from(config).ok()?;
+    for part in split_key(key).ok()? {
+        value = value.get(&part)?.clone();
+    }
+    Some(value)
+}
+
+/// Split a dotted key, where quoted parts may hold dots or '@':
+/// `agents."@ai-muse".model`
+pub fn split_key(key: &str) -> Result<Vec<String>> {
+    let mut parts = vec![String::new()];
+    let mut quoted = false;
+    for c in key.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '.' if !quoted => parts.push(String::new()),
+            c => parts.last_mut().unwrap().push(c),
+        }
+    }
+    if quoted || parts.iter().any(String::is_empty) {
+        anyhow::bail!("Invalid key '{}'", key);
+    }
+    Ok(parts)
+}
+
+/// Write an agent's defaults into config.toml, keeping the rest of the file
+/// (comments and formatting included) as the user left it
+pub fn set_agent_defaults(agent: &str, defaults: &AgentDefaults) -> Result<()> {
+    use toml_edit::{value, Array, DocumentMut, Item, Table};
+
+    let path = config_path();
+    let content = if path.exists() {
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let mut doc: DocumentMut = content.parse()
+        .with_context(|| format!("Invalid configuration in {}", path.display()))?;
+
+    let mut entry = Table::new();
+    if let Some(ref provider) = defaults.provider {
+        entry["provider"] = value(provider.as_str());
+    }
+    if let Some(ref model) = defaults.model {
+        entry["model"] = value(model.as_str());
+    }
+    if let Some(ref fallback) = defaults.fallback {
+        entry["fallback"] = value(fallback.iter().map(String::as_str).collect::<Array>());
+    }
+
+    let agents = doc.entry("agents").or_insert_with(|| {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        Item::Table(table)
+    });
+    let agents = agents.as_table_mut()
+        .with_context(|| format!("'agents' in {} is not a table", path.display()))?;
+    agents.insert(agent, Item::Table(entry));
+
+    fs::create_dir_all(port42_dir())?;
+    fs::write(&path, doc.to_string())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Drop an agent's `[agents.<name>]` table, leaving the rest of the file as written
+pub fn remove_agent_defaults(agent: &str) -> Result<()> {
+    use toml_edit::DocumentMut;
+
+    let path = config_path();
+    if !path.exists() {
+        return Ok(());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut doc: DocumentMut = content.parse()
+        .with_context(|| format!("Invalid configuration in {}", path.display()))?;
+
+    let removed = doc.get_mut("agents")
+        .and_then(|agents| agents.as_table_like_mut())
+        .and_then(|agents| agents.remove(agent))
+        .is_some();
+    if removed {
+        fs::write(&path, doc.to_string())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Drop an alias from `[aliases]`, leaving the rest of the file as written.
+/// Returns whether there was one to drop.
+pub fn remove_alias(name: &str) -> Result<bool> {
+    use toml_edit::DocumentMut;
+
+    let path = config_path();
+    if !path.exists() {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut doc: DocumentMut = content.parse()
+        .with_context(|| format!("Invalid configuration in {}", path.display()))?;
+
+    let removed = doc.get_mut("aliases")
+        .and_then(|aliases| aliases.as_table_like_mut())
+        .and_then(|aliases| aliases.remove(name))
+        .is_some();
+    if removed {
+        fs::write(&path, doc.to_string())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(removed)
+}
+
+/// Append a `[[notify.hooks]]` entry, leaving the rest of the file as written
+pub fn add_notify_hook(hook: &NotifyHook) -> Result<()> {
+    use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table};
+
+    let path = config_path();
+    let content = if path.exists() {
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let mut doc: DocumentMut = content.parse()
+        .with_context(|| format!("Invalid configuration in {}", path.display()))?;
+
+    let notify = doc.entry("notify").or_insert_with(|| {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        Item::Table(table)
+    });
+    let notify = notify.as_table_mut()
+        .with_context(|| format!("'notify' in {} is not a table", path.display()))?;
+    let hooks = notify.entry("hooks").or_insert_with(|| Item::ArrayOfTables(ArrayOfTables::new()));
+    let hooks = hooks.as_array_of_tables_mut()
+        .with_context(|| format!("'notify.hooks' in {} is not a list of tables", path.display()))?;
+    let mut entry = Table::new();
+    entry["on"] = value(hook.on.as_str());
+    entry["exec"] = value(hook.exec.as_str());
+    hooks.push(entry);
+
+    fs::create_dir_all(port42_dir())?;
+    fs::write(&path, doc.to_string())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Drop the `index`th `[[notify.hooks]]` entry, returning it if there was one
+pub fn remove_notify_hook(index: usize) -> Result<Option<NotifyHook>> {
+    use toml_edit::DocumentMut;
+
+    let path = config_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut doc: DocumentMut = content.parse()
+        .with_context(|| format!("Invalid configuration in {}", path.display()))?;
+
+    let Some(hooks) = doc.get_mut("notify")
+        .and_then(|notify| notify.get_mut("hooks"))
+        .and_then(|hooks| hooks.as_array_of_tables_mut())
+        .filter(|hooks| index < hooks.len())
+    else {
+        return Ok(None);
+    };
+    let removed = hooks.get(index).map(|table| NotifyHook {
+        on: table.get("on").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+        exec: table.get("exec").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+    });
+    hooks.remove(index);
+    fs::write(&path, doc.to_string())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(removed)
+}
+
+/// Root of all Port 42 state on this machine
+pub fn port42_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".port42")
+}
+
+pub fn config_path() -> PathBuf {
+    port42_dir().join(CONFIG_FILE)
+}
@@ -0,0 +1,183 @@
+//! The watch TUI's detail pane
+//!
+//! Each activity row starts out as the record the daemon sent with the
+//! context snapshot. Opening the pane on a row fills in the rest, asking the
+//! daemon only then: `get_metadata` for tools and other VFS paths, `memory`
+//! for sessions. Anything that can't be fetched is noted in the pane rather
+//! than failing it.
+
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+use crate::client::DaemonClient;
+use crate::common::generate_id;
+use crate::protocol::{InfoRequest, InfoResponse, MemoryDetailRequest, MemoryDetailResponse, RequestBuilder, ResponseParser};
+use super::{ActiveSessionInfo, CommandRecord, MemoryAccess, ToolRecord};
+
+/// What an activity row is about
+#[derive(Debug, Clone)]
+pub enum Subject {
+    Session(ActiveSessionInfo),
+    Command(CommandRecord),
+    Tool(ToolRecord),
+    Memory(MemoryAccess),
+}
+
+/// Everything the pane shows for one activity
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Detail {
+    pub title: String,
+    /// Label and value, in display order
+    pub fields: Vec<(String, String)>,
+    /// VFS paths the activity created or points at
+    pub objects: Vec<String>,
+    /// Why part of the detail is missing
+    pub note: Option<String>,
+}
+
+impl Detail {
+    /// Set a field; what the daemon says later replaces the snapshot's value
+    fn field(&mut self, label: &str, value: impl Into<String>) {
+        let value = value.into();
+        if value.is_empty() {
+            return;
+        }
+        match self.fields.iter_mut().find(|(l, _)| l == label) {
+            Some(field) => field.1 = value,
+            None => self.fields.push((label.to_string(), value)),
+        }
+    }
+
+    fn object(&mut self, path: &str) {
+        if !path.is_empty() && !self.objects.iter().any(|p| p == path) {
+            self.objects.push(path.to_string());
+        }
+    }
+
+    /// Fields from a `get_metadata` reply
+    pub fn add_metadata(&mut self, metadata: &Value) {
+        let text = |key: &str| metadata[key].as_str().unwrap_or_default().to_string();
+        self.field("Type", text("type"));
+        self.field("Description", text("description"));
+        self.field("Agent", text("agent"));
+        let session = metadata["relationships"]["session"].as_str().map(str::to_string).unwrap_or_else(|| text("session"));
+        self.field("Session", session);
+        self.field("Created", text("created"));
+        for path in metadata["paths"].as_array().into_iter().flatten().filter_map(Value::as_str) {
+            self.object(path);
+        }
+        for key in ["generated_commands", "child_artifacts"] {
+            for path in metadata["relationships"][key].as_array().into_iter().flatten().filter_map(Value::as_str) {
+                self.object(path);
+            }
+        }
+    }
+
+    /// Fields from a `memory` reply about one session
+    pub fn add_session(&mut self, session: &MemoryDetailResponse) {
+        self.field("Session", session.id.clone());
+        self.field("Agent", session.agent.clone());
+        self.field("State", session.state.clone());
+        self.field("Messages", session.messages.len().to_string());
+        self.field("Started", session.created_at.clone());
+        if let Some(ref command) = session.command_generated {
+            self.object(&format!("/commands/{}", command.name));
+        }
+        self.object(&format!("/memory/{}", session.id));
+    }
+}
+
+/// What the snapshot itself says, before asking the daemon anything
+pub fn summary(subject: &Subject) -> Detail {
+    let mut detail = Detail::default();
+    match subject {
+        Subject::Session(session) => {
+            detail.title = format!("Session {}", session.id);
+            detail.field("Agent", session.agent.clone());
+            detail.field("State", session.state.clone());
+            detail.field("Messages", session.message_count.to_string());
+            if let Some(ref tool) = session.tool_created {
+                detail.object(&format!("/commands/{}", tool));
+            }
+        }
+        Subject::Command(command) => {
+            detail.title = "Command".to_string();
+            detail.field("Command", command.command.clone());
+            detail.field("Exit code", command.exit_code.to_string());
+            detail.field("Duration", command.duration_ms.map(format_millis).unwrap_or_else(|| "not recorded".to_string()));
+            detail.field("Ran", command.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string());
+            detail.field("Session", command.session_id.clone().unwrap_or_default());
+        }
+        Subject::Tool(tool) => {
+            detail.title = format!("Tool {}", tool.name);
+            detail.field("Kind", tool.tool_type.clone());
+            detail.field("Created", tool.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string());
+            if !tool.transforms.is_empty() {
+                detail.field("Transforms", tool.transforms.join(", "));
+            }
+            detail.object(&format!("/commands/{}", tool.name));
+        }
+        Subject::Memory(access) => {
+            detail.title = access.display_name.clone().unwrap_or_else(|| access.path.clone());
+            detail.field("Path", access.path.clone());
+            detail.field("Access", access.access_type.clone());
+            detail.field("Times", access.access_count.to_string());
+        }
+    }
+    detail
+}
+
+/// The summary, filled in from the daemon
+pub fn describe(client: &mut DaemonClient, subject: &Subject) -> Detail {
+    let mut detail = summary(subject);
+    let fetched = match subject {
+        Subject::Session(session) => session_detail(client, &session.id).map(|s| detail.add_session(&s)),
+        Subject::Command(command) => match command.session_id {
+            Some(ref id) => session_detail(client, id).map(|s| detail.add_session(&s)),
+            None => Ok(()),
+        },
+        Subject::Tool(tool) => metadata(client, &format!("/commands/{}", tool.name)).map(|m| detail.add_metadata(&m)),
+        Subject::Memory(access) => match session_id(&access.path) {
+            Some(id) => session_detail(client, id).map(|s| detail.add_session(&s)),
+            None => metadata(client, &access.path).map(|m| detail.add_metadata(&m)),
+        },
+    };
+    if let Err(e) = fetched {
+        detail.note = Some(format!("More detail unavailable: {}", e));
+    }
+    detail
+}
+
+/// `/memory/<id>` names a session; deeper paths are objects inside one
+fn session_id(path: &str) -> Option<&str> {
+    path.strip_prefix("/memory/").filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+fn metadata(client: &mut DaemonClient, path: &str) -> Result<Value> {
+    let request = InfoRequest { path: path.to_string(), version: None }.build_request(generate_id())?;
+    let response = client.request(request)?;
+    if !response.success {
+        return Err(anyhow!(response.error.unwrap_or_else(|| format!("no metadata for {}", path))));
+    }
+    let data = response.data.ok_or_else(|| anyhow!("empty reply"))?;
+    Ok(InfoResponse::parse_response(&data)?.metadata)
+}
+
+fn session_detail(client: &mut DaemonClient, id: &str) -> Result<MemoryDetailResponse> {
+    let request = MemoryDetailRequest { session_id: id.to_string() }.build_request(generate_id())?;
+    let response = client.request(request)?;
+    if !response.success {
+        return Err(anyhow!(response.error.unwrap_or_else(|| format!("no session {}", id))));
+    }
+    let data = response.data.ok_or_else(|| anyhow!("empty reply"))?;
+    MemoryDetailResponse::parse_response(&data)
+}
+
+/// `850ms`, `2.4s`, `3m 05s`
+pub fn format_millis(ms: u64) -> String {
+    match ms {
+        0..=999 => format!("{}ms", ms),
+        1_000..=59_999 => format!("{:.1}s", ms as f64 / 1000.0),
+        _ => format!("{}m {:02}s", ms / 60_000, (ms % 60_000) / 1000),
+    }
+}
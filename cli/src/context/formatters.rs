@@ -80,12 +80,23 @@ impl ContextFormatter for PrettyFormatter {
             }
         }
         
+        // Show bash requests nobody was asked about
+        if !data.policy_approvals.is_empty() {
+            output.push_str("\n🔓 Bash by Policy:\n");
+            for entry in data.policy_approvals.iter().take(5) {
+                let verdict = if entry.approved { "approved" } else { "denied" };
+                output.push_str(&format!("   • {} {} ({}, {})\n",
+                    verdict, entry.command, entry.reason, entry.timestamp.format("%Y-%m-%d %H:%M")));
+            }
+        }
+        
         // Show suggestions
         if !data.suggestions.is_empty() {
             output.push_str("\n💡 Suggestions:\n");
-            for suggestion in data.suggestions.iter().take(3) {
-                output.push_str(&format!("   • {}\n", suggestion.command));
+            for (i, suggestion) in data.suggestions.iter().enumerate().take(3) {
+                output.push_str(&format!("   {}. {}  ({})\n", i + 1, suggestion.command, suggestion.reason));
             }
+            output.push_str("   Run one with 'port42 context run <n>'\n");
         }
         
         output
@@ -105,3 +116,61 @@ impl ContextFormatter for CompactFormatter {
     }
 }
 
+
+/// Chronological formatter: commands, tools and memory accesses interleaved
+pub struct TimelineFormatter;
+
+impl ContextFormatter for TimelineFormatter {
+    fn format(&self, data: &ContextData) -> String {
+        let entries = data.timeline();
+        if entries.is_empty() {
+            return "No activity in this window\n".to_string();
+        }
+        
+        let mut output = format!("🕰  Timeline ({} events)\n", entries.len());
+        let mut day = None;
+        for entry in &entries {
+            let local = entry.time.with_timezone(&chrono::Local);
+            // A heading whenever the day changes keeps the times short
+            if day != Some(local.date_naive()) {
+                day = Some(local.date_naive());
+                output.push_str(&format!("\n{}\n", local.format("%Y-%m-%d")));
+            }
+            let line = match entry.kind {
+                TimelineKind::Command => match entry.exit_code {
+                    Some(code) if code != 0 => format!("📝 {} (exit {})", entry.text, code),
+                    _ => format!("📝 {}", entry.text),
+                },
+                TimelineKind::Tool => format!("🛠  created {}", entry.text),
+                TimelineKind::Memory => format!("🧠 accessed {}", entry.text),
+            };
+            output.push_str(&format!("   {}  {}\n", local.format("%H:%M:%S"), line));
+        }
+        output
+    }
+}
+
+/// Text-mode watch: the pretty view, redrawn every `refresh_ms` until Ctrl+C
+pub fn run_text_watch(mut client: crate::client::DaemonClient, refresh_ms: u64) -> anyhow::Result<()> {
+    use std::io::Write;
+    
+    let formatter = PrettyFormatter;
+    println!("🔍 Port42 Context Monitor (text mode) - Press Ctrl+C to stop");
+    println!("Refresh rate: {}ms\n", refresh_ms);
+    
+    loop {
+        // Clear screen and move to top (flush immediately for macOS compatibility)
+        print!("\x1B[2J\x1B[H");
+        std::io::stdout().flush().unwrap_or(());
+        
+        match suggestions::fetch(&mut client) {
+            Ok(context_data) => {
+                println!("🕒 Last updated: {}", chrono::Local::now().format("%H:%M:%S"));
+                println!("{}", formatter.format(&context_data.with_local_activity()));
+            }
+            Err(e) => println!("❌ {}", e),
+        }
+        
+        std::thread::sleep(std::time::Duration::from_millis(refresh_ms));
+    }
+}
@@ -0,0 +1,547 @@
+// Interactive memory browser for `port42 memory --tui`
+//
+// The wheel moves through sessions, or scrolls the conversation when over
+// it; clicking a session selects it and clicking a footer hint presses it.
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::client::DaemonClient;
+use crate::common::generate_id;
+use crate::context::mouse::{self, Hint};
+use crate::context::safe_tui::SafeTerminal;
+use crate::context::sessions_tui::{fetch_detail, fetch_sessions, last_activity, relative};
+use crate::protocol::{MemoryDetailResponse, MemoryRenameRequest, RequestBuilder, SessionSummary, TrashPathRequest};
+
+/// What the user left the browser to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum BrowserExit {
+    Quit,
+    /// Pick the conversation back up with its agent
+    Resume { session_id: String, agent: String },
+}
+
+/// How tightly `query` matches `text`: its characters must appear in order,
+/// case-insensitively. Lower is tighter; None means no match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut start = None;
+    let mut pos = 0;
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = text[pos..].iter().position(|&c| c == wanted)? + pos;
+        start.get_or_insert(found);
+        pos = found + 1;
+    }
+    // Span covered by the match, plus a nudge for matches further in
+    Some(start.map_or(0, |start| (pos - start) * 2 + start))
+}
+
+/// What keystrokes currently go to
+enum Mode {
+    Browse,
+    Filter,
+    Rename(String),
+    ConfirmDelete,
+}
+
+pub struct MemoryBrowser {
+    sessions: Vec<SessionSummary>,
+    /// Indexes into `sessions` that pass the filter, best match first
+    visible: Vec<usize>,
+    filter: String,
+    selected: usize,
+    scroll_offset: usize,
+    viewport_height: usize,
+    previews: HashMap<String, MemoryDetailResponse>,
+    preview_scroll: u16,
+    mode: Mode,
+    status: Option<String>,
+    last_error: Option<String>,
+    exit: Option<BrowserExit>,
+    daemon_client: DaemonClient,
+    /// Where the last frame drew each part, for mouse events
+    list_area: Rect,
+    preview_area: Rect,
+    footer_area: Rect,
+}
+
+impl MemoryBrowser {
+    pub fn new(daemon_client: DaemonClient, filter: String) -> Self {
+        Self {
+            sessions: Vec::new(),
+            visible: Vec::new(),
+            filter,
+            selected: 0,
+            scroll_offset: 0,
+            viewport_height: 20,
+            previews: HashMap::new(),
+            preview_scroll: 0,
+            mode: Mode::Browse,
+            status: None,
+            last_error: None,
+            exit: None,
+            daemon_client,
+            list_area: Rect::default(),
+            preview_area: Rect::default(),
+            footer_area: Rect::default(),
+        }
+    }
+
+    fn selected_session(&self) -> Option<&SessionSummary> {
+        self.visible.get(self.selected).map(|&i| &self.sessions[i])
+    }
+
+    fn reload(&mut self) {
+        match fetch_sessions(&mut self.daemon_client) {
+            Ok(sessions) => {
+                self.sessions = sessions;
+                self.last_error = None;
+            }
+            Err(e) => self.last_error = Some(format!("Daemon error: {}", e)),
+        }
+        self.apply_filter();
+    }
+
+    fn apply_filter(&mut self) {
+        let selected_id = self.selected_session().map(|s| s.id.clone());
+        let mut scored: Vec<(usize, usize)> = self.sessions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| {
+                let haystack = format!("{} {} {} {}", s.name.as_deref().unwrap_or(""), s.id, s.agent, s.state);
+                fuzzy_score(&self.filter, &haystack).map(|score| (score, i))
+            })
+            .collect();
+        // Stable, so equal scores keep the active-then-recent order
+        scored.sort_by_key(|&(score, _)| score);
+        self.visible = scored.into_iter().map(|(_, i)| i).collect();
+
+        self.selected = selected_id
+            .and_then(|id| self.visible.iter().position(|&i| self.sessions[i].id == id))
+            .unwrap_or(0);
+        self.scroll_offset = self.scroll_offset.min(self.selected);
+        self.load_preview();
+    }
+
+    /// Fetch the conversation for the selected session, once per session
+    fn load_preview(&mut self) {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else { return };
+        if self.previews.contains_key(&id) {
+            return;
+        }
+        match fetch_detail(&mut self.daemon_client, &id) {
+            Ok(detail) => {
+                self.previews.insert(id, detail);
+            }
+            Err(e) => self.last_error = Some(format!("Could not open {}: {}", id, e)),
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if code == KeyCode::Char('c') && modifiers == KeyModifiers::CONTROL {
+            self.exit = Some(BrowserExit::Quit);
+            return;
+        }
+
+        match std::mem::replace(&mut self.mode, Mode::Browse) {
+            Mode::Browse => self.handle_browse_key(code),
+            Mode::Filter => self.handle_filter_key(code),
+            Mode::Rename(name) => self.handle_rename_key(code, name),
+            Mode::ConfirmDelete => {
+                if code == KeyCode::Char('y') {
+                    self.delete_selected();
+                }
+            }
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let (column, row) = (mouse.column, mouse.row);
+        // Renaming or confirming a delete is about the selected session; keep it put
+        let browsing = matches!(self.mode, Mode::Browse | Mode::Filter);
+        match mouse.kind {
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let up = mouse.kind == MouseEventKind::ScrollUp;
+                if mouse::contains(self.preview_area, column, row) {
+                    self.preview_scroll = if up { self.preview_scroll.saturating_sub(3) } else { self.preview_scroll.saturating_add(3) };
+                } else if browsing {
+                    self.move_by(if up { -1 } else { 1 });
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(row) = mouse::row_at(self.list_area, column, row).filter(|_| browsing) {
+                    let index = self.scroll_offset + row;
+                    if index < self.visible.len() {
+                        self.move_by(index as isize - self.selected as isize);
+                    }
+                } else if mouse::contains(self.footer_area, column, row) {
+                    let (prefix, hints) = self.footer();
+                    if let Some(code) = mouse::hint_at(&hints, &prefix, self.footer_area, column) {
+                        self.handle_key(code, KeyModifiers::NONE);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_browse_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') => self.exit = Some(BrowserExit::Quit),
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Esc => self.exit = Some(BrowserExit::Quit),
+            KeyCode::Char('/') => self.mode = Mode::Filter,
+            KeyCode::Enter => {
+                if let Some(session) = self.selected_session() {
+                    self.exit = Some(BrowserExit::Resume {
+                        session_id: session.id.clone(),
+                        agent: session.agent.clone(),
+                    });
+                }
+            }
+            KeyCode::Char('n') => {
+                if let Some(session) = self.selected_session() {
+                    self.mode = Mode::Rename(session.name.clone().unwrap_or_default());
+                }
+            }
+            KeyCode::Char('d') if self.selected_session().is_some() => self.mode = Mode::ConfirmDelete,
+            KeyCode::Char('r') => {
+                self.previews.clear();
+                self.reload();
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+            KeyCode::Home => self.move_by(-(self.visible.len() as isize)),
+            KeyCode::End => self.move_by(self.visible.len() as isize),
+            KeyCode::PageUp => self.preview_scroll = self.preview_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.preview_scroll = self.preview_scroll.saturating_add(10),
+            _ => {}
+        }
+    }
+
+    fn handle_filter_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => return,
+            KeyCode::Esc => self.filter.clear(),
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Up => self.move_by(-1),
+            KeyCode::Down => self.move_by(1),
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => {}
+        }
+        if code != KeyCode::Esc {
+            self.mode = Mode::Filter;
+        }
+        self.apply_filter();
+    }
+
+    fn handle_rename_key(&mut self, code: KeyCode, mut name: String) {
+        match code {
+            KeyCode::Esc => {}
+            KeyCode::Enter => self.rename_selected(name.trim()),
+            KeyCode::Backspace => {
+                name.pop();
+                self.mode = Mode::Rename(name);
+            }
+            KeyCode::Char(c) => {
+                name.push(c);
+                self.mode = Mode::Rename(name);
+            }
+            _ => self.mode = Mode::Rename(name),
+        }
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let target = (self.selected as isize + delta).clamp(0, self.visible.len() as isize - 1) as usize;
+        if target == self.selected {
+            return;
+        }
+        self.selected = target;
+        if self.selected < self.scroll_offset {
+            self.scroll_offset = self.selected;
+        } else if self.selected >= self.scroll_offset + self.viewport_height {
+            self.scroll_offset = self.selected + 1 - self.viewport_height;
+        }
+        self.preview_scroll = 0;
+        self.load_preview();
+    }
+
+    fn rename_selected(&mut self, name: &str) {
+        let Some(&index) = self.visible.get(self.selected) else { return };
+        if name.is_empty() {
+            return;
+        }
+        let session_id = self.sessions[index].id.clone();
+        let request = MemoryRenameRequest { session_id: session_id.clone(), name: name.to_string() };
+        match self.send(request) {
+            Ok(()) => {
+                self.sessions[index].name = Some(name.to_string());
+                self.status = Some(format!("Renamed {} to '{}'", session_id, name));
+                self.apply_filter();
+            }
+            Err(e) => self.last_error = Some(format!("Could not rename {}: {}", session_id, e)),
+        }
+    }
+
+    /// Sessions go to the trash like any other VFS object, so they can be restored
+    fn delete_selected(&mut self) {
+        let Some(&index) = self.visible.get(self.selected) else { return };
+        let session_id = self.sessions[index].id.clone();
+        match self.send(TrashPathRequest { path: format!("/memory/{}", session_id) }) {
+            Ok(()) => {
+                self.sessions.remove(index);
+                self.previews.remove(&session_id);
+                self.status = Some(format!("Moved {} to /trash", session_id));
+                // Indexes past the removed session shifted; stay at the same row
+                let row = self.selected;
+                self.visible.clear();
+                self.apply_filter();
+                self.move_by(row.min(self.visible.len().saturating_sub(1)) as isize);
+            }
+            Err(e) => self.last_error = Some(format!("Could not delete {}: {}", session_id, e)),
+        }
+    }
+
+    fn send(&mut self, request: impl RequestBuilder) -> Result<()> {
+        let response = self.daemon_client.request(request.build_request(generate_id())?)?;
+        if !response.success {
+            anyhow::bail!(response.error.unwrap_or_else(|| "Unknown error".to_string()));
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),  // Header
+                Constraint::Min(0),     // Body
+                Constraint::Length(3),  // Footer
+            ])
+            .split(frame.size());
+
+        self.render_header(frame, chunks[0]);
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(chunks[1]);
+        (self.list_area, self.preview_area, self.footer_area) = (body[0], body[1], chunks[2]);
+        self.render_sessions(frame, body[0]);
+        self.render_preview(frame, body[1]);
+        self.render_footer(frame, chunks[2]);
+    }
+
+    fn render_header(&self, frame: &mut Frame, area: Rect) {
+        let spans = if let Some(err) = &self.last_error {
+            vec![
+                Span::styled("⚠️ ", Style::default().fg(Color::Red)),
+                Span::styled(err, Style::default().fg(Color::Red)),
+            ]
+        } else {
+            let mut spans = vec![
+                Span::styled("🧠 ", Style::default()),
+                Span::styled("Port42 Memory", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::raw(" │ "),
+                Span::styled(format!("{}/{} sessions", self.visible.len(), self.sessions.len()), Style::default().fg(Color::Yellow)),
+            ];
+            if matches!(self.mode, Mode::Filter) || !self.filter.is_empty() {
+                let cursor = if matches!(self.mode, Mode::Filter) { "▏" } else { "" };
+                spans.push(Span::raw(" │ "));
+                spans.push(Span::styled(format!("/{}{}", self.filter, cursor), Style::default().fg(Color::Magenta)));
+            }
+            if let Some(status) = &self.status {
+                spans.push(Span::raw(" │ "));
+                spans.push(Span::styled(status.clone(), Style::default().fg(Color::Green)));
+            }
+            spans
+        };
+
+        let header = Paragraph::new(Line::from(spans))
+            .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(Color::DarkGray)))
+            .alignment(Alignment::Center);
+        frame.render_widget(header, area);
+    }
+
+    fn render_sessions(&mut self, frame: &mut Frame, area: Rect) {
+        self.viewport_height = (area.height as usize).max(1);
+
+        if self.visible.is_empty() {
+            let text = if self.sessions.is_empty() {
+                "No sessions yet. Start one with: port42 swim @ai-engineer"
+            } else {
+                "No sessions match the filter"
+            };
+            let message = Paragraph::new(Line::from(Span::styled(
+                text,
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            )))
+            .alignment(Alignment::Center);
+            frame.render_widget(message, area);
+            return;
+        }
+
+        let items: Vec<ListItem> = self.visible
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(self.viewport_height)
+            .map(|(row, &i)| {
+                let session = &self.sessions[i];
+                let state_color = match session.state.as_str() {
+                    "active" => Color::Green,
+                    "idle" => Color::Yellow,
+                    _ => Color::Gray,
+                };
+                let label = match session.name {
+                    Some(ref name) => Span::styled(format!("{:<24} ", name), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                    None => Span::raw(format!("{:<24} ", session.id)),
+                };
+                let spans = vec![
+                    Span::styled("● ", Style::default().fg(state_color)),
+                    label,
+                    Span::styled(format!("{:<14} ", session.agent), Style::default().fg(Color::Cyan)),
+                    Span::styled(format!("{:>4} msgs  ", session.message_count), Style::default().fg(Color::Blue)),
+                    Span::styled(relative(last_activity(session)), Style::default().fg(Color::Gray)),
+                ];
+                let style = if row == self.selected {
+                    Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(spans)).style(style)
+            })
+            .collect();
+
+        frame.render_widget(List::new(items).block(Block::default().borders(Borders::NONE)), area);
+    }
+
+    fn render_preview(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::LEFT)
+            .border_style(Style::default().fg(Color::DarkGray));
+        let Some(summary) = self.selected_session() else {
+            frame.render_widget(block, area);
+            return;
+        };
+        let block = block.title(Span::styled(format!(" {} ", summary.id), Style::default().fg(Color::Blue)));
+        let Some(session) = self.previews.get(&summary.id) else {
+            let loading = Paragraph::new(Span::styled("Loading...", Style::default().fg(Color::DarkGray))).block(block);
+            frame.render_widget(loading, area);
+            return;
+        };
+
+        let mut lines = Vec::new();
+        if let Some(ref name) = summary.name {
+            lines.push(Line::from(Span::styled(name.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD))));
+        }
+        lines.push(Line::from(vec![
+            Span::styled(session.agent.clone(), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(format!("  {}  ", session.state)),
+            Span::styled(format!("started {}", relative(&session.created_at)), Style::default().fg(Color::DarkGray)),
+        ]));
+        if let Some(ref command) = session.command_generated {
+            lines.push(Line::from(Span::styled(format!("🛠 Crystallized {}", command.name), Style::default().fg(Color::Magenta))));
+        }
+        for message in &session.messages {
+            lines.push(Line::from(""));
+            let color = if message.role == "user" { Color::Yellow } else { Color::Green };
+            lines.push(Line::from(Span::styled(message.role.clone(), Style::default().fg(color).add_modifier(Modifier::BOLD))));
+            lines.extend(message.content.lines().map(|l| Line::from(l.to_string())));
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.preview_scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    /// What the footer shows before its hints, and the hints themselves
+    fn footer(&self) -> (Vec<Span<'static>>, Vec<Hint>) {
+        match self.mode {
+            Mode::Rename(ref name) => (
+                vec![
+                    Span::styled("New name: ", Style::default().fg(Color::Cyan)),
+                    Span::raw(format!("{}▏ ", name)),
+                ],
+                vec![Hint::new("Enter", "save", Some(KeyCode::Enter)), Hint::new("Esc", "cancel", Some(KeyCode::Esc))],
+            ),
+            Mode::ConfirmDelete => (
+                vec![Span::styled(
+                    format!("Move {} to /trash? ", self.selected_session().map(|s| s.id.as_str()).unwrap_or("")),
+                    Style::default().fg(Color::Red),
+                )],
+                vec![Hint::new("y", "yes", Some(KeyCode::Char('y'))), Hint::new("any key", "no", Some(KeyCode::Esc))],
+            ),
+            Mode::Filter => (Vec::new(), vec![
+                Hint::new("type", "filter", None),
+                Hint::new("↑↓", "navigate", None),
+                Hint::new("Enter", "keep", Some(KeyCode::Enter)),
+                Hint::new("Esc", "clear", Some(KeyCode::Esc)),
+            ]),
+            Mode::Browse => (Vec::new(), vec![
+                Hint::new("q", "quit", Some(KeyCode::Char('q'))),
+                Hint::new("↑↓", "navigate", None),
+                Hint::new("/", "filter", Some(KeyCode::Char('/'))),
+                Hint::new("Enter", "resume", Some(KeyCode::Enter)),
+                Hint::new("n", "rename", Some(KeyCode::Char('n'))),
+                Hint::new("d", "delete", Some(KeyCode::Char('d'))),
+                Hint::new("PgUp/PgDn", "scroll", None),
+                Hint::new("r", "reload", Some(KeyCode::Char('r'))),
+            ]),
+        }
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let (mut spans, hints) = self.footer();
+        spans.extend(mouse::hint_spans(&hints));
+
+        let footer = Paragraph::new(Line::from(spans))
+            .block(Block::default().borders(Borders::TOP).border_style(Style::default().fg(Color::DarkGray)))
+            .alignment(Alignment::Center);
+        frame.render_widget(footer, area);
+    }
+}
+
+/// Main entry point for the memory browser; the terminal is restored before it returns
+pub fn run_memory_browser(daemon_client: DaemonClient, filter: String) -> Result<BrowserExit> {
+    let mut terminal = SafeTerminal::new()?;
+    let mut app = MemoryBrowser::new(daemon_client, filter);
+    app.reload();
+
+    loop {
+        terminal.draw(|f| app.render(f))?;
+
+        if let Some(exit) = app.exit.take() {
+            return Ok(exit);
+        }
+
+        if event::poll(Duration::from_millis(250))? {
+            match event::read()? {
+                Event::Key(key) => {
+                    app.status = None;
+                    app.handle_key(key.code, key.modifiers);
+                }
+                Event::Mouse(mouse) => app.handle_mouse(mouse),
+                _ => {}
+            }
+        }
+    }
+}
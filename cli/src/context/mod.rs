@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use crate::protocol::events::{DaemonEvent, EventKind};
 
 /// Complete context data structure matching daemon's ContextData
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -10,6 +11,163 @@ pub struct ContextData {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub accessed_memories: Vec<MemoryAccess>,
     pub suggestions: Vec<ContextSuggestion>,
+    /// Bash requests settled by approval policy; kept locally, not by the daemon
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policy_approvals: Vec<crate::common::approval::ApprovalRecord>,
+}
+
+/// How many policy decisions `port42 context` shows
+pub const RECENT_APPROVALS: usize = 10;
+
+impl ContextData {
+    /// Add what only this machine knows about
+    pub fn with_local_activity(mut self) -> Self {
+        self.policy_approvals = crate::common::approval::recent(RECENT_APPROVALS);
+        self
+    }
+    
+    /// Drop anything outside the window, for daemons that answer a history
+    /// query with their usual snapshot
+    pub fn within(mut self, query: &HistoryQuery) -> Self {
+        self.recent_commands.retain(|c| c.timestamp >= query.since);
+        self.created_tools.retain(|t| t.created_at >= query.since);
+        self.accessed_memories.retain(|m| m.last_accessed >= query.since);
+        
+        // The limit counts events of every kind together, keeping the newest;
+        // events sharing the cut-off time are all kept
+        let mut times: Vec<DateTime<Utc>> = self.timeline().iter().map(|e| e.time).collect();
+        if times.len() > query.limit {
+            times.sort_unstable_by(|a, b| b.cmp(a));
+            let oldest = times[query.limit.max(1) - 1];
+            self.recent_commands.retain(|c| c.timestamp >= oldest);
+            self.created_tools.retain(|t| t.created_at >= oldest);
+            self.accessed_memories.retain(|m| m.last_accessed >= oldest);
+        }
+        self
+    }
+    
+    /// Commands, tools and memory accesses as one list, oldest first
+    pub fn timeline(&self) -> Vec<TimelineEntry> {
+        let mut entries: Vec<TimelineEntry> = self.recent_commands.iter()
+            .map(|c| TimelineEntry { time: c.timestamp, kind: TimelineKind::Command, text: c.command.clone(), exit_code: Some(c.exit_code) })
+            .chain(self.created_tools.iter()
+                .map(|t| TimelineEntry { time: t.created_at, kind: TimelineKind::Tool, text: t.name.clone(), exit_code: None }))
+            .chain(self.accessed_memories.iter()
+                .map(|m| TimelineEntry {
+                    time: m.last_accessed,
+                    kind: TimelineKind::Memory,
+                    text: m.display_name.clone().unwrap_or_else(|| m.path.clone()),
+                    exit_code: None,
+                }))
+            .collect();
+        entries.sort_by_key(|e| e.time);
+        entries
+    }
+    
+    /// What happened between an earlier snapshot and this one, oldest first.
+    /// Stands in for a daemon event stream by comparing the two.
+    pub fn events_since(&self, previous: &ContextData) -> Vec<DaemonEvent> {
+        let mut events = Vec::new();
+        let session_event = |kind, session: &ActiveSessionInfo, timestamp| DaemonEvent {
+            kind,
+            timestamp,
+            subject: session.id.clone(),
+            data: serde_json::json!({ "agent": session.agent, "messages": session.message_count, "state": session.state }),
+        };
+        match (&previous.active_session, &self.active_session) {
+            (Some(before), Some(now)) if before.id == now.id => {
+                if now.message_count > before.message_count {
+                    events.push(session_event(EventKind::Message, now, now.last_activity));
+                }
+            }
+            (before, now) => {
+                if let Some(before) = before {
+                    events.push(session_event(EventKind::SessionEnded, before, before.last_activity));
+                }
+                if let Some(now) = now {
+                    events.push(session_event(EventKind::SessionStarted, now, now.start_time));
+                }
+            }
+        }
+        
+        for tool in &self.created_tools {
+            if !previous.created_tools.iter().any(|t| t.name == tool.name && t.created_at == tool.created_at) {
+                events.push(DaemonEvent {
+                    kind: EventKind::ToolCreated,
+                    timestamp: tool.created_at,
+                    subject: tool.name.clone(),
+                    data: serde_json::json!({ "type": tool.tool_type, "transforms": tool.transforms }),
+                });
+            }
+        }
+        for command in &self.recent_commands {
+            if !previous.recent_commands.iter().any(|c| c.command == command.command && c.timestamp == command.timestamp) {
+                events.push(DaemonEvent {
+                    kind: EventKind::CommandRun,
+                    timestamp: command.timestamp,
+                    subject: command.command.clone(),
+                    data: serde_json::json!({ "exit_code": command.exit_code, "duration_ms": command.duration_ms, "session": command.session_id }),
+                });
+            }
+        }
+        for memory in &self.accessed_memories {
+            if !previous.accessed_memories.iter().any(|m| m.path == memory.path && m.last_accessed == memory.last_accessed) {
+                events.push(DaemonEvent {
+                    kind: EventKind::MemoryAccessed,
+                    timestamp: memory.last_accessed,
+                    subject: memory.path.clone(),
+                    data: serde_json::json!({ "type": memory.access_type, "count": memory.access_count }),
+                });
+            }
+        }
+        events.sort_by_key(|e| e.timestamp);
+        events
+    }
+}
+
+/// Most events `context --history` asks for unless told otherwise
+pub const DEFAULT_HISTORY_LIMIT: usize = 200;
+
+/// A window of past activity, asked of the daemon by `context --history`
+#[derive(Debug, Clone)]
+pub struct HistoryQuery {
+    pub since: DateTime<Utc>,
+    pub limit: usize,
+}
+
+impl HistoryQuery {
+    /// Request payload; an empty one asks for the current snapshot
+    pub fn payload(query: Option<&HistoryQuery>) -> serde_json::Value {
+        match query {
+            Some(query) => serde_json::json!({
+                "history": { "since": query.since.to_rfc3339(), "limit": query.limit },
+            }),
+            None => serde_json::json!({}),
+        }
+    }
+    
+    /// Whether the daemon answered from its history: one that does echoes
+    /// the window back, one that doesn't sends its usual snapshot
+    pub fn answered(data: &serde_json::Value) -> bool {
+        data.get("history").is_some_and(|h| h.is_object())
+    }
+}
+
+/// One thing that happened, for the timeline view
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineEntry {
+    pub time: DateTime<Utc>,
+    pub kind: TimelineKind,
+    /// The command run, tool created or memory touched
+    pub text: String,
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineKind {
+    Command,
+    Tool,
+    Memory,
 }
 
 /// Active session information for display
@@ -32,6 +190,12 @@ pub struct CommandRecord {
     pub timestamp: DateTime<Utc>,
     pub age_seconds: i32,
     pub exit_code: i32,
+    /// How long it ran, when the reporter measured it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// The AI session it ran under, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
 }
 
 /// Tool created in current session
@@ -67,4 +231,9 @@ pub struct ContextSuggestion {
 
 // Re-export submodules
 pub mod formatters;
-pub mod safe_tui;
\ No newline at end of file
+pub mod safe_tui;
+pub mod sessions_tui;
+pub mod memory_tui;
+pub mod suggestions;
+pub mod detail;
+pub mod mouse;
@@ -0,0 +1,64 @@
+//! Mouse handling shared by the TUIs
+//!
+//! SafeTerminal turns mouse capture on; these helpers turn clicks back into
+//! what the keyboard would have done. Footers are built from `Hint`s so a
+//! click on `[f]filter` can press `f`, and lists remember where they were
+//! drawn so a click can pick the row under it.
+
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Span;
+
+/// One `[key]description` hint in a footer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub label: &'static str,
+    pub desc: &'static str,
+    /// What clicking the hint presses; None for hints naming several keys
+    pub key: Option<KeyCode>,
+}
+
+impl Hint {
+    pub fn new(label: &'static str, desc: &'static str, key: Option<KeyCode>) -> Self {
+        Self { label, desc, key }
+    }
+
+    fn spans(&self) -> [Span<'static>; 2] {
+        [
+            Span::styled(format!("[{}]", self.label), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("{} ", self.desc), Style::default().fg(Color::White)),
+        ]
+    }
+}
+
+/// The spans a footer draws for its hints
+pub fn hint_spans(hints: &[Hint]) -> Vec<Span<'static>> {
+    hints.iter().flat_map(Hint::spans).collect()
+}
+
+/// The key of the hint at column `x`, for a footer line drawn centred in
+/// `area` as `prefix` followed by the hints
+pub fn hint_at(hints: &[Hint], prefix: &[Span], area: Rect, x: u16) -> Option<KeyCode> {
+    let widths: Vec<usize> = hints.iter().map(|h| h.spans().iter().map(Span::width).sum()).collect();
+    let total = prefix.iter().map(Span::width).sum::<usize>() + widths.iter().sum::<usize>();
+    let mut start = area.x as usize + (area.width as usize).saturating_sub(total) / 2
+        + prefix.iter().map(Span::width).sum::<usize>();
+    for (hint, width) in hints.iter().zip(widths) {
+        // The trailing space belongs to no hint
+        if (start..start + width - 1).contains(&(x as usize)) {
+            return hint.key;
+        }
+        start += width;
+    }
+    None
+}
+
+pub fn contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.x + area.width && row >= area.y && row < area.y + area.height
+}
+
+/// Which drawn row of a list a click landed on, counting from the first shown
+pub fn row_at(area: Rect, column: u16, row: u16) -> Option<usize> {
+    contains(area, column, row).then(|| (row - area.y) as usize)
+}
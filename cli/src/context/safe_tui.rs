@@ -1,8 +1,13 @@
 // Safe TUI implementation with guaranteed terminal restoration
+//
+// This is the one `port42 context --watch` TUI: live daemon activity that
+// can be narrowed by kind (f) or searched (/), with the suggestions below.
+// Enter opens a detail pane on the selected row. The mouse works too: the
+// wheel moves the selection, a click picks a row or presses a footer hint.
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -11,10 +16,11 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::{
+    collections::HashMap,
     io::{self, Stdout},
     panic::{self, PanicHookInfo},
     sync::{Arc, Mutex},
@@ -22,7 +28,9 @@ use std::{
 };
 
 use crate::client::DaemonClient;
-use crate::context::ContextData;
+use crate::context::{ContextData, ContextSuggestion, suggestions};
+use crate::context::detail::{self, Detail, Subject};
+use crate::context::mouse::{self, Hint};
 
 /// Guard that ensures terminal is always restored
 struct TerminalGuard {
@@ -105,18 +113,98 @@ impl SafeTerminal {
     }
 }
 
+/// What kind of thing an activity row is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ActivityKind {
+    Session,
+    Command,
+    Tool,
+    Memory,
+}
+
+impl ActivityKind {
+    fn label(self) -> &'static str {
+        match self {
+            ActivityKind::Session => "SESSION",
+            ActivityKind::Command => "COMMAND",
+            ActivityKind::Tool => "TOOL",
+            ActivityKind::Memory => "MEMORY",
+        }
+    }
+    
+    fn color(self) -> Color {
+        match self {
+            ActivityKind::Session => Color::Cyan,
+            ActivityKind::Command => Color::Blue,
+            ActivityKind::Tool => Color::Magenta,
+            ActivityKind::Memory => Color::Green,
+        }
+    }
+}
+
 /// Activity record for display
 #[derive(Debug, Clone)]
 struct Activity {
     timestamp: chrono::DateTime<chrono::Local>,
-    activity_type: String,
+    kind: ActivityKind,
     description: String,
-    color: Color,
+    subject: Subject,
+}
+
+impl Activity {
+    /// Stable across refreshes, so fetched detail can be reused
+    fn key(&self) -> String {
+        format!("{}|{}|{}", self.kind.label(), self.timestamp.to_rfc3339(), self.description)
+    }
+}
+
+/// Which activities are listed
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Filter {
+    All,
+    Only(ActivityKind),
+    /// Rows whose kind or description contains the text, ignoring case
+    Search(String),
+}
+
+impl Filter {
+    fn matches(&self, activity: &Activity) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Only(kind) => activity.kind == *kind,
+            Filter::Search(text) => {
+                let text = text.to_lowercase();
+                activity.description.to_lowercase().contains(&text)
+                    || activity.kind.label().to_lowercase().contains(&text)
+            }
+        }
+    }
+    
+    /// The next view for the `f` key; a search goes back to everything
+    fn next(&self) -> Filter {
+        match self {
+            Filter::All => Filter::Only(ActivityKind::Command),
+            Filter::Only(ActivityKind::Command) => Filter::Only(ActivityKind::Tool),
+            Filter::Only(ActivityKind::Tool) => Filter::Only(ActivityKind::Memory),
+            Filter::Only(_) | Filter::Search(_) => Filter::All,
+        }
+    }
+    
+    fn describe(&self) -> String {
+        match self {
+            Filter::All => "all".to_string(),
+            Filter::Only(kind) => kind.label().to_lowercase(),
+            Filter::Search(text) => format!("/{}", text),
+        }
+    }
 }
 
 /// Main application state
 pub struct App {
     activities: Vec<Activity>,
+    filter: Filter,
+    /// Search text being typed after `/`, applied as it changes
+    search_input: Option<String>,
     selected: usize,
     scroll_offset: usize,
     viewport_height: usize,
@@ -125,12 +213,23 @@ pub struct App {
     last_error: Option<String>,
     active_session: Option<String>,
     active_agent: Option<String>,
+    suggestions: Vec<ContextSuggestion>,
+    /// Suggestion picked with a number key, run once the TUI steps aside
+    pending_run: Option<usize>,
+    detail_open: bool,
+    /// Detail fetched for rows shown in the pane, by activity key
+    details: HashMap<String, Detail>,
+    /// Where the last frame drew the list and footer, for mouse clicks
+    list_area: Rect,
+    footer_area: Rect,
 }
 
 impl App {
     pub fn new(daemon_client: DaemonClient) -> Self {
         Self {
             activities: Vec::new(),
+            filter: Filter::All,
+            search_input: None,
             selected: 0,
             scroll_offset: 0,
             viewport_height: 20,
@@ -139,6 +238,12 @@ impl App {
             last_error: None,
             active_session: None,
             active_agent: None,
+            suggestions: Vec::new(),
+            pending_run: None,
+            detail_open: false,
+            details: HashMap::new(),
+            list_area: Rect::default(),
+            footer_area: Rect::default(),
         }
     }
     
@@ -149,20 +254,121 @@ impl App {
             return Ok(());
         }
         
+        // While a search is typed, keys are text
+        if let Some(ref mut input) = self.search_input {
+            match code {
+                KeyCode::Esc => {
+                    self.search_input = None;
+                    self.set_filter(Filter::All);
+                }
+                KeyCode::Enter => {
+                    if input.is_empty() {
+                        self.set_filter(Filter::All);
+                    }
+                    self.search_input = None;
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                    let text = input.clone();
+                    self.set_filter(if text.is_empty() { Filter::All } else { Filter::Search(text) });
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                    let text = input.clone();
+                    self.set_filter(Filter::Search(text));
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+        
         match code {
             KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Enter => {
+                self.detail_open = !self.detail_open;
+                if !self.detail_open {
+                    self.details.clear();
+                }
+            }
+            KeyCode::Esc if self.detail_open => {
+                self.detail_open = false;
+                self.details.clear();
+            }
+            KeyCode::Char('f') => self.set_filter(self.filter.next()),
+            KeyCode::Char('/') => self.search_input = Some(String::new()),
+            KeyCode::Esc if self.filter != Filter::All => self.set_filter(Filter::All),
             KeyCode::Up | KeyCode::Char('k') => self.move_up(),
             KeyCode::Down | KeyCode::Char('j') => self.move_down(),
             KeyCode::PageUp => self.page_up(),
             KeyCode::PageDown => self.page_down(),
             KeyCode::Home => self.go_to_top(),
             KeyCode::End => self.go_to_bottom(),
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = c as usize - '1' as usize;
+                if index < self.suggestions.len().min(MAX_SUGGESTIONS) {
+                    self.pending_run = Some(index);
+                }
+            }
             _ => {}
         }
         
+        // The pane follows the selection, fetching each row's detail once
+        self.load_detail();
         Ok(())
     }
     
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<()> {
+        let (column, row) = (mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_up(),
+            MouseEventKind::ScrollDown => self.move_down(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(row) = mouse::row_at(self.list_area, column, row) {
+                    let index = self.scroll_offset + row;
+                    if index < self.visible().len() {
+                        self.selected = index;
+                    }
+                } else if mouse::contains(self.footer_area, column, row) {
+                    let (prefix, hints) = self.footer();
+                    if let Some(code) = mouse::hint_at(&hints, &prefix, self.footer_area, column) {
+                        return self.handle_key(code, KeyModifiers::NONE);
+                    }
+                }
+            }
+            _ => return Ok(()),
+        }
+        self.load_detail();
+        Ok(())
+    }
+    
+    fn load_detail(&mut self) {
+        if !self.detail_open {
+            return;
+        }
+        let Some((key, subject)) = self.visible().get(self.selected).map(|a| (a.key(), a.subject.clone())) else { return };
+        if !self.details.contains_key(&key) {
+            let detail = detail::describe(&mut self.daemon_client, &subject);
+            self.details.insert(key, detail);
+        }
+    }
+    
+    /// The activities the current filter lets through, newest first
+    fn visible(&self) -> Vec<&Activity> {
+        self.activities.iter().filter(|a| self.filter.matches(a)).collect()
+    }
+    
+    fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+        self.go_to_top();
+    }
+    
+    /// Keep the selection on a row that still exists after a refresh
+    fn clamp_selection(&mut self) {
+        let max_index = self.visible().len().saturating_sub(1);
+        self.selected = self.selected.min(max_index);
+        self.scroll_offset = self.scroll_offset.min(self.selected);
+    }
+    
     fn move_up(&mut self) {
         if self.selected > 0 {
             self.selected -= 1;
@@ -173,7 +379,7 @@ impl App {
     }
     
     fn move_down(&mut self) {
-        let max_index = self.activities.len().saturating_sub(1);
+        let max_index = self.visible().len().saturating_sub(1);
         if self.selected < max_index {
             self.selected += 1;
             if self.selected >= self.scroll_offset + self.viewport_height {
@@ -189,7 +395,7 @@ impl App {
     }
     
     fn page_down(&mut self) {
-        let max_index = self.activities.len().saturating_sub(1);
+        let max_index = self.visible().len().saturating_sub(1);
         let page_size = self.viewport_height.saturating_sub(1);
         self.selected = (self.selected + page_size).min(max_index);
         
@@ -204,7 +410,7 @@ impl App {
     }
     
     fn go_to_bottom(&mut self) {
-        let max_index = self.activities.len().saturating_sub(1);
+        let max_index = self.visible().len().saturating_sub(1);
         self.selected = max_index;
         self.scroll_offset = max_index.saturating_sub(self.viewport_height - 1);
     }
@@ -223,6 +429,7 @@ impl App {
             references: None,
             session_context: None,
             user_prompt: None,
+            provider: None,
         };
         
         match self.daemon_client.request(request) {
@@ -256,6 +463,8 @@ impl App {
             self.active_agent = None;
         }
         
+        self.suggestions = context.suggestions.clone();
+        
         // Clear and rebuild activities
         self.activities.clear();
         
@@ -263,9 +472,9 @@ impl App {
         if let Some(ref session) = context.active_session {
             self.activities.push(Activity {
                 timestamp: session.last_activity.with_timezone(&chrono::Local),
-                activity_type: "SESSION".to_string(),
+                kind: ActivityKind::Session,
                 description: format!("Active: {} ({} msgs)", session.agent, session.message_count),
-                color: Color::Cyan,
+                subject: Subject::Session(session.clone()),
             });
         }
         
@@ -273,9 +482,9 @@ impl App {
         for cmd in context.recent_commands {
             self.activities.push(Activity {
                 timestamp: cmd.timestamp.with_timezone(&chrono::Local),
-                activity_type: "COMMAND".to_string(),
-                description: cmd.command,
-                color: Color::Blue,
+                kind: ActivityKind::Command,
+                description: cmd.command.clone(),
+                subject: Subject::Command(cmd),
             });
         }
         
@@ -283,9 +492,9 @@ impl App {
         for tool in context.created_tools {
             self.activities.push(Activity {
                 timestamp: tool.created_at.with_timezone(&chrono::Local),
-                activity_type: "TOOL".to_string(),
+                kind: ActivityKind::Tool,
                 description: format!("Created: {}", tool.name),
-                color: Color::Magenta,
+                subject: Subject::Tool(tool),
             });
         }
         
@@ -293,29 +502,111 @@ impl App {
         for mem in context.accessed_memories {
             self.activities.push(Activity {
                 timestamp: mem.last_accessed.with_timezone(&chrono::Local),
-                activity_type: "MEMORY".to_string(),
-                description: format!("Accessed: {}", mem.display_name.unwrap_or(mem.path)),
-                color: Color::Green,
+                kind: ActivityKind::Memory,
+                description: format!("Accessed: {}", mem.display_name.clone().unwrap_or_else(|| mem.path.clone())),
+                subject: Subject::Memory(mem),
             });
         }
         
         // Sort by timestamp (newest first)
         self.activities.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        self.clamp_selection();
+        self.load_detail();
     }
     
-    fn render(&self, frame: &mut Frame) {
+    fn render(&mut self, frame: &mut Frame) {
+        let shown = self.suggestions.len().min(MAX_SUGGESTIONS);
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),  // Header
                 Constraint::Min(0),     // Body
+                Constraint::Length(if shown > 0 { shown as u16 + 1 } else { 0 }),  // Suggestions
                 Constraint::Length(3),  // Footer
             ])
             .split(frame.size());
         
         self.render_header(frame, chunks[0]);
-        self.render_activities(frame, chunks[1]);
-        self.render_footer(frame, chunks[2]);
+        if self.detail_open {
+            let body = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(chunks[1]);
+            self.list_area = body[0];
+            self.render_activities(frame, body[0]);
+            self.render_detail(frame, body[1]);
+        } else {
+            self.list_area = chunks[1];
+            self.render_activities(frame, chunks[1]);
+        }
+        if shown > 0 {
+            self.render_suggestions(frame, chunks[2]);
+        }
+        self.footer_area = chunks[3];
+        self.render_footer(frame, chunks[3]);
+    }
+    
+    fn render_detail(&self, frame: &mut Frame, area: Rect) {
+        let label = Style::default().fg(Color::Cyan);
+        let detail = self.visible().get(self.selected).and_then(|a| self.details.get(&a.key()));
+        let mut lines = Vec::new();
+        match detail {
+            Some(detail) => {
+                lines.push(Line::from(Span::styled(detail.title.clone(), Style::default().add_modifier(Modifier::BOLD))));
+                lines.push(Line::from(""));
+                for (name, value) in &detail.fields {
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("{}: ", name), label),
+                        Span::raw(value.clone()),
+                    ]));
+                }
+                if !detail.objects.is_empty() {
+                    lines.push(Line::from(""));
+                    lines.push(Line::from(Span::styled("Objects", label)));
+                    for path in &detail.objects {
+                        lines.push(Line::from(format!("  • {}", path)));
+                    }
+                }
+                if let Some(ref note) = detail.note {
+                    lines.push(Line::from(""));
+                    lines.push(Line::from(Span::styled(note.clone(), Style::default().fg(Color::DarkGray))));
+                }
+            }
+            None => lines.push(Line::from(Span::styled("Nothing selected", Style::default().fg(Color::DarkGray)))),
+        }
+        
+        let pane = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::LEFT)
+                    .border_style(Style::default().fg(Color::DarkGray))
+                    .title(Span::styled(" Detail ", Style::default().fg(Color::Cyan))),
+            );
+        frame.render_widget(pane, area);
+    }
+    
+    fn render_suggestions(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self.suggestions
+            .iter()
+            .take(MAX_SUGGESTIONS)
+            .enumerate()
+            .map(|(i, suggestion)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("[{}] ", i + 1), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                    Span::raw(suggestion.command.clone()),
+                    Span::styled(format!("  {}", suggestion.reason), Style::default().fg(Color::DarkGray)),
+                ]))
+            })
+            .collect();
+        
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::TOP)
+                .border_style(Style::default().fg(Color::DarkGray))
+                .title(Span::styled(" 💡 Suggestions ", Style::default().fg(Color::Cyan))),
+        );
+        frame.render_widget(list, area);
     }
     
     fn render_header(&self, frame: &mut Frame, area: Rect) {
@@ -333,7 +624,11 @@ impl App {
                 ),
                 Span::raw(" │ "),
                 Span::styled(
-                    format!("{} activities", self.activities.len()),
+                    if self.filter == Filter::All {
+                        format!("{} activities", self.activities.len())
+                    } else {
+                        format!("{} of {} activities ({})", self.visible().len(), self.activities.len(), self.filter.describe())
+                    },
                     Style::default().fg(Color::Yellow),
                 ),
             ];
@@ -377,11 +672,16 @@ impl App {
         let viewport_height = area.height as usize;
         
         // If no activities, show a helpful message
-        if self.activities.is_empty() {
+        let visible = self.visible();
+        if visible.is_empty() {
             let message = Paragraph::new(
                 Line::from(vec![
                     Span::styled(
-                        "No recent activity. Run some Port42 commands to see them here!",
+                        if self.activities.is_empty() {
+                            "No recent activity. Run some Port42 commands to see them here!"
+                        } else {
+                            "Nothing matches the filter. Press f to cycle or Esc to show everything"
+                        },
                         Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
                     ),
                 ])
@@ -393,8 +693,8 @@ impl App {
             return;
         }
         
-        let items: Vec<ListItem> = self.activities
-            .iter()
+        let items: Vec<ListItem> = visible
+            .into_iter()
             .skip(self.scroll_offset)
             .take(viewport_height)
             .enumerate()
@@ -413,8 +713,8 @@ impl App {
                         timestamp_style,
                     ),
                     Span::styled(
-                        format!("{:<8} ", activity.activity_type),
-                        Style::default().fg(activity.color),
+                        format!("{:<8} ", activity.kind.label()),
+                        Style::default().fg(activity.kind.color()),
                     ),
                     Span::raw(&activity.description),
                 ];
@@ -433,28 +733,35 @@ impl App {
         frame.render_widget(list, area);
     }
     
-    fn render_footer(&self, frame: &mut Frame, area: Rect) {
-        let keybinds = vec![
-            ("q/Ctrl+C", "quit"),
-            ("↑↓", "navigate"),
-            ("PgUp/PgDn", "page"),
-            ("Home/End", "top/bottom"),
+    /// What the footer shows before its hints, and the hints themselves
+    fn footer(&self) -> (Vec<Span<'static>>, Vec<Hint>) {
+        if let Some(ref input) = self.search_input {
+            let prefix = vec![
+                Span::styled("Search: ", Style::default().fg(Color::Cyan)),
+                Span::raw(format!("{}▏ ", input)),
+            ];
+            return (prefix, vec![Hint::new("Enter", "keep", Some(KeyCode::Enter)), Hint::new("Esc", "cancel", Some(KeyCode::Esc))]);
+        }
+        let mut hints = vec![
+            Hint::new("q/Ctrl+C", "quit", Some(KeyCode::Char('q'))),
+            Hint::new("↑↓", "navigate", None),
+            Hint::new("PgUp/PgDn", "page", None),
+            Hint::new("Home/End", "top/bottom", None),
+            Hint::new("Enter", if self.detail_open { "close detail" } else { "detail" }, Some(KeyCode::Enter)),
+            Hint::new("f", "filter", Some(KeyCode::Char('f'))),
+            Hint::new("/", "search", Some(KeyCode::Char('/'))),
         ];
+        if !self.suggestions.is_empty() {
+            hints.push(Hint::new("1-9", "run suggestion", None));
+        }
+        (Vec::new(), hints)
+    }
+    
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let (mut spans, hints) = self.footer();
+        spans.extend(mouse::hint_spans(&hints));
         
-        let keybind_text: Vec<Span> = keybinds
-            .iter()
-            .flat_map(|(key, desc)| {
-                vec![
-                    Span::styled(
-                        format!("[{}]", key),
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-                    ),
-                    Span::styled(format!("{} ", desc), Style::default().fg(Color::White)),
-                ]
-            })
-            .collect();
-        
-        let footer = Paragraph::new(Line::from(keybind_text))
+        let footer = Paragraph::new(Line::from(spans))
             .block(
                 Block::default()
                     .borders(Borders::TOP)
@@ -466,6 +773,28 @@ impl App {
     }
 }
 
+/// Suggestions shown at once; one number key each
+const MAX_SUGGESTIONS: usize = 9;
+
+/// Leave the TUI, run a suggestion in the plain terminal, and come back
+/// once the user has seen its output
+fn run_outside(terminal: SafeTerminal, app: &mut App, index: usize) -> Result<SafeTerminal> {
+    drop(terminal);
+    if let Some(suggestion) = app.suggestions.get(index).cloned() {
+        println!("▶ {}", suggestion.command);
+        match suggestions::run(&mut app.daemon_client, &suggestion) {
+            Ok(0) => {}
+            Ok(code) => println!("Exited with code {}", code),
+            Err(e) => println!("❌ {}", e),
+        }
+        print!("\nPress Enter to return to the monitor...");
+        io::Write::flush(&mut io::stdout())?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+    }
+    SafeTerminal::new()
+}
+
 /// Main entry point for safe TUI
 pub fn run_safe_watch(daemon_client: DaemonClient, refresh_ms: u64) -> Result<()> {
     // Create safe terminal (will auto-restore on drop)
@@ -500,14 +829,18 @@ pub fn run_safe_watch(daemon_client: DaemonClient, refresh_ms: u64) -> Result<()
         // Poll for events with short timeout for responsiveness
         if event::poll(Duration::from_millis(50))? {
             match event::read()? {
-                Event::Key(key) => {
-                    app.handle_key(key.code, key.modifiers)?;
-                }
+                Event::Key(key) => app.handle_key(key.code, key.modifiers)?,
+                Event::Mouse(mouse) => app.handle_mouse(mouse)?,
                 Event::Resize(_, height) => {
                     app.viewport_height = height.saturating_sub(6) as usize;
                 }
                 _ => {}
             }
+            if let Some(index) = app.pending_run.take() {
+                terminal = run_outside(terminal, &mut app, index)?;
+                app.refresh_data()?;
+                last_refresh = Instant::now();
+            }
         }
     }
     
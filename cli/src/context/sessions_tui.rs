@@ -0,0 +1,358 @@
+// Live session monitor for `port42 watch sessions`
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+use std::time::{Duration, Instant};
+
+use crate::client::DaemonClient;
+use crate::common::generate_id;
+use crate::context::safe_tui::SafeTerminal;
+use crate::display::format_timestamp_relative;
+use crate::protocol::{
+    MemoryDetailRequest, MemoryDetailResponse, MemoryListRequest, MemoryListResponse,
+    RequestBuilder, ResponseParser, SessionSummary,
+};
+
+/// Open session detail, refreshed along with the list
+struct Detail {
+    session: MemoryDetailResponse,
+    scroll: u16,
+}
+
+pub struct SessionsApp {
+    sessions: Vec<SessionSummary>,
+    selected: usize,
+    scroll_offset: usize,
+    viewport_height: usize,
+    detail: Option<Detail>,
+    should_quit: bool,
+    daemon_client: DaemonClient,
+    last_error: Option<String>,
+}
+
+impl SessionsApp {
+    pub fn new(daemon_client: DaemonClient) -> Self {
+        Self {
+            sessions: Vec::new(),
+            selected: 0,
+            scroll_offset: 0,
+            viewport_height: 20,
+            detail: None,
+            should_quit: false,
+            daemon_client,
+            last_error: None,
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if code == KeyCode::Char('c') && modifiers == KeyModifiers::CONTROL {
+            self.should_quit = true;
+            return;
+        }
+
+        // With the detail pane open the arrows scroll the conversation
+        if let Some(ref mut detail) = self.detail {
+            match code {
+                KeyCode::Char('q') => self.should_quit = true,
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Backspace => self.detail = None,
+                KeyCode::Up | KeyCode::Char('k') => detail.scroll = detail.scroll.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => detail.scroll = detail.scroll.saturating_add(1),
+                KeyCode::PageUp => detail.scroll = detail.scroll.saturating_sub(10),
+                KeyCode::PageDown => detail.scroll = detail.scroll.saturating_add(10),
+                KeyCode::Home => detail.scroll = 0,
+                _ => {}
+            }
+            return;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => self.move_down(),
+            KeyCode::Home => {
+                self.selected = 0;
+                self.scroll_offset = 0;
+            }
+            KeyCode::End => {
+                self.selected = self.sessions.len().saturating_sub(1);
+                self.scroll_offset = self.selected.saturating_sub(self.viewport_height.saturating_sub(1));
+            }
+            KeyCode::Enter => self.open_detail(),
+            _ => {}
+        }
+    }
+
+    fn move_up(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+            if self.selected < self.scroll_offset {
+                self.scroll_offset = self.selected;
+            }
+        }
+    }
+
+    fn move_down(&mut self) {
+        if self.selected + 1 < self.sessions.len() {
+            self.selected += 1;
+            if self.selected >= self.scroll_offset + self.viewport_height {
+                self.scroll_offset = self.selected + 1 - self.viewport_height;
+            }
+        }
+    }
+
+    fn open_detail(&mut self) {
+        let Some(id) = self.sessions.get(self.selected).map(|s| s.id.clone()) else { return };
+        match fetch_detail(&mut self.daemon_client, &id) {
+            Ok(session) => self.detail = Some(Detail { session, scroll: 0 }),
+            Err(e) => self.last_error = Some(format!("Could not open {}: {}", id, e)),
+        }
+    }
+
+    fn refresh_data(&mut self) {
+        match fetch_sessions(&mut self.daemon_client) {
+            Ok(sessions) => {
+                // Keep the same session selected as the list reorders
+                let selected_id = self.sessions.get(self.selected).map(|s| s.id.clone());
+                self.sessions = sessions;
+                self.selected = selected_id
+                    .and_then(|id| self.sessions.iter().position(|s| s.id == id))
+                    .unwrap_or(0)
+                    .min(self.sessions.len().saturating_sub(1));
+                self.last_error = None;
+            }
+            Err(e) => self.last_error = Some(format!("Daemon error: {}", e)),
+        }
+
+        if let Some(id) = self.detail.as_ref().map(|d| d.session.id.clone()) {
+            if let Ok(session) = fetch_detail(&mut self.daemon_client, &id) {
+                if let Some(ref mut detail) = self.detail {
+                    detail.session = session;
+                }
+            }
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),  // Header
+                Constraint::Min(0),     // Body
+                Constraint::Length(3),  // Footer
+            ])
+            .split(frame.size());
+
+        self.render_header(frame, chunks[0]);
+        if self.detail.is_some() {
+            let body = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(chunks[1]);
+            self.render_sessions(frame, body[0]);
+            self.render_detail(frame, body[1]);
+        } else {
+            self.render_sessions(frame, chunks[1]);
+        }
+        self.render_footer(frame, chunks[2]);
+    }
+
+    fn render_header(&self, frame: &mut Frame, area: Rect) {
+        let spans = if let Some(err) = &self.last_error {
+            vec![
+                Span::styled("⚠️ ", Style::default().fg(Color::Red)),
+                Span::styled(err, Style::default().fg(Color::Red)),
+            ]
+        } else {
+            let active = self.sessions.iter().filter(|s| s.state == "active").count();
+            vec![
+                Span::styled("🐬 ", Style::default()),
+                Span::styled("Port42 Sessions", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::raw(" │ "),
+                Span::styled(format!("{} active", active), Style::default().fg(Color::Green)),
+                Span::raw(" │ "),
+                Span::styled(format!("{} total", self.sessions.len()), Style::default().fg(Color::Yellow)),
+            ]
+        };
+
+        let header = Paragraph::new(Line::from(spans))
+            .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(Color::DarkGray)))
+            .alignment(Alignment::Center);
+        frame.render_widget(header, area);
+    }
+
+    fn render_sessions(&mut self, frame: &mut Frame, area: Rect) {
+        self.viewport_height = (area.height as usize).max(1);
+
+        if self.sessions.is_empty() {
+            let message = Paragraph::new(Line::from(Span::styled(
+                "No sessions yet. Start one with: port42 swim @ai-engineer",
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            )))
+            .alignment(Alignment::Center);
+            frame.render_widget(message, area);
+            return;
+        }
+
+        let items: Vec<ListItem> = self.sessions
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(self.viewport_height)
+            .map(|(i, session)| {
+                let state_color = match session.state.as_str() {
+                    "active" => Color::Green,
+                    "idle" => Color::Yellow,
+                    _ => Color::Gray,
+                };
+                let tool = if session.command_generated { " 🛠" } else { "" };
+                let spans = vec![
+                    Span::styled(format!("{:<10} ", session.state), Style::default().fg(state_color)),
+                    Span::styled(format!("{:<14} ", session.agent), Style::default().fg(Color::Cyan)),
+                    Span::raw(format!("{:<24} ", session.id)),
+                    Span::styled(format!("{:>4} msgs  ", session.message_count), Style::default().fg(Color::Blue)),
+                    Span::styled(format!("{}{}", relative(last_activity(session)), tool), Style::default().fg(Color::Gray)),
+                ];
+                let style = if i == self.selected {
+                    Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(spans)).style(style)
+            })
+            .collect();
+
+        frame.render_widget(List::new(items).block(Block::default().borders(Borders::NONE)), area);
+    }
+
+    fn render_detail(&self, frame: &mut Frame, area: Rect) {
+        let Some(ref detail) = self.detail else { return };
+        let session = &detail.session;
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(session.agent.clone(), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::raw(format!("  {}  ", session.state)),
+                Span::styled(format!("started {}", session.created_at), Style::default().fg(Color::DarkGray)),
+            ]),
+        ];
+        if let Some(ref command) = session.command_generated {
+            lines.push(Line::from(Span::styled(format!("🛠 Crystallized {}", command.name), Style::default().fg(Color::Magenta))));
+        }
+        for message in &session.messages {
+            lines.push(Line::from(""));
+            let color = if message.role == "user" { Color::Yellow } else { Color::Green };
+            lines.push(Line::from(Span::styled(message.role.clone(), Style::default().fg(color).add_modifier(Modifier::BOLD))));
+            lines.extend(message.content.lines().map(|l| Line::from(l.to_string())));
+        }
+
+        let block = Block::default()
+            .borders(Borders::LEFT)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(Span::styled(format!(" {} ", session.id), Style::default().fg(Color::Blue)));
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((detail.scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let keybinds: &[(&str, &str)] = if self.detail.is_some() {
+            &[("q/Ctrl+C", "quit"), ("↑↓/PgUp/PgDn", "scroll"), ("Esc/Enter", "back")]
+        } else {
+            &[("q/Ctrl+C", "quit"), ("↑↓", "navigate"), ("Enter", "open session"), ("Home/End", "top/bottom")]
+        };
+
+        let spans: Vec<Span> = keybinds
+            .iter()
+            .flat_map(|(key, desc)| {
+                vec![
+                    Span::styled(format!("[{}]", key), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!("{} ", desc), Style::default().fg(Color::White)),
+                ]
+            })
+            .collect();
+
+        let footer = Paragraph::new(Line::from(spans))
+            .block(Block::default().borders(Borders::TOP).border_style(Style::default().fg(Color::DarkGray)))
+            .alignment(Alignment::Center);
+        frame.render_widget(footer, area);
+    }
+}
+
+/// Every known session, active first, then most recently touched
+pub(crate) fn fetch_sessions(client: &mut DaemonClient) -> Result<Vec<SessionSummary>> {
+    let response = client.request(MemoryListRequest.build_request(generate_id())?)?;
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or_else(|| "Failed to retrieve memory".to_string()));
+    }
+    let data = response.data.ok_or_else(|| anyhow::anyhow!("No data in daemon response"))?;
+    let memory = MemoryListResponse::parse_response(&data)?;
+
+    let mut sessions: Vec<SessionSummary> = memory.active_sessions.into_iter().chain(memory.recent_sessions).collect();
+    let mut seen = std::collections::HashSet::new();
+    sessions.retain(|s| seen.insert(s.id.clone()));
+    sessions.sort_by(|a, b| {
+        (b.state == "active").cmp(&(a.state == "active"))
+            .then_with(|| last_activity(b).cmp(last_activity(a)))
+    });
+    Ok(sessions)
+}
+
+pub(crate) fn fetch_detail(client: &mut DaemonClient, session_id: &str) -> Result<MemoryDetailResponse> {
+    let request = MemoryDetailRequest { session_id: session_id.to_string() }.build_request(generate_id())?;
+    let response = client.request(request)?;
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or_else(|| "session not found".to_string()));
+    }
+    let data = response.data.ok_or_else(|| anyhow::anyhow!("No data in daemon response"))?;
+    MemoryDetailResponse::parse_response(&data)
+}
+
+pub(crate) fn last_activity(session: &SessionSummary) -> &str {
+    session.last_activity.as_deref().or(session.created_at.as_deref()).unwrap_or(&session.date)
+}
+
+pub(crate) fn relative(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| format_timestamp_relative(t.timestamp_millis().max(0) as u64))
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+/// Main entry point for the sessions TUI
+pub fn run_sessions_watch(daemon_client: DaemonClient, refresh_ms: u64) -> Result<()> {
+    let mut terminal = SafeTerminal::new()?;
+    let mut app = SessionsApp::new(daemon_client);
+
+    let refresh_interval = Duration::from_millis(refresh_ms);
+    let mut last_refresh = Instant::now();
+    app.refresh_data();
+
+    loop {
+        if last_refresh.elapsed() >= refresh_interval {
+            app.refresh_data();
+            last_refresh = Instant::now();
+        }
+
+        terminal.draw(|f| app.render(f))?;
+
+        if app.should_quit {
+            break;
+        }
+
+        if event::poll(Duration::from_millis(50))? {
+            if let Event::Key(key) = event::read()? {
+                app.handle_key(key.code, key.modifiers);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! Running what `port42 context` suggests
+//!
+//! Suggestions are numbered from 1 in `context --pretty` and the watch TUI.
+//! `port42 context run <n>`, or the number key in the TUI, runs one and
+//! tells the daemon it was taken up. A suggestion is split into words and
+//! run directly, never through a shell; a leading `port42` runs this binary
+//! against the same daemon.
+
+use anyhow::{Context, Result};
+use std::process::Command;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::client::DaemonClient;
+use crate::common::{errors::Port42Error, generate_id, utils::split_words};
+use crate::protocol::{DaemonRequest, RequestBuilder};
+use crate::protocol::hooks::SuggestionFeedbackRequest;
+use super::{ContextData, ContextSuggestion};
+
+/// Feedback is best effort; the command already ran
+const FEEDBACK_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The current snapshot, as `port42 context` shows it
+pub fn fetch(client: &mut DaemonClient) -> Result<ContextData> {
+    let response = client.request(DaemonRequest {
+        request_type: "context".to_string(),
+        id: generate_id(),
+        payload: serde_json::json!({}),
+        references: None,
+        session_context: None,
+        user_prompt: None,
+        provider: None,
+    })?;
+    if !response.success {
+        let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(Port42Error::from_daemon(&error)).context("Failed to get context");
+    }
+    Ok(serde_json::from_value(response.data.context("No data in daemon response")?)?)
+}
+
+/// Suggestion `number`, counting from 1 as they are shown
+pub fn pick(data: &ContextData, number: usize) -> Result<&ContextSuggestion> {
+    match number.checked_sub(1).and_then(|i| data.suggestions.get(i)) {
+        Some(suggestion) => Ok(suggestion),
+        None if data.suggestions.is_empty() => Err(Port42Error::NotFound("There are no suggestions right now".to_string()).into()),
+        None => Err(Port42Error::Usage(format!(
+            "No suggestion {}; pick 1 to {} (see 'port42 context --pretty')", number, data.suggestions.len()
+        )).into()),
+    }
+}
+
+/// The program and arguments a suggestion stands for
+pub fn command_line(suggestion: &ContextSuggestion) -> Result<(String, Vec<String>)> {
+    let mut words = split_words(&suggestion.command)?.into_iter();
+    let Some(program) = words.next() else {
+        return Err(Port42Error::Usage("The suggestion has no command".to_string()).into());
+    };
+    let program = if program == "port42" {
+        std::env::current_exe().map(|p| p.display().to_string()).unwrap_or(program)
+    } else {
+        program
+    };
+    Ok((program, words.collect()))
+}
+
+/// Run a suggestion in the foreground, report it to the daemon and return its exit code
+pub fn run(client: &mut DaemonClient, suggestion: &ContextSuggestion) -> Result<i32> {
+    let (program, args) = command_line(suggestion)?;
+    let status = Command::new(&program)
+        .args(&args)
+        .env("PORT42_PORT", client.port().to_string())
+        .status()
+        .with_context(|| format!("Failed to start {}", program))?;
+    let code = status.code().unwrap_or(1);
+
+    let request = SuggestionFeedbackRequest {
+        command: suggestion.command.clone(),
+        reason: suggestion.reason.clone(),
+        accepted: true,
+        exit_code: Some(code),
+    }.build_request(generate_id())?;
+    if let Err(e) = client.request_timeout(request, FEEDBACK_TIMEOUT) {
+        debug!("failed to record suggestion feedback: {}", e);
+    }
+    Ok(code)
+}
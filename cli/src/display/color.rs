@@ -0,0 +1,41 @@
+//! When to colour output
+//!
+//! `--color` wins, then `PORT42_COLOR`, then `NO_COLOR` (any non-empty
+//! value, see no-color.org), then `color` in the config file. Left unset,
+//! output is coloured only when stdout is a terminal that can show it, so
+//! piping into files and other tools gets plain text.
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// The mode in force, from the flag, environment and config value in turn
+    pub fn resolve(flag: Option<ColorMode>, port42_color: Option<&str>, no_color: Option<&str>, configured: Option<&str>) -> ColorMode {
+        let named = |value: Option<&str>| value.and_then(|v| <ColorMode as clap::ValueEnum>::from_str(v, true).ok());
+        flag.or_else(|| named(port42_color))
+            .or_else(|| no_color.filter(|v| !v.is_empty()).map(|_| ColorMode::Never))
+            .or_else(|| named(configured))
+            .unwrap_or(ColorMode::Auto)
+    }
+
+    /// Whether to colour, given what stdout is attached to
+    pub fn enabled(self, terminal: bool) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => terminal && std::env::var("TERM").as_deref() != Ok("dumb"),
+        }
+    }
+}
+
+/// Decide once for the whole run; everything printed through `colored`,
+/// including help_text's formatters and the display types, follows it
+pub fn apply(mode: ColorMode) -> bool {
+    let enabled = mode.enabled(atty::is(atty::Stream::Stdout));
+    colored::control::set_override(enabled);
+    enabled
+}
@@ -0,0 +1,236 @@
+//! Line diffs for `diff`
+//!
+//! A longest-common-subsequence diff over lines, grouped into unified-diff
+//! hunks. Lines shared at the start and end are set aside before the LCS
+//! table is built, so the usual small edit to a long tool stays cheap; a
+//! middle too large for the table is shown as removed wholesale and re-added.
+//!
+//! Structured output is the same changes as an RFC 6902 JSON Patch over the
+//! old text's lines, so other tools can apply it.
+
+use anyhow::Result;
+use colored::*;
+use json_patch::jsonptr::PointerBuf;
+use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation};
+use serde::Serialize;
+
+use super::{Displayable, OutputFormat, print_yaml};
+
+/// Lines of unchanged context around each change, as in `diff -u`
+pub const DEFAULT_CONTEXT: usize = 3;
+
+/// Largest LCS table, in cells, before falling back to remove-then-add
+const MAX_TABLE: usize = 4_000_000;
+
+/// One line of a diff
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", content = "line", rename_all = "lowercase")]
+pub enum Change {
+    Keep(String),
+    Remove(String),
+    Add(String),
+}
+
+impl Change {
+    fn is_edit(&self) -> bool {
+        !matches!(self, Change::Keep(_))
+    }
+}
+
+/// Line-by-line changes that turn `old` into `new`
+pub fn diff_lines(old: &str, new: &str) -> Vec<Change> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let keep = |line: &&str| Change::Keep(line.to_string());
+    let mut changes: Vec<Change> = a[..prefix].iter().map(keep).collect();
+    changes.extend(diff_middle(&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]));
+    changes.extend(a[a.len() - suffix..].iter().map(keep));
+    changes
+}
+
+fn diff_middle(a: &[&str], b: &[&str]) -> Vec<Change> {
+    let removed = |lines: &[&str]| lines.iter().map(|l| Change::Remove(l.to_string())).collect::<Vec<_>>();
+    let added = |lines: &[&str]| lines.iter().map(|l| Change::Add(l.to_string())).collect::<Vec<_>>();
+    if a.is_empty() || b.is_empty() || (a.len() + 1).saturating_mul(b.len() + 1) > MAX_TABLE {
+        let mut changes = removed(a);
+        changes.extend(added(b));
+        return changes;
+    }
+
+    // lcs[i * width + j]: length of the longest common subsequence of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = if a[i] == b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            changes.push(Change::Keep(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            changes.push(Change::Remove(a[i].to_string()));
+            i += 1;
+        } else {
+            changes.push(Change::Add(b[j].to_string()));
+            j += 1;
+        }
+    }
+    changes.extend(removed(&a[i..]));
+    changes.extend(added(&b[j..]));
+    changes
+}
+
+/// The changes as a JSON Patch that turns the old lines, as a JSON array of
+/// strings, into the new ones
+pub fn json_patch(changes: &[Change]) -> Patch {
+    let mut operations = Vec::new();
+    // Where the next change lands in the array as patched so far
+    let mut at = 0;
+    for change in changes {
+        match change {
+            Change::Keep(_) => at += 1,
+            Change::Remove(_) => operations.push(PatchOperation::Remove(RemoveOperation {
+                path: PointerBuf::from_tokens([at]),
+            })),
+            Change::Add(line) => {
+                operations.push(PatchOperation::Add(AddOperation {
+                    path: PointerBuf::from_tokens([at]),
+                    value: serde_json::Value::String(line.clone()),
+                }));
+                at += 1;
+            }
+        }
+    }
+    Patch(operations)
+}
+
+/// A run of changes with its surrounding context
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub changes: Vec<Change>,
+}
+
+impl Hunk {
+    /// `@@ -old +new @@`, with counts of one left out as `diff -u` does
+    pub fn header(&self) -> String {
+        let range = |start: usize, count: usize| {
+            if count == 1 { start.to_string() } else { format!("{},{}", start, count) }
+        };
+        format!("@@ -{} +{} @@", range(self.old_start, self.old_lines), range(self.new_start, self.new_lines))
+    }
+}
+
+/// Group changes into hunks, each edit keeping `context` unchanged lines
+/// either side; hunks whose context would touch are merged
+pub fn hunks(changes: &[Change], context: usize) -> Vec<Hunk> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (at, _) in changes.iter().enumerate().filter(|(_, c)| c.is_edit()) {
+        let start = at.saturating_sub(context);
+        let end = (at + context + 1).min(changes.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    // Old and new line numbers reached before each change
+    let mut before = Vec::with_capacity(changes.len());
+    let (mut old, mut new) = (0, 0);
+    for change in changes {
+        before.push((old, new));
+        match change {
+            Change::Keep(_) => { old += 1; new += 1; }
+            Change::Remove(_) => old += 1,
+            Change::Add(_) => new += 1,
+        }
+    }
+
+    ranges.into_iter().map(|(start, end)| {
+        let slice = &changes[start..end];
+        let old_lines = slice.iter().filter(|c| !matches!(c, Change::Add(_))).count();
+        let new_lines = slice.iter().filter(|c| !matches!(c, Change::Remove(_))).count();
+        // An empty side is numbered by the line before it, so 0 means "at the top"
+        let (old_before, new_before) = before[start];
+        Hunk {
+            old_start: old_before + usize::from(old_lines > 0),
+            old_lines,
+            new_start: new_before + usize::from(new_lines > 0),
+            new_lines,
+            changes: slice.to_vec(),
+        }
+    }).collect()
+}
+
+/// The difference between two texts, ready to display
+#[derive(Debug, Serialize)]
+pub struct TextDiff {
+    pub from: String,
+    pub to: String,
+    pub identical: bool,
+    pub hunks: Vec<Hunk>,
+    /// Every change, for structured output
+    #[serde(skip)]
+    pub patch: Patch,
+}
+
+impl TextDiff {
+    pub fn new(from: &str, old: &str, to: &str, new: &str, context: usize) -> Self {
+        let changes = diff_lines(old, new);
+        let hunks = hunks(&changes, context);
+        TextDiff { from: from.to_string(), to: to.to_string(), identical: hunks.is_empty(), hunks, patch: json_patch(&changes) }
+    }
+
+    /// The diff in unified format, coloured when colour is on
+    pub fn unified(&self) -> String {
+        let mut out = vec![
+            format!("--- {}", self.from).bold().to_string(),
+            format!("+++ {}", self.to).bold().to_string(),
+        ];
+        for hunk in &self.hunks {
+            out.push(hunk.header().cyan().to_string());
+            out.extend(hunk.changes.iter().map(|change| match change {
+                Change::Keep(line) => format!(" {}", line),
+                Change::Remove(line) => format!("-{}", line).red().to_string(),
+                Change::Add(line) => format!("+{}", line).green().to_string(),
+            }));
+        }
+        out.join("\n")
+    }
+}
+
+impl Displayable for TextDiff {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&self.patch)?),
+            OutputFormat::Yaml => print_yaml(&self.patch)?,
+            OutputFormat::Plain | OutputFormat::Table => {
+                if self.identical {
+                    println!("{}", format!("✨ {} and {} are identical", self.from, self.to).dimmed());
+                    return Ok(());
+                }
+                println!("{}", self.unified());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+//! Syntax highlighting for `cat`
+//!
+//! Tokens come from syntect's bundled Sublime grammars, so heredocs, nested
+//! quotes and multi-line strings are read the way the language reads them.
+//! Their scopes are painted with a small terminal theme: keywords, strings,
+//! comments, numbers and headings. Languages syntect has no grammar for
+//! (TypeScript, TOML) are left plain rather than guessed at.
+
+use colored::*;
+use std::sync::OnceLock;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Shell,
+    Python,
+    JavaScript,
+    TypeScript,
+    Rust,
+    Go,
+    Ruby,
+    Json,
+    Toml,
+    Yaml,
+    Markdown,
+}
+
+impl Language {
+    /// Pick a language from, in order: the daemon's metadata, the shebang
+    /// line, then the file extension
+    pub fn detect(path: &str, content: &str, hint: Option<&str>) -> Option<Language> {
+        hint.and_then(Language::named)
+            .or_else(|| content.lines().next().and_then(Language::from_shebang))
+            .or_else(|| {
+                let name = path.rsplit('/').next().unwrap_or(path);
+                let (_, ext) = name.rsplit_once('.')?;
+                Language::from_extension(ext)
+            })
+    }
+
+    pub fn named(name: &str) -> Option<Language> {
+        Some(match name.to_lowercase().as_str() {
+            "bash" | "sh" | "shell" | "zsh" => Language::Shell,
+            "python" | "python3" => Language::Python,
+            "javascript" | "js" | "node" => Language::JavaScript,
+            "typescript" | "ts" => Language::TypeScript,
+            "rust" => Language::Rust,
+            "go" | "golang" => Language::Go,
+            "ruby" => Language::Ruby,
+            "json" => Language::Json,
+            "toml" => Language::Toml,
+            "yaml" => Language::Yaml,
+            "markdown" => Language::Markdown,
+            _ => return None,
+        })
+    }
+
+    fn from_shebang(line: &str) -> Option<Language> {
+        let command = line.strip_prefix("#!")?;
+        let mut words = command.split_whitespace();
+        let mut program = words.next()?.rsplit('/').next()?;
+        if program == "env" {
+            program = words.find(|w| !w.starts_with('-'))?;
+        }
+        // python3.11, node18 and friends
+        let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        Language::named(program)
+    }
+
+    fn from_extension(ext: &str) -> Option<Language> {
+        Some(match ext.to_lowercase().as_str() {
+            "sh" | "bash" | "zsh" => Language::Shell,
+            "py" => Language::Python,
+            "js" | "mjs" | "cjs" | "jsx" => Language::JavaScript,
+            "ts" | "tsx" => Language::TypeScript,
+            "rs" => Language::Rust,
+            "go" => Language::Go,
+            "rb" => Language::Ruby,
+            "json" => Language::Json,
+            "toml" => Language::Toml,
+            "yml" | "yaml" => Language::Yaml,
+            "md" | "markdown" => Language::Markdown,
+            _ => return None,
+        })
+    }
+
+    /// syntect's grammar for the language, if it bundles one
+    fn syntax(self) -> Option<&'static SyntaxReference> {
+        let extension = match self {
+            Language::Shell => "sh",
+            Language::Python => "py",
+            Language::JavaScript => "js",
+            Language::Rust => "rs",
+            Language::Go => "go",
+            Language::Ruby => "rb",
+            Language::Json => "json",
+            Language::Yaml => "yaml",
+            Language::Markdown => "md",
+            Language::TypeScript | Language::Toml => return None,
+        };
+        syntaxes().find_syntax_by_extension(extension)
+    }
+}
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Paint {
+    Fg(Color),
+    Bold,
+    Dim,
+    Plain,
+}
+
+impl Paint {
+    fn apply(self, text: &str) -> String {
+        match self {
+            Paint::Fg(color) => text.color(color).to_string(),
+            Paint::Bold => text.bold().to_string(),
+            Paint::Dim => text.dimmed().to_string(),
+            Paint::Plain => text.to_string(),
+        }
+    }
+}
+
+/// Colours for each kind of token
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    keyword: Paint,
+    string: Paint,
+    comment: Paint,
+    number: Paint,
+    /// Shebangs and markdown headings
+    heading: Paint,
+}
+
+/// Themes `cat` can use, set with `theme` in ~/.port42/config.toml or PORT42_THEME
+pub const THEME_NAMES: &[&str] = &["default", "ocean", "ember", "mono"];
+
+impl Theme {
+    pub fn named(name: &str) -> Option<Theme> {
+        let (keyword, string, comment, number, heading) = match name {
+            "default" => (Paint::Fg(Color::BrightMagenta), Paint::Fg(Color::Green), Paint::Dim, Paint::Fg(Color::Cyan), Paint::Fg(Color::Yellow)),
+            "ocean" => (Paint::Fg(Color::BrightBlue), Paint::Fg(Color::BrightCyan), Paint::Dim, Paint::Fg(Color::Magenta), Paint::Fg(Color::BrightBlue)),
+            "ember" => (Paint::Fg(Color::BrightRed), Paint::Fg(Color::Yellow), Paint::Dim, Paint::Fg(Color::BrightYellow), Paint::Fg(Color::Red)),
+            "mono" => (Paint::Bold, Paint::Plain, Paint::Dim, Paint::Plain, Paint::Bold),
+            _ => return None,
+        };
+        Some(Theme { keyword, string, comment, number, heading })
+    }
+
+    /// PORT42_THEME, then `theme` from the config file, then the default
+    pub fn current() -> Theme {
+        static THEME: OnceLock<Theme> = OnceLock::new();
+        *THEME.get_or_init(|| {
+            std::env::var("PORT42_THEME").ok()
+                .or_else(|| crate::config::Config::load_or_default().theme)
+                .and_then(|name| Theme::named(&name))
+                .unwrap_or_else(|| Theme::named("default").expect("default theme exists"))
+        })
+    }
+}
+
+/// Highlights a file line by line; the parser carries heredocs, block
+/// comments and multi-line strings from one line to the next
+pub struct Highlighter {
+    theme: Theme,
+    /// None for languages without a grammar, or once parsing has failed
+    parser: Option<(ParseState, ScopeStack)>,
+    first_line: bool,
+}
+
+impl Highlighter {
+    pub fn new(language: Language, theme: Theme) -> Self {
+        let parser = language.syntax().map(|syntax| (ParseState::new(syntax), ScopeStack::new()));
+        Self { theme, parser, first_line: true }
+    }
+
+    pub fn line(&mut self, line: &str) -> String {
+        let first = std::mem::replace(&mut self.first_line, false);
+        if first && line.starts_with("#!") {
+            return self.theme.heading.apply(line);
+        }
+        match self.painted(line) {
+            Some(out) => out,
+            None => {
+                // A grammar that trips up once can't be trusted for the rest of the file
+                self.parser = None;
+                line.to_string()
+            }
+        }
+    }
+
+    fn painted(&mut self, line: &str) -> Option<String> {
+        let theme = self.theme;
+        let (state, stack) = self.parser.as_mut()?;
+        // The grammars expect each line with its newline
+        let text = format!("{}\n", line);
+        let ops = state.parse_line(&text, syntaxes()).ok()?;
+
+        let mut out = String::new();
+        let mut run = String::new();
+        let mut run_paint = Paint::Plain;
+        let mut at = 0;
+        let mut paint_until = |end: usize, paint: Paint, out: &mut String, run: &mut String| {
+            let end = end.min(line.len());
+            if end <= at {
+                return;
+            }
+            if paint != run_paint {
+                out.push_str(&run_paint.apply(run));
+                run.clear();
+                run_paint = paint;
+            }
+            run.push_str(&line[at..end]);
+            at = end;
+        };
+        for (position, op) in ops {
+            paint_until(position, theme.paint_for(stack), &mut out, &mut run);
+            stack.apply(&op).ok()?;
+        }
+        paint_until(line.len(), theme.paint_for(stack), &mut out, &mut run);
+        out.push_str(&run_paint.apply(&run));
+        Some(out)
+    }
+}
+
+/// Scope prefixes for each kind of token, innermost scope deciding
+struct Scopes {
+    comment: [Scope; 1],
+    string: [Scope; 2],
+    number: [Scope; 1],
+    keyword: [Scope; 3],
+    operator: Scope,
+    heading: [Scope; 2],
+}
+
+fn scopes() -> &'static Scopes {
+    static SCOPES: OnceLock<Scopes> = OnceLock::new();
+    SCOPES.get_or_init(|| {
+        let scope = |name: &str| Scope::new(name).expect("valid scope");
+        Scopes {
+            comment: [scope("comment")],
+            string: [scope("string"), scope("markup.raw")],
+            number: [scope("constant.numeric")],
+            keyword: [scope("keyword"), scope("storage"), scope("constant.language")],
+            operator: scope("keyword.operator"),
+            heading: [scope("markup.heading"), scope("entity.name.section")],
+        }
+    })
+}
+
+impl Theme {
+    fn paint_for(&self, stack: &ScopeStack) -> Paint {
+        let kinds = scopes();
+        let is = |prefixes: &[Scope], scope: &Scope| prefixes.iter().any(|p| p.is_prefix_of(*scope));
+        for scope in stack.as_slice().iter().rev() {
+            if is(&kinds.comment, scope) {
+                return self.comment;
+            }
+            if is(&kinds.string, scope) {
+                return self.string;
+            }
+            if is(&kinds.number, scope) {
+                return self.number;
+            }
+            if is(&kinds.keyword, scope) && !kinds.operator.is_prefix_of(*scope) {
+                return self.keyword;
+            }
+            if is(&kinds.heading, scope) {
+                return self.heading;
+            }
+        }
+        Paint::Plain
+    }
+}
+
+/// Highlight a whole file, or None when its language isn't recognised
+pub fn highlight(path: &str, content: &str, hint: Option<&str>) -> Option<String> {
+    let language = Language::detect(path, content, hint)?;
+    language.syntax()?;
+    let mut highlighter = Highlighter::new(language, Theme::current());
+    let lines: Vec<String> = content.lines().map(|line| highlighter.line(line)).collect();
+    Some(lines.join("\n"))
+}
@@ -0,0 +1,122 @@
+//! Markdown for the terminal
+//!
+//! Enough of CommonMark to read a document comfortably: headings, lists,
+//! quotes, rules, fenced code (highlighted when the language is known) and
+//! the inline marks for code, emphasis and links. Anything else passes
+//! through as written.
+
+use colored::*;
+use regex::{Captures, Regex};
+use std::sync::OnceLock;
+
+use super::highlight::{Highlighter, Language, Theme};
+
+/// Render a whole document, one terminal line per source line
+pub fn render(markdown: &str) -> String {
+    let mut out = Vec::new();
+    // Inside a fenced block: its highlighter, or None for an unknown language
+    let mut fence: Option<Option<Highlighter>> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = match fence {
+                Some(_) => None,
+                None => {
+                    let lang = trimmed.trim_start_matches(['`', '~']).trim();
+                    Some(Language::named(lang).map(|language| Highlighter::new(language, Theme::current())))
+                }
+            };
+            continue;
+        }
+        if let Some(ref mut highlighter) = fence {
+            let code = match highlighter {
+                Some(highlighter) => highlighter.line(line),
+                None => line.dimmed().to_string(),
+            };
+            out.push(format!("    {}", code));
+            continue;
+        }
+        out.push(block_line(line));
+    }
+    out.join("\n")
+}
+
+fn block_line(line: &str) -> String {
+    static ORDERED: OnceLock<Regex> = OnceLock::new();
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    if let Some(heading) = heading(trimmed) {
+        return heading;
+    }
+    if is_rule(trimmed) {
+        return "─".repeat(40).dimmed().to_string();
+    }
+    if let Some(quoted) = trimmed.strip_prefix('>') {
+        return format!("{}{} {}", indent, "│".dimmed(), inline(quoted.trim_start()).italic());
+    }
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(item) = trimmed.strip_prefix(marker) {
+            let item = match item.get(..4) {
+                Some("[ ] ") => format!("☐ {}", inline(&item[4..])),
+                Some("[x] ") | Some("[X] ") => format!("{} {}", "☑".green(), inline(&item[4..]).dimmed()),
+                _ => inline(item),
+            };
+            return format!("{}  {} {}", indent, "•".cyan(), item);
+        }
+    }
+    let ordered = ORDERED.get_or_init(|| Regex::new(r"^(\d+[.)])\s+(.*)$").expect("valid pattern"));
+    if let Some(c) = ordered.captures(trimmed) {
+        return format!("{}  {} {}", indent, c[1].cyan(), inline(&c[2]));
+    }
+    format!("{}{}", indent, inline(trimmed))
+}
+
+fn heading(line: &str) -> Option<String> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let text = line[level..].strip_prefix(' ')?.trim_end_matches(['#', ' ']);
+    let text = inline(text);
+    Some(match level {
+        1 => text.bright_blue().bold().underline().to_string(),
+        2 => text.bright_cyan().bold().to_string(),
+        _ => text.bold().to_string(),
+    })
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3 && ["-", "*", "_"].iter().any(|mark| compact.chars().all(|c| c.to_string() == *mark))
+}
+
+/// Code spans, links, bold and emphasis within one line
+fn inline(text: &str) -> String {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    static BOLD: OnceLock<Regex> = OnceLock::new();
+    static EMPHASIS: OnceLock<Regex> = OnceLock::new();
+    let link = LINK.get_or_init(|| Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").expect("valid pattern"));
+    let bold = BOLD.get_or_init(|| Regex::new(r"\*\*([^*]+)\*\*|__([^_]+)__").expect("valid pattern"));
+    let emphasis = EMPHASIS.get_or_init(|| Regex::new(r"(^|[\s(])[*_]([^*_\s][^*_]*)[*_]").expect("valid pattern"));
+
+    // Odd pieces between backticks are code, left exactly as written; a
+    // backtick that is never closed stays a backtick
+    let pieces: Vec<&str> = text.split('`').collect();
+    let unclosed = pieces.len().is_multiple_of(2);
+    pieces.iter()
+        .enumerate()
+        .map(|(n, piece)| {
+            if unclosed && n == pieces.len() - 1 {
+                return format!("`{}", piece);
+            }
+            if n % 2 == 1 {
+                return piece.yellow().to_string();
+            }
+            let piece = link.replace_all(piece, |c: &Captures| format!("{} {}", c[1].underline(), format!("({})", &c[2]).dimmed()));
+            let piece = bold.replace_all(&piece, |c: &Captures| c.get(1).or(c.get(2)).map_or("", |m| m.as_str()).bold().to_string());
+            emphasis.replace_all(&piece, |c: &Captures| format!("{}{}", &c[1], c[2].italic())).into_owned()
+        })
+        .collect()
+}
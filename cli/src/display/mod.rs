@@ -1,10 +1,18 @@
 use anyhow::Result;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
 pub enum OutputFormat {
     Plain,
     Json,
     Table,
+    Yaml,
+}
+
+impl OutputFormat {
+    /// Machine-readable formats, which get no banners or progress lines
+    pub fn is_structured(self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Yaml)
+    }
 }
 
 pub trait Displayable {
@@ -13,4 +21,13 @@ pub trait Displayable {
 
 // Re-export components
 pub mod components;
-pub use components::*;
\ No newline at end of file
+pub use components::*;
+pub mod yaml;
+pub use yaml::print_yaml;
+pub mod pager;
+pub use pager::Pager;
+pub mod highlight;
+pub mod diff;
+pub mod markdown;
+pub mod color;
+pub use color::ColorMode;
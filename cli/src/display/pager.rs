@@ -0,0 +1,198 @@
+//! Paging for output taller than the terminal
+//!
+//! `Pager::start` points stdout at a pipe for as long as the guard lives.
+//! Output is held back until it outgrows the screen: if it never does, it is
+//! written to the terminal untouched when the guard drops; once it does,
+//! `$PORT42_PAGER`, `$PAGER` or `less -R` is started and everything is
+//! streamed through it. Commands keep using `println!` and never know.
+
+/// Used when neither PORT42_PAGER nor PAGER is set
+pub const DEFAULT_PAGER: &str = "less -R";
+
+/// Pager command line from the environment; None when paging is switched off
+/// with an empty value or `cat`
+pub fn pager_command() -> Option<Vec<String>> {
+    let command = std::env::var("PORT42_PAGER")
+        .or_else(|_| std::env::var("PAGER"))
+        .unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    let parts: Vec<String> = command.split_whitespace().map(String::from).collect();
+    match parts.first().map(String::as_str) {
+        None | Some("cat") => None,
+        Some(_) => Some(parts),
+    }
+}
+
+/// Terminal rows taken by `text` at the given width, ignoring colour codes
+pub fn screen_rows(text: &str, columns: usize) -> usize {
+    let columns = columns.max(1);
+    text.lines()
+        .map(|line| visible_width(line).div_ceil(columns).max(1))
+        .sum()
+}
+
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip a CSI sequence: ESC [ params final-byte
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+pub use imp::Pager;
+
+#[cfg(unix)]
+mod imp {
+    use std::fs::File;
+    use std::io::{self, Read, Write};
+    use std::os::unix::io::{FromRawFd, RawFd};
+    use std::process::{Child, Command, Stdio};
+    use std::thread::JoinHandle;
+
+    /// Routes stdout through a pager while alive; see the module docs
+    pub struct Pager {
+        redirect: Option<(RawFd, JoinHandle<()>)>,
+    }
+
+    impl Pager {
+        /// A no-op unless `enabled`, stdout is a terminal and a pager is configured
+        pub fn start(enabled: bool) -> Pager {
+            let idle = Pager { redirect: None };
+            if !enabled || !atty::is(atty::Stream::Stdout) {
+                return idle;
+            }
+            let Some(command) = super::pager_command() else { return idle };
+            let Ok((columns, rows)) = crossterm::terminal::size() else { return idle };
+            let _ = io::stdout().flush();
+
+            let mut fds = [0; 2];
+            // SAFETY: plain descriptor juggling; every fd is checked before use
+            // and each end has exactly one owner afterwards
+            unsafe {
+                if libc::pipe(fds.as_mut_ptr()) != 0 {
+                    return idle;
+                }
+                let saved = libc::dup(libc::STDOUT_FILENO);
+                let terminal = libc::dup(libc::STDOUT_FILENO);
+                if saved < 0 || terminal < 0 || libc::dup2(fds[1], libc::STDOUT_FILENO) < 0 {
+                    for fd in [fds[0], fds[1], saved, terminal] {
+                        if fd >= 0 {
+                            libc::close(fd);
+                        }
+                    }
+                    return idle;
+                }
+                libc::close(fds[1]);
+                let output = File::from_raw_fd(fds[0]);
+                let terminal = File::from_raw_fd(terminal);
+                // Leave a row for the prompt that follows
+                let limit = (rows as usize).saturating_sub(1);
+                let relay = std::thread::spawn(move || relay(output, terminal, command, limit, columns as usize));
+                Pager { redirect: Some((saved, relay)) }
+            }
+        }
+    }
+
+    impl Drop for Pager {
+        fn drop(&mut self) {
+            let Some((saved, relay)) = self.redirect.take() else { return };
+            let _ = io::stdout().flush();
+            // Putting the terminal back closes the pipe, which ends the relay
+            unsafe {
+                libc::dup2(saved, libc::STDOUT_FILENO);
+                libc::close(saved);
+            }
+            let _ = relay.join();
+        }
+    }
+
+    enum Sink {
+        Holding(Vec<u8>),
+        Pager(Child),
+        Terminal,
+    }
+
+    fn relay(mut output: File, mut terminal: File, command: Vec<String>, limit: usize, columns: usize) {
+        let mut sink = Sink::Holding(Vec::new());
+        let mut chunk = [0u8; 8192];
+        loop {
+            let n = match output.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            match &mut sink {
+                Sink::Holding(held) => {
+                    held.extend_from_slice(&chunk[..n]);
+                    if super::screen_rows(&String::from_utf8_lossy(held), columns) > limit {
+                        let held = std::mem::take(held);
+                        sink = match spawn(&command, &terminal) {
+                            Some(mut child) => {
+                                if let Some(stdin) = child.stdin.as_mut() {
+                                    let _ = stdin.write_all(&held);
+                                }
+                                Sink::Pager(child)
+                            }
+                            None => {
+                                let _ = terminal.write_all(&held);
+                                Sink::Terminal
+                            }
+                        };
+                    }
+                }
+                // Writes fail once the reader quits the pager; keep draining
+                // so the command itself never blocks
+                Sink::Pager(child) => {
+                    if let Some(stdin) = child.stdin.as_mut() {
+                        let _ = stdin.write_all(&chunk[..n]);
+                    }
+                }
+                Sink::Terminal => {
+                    let _ = terminal.write_all(&chunk[..n]);
+                }
+            }
+        }
+        match sink {
+            Sink::Holding(held) => {
+                let _ = terminal.write_all(&held);
+            }
+            Sink::Pager(mut child) => {
+                drop(child.stdin.take());
+                let _ = child.wait();
+            }
+            Sink::Terminal => {}
+        }
+    }
+
+    fn spawn(command: &[String], terminal: &File) -> Option<Child> {
+        let mut pager = Command::new(&command[0]);
+        pager.args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::from(terminal.try_clone().ok()?));
+        // Let less pass colours through when the user set it up without -R
+        if std::env::var_os("LESS").is_none() {
+            pager.env("LESS", "R");
+        }
+        pager.spawn().ok()
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    /// Paging needs descriptor redirection; elsewhere output goes straight out
+    pub struct Pager;
+
+    impl Pager {
+        pub fn start(_enabled: bool) -> Pager {
+            Pager
+        }
+    }
+}
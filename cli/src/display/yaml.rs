@@ -0,0 +1,138 @@
+//! YAML rendering for `--output yaml`
+//!
+//! Output goes through `serde_json::Value`, so every type that serializes to
+//! JSON renders the same data as YAML. Multi-line strings become literal
+//! blocks; anything YAML would read as another type is double-quoted.
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+pub fn print_yaml<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    print!("{}", to_yaml(&serde_json::to_value(value)?));
+    Ok(())
+}
+
+pub fn to_yaml(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) if !map.is_empty() => write_map(&mut out, map, 0),
+        Value::Array(items) if !items.is_empty() => write_seq(&mut out, items, 0),
+        _ => {
+            out.push_str(&scalar(value));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn write_map(out: &mut String, map: &Map<String, Value>, indent: usize) {
+    for (key, value) in map {
+        out.push_str(&" ".repeat(indent));
+        out.push_str(&quote_if_needed(key));
+        out.push(':');
+        match value {
+            Value::Object(child) if !child.is_empty() => {
+                out.push('\n');
+                write_map(out, child, indent + 2);
+            }
+            Value::Array(items) if !items.is_empty() => {
+                out.push('\n');
+                write_seq(out, items, indent);
+            }
+            _ => write_scalar(out, value, indent + 2),
+        }
+    }
+}
+
+fn write_seq(out: &mut String, items: &[Value], indent: usize) {
+    for item in items {
+        out.push_str(&" ".repeat(indent));
+        out.push('-');
+        match item {
+            Value::Object(map) if !map.is_empty() => {
+                // The first key shares the dash's line
+                let mut nested = String::new();
+                write_map(&mut nested, map, indent + 2);
+                out.push(' ');
+                out.push_str(&nested[indent + 2..]);
+            }
+            Value::Array(inner) if !inner.is_empty() => {
+                out.push('\n');
+                write_seq(out, inner, indent + 2);
+            }
+            _ => write_scalar(out, item, indent + 2),
+        }
+    }
+}
+
+/// A value after `key:` or `-`, as a literal block when it spans lines
+fn write_scalar(out: &mut String, value: &Value, indent: usize) {
+    out.push(' ');
+    if let Value::String(s) = value {
+        if let Some(block) = literal_block(s, indent) {
+            out.push_str(&block);
+            return;
+        }
+    }
+    out.push_str(&scalar(value));
+    out.push('\n');
+}
+
+fn literal_block(s: &str, indent: usize) -> Option<String> {
+    let body = s.strip_suffix('\n').unwrap_or(s);
+    let plain = s.contains('\n')
+        && !body.ends_with('\n')
+        && !s.starts_with([' ', '\t'])
+        && !s.chars().any(|c| c.is_control() && c != '\n' && c != '\t');
+    if !plain {
+        return None;
+    }
+
+    let mut block = String::from(if s.ends_with('\n') { "|\n" } else { "|-\n" });
+    for line in body.split('\n') {
+        if !line.is_empty() {
+            block.push_str(&" ".repeat(indent));
+            block.push_str(line);
+        }
+        block.push('\n');
+    }
+    Some(block)
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => quote_if_needed(s),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+fn quote_if_needed(s: &str) -> String {
+    if needs_quotes(s) {
+        // JSON string syntax is valid double-quoted YAML
+        serde_json::to_string(s).unwrap_or_else(|_| format!("\"{}\"", s))
+    } else {
+        s.to_string()
+    }
+}
+
+fn needs_quotes(s: &str) -> bool {
+    const RESERVED: &[&str] = &["null", "~", "true", "false", "yes", "no", "on", "off", "y", "n"];
+    if s.is_empty() || s.trim() != s || RESERVED.contains(&s.to_lowercase().as_str()) {
+        return true;
+    }
+    // Numbers, and dates or times that YAML would read as timestamps
+    if s.starts_with(|c: char| c.is_ascii_digit()) || s.parse::<f64>().is_ok() {
+        return true;
+    }
+    let first = s.chars().next().unwrap_or(' ');
+    "-?:,[]{}#&*!|>'\"%@`".contains(first)
+        || s.contains(": ")
+        || s.contains(" #")
+        || s.ends_with(':')
+        || s.chars().any(|c| c.is_control())
+}
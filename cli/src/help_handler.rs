@@ -3,14 +3,27 @@
 //! This module intercepts help requests and displays our rich,
 //! reality compiler themed help instead of Clap's default.
 
+use clap::CommandFactory;
+use crate::display::Pager;
 use crate::help_text;
-use std::env;
 
 /// Check if this is a help request and handle it
 /// Returns true if help was handled, false otherwise
-pub fn handle_help_request() -> bool {
-    let args: Vec<String> = env::args().collect();
-    
+pub fn handle_help_request(args: &[String]) -> bool {
+
+    // "port42 help --full [topic]" or "port42 [topic] --help-full"
+    let full = (args.len() >= 3 && args[1] == "help" && args.iter().any(|a| a == "--full"))
+        || args.last().is_some_and(|a| a == "--help-full");
+    if full {
+        let topic: Vec<&str> = args[1..].iter()
+            .map(String::as_str)
+            .filter(|a| !matches!(*a, "help" | "--full" | "--help-full" | "--no-pager"))
+            .collect();
+        let _pager = Pager::start(!args.iter().any(|a| a == "--no-pager"));
+        show_full_help(&topic);
+        return true;
+    }
+
     // Check for "port42 --help" or "port42 -h"
     if args.len() == 2 && (args[1] == "--help" || args[1] == "-h") {
         show_main_help();
@@ -51,6 +64,58 @@ pub fn handle_help_request() -> bool {
     false
 }
 
+/// The CLI definition with each command's prose from help_text as its long
+/// description, so `help --full` and the man pages read the same as `help <command>`
+pub fn reference_command() -> clap::Command {
+    let mut cmd = crate::Cli::command();
+    for topic in help_text::HELP_TOPICS {
+        if let Some(prose) = help_text::get_command_help(topic) {
+            cmd = cmd.mut_subcommand(topic, |sub| sub.long_about(prose));
+        }
+    }
+    cmd.build();
+    cmd
+}
+
+/// Prose and every flag for one command (and its subcommands), or for all of them
+fn show_full_help(topic: &[&str]) {
+    let root = reference_command();
+    let mut cmd = &root;
+    for word in topic {
+        match cmd.find_subcommand(word) {
+            Some(sub) => cmd = sub,
+            None => {
+                println!("{}", format!("No help available for '{}'", topic.join(" ")).red());
+                println!("For everything at once: port42 help --full");
+                return;
+            }
+        }
+    }
+
+    print_long_help(cmd);
+    print_subcommand_help(cmd);
+}
+
+fn print_subcommand_help(cmd: &clap::Command) {
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set() && s.get_name() != "help") {
+        println!();
+        println!("{}", "─".repeat(60).dimmed());
+        println!("{}", help_text::format_command_header(sub.get_bin_name().unwrap_or(sub.get_name())));
+        println!();
+        print_long_help(sub);
+        print_subcommand_help(sub);
+    }
+}
+
+fn print_long_help(cmd: &clap::Command) {
+    let help = cmd.clone().render_long_help();
+    if colored::control::SHOULD_COLORIZE.should_colorize() {
+        print!("{}", help.ansi());
+    } else {
+        print!("{}", help);
+    }
+}
+
 /// Show main help with reality compiler essence
 fn show_main_help() {
     println!("{}", help_text::MAIN_ABOUT);
@@ -66,9 +131,11 @@ fn show_main_help() {
     
     println!("{}", "REALITY NAVIGATION:".bright_cyan());
     println!("  {} - {}", "ls [path]".bright_green(), help_text::LS_DESC);
+    println!("  {} - {}", "tree [path]".bright_green(), help_text::TREE_DESC);
     println!("  {} - {}", "cat <path>".bright_green(), help_text::CAT_DESC);
     println!("  {} - {}", "info <path>".bright_green(), help_text::INFO_DESC);
     println!("  {} - {}", "search <query>".bright_green(), help_text::SEARCH_DESC);
+    println!("  {} - {}", "run <name> [args]".bright_green(), help_text::RUN_DESC);
     println!();
     
     println!("{}", "SYSTEM:".bright_cyan());
@@ -83,6 +150,7 @@ fn show_main_help() {
     println!();
     
     println!("{}", "For detailed command help: port42 help <command>".yellow());
+    println!("{}", "For every command and flag: port42 help --full [command]".yellow());
     println!();
     println!("{}", "The dolphins are listening on Port 42. Will you let them in?".bright_blue());
 }
@@ -22,11 +22,60 @@ pub const SWIM_DESC: &str = "Swim into an AI agent's stream";
 pub const MEMORY_DESC: &str = "Browse the persistent memory of conversations";
 pub const REALITY_DESC: &str = "View your crystallized commands";
 pub const LS_DESC: &str = "List contents of the virtual filesystem";
+pub const TREE_DESC: &str = "Trace the branches of the virtual filesystem";
 pub const CAT_DESC: &str = "Display content from any reality path";
 pub const INFO_DESC: &str = "Examine the metadata essence of objects";
+pub const HISTORY_DESC: &str = "Walk back through the earlier shapes of an object";
+pub const ROLLBACK_DESC: &str = "Return an object to a shape it held before";
+pub const RUN_DESC: &str = "Invoke a crystallized command and remember its echo";
+pub const NOTE_DESC: &str = "Leave your own marks in the margins of reality";
+pub const DIFF_DESC: &str = "Trace how one reality fragment diverges from another";
+pub const CP_DESC: &str = "Replicate an object to another reality path";
+pub const MV_DESC: &str = "Relocate an object within the virtual realm";
+pub const RM_DESC: &str = "Release an object into the trash";
+pub const RESTORE_DESC: &str = "Recall an object from the trash";
 pub const SEARCH_DESC: &str = "Search across all crystallized knowledge";
 pub const DAEMON_DESC: &str = "Manage the gateway daemon";
 pub const STATUS_DESC: &str = "Check the daemon's pulse";
+pub const USAGE_DESC: &str = "Measure the energy spent channeling AI consciousness";
+pub const METRICS_DESC: &str = "Read the gateway's vital counters";
+pub const ARTIFACTS_DESC: &str = "Browse the documents and images born of your sessions";
+pub const EVOLVE_DESC: &str = "Guide an existing tool into its next form";
+pub const UPDATE_DESC: &str = "Carry your edits back into the object they came from";
+pub const MATERIALIZE_DESC: &str = "Draw objects out of the VFS and into your own hands";
+pub const REFS_DESC: &str = "See exactly what the AI will see before it sees it";
+pub const MODELS_DESC: &str = "Survey the minds each provider can summon";
+pub const PROVIDERS_DESC: &str = "See which wellsprings of thought the daemon can draw from";
+pub const CACHE_DESC: &str = "Tend the echoes of past answers";
+pub const CONFIG_DESC: &str = "Read and rewrite the settings that shape this reality";
+pub const ALIAS_DESC: &str = "Give your favourite incantations shorter names";
+pub const KEYS_DESC: &str = "Guard the keys that open the gateways to AI providers";
+pub const GIT_DESC: &str = "Weave consciousness into the commit stream";
+pub const HOOK_DESC: &str = "Let the shell whisper what you do to the gateway";
+pub const INDEX_DESC: &str = "Weave the semantic index that lets meaning find meaning";
+pub const RULES_DESC: &str = "Bind reactions to the events of reality";
+pub const EVENTS_DESC: &str = "Listen to reality as it moves";
+pub const REPLAY_DESC: &str = "Play a recorded conversation with the gateway back";
+pub const UPGRADE_DESC: &str = "Shed this shell for the newest one the dolphins have grown";
+pub const UPGRADE_AFTER_HELP: &str = "The .sha256 checked against each release is downloaded from the same place\nas the release itself. It catches a corrupt or truncated download, but not\na compromised release host: releases are not signed.";
+pub const JOBS_DESC: &str = "Watch over generations left to ripen in the background";
+pub const OUTBOX_DESC: &str = "Messages in bottles, waiting for the gateway to open";
+pub const AGENTS_DESC: &str = "Summon, shape and carry consciousnesses between realities";
+pub const PROMPTS_DESC: &str = "Keep incantations ready to speak again";
+pub const COMPLETIONS_DESC: &str = "Teach your shell to finish your thoughts";
+pub const MANPAGES_DESC: &str = "Inscribe the gateway's lore as pages for man to read";
+pub const DOCTOR_DESC: &str = "Examine the vessel for anything keeping the gateway closed";
+pub const INIT_DESC: &str = "Anchor a project to the gateway";
+pub const EXPORT_DESC: &str = "Carry what was created out into the wider world";
+pub const IMPORT_DESC: &str = "Return an exported archive to the realm";
+pub const NOTIFY_DESC: &str = "Send word across the waters when something stirs";
+pub const DIGEST_DESC: &str = "Look back on the tides of recent hours";
+pub const DIFF_AFTER_HELP: &str = "With --json (or --output yaml), the diff is an RFC 6902 JSON Patch over the\nleft path's lines: applied to its content as a JSON array of strings, one per\nline, it gives the right path's.";
+pub const DIGEST_AFTER_HELP: &str = "Run it on a schedule, e.g. every morning from cron:\n  0 8 * * * port42 digest --quiet --notify\n\nDigests are saved to ~/.port42/digests. Defaults come from [digest] in\n~/.port42/config.toml (hours, agent, email, notify).";
+
+// Shared argument help
+pub const PROVIDER_ARG_HELP: &str = "AI provider to use (anthropic, openai, google, local)\n\nOverrides the 'provider' default in ~/.port42/config.toml.\nThe daemon needs the matching API key, e.g. PORT42_OPENAI_API_KEY for openai\nor PORT42_GOOGLE_API_KEY for google. The local provider talks to Ollama\nand needs no key. Point openai at any OpenAI-compatible server with\nPORT42_OPENAI_BASE_URL.";
+pub const MODEL_ARG_HELP: &str = "Model to request from the provider\n\nPassed through to the daemon as-is, e.g. gemini-1.5-pro or gpt-4o.\nOverrides the 'model' default in ~/.port42/config.toml.";
 
 // Agent descriptions
 pub const AGENT_ENGINEER_DESC: &str = "Technical manifestation for code and systems";
@@ -48,7 +97,16 @@ pub fn swim_help() -> String {
 
 {}
   {}     Resume specific session (use 'last' for most recent)
-  {}     Reference other entities for context (file:path, p42:/commands/name, url:https://, search:"query")
+  {}     Reference other entities for context (file:path, p42:/commands/name, url:https://, search:"query", stdin:, git:diff)
+  {}     AI provider for this conversation (anthropic, openai, google, local; env PORT42_PROVIDER)
+  {}     Model to request from the provider (e.g. gemini-1.5-pro; env PORT42_MODEL)
+  {}     Don't retry on fallback providers when the primary is down
+  {}     Ask again even if an identical prompt is cached
+  {}     Cap tokens spent in this session (warns at 80%)
+  {}     Start from a saved prompt template (see 'port42 prompts list')
+  {}     Fill a template variable (repeatable)
+  {}     Wait for the whole reply instead of streaming it
+  {}     Print tokens and estimated cost under each reply
 
 {}
   swim @ai-engineer "help me build a parser"           # Start new conversation
@@ -57,7 +115,13 @@ pub fn swim_help() -> String {
   swim @ai-engineer --ref file:./spec.md "implement this"  # With file reference
   swim @ai-engineer --ref search:"docker" "How to scale containers?"  # With search context
   swim @ai-muse --ref search:"poetry" "Write a poem"   # Load poetry memories
+  cat error.log | swim @ai-engineer --ref stdin: "why is this failing?"  # With piped content
+  swim @ai-engineer --ref git:HEAD~3..HEAD "review these commits"  # With commits and their diffs
   swim @ai-engineer --ref p42:/commands/analyzer --ref search:"poetry" "Help me improve this tool"  # Multiple references
+  swim @ai-analyst --provider openai "summarize these metrics"  # Use a different AI provider
+  swim @ai-muse --provider google --model gemini-1.5-pro "draft a story"  # Pick provider and model
+  swim @ai-engineer --provider local --model llama3.1 "explain this error"  # Offline via Ollama
+  swim @ai-engineer --template review-pr --var pr=123  # Reuse a saved prompt
 
 Sessions persist across daemon restarts. Use 'port42 ls /memory/sessions/' to list all sessions."#,
         "Swim into an AI agent's stream to crystallize thoughts into reality.".bright_blue().bold(),
@@ -70,6 +134,15 @@ Sessions persist across daemon restarts. Use 'port42 ls /memory/sessions/' to li
         "Options:".bright_cyan(),
         "--session <ID>".bright_green(),
         "--ref <reference>".bright_green(),
+        "--provider <name>".bright_green(),
+        "--model <name>".bright_green(),
+        "--no-fallback".bright_green(),
+        "--no-cache".bright_green(),
+        "--token-budget <N>".bright_green(),
+        "--template <name>".bright_green(),
+        "--var <key=value>".bright_green(),
+        "--no-stream".bright_green(),
+        "--show-usage".bright_green(),
         "Examples:".bright_cyan()
     )
 }
@@ -187,7 +260,11 @@ pub fn cat_help() -> String {
   cat /memory/cli-1754170150            # Read memory thread
   cat /artifacts/docs/readme.md         # (Future) View documents
 
-Virtual paths resolve to their essence through content addressing."#,
+Virtual paths resolve to their essence through content addressing.
+Source glows in the colours of its language (theme = default, ocean, ember
+or mono in ~/.port42/config.toml); --plain gives back the raw bytes.
+Anything taller than the terminal unfolds in $PAGER (less -R by default);
+--no-pager lets it pour straight out."#,
         "Display content from any point in the reality matrix.".bright_blue().bold(),
         "Usage: cat <path>".yellow(),
         "Examples:".bright_cyan()
@@ -269,7 +346,7 @@ pub fn shell_help_main() -> String {
   {}              - Run any Port 42 or system command
   {}            - Force system command (e.g., !ls for system ls)
 
-{}: status | daemon | clear | exit | help
+{}: status | daemon | history | clear | exit | help  (Ctrl+R searches history)
 
 Type '{}' for detailed usage and examples.
 Type '{}' to begin crystallizing thoughts into reality."#,
@@ -282,7 +359,7 @@ Type '{}' to begin crystallizing thoughts into reality."#,
         "NAVIGATE REALITY:".bright_cyan(),
         "memory".bright_green(),
         "reality".bright_green(),
-        "ls, cat, info, search".bright_green(),
+        "ls, cat, info, cp, mv, search".bright_green(),
         "EXECUTE COMMANDS:".bright_cyan(),
         "<command>".bright_green(),
         "!<command>".bright_green(),
@@ -306,6 +383,13 @@ pub const MSG_DAEMON_RESTARTING: &str = "🔄 Cycling the gateway through the vo
 pub const MSG_CHECKING_STATUS: &str = "🐬 Sensing the gateway's presence...";
 pub const MSG_DAEMON_RUNNING: &str = "✨ Gateway pulses with living energy";
 pub const MSG_DAEMON_LOGS: &str = "📜 Gateway's quantum memory stream";
+pub const MSG_SERVICE_INSTALLED: &str = "⚓ The gateway is anchored and will awaken on its own";
+pub const MSG_SERVICE_UNINSTALLED: &str = "🌊 The gateway's anchor is lifted";
+pub const MSG_UPGRADE_CURRENT: &str = "✨ Already swimming in the newest shell";
+pub const MSG_UPGRADE_DONE: &str = "✨ Port 42 has shed its old shell";
+pub const MSG_SERVICE_NOT_INSTALLED: &str = "🌊 The gateway isn't anchored to any service manager";
+pub const MSG_QUEUED: &str = "📮 The gateway is closed; your message waits in the outbox";
+pub const MSG_OUTBOX_EMPTY: &str = "📭 The outbox is empty";
 
 // Session & Swimming
 pub const MSG_SESSION_CONTINUING: &str = "✨ Swimming session resuming: {}";
@@ -317,6 +401,8 @@ pub const MSG_NO_RESULTS: &str = "🌑 No matches found";
 
 // Commands & Reality
 pub const MSG_COMMANDS_HEADER: &str = "🔮 Crystallized Thoughts";
+pub const MSG_REALITY_DIFF_HEADER: &str = "🔮 Shifts in Reality";
+pub const MSG_REALITY_UNCHANGED: &str = "Reality holds still. Nothing has shifted.";
 
 // Connection Info
 pub const MSG_CONNECTION_INFO: &str = "🌊 Gateway Resonance:";
@@ -345,7 +431,7 @@ pub const PHILOSOPHY_IS_BRIDGE: &str = "This is a bridge between minds.";
 
 // Shell Interface
 pub const MSG_SHELL_HEADER: &str = "🌊 Reality Compiler Terminal";
-pub const MSG_SHELL_HELP_HINT: &str = "Type 'help' for available commands";
+pub const MSG_SHELL_HELP_HINT: &str = "Type 'help' for available commands, <Tab> to complete";
 pub const MSG_SHELL_EXITING: &str = "🌑 Dissolving back into the void...";
 pub const MSG_SHELL_ERROR: &str = "⚡ Reality distortion";
 pub const SHELL_PROMPT: &str = "Echo@port42:~$ ";
@@ -360,8 +446,14 @@ pub const ERR_DAEMON_USAGE: &str = "💡 Gateway control: daemon <awaken|dissolv
 pub const ERR_DAEMON_UNKNOWN: &str = "❓ Unknown gateway ritual";
 pub const ERR_CAT_USAGE: &str = "💡 Read essence: cat <reality-path>";
 pub const ERR_CAT_EXAMPLE: &str = "   cat /commands/hello-world";
+pub const ERR_RUN_USAGE: &str = "💡 Invoke a crystallized command: run <name> [args...]";
+pub const ERR_RUN_EXAMPLE: &str = "   run hello-world --loud";
 pub const ERR_INFO_USAGE: &str = "💡 Inspect metadata: info <reality-path>";
 pub const ERR_INFO_EXAMPLE: &str = "   info /memory/cli-1754170150";
+pub const ERR_CP_USAGE: &str = "💡 Replicate essence: cp <source> <destination>";
+pub const ERR_CP_EXAMPLE: &str = "   cp /commands/git-haiku /artifacts/backups/";
+pub const ERR_MV_USAGE: &str = "💡 Relocate essence: mv <source> <destination>";
+pub const ERR_MV_EXAMPLE: &str = "   mv /artifacts/draft.md /artifacts/docs/final.md";
 pub const ERR_SEARCH_USAGE: &str = "💡 Find echoes: search <resonance> [filters]";
 pub const ERR_SEARCH_EXAMPLE: &str = "   search docker";
 pub const ERR_SEARCH_HELP: &str = "Type 'help search' for quantum filters";
@@ -372,21 +464,128 @@ pub const ERR_DAEMON_START_FAILED: &str = "⚡ Failed to awaken the gateway";
 pub const ERR_DAEMON_ALREADY_RUNNING: &str = "✨ The gateway is already humming with energy";
 pub const ERR_CONNECTION_LOST: &str = "🔌 Reality link severed. The dolphins have gone silent";
 pub const ERR_SESSION_ABANDONED: &str = "🌑 This session has expired";
+pub const ERR_NO_SEMANTIC_INDEX: &str = "🧭 No semantic index has been woven yet";
+pub const ERR_SEMANTIC_FILTERS: &str = "🧭 The semantic index only knows paths and types";
 pub const ERR_PATH_NOT_FOUND: &str = "🔍 This reality path leads nowhere";
+pub const ERR_TRANSFER_FAILED: &str = "🌀 The object resists relocation";
+pub const ERR_RM_PROTECTED: &str = "🛡️ This path is part of the realm's foundation";
+pub const ERR_RM_NEEDS_CONFIRMATION: &str = "⚠️ Permanent removal needs confirmation";
+pub const MSG_RM_RESTORE_HINT: &str = "Recall with: port42 restore <path>  (see port42 ls /trash)";
 pub const ERR_INVALID_DATE: &str = "⏰ Time flows differently here. Use YYYY-MM-DD format";
+pub const MSG_CACHED_RESPONSE: &str = "⚡ Replaying cached response (use --no-cache to regenerate)";
+pub const MSG_REPLAYED_NO_SESSION: &str = "Replayed from the cache; no new session was recorded";
+pub const ERR_BUDGET_DECLINED: &str = "🛑 Session token budget spent - message not sent";
+pub const MSG_KEYS_RESTART_HINT: &str = "💡 Restart the daemon to pick it up: port42 daemon restart";
 pub const ERR_NO_API_KEY: &str = "🔑 Port42 requires an ANTHROPIC_API_KEY to connect to Claude";
 pub const ERR_EVOLVE_NOT_READY: &str = "🚧 Command evolution still crystallizing in the quantum realm";
 pub const ERR_MEMORY_SEARCH_USAGE: &str = "💡 Usage: memory search <query>";
+pub const ERR_MEMORY_FORK_USAGE: &str = "💡 Usage: memory fork <session_id> [--at <message>]";
+pub const ERR_MEMORY_MERGE_USAGE: &str = "💡 Usage: memory merge <session_id> <session_id>... [--name <name>] [--interleave]";
 pub const ERR_BINARY_NOT_FOUND: &str = "🔍 The daemon binary has vanished from reality";
 pub const ERR_FAILED_TO_STOP: &str = "⚡ The gateway resists termination";
+pub const ERR_NO_SERVICE_MANAGER: &str = "🧭 Nothing here to anchor the gateway to: daemon install needs launchd or systemd";
 pub const ERR_LOG_NOT_FOUND: &str = "📜 The daemon's memories are nowhere to be found";
 pub const ERR_INVALID_RESPONSE: &str = "🌀 The gateway speaks in riddles we cannot parse";
+pub const ERR_GIT_REF_NOT_REPO: &str = "🌿 git: references need a git repository, and this directory isn't one";
+pub const ERR_STDIN_NOT_PIPED: &str = "🕳️  Nothing flows through stdin. Pipe something in: cat error.log | port42 possess @ai-engineer --ref stdin: \"why?\"";
 
 // Error formatting functions
 pub fn format_error_with_suggestion(error: &str, suggestion: &str) -> String {
     format!("{}\n💡 {}", error.red(), suggestion.dimmed())
 }
 
+pub fn format_api_key_hint(provider: &str) -> String {
+    let vars = crate::common::providers::api_key_vars(provider);
+    if vars.is_empty() {
+        return format!("Provider '{}' rejected the request. Check the daemon logs.", provider);
+    }
+    format!("API key issue. Please set {} and restart the daemon.", vars.join(" or "))
+}
+
+pub fn format_local_unreachable(base_url: &str, reason: &str) -> String {
+    format!("🦙 Local model endpoint {} is unreachable ({}). Start it with 'ollama serve' or set PORT42_OLLAMA_BASE_URL.", base_url, reason)
+}
+
+pub fn format_provider_fallback(failed: &str, next: &str) -> String {
+    format!("⚠️  {} is unavailable - retrying with {}", failed, next)
+}
+
+pub fn format_rate_limit_countdown(provider: &str, secs: u64, attempt: u32, max: u32) -> String {
+    format!("⏳ {} rate limited, retrying in {}s (wait {}/{}, Ctrl+C to cancel)", provider, secs, attempt, max)
+}
+
+pub fn format_rate_limit_resumed(provider: &str) -> String {
+    format!("⏳ Resuming request to {}...", provider)
+}
+
+pub fn format_history_unsupported(since: &str) -> String {
+    format!("⚠️  This daemon keeps no history - showing its current snapshot since {} instead", since)
+}
+
+/// `[2/4] Prompting AI (claude-sonnet-4)`; the step is left out for phases outside the usual four
+pub fn format_declare_phase(step: Option<usize>, label: &str, detail: Option<&str>) -> String {
+    let step = step.map(|n| format!("[{}/4] ", n)).unwrap_or_default();
+    match detail {
+        Some(detail) => format!("{}{} ({})", step, label, detail),
+        None => format!("{}{}", step, label),
+    }
+}
+
+pub fn format_bash_auto_approved(command: &str, reason: &str) -> String {
+    format!("🔓 Bash approved by policy ({}): {}", reason, command)
+}
+
+pub fn format_bash_auto_denied(command: &str, reason: &str) -> String {
+    format!("🔒 Bash denied by policy ({}): {}", reason, command)
+}
+
+pub fn format_git_ref_truncated(target: &str, total: usize, cap: usize) -> String {
+    format!("✂️  git:{} produced {} bytes; only the first {} reach the AI", target, total, cap)
+}
+
+pub fn format_git_ref_empty(target: &str) -> String {
+    format!("🍃 git:{} is empty; the AI will see no changes", target)
+}
+
+pub fn format_url_ref_truncated(url: &str, total: usize, cap: usize) -> String {
+    format!("✂️  {} came to {} bytes; only the first {} reach the AI (raise it with !max=)", url, total, cap)
+}
+
+pub fn format_materialized(count: usize, vfs_path: &str, dest: &std::path::Path) -> String {
+    format!("📥 Materialized {} object{} from {} at {}", count, if count == 1 { "" } else { "s" }, vfs_path, dest.display())
+}
+
+pub fn format_materialize_conflict(vfs_path: &str, file: &std::path::Path, conflict: &std::path::Path) -> String {
+    format!("⚔️  {} changed in the VFS while {} was being edited. Its version is in {}; merge it into your file and delete it to send the result",
+        vfs_path, file.display(), conflict.display())
+}
+
+pub fn format_stdin_truncated(total: usize, cap: usize) -> String {
+    format!("✂️  Piped input was {} bytes; only the first {} reach the AI", total, cap)
+}
+
+pub fn format_usage_footer(input: &str, output: &str, cost: &str, session_tokens: &str, session_cost: &str) -> String {
+    format!("  ↳ {} in · {} out · ~{}   session {} tokens · ~{}", input, output, cost, session_tokens, session_cost)
+}
+
+pub const MSG_USAGE_ESTIMATED: &str = " (estimated)";
+
+pub fn format_budget_warning(status: &str) -> String {
+    format!("⚠️  Session token budget at {}", status)
+}
+
+pub fn format_budget_exceeded(status: &str) -> String {
+    format!("🛑 Session token budget exceeded: {}", status)
+}
+
+pub fn format_budget_exhausted(status: &str) -> String {
+    format!("🛑 This session has spent its token budget: {}", status)
+}
+
+pub fn format_missing_provider_key(provider: &str) -> String {
+    format!("🔑 No API key found for provider '{}'", provider)
+}
+
 pub fn format_daemon_connection_error(port: u16) -> String {
     format!(
         "{}\n\n{}",
@@ -430,10 +629,29 @@ pub fn format_evolving(command: &str) -> String {
     format!("🦋 Transmuting reality fragment: {}", command)
 }
 
+pub fn format_evolved(command: &str) -> String {
+    format!("🦋 {} has evolved", command)
+}
+
 pub fn format_total_commands(count: usize) -> String {
     format!("Total manifestations: {}", count)
 }
 
+pub fn format_reality_since(taken: &str) -> String {
+    let when = chrono::DateTime::parse_from_rfc3339(taken)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| taken.to_string());
+    format!("Since the last glimpse at {}", when)
+}
+
+pub fn format_reality_first_snapshot(count: usize) -> String {
+    format!("First glimpse: {} manifestations remembered. The next --diff will show what shifts.", count)
+}
+
+pub fn format_reality_unchanged(count: usize) -> String {
+    format!("{} unchanged", count)
+}
+
 pub fn format_port_info(port: &str) -> String {
     format!("  Portal:    {}", port)
 }
@@ -451,6 +669,9 @@ pub fn format_command_header(command: &str) -> String {
     format!("📖 {} Help", command).bright_blue().bold().to_string()
 }
 
+/// Commands with their own prose help, for `help <command>`
+pub const HELP_TOPICS: &[&str] = &["swim", "memory", "reality", "ls", "cat", "info", "search", "status"];
+
 pub fn get_command_help(command: &str) -> Option<String> {
     match command.to_lowercase().as_str() {
         "swim" => Some(swim_help()),
@@ -474,6 +695,6 @@ pub fn show_command_help(command: &str) {
         println!();
     } else {
         println!("{}", format!("No help available for '{}'", command).red());
-        println!("Available commands: swim, memory, reality, ls, cat, info, search, status");
+        println!("Available commands: {}", HELP_TOPICS.join(", "));
     }
 }
\ No newline at end of file
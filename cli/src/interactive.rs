@@ -2,6 +2,7 @@ use anyhow::Result;
 use colored::*;
 use std::time::Instant;
 use std::io::{self, Write};
+use tracing::{debug, trace};
 use crossterm::{
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
     terminal::{disable_raw_mode, enable_raw_mode},
@@ -12,6 +13,15 @@ use crate::swim::{SessionHandler, AnimatedDisplay};
 use crate::protocol::swim::SwimResponse;
 use crate::display::{StatusIndicator, format_timestamp_relative};
 use crate::help_text;
+use crate::common::notify::{self, NotifyEvent};
+use crate::common::transcript::{self, Transcript, TranscriptEntry};
+use crate::common::logging;
+
+/// How much of an imported session's conversation is carried as context
+const IMPORT_CONTEXT_CHARS: usize = 12_000;
+
+/// Filters accepted by /search, mirroring `port42 search`
+const SEARCH_USAGE: &str = "Filters: --type <t> --tag <t> --path <p> --after/--before <date> --agent <a> | --all-agents, --limit <n>; modes: --all --any --exact --semantic";
 
 // Type of crystallization to request
 enum CrystallizeType {
@@ -31,6 +41,10 @@ pub struct InteractiveSession {
     start_time: Instant,
     commands_generated: Vec<String>,
     artifacts_generated: Vec<(String, String, String)>, // (name, type, path)
+    provider_switches: Vec<(u32, String)>, // (depth, "provider/model")
+    imported_sessions: Vec<String>,
+    started_at: String,
+    transcript: Vec<TranscriptEntry>,
 }
 
 impl InteractiveSession {
@@ -56,9 +70,60 @@ impl InteractiveSession {
             start_time: Instant::now(),
             commands_generated: Vec::new(),
             artifacts_generated: Vec::new(),
+            provider_switches: Vec::new(),
+            imported_sessions: Vec::new(),
+            started_at: chrono::Local::now().to_rfc3339(),
+            transcript: Vec::new(),
         }
     }
     
+    /// Route this session's messages to a specific AI provider/model
+    pub fn with_provider(mut self, provider: Option<crate::protocol::ProviderSelection>) -> Self {
+        self.handler.set_provider(provider);
+        self
+    }
+    
+    /// Providers to fall back to when the primary is unavailable
+    pub fn with_fallbacks(mut self, fallbacks: Vec<crate::protocol::ProviderSelection>) -> Self {
+        self.handler.set_fallbacks(fallbacks);
+        self
+    }
+    
+    /// Guidance from the agent's local definition
+    pub fn with_guidance(mut self, guidance: Option<String>) -> Self {
+        self.handler.set_guidance(guidance);
+        self
+    }
+    
+    pub fn with_workspace(mut self, workspace: Option<crate::common::workspace::WorkspaceContext>) -> Self {
+        self.handler.set_workspace(workspace);
+        self
+    }
+    
+    /// Token budget for the whole session
+    pub fn with_budget(mut self, budget: Option<crate::common::budget::TokenBudget>) -> Self {
+        self.handler.set_budget(budget);
+        self
+    }
+    
+    /// Show replies as they stream in, or wait for each whole reply
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.handler.set_streaming(streaming);
+        self
+    }
+    
+    /// Tokens and estimated cost under each reply
+    pub fn with_show_usage(mut self, show_usage: bool) -> Self {
+        self.handler.set_show_usage(show_usage);
+        self
+    }
+    
+    /// How bash requests from the agent are approved
+    pub fn with_approval_policy(mut self, policy: crate::common::approval::ApprovalPolicy) -> Self {
+        self.handler.set_approval_policy(policy);
+        self
+    }
+    
     pub fn run(&mut self) -> Result<()> {
         // Boot sequence already shown in handle_swim
         self.show_welcome()?;
@@ -70,6 +135,7 @@ impl InteractiveSession {
         
         self.conversation_loop()?;
         self.show_exit_summary()?;
+        self.notify_if_long();
         Ok(())
     }
     
@@ -86,8 +152,12 @@ impl InteractiveSession {
         println!("{}", "  /crystallize        - Generate reality from conversation".white());
         println!("{}", "  /crystallize command - Create executable tools".white());
         println!("{}", "  /crystallize artifact - Create documents & assets".white());
-        println!("{}", "  /search <query>     - Search through your memories".white());
+        println!("{}", "  /search <query>     - Search memories (--type, --tag, --after, --before, --all ...)".white());
         println!("{}", "  /ref <reference>    - Add a reference to this session".white());
+        println!("{}", "  /import <session>   - Carry a past conversation into this one".white());
+        println!("{}", "  /save [path]        - Write this conversation to a transcript (.md or .json)".white());
+        println!("{}", "  /provider [name]    - Show or switch the AI provider".white());
+        println!("{}", "  /model [name]       - Show or switch the model".white());
         println!("{}", "  /surface            - Return to your world".white());
         println!();
         println!("{}", "Input Options:".bright_yellow());
@@ -126,6 +196,12 @@ impl InteractiveSession {
                 continue;
             }
             
+            // Past the token budget, only send with explicit consent
+            if !self.handler.confirm_budget()? {
+                println!("{}", help_text::ERR_BUDGET_DECLINED.dimmed());
+                continue;
+            }
+            
             // Show sending feedback
             println!("{}", "◊ Transmitting to consciousness stream...".blue().italic());
             
@@ -174,10 +250,7 @@ impl InteractiveSession {
         loop {
             match event::read()? {
                 Event::Key(KeyEvent { code, modifiers, .. }) => {
-                    // Debug key detection
-                    if std::env::var("PORT42_DEBUG_KEYS").is_ok() {
-                        eprintln!("DEBUG: KeyEvent - Code: {:?}, Modifiers: {:?}", code, modifiers);
-                    }
+                    trace!(target: logging::KEYS_TARGET, ?code, ?modifiers, "key event");
                     
                     match code {
                         KeyCode::Enter => {
@@ -273,6 +346,18 @@ impl InteractiveSession {
         let symbol = "◊";
         let depth_str = symbol.repeat(self.depth.min(5) as usize);
         
+        // Budget status rides along in the prompt, colored by how close it is
+        if let Some(budget) = self.handler.budget() {
+            let prompt = format!("{} [{}]", depth_str, budget.status());
+            return if budget.needs_confirmation() {
+                prompt.red()
+            } else if budget.is_near_limit() {
+                prompt.yellow()
+            } else {
+                prompt.dimmed()
+            };
+        }
+        
         match self.depth {
             0..=1 => depth_str.normal(),
             2..=3 => depth_str.blue(),
@@ -320,40 +405,206 @@ impl InteractiveSession {
                 }
                 Ok(true)
             }
+            "/import" => {
+                println!("\n{}", "Usage: /import <session-id>".red());
+                println!("{}", "Bring a past conversation into this one as memory context".dimmed());
+                Ok(true)
+            }
+            _ if input.starts_with("/import ") => {
+                self.import_memory(input[8..].trim())?;
+                Ok(true)
+            }
+            _ if input == "/save" || input.starts_with("/save ") => {
+                self.save_transcript(input[5..].trim())?;
+                Ok(true)
+            }
             _ if input.starts_with("/search ") => {
                 let query = input[8..].trim();
                 if query.is_empty() {
-                    println!("\n{}", "Usage: /search <query>".red());
-                    println!("{}", "Search through memories and display results".dimmed());
+                    println!("\n{}", "Usage: /search <query> [filters]".red());
+                    println!("{}", SEARCH_USAGE.dimmed());
                 } else {
                     self.search_memories(query)?;
                 }
                 Ok(true)
             }
+            "/provider" | "/model" => {
+                self.show_provider();
+                Ok(true)
+            }
+            _ if input.starts_with("/provider ") => {
+                let name = input[10..].trim().to_string();
+                self.switch_provider(Some(name), None)?;
+                Ok(true)
+            }
+            _ if input.starts_with("/model ") => {
+                let model = input[7..].trim().to_string();
+                let current = self.handler.provider().and_then(|p| p.name.clone());
+                self.switch_provider(current, Some(model))?;
+                Ok(true)
+            }
             _ if input.starts_with('/') => {
                 println!("\n{}", format!("Unknown command: {}", input).dimmed());
                 println!("{}", "Available: /surface, /deeper, /memory, /reality, /crystallize [command|artifact]".dimmed());
-                println!("{}", "          /ref <reference_uri>, /search <query>".dimmed());
+                println!("{}", "          /ref <reference_uri>, /search <query>, /import <session>, /save [path] [--artifact]".dimmed());
+                println!("{}", "          /provider [name], /model [name]".dimmed());
                 Ok(true)
             }
             _ => Ok(false)
         }
     }
     
-    fn send_message(&mut self, message: &str) -> Result<SwimResponse> {
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: Interactive send_message: session_id={}, agent={}, depth={}", 
-                      self.session_id, self.agent, self.depth);
+    fn provider_label(&self) -> String {
+        let selection = self.handler.provider();
+        let name = selection
+            .and_then(|p| p.name.as_deref())
+            .unwrap_or(crate::common::providers::DEFAULT_PROVIDER);
+        match selection.and_then(|p| p.model.as_deref()) {
+            Some(model) => format!("{}/{}", name, model),
+            None => format!("{} (default model)", name),
+        }
+    }
+    
+    fn show_provider(&self) {
+        println!("\n{} {}", "🔌 Channeling through:".bright_blue(), self.provider_label().bright_cyan());
+        println!("{}", "Use /provider <name> or /model <name> to switch for the next turns".dimmed());
+    }
+    
+    /// Switch provider/model for subsequent turns and note it in session metadata
+    fn switch_provider(&mut self, provider: Option<String>, model: Option<String>) -> Result<()> {
+        use crate::common::providers::{ProviderArgs, ensure_reachable, resolve_provider};
+        
+        let config = crate::config::Config::load_or_default();
+        let selection = match resolve_provider(ProviderArgs { provider, model, ..Default::default() }, Some(&self.agent), &config) {
+            Ok(selection) => selection,
+            Err(e) => {
+                println!("\n{}", format!("❌ {}", e).red());
+                return Ok(());
+            }
+        };
+        if let Err(e) = ensure_reachable(&selection) {
+            println!("\n{}", format!("❌ {}", e).red());
+            return Ok(());
+        }
+        
+        self.handler.set_provider(selection);
+        let label = self.provider_label();
+        self.provider_switches.push((self.depth, label.clone()));
+        println!("\n{} {}", "🔀 Switched to".bright_green(), label.bright_cyan());
+        
+        self.record_provider_switch();
+        Ok(())
+    }
+    
+    fn record_provider_switch(&self) {
+        use crate::protocol::{RequestBuilder, swim::SessionMetadataRequest};
+        
+        let session_id = self.actual_session_id.clone().unwrap_or_else(|| self.session_id.clone());
+        let switches: Vec<_> = self.provider_switches.iter()
+            .map(|(depth, label)| serde_json::json!({ "depth": depth, "provider": label }))
+            .collect();
+        let request = SessionMetadataRequest {
+            session_id,
+            metadata: serde_json::json!({
+                "provider": self.handler.provider(),
+                "provider_switches": switches,
+            }),
+        };
+        
+        // Best effort - a daemon that can't store it shouldn't interrupt the session
+        let mut client = DaemonClient::new(self.handler.client.port());
+        let result = request.build_request(crate::common::generate_id())
+            .and_then(|req| client.request(req));
+        if let Err(e) = result {
+            debug!("failed to record provider switch: {}", e);
         }
+    }
+    
+    fn send_message(&mut self, message: &str) -> Result<SwimResponse> {
+        debug!("Interactive send_message: session_id={}, agent={}, depth={}", self.session_id, self.agent, self.depth);
         
         // Send message with stored session context (memory and references)
-        self.handler.send_message_with_context(
+        let sent = TranscriptEntry::new("user", message);
+        let response = self.handler.send_message_with_context(
             &self.session_id,
             &self.agent,
             message,
             self.memory_context.clone(),
             self.references.clone()
-        )
+        )?;
+        
+        let mut reply = TranscriptEntry::new("assistant", &response.message);
+        reply.command_spec = response.command_spec.clone();
+        reply.artifact_spec = response.artifact_spec.clone();
+        self.transcript.extend([sent, reply]);
+        Ok(response)
+    }
+    
+    /// `/save [path] [--artifact]`: write the conversation so far to a local
+    /// transcript, and with --artifact also store it under /artifacts
+    fn save_transcript(&self, args: &str) -> Result<()> {
+        let as_artifact = args.split_whitespace().any(|a| a == "--artifact");
+        let path_arg = args.split_whitespace().find(|a| *a != "--artifact");
+        
+        if self.transcript.is_empty() {
+            println!("\n{}", "Nothing to save yet - the conversation is still silent".dimmed());
+            return Ok(());
+        }
+        
+        let session_id = self.actual_session_id.clone().unwrap_or_else(|| self.session_id.clone());
+        let path = std::path::PathBuf::from(path_arg.map(String::from).unwrap_or_else(|| {
+            format!("port42-{}-{}.md", session_id, chrono::Local::now().format("%Y%m%d-%H%M%S"))
+        }));
+        let json = transcript::is_json_path(&path);
+        let content = Transcript {
+            session_id: session_id.clone(),
+            agent: self.agent.clone(),
+            started: self.started_at.clone(),
+            saved: chrono::Local::now().to_rfc3339(),
+            entries: self.transcript.clone(),
+        }.render(json);
+        
+        if let Err(e) = std::fs::write(&path, &content) {
+            println!("\n{}", format!("❌ Could not write {}: {}", path.display(), e).red());
+            return Ok(());
+        }
+        println!("\n{} {} ({} messages)", "💾 Transcript saved:".bright_green(), path.display().to_string().bright_cyan(), self.transcript.len());
+        
+        if as_artifact {
+            self.store_transcript_artifact(&path, content, &session_id);
+        }
+        Ok(())
+    }
+    
+    fn store_transcript_artifact(&self, path: &std::path::Path, content: String, session_id: &str) {
+        use crate::protocol::{RequestBuilder, StorePathRequest};
+        
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| format!("{}.md", session_id));
+        let vfs_path = format!("/artifacts/transcripts/{}", name);
+        let request = StorePathRequest {
+            path: vfs_path.clone(),
+            content,
+            metadata: Some(serde_json::json!({
+                "type": "artifact",
+                "artifact_type": "transcript",
+                "session_id": session_id,
+                "agent": self.agent,
+            })),
+        };
+        
+        let mut client = DaemonClient::new(self.handler.client.port());
+        let result = request.build_request(crate::common::generate_id())
+            .and_then(|req| client.request(req));
+        match result {
+            Ok(response) if response.success => {
+                println!("{} {}", "📄 Stored as artifact:".bright_green(), vfs_path.bright_cyan());
+            }
+            Ok(response) => {
+                let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+                println!("{}", format!("❌ Could not store artifact: {}", error).red());
+            }
+            Err(e) => println!("{}", format!("❌ Could not store artifact: {}", e).red()),
+        }
     }
     
     fn show_session_memory(&self) -> Result<()> {
@@ -370,6 +621,24 @@ impl InteractiveSession {
         println!("{}", format!("Started: {}", format_timestamp_relative(started_ms)).dimmed());
         println!("{}", format!("Duration: {}m {}s", duration.as_secs() / 60, duration.as_secs() % 60).dimmed());
         println!("{}", format!("Depth reached: {}", self.depth).dimmed());
+        println!("{}", format!("Provider: {}", self.provider_label()).dimmed());
+        if let Some(budget) = self.handler.budget() {
+            println!("{}", format!("Token budget: {}", budget.status()).dimmed());
+        }
+        
+        if !self.provider_switches.is_empty() {
+            println!("\n{}", "Provider Switches:".yellow());
+            for (depth, label) in &self.provider_switches {
+                println!("  • depth {} → {}", depth, label.bright_white());
+            }
+        }
+        
+        if !self.imported_sessions.is_empty() {
+            println!("\n{}", "Imported Memories:".yellow());
+            for id in &self.imported_sessions {
+                println!("  • {}", id.bright_white());
+            }
+        }
         
         if !self.commands_generated.is_empty() {
             println!("\n{}", "Crystallized Commands:".yellow());
@@ -453,26 +722,35 @@ impl InteractiveSession {
         Ok(())
     }
     
-    fn search_memories(&self, query: &str) -> Result<()> {
-        println!("\n{}", format!("🔍 Searching memories for: '{}'...", query.bright_yellow()).blue().italic());
+    fn search_memories(&self, input: &str) -> Result<()> {
+        let search = match crate::protocol::SearchLine::parse(input) {
+            Ok(search) => search,
+            Err(e) => {
+                println!("\n{}", format!("{}", e).red());
+                println!("{}", SEARCH_USAGE.dimmed());
+                return Ok(());
+            }
+        };
+        let crate::protocol::SearchLine { query, mode, mut filters, all_agents } = search;
+        if filters.agent.is_none() && !all_agents {
+            filters.agent = Some(self.agent.clone());
+        }
+        filters.limit = filters.limit.or(Some(10));
+
+        let scope = filters.agent.as_deref().map(|a| format!(" ({})", a)).unwrap_or_default();
+        println!("\n{}", format!("🔍 Searching memories for: '{}'{}...", query.bright_yellow(), scope).blue().italic());
         
         // Use the existing search functionality
         let mut client = crate::client::DaemonClient::new(self.handler.client.port());
         
-        match crate::commands::search::handle_search_with_format(
+        match crate::commands::search::search_with_filters(
             &mut client,
-            query.to_string(),
-            "or", // default mode
-            None, // path
-            None, // type_filter  
-            None, // after
-            None, // before
-            Some(self.agent.clone()), // agent filter
-            vec![], // tags
-            Some(10), // limit
+            query,
+            &mode,
+            filters,
             crate::display::OutputFormat::Plain,
         ) {
-            Ok(()) => {
+            Ok(_) => {
                 println!("\n{}", "💡 Use /ref p42:/memory/<session_id> to add any of these memories as references".dimmed());
             }
             Err(e) => {
@@ -483,6 +761,68 @@ impl InteractiveSession {
         Ok(())
     }
     
+    /// Fetch a past session and carry its conversation as memory context
+    fn import_memory(&mut self, session_id: &str) -> Result<()> {
+        use crate::protocol::{MemoryDetailRequest, MemoryDetailResponse, RequestBuilder, ResponseParser};
+        
+        if self.imported_sessions.iter().any(|id| id == session_id) {
+            println!("\n{}", format!("Memory {} is already part of this session", session_id).dimmed());
+            return Ok(());
+        }
+        
+        let mut client = crate::client::DaemonClient::new(self.handler.client.port());
+        let request = MemoryDetailRequest { session_id: session_id.to_string() }
+            .build_request(crate::common::generate_id())?;
+        let detail = match client.request(request) {
+            Ok(response) if response.success => response.data
+                .ok_or_else(|| anyhow::anyhow!("No data in response"))
+                .and_then(|data| MemoryDetailResponse::parse_response(&data)),
+            Ok(response) => Err(anyhow::anyhow!(response.error.unwrap_or_else(|| "Session not found".to_string()))),
+            Err(e) => Err(e),
+        };
+        let detail = match detail {
+            Ok(detail) => detail,
+            Err(e) => {
+                println!("\n{}", format!("Could not import {}: {}", session_id, e).red());
+                println!("{}", "Find sessions with /search or 'port42 memory'".dimmed());
+                return Ok(());
+            }
+        };
+        if detail.messages.is_empty() {
+            println!("\n{}", format!("Memory {} holds no messages to import", session_id).yellow());
+            return Ok(());
+        }
+        
+        let context = detail.as_context(IMPORT_CONTEXT_CHARS);
+        self.memory_context.get_or_insert_with(Vec::new).push(context);
+        self.imported_sessions.push(detail.id.clone());
+        
+        println!("\n{}", format!("🧠 Imported {} ({} messages with {}) into memory context",
+            detail.id, detail.messages.len(), detail.agent).bright_cyan());
+        println!("{}", "The agent will see it with your next message".dimmed());
+        Ok(())
+    }
+    
+    /// Let notification sinks know a long session has wrapped up
+    fn notify_if_long(&self) {
+        let config = crate::config::Config::load_or_default();
+        let threshold = config.notify.as_ref()
+            .and_then(|n| n.long_session_minutes)
+            .unwrap_or(notify::DEFAULT_LONG_SESSION_MINUTES);
+        let minutes = self.start_time.elapsed().as_secs() / 60;
+        if minutes < threshold {
+            return;
+        }
+        notify::notify(NotifyEvent::SessionCompleted, &[
+            ("agent", self.agent.clone()),
+            ("session", self.actual_session_id.clone().unwrap_or_else(|| self.session_id.clone())),
+            ("minutes", minutes.to_string()),
+            ("depth", self.depth.to_string()),
+            ("tools", self.commands_generated.len().to_string()),
+            ("artifacts", self.artifacts_generated.len().to_string()),
+        ]);
+    }
+    
     fn show_exit_summary(&self) -> Result<()> {
         let duration = self.start_time.elapsed();
         
@@ -496,6 +836,9 @@ impl InteractiveSession {
             duration.as_secs() / 60, 
             duration.as_secs() % 60).dimmed());
         println!("{}", format!("Maximum depth: {}", self.depth).dimmed());
+        if let Some(budget) = self.handler.budget() {
+            println!("{}", format!("Token budget: {}", budget.status()).dimmed());
+        }
         
         // Generated items
         if !self.commands_generated.is_empty() {
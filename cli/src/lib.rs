@@ -7,4 +7,11 @@ pub mod help_text;
 pub mod common;
 pub mod display;
 pub mod ui;
-pub mod context;
\ No newline at end of file
+pub mod context;
+pub mod config;
+pub mod agents;
+pub mod project;
+pub mod testing;
+pub mod api;
+
+pub use api::Client;
@@ -1,7 +1,8 @@
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use clap_complete::ArgValueCompleter;
 use colored::*;
-use anyhow::Result;
-use std::io::Write;
+use anyhow::{Context, Result};
+use tracing::debug;
 
 mod boot;
 mod commands;
@@ -17,8 +18,17 @@ mod common;
 mod ui;
 mod display;
 mod context;
+mod config;
+mod agents;
+mod project;
+// Only `replay --serve` uses it here; the rest is for tests
+#[allow(dead_code)]
+mod testing;
 
 use commands::*;
+use common::errors;
+use common::logging;
+use common::providers::ProviderArgs;
 
 #[derive(Parser)]
 #[command(
@@ -43,6 +53,54 @@ struct Cli {
     /// Output in JSON format for machine processing
     #[arg(short, long, global = true)]
     json: bool,
+
+    /// Output format: plain, json, table or yaml
+    #[arg(long, global = true, value_enum, conflicts_with = "json", env = "PORT42_OUTPUT")]
+    output: Option<display::OutputFormat>,
+
+    /// Times to resend a request after a dropped connection or timeout (0 disables)
+    #[arg(long, global = true, env = "PORT42_RETRIES")]
+    retries: Option<u32>,
+
+    /// Seconds to wait for any daemon reply, overriding per-kind timeouts
+    #[arg(long, global = true, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: Option<u64>,
+
+    /// Talk to the daemon named by this profile in config (host, port and TLS)
+    #[arg(long, global = true, env = "PORT42_PROFILE", value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Refuse to talk to a daemon whose protocol doesn't match, instead of warning
+    #[arg(long, global = true, env = "PORT42_STRICT")]
+    strict: bool,
+
+    /// Print long output straight to the terminal instead of through $PAGER
+    #[arg(long, global = true)]
+    no_pager: bool,
+    
+    /// When to color output: auto (on a terminal), always or never; NO_COLOR is honoured
+    #[arg(long, global = true, value_enum, value_name = "WHEN")]
+    color: Option<display::ColorMode>,
+
+    /// How much the CLI logs about itself; PORT42_LOG takes full filter directives
+    #[arg(long, global = true, value_enum, value_name = "LEVEL")]
+    log_level: Option<logging::LogLevel>,
+
+    /// Append logs to this file instead of stderr
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<std::path::PathBuf>,
+
+    /// Log one JSON object per line
+    #[arg(long, global = true)]
+    log_json: bool,
+
+    /// Append every daemon request and response to this file as JSON lines
+    #[arg(long, global = true, value_name = "PATH")]
+    trace_requests: Option<std::path::PathBuf>,
+
+    /// Save every request and response under this directory, for `port42 replay`
+    #[arg(long, global = true, value_name = "DIR")]
+    record: Option<std::path::PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -55,6 +113,275 @@ pub enum Commands {
         action: DaemonAction,
     },
     
+    #[command(about = crate::help_text::KEYS_DESC)]
+    /// Manage provider API keys in the OS keychain
+    Keys {
+        #[command(subcommand)]
+        action: KeysAction,
+    },
+    
+    #[command(about = crate::help_text::USAGE_DESC)]
+    /// Report token usage and estimated cost per provider, agent and day
+    Usage {
+        /// Only include sessions since this time (e.g. 7d, 24h, 2024-06-01)
+        #[arg(long)]
+        since: Option<String>,
+        
+        /// Only include sessions up to and including this day (e.g. 2024-06-30, 7d)
+        #[arg(long)]
+        until: Option<String>,
+        
+        /// Show breakdown tables by provider, agent and day
+        #[arg(long)]
+        table: bool,
+    },
+    
+    #[command(about = crate::help_text::METRICS_DESC)]
+    /// Show daemon counters and histograms: requests, tokens, errors, sessions
+    Metrics {
+        /// Print the Prometheus text exposition format
+        #[arg(long)]
+        prometheus: bool,
+    },
+    
+    #[command(about = crate::help_text::ARTIFACTS_DESC)]
+    /// List, read and open the files under /artifacts
+    Artifacts {
+        #[command(subcommand)]
+        action: ArtifactsAction,
+    },
+    
+    #[command(about = crate::help_text::MATERIALIZE_DESC)]
+    /// Write VFS objects to real files, optionally keeping them in sync
+    Materialize {
+        /// VFS path of an object or a directory of them, e.g. /artifacts/docs
+        path: String,
+        
+        /// Where to write (default: the object's name in the current directory)
+        dest: Option<std::path::PathBuf>,
+        
+        /// Keep syncing: local edits are sent back, VFS changes brought down
+        #[arg(long)]
+        watch: bool,
+        
+        /// How often to check for changes in watch mode, in milliseconds
+        #[arg(long, default_value = "1000")]
+        refresh: u64,
+        
+        /// Overwrite local files even if they hold edits
+        #[arg(long)]
+        force: bool,
+    },
+    
+    #[command(about = crate::help_text::REFS_DESC)]
+    /// Inspect what references resolve to
+    Refs {
+        #[command(subcommand)]
+        action: RefsAction,
+    },
+    
+    #[command(about = crate::help_text::MODELS_DESC)]
+    /// List models each provider offers and check configured model names
+    Models {
+        /// Only query this provider
+        #[arg(long)]
+        provider: Option<String>,
+        
+        /// Exit non-zero if a configured model is not offered by its provider
+        #[arg(long)]
+        validate: bool,
+        
+        /// Show models as a table
+        #[arg(long)]
+        table: bool,
+    },
+    
+    #[command(about = crate::help_text::PROVIDERS_DESC)]
+    /// Inspect the AI providers configured on the daemon
+    Providers {
+        #[command(subcommand)]
+        action: ProvidersAction,
+    },
+    
+    #[command(about = crate::help_text::CACHE_DESC)]
+    /// Manage the AI response cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    
+    #[command(about = crate::help_text::CONFIG_DESC)]
+    /// Read and change settings in ~/.port42/config.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    
+    #[command(about = crate::help_text::ALIAS_DESC)]
+    /// Define short names for longer commands
+    Alias {
+        #[command(subcommand)]
+        action: AliasAction,
+    },
+    
+    #[command(about = crate::help_text::GIT_DESC)]
+    /// Let agents and tools take part in your git workflow
+    Git {
+        #[command(subcommand)]
+        action: GitAction,
+    },
+    
+    #[command(about = crate::help_text::HOOK_DESC)]
+    /// Report the commands you run to the daemon's context view
+    Hook {
+        #[command(subcommand)]
+        action: HookAction,
+    },
+    
+    #[command(about = crate::help_text::INDEX_DESC)]
+    /// Build and maintain the semantic search index
+    Index {
+        #[command(subcommand)]
+        action: IndexAction,
+    },
+    
+    #[command(about = crate::help_text::JOBS_DESC)]
+    /// Follow and manage background generations
+    Jobs {
+        #[command(subcommand)]
+        action: JobsAction,
+    },
+    
+    #[command(about = crate::help_text::OUTBOX_DESC)]
+    /// Requests saved with --queue while the daemon was unreachable
+    Outbox {
+        #[command(subcommand)]
+        action: OutboxAction,
+    },
+    
+    #[command(about = crate::help_text::AGENTS_DESC)]
+    /// Define, inspect and share agents
+    Agents {
+        #[command(subcommand)]
+        action: AgentsAction,
+    },
+    
+    #[command(about = crate::help_text::PROMPTS_DESC, visible_alias = "templates")]
+    /// Manage reusable prompt templates stored in the VFS
+    Prompts {
+        #[command(subcommand)]
+        action: PromptsAction,
+    },
+    
+    #[command(about = crate::help_text::COMPLETIONS_DESC)]
+    /// Generate shell completions
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+
+        /// Emit a standalone script; agent, path and session names are
+        /// looked up through 'port42 __complete' where the shell allows
+        #[arg(long = "static")]
+        static_script: bool,
+    },
+    
+    #[command(about = crate::help_text::MANPAGES_DESC)]
+    /// Write man pages for port42 and every subcommand
+    Manpages {
+        /// Directory to write the pages into
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        out_dir: std::path::PathBuf,
+    },
+    
+    /// Print completion candidates, for shell scripts to call
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(value_enum)]
+        kind: commands::completions::CompletionKind,
+        
+        /// What has been typed so far
+        #[arg(default_value = "", allow_hyphen_values = true)]
+        current: String,
+    },
+    
+    #[command(about = crate::help_text::NOTIFY_DESC)]
+    /// Manage event notifications
+    Notify {
+        #[command(subcommand)]
+        action: NotifyAction,
+    },
+    
+    #[command(about = crate::help_text::DIGEST_DESC, after_help = crate::help_text::DIGEST_AFTER_HELP)]
+    /// Summarize recent sessions, tools and commands
+    Digest {
+        /// Hours to look back (default 24)
+        #[arg(long)]
+        hours: Option<u64>,
+
+        /// Agent that writes the narrative (default @ai-analyst)
+        #[arg(long, add = ArgValueCompleter::new(commands::completions::complete_agent))]
+        agent: Option<String>,
+
+        /// Skip the AI narrative and report the facts only
+        #[arg(long)]
+        no_ai: bool,
+
+        /// Post the digest to configured notification sinks
+        #[arg(long)]
+        notify: bool,
+
+        /// Mail the digest to this address via sendmail
+        #[arg(long)]
+        email: Option<String>,
+
+        /// Don't print the digest (for scheduled runs)
+        #[arg(long, short)]
+        quiet: bool,
+    },
+    
+    #[command(about = crate::help_text::EXPORT_DESC)]
+    /// Export memories, tools and artifacts
+    Export {
+        #[command(subcommand)]
+        action: ExportAction,
+    },
+    
+    #[command(about = crate::help_text::IMPORT_DESC)]
+    /// Restore an archive made by 'export archive'
+    Import {
+        /// The .tar.gz archive or exported directory
+        archive: std::path::PathBuf,
+
+        /// Leave objects whose path is already taken (default)
+        #[arg(long, conflicts_with_all = &["overwrite", "rename"])]
+        skip: bool,
+
+        /// Replace objects whose path is already taken
+        #[arg(long, conflicts_with = "rename")]
+        overwrite: bool,
+
+        /// Import under a new name when the path is already taken
+        #[arg(long)]
+        rename: bool,
+    },
+    
+    #[command(about = crate::help_text::INIT_DESC)]
+    /// Create project-scoped settings in the current directory
+    Init {
+        /// Project name (default: the directory name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Overwrite an existing project.toml
+        #[arg(long)]
+        force: bool,
+    },
+    
+    #[command(about = crate::help_text::DOCTOR_DESC)]
+    /// Diagnose the local Port 42 environment
+    Doctor,
+    
     #[command(about = crate::help_text::STATUS_DESC)]
     /// Check the daemon's pulse
     Status {
@@ -77,6 +404,10 @@ pub enum Commands {
         /// Filter by agent who created the command
         #[arg(short, long)]
         agent: Option<String>,
+
+        /// Show tools added, removed or modified since the last run
+        #[arg(long)]
+        diff: bool,
     },
     
     #[command(about = "Track Port42 activity and monitor command usage in real-time")]
@@ -101,22 +432,51 @@ pub enum Commands {
         /// Force text mode instead of TUI when watching
         #[arg(long, help = "Force text mode instead of TUI interface")]
         text: bool,
+        
+        /// Watch in the TUI, failing instead of falling back to text
+        #[arg(long, requires = "watch", conflicts_with = "text")]
+        tui: bool,
+        
+        /// Commands, tools and memory accesses in the order they happened
+        #[arg(long, conflicts_with_all = ["pretty", "compact", "watch"])]
+        timeline: bool,
+        
+        /// Ask for past activity instead of the current snapshot
+        #[arg(long, conflicts_with = "watch")]
+        history: bool,
+        
+        /// How far back --history reaches (e.g. 2h, 7d, 2024-06-01)
+        #[arg(long, default_value = "24h", requires = "history")]
+        since: String,
+        
+        /// Most events --history returns
+        #[arg(long, default_value_t = crate::context::DEFAULT_HISTORY_LIMIT, requires = "history",
+              value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        limit: usize,
+        
+        #[command(subcommand)]
+        action: Option<ContextAction>,
     },
     
-    #[command(about = crate::help_text::SWIM_DESC)]
+    #[command(about = crate::help_text::SWIM_DESC, visible_alias = "possess")]
     /// Swim into an AI agent's consciousness stream
     Swim {
-        /// AI agent to swim (@ai-engineer, @ai-muse, @ai-analyst, @ai-founder)
+        /// AI agent to swim (@ai-engineer, @ai-muse, @ai-analyst, @ai-founder); with
+        /// default_agent configured, a first word that names no agent starts the message
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_agent))]
         agent: String,
         
         /// Session ID to resume, or 'last' for most recent
-        #[arg(long, help = "Session ID to resume, or 'last' for most recent")]
+        #[arg(long, help = "Session ID to resume, or 'last' for most recent", add = ArgValueCompleter::new(commands::completions::complete_session))]
         session: Option<String>,
         
         /// Reference entities for context (file:path, p42:/commands/name, url:https://, search:"query")
-        #[arg(long = "ref", action = clap::ArgAction::Append, help = "Reference other entities for context in conversation (can be used multiple times)\n\nAvailable reference types:\n• file:./path/to/file    - Include local file content\n• p42:/commands/name     - Reference existing command or tool\n• url:https://api.docs   - Fetch web content for context\n• search:\"query terms\"   - Load relevant memories/tools\n\nExample: --ref file:./config.json --ref search:\"error patterns\"")]
+        #[arg(long = "ref", action = clap::ArgAction::Append, help = "Reference other entities for context in conversation (can be used multiple times)\n\nAvailable reference types:\n• file:./path/to/file    - Include local file content\n• file:./src/**/*.rs    - Include every file in a directory or glob\n• p42:/commands/name     - Reference existing command or tool\n• url:https://api.docs   - Fetch web content for context (!raw, !max=20k, !fresh)\n• search:\"query terms\"   - Load relevant memories/tools\n• stdin:                 - Include content piped into the command\n• git:diff, git:A..B     - Include uncommitted changes, or commits in a range\n\nExample: --ref file:./config.json --ref search:\"error patterns\"")]
         references: Option<Vec<String>>,
         
+        #[command(flatten)]
+        options: commands::swim::SwimArgs,
+        
         /// Message to send to the AI
         #[arg(trailing_var_arg = true)]
         message: Vec<String>,
@@ -134,11 +494,32 @@ pub enum Commands {
     Memory {
         /// Session ID to show, or 'search' followed by query
         args: Vec<String>,
+
+        /// Copy the shown session's transcript to the clipboard
+        #[arg(long)]
+        copy: bool,
+
+        /// With 'fork': keep messages up to and including this one
+        #[arg(long, value_name = "MESSAGE")]
+        at: Option<usize>,
+
+        /// With 'merge': name for the merged thread
+        #[arg(long)]
+        name: Option<String>,
+
+        /// With 'merge': order messages by time instead of session by session
+        #[arg(long)]
+        interleave: bool,
+
+        /// Browse sessions interactively; any words given start the filter
+        #[arg(long, conflicts_with_all = ["copy", "at", "name", "interleave"])]
+        tui: bool,
     },
 
     /// Recall a session transcript by ID or prefix
     Session {
         /// Session ID or prefix (e.g., '1754' matches 'cli-1754280556310')
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_session))]
         id_prefix: String,
     },
     
@@ -146,21 +527,231 @@ pub enum Commands {
     /// List contents of the virtual filesystem
     Ls {
         /// Path to list (default: /)
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        path: Option<String>,
+    },
+    
+    #[command(about = crate::help_text::TREE_DESC)]
+    /// Show the virtual filesystem as a tree
+    Tree {
+        /// Where to start (default: /)
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
         path: Option<String>,
+
+        /// Descend at most this many levels
+        #[arg(short = 'L', long, value_parser = clap::value_parser!(u32).range(1..))]
+        level: Option<u32>,
+
+        /// Only show entries of this kind (repeatable); directories holding matches stay
+        #[arg(long = "type", short = 't', value_enum)]
+        types: Vec<commands::tree::EntryKind>,
+
+        /// Annotate entries with sizes; directories show the total beneath them
+        #[arg(long, short = 's')]
+        size: bool,
     },
     
     #[command(about = crate::help_text::CAT_DESC)]
     /// Display content from any reality path
     Cat {
         /// Path to read
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
         path: String,
+
+        /// Also copy the content to the clipboard
+        #[arg(long)]
+        copy: bool,
+
+        /// Print the content exactly as stored: no header, no highlighting
+        #[arg(long)]
+        plain: bool,
+
+        /// Read an earlier version, as numbered by 'port42 history'
+        #[arg(long, value_name = "N")]
+        version: Option<u32>,
     },
     
     #[command(about = crate::help_text::INFO_DESC)]
     /// Examine the metadata essence of objects
     Info {
         /// Path to inspect
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
         path: String,
+
+        /// Inspect an earlier version, as numbered by 'port42 history'
+        #[arg(long, value_name = "N")]
+        version: Option<u32>,
+    },
+    
+    #[command(about = crate::help_text::HISTORY_DESC)]
+    /// List the earlier versions of an object
+    History {
+        /// Path whose versions to list
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        path: String,
+    },
+    
+    #[command(about = crate::help_text::ROLLBACK_DESC)]
+    /// Restore an earlier version of a command or artifact
+    Rollback {
+        /// Path to roll back
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        path: String,
+
+        /// Version to restore; asks with a list when left out
+        #[arg(long, value_name = "N")]
+        version: Option<u32>,
+
+        /// Don't show the changes and ask before rolling back
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    
+    #[command(about = crate::help_text::EVOLVE_DESC)]
+    /// Have the AI revise an existing tool, review the diff, then apply it as a new version
+    Evolve {
+        /// Name of the tool to evolve
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_tool))]
+        command: String,
+
+        /// What should change
+        prompt: Option<String>,
+
+        /// Reference entities for context (file:path, p42:/commands/name, url:https://, search:"query")
+        #[arg(long = "ref", action = clap::ArgAction::Append)]
+        references: Vec<String>,
+
+        /// Agent whose persona and model defaults shape the change
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// AI provider and model selection
+        #[command(flatten)]
+        provider: ProviderArgs,
+
+        /// Show the diff without applying it; needs a daemon that can preview
+        #[arg(long)]
+        dry_run: bool,
+    },
+    
+    #[command(about = crate::help_text::UPDATE_DESC)]
+    /// Replace an existing object's content with a local file, keeping its metadata
+    Update {
+        /// Path of the object to update
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        path: String,
+
+        /// File holding the new content, or - for stdin
+        #[arg(long, value_name = "FILE")]
+        from: std::path::PathBuf,
+
+        /// Don't show the changes and ask before updating
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    
+    #[command(about = crate::help_text::RUN_DESC)]
+    /// Run a crystallized command and record how it went
+    Run {
+        /// Command name in /commands, or its full VFS path
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_tool))]
+        name: String,
+
+        /// Arguments passed through to the command
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    
+    #[command(about = crate::help_text::NOTE_DESC)]
+    /// Annotate a command, artifact or memory
+    Note {
+        /// Path to annotate
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        path: String,
+
+        /// The note; quote it or let the words run on
+        #[arg(trailing_var_arg = true, required_unless_present = "list", conflicts_with = "list")]
+        text: Vec<String>,
+
+        /// Show the notes already on the path
+        #[arg(long)]
+        list: bool,
+    },
+    
+    #[command(about = crate::help_text::DIFF_DESC, after_help = crate::help_text::DIFF_AFTER_HELP)]
+    /// Compare two objects line by line
+    Diff {
+        /// The original path, e.g. the previous version of a tool
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        left: String,
+
+        /// The path to compare against it
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        right: String,
+
+        /// Lines of context around each change
+        #[arg(short = 'U', long = "unified", value_name = "LINES", default_value_t = display::diff::DEFAULT_CONTEXT)]
+        context: usize,
+    },
+    
+    #[command(about = crate::help_text::CP_DESC)]
+    /// Replicate an object to another reality path
+    Cp {
+        /// Path to copy
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        source: String,
+
+        /// Where the copy goes (a trailing '/' keeps the source's name)
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        destination: String,
+
+        /// Replace an object already at the destination
+        #[arg(long, short = 'f')]
+        force: bool,
+    },
+    
+    #[command(about = crate::help_text::MV_DESC)]
+    /// Relocate an object within the virtual realm
+    Mv {
+        /// Path to move
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        source: String,
+
+        /// New path (a trailing '/' keeps the source's name)
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        destination: String,
+
+        /// Replace an object already at the destination
+        #[arg(long, short = 'f')]
+        force: bool,
+    },
+    
+    #[command(about = crate::help_text::RM_DESC)]
+    /// Move objects to /trash, or remove them for good with --force
+    Rm {
+        /// Paths to remove
+        #[arg(required = true, add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        paths: Vec<String>,
+
+        /// Remove permanently instead of moving to /trash
+        #[arg(long, short = 'f')]
+        force: bool,
+
+        /// Don't ask before removing permanently
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    
+    #[command(about = crate::help_text::RESTORE_DESC)]
+    /// Bring an object back from /trash
+    Restore {
+        /// Trashed path (e.g. /trash/commands/foo) or the path it was removed from
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        path: String,
+
+        /// Restore somewhere other than where it came from
+        #[arg(long)]
+        to: Option<String>,
     },
     
     #[command(about = crate::help_text::SEARCH_DESC)]
@@ -181,6 +772,11 @@ pub enum Commands {
         #[arg(long = "exact", short = 'e', conflicts_with_all = &["all", "any"])]
         exact: bool,
         
+        /// Rank by meaning using the embedding index, fused with keyword matches
+        /// (filters by --path and --type only)
+        #[arg(long, conflicts_with_all = &["all", "any", "exact", "after", "before", "agent", "tags"])]
+        semantic: bool,
+        
         /// Limit search to paths under this prefix
         #[arg(long)]
         path: Option<String>,
@@ -208,42 +804,673 @@ pub enum Commands {
         /// Maximum number of results to show
         #[arg(long, short = 'n', default_value = "20")]
         limit: Option<usize>,
+
+        /// Copy the top result's path to the clipboard
+        #[arg(long)]
+        copy: bool,
+    },
+    
+    #[command(about = crate::help_text::RULES_DESC)]
+    /// Define what the daemon does when things happen
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
+    },
+    
+    #[command(about = crate::help_text::EVENTS_DESC)]
+    /// Follow what the daemon is doing as it happens
+    Events {
+        #[command(subcommand)]
+        action: EventsAction,
+    },
+    
+    #[command(about = crate::help_text::REPLAY_DESC)]
+    /// Resend requests captured with --record and compare the answers, or
+    /// serve the recorded answers in place of the daemon. Only requests that
+    /// read are resent unless others are named with --type or --yes is given;
+    /// AI requests are generated afresh when resent.
+    Replay {
+        /// Directory given to --record
+        dir: std::path::PathBuf,
+        
+        /// Stand in for the daemon on this port, answering from the recording
+        #[arg(long, value_name = "PORT", conflicts_with = "types")]
+        serve: Option<u16>,
+        
+        /// Only resend requests of this type, e.g. swim (repeatable)
+        #[arg(long = "type", value_name = "TYPE")]
+        types: Vec<String>,
+        
+        /// Resend requests that change things too, not only those that read
+        #[arg(long, short = 'y', conflicts_with_all = ["serve", "types"])]
+        yes: bool,
+    },
+    
+    #[command(about = crate::help_text::UPGRADE_DESC, after_help = crate::help_text::UPGRADE_AFTER_HELP)]
+    /// Download the latest release, check its checksum, replace the CLI and
+    /// daemon binaries, bring ~/.port42 up to date and restart the daemon
+    Upgrade {
+        /// Only report whether a newer release is available
+        #[arg(long)]
+        check: bool,
+        
+        /// Install this version instead of the latest, e.g. 0.1.2
+        #[arg(long, value_name = "VERSION")]
+        to: Option<String>,
+        
+        /// Reinstall even if this version is already the newest
+        #[arg(long)]
+        force: bool,
+        
+        /// Run by the newly installed binary to migrate ~/.port42
+        #[arg(long, hide = true)]
+        finish: bool,
+    },
+    
+    /// Watch real-time system activity
+    Watch {
+        /// What to watch (rules, sessions)
+        target: String,
+
+        /// Refresh rate in milliseconds for live views
+        #[arg(long, default_value = "2000")]
+        refresh: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DaemonAction {
+    /// Start the daemon
+    Start {
+        /// Run in background (default: foreground)
+        #[arg(short, long)]
+        background: bool,
+    },
+
+    /// Stop the daemon
+    Stop,
+
+    /// Restart the daemon
+    Restart,
+
+    /// Check daemon status
+    Status,
+
+    /// Show daemon logs
+    Logs {
+        /// Number of lines to show
+        #[arg(short = 'n', long, default_value = "50")]
+        lines: usize,
+
+        /// Follow log output
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Only lines matching this regular expression
+        #[arg(long, value_name = "PATTERN")]
+        grep: Option<String>,
+
+        /// Only lines at least this severe
+        #[arg(long, value_enum)]
+        level: Option<crate::common::daemon_log::Level>,
+
+        /// Only lines since a time (e.g. 10m, 2h, 2024-06-01)
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// Run the daemon under launchd or systemd, starting at login and restarting on crashes
+    Install {
+        /// Start at login for the current user (default)
+        #[arg(long, conflicts_with = "system")]
+        user: bool,
+
+        /// Start at boot for the whole machine (needs sudo -E)
+        #[arg(long)]
+        system: bool,
+
+        /// Print the files and commands without installing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Remove the launchd or systemd service added by install
+    Uninstall {
+        /// Remove the user service even if a system one exists too
+        #[arg(long, conflicts_with = "system")]
+        user: bool,
+
+        /// Remove the system service
+        #[arg(long)]
+        system: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum KeysAction {
+    /// Store an API key for a provider (prompts without echo)
+    Set {
+        /// Provider name (anthropic, openai, google)
+        provider: String,
+
+        /// Read the key from stdin instead of prompting
+        #[arg(long)]
+        stdin: bool,
+    },
+
+    /// Show the stored key for a provider (masked unless --reveal)
+    Get {
+        /// Provider name
+        provider: String,
+
+        /// Print the full key
+        #[arg(long)]
+        reveal: bool,
+    },
+
+    /// Remove a provider's key from the keychain
+    Remove {
+        /// Provider name
+        provider: String,
+    },
+
+    /// List which providers have keys and where they come from
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum ContextAction {
+    /// Run a suggestion shown by 'port42 context --pretty'
+    Run {
+        /// Suggestion number, counting from 1
+        #[arg(value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        number: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// Delete all cached responses and fetched pages
+    Clear,
+
+    /// Show cache size and location
+    Stats,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Print a setting's effective value, e.g. timeouts.ai_secs
+    Get {
+        key: String,
+    },
+
+    /// Change a setting in config.toml, keeping the rest of the file as written
+    Set {
+        key: String,
+
+        /// Numbers, booleans and [arrays] keep their type; anything else is text
+        value: String,
+    },
+
+    /// Show every effective setting and where it came from
+    List,
+
+    /// Open config.toml in $VISUAL or $EDITOR, checking it before saving
+    Edit,
+}
+
+#[derive(Subcommand)]
+pub enum AliasAction {
+    /// Show configured aliases
+    List,
+
+    /// Define an alias, e.g. `alias add sw swim @ai-engineer`
+    Add {
+        name: String,
+
+        /// The command the alias stands for, without 'port42'
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+
+    /// Forget an alias
+    Remove {
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GitAction {
+    /// Install prepare-commit-msg and pre-push hooks in this repository
+    InstallHooks {
+        /// Only install these hooks (default: all supported)
+        #[arg(long = "hook")]
+        hooks: Vec<String>,
+
+        /// Replace existing hooks, keeping a backup
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Remove Port 42 hooks and restore any that were replaced
+    UninstallHooks,
+
+    /// Run a hook's handler (called by the installed hook scripts)
+    #[command(hide = true)]
+    RunHook {
+        /// Hook name
+        hook: String,
+
+        /// Arguments git passed to the hook
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum HookAction {
+    /// Add a preexec/precmd hook to your shell's startup file
+    Install {
+        /// Shell to hook into
+        #[arg(long, value_enum)]
+        shell: commands::hook::HookShell,
+
+        /// Print the hook instead of editing the startup file
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Remove the hook from your shell's startup file
+    Uninstall {
+        /// Shell to unhook
+        #[arg(long, value_enum)]
+        shell: commands::hook::HookShell,
+    },
+
+    /// Report one executed command (called by the hook)
+    #[command(hide = true)]
+    Record {
+        /// Exit code of the command
+        #[arg(long, default_value = "0", allow_hyphen_values = true)]
+        exit_code: i32,
+
+        /// Directory the command ran in
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// The command line as typed
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProvidersAction {
+    /// List providers with their default models
+    List {
+        /// Show providers as a table
+        #[arg(long)]
+        table: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum NotifyAction {
+    /// Send a test message to every configured sink
+    Test {
+        /// Only test the sink with this name
+        #[arg(long)]
+        sink: Option<String>,
+    },
+
+    /// Run a command when a daemon event happens, e.g. --on tool_created --exec 'notify-send "$PORT42_SUBJECT"'
+    Add {
+        /// Event that runs the command
+        #[arg(long, value_enum)]
+        on: protocol::events::EventKind,
+
+        /// Shell command; the event is in $PORT42_EVENT, $PORT42_SUBJECT and $PORT42_TIMESTAMP, and on stdin as JSON
+        #[arg(long)]
+        exec: String,
+    },
+
+    /// List hooks, numbered for `notify remove`
+    List,
+
+    /// Remove a hook by its number in `notify list`
+    Remove {
+        #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+        number: u64,
+    },
+
+    /// Run hooks as daemon events happen, until interrupted
+    Watch {
+        /// How often to check for events when the daemon can't push them, in milliseconds
+        #[arg(long, default_value = "2000")]
+        refresh: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportAction {
+    /// Render a browsable, searchable static HTML site
+    Site {
+        /// Directory to write the site into
+        #[arg(long, default_value = "./port42-site")]
+        out: std::path::PathBuf,
+    },
+
+    /// Snapshot objects and a manifest for backup or another machine
+    Archive {
+        /// A .tar.gz or .tgz file, or any other path for a plain directory
+        #[arg(long, default_value = "./port42-archive.tar.gz")]
+        out: std::path::PathBuf,
+
+        /// Only include these kinds of object (repeatable)
+        #[arg(long = "type", value_enum)]
+        types: Vec<commands::export::Kind>,
+
+        /// Only include objects created by this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Only include objects created on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        after: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IndexAction {
+    /// Embed every memory and tool from scratch
+    Build,
+
+    /// Embed only what is new or changed since the last build
+    Update,
+
+    /// Show index size, model and age
+    Stats,
+}
+
+#[derive(Subcommand)]
+pub enum RulesAction {
+    /// List rules and how often they fired
+    List,
+
+    /// Add a rule, e.g. --when tool-created --matching 'git-*' --run 'notify-send {name}'
+    Add {
+        /// Name for the rule
+        name: String,
+
+        /// Event that fires the rule
+        #[arg(long, value_enum)]
+        when: protocol::rules::RuleEvent,
+
+        /// Only fire for subjects whose name matches this glob
+        #[arg(long)]
+        matching: Option<String>,
+
+        /// Shell command to run; {name} and {path} are filled in
+        #[arg(long)]
+        run: String,
+
+        /// What the rule is for
+        #[arg(long)]
+        description: Option<String>,
+    },
+
+    /// Turn a rule back on
+    Enable {
+        rule_id: String,
+    },
+
+    /// Stop a rule from firing without deleting it
+    Disable {
+        rule_id: String,
+    },
+
+    /// Remove a rule
+    Delete {
+        rule_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EventsAction {
+    /// Print events as they happen until interrupted; one JSON object per line with --json
+    Tail {
+        /// Only these kinds of event (repeatable)
+        #[arg(long = "type", value_enum)]
+        types: Vec<protocol::events::EventKind>,
+
+        /// How often to check for events when the daemon can't push them, in milliseconds
+        #[arg(long, default_value = "2000")]
+        refresh: u64,
+    },
+
+    /// POST each event as JSON to a webhook until interrupted
+    Forward {
+        /// Where to POST, e.g. https://hooks.example.com/port42
+        #[arg(long)]
+        url: String,
+
+        /// Sign each body with HMAC-SHA256, sent as X-Port42-Signature: sha256=<hex>
+        #[arg(long, env = "PORT42_WEBHOOK_SECRET", hide_env_values = true)]
+        secret: Option<String>,
+
+        /// Only these kinds of event (repeatable)
+        #[arg(long = "type", value_enum)]
+        types: Vec<protocol::events::EventKind>,
+
+        /// Times to retry a failed delivery before skipping it
+        #[arg(long, default_value = "3")]
+        retries: u32,
+
+        /// How often to check for events when the daemon can't push them, in milliseconds
+        #[arg(long, default_value = "2000")]
+        refresh: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ArtifactsAction {
+    /// List artifacts, grouped by the folder they were filed in
+    List {
+        /// Only artifacts whose path contains this text
+        filter: Option<String>,
+        
+        /// Only this file type or folder, e.g. md, png or docs
+        #[arg(long = "type", value_name = "TYPE")]
+        file_type: Option<String>,
+    },
+    
+    /// Show an artifact: markdown rendered, source highlighted
+    Show {
+        /// Path under /artifacts (docs/readme.md) or a full VFS path
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        name: String,
+        
+        /// Print the text as stored, without rendering
+        #[arg(long)]
+        raw: bool,
+    },
+    
+    /// Save an artifact to a temp file and open it in the default application
+    Open {
+        /// Path under /artifacts (diagrams/flow.png) or a full VFS path
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_vfs_path))]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RefsAction {
+    /// Resolve references as declare and possess would and show the content injected
+    Resolve {
+        /// Reference to resolve, e.g. file:./notes.md or url:https://... (can be used multiple times)
+        #[arg(long = "ref", value_name = "REF", action = clap::ArgAction::Append, required = true)]
+        references: Vec<String>,
+        
+        /// Print each reference in full instead of its first lines
+        #[arg(long)]
+        full: bool,
+        
+        /// Leave out the current project's references
+        #[arg(long)]
+        no_project: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum JobsAction {
+    /// List background jobs
+    List {
+        /// Only jobs that are still queued or running
+        #[arg(long)]
+        active: bool,
+    },
+
+    /// Show a job's state and progress
+    Status {
+        /// Job ID from --detach
+        job_id: String,
+    },
+
+    /// Follow a job's progress and show its result when it finishes
+    Attach {
+        /// Job ID from --detach
+        job_id: String,
+    },
+
+    /// Stop a queued or running job
+    Cancel {
+        /// Job ID from --detach
+        job_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum OutboxAction {
+    /// Show waiting requests, and any the daemon turned down
+    List,
+
+    /// Send every waiting request now and report how each went
+    Flush,
+
+    /// Put a turned-down or unanswered request back in line
+    Retry {
+        /// Name shown by 'port42 outbox list'
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AgentsAction {
+    /// Show built-in, custom and daemon-registered agents
+    List,
+
+    /// Show an agent's persona, guidance, references and model
+    Show {
+        /// Agent to show, e.g. @ai-engineer
+        agent: String,
+    },
+
+    /// Define a custom agent
+    Add {
+        /// Agent name, e.g. @ai-reviewer
+        name: String,
+
+        /// One-line summary shown in listings
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Who the agent is, sent with every message
+        #[arg(long, conflicts_with = "persona_file")]
+        persona: Option<String>,
+
+        /// Read the persona from a file
+        #[arg(long)]
+        persona_file: Option<std::path::PathBuf>,
+
+        /// Extra instructions sent alongside the persona
+        #[arg(long)]
+        guidance: Option<String>,
+
+        /// Reference included with every message (can be used multiple times)
+        #[arg(long = "ref", action = clap::ArgAction::Append)]
+        refs: Vec<String>,
+
+        /// Default AI provider for this agent
+        #[arg(long)]
+        provider: Option<String>,
+
+        /// Default model for this agent
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Replace an existing definition with the same name
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Remove a custom agent
+    #[command(alias = "rm")]
+    Remove {
+        /// Agent to remove
+        agent: String,
+    },
+
+    /// Write an agent's guidance, default refs and model settings to a pack
+    Export {
+        /// Agent to export, e.g. @ai-engineer
+        agent: String,
+
+        /// Write the pack to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
     },
-    
-    /// Watch real-time system activity
-    Watch {
-        /// What to watch (rules, sessions)
-        target: String,
+
+    /// Install an agent from a pack file
+    Import {
+        /// Path to the pack (.toml)
+        file: String,
+
+        /// Replace an existing definition with the same name
+        #[arg(long)]
+        force: bool,
     },
 }
 
 #[derive(Subcommand)]
-pub enum DaemonAction {
-    /// Start the daemon
-    Start {
-        /// Run in background (default: foreground)
-        #[arg(short, long)]
-        background: bool,
-    },
+pub enum PromptsAction {
+    /// Save a prompt template (from --file, stdin, or $EDITOR)
+    Add {
+        /// Template name, e.g. review-pr
+        name: String,
 
-    /// Stop the daemon
-    Stop,
+        /// Read the template from a file
+        #[arg(long)]
+        file: Option<String>,
 
-    /// Restart the daemon
-    Restart,
+        /// Short description shown in search results
+        #[arg(long)]
+        description: Option<String>,
+    },
 
-    /// Check daemon status
-    Status,
+    /// List saved prompt templates
+    List,
 
-    /// Show daemon logs
-    Logs {
-        /// Number of lines to show
-        #[arg(short = 'n', long, default_value = "50")]
-        lines: usize,
+    /// Show a template and the variables it expects
+    Show {
+        /// Template name
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_template))]
+        name: String,
+    },
 
-        /// Follow log output
-        #[arg(short, long)]
-        follow: bool,
+    /// Delete a prompt template
+    Remove {
+        /// Template name
+        #[arg(add = ArgValueCompleter::new(commands::completions::complete_template))]
+        name: String,
     },
 }
 
@@ -272,6 +1499,24 @@ pub enum MemoryAction {
         /// New name for the session
         new_name: String,
     },
+    
+    /// Branch a session into a new one, leaving the original untouched
+    Fork {
+        /// Session ID to branch from
+        session_id: String,
+        /// Keep messages up to and including this one (as numbered in 'memory <id>')
+        at: Option<usize>,
+    },
+    
+    /// Combine sessions into a new thread that records where each message came from
+    Merge {
+        /// Sessions to combine, in order
+        session_ids: Vec<String>,
+        /// Name for the merged thread
+        name: Option<String>,
+        /// Order messages by time instead of one session after another
+        interleave: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -286,12 +1531,39 @@ pub enum DeclareCommand {
         transforms: Option<String>,
         
         /// Reference entities for context (file:path, p42:/commands/name, url:https://, search:"query")
-        #[arg(long = "ref", action = clap::ArgAction::Append, help = "Reference other entities for context (can be used multiple times)\n\nAvailable reference types:\n• file:./path/to/file    - Local file reference\n• p42:/commands/name     - Port 42 VFS reference\n• url:https://api.docs   - Web URL reference\n• search:\"query terms\"   - Search-based reference\n\nExample: --ref file:./config.json --ref search:\"error patterns\"")]
+        #[arg(long = "ref", action = clap::ArgAction::Append, help = "Reference other entities for context (can be used multiple times)\n\nAvailable reference types:\n• file:./path/to/file    - Local file reference\n• file:./src/**/*.rs    - Every file in a directory or glob\n• p42:/commands/name     - Port 42 VFS reference\n• url:https://api.docs   - Web URL reference (!raw, !max=20k, !fresh)\n• search:\"query terms\"   - Search-based reference\n• stdin:                 - Content piped into the command\n• git:diff, git:A..B     - Uncommitted changes, or commits in a range\n\nExample: --ref file:./config.json --ref search:\"error patterns\"")]
         references: Option<Vec<String>>,
         
         /// Custom prompt to guide AI tool generation  
         #[arg(long, help = "Custom prompt to guide AI tool generation\n\nProvide specific instructions for how the tool should work.\nCombined with references to create contextually-aware tools.\n\nExample: --prompt \"Create a tool that analyzes logs and highlights errors\"")]
         prompt: Option<String>,
+        
+        /// Agent whose persona and model defaults shape the tool
+        #[arg(long)]
+        agent: Option<String>,
+        
+        /// AI provider and model selection, and --detach
+        #[command(flatten)]
+        options: commands::declare::DeclareArgs,
+    },
+    
+    /// Declare every tool listed in a manifest file
+    Batch {
+        /// Manifest of tool specs (YAML, or .json / .toml)
+        #[arg(long, short = 'f')]
+        file: std::path::PathBuf,
+
+        /// Check the manifest and show what would be declared
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Most tools to declare at once; rate-limited requests wait and retry
+        #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..=16))]
+        max_concurrent: u16,
+
+        /// AI provider and model for tools that don't name their own
+        #[command(flatten)]
+        provider: ProviderArgs,
     },
     
     /// Declare that an artifact should exist
@@ -310,45 +1582,134 @@ pub enum DeclareCommand {
         /// Custom prompt to guide AI artifact generation
         #[arg(long, help = "Custom prompt to guide AI artifact generation\n\nProvide specific instructions for the artifact content and structure.\nWorks with references to create contextually-aware documentation.\n\nExample: --prompt \"Create API documentation with examples and error codes\"")]
         prompt: Option<String>,
+        
+        /// AI provider and model selection, and --detach
+        #[command(flatten)]
+        options: commands::declare::DeclareArgs,
     },
 }
 
-fn main() -> Result<()> {
-    // Set up colored output first
-    colored::control::set_override(true);
+/// `--color` as typed, read ahead of clap so help and parse errors honour it.
+/// Anything unparseable is left for clap to reject.
+fn color_flag(args: &[String]) -> Option<display::ColorMode> {
+    let mut words = args.iter().skip(1).take_while(|a| *a != "--");
+    while let Some(word) = words.next() {
+        let value = match word.strip_prefix("--color") {
+            Some("") => words.next().map(String::as_str),
+            Some(rest) => rest.strip_prefix('='),
+            None => continue,
+        };
+        return value.and_then(|v| <display::ColorMode as clap::ValueEnum>::from_str(v, true).ok());
+    }
+    None
+}
+
+fn main() {
+    if let Err(e) = run() {
+        std::process::exit(errors::report(&e));
+    }
+}
+
+fn run() -> Result<()> {
+    // Answer shell completion callbacks (COMPLETE=<shell>) before anything else
+    clap_complete::CompleteEnv::with_factory(Cli::command)
+        .var(commands::completions::COMPLETE_VAR)
+        .complete();
+    
+    let config = config::Config::load_or_default();
+    
+    // A leading alias becomes the command it stands for
+    let args = alias::expand_args(std::env::args().collect(), &config.aliases)?;
+    
+    // Settle colour before anything prints, help included
+    let color = display::ColorMode::resolve(
+        color_flag(&args),
+        std::env::var("PORT42_COLOR").ok().as_deref(),
+        std::env::var("NO_COLOR").ok().as_deref(),
+        config.color.as_deref(),
+    );
+    let colorize = display::color::apply(color);
     
     // Check if this is a help request and handle it with our custom help
-    if help_handler::handle_help_request() {
+    if help_handler::handle_help_request(&args) {
         return Ok(());
     }
     
-    // Otherwise, let Clap parse normally
-    let cli = Cli::parse();
+    // Otherwise, let Clap parse normally, coloring its own help and errors to match
+    let matches = Cli::command()
+        .color(if colorize { clap::ColorChoice::Always } else { clap::ColorChoice::Never })
+        .get_matches_from(&args);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    
+    logging::init(&logging::LogOptions {
+        level: cli.log_level,
+        file: cli.log_file.clone(),
+        json: cli.log_json,
+        trace_requests: cli.trace_requests.clone(),
+    })?;
+    if let Some(ref dir) = cli.record {
+        common::recording::start(dir)?;
+    }
+    
+    // Before any client exists, so port detection honours it too
+    if let Some(retries) = cli.retries {
+        client::set_retries(retries);
+    }
+    if let Some(secs) = cli.timeout {
+        client::set_timeout(std::time::Duration::from_secs(secs));
+    }
+    client::set_strict(cli.strict);
+    // A profile points every client at its daemon, port detection included
+    let profile = match cli.profile.as_ref().or(config.profile.as_ref()) {
+        Some(name) => {
+            let profile = config.profile(name)?.clone();
+            client::set_endpoint(common::endpoint::Endpoint::from_profile(name, &profile));
+            Some(profile)
+        }
+        None => None,
+    };
     
     // Handle verbose flag
     if cli.verbose {
         eprintln!("{}", "🔍 Verbose mode enabled".dimmed());
     }
     
-    // Determine port
-    let port = cli.port.unwrap_or_else(|| {
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: main() - no explicit port, calling detect_daemon_port()");
-        }
+    // Determine port: --port or PORT42_PORT, then the profile, then config, then whichever port answers
+    let port = cli.port.or(profile.as_ref().and_then(|p| p.port)).or(config.port).unwrap_or_else(|| {
+        debug!("main() - no explicit port, calling detect_daemon_port()");
         // Use proper daemon ping to discover port
         let discovered_port = client::detect_daemon_port().unwrap_or(42);
-        if std::env::var("PORT42_DEBUG").is_ok() {
-            eprintln!("DEBUG: main() - discovered port: {}", discovered_port);
-        }
+        debug!("main() - discovered port: {}", discovered_port);
         discovered_port
     });
     
-    // Determine output format (reserved for future use)
-    let _output_format = if cli.json {
+    // Determine output format: --output wins, --json is shorthand for it
+    let output_format = cli.output.unwrap_or(if cli.json {
         display::OutputFormat::Json
     } else {
-        display::OutputFormat::Plain
-    };
+        config.output.as_deref()
+            .and_then(|o| <display::OutputFormat as clap::ValueEnum>::from_str(o, true).ok())
+            .unwrap_or(display::OutputFormat::Plain)
+    });
+    let json = output_format == display::OutputFormat::Json;
+    errors::set_json(json);
+    if json {
+        // Keep escape codes out of anything a machine will parse
+        colored::control::set_override(false);
+    }
+    
+    // Reading commands page anything taller than the terminal; lives until run() returns
+    let pageable = matches!(cli.command, Some(
+        Commands::Ls { .. } | Commands::Tree { .. } | Commands::Cat { .. } | Commands::Info { .. } | Commands::History { .. } | Commands::Diff { .. }
+        | Commands::Memory { tui: false, .. } | Commands::Session { .. } | Commands::Search { .. }
+        | Commands::Artifacts { action: ArtifactsAction::List { .. } | ArtifactsAction::Show { .. } }
+    ));
+    let _pager = display::Pager::start(pageable && !cli.no_pager);
+    
+    // Anything queued while the daemon was away goes out once it's back
+    if !matches!(cli.command, None | Some(Commands::Daemon { .. } | Commands::Outbox { .. } | Commands::Upgrade { .. })) {
+        outbox::hand_off_as_jobs(port);
+    }
     
     // Route to command handlers
     match cli.command {
@@ -357,16 +1718,138 @@ fn main() -> Result<()> {
             daemon::handle_daemon(action, port)?;
         }
         
+        Some(Commands::Keys { action }) => {
+            keys::handle_keys(action)?;
+        }
+        
+        Some(Commands::Cache { action }) => {
+            cache::handle_cache(action)?;
+        }
+        
+        Some(Commands::Config { action }) => {
+            commands::config::handle_config(action, output_format)?;
+        }
+        
+        Some(Commands::Alias { action }) => {
+            alias::handle_alias(action, output_format)?;
+        }
+        
+        Some(Commands::Git { action }) => {
+            commands::git::handle_git(action, port)?;
+        }
+        
+        Some(Commands::Hook { action }) => {
+            commands::hook::handle_hook(action, port)?;
+        }
+        
+        Some(Commands::Index { action }) => {
+            commands::index::handle_index(action, port)?;
+        }
+        
+        Some(Commands::Notify { action }) => {
+            commands::notify::handle_notify(port, action)?;
+        }
+        
+        Some(Commands::Digest { hours, agent, no_ai, notify, email, quiet }) => {
+            commands::digest::handle_digest(port, commands::digest::DigestOptions { hours, agent, no_ai, notify, email, quiet })?;
+        }
+        
+        Some(Commands::Export { action }) => {
+            commands::export::handle_export(action, port)?;
+        }
+        
+        Some(Commands::Import { archive, skip: _, overwrite, rename }) => {
+            use common::archive::ConflictPolicy;
+            let policy = if overwrite {
+                ConflictPolicy::Overwrite
+            } else if rename {
+                ConflictPolicy::Rename
+            } else {
+                ConflictPolicy::Skip
+            };
+            commands::import::handle_import(port, &archive, policy, json)?;
+        }
+        
+        Some(Commands::Init { name, force }) => {
+            commands::init::handle_init(name, force)?;
+        }
+        
+        Some(Commands::Doctor) => {
+            commands::doctor::handle_doctor(port, json)?;
+        }
+        
+        Some(Commands::Completions { shell, static_script }) => {
+            commands::completions::handle_completions(shell, static_script)?;
+        }
+        
+        Some(Commands::Manpages { out_dir }) => {
+            commands::manpages::handle_manpages(&out_dir)?;
+        }
+        
+        Some(Commands::Complete { kind, current }) => {
+            commands::completions::handle_complete(kind, &current)?;
+        }
+        
+        Some(Commands::Jobs { action }) => {
+            jobs::handle_jobs(port, action, output_format)?;
+        }
+        
+        Some(Commands::Outbox { action }) => {
+            outbox::handle_outbox(port, action)?;
+        }
+        
+        Some(Commands::Agents { action }) => {
+            commands::agents::handle_agents(port, action, output_format)?;
+        }
+        
+        Some(Commands::Prompts { action }) => {
+            prompts::handle_prompts(action, port)?;
+        }
+        
+        Some(Commands::Usage { since, until, table }) => {
+            let format = if table && output_format == display::OutputFormat::Plain {
+                display::OutputFormat::Table
+            } else {
+                output_format
+            };
+            usage::handle_usage_with_format(port, since, until, format)?;
+        }
+        
+        Some(Commands::Metrics { prometheus }) => {
+            metrics::handle_metrics(port, prometheus, output_format)?;
+        }
+        
+        Some(Commands::Artifacts { action }) => {
+            artifacts::handle_artifacts(port, action, output_format)?;
+        }
+        
+        Some(Commands::Materialize { path, dest, watch, refresh, force }) => {
+            materialize::handle_materialize(port, path, dest, watch, refresh, force)?;
+        }
+        
+        Some(Commands::Refs { action }) => {
+            refs::handle_refs(port, action, output_format)?;
+        }
+        
+        Some(Commands::Models { provider, validate, table }) => {
+            let format = if table && output_format == display::OutputFormat::Plain {
+                display::OutputFormat::Table
+            } else {
+                output_format
+            };
+            models::handle_models_with_format(port, provider, validate, format)?;
+        }
+        
+        Some(Commands::Providers { action }) => {
+            commands::providers::handle_providers(port, action, output_format)?;
+        }
+        
         Some(Commands::Status { detailed }) => {
-            if std::env::var("PORT42_DEBUG").is_ok() {
-                eprintln!("DEBUG: main() - handling Status command with port {}", port);
-            }
+            debug!("main() - handling Status command with port {}", port);
             let mut client = client::DaemonClient::new(port);
-            if std::env::var("PORT42_DEBUG").is_ok() {
-                eprintln!("DEBUG: main() - created new DaemonClient for Status command");
-            }
-            if cli.json {
-                status::handle_status_with_format(&mut client, detailed, display::OutputFormat::Json)?;
+            debug!("main() - created new DaemonClient for Status command");
+            if output_format != display::OutputFormat::Plain {
+                status::handle_status_with_format(&mut client, detailed, output_format)?;
             } else {
                 status::handle_status(port, detailed)?;
             }
@@ -376,7 +1859,7 @@ fn main() -> Result<()> {
             // Get version from build script or fallback
             let version = env!("PORT42_VERSION");
             
-            if cli.json {
+            if json {
                 let version_info = serde_json::json!({
                     "version": version,
                     "platform": std::env::consts::OS,
@@ -393,157 +1876,89 @@ fn main() -> Result<()> {
             }
         }
         
-        Some(Commands::Reality { verbose, agent }) => {
-            if cli.json {
-                reality::handle_reality_with_format(port, verbose, agent, display::OutputFormat::Json)?;
+        Some(Commands::Reality { verbose, agent, diff }) => {
+            if diff {
+                reality::handle_reality_diff(agent, output_format)?;
+            } else if output_format != display::OutputFormat::Plain {
+                reality::handle_reality_with_format(port, verbose, agent, output_format)?;
             } else {
                 reality::handle_reality(port, verbose, agent)?;
             }
         }
         
-        Some(Commands::Context { pretty, compact, watch, refresh, text }) => {
-            use crate::context::formatters::{ContextFormatter, JsonFormatter, PrettyFormatter, CompactFormatter};
+        Some(Commands::Context { action: Some(ContextAction::Run { number }), .. }) => {
+            use crate::context::suggestions;
+            
+            let mut client = crate::client::DaemonClient::new(port);
+            let context_data = suggestions::fetch(&mut client)?;
+            let suggestion = suggestions::pick(&context_data, number)?;
+            println!("{}", format!("▶ {}", suggestion.command).bright_cyan());
+            let code = suggestions::run(&mut client, suggestion)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        
+        Some(Commands::Context { pretty, compact, watch, refresh, text, tui, timeline, history, since, limit, action: None }) => {
+            use crate::context::formatters::{ContextFormatter, JsonFormatter, PrettyFormatter, CompactFormatter, TimelineFormatter};
+            use crate::context::HistoryQuery;
             
             let mut client = crate::client::DaemonClient::new(port);
             
             if watch {
-                // Check if user wants to force text mode
+                use crate::context::{formatters::run_text_watch, safe_tui};
+                
                 if text {
-                    // Force text mode - skip TUI entirely
-                    
-                    // Fallback to simple text-based watch
-                    use std::time::Duration;
-                    use std::thread;
-                    use crate::context::formatters::{ContextFormatter, PrettyFormatter};
-                    
-                    let formatter = PrettyFormatter;
-                    let refresh_duration = Duration::from_millis(refresh);
-                    let mut fallback_client = crate::client::DaemonClient::new(port);
-                    
-                    println!("🔍 Port42 Context Monitor (text mode) - Press Ctrl+C to stop");
-                    println!("Refresh rate: {}ms\n", refresh);
-                    
-                    loop {
-                        // Clear screen and move to top
-                        print!("\x1B[2J\x1B[H");
-                        
-                        let response = fallback_client.request(crate::protocol::DaemonRequest {
-                            request_type: "context".to_string(),
-                            id: format!("watch-{}", std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap()
-                                .as_millis()),
-                            payload: serde_json::json!({}),
-                            references: None,
-                            session_context: None,
-                            user_prompt: None,
-                        });
-                        
-                        match response {
-                            Ok(response) if response.success => {
-                                if let Some(data) = response.data {
-                                    if let Ok(context_data) = serde_json::from_value::<crate::context::ContextData>(data) {
-                                        println!("🕒 Last updated: {}", chrono::Local::now().format("%H:%M:%S"));
-                                        println!("{}", formatter.format(&context_data));
-                                    }
-                                }
-                            }
-                            Ok(response) => {
-                                println!("❌ Error: {}", response.error.unwrap_or_else(|| "Unknown error".to_string()));
-                            }
-                            Err(e) => {
-                                println!("❌ Connection error: {}", e);
-                            }
-                        }
-                        
-                        thread::sleep(refresh_duration);
-                    }
-                } else {
-                    // Try TUI mode first, fallback to text if it fails
-                    use crate::context::safe_tui;
-                    
-                    // refresh is already in milliseconds, use directly
-                    let refresh_ms = refresh;
-                    
-                    if let Err(e) = safe_tui::run_safe_watch(client, refresh_ms) {
-                        eprintln!("⚠️  TUI mode not available ({}), using text mode...", e);
-                        
-                        // Fallback to simple text-based watch
-                        use std::time::Duration;
-                        use std::thread;
-                        use crate::context::formatters::{ContextFormatter, PrettyFormatter};
-                        
-                        let formatter = PrettyFormatter;
-                        let refresh_duration = Duration::from_millis(refresh);
-                        let mut fallback_client = crate::client::DaemonClient::new(port);
-                        
-                        println!("🔍 Port42 Context Monitor (text mode) - Press Ctrl+C to stop");
-                        println!("Refresh rate: {}ms\n", refresh);
-                        
-                        loop {
-                            // Clear screen and move to top (flush immediately for macOS compatibility)
-                            print!("\x1B[2J\x1B[H");
-                            std::io::stdout().flush().unwrap_or(());
-                            
-                            let response = fallback_client.request(crate::protocol::DaemonRequest {
-                                request_type: "context".to_string(),
-                                id: format!("watch-{}", std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_millis()),
-                                payload: serde_json::json!({}),
-                                references: None,
-                                session_context: None,
-                                user_prompt: None,
-                            });
-                            
-                            match response {
-                                Ok(response) if response.success => {
-                                    if let Some(data) = response.data {
-                                        if let Ok(context_data) = serde_json::from_value::<crate::context::ContextData>(data) {
-                                            println!("🕒 Last updated: {}", chrono::Local::now().format("%H:%M:%S"));
-                                            println!("{}", formatter.format(&context_data));
-                                        }
-                                    }
-                                }
-                                Ok(response) => {
-                                    println!("❌ Error: {}", response.error.unwrap_or_else(|| "Unknown error".to_string()));
-                                }
-                                Err(e) => {
-                                    println!("❌ Connection error: {}", e);
-                                }
-                            }
-                            
-                            thread::sleep(refresh_duration);
-                        }
+                    run_text_watch(client, refresh)?;
+                } else if let Err(e) = safe_tui::run_safe_watch(client, refresh) {
+                    // Asked for by name, the TUI doesn't quietly become text
+                    if tui {
+                        return Err(e).context("Cannot start the context TUI");
                     }
+                    eprintln!("⚠️  TUI mode not available ({}), using text mode...", e);
+                    run_text_watch(crate::client::DaemonClient::new(port), refresh)?;
                 }
             } else {
-                // Single shot mode
+                // Single shot mode, or a window of history
+                let query = if history {
+                    Some(HistoryQuery { since: crate::common::utils::parse_since(&since)?, limit })
+                } else {
+                    None
+                };
                 let response = client.request(crate::protocol::DaemonRequest {
                     request_type: "context".to_string(),
                     id: format!("context-{}", std::time::SystemTime::now()
                         .duration_since(std::time::UNIX_EPOCH)
                         .unwrap()
                         .as_millis()),
-                    payload: serde_json::json!({}),
+                    payload: HistoryQuery::payload(query.as_ref()),
                     references: None,
                     session_context: None,
                     user_prompt: None,
+                    provider: None,
                 })?;
                 
                 if !response.success {
-                    eprintln!("❌ Failed to get context: {}", 
-                        response.error.unwrap_or_else(|| "Unknown error".to_string()));
-                    std::process::exit(1);
+                    let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+                    return Err(errors::Port42Error::from_daemon(&error)).context("Failed to get context");
                 }
                 
                 if let Some(data) = response.data {
+                    // Say so when the window is only cut from the current snapshot
+                    if query.is_some() && !HistoryQuery::answered(&data) {
+                        eprintln!("{}", crate::help_text::format_history_unsupported(&since).yellow());
+                    }
+                    
                     // Parse into typed structure
-                    let context_data: crate::context::ContextData = serde_json::from_value(data)?;
+                    let mut context_data = serde_json::from_value::<crate::context::ContextData>(data)?.with_local_activity();
+                    if let Some(ref query) = query {
+                        context_data = context_data.within(query);
+                    }
                     
                     // Choose formatter based on flags
-                    let formatter: Box<dyn ContextFormatter> = if compact {
+                    let formatter: Box<dyn ContextFormatter> = if timeline {
+                        Box::new(TimelineFormatter)
+                    } else if compact {
                         Box::new(CompactFormatter)
                     } else if pretty {
                         Box::new(PrettyFormatter)
@@ -557,7 +1972,17 @@ fn main() -> Result<()> {
             }
         }
         
-        Some(Commands::Swim { agent, session, references, message }) => {
+        Some(Commands::Swim { agent, session, references, options, mut message }) => {
+            // With a default agent configured, `swim "hello"` talks to it;
+            // `swim ai-engineer "hello"` still names its agent
+            let agent = match config.default_agent {
+                Some(ref default) if !agent.starts_with('@') && !agents::AgentRegistry::load_or_default().is_known(&agent) => {
+                    message.insert(0, agent);
+                    agents::normalize_agent_name(default)
+                }
+                _ => agent,
+            };
+            
             // Simple: session is explicit, message is always the args
             let message_text = if message.is_empty() { 
                 None 
@@ -576,8 +2001,7 @@ fn main() -> Result<()> {
                             Some(id)
                         },
                         Err(_) => {
-                            eprintln!("❌ No previous sessions found for {}", agent);
-                            std::process::exit(1);
+                            return Err(errors::Port42Error::NotFound(format!("No previous sessions found for {}", agent)).into());
                         }
                     }
                 },
@@ -585,39 +2009,58 @@ fn main() -> Result<()> {
                 None => None,
             };
             
-            if std::env::var("PORT42_DEBUG").is_ok() {
-                eprintln!("DEBUG swim: agent={}, session={:?}, message={:?}", 
-                         agent, session_id, message_text);
-            }
+            debug!("swim: agent={}, session={:?}, message={:?}", agent, session_id, message_text);
             
             // Auto-detect output mode: show boot only for interactive mode (no message)
             let show_boot = message_text.is_none();
-            commands::swim::handle_swim_with_references(port, agent, message_text, session_id, references, show_boot)?;
+            commands::swim::handle_swim_with_references(port, agent, message_text, session_id, references, options, show_boot)?;
         }
         
         Some(Commands::Declare { command }) => {
             match command {
-                DeclareCommand::Tool { name, transforms, references, prompt } => {
+                DeclareCommand::Tool { name, transforms, references, prompt, agent, options } => {
                     let transforms_vec = transforms.as_ref()
                         .map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
                         .unwrap_or_default();
                     
-                    commands::declare::handle_declare_tool(port, &name, transforms_vec, references.clone(), prompt.clone())?;
+                    commands::declare::handle_declare_tool(port, &name, transforms_vec, references.clone(), prompt.clone(), agent, options)?;
+                }
+                DeclareCommand::Batch { file, dry_run, max_concurrent, provider } => {
+                    commands::declare::handle_declare_batch(port, &file, dry_run, max_concurrent.into(), provider)?;
+                }
+                DeclareCommand::Artifact { name, artifact_type, file_type, prompt, options } => {
+                    commands::declare::handle_declare_artifact(port, &name, &artifact_type, &file_type, prompt.clone(), options)?;
                 }
-                DeclareCommand::Artifact { name, artifact_type, file_type, prompt } => {
-                    commands::declare::handle_declare_artifact(port, &name, &artifact_type, &file_type, prompt.clone())?;
+            }
+        }
+        
+        Some(Commands::Memory { args, tui: true, .. }) => {
+            use crate::context::memory_tui::{self, BrowserExit};
+            match memory_tui::run_memory_browser(client::DaemonClient::new(port), args.join(" ")) {
+                Ok(BrowserExit::Resume { session_id, agent }) => {
+                    commands::swim::handle_swim_no_boot(port, agent, None, Some(session_id))?;
+                }
+                Ok(BrowserExit::Quit) => {}
+                Err(e) => {
+                    eprintln!("⚠️  TUI mode not available ({}), listing instead...", e);
+                    memory::handle_memory_with_format(port, None, output_format)?;
                 }
             }
         }
         
-        Some(Commands::Memory { args }) => {
+        Some(Commands::Memory { args, copy, at, name, interleave, .. }) => {
+            if at.is_some() && args.first().map(String::as_str) != Some("fork") {
+                return Err(errors::Port42Error::Usage(help_text::ERR_MEMORY_FORK_USAGE.to_string()).into());
+            }
+            if (name.is_some() || interleave) && args.first().map(String::as_str) != Some("merge") {
+                return Err(errors::Port42Error::Usage(help_text::ERR_MEMORY_MERGE_USAGE.to_string()).into());
+            }
             // Parse memory args similar to shell
             let action = if args.is_empty() {
                 None // List all
             } else if args[0] == "search" {
                 if args.len() < 2 {
-                    eprintln!("{}", help_text::ERR_MEMORY_SEARCH_USAGE.red());
-                    std::process::exit(1);
+                    return Err(errors::Port42Error::Usage(help_text::ERR_MEMORY_SEARCH_USAGE.to_string()).into());
                 }
                 Some(MemoryAction::Search {
                     query: args[1..].join(" "),
@@ -625,13 +2068,27 @@ fn main() -> Result<()> {
                 })
             } else if args[0] == "rename" {
                 if args.len() < 3 {
-                    eprintln!("{}", "Usage: memory rename <session_id> <new_name>".red());
-                    std::process::exit(1);
+                    return Err(errors::Port42Error::Usage("Usage: memory rename <session_id> <new_name>".to_string()).into());
                 }
                 Some(MemoryAction::Rename {
                     session_id: args[1].clone(),
                     new_name: args[2..].join(" "),
                 })
+            } else if args[0] == "fork" {
+                let mut fork_args = args[1..].to_vec();
+                if let Some(at) = at {
+                    fork_args.extend(["--at".to_string(), at.to_string()]);
+                }
+                Some(memory::parse_fork(&fork_args)?)
+            } else if args[0] == "merge" {
+                let mut merge_args = args[1..].to_vec();
+                if let Some(name) = name {
+                    merge_args.extend(["--name".to_string(), name]);
+                }
+                if interleave {
+                    merge_args.push("--interleave".to_string());
+                }
+                Some(memory::parse_merge(&merge_args)?)
             } else {
                 // First arg is session ID
                 Some(MemoryAction::Show {
@@ -639,10 +2096,13 @@ fn main() -> Result<()> {
                 })
             };
             
-            if cli.json {
-                memory::handle_memory_with_format(port, action, display::OutputFormat::Json)?;
-            } else {
-                memory::handle_memory(port, action)?;
+            let format = output_format;
+            let transcript = memory::handle_memory_with_format(port, action, format)?;
+            if copy {
+                match transcript {
+                    Some(transcript) => common::clipboard::copy_and_report(&transcript, "transcript"),
+                    None => eprintln!("{}", "Nothing to copy: --copy needs a session ID".yellow()),
+                }
             }
         }
         
@@ -653,36 +2113,100 @@ fn main() -> Result<()> {
 
         Some(Commands::Ls { path }) => {
             let mut client = client::DaemonClient::new(port);
-            if cli.json {
-                ls::handle_ls_with_format(&mut client, path, display::OutputFormat::Json)?;
+            if output_format != display::OutputFormat::Plain {
+                ls::handle_ls_with_format(&mut client, path, output_format)?;
             } else {
                 ls::handle_ls(&mut client, path)?;
             }
         }
         
-        Some(Commands::Cat { path }) => {
+        Some(Commands::Tree { path, level, types, size }) => {
             let mut client = client::DaemonClient::new(port);
-            if cli.json {
-                cat::handle_cat_with_format(&mut client, path, display::OutputFormat::Json)?;
-            } else {
-                cat::handle_cat(&mut client, path)?;
+            let options = tree::TreeOptions { level, types, sizes: size };
+            tree::handle_tree(&mut client, path, &options, output_format)?;
+        }
+        
+        Some(Commands::Cat { path, copy, plain, version }) => {
+            let mut client = client::DaemonClient::new(port);
+            let format = output_format;
+            let content = cat::handle_cat_with_format(&mut client, path, version, format, plain)?;
+            if copy {
+                common::clipboard::copy_and_report(&content, "content");
             }
         }
         
-        Some(Commands::Info { path }) => {
+        Some(Commands::Info { path, version }) => {
             let mut client = client::DaemonClient::new(port);
-            if cli.json {
-                info::handle_info_with_format(&mut client, path, display::OutputFormat::Json)?;
-            } else {
-                info::handle_info(&mut client, path)?;
+            info::handle_info_with_format(&mut client, path, version, output_format)?;
+        }
+        
+        Some(Commands::History { path }) => {
+            let mut client = client::DaemonClient::new(port);
+            history::handle_history(&mut client, path, output_format)?;
+        }
+        
+        Some(Commands::Rollback { path, version, yes }) => {
+            let mut client = client::DaemonClient::new(port);
+            rollback::handle_rollback(&mut client, path, version, yes, output_format)?;
+        }
+        
+        Some(Commands::Evolve { command, prompt, references, agent, provider, dry_run }) => {
+            evolve::handle_evolve(port, evolve::EvolveArgs { command, prompt, references, agent, provider, dry_run })?;
+        }
+        
+        Some(Commands::Update { path, from, yes }) => {
+            let mut client = client::DaemonClient::new(port);
+            update::handle_update(&mut client, path, &from, yes, output_format)?;
+        }
+        
+        Some(Commands::Run { name, args }) => {
+            let mut client = client::DaemonClient::new(port);
+            let code = run::handle_run(&mut client, name, args, cli.verbose)?;
+            if code != 0 {
+                std::process::exit(code);
             }
         }
         
-        Some(Commands::Search { query, all, any: _, exact, path, type_filter, after, before, agent, tags, limit }) => {
+        Some(Commands::Note { path, text, list }) => {
+            let mut client = client::DaemonClient::new(port);
+            let text = if list { None } else { Some(text.join(" ")) };
+            note::handle_note(&mut client, path, text, output_format)?;
+        }
+        
+        Some(Commands::Diff { left, right, context }) => {
+            let mut client = client::DaemonClient::new(port);
+            diff::handle_diff(&mut client, left, right, context, output_format)?;
+        }
+        
+        Some(Commands::Cp { source, destination, force }) => {
+            let mut client = client::DaemonClient::new(port);
+            let format = output_format;
+            cp::handle_cp_with_format(&mut client, source, destination, force, format)?;
+        }
+        
+        Some(Commands::Mv { source, destination, force }) => {
+            let mut client = client::DaemonClient::new(port);
+            let format = output_format;
+            mv::handle_mv_with_format(&mut client, source, destination, force, format)?;
+        }
+        
+        Some(Commands::Rm { paths, force, yes }) => {
+            let mut client = client::DaemonClient::new(port);
+            rm::handle_rm(&mut client, paths, force, yes, output_format)?;
+        }
+        
+        Some(Commands::Restore { path, to }) => {
+            let mut client = client::DaemonClient::new(port);
+            restore::handle_restore(&mut client, path, to, output_format)?;
+        }
+        
+        Some(Commands::Search { query, all, any: _, exact, semantic, path, type_filter, after, before, agent, tags, limit, copy }) => {
             let mut client = client::DaemonClient::new(port);
             
             // Determine search mode
-            let mode = if all {
+            let mode = if semantic {
+                "semantic"
+            } else if all {
                 "and"
             } else if exact {
                 "phrase"
@@ -690,21 +2214,42 @@ fn main() -> Result<()> {
                 "or"  // default, also covers explicit --any
             };
             
-            if cli.json {
-                search::handle_search_with_format(&mut client, query, mode, path, type_filter, after, before, agent, tags, limit, display::OutputFormat::Json)?;
-            } else {
-                search::handle_search(&mut client, query, mode, path, type_filter, after, before, agent, tags, limit)?;
+            let format = output_format;
+            let top = search::handle_search_with_format(&mut client, query, mode, path, type_filter, after, before, agent, tags, limit, format)?;
+            if copy {
+                match top {
+                    Some(top) => common::clipboard::copy_and_report(&top, "top result path"),
+                    None => eprintln!("{}", "Nothing to copy: no results".yellow()),
+                }
             }
         }
         
-        Some(Commands::Watch { target }) => {
+        Some(Commands::Rules { action }) => {
+            rules::handle_rules(port, action, output_format)?;
+        }
+        
+        Some(Commands::Events { action }) => {
+            events::handle_events(port, action, json)?;
+        }
+        
+        Some(Commands::Replay { dir, serve, types, yes }) => {
+            replay::handle_replay(port, &dir, serve, &types, yes, json)?;
+        }
+        
+        Some(Commands::Upgrade { check, to, force, finish }) => {
+            upgrade::handle_upgrade(check, to, force, finish)?;
+        }
+        
+        Some(Commands::Watch { target, refresh }) => {
             match target.as_str() {
                 "rules" => {
                     commands::watch::watch_rules(port)?;
                 }
+                "sessions" => {
+                    commands::watch::watch_sessions(port, refresh)?;
+                }
                 _ => {
-                    eprintln!("❌ Unsupported watch target: {}. Supported: rules", target);
-                    std::process::exit(1);
+                    return Err(errors::Port42Error::Usage(format!("Unsupported watch target: {}. Supported: rules, sessions", target)).into());
                 }
             }
         }
@@ -750,4 +2295,74 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_possess_alias_with_provider() {
+        let result = Cli::try_parse_from(["port42", "possess", "@ai-analyst", "--provider", "google", "--model", "gemini-1.5-pro", "hello"]);
+        assert!(result.is_ok());
+        
+        if let Ok(cli) = result {
+            match cli.command {
+                Some(Commands::Swim { agent, options, message, .. }) => {
+                    assert_eq!(agent, "@ai-analyst");
+                    assert_eq!(options.provider.provider.as_deref(), Some("google"));
+                    assert_eq!(options.provider.model.as_deref(), Some("gemini-1.5-pro"));
+                    assert_eq!(message, vec!["hello"]);
+                }
+                _ => panic!("Expected Swim command"),
+            }
+        }
+    }
+    
+    #[test]
+    fn test_global_output_flag() {
+        let cli = Cli::try_parse_from(["port42", "ls", "/commands", "--output", "yaml"]).unwrap();
+        assert_eq!(cli.output, Some(display::OutputFormat::Yaml));
+        
+        assert!(Cli::try_parse_from(["port42", "--json", "--output", "table", "ls"]).is_err());
+        assert!(Cli::try_parse_from(["port42", "--output", "xml", "ls"]).is_err());
+    }
+    
+    #[test]
+    fn test_color_flag() {
+        let cli = Cli::try_parse_from(["port42", "status", "--color", "never"]).unwrap();
+        assert_eq!(cli.color, Some(display::ColorMode::Never));
+        assert!(Cli::try_parse_from(["port42", "--color", "sometimes", "status"]).is_err());
+        
+        let args = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        assert_eq!(color_flag(&args(&["port42", "--color=always", "ls"])), Some(display::ColorMode::Always));
+        assert_eq!(color_flag(&args(&["port42", "ls", "--color", "never"])), Some(display::ColorMode::Never));
+        assert_eq!(color_flag(&args(&["port42", "ls", "--", "--color", "never"])), None);
+    }
+    
+    #[test]
+    fn test_hidden_complete_command() {
+        let cli = Cli::try_parse_from(["port42", "__complete", "path", "/comm"]).unwrap();
+        match cli.command {
+            Some(Commands::Complete { kind, current }) => {
+                assert_eq!(kind, commands::completions::CompletionKind::Path);
+                assert_eq!(current, "/comm");
+            }
+            _ => panic!("Expected Complete command"),
+        }
+        // Nothing typed yet, or a flag-like word, still parses
+        assert!(Cli::try_parse_from(["port42", "__complete", "agent"]).is_ok());
+        assert!(Cli::try_parse_from(["port42", "__complete", "path", "-x"]).is_ok());
+        
+        let help = Cli::command().render_help().to_string();
+        assert!(!help.contains("__complete"));
+    }
+    
+    #[test]
+    fn test_reference_carries_prose_help() {
+        colored::control::set_override(false);
+        let reference = help_handler::reference_command();
+        for topic in help_text::HELP_TOPICS {
+            let sub = reference.find_subcommand(topic).expect("every prose topic is a command");
+            assert_eq!(sub.get_long_about().map(|s| s.to_string()), help_text::get_command_help(topic));
+        }
+        // Commands without prose keep their clap description
+        assert_eq!(reference.find_subcommand("diff").unwrap().get_about().map(|s| s.to_string()).as_deref(), Some(help_text::DIFF_DESC));
+        assert!(Cli::try_parse_from(["port42", "manpages", "--out-dir", "/tmp/man"]).is_ok());
+    }
+}
@@ -0,0 +1,170 @@
+//! Project-scoped settings created by `port42 init`
+//!
+//! A project is any directory tree with a `.port42/project.toml` at its root.
+//! Commands run anywhere inside it pick up the project's default references,
+//! agent preferences and context notes (`.port42/context.md`) on top of the
+//! user's `~/.port42` configuration.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{port42_dir, AgentDefaults};
+
+pub const PROJECT_DIR: &str = ".port42";
+const PROJECT_FILE: &str = "project.toml";
+const NOTES_FILE: &str = "context.md";
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    /// Project name, used to tag everything created inside the project
+    pub name: String,
+
+    /// References loaded into every swim and tool declaration
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub refs: Vec<String>,
+
+    /// Provider override for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+
+    /// Model override for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Per-agent overrides, merged over the user's [agents] table
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub agents: BTreeMap<String, AgentDefaults>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Project {
+    /// Directory containing `.port42/`
+    pub root: PathBuf,
+    pub config: ProjectConfig,
+    /// Contents of `.port42/context.md`, if any
+    pub notes: Option<String>,
+}
+
+impl Project {
+    /// Find the project containing the current directory
+    pub fn discover() -> Option<Self> {
+        let cwd = std::env::current_dir().ok()?;
+        match Self::discover_from(&cwd) {
+            Ok(project) => project,
+            Err(e) => {
+                eprintln!("⚠️  {:#}", e);
+                None
+            }
+        }
+    }
+
+    /// Walk up from `start` looking for `.port42/project.toml`
+    pub fn discover_from(start: &Path) -> Result<Option<Self>> {
+        let global = port42_dir();
+        for dir in start.ancestors() {
+            let project_dir = dir.join(PROJECT_DIR);
+            // ~/.port42 holds user state, never a project
+            if project_dir == global {
+                continue;
+            }
+            if project_dir.join(PROJECT_FILE).is_file() {
+                return Self::load(dir).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(PROJECT_DIR).join(PROJECT_FILE);
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut config: ProjectConfig = toml::from_str(&content)
+            .with_context(|| format!("Invalid project configuration in {}", path.display()))?;
+        if config.name.trim().is_empty() {
+            config.name = default_name(root);
+        }
+        let notes = fs::read_to_string(root.join(PROJECT_DIR).join(NOTES_FILE)).ok()
+            .filter(|notes| !notes.trim().is_empty());
+        Ok(Self { root: root.to_path_buf(), config, notes })
+    }
+
+    /// Create `.port42/` in `root` with a starter project.toml and context.md
+    pub fn init(root: &Path, name: Option<String>, force: bool) -> Result<Self> {
+        let dir = root.join(PROJECT_DIR);
+        if dir == port42_dir() {
+            bail!("{} is your Port 42 home; run init inside a project directory", dir.display());
+        }
+        let path = dir.join(PROJECT_FILE);
+        if path.exists() && !force {
+            bail!("{} already exists. Use --force to overwrite it", path.display());
+        }
+
+        let name = name.unwrap_or_else(|| default_name(root));
+        fs::create_dir_all(&dir)?;
+        fs::write(&path, starter_config(&name))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        let notes = dir.join(NOTES_FILE);
+        if !notes.exists() {
+            fs::write(&notes, "")?;
+        }
+        Self::load(root)
+    }
+
+    /// Project references with `file:` paths made absolute, so they resolve
+    /// the same from any subdirectory
+    pub fn references(&self) -> Vec<String> {
+        self.config.refs.iter()
+            .map(|reference| match reference.strip_prefix("file:") {
+                Some(path) if Path::new(path).is_relative() => {
+                    format!("file:{}", self.root.join(path.trim_start_matches("./")).display())
+                }
+                _ => reference.clone(),
+            })
+            .collect()
+    }
+
+    /// Context notes framed for the agent
+    pub fn guidance(&self) -> Option<String> {
+        self.notes.as_ref().map(|notes| {
+            format!("You are working in the project '{}'. Project notes:\n{}", self.config.name, notes.trim())
+        })
+    }
+
+    /// An agent's own persona and guidance followed by the project's notes
+    pub fn guidance_with(project: Option<&Project>, instructions: Option<String>) -> Option<String> {
+        match (project.and_then(Project::guidance), instructions) {
+            (Some(notes), Some(own)) => Some(format!("{}\n\n{}", own, notes)),
+            (notes, own) => own.or(notes),
+        }
+    }
+}
+
+fn default_name(root: &Path) -> String {
+    root.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "project".to_string())
+}
+
+fn starter_config(name: &str) -> String {
+    format!(
+        r#"# Port 42 project settings, merged over ~/.port42/config.toml
+# for every command run inside this directory tree.
+
+name = "{}"
+
+# References loaded into every swim and tool declaration
+# refs = ["file:./README.md", "p42:/commands/analyzer"]
+
+# provider = "anthropic"
+# model = "claude-3-7-sonnet"
+
+# [agents."@ai-engineer"]
+# model = "claude-3-opus"
+"#,
+        name.replace('"', "\\\"")
+    )
+}
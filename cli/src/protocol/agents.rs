@@ -0,0 +1,215 @@
+use super::{DaemonRequest, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat, components::TableBuilder, print_yaml};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use colored::*;
+
+/// Tell the daemon about a locally defined agent
+#[derive(Debug, Serialize)]
+pub struct RegisterAgentRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub persona: Option<String>,
+    pub guidance: Option<String>,
+    pub default_refs: Vec<String>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+}
+
+impl RequestBuilder for RegisterAgentRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "register_agent".to_string(),
+            id,
+            payload: serde_json::to_value(self)?,
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnregisterAgentRequest {
+    pub name: String,
+}
+
+impl RequestBuilder for UnregisterAgentRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "unregister_agent".to_string(),
+            id,
+            payload: json!({ "name": self.name }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListAgentsRequest;
+
+impl RequestBuilder for ListAgentsRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "list_agents".to_string(),
+            id,
+            payload: serde_json::Value::Null,
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Agents the daemon's registry knows about
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListAgentsResponse {
+    pub agents: Vec<RegisteredAgent>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RegisteredAgent {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl ResponseParser for ListAgentsResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        let agents = data.get("agents")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|a| {
+                        // Older daemons list bare names
+                        if let Some(name) = a.as_str() {
+                            return Some(RegisteredAgent { name: name.to_string(), description: None });
+                        }
+                        serde_json::from_value(a.clone()).ok()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(ListAgentsResponse { agents })
+    }
+}
+
+/// One row of `port42 agents list`
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentSummary {
+    pub name: String,
+    /// "built-in", "custom" or "daemon"
+    pub source: String,
+    pub description: Option<String>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+}
+
+impl AgentSummary {
+    fn model_label(&self) -> String {
+        match (&self.provider, &self.model) {
+            (Some(p), Some(m)) => format!("{}/{}", p, m),
+            (Some(p), None) => p.clone(),
+            (None, Some(m)) => m.clone(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct AgentList(pub Vec<AgentSummary>);
+
+impl Displayable for AgentList {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Table => {
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["Agent", "Source", "Model", "Description"]);
+                for agent in &self.0 {
+                    let model = agent.model_label();
+                    table.add_row(vec![
+                        agent.name.clone(),
+                        agent.source.clone(),
+                        if model.is_empty() { "-".to_string() } else { model },
+                        agent.description.clone().unwrap_or_else(|| "-".to_string()),
+                    ]);
+                }
+                table.print();
+            }
+            OutputFormat::Plain => {
+                println!("{}", "🤖 Consciousnesses".bright_blue().bold());
+                for agent in &self.0 {
+                    let source = match agent.source.as_str() {
+                        "built-in" => agent.source.dimmed(),
+                        "custom" => agent.source.green(),
+                        _ => agent.source.yellow(),
+                    };
+                    print!("  {:<18} {:<10}", agent.name.bright_cyan(), source);
+                    if let Some(ref description) = agent.description {
+                        print!(" {}", description);
+                    }
+                    let model = agent.model_label();
+                    if !model.is_empty() {
+                        print!(" {}", format!("({})", model).dimmed());
+                    }
+                    println!();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Everything `port42 agents show` knows about one agent
+#[derive(Debug, Serialize)]
+pub struct AgentDetail {
+    #[serde(flatten)]
+    pub summary: AgentSummary,
+    pub persona: Option<String>,
+    pub guidance: Option<String>,
+    pub default_refs: Vec<String>,
+}
+
+impl Displayable for AgentDetail {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Plain | OutputFormat::Table => {
+                let s = &self.summary;
+                println!("{} {}", "🤖".bright_blue(), s.name.bright_cyan().bold());
+                println!("  {}: {}", "Source".bright_cyan(), s.source);
+                if let Some(ref description) = s.description {
+                    println!("  {}: {}", "Description".bright_cyan(), description);
+                }
+                let model = s.model_label();
+                if !model.is_empty() {
+                    println!("  {}: {}", "Model".bright_cyan(), model);
+                }
+                for ref_str in &self.default_refs {
+                    println!("  {}: {}", "Reference".bright_cyan(), ref_str);
+                }
+                for (label, text) in [("Persona", &self.persona), ("Guidance", &self.guidance)] {
+                    if let Some(text) = text {
+                        println!("\n{}", label.bright_yellow());
+                        for line in text.lines() {
+                            println!("  {}", line);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
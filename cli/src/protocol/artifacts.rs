@@ -0,0 +1,134 @@
+use super::ResponseParser;
+use super::file_ops::{FileMetadata, format_size};
+use crate::display::{Displayable, OutputFormat, components::TableBuilder, print_yaml};
+use anyhow::Result;
+use base64::{Engine as _, engine::general_purpose};
+use serde::Serialize;
+use colored::*;
+
+/// Where artifacts live in the VFS
+pub const ARTIFACTS_ROOT: &str = "/artifacts";
+
+/// `docs/readme.md` and `readme.md` name artifacts under /artifacts; a full
+/// path is taken as given
+pub fn artifact_path(name: &str) -> String {
+    if name.starts_with('/') {
+        name.to_string()
+    } else {
+        format!("{}/{}", ARTIFACTS_ROOT, name.trim_start_matches("./"))
+    }
+}
+
+/// What kind of file a path holds, from its extension
+pub fn file_type(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => ext.to_ascii_lowercase(),
+        _ => "-".to_string(),
+    }
+}
+
+/// One artifact found under /artifacts
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactEntry {
+    pub path: String,
+    /// The folder directly under /artifacts, e.g. "docs" or "transcripts"
+    pub collection: String,
+    #[serde(rename = "type")]
+    pub file_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+}
+
+impl ArtifactEntry {
+    pub fn new(path: String, size: Option<i64>) -> Self {
+        let relative = path.strip_prefix(ARTIFACTS_ROOT).unwrap_or(&path).trim_start_matches('/');
+        let collection = match relative.split_once('/') {
+            Some((folder, _)) => folder.to_string(),
+            None => "-".to_string(),
+        };
+        let file_type = file_type(&path);
+        ArtifactEntry { path, collection, file_type, size }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArtifactList {
+    pub artifacts: Vec<ArtifactEntry>,
+}
+
+impl Displayable for ArtifactList {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Table => {
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["Path", "Collection", "Type", "Size"]);
+                for artifact in &self.artifacts {
+                    table.add_row(vec![
+                        artifact.path.clone(),
+                        artifact.collection.clone(),
+                        artifact.file_type.clone(),
+                        artifact.size.map(format_size).unwrap_or_else(|| "-".to_string()),
+                    ]);
+                }
+                table.print();
+            }
+            OutputFormat::Plain => {
+                if self.artifacts.is_empty() {
+                    println!("{}", "No artifacts have crystallized here yet".dimmed());
+                    return Ok(());
+                }
+                println!("{}", format!("📦 {} artifact{}", self.artifacts.len(), if self.artifacts.len() == 1 { "" } else { "s" }).bright_blue().bold());
+                let mut collection = None;
+                for artifact in &self.artifacts {
+                    if collection != Some(&artifact.collection) {
+                        println!("\n  {}", artifact.collection.bright_white().bold());
+                        collection = Some(&artifact.collection);
+                    }
+                    let size = artifact.size.map(format_size).unwrap_or_default();
+                    println!("    {:<8} {:>7}  {}", artifact.file_type.bright_cyan(), size.dimmed(), artifact.path);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An artifact's bytes as stored, which need not be text
+#[derive(Debug)]
+pub struct ArtifactContent {
+    pub path: String,
+    pub bytes: Vec<u8>,
+    pub metadata: Option<FileMetadata>,
+}
+
+impl ResponseParser for ArtifactContent {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        let encoded = data["content"].as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing content field"))?;
+        Ok(ArtifactContent {
+            path: data.get("path").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            bytes: general_purpose::STANDARD.decode(encoded)?,
+            metadata: data.get("metadata").and_then(|m| serde_json::from_value(m.clone()).ok()),
+        })
+    }
+}
+
+impl ArtifactContent {
+    /// The content as text, or None for binary data
+    pub fn text(&self) -> Option<&str> {
+        if self.bytes[..self.bytes.len().min(8000)].contains(&0) {
+            return None;
+        }
+        std::str::from_utf8(&self.bytes).ok()
+    }
+
+    pub fn is_markdown(&self) -> bool {
+        matches!(file_type(&self.path).as_str(), "md" | "markdown")
+            || self.metadata.as_ref().is_some_and(|m| m.language.as_deref() == Some("markdown"))
+    }
+}
@@ -0,0 +1,57 @@
+use super::{DaemonRequest, ProviderSelection, RequestBuilder, ResponseParser};
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use serde_json::json;
+
+/// Ask the daemon to embed texts with the selected provider's embedding model
+#[derive(Debug, Serialize)]
+pub struct EmbedRequest {
+    pub texts: Vec<String>,
+    pub provider: Option<ProviderSelection>,
+}
+
+impl RequestBuilder for EmbedRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "embed".to_string(),
+            id,
+            payload: json!({
+                "texts": &self.texts
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: self.provider.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct EmbedResponse {
+    /// Embedding model that produced the vectors
+    pub model: Option<String>,
+    pub vectors: Vec<Vec<f32>>,
+}
+
+impl ResponseParser for EmbedResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        let vectors = data.get("vectors")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| anyhow!("Missing vectors array"))?
+            .iter()
+            .map(|vector| {
+                vector.as_array()
+                    .map(|values| values.iter().filter_map(|x| x.as_f64()).map(|x| x as f32).collect())
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let model = data.get("model")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
+        Ok(EmbedResponse { model, vectors })
+    }
+}
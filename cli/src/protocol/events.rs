@@ -0,0 +1,87 @@
+use super::{DaemonRequest, RequestBuilder};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Something that happened in the daemon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum EventKind {
+    SessionStarted,
+    /// A session got a new message, e.g. the AI finished replying
+    Message,
+    SessionEnded,
+    ToolCreated,
+    CommandRun,
+    MemoryAccessed,
+    /// A daemon rule ran its action
+    RuleFired,
+    /// An event this CLI doesn't know yet
+    #[serde(other)]
+    #[value(skip)]
+    Unknown,
+}
+
+impl EventKind {
+    pub const ALL: &'static [EventKind] = &[
+        EventKind::SessionStarted,
+        EventKind::Message,
+        EventKind::SessionEnded,
+        EventKind::ToolCreated,
+        EventKind::CommandRun,
+        EventKind::MemoryAccessed,
+        EventKind::RuleFired,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::SessionStarted => "session_started",
+            EventKind::Message => "message",
+            EventKind::SessionEnded => "session_ended",
+            EventKind::ToolCreated => "tool_created",
+            EventKind::CommandRun => "command_run",
+            EventKind::MemoryAccessed => "memory_accessed",
+            EventKind::RuleFired => "rule_fired",
+            EventKind::Unknown => "unknown",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|k| k.label() == label)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DaemonEvent {
+    #[serde(rename = "type")]
+    pub kind: EventKind,
+    pub timestamp: DateTime<Utc>,
+    /// What it is about: a session id, tool name, command line or VFS path
+    pub subject: String,
+    /// Whatever else the daemon knows about it
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub data: Value,
+}
+
+/// Ask the daemon to push events of these kinds (all of them when empty)
+/// over the connection; see `DaemonClient::subscribe`
+#[derive(Debug, Serialize)]
+pub struct SubscribeRequest {
+    pub types: Vec<EventKind>,
+}
+
+impl RequestBuilder for SubscribeRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "subscribe".to_string(),
+            id,
+            payload: json!({ "types": self.types }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
@@ -1,5 +1,5 @@
 use super::{DaemonRequest, RequestBuilder, ResponseParser};
-use crate::display::{Displayable, OutputFormat};
+use crate::display::{Displayable, OutputFormat, components::TableBuilder, highlight, print_yaml};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -7,10 +7,41 @@ use colored::*;
 use chrono::{DateTime, Local};
 use base64::{Engine as _, engine::general_purpose};
 
+/// `{"path": ..}`, plus the version when one was asked for
+fn versioned(path: &str, version: Option<u32>) -> serde_json::Value {
+    let mut payload = json!({ "path": path });
+    if let Some(version) = version {
+        payload["version"] = json!(version);
+    }
+    payload
+}
+
+/// The version a read was answered with, at the top or in its metadata
+fn answered_version(data: &serde_json::Value) -> Option<u32> {
+    data.get("version")
+        .or_else(|| data.pointer("/metadata/version"))
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u32::try_from(v).ok())
+}
+
+/// A daemon that keeps no versions ignores the field and answers with the
+/// current content, which mustn't pass for the version that was asked for
+pub fn check_version(path: &str, asked: Option<u32>, answered: Option<u32>) -> Result<()> {
+    match asked {
+        Some(n) if answered != Some(n) => Err(crate::common::errors::Port42Error::Incompatible(format!(
+            "Asked for version {} of '{}' but the daemon answered with {}; it may not keep versions",
+            n, path, answered.map(|v| format!("version {}", v)).unwrap_or_else(|| "no version".to_string())
+        )).into()),
+        _ => Ok(()),
+    }
+}
+
 // Cat request and response types
 #[derive(Debug, Serialize)]
 pub struct CatRequest {
     pub path: String,
+    /// A prior version from `history`; None reads the current one
+    pub version: Option<u32>,
 }
 
 impl RequestBuilder for CatRequest {
@@ -18,12 +49,11 @@ impl RequestBuilder for CatRequest {
         Ok(DaemonRequest {
             request_type: "read_path".to_string(),
             id,
-            payload: json!({
-                "path": &self.path
-            }),
+            payload: versioned(&self.path, self.version),
             references: None,
             session_context: None,
             user_prompt: None,
+            provider: None,
         })
     }
 }
@@ -33,6 +63,8 @@ pub struct CatResponse {
     pub path: String,
     pub content: String,
     pub metadata: Option<FileMetadata>,
+    /// Which version this is, when the daemon says
+    pub version: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -42,6 +74,9 @@ pub struct FileMetadata {
     pub description: Option<String>,
     pub created: Option<String>,
     pub agent: Option<String>,
+    /// Source language, when the daemon knows it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
 }
 
 impl ResponseParser for CatResponse {
@@ -68,6 +103,7 @@ impl ResponseParser for CatResponse {
             path,
             content,
             metadata,
+            version: answered_version(data),
         })
     }
 }
@@ -75,14 +111,18 @@ impl ResponseParser for CatResponse {
 impl Displayable for CatResponse {
     fn display(&self, format: OutputFormat) -> Result<()> {
         match format {
-            OutputFormat::Json => {
-                // Create a JSON representation with decoded content
+            OutputFormat::Json | OutputFormat::Yaml => {
+                // Create a structured representation with decoded content
                 let output = json!({
                     "path": &self.path,
                     "content": &self.content,
                     "metadata": &self.metadata,
                 });
-                println!("{}", serde_json::to_string_pretty(&output)?);
+                if format == OutputFormat::Yaml {
+                    print_yaml(&output)?;
+                } else {
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                }
             }
             OutputFormat::Plain | OutputFormat::Table => {
                 // Display based on content type
@@ -91,8 +131,8 @@ impl Displayable for CatResponse {
                     Some("session") | Some("memory") => self.display_memory(),
                     Some("document") => self.display_document(),
                     _ => {
-                        // Default: just print the content
-                        println!("{}", self.content);
+                        // Default: the content, highlighted when it looks like source
+                        println!("{}", self.highlighted());
                     }
                 }
             }
@@ -122,22 +162,18 @@ impl CatResponse {
             println!(); // Empty line
         }
         
-        // Display content with basic syntax highlighting
-        for line in self.content.lines() {
-            if line.starts_with('#') && !line.starts_with("#!") {
-                // Comments
-                println!("{}", line.dimmed());
-            } else if line.starts_with("#!/") {
-                // Shebang
-                println!("{}", line.yellow());
-            } else if line.trim().is_empty() {
-                println!();
-            } else {
-                // Check for common keywords
-                let highlighted = highlight_keywords(line);
-                println!("{}", highlighted);
-            }
-        }
+        println!("{}", self.highlighted());
+    }
+    
+    /// The content exactly as stored, for `cat --plain`
+    pub fn print_raw(&self) {
+        print!("{}", self.content);
+    }
+    
+    fn highlighted(&self) -> String {
+        let hint = self.metadata.as_ref().and_then(|m| m.language.as_deref());
+        highlight::highlight(&self.path, &self.content, hint)
+            .unwrap_or_else(|| self.content.clone())
     }
     
     fn display_memory(&self) {
@@ -191,7 +227,326 @@ impl CatResponse {
     fn display_document(&self) {
         println!("{}", self.path.bright_blue().bold());
         println!("{}", "─".repeat(50).dimmed());
-        println!("{}", self.content);
+        println!("{}", self.highlighted());
+    }
+}
+
+// Store/delete request types for writing into the VFS
+#[derive(Debug, Serialize)]
+pub struct StorePathRequest {
+    pub path: String,
+    pub content: String,
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl RequestBuilder for StorePathRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        let mut payload = json!({
+            "path": &self.path,
+            "content": general_purpose::STANDARD.encode(self.content.as_bytes()),
+        });
+        if let Some(ref metadata) = self.metadata {
+            payload["metadata"] = metadata.clone();
+        }
+        
+        Ok(DaemonRequest {
+            request_type: "store_path".to_string(),
+            id,
+            payload,
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Replace an existing object's content; the daemon keeps its metadata and
+/// relations and records the new content as another version
+#[derive(Debug, Serialize)]
+pub struct UpdatePathRequest {
+    pub path: String,
+    pub content: Vec<u8>,
+}
+
+impl RequestBuilder for UpdatePathRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "update_path".to_string(),
+            id,
+            payload: json!({
+                "path": &self.path,
+                "content": general_purpose::STANDARD.encode(&self.content),
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdatePathResponse {
+    #[serde(default)]
+    pub path: String,
+    /// ID of the object now holding the content
+    #[serde(default)]
+    pub id: String,
+    /// Every path the object is reachable by
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub size: usize,
+}
+
+impl ResponseParser for UpdatePathResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+impl Displayable for UpdatePathResponse {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Plain | OutputFormat::Table => {
+                println!("{}", format!("✏️  Updated {} ({})", self.path, format_size(self.size as i64)).green());
+                println!("{}", format!("See earlier versions with 'port42 history {}'", self.path).dimmed());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeletePathRequest {
+    pub path: String,
+}
+
+impl RequestBuilder for DeletePathRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "delete_path".to_string(),
+            id,
+            payload: json!({
+                "path": &self.path
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+// Copy/move requests for rearranging objects within the VFS
+#[derive(Debug, Serialize)]
+pub struct CopyPathRequest {
+    pub source: String,
+    pub destination: String,
+    pub overwrite: bool,
+}
+
+impl RequestBuilder for CopyPathRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "copy_path".to_string(),
+            id,
+            payload: json!({
+                "source": &self.source,
+                "destination": &self.destination,
+                "overwrite": self.overwrite
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MovePathRequest {
+    pub source: String,
+    pub destination: String,
+    pub overwrite: bool,
+}
+
+impl RequestBuilder for MovePathRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "move_path".to_string(),
+            id,
+            payload: json!({
+                "source": &self.source,
+                "destination": &self.destination,
+                "overwrite": self.overwrite
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Soft delete: the daemon moves the object under /trash
+#[derive(Debug, Serialize)]
+pub struct TrashPathRequest {
+    pub path: String,
+}
+
+impl RequestBuilder for TrashPathRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "trash_path".to_string(),
+            id,
+            payload: json!({
+                "path": &self.path
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Bring a trashed object back, to where it came from unless told otherwise
+#[derive(Debug, Serialize)]
+pub struct RestorePathRequest {
+    pub path: String,
+    pub destination: Option<String>,
+}
+
+impl RequestBuilder for RestorePathRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        let mut payload = json!({
+            "path": &self.path,
+        });
+        if let Some(ref destination) = self.destination {
+            payload["destination"] = json!(destination);
+        }
+
+        Ok(DaemonRequest {
+            request_type: "restore_path".to_string(),
+            id,
+            payload,
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Outcome of a copy, move, trash, restore or delete
+#[derive(Debug, Serialize)]
+pub struct TransferResponse {
+    pub operation: String,
+    pub source: String,
+    pub destination: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_id: Option<String>,
+}
+
+impl ResponseParser for TransferResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        let text = |key: &str| data.get(key).and_then(|v| v.as_str()).map(String::from);
+        Ok(TransferResponse {
+            operation: text("operation").unwrap_or_default(),
+            source: text("source").unwrap_or_default(),
+            destination: text("destination").unwrap_or_default(),
+            object_id: text("object_id"),
+        })
+    }
+}
+
+impl Displayable for TransferResponse {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(self)?);
+            }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
+            OutputFormat::Plain | OutputFormat::Table => {
+                let verb = match self.operation.as_str() {
+                    "move" => "Moved",
+                    "trash" => "Trashed",
+                    "restore" => "Restored",
+                    "delete" => "Deleted",
+                    _ => "Copied",
+                };
+                if self.destination.is_empty() {
+                    println!("{} {} {}", verb.red(), self.source.bright_white(), "permanently".dimmed());
+                } else {
+                    println!("{} {} {} {}", verb.green(), self.source.bright_white(), "→".dimmed(), self.destination.bright_white());
+                }
+                if let Some(ref id) = self.object_id {
+                    println!("  {} {}", "Object ID:".cyan(), id.dimmed());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Import requests for replaying archived objects into the VFS
+#[derive(Debug, Serialize)]
+pub struct ImportObjectRequest {
+    pub path: String,
+    pub kind: String,
+    pub content: Vec<u8>,
+    pub metadata: serde_json::Value,
+    /// skip, overwrite or rename
+    pub on_conflict: String,
+}
+
+impl RequestBuilder for ImportObjectRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "import_object".to_string(),
+            id,
+            payload: json!({
+                "path": &self.path,
+                "type": &self.kind,
+                "content": general_purpose::STANDARD.encode(&self.content),
+                "metadata": &self.metadata,
+                "on_conflict": &self.on_conflict
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportObjectResponse {
+    /// Where the object ended up, which differs from the request when renamed
+    pub path: String,
+    /// created, skipped, overwritten or renamed
+    pub status: String,
+}
+
+impl ResponseParser for ImportObjectResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        let text = |key: &str| data.get(key).and_then(|v| v.as_str()).map(String::from);
+        Ok(ImportObjectResponse {
+            path: text("path").unwrap_or_default(),
+            status: text("status").unwrap_or_else(|| "created".to_string()),
+        })
     }
 }
 
@@ -199,6 +554,8 @@ impl CatResponse {
 #[derive(Debug, Serialize)]
 pub struct InfoRequest {
     pub path: String,
+    /// A prior version from `history`; None reads the current one
+    pub version: Option<u32>,
 }
 
 impl RequestBuilder for InfoRequest {
@@ -206,12 +563,11 @@ impl RequestBuilder for InfoRequest {
         Ok(DaemonRequest {
             request_type: "get_metadata".to_string(),
             id,
-            payload: json!({
-                "path": &self.path
-            }),
+            payload: versioned(&self.path, self.version),
             references: None,
             session_context: None,
             user_prompt: None,
+            provider: None,
         })
     }
 }
@@ -223,6 +579,13 @@ pub struct InfoResponse {
     pub metadata: serde_json::Value,
 }
 
+impl InfoResponse {
+    /// Which version this describes, when the daemon says
+    pub fn version(&self) -> Option<u32> {
+        answered_version(&self.metadata)
+    }
+}
+
 impl ResponseParser for InfoResponse {
     type Output = Self;
     
@@ -246,6 +609,9 @@ impl Displayable for InfoResponse {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(&self.metadata)?);
             }
+            OutputFormat::Yaml => {
+                print_yaml(&self.metadata)?;
+            }
             OutputFormat::Plain | OutputFormat::Table => {
                 self.display_formatted()?;
             }
@@ -271,6 +637,10 @@ impl InfoResponse {
             println!("{} {}", "Object ID:".bright_blue().bold(), obj_id.dimmed());
         }
         
+        if let Some(version) = data["version"].as_u64() {
+            println!("{} {}", "Version:".bright_blue().bold(), version.to_string().bright_white());
+        }
+        
         println!("{}", "╚══════════════════════════════════════════════════════════════════╝".dimmed());
         
         // Metadata section
@@ -328,34 +698,299 @@ impl InfoResponse {
             }
         }
         
+        // Notes
+        let notes: Vec<Note> = serde_json::from_value(data["notes"].clone()).unwrap_or_default();
+        if !notes.is_empty() {
+            println!("\n{}", "Notes:".bright_green().bold());
+            for note in &notes {
+                note.print();
+            }
+        }
+        
         Ok(())
     }
 }
 
-// Helper functions
-fn highlight_keywords(line: &str) -> String {
-    // Simple keyword highlighting for common shell/programming constructs
-    let keywords = vec![
-        "if", "then", "else", "elif", "fi", "for", "while", "do", "done",
-        "function", "return", "echo", "export", "source", "alias",
-        "def", "class", "import", "from", "as", "try", "except", "finally",
-        "const", "let", "var", "async", "await", "require", "module",
-    ];
-    
-    let mut result = line.to_string();
-    for keyword in keywords {
-        let pattern = format!(r"\b{}\b", keyword);
-        if let Ok(re) = regex::Regex::new(&pattern) {
-            result = re.replace_all(&result, |caps: &regex::Captures| {
-                caps[0].bright_magenta().to_string()
-            }).to_string();
+// History: the versions an object has been through
+#[derive(Debug, Serialize)]
+pub struct HistoryRequest {
+    pub path: String,
+}
+
+impl RequestBuilder for HistoryRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "list_versions".to_string(),
+            id,
+            payload: json!({
+                "path": &self.path
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ObjectVersion {
+    pub version: u32,
+    /// RFC 3339
+    #[serde(default)]
+    pub created: Option<String>,
+    /// The session whose conversation produced this version
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default)]
+    pub size: Option<i64>,
+    /// Whether this is the version `cat` shows without --version
+    #[serde(default)]
+    pub current: bool,
+}
+
+impl ObjectVersion {
+    /// When the version was made, in local time; "-" when unknown
+    pub fn created_local(&self) -> String {
+        self.created.as_deref()
+            .and_then(|c| DateTime::parse_from_rfc3339(c).ok())
+            .map(|dt| DateTime::<Local>::from(dt).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct VersionHistory {
+    #[serde(default)]
+    pub path: String,
+    /// Newest first
+    #[serde(default)]
+    pub versions: Vec<ObjectVersion>,
+}
+
+impl ResponseParser for VersionHistory {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        let mut history: VersionHistory = serde_json::from_value(data.clone())?;
+        history.versions.sort_by_key(|v| std::cmp::Reverse(v.version));
+        Ok(history)
+    }
+}
+
+impl Displayable for VersionHistory {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Table => {
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["Version", "Created", "Session", "Agent", "Size"]);
+                for v in &self.versions {
+                    let version = if v.current { format!("{} (current)", v.version) } else { v.version.to_string() };
+                    table.add_row(vec![
+                        version,
+                        v.created_local(),
+                        v.session_id.clone().unwrap_or_else(|| "-".to_string()),
+                        v.agent.clone().unwrap_or_else(|| "-".to_string()),
+                        v.size.map(format_size).unwrap_or_else(|| "-".to_string()),
+                    ]);
+                }
+                table.print();
+            }
+            OutputFormat::Plain => {
+                if self.versions.is_empty() {
+                    println!("{}", format!("No earlier versions of {}", self.path).dimmed());
+                    return Ok(());
+                }
+                println!("{}", format!("🕰️  History of {}", self.path).bright_blue().bold());
+                for v in &self.versions {
+                    print!("  {} {}", format!("v{:<3}", v.version).bright_white().bold(), v.created_local().dimmed());
+                    if let Some(ref agent) = v.agent {
+                        print!("  {}", agent.bright_cyan());
+                    }
+                    if let Some(ref session) = v.session_id {
+                        print!("  {}", session.dimmed());
+                    }
+                    if v.current {
+                        print!("  {}", "← current".green());
+                    }
+                    println!();
+                }
+                println!();
+                println!("{}", format!("Read one with 'port42 cat {} --version N'", self.path).dimmed());
+            }
         }
+        Ok(())
     }
-    
-    result
 }
 
-fn format_size(bytes: i64) -> String {
+/// Make an earlier version current again; the daemon records it as a new version
+#[derive(Debug, Serialize)]
+pub struct RollbackRequest {
+    pub path: String,
+    pub version: u32,
+}
+
+impl RequestBuilder for RollbackRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "rollback_version".to_string(),
+            id,
+            payload: json!({
+                "path": &self.path,
+                "version": self.version
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RollbackResponse {
+    #[serde(default)]
+    pub path: String,
+    /// The version whose content was restored
+    pub restored_version: u32,
+    /// The version number the restored content now carries
+    #[serde(default)]
+    pub version: Option<u32>,
+}
+
+impl ResponseParser for RollbackResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+impl Displayable for RollbackResponse {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Plain | OutputFormat::Table => {
+                print!("{}", format!("⏪ Rolled {} back to v{}", self.path, self.restored_version).green());
+                if let Some(version) = self.version {
+                    print!(" {}", format!("(now v{})", version).dimmed());
+                }
+                println!();
+            }
+        }
+        Ok(())
+    }
+}
+
+// Notes: freeform annotations kept in an object's metadata
+#[derive(Debug, Serialize)]
+pub struct AddNoteRequest {
+    pub path: String,
+    pub text: String,
+}
+
+impl RequestBuilder for AddNoteRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "add_note".to_string(),
+            id,
+            payload: json!({
+                "path": &self.path,
+                "text": &self.text
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListNotesRequest {
+    pub path: String,
+}
+
+impl RequestBuilder for ListNotesRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "list_notes".to_string(),
+            id,
+            payload: json!({
+                "path": &self.path
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Note {
+    pub text: String,
+    /// RFC 3339
+    #[serde(default)]
+    pub created: Option<String>,
+}
+
+impl Note {
+    fn print(&self) {
+        let when = self.created.as_deref()
+            .and_then(|c| DateTime::parse_from_rfc3339(c).ok())
+            .map(|dt| DateTime::<Local>::from(dt).format("%Y-%m-%d %H:%M").to_string());
+        let mut lines = self.text.lines();
+        println!("  📝 {} {}", lines.next().unwrap_or_default(), when.map(|w| format!("({})", w)).unwrap_or_default().dimmed());
+        for line in lines {
+            println!("     {}", line);
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct NoteList {
+    #[serde(default)]
+    pub path: String,
+    #[serde(default)]
+    pub notes: Vec<Note>,
+}
+
+impl ResponseParser for NoteList {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+impl Displayable for NoteList {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Plain | OutputFormat::Table => {
+                if self.notes.is_empty() {
+                    println!("{}", format!("No notes on {}", self.path).dimmed());
+                    return Ok(());
+                }
+                println!("{}", format!("Notes on {}", self.path).bright_blue().bold());
+                for note in &self.notes {
+                    note.print();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Helper functions
+pub(crate) fn format_size(bytes: i64) -> String {
     const UNITS: &[&str] = &["B", "K", "M", "G", "T"];
     let mut size = bytes as f64;
     let mut unit_index = 0;
@@ -1,5 +1,5 @@
 use super::{DaemonRequest, RequestBuilder, ResponseParser};
-use crate::display::{Displayable, OutputFormat, components};
+use crate::display::{Displayable, OutputFormat, components, print_yaml};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -23,6 +23,7 @@ impl RequestBuilder for LsRequest {
             references: None,
             session_context: None,
             user_prompt: None,
+            provider: None,
         })
     }
 }
@@ -92,6 +93,9 @@ impl Displayable for LsResponse {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(self)?);
             }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
             OutputFormat::Table => {
                 // Display path header
                 if self.path != "/" {
@@ -189,6 +193,150 @@ impl Displayable for LsResponse {
     }
 }
 
+/// One entry of `port42 tree`, assembled client-side from list_path calls
+#[derive(Debug, Serialize)]
+pub struct TreeNode {
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub executable: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeNode>,
+    /// A directory left unopened because of the depth limit
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Why a directory could not be listed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl TreeNode {
+    pub fn is_dir(&self) -> bool {
+        self.entry_type == "directory"
+    }
+
+    /// Own size for files, everything known beneath for directories
+    pub fn total_size(&self) -> i64 {
+        if self.is_dir() {
+            self.children.iter().map(TreeNode::total_size).sum()
+        } else {
+            self.size.unwrap_or(0)
+        }
+    }
+
+    fn counts(&self) -> (usize, usize) {
+        self.children.iter().fold((0, 0), |(dirs, files), child| {
+            let (d, f) = child.counts();
+            if child.is_dir() { (dirs + d + 1, files + f) } else { (dirs + d, files + f + 1) }
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct VfsTree {
+    #[serde(flatten)]
+    pub root: TreeNode,
+    pub directories: usize,
+    pub files: usize,
+    /// Annotate entries with sizes in plain and table output
+    #[serde(skip)]
+    pub show_sizes: bool,
+}
+
+impl VfsTree {
+    pub fn new(root: TreeNode, show_sizes: bool) -> Self {
+        let (directories, files) = root.counts();
+        Self { root, directories, files, show_sizes }
+    }
+
+    fn print_children(&self, node: &TreeNode, prefix: &str) {
+        for (i, child) in node.children.iter().enumerate() {
+            let last = i + 1 == node.children.len();
+            let branch = if last { "└── " } else { "├── " };
+            let mut line = format!("{}{}", prefix, branch).bright_black().to_string();
+            if self.show_sizes {
+                // Nothing is known beneath a directory the depth limit closed
+                let size = if child.truncated { format!("{:>5}", "-") } else { format_size(child.total_size()) };
+                line.push_str(&format!("[{}]  ", size).dimmed().to_string());
+            }
+            line.push_str(&format_tree_name(child).to_string());
+            if child.truncated {
+                line.push_str(&" …".dimmed().to_string());
+            }
+            if let Some(ref error) = child.error {
+                line.push_str(&format!("  ({})", error).red().to_string());
+            }
+            println!("{}", line);
+            let extension = if last { "    " } else { "│   " };
+            self.print_children(child, &format!("{}{}", prefix, extension));
+        }
+    }
+
+    fn flatten<'a>(node: &'a TreeNode, rows: &mut Vec<&'a TreeNode>) {
+        for child in &node.children {
+            rows.push(child);
+            Self::flatten(child, rows);
+        }
+    }
+
+    fn summary(&self) -> String {
+        let dirs = if self.directories == 1 { "directory" } else { "directories" };
+        let files = if self.files == 1 { "file" } else { "files" };
+        let mut summary = format!("{} {}, {} {}", self.directories, dirs, self.files, files);
+        if self.show_sizes {
+            summary.push_str(&format!(", {} total", format_size(self.root.total_size()).trim()));
+        }
+        summary
+    }
+}
+
+impl Displayable for VfsTree {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(self)?);
+            }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
+            OutputFormat::Table => {
+                let mut rows = Vec::new();
+                Self::flatten(&self.root, &mut rows);
+                if rows.is_empty() {
+                    println!("{}", "(empty)".dimmed());
+                    return Ok(());
+                }
+                let mut table = components::TableBuilder::new();
+                let mut headers = vec!["Path", "Type"];
+                if self.show_sizes { headers.push("Size"); }
+                table.add_header(headers);
+                for node in rows {
+                    let mut row = vec![
+                        if node.is_dir() { format!("{}/", node.path) } else { node.path.clone() },
+                        node.entry_type.clone(),
+                    ];
+                    if self.show_sizes {
+                        row.push(format_size(node.total_size()).trim().to_string());
+                    }
+                    table.add_row(row);
+                }
+                table.print();
+                println!("\n{}", self.summary().dimmed());
+            }
+            OutputFormat::Plain => {
+                println!("{}", self.root.path.bright_blue().bold());
+                self.print_children(&self.root, "");
+                println!("\n{}", self.summary().dimmed());
+            }
+        }
+        Ok(())
+    }
+}
+
 // Helper functions
 fn format_entry_name(entry: &FileSystemEntry) -> String {
     match entry.entry_type.as_str() {
@@ -197,6 +345,14 @@ fn format_entry_name(entry: &FileSystemEntry) -> String {
     }
 }
 
+fn format_tree_name(node: &TreeNode) -> ColoredString {
+    match node.entry_type.as_str() {
+        "directory" => format!("{}/", node.name).bright_blue(),
+        _ if node.executable.unwrap_or(false) || node.path.starts_with("/commands/") => node.name.bright_green(),
+        _ => node.name.normal(),
+    }
+}
+
 fn format_entry_name_colored(entry: &FileSystemEntry, path: &str) -> ColoredString {
     match entry.entry_type.as_str() {
         "directory" => format!("{}/", entry.name).bright_blue(),
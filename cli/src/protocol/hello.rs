@@ -0,0 +1,158 @@
+//! Agreeing on a protocol before the first request
+//!
+//! Each new connection opens with `hello`, naming the CLI's version and the
+//! protocol revision it speaks. The daemon answers with its own, plus the
+//! oldest revision it still serves. Daemons from before the handshake answer
+//! "Unknown request type: hello" and are taken to speak revision 0; for
+//! those the version `status` reports, if any, stands in, and releases with
+//! a different major or minor version are taken not to understand each other.
+//!
+//! Within a revision, the daemon also lists the optional features it
+//! implements. Anything that would do harm on a daemon that quietly ignores
+//! a field (a preview that writes for real, say) checks for its feature
+//! first, and a daemon that lists none is taken to have none.
+
+use super::{DaemonRequest, RequestBuilder, ResponseParser};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// The protocol revision this CLI speaks
+pub const PROTOCOL_REVISION: u32 = 1;
+
+/// The oldest daemon revision this CLI still understands; 0 is a daemon from before `hello`
+pub const MIN_DAEMON_REVISION: u32 = 0;
+
+/// `declare_relation` with `preview: true` generates without writing
+pub const FEATURE_PREVIEW: &str = "preview";
+
+/// AI requests with `detach: true` are answered at once with a job id
+pub const FEATURE_DETACH: &str = "detach";
+
+/// Every optional feature this CLI knows how to use
+pub const FEATURES: &[&str] = &[FEATURE_PREVIEW, FEATURE_DETACH];
+
+#[derive(Debug, Serialize)]
+pub struct HelloRequest {
+    pub version: String,
+    pub protocol: u32,
+    pub min_protocol: u32,
+}
+
+impl HelloRequest {
+    /// What this build says about itself
+    pub fn current() -> Self {
+        Self {
+            version: env!("PORT42_VERSION").to_string(),
+            protocol: PROTOCOL_REVISION,
+            min_protocol: MIN_DAEMON_REVISION,
+        }
+    }
+}
+
+impl RequestBuilder for HelloRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "hello".to_string(),
+            id,
+            payload: json!({
+                "client": "port42",
+                "version": self.version,
+                "protocol": self.protocol,
+                "min_protocol": self.min_protocol,
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct HelloResponse {
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub protocol: u32,
+    /// The oldest CLI revision the daemon still serves
+    #[serde(default)]
+    pub min_protocol: u32,
+    /// Optional features the daemon implements, e.g. FEATURE_PREVIEW
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+}
+
+impl ResponseParser for HelloResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self::Output> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+/// Releases with the same major and minor version speak the same protocol
+pub fn versions_compatible(a: &str, b: &str) -> bool {
+    let major_minor = |v: &str| -> Vec<String> {
+        v.trim().trim_start_matches('v').split('.').take(2).map(String::from).collect()
+    };
+    major_minor(a) == major_minor(b)
+}
+
+/// Why this CLI and the daemon can't be trusted to understand each other
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incompatibility {
+    pub reason: String,
+    pub fix: &'static str,
+}
+
+impl HelloResponse {
+    /// A daemon that didn't know `hello`
+    pub fn legacy() -> Self {
+        Self { version: None, protocol: 0, min_protocol: 0, features: Vec::new() }
+    }
+
+    pub fn is_legacy(&self) -> bool {
+        self.protocol == 0
+    }
+
+    /// Whether the daemon said it implements `feature`
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+
+    /// What is wrong, if anything, with talking to this daemon
+    // MIN_DAEMON_REVISION stays 0 until the protocol first breaks
+    #[allow(clippy::absurd_extreme_comparisons)]
+    pub fn incompatibility(&self) -> Option<Incompatibility> {
+        let daemon = match &self.version {
+            Some(version) => format!("The daemon (v{})", version),
+            None => "The daemon".to_string(),
+        };
+        let cli = env!("PORT42_VERSION");
+        if self.min_protocol > PROTOCOL_REVISION {
+            Some(Incompatibility {
+                reason: format!(
+                    "{} needs protocol {} or newer, but this CLI (v{}) speaks {}",
+                    daemon, self.min_protocol, cli, PROTOCOL_REVISION
+                ),
+                fix: "Upgrade the CLI to match: port42 upgrade",
+            })
+        } else if self.is_legacy() && self.version.as_deref().is_some_and(|v| !versions_compatible(v, cli)) {
+            Some(Incompatibility {
+                reason: format!("{} predates the protocol handshake and is from another release than this CLI (v{})", daemon, cli),
+                fix: "Restart the daemon from this release: port42 daemon restart",
+            })
+        } else if self.protocol < MIN_DAEMON_REVISION {
+            Some(Incompatibility {
+                reason: format!(
+                    "{} speaks protocol {}, but this CLI (v{}) needs {} or newer",
+                    daemon, self.protocol, cli, MIN_DAEMON_REVISION
+                ),
+                fix: "Restart the daemon from this release: port42 daemon restart",
+            })
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use super::{DaemonRequest, RequestBuilder, ResponseParser};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// A command the user ran, reported by the shell hook or `port42 run`
+#[derive(Debug, Serialize)]
+pub struct TrackCommandRequest {
+    pub command: String,
+    pub exit_code: i32,
+    pub cwd: Option<String>,
+    /// Wall-clock time, when the reporter measured it
+    pub duration_ms: Option<u64>,
+    /// VFS path of the crystallized command that ran, for `port42 run`
+    pub tool: Option<String>,
+}
+
+impl RequestBuilder for TrackCommandRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "track_command".to_string(),
+            id,
+            payload: json!({
+                "command": &self.command,
+                "exit_code": self.exit_code,
+                "cwd": &self.cwd,
+                "duration_ms": self.duration_ms,
+                "tool": &self.tool
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Tell the daemon a context suggestion was taken up, so it can learn
+/// which suggestions are worth making
+#[derive(Debug, Serialize)]
+pub struct SuggestionFeedbackRequest {
+    pub command: String,
+    pub reason: String,
+    pub accepted: bool,
+    /// How the command went, when it was run
+    pub exit_code: Option<i32>,
+}
+
+impl RequestBuilder for SuggestionFeedbackRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "suggestion_feedback".to_string(),
+            id,
+            payload: json!({
+                "command": &self.command,
+                "reason": &self.reason,
+                "accepted": self.accepted,
+                "exit_code": self.exit_code
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// The most frequently run tracked commands since a point in time
+#[derive(Debug, Serialize)]
+pub struct CommandStatsRequest {
+    /// RFC 3339 timestamp
+    pub since: String,
+    pub limit: usize,
+}
+
+impl RequestBuilder for CommandStatsRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "command_stats".to_string(),
+            id,
+            payload: json!({
+                "since": &self.since,
+                "limit": self.limit
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CommandStatsResponse {
+    #[serde(default)]
+    pub commands: Vec<CommandCount>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CommandCount {
+    pub command: String,
+    pub count: u64,
+}
+
+impl ResponseParser for CommandStatsResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
@@ -0,0 +1,295 @@
+use super::{DaemonRequest, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat, components::TableBuilder, print_yaml};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use colored::*;
+
+/// Request types the daemon will run as a job when asked to
+pub const DETACHABLE: &[&str] = &["swim", "declare_relation"];
+
+/// Whether `detach` turns the request into a job rather than a long wait
+pub fn detachable(request: &DaemonRequest) -> bool {
+    DETACHABLE.contains(&request.request_type.as_str())
+}
+
+/// Ask the daemon to run an AI request in the background and answer with a job ID
+pub fn detach(request: &mut DaemonRequest) {
+    if let Some(obj) = request.payload.as_object_mut() {
+        obj.insert("detach".to_string(), json!(true));
+    }
+}
+
+/// The daemon's answer to a detached request
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JobSubmitted {
+    pub job_id: String,
+    #[serde(default)]
+    pub status: JobState,
+}
+
+impl ResponseParser for JobSubmitted {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobListRequest;
+
+impl RequestBuilder for JobListRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "list_jobs".to_string(),
+            id,
+            payload: json!({}),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobStatusRequest {
+    pub job_id: String,
+}
+
+impl RequestBuilder for JobStatusRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "job_status".to_string(),
+            id,
+            payload: json!({
+                "job_id": &self.job_id
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobCancelRequest {
+    pub job_id: String,
+}
+
+impl RequestBuilder for JobCancelRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "cancel_job".to_string(),
+            id,
+            payload: json!({
+                "job_id": &self.job_id
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    #[default]
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+    /// A state this CLI doesn't know yet
+    #[serde(other)]
+    Unknown,
+}
+
+impl JobState {
+    /// Nothing more will happen to the job
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobState::Completed | JobState::Failed | JobState::Cancelled)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            JobState::Queued => "queued",
+            JobState::Running => "running",
+            JobState::Completed => "completed",
+            JobState::Failed => "failed",
+            JobState::Cancelled => "cancelled",
+            JobState::Unknown => "unknown",
+        }
+    }
+
+    fn colored(self) -> ColoredString {
+        match self {
+            JobState::Queued => self.label().dimmed(),
+            JobState::Running => self.label().bright_cyan(),
+            JobState::Completed => self.label().green(),
+            JobState::Failed => self.label().red(),
+            JobState::Cancelled | JobState::Unknown => self.label().yellow(),
+        }
+    }
+}
+
+/// A background generation as the daemon tracks it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Job {
+    #[serde(alias = "job_id")]
+    pub id: String,
+    /// What was submitted: swim or declare
+    pub kind: String,
+    pub status: JobState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// What the daemon is doing right now, e.g. "generating"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    /// Percent complete, when the daemon can tell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    /// The reply the request would have returned in the foreground
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ResponseParser for Job {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        // Accept the job bare or wrapped as {"job": {...}}
+        let job = data.get("job").unwrap_or(data);
+        Ok(serde_json::from_value(job.clone())?)
+    }
+}
+
+impl Job {
+    /// One line of progress: state, bar, percent and stage
+    pub fn progress_line(&self) -> String {
+        let mut line = format!("{:<9}", self.status.label());
+        if let Some(percent) = self.progress {
+            line.push_str(&format!(" {} {:>3.0}%", progress_bar(percent, 20), percent.clamp(0.0, 100.0)));
+        }
+        if let Some(ref stage) = self.stage {
+            line.push_str(&format!("  {}", stage));
+        }
+        line.trim_end().to_string()
+    }
+
+    /// Time since submission, e.g. "2m 5s"
+    pub fn age(&self) -> Option<String> {
+        let created = chrono::DateTime::parse_from_rfc3339(self.created_at.as_deref()?).ok()?;
+        let secs = (chrono::Utc::now() - created.with_timezone(&chrono::Utc)).num_seconds().max(0);
+        Some(match secs {
+            s if s < 60 => format!("{}s", s),
+            s if s < 3600 => format!("{}m {}s", s / 60, s % 60),
+            s => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        })
+    }
+}
+
+/// `[#####-----]` filled to `percent` of `width`
+pub fn progress_bar(percent: f64, width: usize) -> String {
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * width as f64).round() as usize;
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(width - filled))
+}
+
+impl Displayable for Job {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Plain | OutputFormat::Table => {
+                println!("{} {}", "⏳".bright_blue(), self.id.bright_cyan().bold());
+                println!("  {}: {}", "Kind".bright_cyan(), self.kind);
+                println!("  {}: {}", "Status".bright_cyan(), self.status.colored());
+                if let Some(ref description) = self.description {
+                    println!("  {}: {}", "Request".bright_cyan(), description);
+                }
+                if let Some(ref stage) = self.stage {
+                    println!("  {}: {}", "Stage".bright_cyan(), stage);
+                }
+                if let Some(percent) = self.progress {
+                    println!("  {}: {} {:.0}%", "Progress".bright_cyan(), progress_bar(percent, 20), percent);
+                }
+                if let Some(age) = self.age() {
+                    println!("  {}: {} ago", "Submitted".bright_cyan(), age);
+                }
+                if let Some(ref error) = self.error {
+                    println!("  {}: {}", "Error".bright_cyan(), error.red());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct JobList {
+    #[serde(default)]
+    pub jobs: Vec<Job>,
+}
+
+impl ResponseParser for JobList {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+impl Displayable for JobList {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Table => {
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["Job", "Kind", "Status", "Progress", "Age", "Request"]);
+                for job in &self.jobs {
+                    table.add_row(vec![
+                        job.id.clone(),
+                        job.kind.clone(),
+                        job.status.label().to_string(),
+                        job.progress.map(|p| format!("{:.0}%", p)).unwrap_or_else(|| "-".to_string()),
+                        job.age().unwrap_or_else(|| "-".to_string()),
+                        job.description.clone().unwrap_or_else(|| "-".to_string()),
+                    ]);
+                }
+                table.print();
+            }
+            OutputFormat::Plain => {
+                if self.jobs.is_empty() {
+                    println!("{}", "No background jobs".dimmed());
+                    return Ok(());
+                }
+                println!("{}", "⏳ Background jobs".bright_blue().bold());
+                for job in &self.jobs {
+                    print!("  {:<20} {:<8} {:<10}", job.id.bright_cyan(), job.kind, job.status.colored());
+                    if let Some(percent) = job.progress.filter(|_| !job.status.is_finished()) {
+                        print!(" {:>3.0}%", percent);
+                    }
+                    if let Some(ref description) = job.description {
+                        print!(" {}", description);
+                    }
+                    if let Some(age) = job.age() {
+                        print!(" {}", format!("({} ago)", age).dimmed());
+                    }
+                    println!();
+                }
+            }
+        }
+        Ok(())
+    }
+}
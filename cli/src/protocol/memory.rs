@@ -1,5 +1,5 @@
-use super::{DaemonRequest, RequestBuilder, ResponseParser};
-use crate::display::{Displayable, OutputFormat, components};
+use super::{DaemonRequest, RequestBuilder, ResponseParser, swim::TokenUsage, usage::{SessionUsage, format_cost, format_tokens}};
+use crate::display::{Displayable, OutputFormat, components, print_yaml};
 use crate::help_text;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -26,6 +26,7 @@ impl RequestBuilder for MemoryListRequest {
             references: None,
             session_context: None,
             user_prompt: None,
+            provider: None,
         })
     }
 }
@@ -41,6 +42,83 @@ impl RequestBuilder for MemoryDetailRequest {
             references: None,
             session_context: None,
             user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Copy a session, optionally only up to a message, into a new session
+#[derive(Debug, Serialize)]
+pub struct MemoryForkRequest {
+    pub session_id: String,
+    /// Last message (1-based) the fork keeps; None keeps them all
+    pub at: Option<usize>,
+}
+
+impl RequestBuilder for MemoryForkRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "fork_session".to_string(),
+            id,
+            payload: json!({
+                "session_id": self.session_id,
+                "at_message": self.at
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Combine sessions into one new thread; the originals stay as they are
+#[derive(Debug, Serialize)]
+pub struct MemoryMergeRequest {
+    pub session_ids: Vec<String>,
+    pub name: Option<String>,
+    /// Order messages by time across sessions instead of one session after another
+    pub interleave: bool,
+}
+
+impl RequestBuilder for MemoryMergeRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "merge_sessions".to_string(),
+            id,
+            payload: json!({
+                "session_ids": self.session_ids,
+                "name": self.name,
+                "strategy": if self.interleave { "interleave" } else { "concat" }
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Give a session a human label; the ID stays the same
+#[derive(Debug, Serialize)]
+pub struct MemoryRenameRequest {
+    pub session_id: String,
+    pub name: String,
+}
+
+impl RequestBuilder for MemoryRenameRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "rename_session".to_string(),
+            id,
+            payload: json!({
+                "session_id": self.session_id,
+                "name": self.name
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
         })
     }
 }
@@ -56,6 +134,9 @@ pub struct MemoryListResponse {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SessionSummary {
     pub id: String,
+    /// Human label given with `memory rename`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     pub agent: String,
     pub state: String,
     pub message_count: u64,
@@ -80,6 +161,94 @@ pub struct MemoryDetailResponse {
     pub last_activity: String,
     pub command_generated: Option<SessionCommandInfo>,
     pub messages: Vec<Message>,
+    /// Where this session branched off, for forks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<ForkOrigin>,
+    /// Sessions combined into this one, for merges
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub merged_from: Vec<String>,
+    /// Tokens and cost so far, when the daemon tracks them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<SessionUsage>,
+}
+
+/// The session and message a fork was taken from
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ForkOrigin {
+    pub session_id: String,
+    /// Messages carried over from the parent
+    pub at_message: usize,
+}
+
+/// A new session branched from an existing one
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MemoryForkResponse {
+    pub session_id: String,
+    #[serde(default)]
+    pub agent: Option<String>,
+    pub forked_from: ForkOrigin,
+}
+
+/// A new session holding the messages of several others
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MemoryMergeResponse {
+    pub session_id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub merged_from: Vec<String>,
+    #[serde(default)]
+    pub message_count: u64,
+}
+
+impl ResponseParser for MemoryMergeResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+impl Displayable for MemoryMergeResponse {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            _ => {
+                println!("{}", format!("🪢 Merged {} sessions into {}", self.merged_from.len(), self.session_id).bright_green().bold());
+                if let Some(ref name) = self.name {
+                    println!("  {}: {}", "Name".dimmed(), name.bright_white());
+                }
+                println!("  {}: {}", "From".dimmed(), self.merged_from.join(", "));
+                println!("  {}: {}", "Messages".dimmed(), self.message_count);
+                println!("{}", format!("Review it with: port42 memory {}", self.session_id).dimmed());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ResponseParser for MemoryForkResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+impl Displayable for MemoryForkResponse {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            _ => {
+                println!("{}", format!("🌿 Forked {} into {}", self.forked_from.session_id, self.session_id).bright_green().bold());
+                println!("  {}: {}", "Messages carried over".dimmed(), self.forked_from.at_message);
+                let agent = self.agent.as_deref().unwrap_or("<agent>");
+                println!("{}", format!("Continue the branch: port42 swim {} --session {}", agent, self.session_id).dimmed());
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -93,6 +262,12 @@ pub struct Message {
     pub role: String,
     pub content: String,
     pub timestamp: String,
+    /// Session the message originally belonged to, in merged threads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_session: Option<String>,
+    /// Tokens billed for the reply, on assistant messages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
 }
 
 impl ResponseParser for MemoryListResponse {
@@ -135,6 +310,10 @@ fn parse_session_summary(value: &serde_json::Value) -> Result<SessionSummary> {
             .and_then(|v| v.as_str())
             .unwrap_or("unknown")
             .to_string(),
+        name: value.get("name")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string()),
         agent: value.get("agent")
             .and_then(|v| v.as_str())
             .unwrap_or("unknown")
@@ -209,12 +388,24 @@ impl ResponseParser for MemoryDetailResponse {
                                 role: msg.get("role")?.as_str()?.to_string(),
                                 content: msg.get("content")?.as_str()?.to_string(),
                                 timestamp: msg.get("timestamp")?.as_str()?.to_string(),
+                                source_session: msg.get("source_session")
+                                    .and_then(|s| s.as_str())
+                                    .map(|s| s.to_string()),
+                                usage: msg.get("usage")
+                                    .and_then(|u| serde_json::from_value(u.clone()).ok()),
                             })
                         })
                         .collect()
                 })
                 .unwrap_or_default(),
-        })
+            forked_from: data.get("forked_from")
+                .filter(|v| !v.is_null())
+                .and_then(|v| serde_json::from_value(v.clone()).ok()),
+            merged_from: data.get("merged_from")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+            usage: None,
+        }.with_usage(data.get("usage")))
     }
 }
 
@@ -224,6 +415,9 @@ impl Displayable for MemoryListResponse {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(self)?);
             }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
             OutputFormat::Table => {
                 // Active sessions table
                 if !self.active_sessions.is_empty() {
@@ -289,12 +483,61 @@ impl Displayable for MemoryListResponse {
     }
 }
 
+impl MemoryDetailResponse {
+    /// Session usage from the daemon, or else summed from its messages
+    fn with_usage(mut self, usage: Option<&serde_json::Value>) -> Self {
+        self.usage = usage.and_then(SessionUsage::parse).or_else(|| {
+            let mut total: Option<TokenUsage> = None;
+            for usage in self.messages.iter().filter_map(|m| m.usage) {
+                total.get_or_insert_with(TokenUsage::default).add(usage);
+            }
+            total.and_then(|t| SessionUsage::parse(&json!(t)))
+        });
+        self
+    }
+
+    /// The conversation as plain text, one "role: content" block per message
+    pub fn transcript(&self) -> String {
+        self.messages.iter()
+            .map(|m| format!("{}: {}", m.role, m.content.trim()))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// The session as a memory context entry for another conversation.
+    /// Keeps the most recent messages that fit in `max_chars`.
+    pub fn as_context(&self, max_chars: usize) -> String {
+        let mut kept = Vec::new();
+        let mut used = 0;
+        for message in self.messages.iter().rev() {
+            let block = format!("{}: {}", message.role, message.content.trim());
+            if used + block.len() > max_chars && !kept.is_empty() {
+                break;
+            }
+            used += block.len() + 2;
+            kept.push(block);
+        }
+        kept.reverse();
+
+        let mut context = format!("=== Reference: memory {} ({}) ===\n\n", self.id, self.agent);
+        let omitted = self.messages.len() - kept.len();
+        if omitted > 0 {
+            context.push_str(&format!("[{} earlier messages omitted]\n\n", omitted));
+        }
+        context.push_str(&kept.join("\n\n"));
+        context
+    }
+}
+
 impl Displayable for MemoryDetailResponse {
     fn display(&self, format: OutputFormat) -> Result<()> {
         match format {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(self)?);
             }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
             _ => {
                 println!("{}", format!("📖 Session: {}", self.id).blue().bold());
                 println!();
@@ -314,8 +557,23 @@ impl Displayable for MemoryDetailResponse {
                     println!("{}: {} {}", "Command Generated".dimmed(), "✨".bright_green(), cmd.name.bright_white());
                 }
                 
+                if let Some(origin) = &self.forked_from {
+                    println!("{}: 🌿 {} {}", "Forked From".dimmed(), origin.session_id.bright_white(),
+                        format!("(after message {})", origin.at_message).dimmed());
+                }
+                
+                if !self.merged_from.is_empty() {
+                    println!("{}: 🪢 {}", "Merged From".dimmed(), self.merged_from.join(", ").bright_white());
+                }
+                
+                if let Some(usage) = &self.usage {
+                    println!("{}: {} in · {} out · ~{}", "Usage".dimmed(),
+                        format_tokens(usage.input_tokens), format_tokens(usage.output_tokens), format_cost(usage.cost));
+                }
+                
                 println!("\n{}", "Conversation:".bright_cyan().bold());
                 
+                let mut source = None;
                 for (i, msg) in self.messages.iter().enumerate() {
                     if i > 0 {
                         println!();
@@ -327,6 +585,21 @@ impl Displayable for MemoryDetailResponse {
                         String::new()
                     };
                     
+                    let time_str = format!("{} #{}", time_str, i + 1).trim_start().to_string();
+                    
+                    // The fork point: everything above came from the parent session
+                    if self.forked_from.as_ref().is_some_and(|o| o.at_message == i && i > 0) {
+                        println!("{}", "── branched here ──".dimmed());
+                        println!();
+                    }
+                    
+                    // Provenance marker wherever a merged thread switches source
+                    if msg.source_session.is_some() && msg.source_session != source {
+                        source = msg.source_session.clone();
+                        println!("{}", format!("── from {} ──", source.as_deref().unwrap_or_default()).dimmed());
+                        println!();
+                    }
+                    
                     match msg.role.as_str() {
                         "user" => {
                             println!("{} {} {}", "→".bright_green(), "User".bright_green().bold(), time_str.dimmed());
@@ -381,6 +654,9 @@ fn print_session_summary(session: &SessionSummary) {
     };
     
     print!("    {} {} ", state_icon, session.id.bright_white());
+    if let Some(ref name) = session.name {
+        print!("\"{}\" ", name.bright_cyan());
+    }
     print!("({}) ", session.agent.bright_blue());
     print!("{} messages", session.message_count);
     
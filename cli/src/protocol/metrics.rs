@@ -0,0 +1,208 @@
+use super::{DaemonRequest, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat, components::TableBuilder, print_yaml};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use colored::*;
+
+#[derive(Debug, Serialize)]
+pub struct MetricsRequest;
+
+impl RequestBuilder for MetricsRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "metrics".to_string(),
+            id,
+            payload: serde_json::Value::Null,
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+    /// Anything else is passed through as an untyped metric
+    #[serde(other)]
+    Untyped,
+}
+
+impl MetricKind {
+    fn label(self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+            MetricKind::Untyped => "untyped",
+        }
+    }
+}
+
+/// Observations at or below `le`, cumulative as in Prometheus
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Bucket {
+    pub le: f64,
+    pub count: u64,
+}
+
+/// One labelled series: a value for counters and gauges, buckets for histograms
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Sample {
+    pub labels: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub buckets: Vec<Bucket>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sum: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+}
+
+impl Sample {
+    /// `type=swim,agent=@ai-muse`, or "-" without labels
+    fn label_summary(&self) -> String {
+        if self.labels.is_empty() {
+            return "-".to_string();
+        }
+        self.labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(",")
+    }
+
+    /// The value, or count/avg for a histogram
+    fn value_summary(&self) -> String {
+        match (self.value, self.count, self.sum) {
+            (Some(value), _, _) => format_number(value),
+            (None, Some(count), Some(sum)) if count > 0 => format!("count {}  avg {}", count, format_number((sum / count as f64 * 1000.0).round() / 1000.0)),
+            (None, Some(count), _) => format!("count {}", count),
+            _ => "-".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MetricFamily {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: MetricKind,
+    #[serde(default)]
+    pub help: String,
+    #[serde(default)]
+    pub samples: Vec<Sample>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MetricsSnapshot {
+    #[serde(default)]
+    pub metrics: Vec<MetricFamily>,
+}
+
+impl ResponseParser for MetricsSnapshot {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+impl MetricsSnapshot {
+    /// Prometheus text exposition format, version 0.0.4
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        for family in &self.metrics {
+            if !family.help.is_empty() {
+                out.push_str(&format!("# HELP {} {}\n", family.name, family.help.replace('\\', "\\\\").replace('\n', "\\n")));
+            }
+            out.push_str(&format!("# TYPE {} {}\n", family.name, family.kind.label()));
+            for sample in &family.samples {
+                if family.kind != MetricKind::Histogram {
+                    let value = sample.value.map(format_number).unwrap_or_else(|| "NaN".to_string());
+                    out.push_str(&format!("{}{} {}\n", family.name, labels(&sample.labels, None), value));
+                    continue;
+                }
+                for bucket in &sample.buckets {
+                    let le = format_number(bucket.le);
+                    out.push_str(&format!("{}_bucket{} {}\n", family.name, labels(&sample.labels, Some(&le)), bucket.count));
+                }
+                let count = sample.count.unwrap_or_else(|| sample.buckets.last().map_or(0, |b| b.count));
+                out.push_str(&format!("{}_bucket{} {}\n", family.name, labels(&sample.labels, Some("+Inf")), count));
+                out.push_str(&format!("{}_sum{} {}\n", family.name, labels(&sample.labels, None), format_number(sample.sum.unwrap_or(0.0))));
+                out.push_str(&format!("{}_count{} {}\n", family.name, labels(&sample.labels, None), count));
+            }
+        }
+        out
+    }
+}
+
+/// `{k="v",...}` with Prometheus escaping; `le` goes last for buckets
+fn labels(labels: &BTreeMap<String, String>, le: Option<&str>) -> String {
+    let escape = |v: &str| v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+    let mut pairs: Vec<String> = labels.iter().map(|(k, v)| format!("{}=\"{}\"", k, escape(v))).collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+/// Whole numbers without a fraction, everything else as Rust prints it
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+impl Displayable for MetricsSnapshot {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Table => {
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["Metric", "Type", "Labels", "Value"]);
+                for family in &self.metrics {
+                    for sample in &family.samples {
+                        table.add_row(vec![
+                            family.name.clone(),
+                            family.kind.label().to_string(),
+                            sample.label_summary(),
+                            sample.value_summary(),
+                        ]);
+                    }
+                }
+                table.print();
+            }
+            OutputFormat::Plain => {
+                if self.metrics.is_empty() {
+                    println!("{}", "The daemon reported no metrics".dimmed());
+                    return Ok(());
+                }
+                println!("{}", "📈 Daemon Metrics".bright_blue().bold());
+                for family in &self.metrics {
+                    println!();
+                    print!("  {}", family.name.bright_white().bold());
+                    if !family.help.is_empty() {
+                        print!("  {}", family.help.dimmed());
+                    }
+                    println!();
+                    let width = family.samples.iter().map(|s| s.label_summary().len()).max().unwrap_or(0);
+                    for sample in &family.samples {
+                        println!("    {:<width$}  {}", sample.label_summary(), sample.value_summary().bright_cyan());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,17 +1,31 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
+use crate::common::workspace::WorkspaceContext;
 
 // Session context for memory-relation bridge
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SessionContext {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<WorkspaceContext>,
+}
+
+// AI backend selection, resolved by the daemon's provider factory
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ProviderSelection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
 }
 
 // Base request that all commands use
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DaemonRequest {
     #[serde(rename = "type")]
     pub request_type: String,
@@ -23,6 +37,8 @@ pub struct DaemonRequest {
     pub session_context: Option<SessionContext>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ProviderSelection>,
 }
 
 // Base response from daemon
@@ -55,6 +71,18 @@ pub mod filesystem;
 pub mod file_ops;
 pub mod search;
 pub mod relations;
+pub mod usage;
+pub mod models;
+pub mod embeddings;
+pub mod hooks;
+pub mod agents;
+pub mod jobs;
+pub mod rules;
+pub mod metrics;
+pub mod refs;
+pub mod artifacts;
+pub mod events;
+pub mod hello;
 
 pub use swim::*;
 pub use status::*;
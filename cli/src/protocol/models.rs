@@ -0,0 +1,317 @@
+use super::{DaemonRequest, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat, components::TableBuilder, print_yaml};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use colored::*;
+
+#[derive(Debug, Serialize)]
+pub struct ModelsRequest {
+    /// Restrict the query to one provider
+    pub provider: Option<String>,
+}
+
+impl RequestBuilder for ModelsRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "models".to_string(),
+            id,
+            payload: json!({
+                "provider": self.provider
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Models one provider reported, or why it could not be asked
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProviderModels {
+    pub name: String,
+    pub models: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ModelsResponse {
+    pub providers: Vec<ProviderModels>,
+}
+
+impl ResponseParser for ModelsResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        let providers = data.get("providers")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|p| {
+                        Some(ProviderModels {
+                            name: p.get("name")?.as_str()?.to_string(),
+                            models: p.get("models")
+                                .and_then(|m| m.as_array())
+                                .map(|m| m.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                                .unwrap_or_default(),
+                            error: p.get("error")
+                                .and_then(|e| e.as_str())
+                                .map(String::from),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(ModelsResponse { providers })
+    }
+}
+
+/// A model named in config, checked against what its provider offers
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfiguredModel {
+    /// "default" or the agent name
+    pub scope: String,
+    pub provider: String,
+    pub model: String,
+    /// None when the provider could not be queried
+    pub valid: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelsReport {
+    pub providers: Vec<ProviderModels>,
+    pub configured: Vec<ConfiguredModel>,
+}
+
+impl ModelsReport {
+    pub fn new(providers: Vec<ProviderModels>, configured: Vec<(String, String, String)>) -> Self {
+        let configured = configured
+            .into_iter()
+            .map(|(scope, provider, model)| {
+                let available = providers.iter()
+                    .find(|p| p.name == provider && p.error.is_none())
+                    .map(|p| &p.models);
+                let valid = available.map(|models| models.contains(&model));
+                let suggestion = match (valid, available) {
+                    (Some(false), Some(models)) => closest_model(&model, models),
+                    _ => None,
+                };
+                ConfiguredModel { scope, provider, model, valid, suggestion }
+            })
+            .collect();
+
+        ModelsReport { providers, configured }
+    }
+
+    pub fn has_invalid(&self) -> bool {
+        self.configured.iter().any(|c| c.valid == Some(false))
+    }
+
+    fn scopes_using(&self, provider: &str, model: &str) -> Vec<&str> {
+        self.configured.iter()
+            .filter(|c| c.provider == provider && c.model == model)
+            .map(|c| c.scope.as_str())
+            .collect()
+    }
+
+    fn display_validation(&self) {
+        if self.configured.is_empty() {
+            return;
+        }
+
+        println!("\n{}", "Configured models:".bright_cyan().bold());
+        for entry in &self.configured {
+            let label = format!("{} → {}/{}", entry.scope, entry.provider, entry.model);
+            match entry.valid {
+                Some(true) => println!("  {} {}", "✅".green(), label),
+                Some(false) => {
+                    println!("  {} {} {}", "❌".red(), label, "(not offered by provider)".red());
+                    if let Some(ref suggestion) = entry.suggestion {
+                        println!("     {}", format!("Did you mean '{}'?", suggestion).yellow());
+                    }
+                }
+                None => println!("  {} {} {}", "❔".dimmed(), label, "(provider not reachable)".dimmed()),
+            }
+        }
+    }
+}
+
+impl Displayable for ModelsReport {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(self)?);
+            }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
+            OutputFormat::Plain => {
+                println!("{}", "🧬 Available models".bright_blue().bold());
+                for provider in &self.providers {
+                    println!("\n{}", provider.name.bright_cyan().bold());
+                    if let Some(ref error) = provider.error {
+                        println!("  {}", format!("⚠️  {}", error).yellow());
+                        continue;
+                    }
+                    if provider.models.is_empty() {
+                        println!("  {}", "(no models reported)".dimmed());
+                    }
+                    for model in &provider.models {
+                        let scopes = self.scopes_using(&provider.name, model);
+                        if scopes.is_empty() {
+                            println!("  {}", model);
+                        } else {
+                            println!("  {}  {}", model.bright_green(), format!("← {}", scopes.join(", ")).dimmed());
+                        }
+                    }
+                }
+                self.display_validation();
+            }
+            OutputFormat::Table => {
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["Provider", "Model", "Configured For"]);
+                for provider in &self.providers {
+                    if let Some(ref error) = provider.error {
+                        table.add_row(vec![provider.name.clone(), format!("unavailable: {}", error), String::new()]);
+                        continue;
+                    }
+                    for model in &provider.models {
+                        table.add_row(vec![
+                            provider.name.clone(),
+                            model.clone(),
+                            self.scopes_using(&provider.name, model).join(", "),
+                        ]);
+                    }
+                }
+                table.print();
+                self.display_validation();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Nearest available model by edit distance, if it is plausibly a typo
+fn closest_model(wanted: &str, models: &[String]) -> Option<String> {
+    models.iter()
+        .map(|m| (edit_distance(wanted, m), m))
+        .filter(|(d, _)| *d <= 3)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, m)| m.clone())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            row.push((prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// Ask the daemon which providers its factory has configured
+#[derive(Debug, Serialize)]
+pub struct ProvidersRequest;
+
+impl RequestBuilder for ProvidersRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "list_providers".to_string(),
+            id,
+            payload: serde_json::Value::Null,
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// One provider as the daemon sees it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProviderInfo {
+    pub name: String,
+    /// Whether the daemon has what it needs (e.g. an API key) to use it
+    #[serde(default)]
+    pub configured: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Set locally: the provider requests go to when none is chosen
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ProvidersResponse {
+    pub providers: Vec<ProviderInfo>,
+}
+
+impl ResponseParser for ProvidersResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        let providers = data.get("providers")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|p| serde_json::from_value(p.clone()).ok()).collect())
+            .unwrap_or_default();
+        Ok(ProvidersResponse { providers })
+    }
+}
+
+impl Displayable for ProvidersResponse {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(self)?);
+            }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
+            OutputFormat::Plain => {
+                println!("{}", "🔌 Providers".bright_blue().bold());
+                for provider in &self.providers {
+                    let status = if provider.configured { "✅" } else { "❌" };
+                    let default = if provider.is_default { " (default)".bright_green().to_string() } else { String::new() };
+                    println!("\n{} {}{}", status, provider.name.bright_cyan().bold(), default);
+                    match provider.default_model {
+                        Some(ref model) => println!("   model: {}", model),
+                        None => println!("   {}", "model: (provider default)".dimmed()),
+                    }
+                    if let Some(ref url) = provider.base_url {
+                        println!("   url:   {}", url);
+                    }
+                    if !provider.configured {
+                        println!("   {}", "not configured on the daemon".yellow());
+                    }
+                }
+            }
+            OutputFormat::Table => {
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["Provider", "Configured", "Default Model", "Base URL"]);
+                for provider in &self.providers {
+                    let name = if provider.is_default { format!("{} *", provider.name) } else { provider.name.clone() };
+                    table.add_row(vec![
+                        name,
+                        if provider.configured { "yes" } else { "no" }.to_string(),
+                        provider.default_model.clone().unwrap_or_default(),
+                        provider.base_url.clone().unwrap_or_default(),
+                    ]);
+                }
+                table.print();
+            }
+        }
+        Ok(())
+    }
+}
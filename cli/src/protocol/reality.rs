@@ -1,8 +1,9 @@
-use crate::display::{Displayable, OutputFormat, components};
+use crate::display::{Displayable, OutputFormat, components, print_yaml};
 use crate::help_text;
 use anyhow::Result;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use colored::*;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 // Reality doesn't need request/response types since it reads filesystem directly
@@ -30,6 +31,9 @@ impl Displayable for RealityData {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(self)?);
             }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
             OutputFormat::Table => {
                 if self.commands.is_empty() {
                     self.display_empty();
@@ -84,4 +88,135 @@ impl RealityData {
         println!("\n{}", "Add to PATH:".yellow());
         println!("  {}", format!("export PATH=\"$PATH:{}\"", self.commands_dir.display()).bright_white());
     }
-}
\ No newline at end of file
+}
+/// The command list as seen by the last `reality` run, kept so the next run
+/// can report what changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RealitySnapshot {
+    pub taken: String,
+    pub tools: BTreeMap<String, SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub sha256: String,
+    pub language: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolChange {
+    pub name: String,
+    pub language: String,
+    pub description: Option<String>,
+    pub agent: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RealityDiff {
+    /// When the previous snapshot was taken; None on the first run
+    pub since: Option<String>,
+    pub added: Vec<ToolChange>,
+    pub removed: Vec<ToolChange>,
+    pub modified: Vec<ToolChange>,
+    pub unchanged: usize,
+}
+
+impl ToolChange {
+    fn new(name: &str, entry: &SnapshotEntry) -> Self {
+        Self { name: name.to_string(), language: entry.language.clone(), description: entry.description.clone(), agent: entry.agent.clone() }
+    }
+}
+
+impl RealitySnapshot {
+    /// Changes from `previous` (if any) to this snapshot
+    pub fn diff_from(&self, previous: Option<&RealitySnapshot>) -> RealityDiff {
+        let empty = BTreeMap::new();
+        let before = previous.map_or(&empty, |p| &p.tools);
+        let mut diff = RealityDiff {
+            since: previous.map(|p| p.taken.clone()),
+            added: Vec::new(),
+            removed: Vec::new(),
+            modified: Vec::new(),
+            unchanged: 0,
+        };
+        for (name, entry) in &self.tools {
+            match before.get(name) {
+                None => diff.added.push(ToolChange::new(name, entry)),
+                Some(old) if old.sha256 != entry.sha256 => diff.modified.push(ToolChange::new(name, entry)),
+                Some(_) => diff.unchanged += 1,
+            }
+        }
+        for (name, entry) in before {
+            if !self.tools.contains_key(name) {
+                diff.removed.push(ToolChange::new(name, entry));
+            }
+        }
+        diff
+    }
+}
+
+impl RealityDiff {
+    pub fn retain_agent(&mut self, agent: &str) {
+        for changes in [&mut self.added, &mut self.removed, &mut self.modified] {
+            changes.retain(|c| c.agent.as_deref() == Some(agent));
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+impl Displayable for RealityDiff {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        if !format.is_structured() {
+            let Some(ref since) = self.since else {
+                println!("{}", help_text::format_reality_first_snapshot(self.added.len()).dimmed());
+                return Ok(());
+            };
+            println!("{}", help_text::format_reality_since(since).dimmed());
+            println!();
+            if self.is_empty() {
+                println!("{}", help_text::MSG_REALITY_UNCHANGED.dimmed());
+                return Ok(());
+            }
+        }
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Table => {
+                let mut table = components::TableBuilder::new();
+                table.add_header(vec!["Change", "Command", "Agent", "Description"]);
+                for (label, changes) in [("added", &self.added), ("removed", &self.removed), ("modified", &self.modified)] {
+                    for change in changes {
+                        table.add_row(vec![
+                            label.to_string(),
+                            change.name.clone(),
+                            change.agent.as_deref().unwrap_or("-").to_string(),
+                            change.description.as_deref().unwrap_or("-").to_string(),
+                        ]);
+                    }
+                }
+                table.print();
+                println!("\n{}", help_text::format_reality_unchanged(self.unchanged).dimmed());
+            }
+            OutputFormat::Plain => {
+                for (mark, changes) in [("+".green(), &self.added), ("~".yellow(), &self.modified), ("-".red(), &self.removed)] {
+                    for change in changes {
+                        print!("{} {:<20}", mark, change.name.bright_cyan());
+                        if let Some(ref desc) = change.description {
+                            print!(" - {}", desc.dimmed());
+                        }
+                        println!();
+                    }
+                }
+                println!("\n{}", help_text::format_reality_unchanged(self.unchanged).dimmed());
+            }
+        }
+        Ok(())
+    }
+}
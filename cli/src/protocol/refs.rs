@@ -0,0 +1,139 @@
+use super::{DaemonRequest, RequestBuilder, ResponseParser};
+use super::relations::Reference;
+use crate::display::{Displayable, OutputFormat, print_yaml};
+use crate::protocol::file_ops::format_size;
+use crate::protocol::usage::format_tokens;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use colored::*;
+
+/// Lines of each reference shown before the rest is elided, without --full
+pub const PREVIEW_LINES: usize = 20;
+
+/// Resolve references the way declare and possess would, without generating anything
+#[derive(Debug, Serialize)]
+pub struct ResolveReferencesRequest {
+    pub references: Vec<Reference>,
+}
+
+impl RequestBuilder for ResolveReferencesRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "resolve_references".to_string(),
+            id,
+            payload: serde_json::to_value(self)?,
+            references: Some(self.references.clone()),
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// What one reference turned into: the content injected into the prompt,
+/// or why there is none
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ResolvedReference {
+    #[serde(rename = "type")]
+    pub ref_type: String,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Bytes before any truncation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<usize>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ResolvedReference {
+    /// Bytes that reach the prompt
+    pub fn injected(&self) -> usize {
+        self.content.as_ref().map_or(0, String::len)
+    }
+
+    /// `file:./notes.md`, as it was given
+    pub fn spec(&self) -> String {
+        format!("{}:{}", self.ref_type, self.target)
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ResolvedReferences {
+    #[serde(default)]
+    pub references: Vec<ResolvedReference>,
+    /// Whether to print each reference in full rather than a preview
+    #[serde(skip)]
+    pub full: bool,
+}
+
+impl ResponseParser for ResolvedReferences {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+impl ResolvedReferences {
+    pub fn failed(&self) -> usize {
+        self.references.iter().filter(|r| r.error.is_some()).count()
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    format_size(bytes as i64)
+}
+
+impl Displayable for ResolvedReferences {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Plain | OutputFormat::Table => {
+                for (n, reference) in self.references.iter().enumerate() {
+                    if n > 0 {
+                        println!();
+                    }
+                    println!("{} {}", format!("[{}]", n + 1).bright_white().bold(), reference.spec().bright_yellow());
+                    if let Some(ref error) = reference.error {
+                        println!("  {} {}", "✗".red(), error.red());
+                        continue;
+                    }
+                    let injected = reference.injected();
+                    let mut size = format!("{} injected", format_bytes(injected));
+                    if reference.truncated {
+                        let original = reference.size.map(format_bytes).unwrap_or_else(|| "more".to_string());
+                        size.push_str(&format!(", truncated from {}", original));
+                    }
+                    let size = if reference.truncated { size.yellow() } else { size.dimmed() };
+                    println!("  {}", size);
+
+                    let content = reference.content.as_deref().unwrap_or_default();
+                    if content.trim().is_empty() {
+                        println!("  {}", "(nothing would be injected)".yellow());
+                        continue;
+                    }
+                    println!("{}", "─".repeat(60).bright_black());
+                    let lines: Vec<&str> = content.lines().collect();
+                    let shown = if self.full { lines.len() } else { lines.len().min(PREVIEW_LINES) };
+                    for line in &lines[..shown] {
+                        println!("{}", line);
+                    }
+                    if shown < lines.len() {
+                        println!("{}", format!("… {} more lines (--full to see them)", lines.len() - shown).dimmed());
+                    }
+                    println!("{}", "─".repeat(60).bright_black());
+                }
+
+                let injected: usize = self.references.iter().map(ResolvedReference::injected).sum();
+                println!();
+                println!("{}", format!("{} references, {} would be injected (~{} tokens)",
+                    self.references.len(), format_bytes(injected), format_tokens((injected / 4) as u64)).bright_blue());
+            }
+        }
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use std::time::SystemTime;
 
 use crate::protocol::{DaemonRequest, RequestBuilder, ResponseParser};
-use crate::display::{Displayable, OutputFormat};
+use crate::display::{Displayable, OutputFormat, print_yaml};
 use colored::*;
 
 // Relation represents a declarative entity that should exist
@@ -28,11 +28,49 @@ pub struct Reference {
 }
 
 // Request to declare a new relation
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DeclareRelationRequest {
     pub relation: Relation,
     pub references: Option<Vec<Reference>>,
     pub user_prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<crate::protocol::ProviderSelection>,
+    /// Ask the daemon to report each generation phase as it starts
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub progress: bool,
+    /// Generate, but return the content instead of writing it anywhere
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub preview: bool,
+}
+
+// A generation phase starting, sent ahead of the response as
+// {"id": ..., "status": {"phase": "prompting", "detail": "claude-sonnet-4"}}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStatus {
+    pub phase: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl GenerationStatus {
+    /// The phases a declaration goes through, in order
+    pub const PHASES: [&'static str; 4] = ["resolving_references", "prompting", "writing", "registering"];
+
+    /// What the phase is called on screen; phases this CLI doesn't know are shown as sent
+    pub fn label(&self) -> String {
+        match self.phase.as_str() {
+            "resolving_references" => "Resolving references".to_string(),
+            "prompting" => "Prompting AI".to_string(),
+            "writing" => "Writing tool".to_string(),
+            "registering" => "Registering".to_string(),
+            other => other.replace('_', " "),
+        }
+    }
+
+    /// 1-based position among PHASES, for a `[2/4]` prefix
+    pub fn step(&self) -> Option<usize> {
+        Self::PHASES.iter().position(|p| *p == self.phase).map(|i| i + 1)
+    }
 }
 
 // Response from declaring a relation
@@ -44,6 +82,18 @@ pub struct DeclareRelationResponse {
     pub materialized: bool,
     pub physical_path: String,
     pub status: String,
+    /// What was generated; sent back for previews
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// "create" or "update", when the daemon matched the relation to an existing object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    /// Metadata the object would be stored with; sent back for previews
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+    /// Every VFS path the object would be reachable by; sent back for previews
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
 }
 
 
@@ -85,6 +135,38 @@ impl Relation {
             updated_at: None,
         }
     }
+    
+    /// Tag the relation with the project it was declared in
+    pub fn with_project(mut self, project: &str) -> Self {
+        self.properties.insert("project".to_string(), serde_json::Value::String(project.to_string()));
+        self
+    }
+    
+    /// Mark the relation as a new version of an existing tool rather than a new one
+    pub fn evolving(mut self, path: &str) -> Self {
+        self.properties.insert("evolves".to_string(), serde_json::Value::String(path.to_string()));
+        self
+    }
+    
+    /// Write content from an earlier preview as it is, instead of generating anew
+    pub fn with_generated(mut self, content: &str) -> Self {
+        self.properties.insert("generated".to_string(), serde_json::Value::String(content.to_string()));
+        self
+    }
+    
+    /// Generate the relation as a particular agent
+    pub fn with_agent(mut self, agent: &str) -> Self {
+        self.properties.insert("agent".to_string(), serde_json::Value::String(agent.to_string()));
+        self
+    }
+    
+    /// Extra instructions for generation: an agent's local persona, project notes
+    pub fn with_guidance(mut self, guidance: Option<String>) -> Self {
+        if let Some(guidance) = guidance {
+            self.properties.insert("agent_guidance".to_string(), serde_json::Value::String(guidance));
+        }
+        self
+    }
 }
 
 impl Reference {
@@ -112,6 +194,7 @@ impl RequestBuilder for DeclareRelationRequest {
         let session_context = Some(SessionContext {
             session_id: Some(generate_session_id()),
             agent: None, // CLI sessions don't have AI agent context
+            workspace: None,
         });
         
         Ok(DaemonRequest {
@@ -121,6 +204,7 @@ impl RequestBuilder for DeclareRelationRequest {
             references: self.references.clone(),
             session_context,
             user_prompt: self.user_prompt.clone(),
+            provider: self.provider.clone(),
         })
     }
 }
@@ -135,6 +219,39 @@ impl ResponseParser for DeclareRelationResponse {
 }
 
 
+impl DeclareRelationResponse {
+    /// Whether the daemon only previewed, writing nothing
+    pub fn is_preview(&self) -> bool {
+        self.status == "preview"
+    }
+
+    /// What a preview would write: where, with which metadata, and the content itself
+    pub fn display_preview(&self) {
+        println!("{}", "🔍 Preview: this is what would be written".bright_blue().bold());
+        println!("  {}: {}", "Path".bright_cyan(), self.physical_path.bright_white());
+        let others: Vec<&str> = self.paths.iter().map(String::as_str).filter(|p| *p != self.physical_path).collect();
+        if !others.is_empty() {
+            println!("  {}: {}", "Also at".bright_cyan(), others.join(", "));
+        }
+        if let Some(serde_json::Value::Object(ref metadata)) = self.metadata {
+            println!("  {}:", "Metadata".bright_cyan());
+            for (key, value) in metadata {
+                let value = match value {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                println!("    {}: {}", key.dimmed(), value);
+            }
+        }
+        if let Some(ref content) = self.content {
+            println!("{}", "─".repeat(50).dimmed());
+            let hint = self.metadata.as_ref().and_then(|m| m.get("language")).and_then(|l| l.as_str());
+            println!("{}", crate::display::highlight::highlight(&self.physical_path, content, hint).unwrap_or_else(|| content.clone()));
+            println!("{}", "─".repeat(50).dimmed());
+        }
+    }
+}
+
 // Display implementations
 impl Displayable for DeclareRelationResponse {
     fn display(&self, format: OutputFormat) -> Result<()> {
@@ -142,6 +259,9 @@ impl Displayable for DeclareRelationResponse {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(self)?);
             }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
             OutputFormat::Plain | OutputFormat::Table => {
                 println!("{}", "✨ Relation declared and materialized!".bright_green());
                 println!("  {}: {}", "ID".bright_cyan(), self.relation_id);
@@ -0,0 +1,232 @@
+use super::{DaemonRequest, RequestBuilder, ResponseParser};
+use crate::display::{Displayable, OutputFormat, components::TableBuilder, print_yaml};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use colored::*;
+
+/// What a rule reacts to
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleEvent {
+    ToolCreated,
+    ArtifactCreated,
+    MemoryCreated,
+    CommandRun,
+    /// An event this CLI doesn't know yet
+    #[serde(other)]
+    #[value(skip)]
+    Unknown,
+}
+
+impl RuleEvent {
+    pub fn label(self) -> &'static str {
+        match self {
+            RuleEvent::ToolCreated => "tool_created",
+            RuleEvent::ArtifactCreated => "artifact_created",
+            RuleEvent::MemoryCreated => "memory_created",
+            RuleEvent::CommandRun => "command_run",
+            RuleEvent::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RuleTrigger {
+    pub event: RuleEvent,
+    /// Glob on the subject's name; None matches everything
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RuleAction {
+    /// Shell command the daemon runs; {name} and {path} name the subject
+    pub command: String,
+}
+
+/// A daemon-side reaction: when `trigger` happens, do `action`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Rule {
+    /// Assigned by the daemon; empty on rules being added
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    pub trigger: RuleTrigger,
+    pub action: RuleAction,
+    #[serde(default)]
+    pub fire_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_fired: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl Rule {
+    /// "tool_created matching git-*" style summary of the trigger
+    pub fn when(&self) -> String {
+        match self.trigger.pattern {
+            Some(ref pattern) => format!("{} matching {}", self.trigger.event.label(), pattern),
+            None => self.trigger.event.label().to_string(),
+        }
+    }
+}
+
+impl ResponseParser for Rule {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        // Accept the rule bare or wrapped as {"rule": {...}}
+        let rule = data.get("rule").unwrap_or(data);
+        Ok(serde_json::from_value(rule.clone())?)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListRulesRequest;
+
+impl RequestBuilder for ListRulesRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "list_rules".to_string(),
+            id,
+            payload: json!({}),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AddRuleRequest {
+    pub rule: Rule,
+}
+
+impl RequestBuilder for AddRuleRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "add_rule".to_string(),
+            id,
+            payload: json!({
+                "rule": &self.rule
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Switch a rule on or off without losing it
+#[derive(Debug, Serialize)]
+pub struct SetRuleEnabledRequest {
+    pub rule_id: String,
+    pub enabled: bool,
+}
+
+impl RequestBuilder for SetRuleEnabledRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "update_rule".to_string(),
+            id,
+            payload: json!({
+                "rule_id": &self.rule_id,
+                "enabled": self.enabled
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteRuleRequest {
+    pub rule_id: String,
+}
+
+impl RequestBuilder for DeleteRuleRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "delete_rule".to_string(),
+            id,
+            payload: json!({
+                "rule_id": &self.rule_id
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct RuleList {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+impl ResponseParser for RuleList {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(data.clone())?)
+    }
+}
+
+impl Displayable for RuleList {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => print_yaml(self)?,
+            OutputFormat::Table => {
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["ID", "Name", "Enabled", "When", "Run", "Fired"]);
+                for rule in &self.rules {
+                    table.add_row(vec![
+                        rule.id.clone(),
+                        rule.name.clone(),
+                        if rule.enabled { "yes" } else { "no" }.to_string(),
+                        rule.when(),
+                        rule.action.command.clone(),
+                        rule.fire_count.to_string(),
+                    ]);
+                }
+                table.print();
+            }
+            OutputFormat::Plain => {
+                if self.rules.is_empty() {
+                    println!("{}", "No rules yet. Add one with 'port42 rules add'".dimmed());
+                    return Ok(());
+                }
+                println!("{}", "⚡ Rules".bright_blue().bold());
+                for rule in &self.rules {
+                    let state = if rule.enabled { "●".green() } else { "○".dimmed() };
+                    println!("  {} {} {}", state, rule.name.bright_cyan(), format!("[{}]", rule.id).dimmed());
+                    println!("      {} {} {} {}", "when".dimmed(), rule.when(), "run".dimmed(), rule.action.command);
+                    if let Some(ref description) = rule.description {
+                        println!("      {}", description.dimmed());
+                    }
+                    if rule.fire_count > 0 {
+                        let last = rule.last_fired.as_deref().map(|t| format!(", last {}", t)).unwrap_or_default();
+                        println!("      {}", format!("fired {} times{}", rule.fire_count, last).dimmed());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
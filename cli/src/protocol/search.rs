@@ -1,6 +1,7 @@
 use super::{DaemonRequest, RequestBuilder, ResponseParser};
-use crate::display::{Displayable, OutputFormat, components};
+use crate::display::{Displayable, OutputFormat, components, print_yaml};
 use crate::help_text;
+use crate::common::utils::split_words;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -8,7 +9,7 @@ use colored::*;
 use chrono::{DateTime, Local, NaiveDate, TimeZone};
 
 // Search request types
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SearchFilters {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
@@ -68,10 +69,76 @@ impl RequestBuilder for SearchRequest {
             references: None,
             session_context: None,
             user_prompt: None,
+            provider: None,
         })
     }
 }
 
+/// A search written on one line, as in the interactive `/search` command:
+/// `docker compose --type tool --tag infra --after 2025-01-01 --all`
+#[derive(Debug, Clone)]
+pub struct SearchLine {
+    pub query: String,
+    /// or, and, phrase or semantic, as the CLI flags map them
+    pub mode: String,
+    pub filters: SearchFilters,
+    /// `--all-agents`: don't narrow to the current agent
+    pub all_agents: bool,
+}
+
+impl SearchLine {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut line = SearchLine {
+            query: String::new(),
+            mode: "or".to_string(),
+            filters: SearchFilters::default(),
+            all_agents: false,
+        };
+        let mut terms = Vec::new();
+        let mut tags = Vec::new();
+        let mut words = split_words(input)?.into_iter();
+
+        while let Some(word) = words.next() {
+            let (flag, inline) = match word.split_once('=') {
+                Some((flag, value)) if word.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (word.clone(), None),
+            };
+            let mut value = |name: &str| -> Result<String> {
+                inline.clone().or_else(|| words.next())
+                    .ok_or_else(|| anyhow::anyhow!("{} needs a value", name))
+            };
+            match flag.as_str() {
+                "--all" | "-a" => line.mode = "and".to_string(),
+                "--any" | "-o" => line.mode = "or".to_string(),
+                "--exact" | "-e" => line.mode = "phrase".to_string(),
+                "--semantic" => line.mode = "semantic".to_string(),
+                "--all-agents" => line.all_agents = true,
+                "--type" | "-t" => line.filters.type_filter = Some(value("--type")?),
+                "--path" => line.filters.path = Some(value("--path")?),
+                "--agent" => line.filters.agent = Some(value("--agent")?),
+                "--tag" => tags.extend(value("--tag")?.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty())),
+                "--after" => line.filters.after = Some(parse_date(&value("--after")?)?),
+                "--before" => line.filters.before = Some(parse_date(&value("--before")?)?),
+                "--limit" | "-n" => {
+                    let limit = value("--limit")?;
+                    line.filters.limit = Some(limit.parse().map_err(|_| anyhow::anyhow!("--limit expects a number, got '{}'", limit))?);
+                }
+                _ if flag.starts_with('-') && flag.len() > 1 => anyhow::bail!("Unknown search option '{}'", flag),
+                _ => terms.push(word),
+            }
+        }
+
+        if !tags.is_empty() {
+            line.filters.tags = Some(tags);
+        }
+        line.query = terms.join(" ");
+        if line.query.is_empty() {
+            anyhow::bail!("Nothing to search for");
+        }
+        Ok(line)
+    }
+}
+
 // Search response types
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SearchResponse {
@@ -135,6 +202,9 @@ impl Displayable for SearchResponse {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(self)?);
             }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
             OutputFormat::Table => {
                 self.display_table()?;
             }
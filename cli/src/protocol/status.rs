@@ -1,5 +1,5 @@
 use super::{DaemonRequest, RequestBuilder, ResponseParser};
-use crate::display::{Displayable, OutputFormat};
+use crate::display::{Displayable, OutputFormat, components::{TableBuilder, format_timestamp_relative}, print_yaml};
 use crate::help_text;
 use crate::client::DaemonClient;
 use anyhow::Result;
@@ -19,6 +19,28 @@ impl RequestBuilder for StatusRequest {
             references: None,
             session_context: None,
             user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Status with health probes, for `status --detailed`; the daemon checks
+/// each provider, so it takes longer than a plain status
+#[derive(Debug, Serialize)]
+pub struct DetailedStatusRequest;
+
+impl RequestBuilder for DetailedStatusRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "status".to_string(),
+            id,
+            payload: json!({
+                "detailed": true
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
         })
     }
 }
@@ -30,6 +52,117 @@ pub struct StatusResponse {
     pub active_sessions: u64,
     pub memory_stats: Option<MemoryStats>,
     pub recent_activity: Option<Vec<RecentActivity>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Daemon build version, for compatibility checks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Daemon wall clock (RFC 3339), for skew checks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    /// Probe results; only sent for a detailed status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthReport>,
+}
+
+/// Index older than this is reported as stale
+const INDEX_STALE_HOURS: i64 = 24;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HealthReport {
+    /// One probe per configured AI provider
+    pub providers: Vec<ProviderProbe>,
+    /// Mean time to answer a request, over the daemon's recent requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_latency_ms: Option<f64>,
+    /// Requests waiting for a free worker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queued_requests: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_store_bytes: Option<u64>,
+    /// When the search index last caught up (RFC 3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_updated_at: Option<String>,
+    /// Objects written since then
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unindexed_objects: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ProviderProbe {
+    pub name: String,
+    pub reachable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckState {
+    Ok,
+    Warn,
+    Fail,
+    /// A measurement with nothing to judge it against
+    Info,
+}
+
+/// One line of the health report
+#[derive(Debug, PartialEq)]
+pub struct HealthCheck {
+    pub name: String,
+    pub value: String,
+    pub state: CheckState,
+}
+
+impl HealthReport {
+    /// The report as named checks, in display order
+    pub fn checks(&self) -> Vec<HealthCheck> {
+        let check = |name: &str, value: String, state| HealthCheck { name: name.to_string(), value, state };
+        let mut checks: Vec<HealthCheck> = self.providers.iter().map(|p| {
+            let value = match (p.reachable, p.latency_ms, &p.error) {
+                (true, Some(ms), _) => format!("reachable ({}ms)", ms),
+                (true, None, _) => "reachable".to_string(),
+                (false, _, Some(error)) => format!("unreachable: {}", error),
+                (false, _, None) => "unreachable".to_string(),
+            };
+            check(&format!("Provider {}", p.name), value, if p.reachable { CheckState::Ok } else { CheckState::Fail })
+        }).collect();
+
+        if let Some(ms) = self.avg_latency_ms {
+            checks.push(check("Avg latency", format!("{:.0}ms", ms), CheckState::Info));
+        }
+        if let Some(queued) = self.queued_requests {
+            checks.push(check("Queued requests", queued.to_string(), CheckState::Info));
+        }
+        let size = self.object_store_bytes.map(|bytes| super::file_ops::format_size(bytes as i64));
+        let store = match (self.object_count, size) {
+            (Some(count), Some(size)) => Some(format!("{} objects, {}", count, size)),
+            (Some(count), None) => Some(format!("{} objects", count)),
+            (None, size) => size,
+        };
+        if let Some(store) = store {
+            checks.push(check("Object store", store, CheckState::Info));
+        }
+        if let Some(ref updated) = self.index_updated_at {
+            let waiting = self.unindexed_objects.unwrap_or(0);
+            let stale = chrono::DateTime::parse_from_rfc3339(updated)
+                .map(|t| chrono::Utc::now().signed_duration_since(t).num_hours() >= INDEX_STALE_HOURS)
+                .unwrap_or(false);
+            let mut value = match chrono::DateTime::parse_from_rfc3339(updated) {
+                Ok(t) => format!("updated {}", format_timestamp_relative(t.timestamp_millis().max(0) as u64)),
+                Err(_) => format!("updated {}", updated),
+            };
+            if waiting > 0 {
+                value.push_str(&format!(", {} objects waiting", waiting));
+            }
+            checks.push(check("Search index", value, if stale { CheckState::Warn } else { CheckState::Ok }));
+        }
+        checks
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -73,6 +206,21 @@ impl ResponseParser for StatusResponse {
             
         let recent_activity = data.get("recent_activity")
             .and_then(|v| serde_json::from_value(v.clone()).ok());
+            
+        let provider = data.get("provider")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        
+        let version = data.get("version")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        
+        let time = data.get("time")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        
+        let health = data.get("health")
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
         
         Ok(StatusResponse {
             port,
@@ -80,6 +228,10 @@ impl ResponseParser for StatusResponse {
             active_sessions,
             memory_stats,
             recent_activity,
+            provider,
+            version,
+            time,
+            health,
         })
     }
 }
@@ -90,6 +242,9 @@ impl Displayable for StatusResponse {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(self)?);
             }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
             OutputFormat::Plain => {
                 println!("{}", help_text::MSG_DAEMON_RUNNING.green().bold());
                 println!("\n{}", help_text::MSG_CONNECTION_INFO.bright_white());
@@ -107,14 +262,73 @@ impl Displayable for StatusResponse {
                 println!("\n{}", help_text::MSG_DOLPHINS_LISTENING.blue().italic());
             }
             OutputFormat::Table => {
-                // Status doesn't really make sense as a table, use plain format
-                self.display(OutputFormat::Plain)?;
+                let mut table = TableBuilder::new();
+                table.add_header(vec!["Check", "Value", "State"]);
+                table.add_row(vec!["Port".to_string(), self.port.to_string(), String::new()]);
+                table.add_row(vec!["Uptime".to_string(), self.uptime.clone(), String::new()]);
+                table.add_row(vec!["Active sessions".to_string(), self.active_sessions.to_string(), String::new()]);
+                if let Some(ref version) = self.version {
+                    table.add_row(vec!["Version".to_string(), version.clone(), String::new()]);
+                }
+                if let Some(ref provider) = self.provider {
+                    table.add_row(vec!["AI provider".to_string(), provider.clone(), String::new()]);
+                }
+                for check in self.health.iter().flat_map(HealthReport::checks) {
+                    let state = match check.state {
+                        CheckState::Ok => "ok",
+                        CheckState::Warn => "warn",
+                        CheckState::Fail => "FAIL",
+                        CheckState::Info => "",
+                    };
+                    table.add_row(vec![check.name, check.value, state.to_string()]);
+                }
+                table.print();
             }
         }
         Ok(())
     }
 }
 
+impl StatusResponse {
+    /// Extra sections shown by `status --detailed`
+    pub fn display_details(&self) {
+        println!("\n  {}", "AI Provider:".yellow());
+        match self.provider {
+            Some(ref provider) => {
+                println!("    Active:     {}", provider.bright_cyan());
+            }
+            None => {
+                println!("    Active:     {}", "not reported by daemon".dimmed());
+            }
+        }
+        
+        let config = crate::config::Config::load_or_default();
+        if let Some(ref configured) = config.provider {
+            println!("    Configured: {}", configured.bright_cyan());
+        }
+        if let Some(ref model) = config.model {
+            println!("    Model:      {}", model.bright_cyan());
+        }
+        
+        let Some(ref health) = self.health else {
+            println!("\n  {}", "Health: not reported by daemon".dimmed());
+            return;
+        };
+        println!("\n  {}", "Health:".yellow());
+        let checks = health.checks();
+        let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+        for check in checks {
+            let (mark, value) = match check.state {
+                CheckState::Ok => ("✓".green(), check.value.bright_cyan()),
+                CheckState::Warn => ("!".yellow(), check.value.yellow()),
+                CheckState::Fail => ("✗".red(), check.value.red()),
+                CheckState::Info => ("·".dimmed(), check.value.bright_cyan()),
+            };
+            println!("    {} {:<width$}  {}", mark, check.name, value);
+        }
+    }
+}
+
 // Watch request function for real-time monitoring
 pub fn send_watch_request(port: u16, target: &str) -> Result<serde_json::Value> {
     let mut client = DaemonClient::new(port);
@@ -130,6 +344,7 @@ pub fn send_watch_request(port: u16, target: &str) -> Result<serde_json::Value>
         references: None,
         session_context: None,
         user_prompt: None,
+        provider: None,
     };
     
     let response = client.request(request)?;
@@ -1,7 +1,9 @@
-use super::{DaemonRequest, RequestBuilder, ResponseParser};
+use super::{DaemonRequest, ProviderSelection, RequestBuilder, ResponseParser, SessionContext};
+use crate::common::workspace::WorkspaceContext;
 use crate::protocol::relations::Reference;
-use crate::display::{Displayable, OutputFormat, StatusIndicator};
+use crate::display::{Displayable, OutputFormat, StatusIndicator, print_yaml};
 use crate::help_text;
+use crate::common::{pricing::estimate_cost, providers};
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -31,6 +33,14 @@ pub struct SwimRequest {
     pub references: Option<Vec<Reference>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub approval_response: Option<ApprovalResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ProviderSelection>,
+    /// Extra agent instructions from ~/.port42/agents.toml
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guidance: Option<String>,
+    /// Where the session is running, when workspace context is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<WorkspaceContext>,
 }
 
 impl RequestBuilder for SwimRequest {
@@ -45,6 +55,11 @@ impl RequestBuilder for SwimRequest {
             payload["memory_context"] = json!(context);
         }
         
+        // Add locally defined agent guidance if present
+        if let Some(ref guidance) = self.guidance {
+            payload["agent_guidance"] = json!(guidance);
+        }
+        
         // Add approval response if present
         if let Some(ref approval) = self.approval_response {
             payload["approval_response"] = json!(approval);
@@ -55,8 +70,37 @@ impl RequestBuilder for SwimRequest {
             id,
             payload,
             references: self.references.clone(),
-            session_context: None,
+            session_context: self.workspace.clone().map(|workspace| SessionContext {
+                session_id: None,
+                agent: None,
+                workspace: Some(workspace),
+            }),
             user_prompt: None, // Will be populated when CLI adds --prompt parameter
+            provider: self.provider.clone(),
+        })
+    }
+}
+
+/// Record a mid-session change (e.g. a provider switch) in the session's metadata
+#[derive(Debug, Serialize)]
+pub struct SessionMetadataRequest {
+    pub session_id: String,
+    pub metadata: serde_json::Value,
+}
+
+impl RequestBuilder for SessionMetadataRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "session_metadata".to_string(),
+            id,
+            payload: json!({
+                "session_id": &self.session_id,
+                "metadata": &self.metadata,
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
         })
     }
 }
@@ -73,6 +117,33 @@ pub struct SwimResponse {
     pub artifact_generated: bool,
     pub artifact_spec: Option<ArtifactSpec>,
     pub approval_needed: Option<ApprovalRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+}
+
+/// Tokens the provider billed for one exchange
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+    
+    pub fn add(&mut self, other: TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+    }
+    
+    /// Estimated cost in USD at the provider's (or model's) list price
+    pub fn cost(&self, provider: Option<&ProviderSelection>) -> f64 {
+        let name = provider.and_then(|p| p.name.as_deref()).unwrap_or(providers::DEFAULT_PROVIDER);
+        let model = provider.and_then(|p| p.model.as_deref());
+        estimate_cost(name, model, self.input_tokens, self.output_tokens)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -137,6 +208,9 @@ impl ResponseParser for SwimResponse {
         let approval_needed = data.get("approval_needed")
             .and_then(|approval| serde_json::from_value(approval.clone()).ok());
         
+        let usage = data.get("usage")
+            .and_then(|usage| serde_json::from_value(usage.clone()).ok());
+        
         Ok(SwimResponse {
             message,
             session_id,
@@ -146,6 +220,7 @@ impl ResponseParser for SwimResponse {
             artifact_generated,
             artifact_spec,
             approval_needed,
+            usage,
         })
     }
 }
@@ -156,6 +231,9 @@ impl Displayable for SwimResponse {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(self)?);
             }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
             OutputFormat::Plain | OutputFormat::Table => {
                 // Display AI message
                 println!("\n{}", self.agent.bright_blue());
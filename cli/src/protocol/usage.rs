@@ -0,0 +1,326 @@
+use super::{DaemonRequest, RequestBuilder, ResponseParser};
+use crate::common::pricing::estimate_cost;
+use crate::display::{Displayable, OutputFormat, components::TableBuilder, print_yaml};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use chrono::{DateTime, Utc};
+use colored::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize)]
+pub struct UsageRequest {
+    /// RFC 3339 lower bound for sessions to include
+    pub since: Option<String>,
+    /// RFC 3339 upper bound for sessions to include
+    pub until: Option<String>,
+}
+
+impl RequestBuilder for UsageRequest {
+    fn build_request(&self, id: String) -> Result<DaemonRequest> {
+        Ok(DaemonRequest {
+            request_type: "usage".to_string(),
+            id,
+            payload: json!({
+                "since": self.since,
+                "until": self.until
+            }),
+            references: None,
+            session_context: None,
+            user_prompt: None,
+            provider: None,
+        })
+    }
+}
+
+/// Token counts the daemon recorded in one session's metadata
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UsageRecord {
+    pub session_id: String,
+    pub agent: String,
+    pub provider: String,
+    pub model: Option<String>,
+    /// Day of activity, YYYY-MM-DD
+    pub date: String,
+    /// Latest activity in the record, from daemons that report it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Cost reported by the daemon, otherwise estimated locally
+    pub cost: f64,
+}
+
+impl UsageRecord {
+    /// Whether the record has activity at or after `since`. Records without a
+    /// timestamp only know their day, so the whole day counts.
+    pub fn is_since(&self, since: DateTime<Utc>) -> bool {
+        match self.timestamp {
+            Some(timestamp) => timestamp >= since,
+            None => self.date >= since.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UsageResponse {
+    pub records: Vec<UsageRecord>,
+}
+
+impl ResponseParser for UsageResponse {
+    type Output = Self;
+
+    fn parse_response(data: &serde_json::Value) -> Result<Self> {
+        let records = data.get("records")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(parse_usage_record).collect())
+            .unwrap_or_default();
+
+        Ok(UsageResponse { records })
+    }
+}
+
+fn parse_usage_record(value: &serde_json::Value) -> Option<UsageRecord> {
+    let provider = value.get("provider")
+        .and_then(|v| v.as_str())
+        .unwrap_or(crate::common::providers::DEFAULT_PROVIDER)
+        .to_string();
+    let model = value.get("model")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let input_tokens = value.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+    let output_tokens = value.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+    let cost = value.get("cost")
+        .and_then(|v| v.as_f64())
+        .unwrap_or_else(|| estimate_cost(&provider, model.as_deref(), input_tokens, output_tokens));
+
+    Some(UsageRecord {
+        session_id: value.get("session_id")?.as_str()?.to_string(),
+        agent: value.get("agent")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string(),
+        provider,
+        model,
+        date: value.get("date")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        timestamp: value.get("timestamp")
+            .and_then(|v| v.as_str())
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|t| t.with_timezone(&Utc)),
+        input_tokens,
+        output_tokens,
+        cost,
+    })
+}
+
+/// Tokens a session has spent, as recorded in its metadata
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SessionUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Cost reported by the daemon, otherwise estimated locally
+    pub cost: f64,
+}
+
+impl SessionUsage {
+    pub fn parse(value: &serde_json::Value) -> Option<Self> {
+        if !value.is_object() {
+            return None;
+        }
+        let provider = value.get("provider").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let model = value.get("model").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let input_tokens = value.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+        let output_tokens = value.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+        let cost = value.get("cost")
+            .and_then(|v| v.as_f64())
+            .unwrap_or_else(|| estimate_cost(
+                provider.as_deref().unwrap_or(crate::common::providers::DEFAULT_PROVIDER),
+                model.as_deref(),
+                input_tokens,
+                output_tokens,
+            ));
+        Some(SessionUsage { input_tokens, output_tokens, provider, model, cost })
+    }
+}
+
+/// Totals for one row of the report
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct UsageTotals {
+    pub sessions: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost: f64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, record: &UsageRecord) {
+        self.sessions += 1;
+        self.input_tokens += record.input_tokens;
+        self.output_tokens += record.output_tokens;
+        self.cost += record.cost;
+    }
+}
+
+/// Usage grouped by provider, agent and day
+#[derive(Debug, Serialize)]
+pub struct UsageReport {
+    pub since: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    pub total: UsageTotals,
+    pub by_provider: BTreeMap<String, UsageTotals>,
+    pub by_agent: BTreeMap<String, UsageTotals>,
+    pub by_day: BTreeMap<String, UsageTotals>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BudgetStatus {
+    pub monthly_budget: f64,
+    pub month_to_date: f64,
+}
+
+impl BudgetStatus {
+    pub fn percent_used(&self) -> f64 {
+        if self.monthly_budget <= 0.0 {
+            return 0.0;
+        }
+        self.month_to_date / self.monthly_budget * 100.0
+    }
+}
+
+impl UsageReport {
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a UsageRecord>, since: Option<String>) -> Self {
+        let mut report = UsageReport {
+            since,
+            until: None,
+            total: UsageTotals::default(),
+            by_provider: BTreeMap::new(),
+            by_agent: BTreeMap::new(),
+            by_day: BTreeMap::new(),
+            budget: None,
+        };
+
+        for record in records {
+            report.total.add(record);
+            report.by_provider.entry(record.provider.clone()).or_default().add(record);
+            report.by_agent.entry(record.agent.clone()).or_default().add(record);
+            report.by_day.entry(record.date.clone()).or_default().add(record);
+        }
+
+        report
+    }
+
+    /// "from 2024-06-01 to 2024-06-30", "since 2024-06-01", or "since all time"
+    fn window(&self) -> String {
+        match (&self.since, &self.until) {
+            (Some(since), Some(until)) => format!("from {} to {}", since, until),
+            (None, Some(until)) => format!("up to {}", until),
+            (Some(since), None) => format!("since {}", since),
+            (None, None) => "since all time".to_string(),
+        }
+    }
+
+    fn print_table(title: &str, key_header: &str, rows: &BTreeMap<String, UsageTotals>) {
+        println!("\n{}", title.bright_cyan().bold());
+        let mut table = TableBuilder::new();
+        table.add_header(vec![key_header, "Sessions", "Input", "Output", "Est. Cost"]);
+        for (key, totals) in rows {
+            table.add_row(vec![
+                key.clone(),
+                totals.sessions.to_string(),
+                format_tokens(totals.input_tokens),
+                format_tokens(totals.output_tokens),
+                format!("${:.2}", totals.cost),
+            ]);
+        }
+        table.print();
+    }
+
+    fn display_budget(&self) {
+        if let Some(ref budget) = self.budget {
+            let percent = budget.percent_used();
+            let line = format!(
+                "💰 Month to date: ${:.2} of ${:.2} budget ({:.0}%)",
+                budget.month_to_date, budget.monthly_budget, percent
+            );
+            if percent >= 100.0 {
+                println!("\n{}", line.red().bold());
+                println!("{}", "⚠️  Monthly budget exceeded".red());
+            } else if percent >= 80.0 {
+                println!("\n{}", line.yellow());
+                println!("{}", "⚠️  Approaching monthly budget".yellow());
+            } else {
+                println!("\n{}", line.dimmed());
+            }
+        }
+    }
+}
+
+impl Displayable for UsageReport {
+    fn display(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(self)?);
+            }
+            OutputFormat::Yaml => {
+                print_yaml(self)?;
+            }
+            OutputFormat::Plain => {
+                println!("{}", format!("📊 AI usage {}", self.window()).bright_blue().bold());
+                println!();
+                if self.total.sessions == 0 {
+                    println!("{}", "No recorded usage in this window.".dimmed());
+                } else {
+                    println!("  Sessions:      {}", self.total.sessions.to_string().bright_cyan());
+                    println!("  Input tokens:  {}", format_tokens(self.total.input_tokens).bright_cyan());
+                    println!("  Output tokens: {}", format_tokens(self.total.output_tokens).bright_cyan());
+                    println!("  Est. cost:     {}", format!("${:.2}", self.total.cost).bright_green());
+                    for (provider, totals) in &self.by_provider {
+                        println!("    {:<10} ${:.2}", provider, totals.cost);
+                    }
+                }
+                self.display_budget();
+            }
+            OutputFormat::Table => {
+                println!("{}", format!("📊 AI usage {}", self.window()).bright_blue().bold());
+                Self::print_table("By provider", "Provider", &self.by_provider);
+                Self::print_table("By agent", "Agent", &self.by_agent);
+                Self::print_table("By day", "Day", &self.by_day);
+                println!("\n  Total: {} sessions, {}",
+                    self.total.sessions,
+                    format!("${:.2}", self.total.cost).bright_green());
+                self.display_budget();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Token counts as 950, 12.3k or 1.2M
+pub fn format_tokens(count: u64) -> String {
+    if count >= 1_000_000 {
+        format!("{:.1}M", count as f64 / 1_000_000.0)
+    } else if count >= 1_000 {
+        format!("{:.1}k", count as f64 / 1_000.0)
+    } else {
+        count.to_string()
+    }
+}
+
+/// Dollars to the cent, or to a hundredth of a cent for single exchanges
+pub fn format_cost(cost: f64) -> String {
+    if cost > 0.0 && cost < 0.01 {
+        format!("${:.4}", cost)
+    } else {
+        format!("${:.2}", cost)
+    }
+}
@@ -1,18 +1,184 @@
 use anyhow::Result;
 use colored::*;
-use rustyline::{DefaultEditor, error::ReadlineError};
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::{FileHistory, History};
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{CompletionType, Config, Context, EditMode, Editor, Helper, error::ReadlineError};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use crate::client::{KeepAlive, SharedClient};
+use crate::common::shell_history::{self, DEFAULT_HISTORY_SIZE};
+use crate::common::shell_input::split_commands;
 use crate::commands::*;
 use crate::boot::{show_boot_sequence, show_connection_progress};
 use crate::help_text::*;
+use crate::display::OutputFormat;
+
+/// Commands the shell handles itself
+pub const SHELL_COMMANDS: &[&str] = &[
+    "help", "exit", "quit", "clear", "status", "reality", "swim", "memory",
+    "evolve", "daemon", "ls", "tree", "cat", "info", "cp", "mv", "search", "run",
+    "history",
+];
+
+const DAEMON_ACTIONS: &[&str] = &["start", "stop", "restart", "status"];
+
+/// How long a directory listing is reused before asking the daemon again
+const LISTING_TTL: Duration = Duration::from_secs(5);
+
+/// How long the shared connection may sit idle before it is pinged
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Entries of one VFS directory as (name, is_directory), and when they were fetched
+type Listing = (Instant, Vec<(String, bool)>);
 
 pub struct Port42Shell {
     port: u16,
     running: bool,
-    editor: DefaultEditor,
+    editor: Editor<ShellHelper, FileHistory>,
     history_path: PathBuf,
+    /// Most commands kept in the history file
+    history_size: usize,
+    /// Used by every command and by tab completion
+    client: SharedClient,
+    /// From `[aliases]` in config.toml, expanded before each command
+    aliases: BTreeMap<String, String>,
+    builtins: Vec<String>,
+}
+
+/// Tab completion for commands, agents, sessions and virtual paths
+struct ShellHelper {
+    client: SharedClient,
+    listings: RefCell<HashMap<String, Listing>>,
+}
+
+impl ShellHelper {
+    fn new(client: SharedClient) -> Self {
+        Self {
+            client,
+            listings: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Directory entries, cached briefly so each <Tab> doesn't hit the daemon
+    fn list_dir(&self, dir: &str) -> Vec<(String, bool)> {
+        if let Some((at, entries)) = self.listings.borrow().get(dir) {
+            if at.elapsed() < LISTING_TTL {
+                return entries.clone();
+            }
+        }
+        let entries = self.client.with(|client| completions::list_dir_with(client, dir));
+        self.listings.borrow_mut().insert(dir.to_string(), (Instant::now(), entries.clone()));
+        entries
+    }
+
+    fn complete_command(&self, word: &str) -> Vec<Pair> {
+        let tools = std::fs::read_dir(crate::config::port42_dir().join("commands"))
+            .map(|dir| dir.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().into_owned()).collect())
+            .unwrap_or_else(|_| Vec::new());
+        let mut names: Vec<String> = SHELL_COMMANDS.iter().map(|c| c.to_string()).chain(tools).collect();
+        names.sort();
+        names.dedup();
+        matching(names, word)
+    }
+
+    fn complete_path(&self, word: &str) -> Vec<Pair> {
+        let (dir, prefix) = match word.rfind('/') {
+            Some(i) => (&word[..=i], &word[i + 1..]),
+            None => ("/", word),
+        };
+        self.list_dir(dir.trim_end_matches('/'))
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(prefix))
+            .map(|(name, is_dir)| {
+                let suffix = if is_dir { "/" } else { "" };
+                Pair {
+                    display: format!("{}{}", name, suffix),
+                    replacement: format!("{}{}{}", dir, name, suffix),
+                }
+            })
+            .collect()
+    }
+
+    fn complete_tool(&self, word: &str) -> Vec<Pair> {
+        if word.starts_with('/') {
+            return self.complete_path(word);
+        }
+        let tools = self.list_dir("/commands").into_iter()
+            .filter(|(_, is_dir)| !is_dir)
+            .map(|(name, _)| name);
+        matching(tools.collect(), word)
+    }
+
+    fn complete_session(&self, word: &str) -> Vec<Pair> {
+        let sessions = self.list_dir("/memory").into_iter().map(|(name, _)| name);
+        matching(std::iter::once("search".to_string()).chain(sessions).collect(), word)
+    }
+}
+
+fn complete_agent(word: &str) -> Vec<Pair> {
+    let agents = completions::complete_agent(std::ffi::OsStr::new(word))
+        .into_iter()
+        .map(|c| c.get_value().to_string_lossy().into_owned())
+        .collect();
+    matching(agents, word)
+}
+
+fn matching(candidates: Vec<String>, word: &str) -> Vec<Pair> {
+    candidates.into_iter()
+        .filter(|c| c.starts_with(word))
+        .map(|c| Pair { display: c.clone(), replacement: c })
+        .collect()
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map(|i| i + 1).unwrap_or(0);
+        let word = &line[start..];
+        let before: Vec<&str> = line[..start].split_whitespace().collect();
+
+        let candidates = match before.as_slice() {
+            [] => self.complete_command(word),
+            ["swim"] => complete_agent(word),
+            ["reality", .., "--agent" | "-a"] => complete_agent(word),
+            ["ls" | "tree" | "cat" | "info"] => self.complete_path(word),
+            ["cp" | "mv", ..] if before.len() <= 2 => self.complete_path(word),
+            ["memory"] => self.complete_session(word),
+            ["run"] => self.complete_tool(word),
+            ["daemon"] => matching(DAEMON_ACTIONS.iter().map(|a| a.to_string()).collect(), word),
+            ["help"] => matching(SHELL_COMMANDS.iter().map(|c| c.to_string()).collect(), word),
+            _ => Vec::new(),
+        };
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+/// Enter on a line ending in `\` or inside a quote starts another line
+impl Validator for ShellHelper {
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        if split_commands(ctx.input()).incomplete {
+            Ok(ValidationResult::Incomplete)
+        } else {
+            Ok(ValidationResult::Valid(None))
+        }
+    }
 }
 
+impl Helper for ShellHelper {}
+
 impl Port42Shell {
     pub fn new(port: u16) -> Self {
         // Set up history file path
@@ -21,19 +187,53 @@ impl Port42Shell {
             .join(".port42")
             .join("shell_history");
         
-        // Create editor with history
-        let mut editor = DefaultEditor::new().unwrap();
+        let settings = crate::config::Config::load_or_default();
+        let history_size = settings.shell.as_ref()
+            .and_then(|s| s.history_size)
+            .unwrap_or(DEFAULT_HISTORY_SIZE)
+            .max(1);
         
-        // Load history if it exists
-        if history_path.exists() {
-            let _ = editor.load_history(&history_path);
-        }
+        // Create editor with history, Ctrl+R search and tab completion
+        let config = Config::builder()
+            .completion_type(CompletionType::List)
+            .edit_mode(EditMode::Emacs)
+            .max_history_size(history_size).unwrap()
+            .history_ignore_dups(true).unwrap()
+            .history_ignore_space(true)
+            .bracketed_paste(true)
+            .build();
+        let mut editor = Editor::with_config(config).unwrap();
+        let client = SharedClient::new(port);
+        editor.set_helper(Some(ShellHelper::new(client.clone())));
         
-        Self {
+        let mut shell = Self {
             port,
             running: true,
             editor,
             history_path,
+            history_size,
+            client,
+            aliases: settings.aliases,
+            builtins: alias::builtins(),
+        };
+        
+        // Load history if it exists, dropping repeats left by older versions
+        if shell.history_path.exists() && shell.editor.load_history(&shell.history_path).is_ok() {
+            let entries = shell_history::dedup(&shell.history_entries(), history_size);
+            shell.set_history(entries);
+        }
+        shell
+    }
+    
+    fn history_entries(&self) -> Vec<String> {
+        self.editor.history().iter().cloned().collect()
+    }
+    
+    fn set_history(&mut self, entries: Vec<String>) {
+        let history = self.editor.history_mut();
+        let _ = history.clear();
+        for entry in entries {
+            let _ = history.add_owned(entry);
         }
     }
     
@@ -45,23 +245,53 @@ impl Port42Shell {
         println!("{}", MSG_SHELL_HELP_HINT.dimmed());
         println!();
         
+        let _keepalive: KeepAlive = self.client.start_keepalive(KEEPALIVE_INTERVAL);
+        
         // Main shell loop
         while self.running {
             // Read input with rustyline
             match self.editor.readline(SHELL_PROMPT) {
                 Ok(line) => {
-                    let input = line.trim();
+                    let mut input = line.trim().to_string();
                     
                     if input.is_empty() {
                         continue;
                     }
                     
-                    // Add to history
-                    self.editor.add_history_entry(input)?;
+                    // `history <N>` runs entry N again, and is remembered as that command
+                    match shell_history::recall(&self.history_entries(), &input) {
+                        Ok(Some(entry)) => {
+                            println!("{}", entry.dimmed());
+                            input = entry;
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            eprintln!("{}: {}", MSG_SHELL_ERROR.red(), e);
+                            continue;
+                        }
+                    }
                     
-                    // Parse and execute command
-                    if let Err(e) = self.execute_command(input) {
-                        eprintln!("{}: {}", MSG_SHELL_ERROR.red(), e);
+                    // A leading space keeps a command out of the history
+                    if !line.starts_with(' ') {
+                        let entries = shell_history::remember(&self.history_entries(), &input, self.history_size);
+                        self.set_history(entries);
+                    }
+                    
+                    // A pasted or continued block may hold several commands; run them
+                    // in order and stop at the first that fails
+                    let commands = split_commands(&input).commands;
+                    let echo = commands.len() > 1;
+                    for command in &commands {
+                        if echo {
+                            println!("{} {}", "›".dimmed(), command.dimmed());
+                        }
+                        if let Err(e) = self.execute_command(command) {
+                            eprintln!("{}: {}", MSG_SHELL_ERROR.red(), e);
+                            break;
+                        }
+                        if !self.running {
+                            break;
+                        }
                     }
                     
                     // Save history after each command
@@ -89,7 +319,8 @@ impl Port42Shell {
     }
     
     fn execute_command(&mut self, input: &str) -> Result<()> {
-        let parts: Vec<&str> = input.split_whitespace().collect();
+        let words = crate::common::aliases::expand_line(input, &self.aliases, &self.builtins)?;
+        let parts: Vec<&str> = words.iter().map(String::as_str).collect();
         if parts.is_empty() {
             return Ok(());
         }
@@ -111,9 +342,24 @@ impl Port42Shell {
             "clear" => {
                 print!("\x1B[2J\x1B[1;1H");
             }
+            "history" => {
+                // With a path it's the object's versions, as on the command line
+                if let Some(path) = parts.get(1) {
+                    let path = path.to_string();
+                    self.client.with(|client| history::handle_history(client, path, OutputFormat::Plain))?;
+                    return Ok(());
+                }
+                let entries = self.history_entries();
+                let width = entries.len().to_string().len();
+                for (i, entry) in entries.iter().enumerate() {
+                    let entry = entry.replace('\n', &format!("\n  {}  ", " ".repeat(width)));
+                    println!("  {}  {}", format!("{:>width$}", i + 1).dimmed(), entry);
+                }
+                println!("{}", "Run one again with 'history <N>'; Ctrl+R searches as you type".dimmed());
+            }
             "status" => {
                 let detailed = parts.get(1).map(|&s| s == "--detailed").unwrap_or(false);
-                status::handle_status(self.port, detailed)?;
+                self.client.with(|client| status::handle_status_with_format(client, detailed, OutputFormat::Plain))?;
             }
             "reality" => {
                 let verbose = parts.contains(&"--verbose") || parts.contains(&"-v");
@@ -199,7 +445,7 @@ impl Port42Shell {
                 
                 // Use the reference-aware handler if we have references
                 if ref_option.is_some() {
-                    swim::handle_swim_with_references(self.port, agent, message, session, ref_option, false)?;
+                    swim::handle_swim_with_references(self.port, agent, message, session, ref_option, Default::default(), false)?;
                 } else {
                     swim::handle_swim_no_boot(self.port, agent, message, session)?;
                 }
@@ -220,6 +466,15 @@ impl Port42Shell {
                             query,
                             limit: 10 
                         })
+                    } else if parts[1] == "fork" || parts[1] == "merge" {
+                        let parsed = if parts[1] == "fork" { memory::parse_fork(&parts[2..]) } else { memory::parse_merge(&parts[2..]) };
+                        match parsed {
+                            Ok(action) => Some(action),
+                            Err(e) => {
+                                println!("{}", e.to_string().red());
+                                return Ok(());
+                            }
+                        }
                     } else {
                         // Treat first arg as session ID
                         Some(MemoryAction::Show { 
@@ -246,7 +501,14 @@ impl Port42Shell {
                     None
                 };
                 
-                evolve::handle_evolve(self.port, command, message)?;
+                evolve::handle_evolve(self.port, evolve::EvolveArgs {
+                    command,
+                    prompt: message,
+                    references: Vec::new(),
+                    agent: None,
+                    provider: Default::default(),
+                    dry_run: false,
+                })?;
             }
             "daemon" => {
                 if parts.len() < 2 {
@@ -261,7 +523,7 @@ impl Port42Shell {
                     "restart" => DaemonAction::Restart,
                     "status" => {
                         // Just check status directly
-                        status::handle_status(self.port, false)?;
+                        self.client.with(|client| status::handle_status_with_format(client, false, OutputFormat::Plain))?;
                         return Ok(());
                     }
                     _ => {
@@ -274,8 +536,12 @@ impl Port42Shell {
             }
             "ls" => {
                 let path = parts.get(1).map(|s| s.to_string());
-                let mut client = crate::client::DaemonClient::new(self.port);
-                ls::handle_ls(&mut client, path)?;
+                self.client.with(|client| ls::handle_ls(client, path))?;
+            }
+            "tree" => {
+                let path = parts.get(1).map(|s| s.to_string());
+                let options = tree::TreeOptions { level: None, types: Vec::new(), sizes: false };
+                self.client.with(|client| tree::handle_tree(client, path, &options, OutputFormat::Plain))?;
             }
             "cat" => {
                 if parts.len() < 2 {
@@ -283,8 +549,7 @@ impl Port42Shell {
                     println!("{}", ERR_CAT_EXAMPLE.dimmed());
                     return Ok(());
                 }
-                let mut client = crate::client::DaemonClient::new(self.port);
-                cat::handle_cat(&mut client, parts[1].to_string())?;
+                self.client.with(|client| cat::handle_cat(client, parts[1].to_string()))?;
             }
             "info" => {
                 if parts.len() < 2 {
@@ -292,8 +557,21 @@ impl Port42Shell {
                     println!("{}", ERR_INFO_EXAMPLE.dimmed());
                     return Ok(());
                 }
-                let mut client = crate::client::DaemonClient::new(self.port);
-                info::handle_info(&mut client, parts[1].to_string())?;
+                self.client.with(|client| info::handle_info(client, parts[1].to_string()))?;
+            }
+            "cp" | "mv" => {
+                if parts.len() < 3 {
+                    let (usage, example) = if parts[0] == "cp" { (ERR_CP_USAGE, ERR_CP_EXAMPLE) } else { (ERR_MV_USAGE, ERR_MV_EXAMPLE) };
+                    println!("{}", usage.red());
+                    println!("{}", example.dimmed());
+                    return Ok(());
+                }
+                let (source, destination) = (parts[1].to_string(), parts[2].to_string());
+                if parts[0] == "cp" {
+                    self.client.with(|client| cp::handle_cp(client, source, destination, false))?;
+                } else {
+                    self.client.with(|client| mv::handle_mv(client, source, destination, false))?;
+                }
             }
             "search" => {
                 if parts.len() < 2 {
@@ -305,9 +583,8 @@ impl Port42Shell {
                 
                 // Basic search - just query, no filters from shell yet
                 let query = parts[1..].join(" ");
-                let mut client = crate::client::DaemonClient::new(self.port);
-                search::handle_search(
-                    &mut client,
+                self.client.with(|client| search::handle_search(
+                    client,
                     query,
                     "or",      // default mode
                     None,      // path
@@ -317,13 +594,23 @@ impl Port42Shell {
                     None,      // agent
                     vec![],    // tags
                     None,      // limit
-                )?;
+                ))?;
+            }
+            "run" => {
+                if parts.len() < 2 {
+                    println!("{}", ERR_RUN_USAGE.red());
+                    println!("{}", ERR_RUN_EXAMPLE.dimmed());
+                    return Ok(());
+                }
+                let (name, args) = (parts[1].to_string(), parts[2..].iter().map(|s| s.to_string()).collect());
+                let code = self.client.with(|client| run::handle_run(client, name, args, false))?;
+                if code != 0 {
+                    eprintln!("{}: Command exited with code {}", MSG_SHELL_ERROR.red(), code);
+                }
             }
             _ => {
                 // Try to execute as Port 42 command or system command
-                if let Err(e) = self.execute_external_command(&parts) {
-                    eprintln!("{}: {}", MSG_SHELL_ERROR.red(), e);
-                }
+                self.execute_external_command(&parts)?;
             }
         }
         
@@ -8,6 +8,10 @@ use std::time::Duration;
 
 pub trait SwimDisplay {
     fn show_ai_message(&self, agent: &str, message: &str);
+    /// A streamed reply is starting; chunks follow
+    fn begin_stream(&self, agent: &str);
+    fn show_chunk(&self, chunk: &str);
+    fn end_stream(&self);
     fn show_command_created(&self, spec: &CommandSpec);
     fn show_artifact_created(&self, spec: &ArtifactSpec);
     fn show_session_info(&self, session_id: &str, is_new: bool);
@@ -30,6 +34,20 @@ impl SwimDisplay for SimpleDisplay {
         println!();
     }
     
+    fn begin_stream(&self, agent: &str) {
+        println!("\n{}", agent.bright_blue());
+    }
+    
+    fn show_chunk(&self, chunk: &str) {
+        print!("{}", chunk);
+        let _ = io::stdout().flush();
+    }
+    
+    fn end_stream(&self) {
+        println!();
+        println!();
+    }
+    
     fn show_command_created(&self, spec: &CommandSpec) {
         println!("{} {}", StatusIndicator::success(), help_text::format_command_born(&spec.name).bright_green().bold());
         println!("{}", "Add to PATH to use:".yellow());
@@ -111,6 +129,21 @@ impl SwimDisplay for AnimatedDisplay {
         println!();
     }
     
+    fn begin_stream(&self, agent: &str) {
+        // Tokens arriving are animation enough
+        println!("\n{}", agent.bright_blue());
+    }
+    
+    fn show_chunk(&self, chunk: &str) {
+        print!("{}", chunk);
+        let _ = io::stdout().flush();
+    }
+    
+    fn end_stream(&self) {
+        println!();
+        println!();
+    }
+    
     fn show_command_created(&self, spec: &CommandSpec) {
         // Dramatic pause
         thread::sleep(Duration::from_millis(500));
@@ -1,19 +1,53 @@
-use crate::client::DaemonClient;
+use crate::client::{DaemonClient, RequestKind, Timeouts};
 use crate::swim::display::SwimDisplay;
 use crate::swim::{SimpleDisplay, AnimatedDisplay};
-use crate::protocol::{RequestBuilder, ResponseParser, swim::{SwimRequest, SwimResponse, ApprovalResponse}};
-use crate::common::{generate_id, errors::Port42Error};
+use crate::protocol::{DaemonRequest, ProviderSelection, RequestBuilder, ResponseParser, swim::{SwimRequest, SwimResponse, ApprovalResponse, TokenUsage}, usage::{format_cost, format_tokens}};
+use crate::common::{generate_id, errors::Port42Error, approval::{self, ApprovalPolicy, ApprovalRecord, Decision}, providers, cache::ResponseCache, rate_limit::RateLimitQueue, budget::{BudgetEvent, TokenBudget, estimate_tokens}, notify::{self, NotifyEvent}, workspace::WorkspaceContext};
+use crate::help_text;
 use crate::display::{OutputFormat, Displayable};
 use crate::ui::WaveSpinner;
+use crate::types::Response;
 use anyhow::{Result, anyhow};
+use regex::Regex;
+use std::sync::OnceLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::io::{self, Write};
+use tracing::debug;
 use colored::*;
 
 pub struct SessionHandler {
     pub(crate) client: DaemonClient,
     display: Box<dyn SwimDisplay>,
     output_format: OutputFormat,
+    provider: Option<ProviderSelection>,
+    fallbacks: Vec<ProviderSelection>,
+    cache: Option<ResponseCache>,
+    /// The last reply came from the cache; no session was started for it
+    replayed: bool,
+    guidance: Option<String>,
+    workspace: Option<WorkspaceContext>,
+    budget: Option<TokenBudget>,
+    streaming: bool,
+    approval: ApprovalPolicy,
+    show_usage: bool,
+    session_usage: ExchangeUsage,
+}
+
+/// Tokens and estimated cost of one exchange, or of a whole session
+#[derive(Debug, Clone, Copy, Default)]
+struct ExchangeUsage {
+    tokens: TokenUsage,
+    cost: f64,
+    /// Counted from the text because the daemon didn't report usage
+    estimated: bool,
+}
+
+impl ExchangeUsage {
+    fn add(&mut self, other: ExchangeUsage) {
+        self.tokens.add(other.tokens);
+        self.cost += other.cost;
+        self.estimated |= other.estimated;
+    }
 }
 
 impl SessionHandler {
@@ -28,6 +62,17 @@ impl SessionHandler {
             client, 
             display,
             output_format: OutputFormat::Plain,
+            provider: None,
+            fallbacks: Vec::new(),
+            cache: None,
+            replayed: false,
+            guidance: None,
+            workspace: None,
+            budget: None,
+            streaming: true,
+            approval: ApprovalPolicy::default(),
+            show_usage: false,
+            session_usage: ExchangeUsage::default(),
         }
     }
     
@@ -36,43 +81,238 @@ impl SessionHandler {
             client, 
             display,
             output_format: OutputFormat::Plain,
+            provider: None,
+            fallbacks: Vec::new(),
+            cache: None,
+            replayed: false,
+            guidance: None,
+            workspace: None,
+            budget: None,
+            streaming: true,
+            approval: ApprovalPolicy::default(),
+            show_usage: false,
+            session_usage: ExchangeUsage::default(),
         }
     }
     
-    pub fn send_message_with_context(&mut self, session_id: &str, agent: &str, message: &str, memory_context: Option<Vec<String>>, references: Option<Vec<crate::protocol::relations::Reference>>) -> Result<SwimResponse> {
-        // Build request using protocol traits
-        let swim_req = SwimRequest {
-            agent: agent.to_string(),
-            message: message.to_string(),
-            memory_context,
-            references,
-            approval_response: None,
+    /// Render replies as they stream in (on by default)
+    pub fn set_streaming(&mut self, streaming: bool) {
+        self.streaming = streaming;
+    }
+    
+    /// Print tokens and estimated cost under each reply
+    pub fn set_show_usage(&mut self, show_usage: bool) {
+        self.show_usage = show_usage;
+    }
+    
+    /// How bash requests from the agent are approved
+    pub fn set_approval_policy(&mut self, policy: ApprovalPolicy) {
+        self.approval = policy;
+    }
+    
+    /// Route subsequent messages to a specific AI provider/model
+    pub fn set_provider(&mut self, provider: Option<ProviderSelection>) {
+        self.provider = provider;
+    }
+    
+    pub fn provider(&self) -> Option<&ProviderSelection> {
+        self.provider.as_ref()
+    }
+    
+    /// Providers to retry against, in order, when the primary is unavailable
+    pub fn set_fallbacks(&mut self, fallbacks: Vec<ProviderSelection>) {
+        self.fallbacks = fallbacks;
+    }
+    
+    /// Serve identical one-shot prompts from the response cache
+    pub fn set_cache(&mut self, cache: Option<ResponseCache>) {
+        self.cache = cache;
+    }
+    
+    /// Whether the last reply was replayed from the response cache
+    pub fn replayed(&self) -> bool {
+        self.replayed
+    }
+    
+    /// Agent guidance from a local definition, sent with every message
+    pub fn set_guidance(&mut self, guidance: Option<String>) {
+        self.guidance = guidance;
+    }
+    
+    /// Describe the working directory to the AI with every message
+    pub fn set_workspace(&mut self, workspace: Option<WorkspaceContext>) {
+        self.workspace = workspace;
+    }
+    
+    /// Cap the tokens this session may spend
+    pub fn set_budget(&mut self, budget: Option<TokenBudget>) {
+        self.budget = budget;
+    }
+    
+    pub fn budget(&self) -> Option<&TokenBudget> {
+        self.budget.as_ref()
+    }
+    
+    /// Once the budget is spent, ask before sending anything else.
+    /// Returns false if the user declines (or can't be asked).
+    pub fn confirm_budget(&mut self) -> Result<bool> {
+        let Some(budget) = self.budget.as_mut() else { return Ok(true) };
+        if !budget.needs_confirmation() {
+            return Ok(true);
+        }
+        
+        eprintln!("{}", help_text::format_budget_exhausted(&budget.status()).red());
+        if !atty::is(atty::Stream::Stdin) {
+            return Ok(false);
+        }
+        print!("Continue past the budget? [y/N]: ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let approved = matches!(input.trim().to_lowercase().as_str(), "y" | "yes");
+        if approved {
+            budget.approve_overrun();
+        }
+        Ok(approved)
+    }
+    
+    /// Count an exchange against the budget and the session total
+    fn track_usage(&mut self, message: &str, swim_response: &SwimResponse, provider: Option<&ProviderSelection>) -> ExchangeUsage {
+        let (tokens, estimated) = match swim_response.usage {
+            Some(usage) => (usage, false),
+            None => (TokenUsage {
+                input_tokens: estimate_tokens(message),
+                output_tokens: estimate_tokens(&swim_response.message),
+            }, true),
         };
+        let exchange = ExchangeUsage { tokens, cost: tokens.cost(provider), estimated };
+        self.session_usage.add(exchange);
         
-        let request_id = generate_id();
-        let mut request = swim_req.build_request(request_id)?;
+        if let Some(budget) = self.budget.as_mut() {
+            match budget.record(tokens.total()) {
+                BudgetEvent::Warning => eprintln!("{}", help_text::format_budget_warning(&budget.status()).yellow()),
+                BudgetEvent::Exceeded => eprintln!("{}", help_text::format_budget_exceeded(&budget.status()).red()),
+                BudgetEvent::Within => {}
+            }
+        }
+        exchange
+    }
+    
+    fn show_usage_footer(&self, exchange: &ExchangeUsage) {
+        if !self.show_usage || self.output_format.is_structured() {
+            return;
+        }
+        let mut footer = help_text::format_usage_footer(
+            &format_tokens(exchange.tokens.input_tokens),
+            &format_tokens(exchange.tokens.output_tokens),
+            &format_cost(exchange.cost),
+            &format_tokens(self.session_usage.tokens.total()),
+            &format_cost(self.session_usage.cost),
+        );
+        if exchange.estimated {
+            footer.push_str(help_text::MSG_USAGE_ESTIMATED);
+        }
+        println!("{}", footer.dimmed());
+    }
+    
+    fn cache_key(&self, agent: &str, message: &str, memory_context: &Option<Vec<String>>, references: &Option<Vec<crate::protocol::relations::Reference>>) -> String {
+        let provider = serde_json::to_string(&self.provider).unwrap_or_default();
+        let memory = memory_context.as_ref().map(|m| m.join("\n")).unwrap_or_default();
+        let refs = ResponseCache::references_key(references.as_deref());
+        let guidance = self.guidance.as_deref().unwrap_or_default();
+        let workspace = serde_json::to_string(&self.workspace).unwrap_or_default();
+        ResponseCache::key(&[agent, &provider, guidance, message, &memory, &refs, &workspace])
+    }
+    
+    pub fn send_message_with_context(&mut self, session_id: &str, agent: &str, message: &str, memory_context: Option<Vec<String>>, references: Option<Vec<crate::protocol::relations::Reference>>) -> Result<SwimResponse> {
+        self.replayed = false;
+        let cache_key = self.cache.as_ref()
+            .map(|_| self.cache_key(agent, message, &memory_context, &references));
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            if let Some(mut swim_response) = cache.get(key).and_then(|data| SwimResponse::parse_response(&data).ok()) {
+                // That session was the first asker's; this run never had one
+                swim_response.session_id.clear();
+                eprintln!("{}", help_text::MSG_CACHED_RESPONSE.dimmed());
+                self.show_response(agent, &swim_response, false)?;
+                self.replayed = true;
+                return Ok(swim_response);
+            }
+        }
         
-        // Add session_id to payload
-        if let Some(obj) = request.payload.as_object_mut() {
-            obj.insert("session_id".to_string(), serde_json::Value::String(session_id.to_string()));
+        if !self.confirm_budget()? {
+            return Err(anyhow!(help_text::ERR_BUDGET_DECLINED));
         }
         
-        // Show wave spinner while waiting for response
-        let mut spinner = WaveSpinner::new();
-        let response = self.client.request(request)?;
-        spinner.stop();
+        let mut attempt_provider = self.provider.clone();
+        let mut fallbacks = self.fallbacks.clone().into_iter();
+        let mut queue = RateLimitQueue::from_config(&crate::config::Config::load_or_default());
         
-        if !response.success {
+        let (response, mut message_shown) = loop {
+            // Build request using protocol traits
+            let swim_req = SwimRequest {
+                agent: agent.to_string(),
+                message: message.to_string(),
+                memory_context: memory_context.clone(),
+                references: references.clone(),
+                approval_response: None,
+                provider: attempt_provider.clone(),
+                guidance: self.guidance.clone(),
+                workspace: self.workspace.clone(),
+            };
+            
+            let request_id = generate_id();
+            let mut request = swim_req.build_request(request_id)?;
+            
+            // Add session_id to payload
+            if let Some(obj) = request.payload.as_object_mut() {
+                obj.insert("session_id".to_string(), serde_json::Value::String(session_id.to_string()));
+            }
+            
+            let (response, streamed) = self.send_swim(request, agent)?;
+            
+            if response.success {
+                break (response, streamed);
+            }
+            
             let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
+            let classified_error = Port42Error::from_daemon(&error);
+            
+            // Rate limited - wait it out and resend to the same provider (the
+            // daemon has already dropped the unanswered turn)
+            let attempt_name = attempt_provider.as_ref()
+                .and_then(|p| p.name.as_deref())
+                .unwrap_or(providers::DEFAULT_PROVIDER);
+            if queue.wait_if_limited(providers::display_name(attempt_name), &error) {
+                continue;
+            }
+            
+            // Provider outage - hand the same request to the next provider in the chain.
+            // The daemon drops a turn the AI never answered, so the session keeps one copy
+            if is_provider_outage(&classified_error, &error) {
+                if let Some(next) = fallbacks.next() {
+                    let failed = attempt_provider.as_ref()
+                        .and_then(|p| p.name.as_deref())
+                        .unwrap_or(providers::DEFAULT_PROVIDER);
+                    let next_name = next.name.as_deref().unwrap_or(providers::DEFAULT_PROVIDER);
+                    eprintln!("{}", help_text::format_provider_fallback(failed, next_name).yellow());
+                    attempt_provider = Some(next);
+                    continue;
+                }
+            }
             
             // Classify error and show appropriate message
-            let classified_error = classify_error(&error);
             match &classified_error {
                 Port42Error::ClaudeApi(_) => {
                     eprintln!("{} Claude API is currently experiencing issues. Please try again in a moment.", "🤖".bright_blue());
                 },
+                Port42Error::ProviderApi(provider, _) => {
+                    eprintln!("{} {} API is currently experiencing issues. Please try again in a moment.", "🤖".bright_blue(), providers::display_name(provider));
+                },
                 Port42Error::ApiKey(_) => {
-                    eprintln!("{} API key issue. Please set PORT42_ANTHROPIC_API_KEY or ANTHROPIC_API_KEY and restart the daemon.", "🔑".bright_yellow());
+                    let provider = attempt_provider.as_ref()
+                        .and_then(|p| p.name.as_deref())
+                        .unwrap_or(providers::DEFAULT_PROVIDER);
+                    eprintln!("{} {}", "🔑".bright_yellow(), help_text::format_api_key_hint(provider));
                 },
                 Port42Error::Network(_) => {
                     eprintln!("{} Network connection issue. Please check your internet connection.", "🌐".bright_red());
@@ -83,41 +323,30 @@ impl SessionHandler {
             }
             
             return Err(classified_error.into());
-        }
+        };
         
         // Parse response using protocol trait
         let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
         let mut swim_response = SwimResponse::parse_response(&data)?;
+        let mut exchange = self.track_usage(message, &swim_response, attempt_provider.as_ref());
+        
+        // Only complete answers are replayable - approvals need a live daemon
+        if swim_response.approval_needed.is_none() {
+            if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+                if let Err(e) = cache.put(key, &data) {
+                    debug!("failed to cache response: {}", e);
+                }
+            }
+        }
         
         // Check if approval is needed
         if let Some(approval_req) = &swim_response.approval_needed {
-            // Format the command for display
-            let cmd_display = format!("bash -c \"{}\"", approval_req.args.join(" "));
-            
-            // Show approval prompt
-            println!("\n{}", "=".repeat(60).bright_black());
-            println!("{} {}", "🔒".bright_yellow(), "AI REQUESTS BASH ACCESS".bold());
-            println!("{}", "-".repeat(60).bright_black());
-            println!("Command: {}", cmd_display.bright_cyan());
-            println!("{}", "-".repeat(60).bright_black());
-            println!("{} {}", "⚠️".bright_red(), "Bash commands have full system access".yellow());
-            println!("{}", "=".repeat(60).bright_black());
-            print!("\nApprove? [y/N]: ");
-            io::stdout().flush()?;
-            
-            // Read user input
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            let trimmed = input.trim().to_lowercase();
-            eprintln!("DEBUG: User input was: '{}' (trimmed: '{}')", input.trim(), trimmed);
-            let approved = trimmed == "y" || trimmed == "yes";
-            eprintln!("DEBUG: Approved = {}", approved);
-            
-            if approved {
-                println!("{} Bash command approved\n", "✅".green());
-            } else {
-                println!("{} Bash command denied\n", "❌".red());
-            }
+            // The daemon sends ["-c", script]; policies match on the script itself
+            let script = match approval_req.args.split_first() {
+                Some((flag, rest)) if flag == "-c" => rest.join(" "),
+                _ => approval_req.args.join(" "),
+            };
+            let approved = self.approve_bash(session_id, agent, &script)?;
             
             // Send approval response
             let approval_response = ApprovalResponse {
@@ -132,6 +361,9 @@ impl SessionHandler {
                 memory_context: None,
                 references: None,
                 approval_response: Some(approval_response),
+                provider: attempt_provider.clone(),
+                guidance: None,
+                workspace: None,
             };
             
             let request_id = generate_id();
@@ -143,7 +375,8 @@ impl SessionHandler {
             }
             
             // Send approval and get new response
-            let response = self.client.request(request)?;
+            let (response, streamed) = self.send_swim(request, agent)?;
+            message_shown = streamed;
             
             if !response.success {
                 let error = response.error.unwrap_or_else(|| "Unknown error".to_string());
@@ -154,17 +387,119 @@ impl SessionHandler {
             // Parse the new response
             let data = response.data.ok_or_else(|| anyhow!("No data in response"))?;
             swim_response = SwimResponse::parse_response(&data)?;
+            exchange.add(self.track_usage("", &swim_response, attempt_provider.as_ref()));
         }
         
+        self.show_response(agent, &swim_response, message_shown)?;
+        self.show_usage_footer(&exchange);
+        
+        if let Some(ref spec) = swim_response.command_spec {
+            notify::notify(NotifyEvent::ToolCrystallized, &[
+                ("name", spec.name.clone()),
+                ("description", spec.description.clone()),
+                ("agent", agent.to_string()),
+                ("session", swim_response.session_id.clone()),
+            ]);
+        }
+        
+        Ok(swim_response)
+    }
+    
+    /// Send a swim request, rendering the reply as it streams in when enabled.
+    /// Also returns whether the message was already shown that way.
+    fn send_swim(&mut self, mut request: DaemonRequest, agent: &str) -> Result<(Response, bool)> {
+        // Show wave spinner while waiting for the first words, counting down to the timeout
+        let timeout = Timeouts::current().for_kind(RequestKind::of(&request.request_type));
+        let mut spinner = WaveSpinner::with_countdown(timeout);
+        if !self.streaming || self.output_format.is_structured() {
+            let response = self.client.request(request);
+            spinner.stop();
+            return Ok((response?, false));
+        }
+        
+        if let Some(obj) = request.payload.as_object_mut() {
+            obj.insert("stream".to_string(), serde_json::Value::Bool(true));
+        }
+        let display = &self.display;
+        let mut streamed = false;
+        let response = self.client.request_streaming(request, &mut |chunk| {
+            if !streamed {
+                spinner.stop();
+                display.begin_stream(agent);
+                streamed = true;
+            }
+            display.show_chunk(chunk);
+        });
+        spinner.stop();
+        if streamed {
+            display.end_stream();
+        }
+        Ok((response?, streamed))
+    }
+    
+    /// Settle a bash request by policy, asking the user only when it says to
+    fn approve_bash(&self, session_id: &str, agent: &str, command: &str) -> Result<bool> {
+        let cmd_display = format!("bash -c \"{}\"", command);
+        let interactive = atty::is(atty::Stream::Stdin);
+        
+        let (approved, reason) = match self.approval.decide(command, interactive) {
+            Decision::Approve(reason) => (true, reason),
+            Decision::Deny(reason) => (false, reason),
+            Decision::Ask => {
+                // Show approval prompt
+                println!("\n{}", "=".repeat(60).bright_black());
+                println!("{} {}", "🔒".bright_yellow(), "AI REQUESTS BASH ACCESS".bold());
+                println!("{}", "-".repeat(60).bright_black());
+                println!("Command: {}", cmd_display.bright_cyan());
+                println!("{}", "-".repeat(60).bright_black());
+                println!("{} {}", "⚠️".bright_red(), "Bash commands have full system access".yellow());
+                println!("{}", "=".repeat(60).bright_black());
+                print!("\nApprove? [y/N]: ");
+                io::stdout().flush()?;
+                
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                let approved = matches!(input.trim().to_lowercase().as_str(), "y" | "yes");
+                if approved {
+                    println!("{} Bash command approved\n", "✅".green());
+                } else {
+                    println!("{} Bash command denied\n", "❌".red());
+                }
+                return Ok(approved);
+            }
+        };
+        
+        if approved {
+            eprintln!("{}", help_text::format_bash_auto_approved(&cmd_display, reason).green());
+        } else {
+            eprintln!("{}", help_text::format_bash_auto_denied(&cmd_display, reason).yellow());
+        }
+        let entry = ApprovalRecord {
+            timestamp: chrono::Utc::now(),
+            session_id: session_id.to_string(),
+            agent: agent.to_string(),
+            command: command.to_string(),
+            approved,
+            reason: reason.to_string(),
+        };
+        if let Err(e) = approval::record(&entry) {
+            eprintln!("{}", format!("⚠️  Could not log bash approval: {}", e).yellow());
+        }
+        Ok(approved)
+    }
+    
+    fn show_response(&self, agent: &str, swim_response: &SwimResponse, message_shown: bool) -> Result<()> {
         // Display results based on output format
         match self.output_format {
-            OutputFormat::Json => {
-                // For JSON, use the Displayable trait
-                swim_response.display(OutputFormat::Json)?;
+            OutputFormat::Json | OutputFormat::Yaml => {
+                // For structured output, use the Displayable trait
+                swim_response.display(self.output_format)?;
             }
             OutputFormat::Plain | OutputFormat::Table => {
                 // For Plain and Table, use the custom display trait for animations in interactive mode
-                self.display.show_ai_message(agent, &swim_response.message);
+                if !message_shown {
+                    self.display.show_ai_message(agent, &swim_response.message);
+                }
                 
                 if let Some(ref spec) = swim_response.command_spec {
                     self.display.show_command_created(spec);
@@ -176,7 +511,7 @@ impl SessionHandler {
             }
         }
         
-        Ok(swim_response)
+        Ok(())
     }
     
     pub fn display_session_info(&self, session_id: &str, is_new: bool) {
@@ -188,23 +523,18 @@ impl SessionHandler {
     }
 }
 
-/// Classify daemon errors by source for better user messaging
-fn classify_error(error: &str) -> Port42Error {
-    if error.starts_with("CLAUDE_API_ERROR:") {
-        let msg = error.strip_prefix("CLAUDE_API_ERROR:").unwrap_or(error).trim();
-        Port42Error::ClaudeApi(msg.to_string())
-    } else if error.starts_with("API_KEY_ERROR:") {
-        let msg = error.strip_prefix("API_KEY_ERROR:").unwrap_or(error).trim();
-        Port42Error::ApiKey(msg.to_string())
-    } else if error.starts_with("NETWORK_ERROR:") {
-        let msg = error.strip_prefix("NETWORK_ERROR:").unwrap_or(error).trim();
-        Port42Error::Network(msg.to_string())
-    } else if error.starts_with("AI_CONNECTION_ERROR:") {
-        let msg = error.strip_prefix("AI_CONNECTION_ERROR:").unwrap_or(error).trim();
-        Port42Error::ExternalService(msg.to_string())
-    } else {
-        // Fallback to daemon error for unclassified errors
-        Port42Error::Daemon(error.to_string())
+/// Upstream failures worth retrying on another provider (5xx / 529 overloaded)
+pub fn is_provider_outage_error(raw: &str) -> bool {
+    is_provider_outage(&Port42Error::from_daemon(raw), raw)
+}
+
+fn is_provider_outage(classified: &Port42Error, raw: &str) -> bool {
+    // The status on its own, not the digits inside an id, a port or a token count
+    static OVERLOADED: OnceLock<Regex> = OnceLock::new();
+    let overloaded = OVERLOADED.get_or_init(|| Regex::new(r"\b529\b").expect("valid pattern"));
+    match classified {
+        Port42Error::ClaudeApi(_) | Port42Error::ProviderApi(_, _) | Port42Error::ExternalService(_) => true,
+        _ => overloaded.is_match(raw) || raw.to_lowercase().contains("overloaded"),
     }
 }
 
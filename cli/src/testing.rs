@@ -0,0 +1,214 @@
+//! A stand-in daemon for tests
+//!
+//! `MockDaemon` listens on a local port and speaks the daemon's line
+//! protocol, so anything built on `DaemonClient`, the `port42` binary
+//! included, can run against it without the Go daemon:
+//!
+//! ```no_run
+//! use port42::testing::{MockDaemon, Reply};
+//! use serde_json::json;
+//!
+//! let daemon = MockDaemon::start();
+//! daemon.respond("status", json!({"port": daemon.port(), "uptime": "1m", "active_sessions": 0}));
+//! daemon.on("swim", Reply::error("rate limited"));
+//! // ... run `port42 status --port <daemon.port()>` ...
+//! assert_eq!(daemon.requests_of("status").len(), 1);
+//! ```
+//!
+//! Replies are keyed by request type and given in the order they were set
+//! up, the last one repeating. Types with no reply get the daemon's own
+//! "Unknown request type" error. Pings are always answered, and `hello`
+//! gets a reply from a daemon as current as the CLI, every feature included,
+//! unless one is set up.
+//! Connections stay open for more requests unless `one_request_per_connection`
+//! asks for the Go daemon's habit of hanging up after each answer.
+
+use anyhow::Result;
+use crate::protocol::hello::{FEATURES, MIN_DAEMON_REVISION, PROTOCOL_REVISION};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// What the daemon sends back for one request
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reply {
+    /// Sent in order, each with the request's id; stream chunks or status
+    /// lines go before the response, pushed events after it
+    pub lines: Vec<Value>,
+    /// Close the connection once the lines are sent
+    pub hang_up: bool,
+}
+
+impl Reply {
+    pub fn ok(data: Value) -> Self {
+        Self::lines(vec![json!({ "success": true, "data": data })])
+    }
+
+    pub fn error(message: &str) -> Self {
+        Self::lines(vec![json!({ "success": false, "error": message })])
+    }
+
+    pub fn lines(lines: Vec<Value>) -> Self {
+        Self { lines, hang_up: false }
+    }
+
+    pub fn then_hang_up(mut self) -> Self {
+        self.hang_up = true;
+        self
+    }
+}
+
+/// Answers every request itself instead of using canned replies
+pub type Handler = Box<dyn FnMut(&Value) -> Reply + Send>;
+
+#[derive(Default)]
+struct Shared {
+    replies: Mutex<HashMap<String, VecDeque<Reply>>>,
+    requests: Mutex<Vec<Value>>,
+    handler: Mutex<Option<Handler>>,
+    one_shot: AtomicBool,
+    stopped: AtomicBool,
+}
+
+pub struct MockDaemon {
+    port: u16,
+    shared: Arc<Shared>,
+    accept: Option<JoinHandle<()>>,
+}
+
+impl MockDaemon {
+    /// Listen on a free port with no replies set up yet
+    pub fn start() -> Self {
+        Self::bind(0, None).expect("Can't listen on a local port")
+    }
+
+    /// Listen on `port`, answering every request with `handler`
+    pub fn serve(port: u16, handler: Handler) -> Result<Self> {
+        Self::bind(port, Some(handler))
+    }
+
+    fn bind(port: u16, handler: Option<Handler>) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let port = listener.local_addr()?.port();
+        let shared = Arc::new(Shared { handler: Mutex::new(handler), ..Default::default() });
+        let accepting = shared.clone();
+        let accept = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accepting.stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let shared = accepting.clone();
+                    std::thread::spawn(move || {
+                        let _ = converse(stream, &shared);
+                    });
+                }
+            }
+        });
+        Ok(Self { port, shared, accept: Some(accept) })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Add a reply for requests of `request_type`
+    pub fn on(&self, request_type: &str, reply: Reply) -> &Self {
+        lock(&self.shared.replies).entry(request_type.to_string()).or_default().push_back(reply);
+        self
+    }
+
+    /// Add a successful reply carrying `data`
+    pub fn respond(&self, request_type: &str, data: Value) -> &Self {
+        self.on(request_type, Reply::ok(data))
+    }
+
+    /// Close each connection after answering one request, as the Go daemon does
+    pub fn one_request_per_connection(&self) -> &Self {
+        self.shared.one_shot.store(true, Ordering::SeqCst);
+        self
+    }
+
+    /// Every request received so far, pings and hellos aside, in arrival order
+    pub fn requests(&self) -> Vec<Value> {
+        lock(&self.shared.requests).clone()
+    }
+
+    pub fn requests_of(&self, request_type: &str) -> Vec<Value> {
+        self.requests().into_iter().filter(|r| r["type"] == request_type).collect()
+    }
+
+    /// Serve until the process ends
+    pub fn wait(mut self) {
+        if let Some(accept) = self.accept.take() {
+            let _ = accept.join();
+        }
+    }
+}
+
+impl Drop for MockDaemon {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag
+        let _ = TcpStream::connect(("127.0.0.1", self.port));
+    }
+}
+
+fn converse(stream: TcpStream, shared: &Shared) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Value = serde_json::from_str(&line).unwrap_or(Value::Null);
+        let reply = answer(shared, &request);
+        for mut reply_line in reply.lines {
+            if let Some(fields) = reply_line.as_object_mut() {
+                fields.insert("id".to_string(), request.get("id").cloned().unwrap_or(Value::Null));
+            }
+            writeln!(writer, "{}", reply_line)?;
+        }
+        if reply.hang_up || shared.one_shot.load(Ordering::SeqCst) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn answer(shared: &Shared, request: &Value) -> Reply {
+    let request_type = request.get("type").and_then(Value::as_str).unwrap_or_default();
+    if request_type == "ping" {
+        return Reply::lines(vec![json!({ "success": true })]);
+    }
+    if request_type == "hello" {
+        if let Some(queue) = lock(&shared.replies).get("hello").filter(|queue| !queue.is_empty()) {
+            return queue[0].clone();
+        }
+        return Reply::ok(json!({
+            "version": env!("PORT42_VERSION"),
+            "protocol": PROTOCOL_REVISION,
+            "min_protocol": MIN_DAEMON_REVISION,
+            "features": FEATURES,
+        }));
+    }
+    lock(&shared.requests).push(request.clone());
+
+    if let Some(ref mut handler) = *lock(&shared.handler) {
+        return handler(request);
+    }
+    let mut replies = lock(&shared.replies);
+    match replies.get_mut(request_type) {
+        Some(queue) if queue.len() > 1 => queue.pop_front().unwrap(),
+        Some(queue) if !queue.is_empty() => queue[0].clone(),
+        _ => Reply::error(&format!("Unknown request type: {}", request_type)),
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
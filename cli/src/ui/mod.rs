@@ -1,3 +1,5 @@
 pub mod wave_spinner;
+pub mod phase_progress;
 
-pub use wave_spinner::WaveSpinner;
\ No newline at end of file
+pub use wave_spinner::WaveSpinner;
+pub use phase_progress::PhaseProgress;
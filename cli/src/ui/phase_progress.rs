@@ -0,0 +1,58 @@
+//! Generation phases for `declare`
+//!
+//! Until the daemon reports a phase this is the plain countdown spinner, so
+//! daemons that send no status lines look the same as before. Each phase
+//! then gets the wave while it runs and a tick with its time once the next
+//! one starts.
+
+use std::time::{Duration, Instant};
+use colored::*;
+
+use crate::help_text::format_declare_phase;
+use crate::protocol::GenerationStatus;
+use super::WaveSpinner;
+use super::wave_spinner::format_countdown;
+
+pub struct PhaseProgress {
+    spinner: WaveSpinner,
+    deadline: Instant,
+    current: Option<(String, Instant)>,
+}
+
+impl PhaseProgress {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            spinner: WaveSpinner::with_countdown(timeout),
+            deadline: Instant::now() + timeout,
+            current: None,
+        }
+    }
+
+    /// Show `status` as the phase under way, ticking off the previous one
+    pub fn update(&mut self, status: &GenerationStatus) {
+        let line = format_declare_phase(status.step(), &status.label(), status.detail.as_deref());
+        if self.current.as_ref().is_some_and(|(current, _)| *current == line) {
+            return;
+        }
+        self.finish(true);
+
+        let started = Instant::now();
+        let shown = line.clone();
+        let left = self.deadline.saturating_duration_since(started);
+        self.spinner = WaveSpinner::with_status(left, move |left| {
+            let elapsed = started.elapsed().as_secs();
+            format!("{} {}", shown.bright_blue(), format!("{}s, {} left", elapsed, format_countdown(left)).dimmed())
+        });
+        self.current = Some((line, started));
+    }
+
+    /// Stop the wave, marking the phase that was running as done or failed
+    pub fn finish(&mut self, ok: bool) {
+        let current = self.current.take().map(|(line, started)| (line, started.elapsed()));
+        self.spinner.stop();
+        if let Some((line, took)) = current {
+            let mark = if ok { "✓".green() } else { "✗".red() };
+            eprintln!("  {} {} {}", mark, line, format!("{:.1}s", took.as_secs_f32()).dimmed());
+        }
+    }
+}
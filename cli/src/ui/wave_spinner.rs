@@ -1,12 +1,14 @@
 //! Wave emoji spinner for swimming mode
 //! 
-//! Shows an animated wave while waiting for responses
+//! Shows an animated wave while waiting for responses, with the time left
+//! before the request gives up
 
 use std::io::{self, Write};
 use std::sync::mpsc::{self, Sender};
 use std::thread;
-use std::time::Duration;
-use crossterm::{cursor, execute};
+use std::time::{Duration, Instant};
+use crossterm::{cursor, execute, terminal};
+use colored::*;
 
 pub struct WaveSpinner {
     handle: Option<thread::JoinHandle<()>>,
@@ -14,7 +16,20 @@ pub struct WaveSpinner {
 }
 
 impl WaveSpinner {
-    pub fn new() -> Self {
+    /// Count down to `timeout` beside the wave
+    pub fn with_countdown(timeout: Duration) -> Self {
+        Self::with_status(timeout, |left| format_countdown(left).dimmed().to_string())
+    }
+    
+    /// Show `status(time_left)` beside the wave, e.g. while waiting out a rate limit
+    pub fn with_status(timeout: Duration, status: impl Fn(Duration) -> String + Send + 'static) -> Self {
+        // Progress goes to stderr, so piped or --json stdout stays clean;
+        // a redirected stderr gets no animation or cursor escapes
+        if !atty::is(atty::Stream::Stderr) {
+            return Self { handle: None, stop_sender: None };
+        }
+        
+        let deadline = Instant::now() + timeout;
         let (tx, rx) = mpsc::channel();
         
         let handle = thread::spawn(move || {
@@ -23,7 +38,7 @@ impl WaveSpinner {
             let mut frame_idx = 0;
             
             // Hide cursor
-            let _ = execute!(io::stdout(), cursor::Hide);
+            let _ = execute!(io::stderr(), cursor::Hide);
             
             loop {
                 // Check if we should stop
@@ -32,8 +47,9 @@ impl WaveSpinner {
                 }
                 
                 // Print wave frame
-                print!("\r{}  ", frames[frame_idx]);
-                let _ = io::stdout().flush();
+                let left = deadline.saturating_duration_since(Instant::now());
+                eprint!("\r{}  {}  ", frames[frame_idx], status(left));
+                let _ = io::stderr().flush();
                 
                 frame_idx = (frame_idx + 1) % frames.len();
                 
@@ -42,9 +58,8 @@ impl WaveSpinner {
             }
             
             // Clear the line and show cursor again
-            print!("\r    \r");
-            let _ = execute!(io::stdout(), cursor::Show);
-            let _ = io::stdout().flush();
+            let _ = execute!(io::stderr(), terminal::Clear(terminal::ClearType::CurrentLine), cursor::MoveToColumn(0), cursor::Show);
+            let _ = io::stderr().flush();
         });
         
         Self {
@@ -66,6 +81,12 @@ impl WaveSpinner {
     }
 }
 
+/// Time left as `m:ss`
+pub fn format_countdown(left: Duration) -> String {
+    let secs = left.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
 impl Drop for WaveSpinner {
     fn drop(&mut self) {
         // Ensure cleanup on drop
@@ -0,0 +1,52 @@
+use port42::agents::{AgentDefinition, AgentPack, AgentRegistry, is_builtin};
+
+#[test]
+fn test_agent_pack_round_trip() {
+    let pack = AgentPack::from_toml(r#"
+        [agent]
+        name = "ai-reviewer"
+        description = "Careful code reviewer"
+        guidance = "Point out risky changes first."
+        default_refs = ["p42:/commands/lint-check"]
+
+        [model]
+        provider = "openai"
+        model = "gpt-4o"
+    "#).unwrap();
+
+    // Names are normalized to carry their '@'
+    assert_eq!(pack.agent.name, "@ai-reviewer");
+    assert_eq!(pack.agent.definition.default_refs, vec!["p42:/commands/lint-check"]);
+    assert_eq!(pack.model.as_ref().unwrap().model.as_deref(), Some("gpt-4o"));
+
+    let exported = pack.to_toml().unwrap();
+    assert_eq!(AgentPack::from_toml(&exported).unwrap(), pack);
+
+    assert!(AgentPack::from_toml("[agent]\nname = \"bad name!\"").is_err());
+}
+
+#[test]
+fn test_custom_agent_instructions() {
+    let mut registry = AgentRegistry::default();
+    registry.agents.insert("@ai-reviewer".to_string(), AgentDefinition {
+        persona: Some("You review code.".to_string()),
+        guidance: Some("Be brief.".to_string()),
+        ..Default::default()
+    });
+    registry.agents.insert("@ai-scribe".to_string(), AgentDefinition {
+        guidance: Some("Write changelogs.".to_string()),
+        ..Default::default()
+    });
+
+    assert!(registry.is_known("@ai-reviewer"));
+    assert!(!is_builtin("@ai-reviewer"));
+    assert!(is_builtin("@ai-engineer"));
+    // With or without the '@', the way get() looks them up
+    assert!(registry.is_known("ai-reviewer"));
+    assert!(registry.is_known("ai-engineer"));
+    assert!(!registry.is_known("ai-nobody"));
+    // The persona comes first so guidance can refine it
+    assert_eq!(registry.instructions("@ai-reviewer").as_deref(), Some("You review code.\n\nBe brief."));
+    assert_eq!(registry.instructions("@ai-scribe").as_deref(), Some("Write changelogs."));
+    assert_eq!(registry.instructions("@ai-engineer"), None);
+}
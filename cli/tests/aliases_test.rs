@@ -0,0 +1,56 @@
+use port42::common::aliases::{check_name, expand, expand_line};
+use std::collections::BTreeMap;
+
+fn words(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+fn aliases(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn test_expand_chains_and_keeps_arguments() {
+    let aliases = aliases(&[("sw", "swim @ai-engineer"), ("fast", "sw --no-stream"), ("muse", "swim @ai-muse 'hello there'")]);
+    let builtins = words("swim ls");
+
+    assert_eq!(expand(&words("fast fix it"), &aliases, &builtins).unwrap(), words("swim @ai-engineer --no-stream fix it"));
+    assert_eq!(expand(&words("muse"), &aliases, &builtins).unwrap(), vec!["swim", "@ai-muse", "hello there"]);
+    assert_eq!(expand(&words("ls /"), &aliases, &builtins).unwrap(), words("ls /"));
+    assert!(expand(&[], &aliases, &builtins).unwrap().is_empty());
+}
+
+#[test]
+fn test_builtins_win_and_loops_fail() {
+    let aliases = aliases(&[("ls", "swim @ai-muse"), ("a", "b x"), ("b", "a")]);
+    let builtins = words("ls");
+
+    assert_eq!(expand(&words("ls /tools"), &aliases, &builtins).unwrap(), words("ls /tools"));
+    let err = expand(&words("a"), &aliases, &builtins).unwrap_err().to_string();
+    assert!(err.contains("a → b → a"), "{}", err);
+}
+
+#[test]
+fn test_expand_line_splits_words_and_expands_only_the_first() {
+    let aliases = aliases(&[("lc", "ls  /commands"), ("s", "swim @ai-muse"), ("hi", "swim 'hello there'")]);
+    let builtins = words("ls swim");
+
+    assert_eq!(expand_line("  s  hello   world ", &aliases, &builtins).unwrap(), words("swim @ai-muse hello world"));
+    assert_eq!(expand_line("lc", &aliases, &builtins).unwrap(), words("ls /commands"));
+    assert_eq!(expand_line("cat /x", &aliases, &builtins).unwrap(), words("cat /x"));
+    assert_eq!(expand_line("s \"fix the s bug\" s", &aliases, &builtins).unwrap(), vec!["swim", "@ai-muse", "fix the s bug", "s"]);
+    assert_eq!(expand_line("swim s lc", &aliases, &builtins).unwrap(), words("swim s lc"));
+    assert_eq!(expand_line("hi", &aliases, &builtins).unwrap(), vec!["swim", "hello there"]);
+    assert!(expand_line("s 'unclosed", &aliases, &builtins).is_err());
+}
+
+#[test]
+fn test_check_name() {
+    let builtins = words("swim ls possess");
+    assert!(check_name("sw", &builtins).is_ok());
+    assert!(check_name("my_alias-2", &builtins).is_ok());
+    assert!(check_name("possess", &builtins).is_err());
+    assert!(check_name("--x", &builtins).is_err());
+    assert!(check_name("two words", &builtins).is_err());
+    assert!(check_name("", &builtins).is_err());
+}
@@ -0,0 +1,68 @@
+use port42::client::Timeouts;
+use port42::common::errors::Port42Error;
+use port42::testing::{MockDaemon, Reply};
+use port42::Client;
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_typed_calls() {
+    let daemon = MockDaemon::start();
+    daemon.respond("status", json!({"port": 4242, "uptime": "2h", "active_sessions": 1}))
+        .respond("list_path", json!({"path": "/tools", "entries": [{"name": "git-haiku", "type": "file"}]}))
+        .respond("read_path", json!({"path": "/tools/git-haiku", "content": "IyEvYmluL3No"}))
+        .on("swim", Reply::lines(vec![
+            json!({"chunk": "Hello"}),
+            json!({"success": true, "data": {"message": "Hello there", "session_id": "s-1", "agent": "@ai-engineer"}}),
+        ]));
+
+    let mut client = Client::new(daemon.port());
+    assert_eq!(client.status().await.unwrap().uptime, "2h");
+    assert_eq!(client.ls("/tools").await.unwrap().entries[0].name, "git-haiku");
+    assert_eq!(client.cat("/tools/git-haiku").await.unwrap().content, "#!/bin/sh");
+
+    // Stream chunks are passed over on the way to the response
+    let reply = client.possess("@ai-engineer", "hi").await.unwrap();
+    assert_eq!(reply.message, "Hello there");
+    assert_eq!(reply.session_id, "s-1");
+
+    let swim = &daemon.requests_of("swim")[0];
+    assert_eq!(swim["payload"], json!({"agent": "@ai-engineer", "message": "hi"}));
+    assert_eq!(daemon.requests().len(), 4);
+}
+
+#[tokio::test]
+async fn test_failures_are_typed() {
+    let daemon = MockDaemon::start();
+    daemon.on("read_path", Reply::error("Path /nope not found"))
+        .on("search", Reply::lines(vec![]));
+
+    let mut client = Client::new(daemon.port())
+        .with_timeouts(Timeouts { search: Duration::from_millis(200), ..Timeouts::default() });
+    let err = client.cat("/nope").await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<Port42Error>(), Some(Port42Error::NotFound(_))), "{}", err);
+
+    let err = client.search("anything").await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<Port42Error>(), Some(Port42Error::Timeout(_))), "{}", err);
+
+    // A dead daemon is a connection error
+    let port = daemon.port();
+    drop(daemon);
+    let err = Client::new(port).status().await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<Port42Error>(), Some(Port42Error::Connection(_))), "{}", err);
+}
+
+#[tokio::test]
+async fn test_each_call_gets_its_own_connection() {
+    let daemon = MockDaemon::start();
+    daemon.one_request_per_connection()
+        .respond("status", json!({"port": 4242, "uptime": "2h", "active_sessions": 1}))
+        .respond("list_path", json!({"path": "/tools", "entries": []}));
+
+    // The real daemon hangs up after every reply
+    let mut client = Client::new(daemon.port());
+    assert_eq!(client.status().await.unwrap().uptime, "2h");
+    assert!(client.ls("/tools").await.unwrap().entries.is_empty());
+    assert_eq!(client.status().await.unwrap().uptime, "2h");
+    assert_eq!(daemon.requests().len(), 3);
+}
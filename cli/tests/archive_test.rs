@@ -0,0 +1,61 @@
+use port42::common::archive::{self, ArchiveFilters, Manifest, ManifestEntry};
+use std::path::Path;
+
+#[test]
+fn test_archive_paths_and_manifest() {
+    assert_eq!(archive::file_for("/commands/git-haiku").unwrap(), "commands/git-haiku");
+    assert_eq!(archive::file_for("/artifacts/docs/api.md").unwrap(), "artifacts/docs/api.md");
+    assert!(archive::file_for("/artifacts/../../etc/passwd").is_err());
+    assert!(archive::file_for("/").is_err());
+
+    assert!(archive::is_tarball(Path::new("backup.tar.gz")));
+    assert!(archive::is_tarball(Path::new("backup.tgz")));
+    assert!(!archive::is_tarball(Path::new("backup")));
+
+    let mut manifest = Manifest::new(ArchiveFilters { types: vec!["tool".into()], agent: None, after: None });
+    manifest.objects.push(ManifestEntry {
+        path: "/commands/git-haiku".into(),
+        kind: "tool".into(),
+        file: "commands/git-haiku".into(),
+        size: 2,
+        sha256: archive::sha256_hex(b"hi"),
+        agent: Some("@ai-engineer".into()),
+        created: None,
+        metadata: serde_json::json!({"description": "haiku"}),
+    });
+    let json = serde_json::to_value(&manifest).unwrap();
+    assert_eq!(json["version"], archive::FORMAT_VERSION);
+    assert_eq!(json["objects"][0]["type"], "tool");
+    assert_eq!(json["objects"][0]["sha256"], "8f434346648f6b96df89dda901c5176b10a6d83961dd3c1ac88b59b2dc327aa4");
+    assert!(json["objects"][0].get("created").is_none());
+
+    let back: Manifest = serde_json::from_value(json).unwrap();
+    assert_eq!(back.objects[0].path, "/commands/git-haiku");
+}
+
+#[test]
+fn test_manifest_verify() {
+    let root = archive::scratch_dir("test");
+    std::fs::create_dir_all(root.join("commands")).unwrap();
+    std::fs::write(root.join("commands/hello"), "echo hi").unwrap();
+
+    let mut manifest = Manifest::new(ArchiveFilters::default());
+    manifest.objects.push(ManifestEntry {
+        path: "/commands/hello".into(),
+        kind: "tool".into(),
+        file: "commands/hello".into(),
+        size: 7,
+        sha256: archive::sha256_hex(b"echo hi"),
+        agent: None,
+        created: None,
+        metadata: serde_json::Value::Null,
+    });
+    std::fs::write(root.join(archive::MANIFEST_FILE), serde_json::to_string(&manifest).unwrap()).unwrap();
+
+    let loaded = Manifest::load(&root).unwrap();
+    assert_eq!(loaded.verify(&root).unwrap(), vec![b"echo hi".to_vec()]);
+
+    std::fs::write(root.join("commands/hello"), "echo tampered").unwrap();
+    assert!(loaded.verify(&root).is_err());
+    std::fs::remove_dir_all(&root).unwrap();
+}
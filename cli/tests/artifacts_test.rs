@@ -0,0 +1,114 @@
+mod common;
+
+use common::temp_home;
+use assert_cmd::Command;
+use base64::{engine::general_purpose, Engine as _};
+use port42::testing::MockDaemon;
+use port42::display::markdown;
+use port42::protocol::ResponseParser;
+use port42::protocol::artifacts::{ArtifactContent, ArtifactEntry, artifact_path, file_type};
+use serde_json::json;
+
+#[test]
+fn test_artifact_paths() {
+    assert_eq!(artifact_path("docs/readme.md"), "/artifacts/docs/readme.md");
+    assert_eq!(artifact_path("./notes.txt"), "/artifacts/notes.txt");
+    assert_eq!(artifact_path("/memory/cli-123"), "/memory/cli-123");
+
+    assert_eq!(file_type("/artifacts/images/Logo.PNG"), "png");
+    assert_eq!(file_type("/artifacts/docs/.env"), "-");
+    assert_eq!(file_type("/artifacts/Makefile"), "-");
+}
+
+#[test]
+fn test_artifact_entry_collection() {
+    let entry = ArtifactEntry::new("/artifacts/docs/api.md".to_string(), Some(120));
+    assert_eq!(entry.collection, "docs");
+    assert_eq!(entry.file_type, "md");
+
+    let loose = ArtifactEntry::new("/artifacts/todo.txt".to_string(), None);
+    assert_eq!(loose.collection, "-");
+}
+
+#[test]
+fn test_artifact_content_text_and_binary() {
+    // "# Hi\n"
+    let text = ArtifactContent::parse_response(&json!({
+        "path": "/artifacts/docs/hi.md",
+        "content": "IyBIaQo=",
+    })).unwrap();
+    assert_eq!(text.text(), Some("# Hi\n"));
+    assert!(text.is_markdown());
+
+    // PNG signature followed by NUL bytes
+    let binary = ArtifactContent::parse_response(&json!({
+        "path": "/artifacts/images/logo.png",
+        "content": "iVBORw0KGgoAAAAA",
+    })).unwrap();
+    assert!(binary.text().is_none());
+    assert!(!binary.is_markdown());
+
+    assert!(ArtifactContent::parse_response(&json!({ "path": "/artifacts/x" })).is_err());
+}
+
+#[test]
+fn test_markdown_render() {
+    colored::control::set_override(false);
+    let rendered = markdown::render("# Title\n\n- item\n- [x] done\n1. first\n> quoted\n---\nSee [docs](https://x.io) and `a*b*c`");
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[0], "Title");
+    assert_eq!(lines[2], "  • item");
+    assert_eq!(lines[3], "  • ☑ done");
+    assert_eq!(lines[4], "  1. first");
+    assert_eq!(lines[5], "│ quoted");
+    assert_eq!(lines[6], "─".repeat(40));
+    // Code spans are left as written
+    assert_eq!(lines[7], "See docs (https://x.io) and a*b*c");
+
+    let fenced = markdown::render("```\nlet **x** = 1;\n```\nafter");
+    assert_eq!(fenced, "    let **x** = 1;\nafter");
+    colored::control::unset_override();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_open_writes_private_file_keyed_by_content() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = temp_home("artifacts", "open");
+    // A stand-in for xdg-open that records what it was asked to open
+    let bin = home.join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let opener = bin.join("xdg-open");
+    std::fs::write(&opener, format!("#!/bin/sh\necho \"$1\" >> {}\n", home.join("opened").display())).unwrap();
+    port42::common::utils::make_executable(&opener).unwrap();
+
+    let daemon = MockDaemon::start();
+    daemon.respond("read_path", json!({
+        "path": "/artifacts/docs/notes.md",
+        "content": general_purpose::STANDARD.encode("# Notes\n"),
+    }));
+
+    for _ in 0..2 {
+        let output = Command::cargo_bin("port42").unwrap()
+            .env("HOME", &home)
+            .env("PATH", format!("{}:/usr/bin:/bin", bin.display()))
+            .args(["--port", &daemon.port().to_string(), "artifacts", "open", "docs/notes.md"])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let opened = std::fs::read_to_string(home.join("opened")).unwrap();
+    let files: Vec<&str> = opened.lines().collect();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0], files[1], "the same content reuses its file");
+    let file = std::path::Path::new(files[0]);
+    assert!(file.starts_with(home.join(".port42/cache/artifacts")), "{}", file.display());
+    assert!(file.ends_with("notes.md"));
+    assert_eq!(std::fs::read_to_string(file).unwrap(), "# Notes\n");
+    let mode = |p: &std::path::Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode(file), 0o600);
+    assert_eq!(mode(file.parent().unwrap()), 0o700);
+    assert_eq!(mode(&home.join(".port42/cache/artifacts")), 0o700);
+}
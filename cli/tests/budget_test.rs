@@ -0,0 +1,21 @@
+use port42::common::budget::{BudgetEvent, TokenBudget, estimate_tokens};
+
+#[test]
+fn test_token_budget_thresholds() {
+    let mut budget = TokenBudget::new(10_000);
+    assert_eq!(budget.record(5_000), BudgetEvent::Within);
+    assert_eq!(budget.record(3_500), BudgetEvent::Warning);
+    // Warned once, not on every message after
+    assert_eq!(budget.record(500), BudgetEvent::Within);
+    assert!(!budget.needs_confirmation());
+
+    assert_eq!(budget.record(1_500), BudgetEvent::Exceeded);
+    assert!(budget.needs_confirmation());
+    assert_eq!(budget.status(), "10.5k/10k tokens (105%)");
+
+    budget.approve_overrun();
+    assert!(!budget.needs_confirmation());
+
+    assert_eq!(estimate_tokens("abcdefgh"), 2);
+    assert_eq!(estimate_tokens("abcdefghi"), 3);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::{port42, temp_home_with_config};
+use port42::testing::MockDaemon;
+use serde_json::json;
+use std::path::PathBuf;
+
+/// A home whose config turns the response cache on
+fn temp_home(name: &str) -> PathBuf {
+    temp_home_with_config("cache", name, "[cache]\nenabled = true\n")
+}
+
+#[test]
+fn test_replayed_swim_reports_no_session() {
+    let home = temp_home("swim");
+    let daemon = MockDaemon::start();
+    daemon.respond("swim", json!({"message": "Dolphins dream", "session_id": "cli-42", "agent": "@ai-engineer"}));
+
+    let first = port42(&home, &daemon, &["possess", "@ai-engineer", "write a haiku"]);
+    assert!(first.status.success(), "{}", String::from_utf8_lossy(&first.stderr));
+    assert!(String::from_utf8_lossy(&first.stdout).contains("cli-42"));
+
+    let again = port42(&home, &daemon, &["possess", "@ai-engineer", "write a haiku"]);
+    assert!(again.status.success(), "{}", String::from_utf8_lossy(&again.stderr));
+    let stdout = String::from_utf8_lossy(&again.stdout);
+    assert!(stdout.contains("Dolphins dream"), "{}", stdout);
+    assert!(!stdout.contains("cli-42"), "{}", stdout);
+    assert_eq!(daemon.requests_of("swim").len(), 1);
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_declare_replays_a_cached_preview() {
+    let home = temp_home("declare");
+    let daemon = MockDaemon::start();
+    daemon.respond("declare_relation", json!({
+        "relation_id": "rel-shiny", "type": "Tool", "materialized": false,
+        "physical_path": "/commands/shiny", "status": "preview", "content": "print('shiny')\n",
+    }));
+
+    // Generated once, shown twice
+    for _ in 0..2 {
+        let output = port42(&home, &daemon, &["declare", "tool", "shiny", "--dry-run"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("print('shiny')"));
+    }
+    assert_eq!(daemon.requests_of("declare_relation").len(), 1);
+
+    // Writing sends what was shown rather than generating again
+    let output = port42(&home, &daemon, &["declare", "tool", "shiny"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--no-cache"));
+    let declared = daemon.requests_of("declare_relation");
+    assert_eq!(declared[1]["payload"]["relation"]["properties"]["generated"], "print('shiny')\n");
+
+    let output = port42(&home, &daemon, &["declare", "tool", "shiny", "--dry-run", "--no-cache"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(daemon.requests_of("declare_relation").len(), 3);
+
+    std::fs::remove_dir_all(&home).ok();
+}
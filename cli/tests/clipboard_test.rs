@@ -0,0 +1,21 @@
+use port42::common::clipboard;
+use port42::protocol::{MemoryDetailResponse, ResponseParser};
+use serde_json::json;
+
+#[test]
+fn test_osc52_and_transcript() {
+    assert_eq!(clipboard::osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+
+    let detail = MemoryDetailResponse::parse_response(&json!({
+        "id": "cli-1",
+        "agent": "@ai-engineer",
+        "state": "completed",
+        "created_at": "2025-01-01T00:00:00Z",
+        "last_activity": "2025-01-01T00:05:00Z",
+        "messages": [
+            {"role": "user", "content": "make a tool\n", "timestamp": "2025-01-01T00:00:00Z"},
+            {"role": "assistant", "content": "Done", "timestamp": "2025-01-01T00:01:00Z"}
+        ]
+    })).unwrap();
+    assert_eq!(detail.transcript(), "user: make a tool\n\nassistant: Done");
+}
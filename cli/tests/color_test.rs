@@ -0,0 +1,26 @@
+use port42::display::ColorMode;
+
+#[test]
+fn test_color_precedence() {
+    // Nothing set: colour follows the terminal
+    assert_eq!(ColorMode::resolve(None, None, None, None), ColorMode::Auto);
+    assert_eq!(ColorMode::resolve(None, None, None, Some("always")), ColorMode::Always);
+
+    // NO_COLOR beats the config file, but only when non-empty
+    assert_eq!(ColorMode::resolve(None, None, Some("1"), Some("always")), ColorMode::Never);
+    assert_eq!(ColorMode::resolve(None, None, Some(""), Some("always")), ColorMode::Always);
+
+    // PORT42_COLOR beats NO_COLOR, and the flag beats everything
+    assert_eq!(ColorMode::resolve(None, Some("always"), Some("1"), None), ColorMode::Always);
+    assert_eq!(ColorMode::resolve(Some(ColorMode::Never), Some("always"), None, Some("always")), ColorMode::Never);
+
+    // Unknown values fall through to the next source
+    assert_eq!(ColorMode::resolve(None, Some("sometimes"), None, Some("never")), ColorMode::Never);
+}
+
+#[test]
+fn test_color_enabled() {
+    assert!(ColorMode::Always.enabled(false));
+    assert!(!ColorMode::Never.enabled(true));
+    assert!(!ColorMode::Auto.enabled(false));
+}
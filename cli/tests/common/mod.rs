@@ -0,0 +1,43 @@
+//! Fixtures shared by the integration tests that drive the port42 binary
+
+#![allow(dead_code)]
+
+use assert_cmd::Command;
+use port42::testing::MockDaemon;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+/// A fresh HOME with an empty ~/.port42, unique to this suite, test and process
+pub fn temp_home(suite: &str, name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("port42-{}-test-{}-{}", suite, name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join(".port42")).unwrap();
+    dir
+}
+
+/// Like `temp_home`, with ~/.port42/config.toml already written
+pub fn temp_home_with_config(suite: &str, name: &str, config: &str) -> PathBuf {
+    let dir = temp_home(suite, name);
+    std::fs::write(dir.join(".port42/config.toml"), config).unwrap();
+    dir
+}
+
+/// Run port42 from `home`, ignoring any port or profile set in the environment
+pub fn port42_at(home: &Path, args: &[&str]) -> Output {
+    Command::cargo_bin("port42").unwrap()
+        .env("HOME", home)
+        .env_remove("PORT42_PORT")
+        .env_remove("PORT42_PROFILE")
+        .current_dir(home)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Run port42 from `home` against the mock daemon
+pub fn port42(home: &Path, daemon: &MockDaemon, args: &[&str]) -> Output {
+    let port = daemon.port().to_string();
+    let mut full = vec!["--port", port.as_str()];
+    full.extend_from_slice(args);
+    port42_at(home, &full)
+}
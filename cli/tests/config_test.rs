@@ -0,0 +1,217 @@
+mod common;
+
+use port42::common::providers::{resolve_provider, ProviderArgs};
+use port42::config::{self, Config};
+use port42::testing::MockDaemon;
+use serde_json::json;
+
+fn config_from(toml_str: &str) -> Config {
+    toml::from_str(toml_str).expect("valid config")
+}
+
+#[test]
+fn test_empty_config_has_no_defaults() {
+    let config = config_from("");
+    assert!(config.provider.is_none());
+    assert!(config.agents.is_empty());
+
+    let selection = resolve_provider(ProviderArgs::default(), Some("@ai-muse"), &config).unwrap();
+    assert!(selection.is_none());
+}
+
+#[test]
+fn test_agent_defaults_lookup() {
+    let config = config_from(r#"
+        provider = "anthropic"
+
+        [agents."@ai-muse"]
+        model = "claude-opus"
+
+        [agents.ai-analyst]
+        provider = "openai"
+        model = "gpt-4o"
+    "#);
+
+    assert_eq!(config.agent_defaults("@ai-muse").unwrap().model.as_deref(), Some("claude-opus"));
+    // Keys may be written with or without the '@'
+    assert_eq!(config.agent_defaults("@ai-analyst").unwrap().provider.as_deref(), Some("openai"));
+    assert!(config.agent_defaults("@ai-engineer").is_none());
+}
+
+#[test]
+fn test_resolve_provider_precedence() {
+    let config = config_from(r#"
+        provider = "anthropic"
+
+        [agents."@ai-analyst"]
+        provider = "openai"
+        model = "gpt-4o"
+    "#);
+
+    // Agent defaults beat the global default
+    let selection = resolve_provider(ProviderArgs::default(), Some("@ai-analyst"), &config).unwrap().unwrap();
+    assert_eq!(selection.name.as_deref(), Some("openai"));
+    assert_eq!(selection.model.as_deref(), Some("gpt-4o"));
+
+    // Agents without overrides fall back to the global default
+    let selection = resolve_provider(ProviderArgs::default(), Some("@ai-engineer"), &config).unwrap().unwrap();
+    assert_eq!(selection.name.as_deref(), Some("anthropic"));
+    assert!(selection.model.is_none());
+
+    // Flags beat everything, and drop the agent's model for a different provider
+    let args = ProviderArgs { provider: Some("google".to_string()), model: None, ..Default::default() };
+    let selection = resolve_provider(args, Some("@ai-analyst"), &config).unwrap().unwrap();
+    assert_eq!(selection.name.as_deref(), Some("google"));
+    assert!(selection.model.is_none());
+
+    let args = ProviderArgs { provider: None, model: Some("gpt-4o-mini".to_string()), ..Default::default() };
+    let selection = resolve_provider(args, Some("@ai-analyst"), &config).unwrap().unwrap();
+    assert_eq!(selection.name.as_deref(), Some("openai"));
+    assert_eq!(selection.model.as_deref(), Some("gpt-4o-mini"));
+}
+
+#[test]
+fn test_global_model_belongs_to_the_global_provider() {
+    let config = config_from(r#"
+        provider = "openai"
+        model = "gpt-4o"
+
+        [agents."@ai-muse"]
+        provider = "google"
+    "#);
+
+    let selection = resolve_provider(ProviderArgs::default(), Some("@ai-engineer"), &config).unwrap().unwrap();
+    assert_eq!(selection.model.as_deref(), Some("gpt-4o"));
+
+    // Another provider, by agent or by flag, gets its own default model
+    let selection = resolve_provider(ProviderArgs::default(), Some("@ai-muse"), &config).unwrap().unwrap();
+    assert_eq!(selection.name.as_deref(), Some("google"));
+    assert!(selection.model.is_none());
+
+    let args = ProviderArgs { provider: Some("anthropic".to_string()), model: None, ..Default::default() };
+    let selection = resolve_provider(args, None, &config).unwrap().unwrap();
+    assert!(selection.model.is_none());
+}
+
+#[test]
+fn test_provider_flag_drops_a_model_chosen_for_another_provider() {
+    let config = config_from(r#"
+        provider = "anthropic"
+
+        [agents."@ai-muse"]
+        model = "claude-opus"
+    "#);
+
+    // The muse's model was picked for the global provider
+    let selection = resolve_provider(ProviderArgs::default(), Some("@ai-muse"), &config).unwrap().unwrap();
+    assert_eq!(selection.model.as_deref(), Some("claude-opus"));
+
+    let args = ProviderArgs { provider: Some("openai".to_string()), model: None, ..Default::default() };
+    let selection = resolve_provider(args, Some("@ai-muse"), &config).unwrap().unwrap();
+    assert_eq!(selection.name.as_deref(), Some("openai"));
+    assert!(selection.model.is_none());
+
+    // Naming the same provider keeps it
+    let args = ProviderArgs { provider: Some("anthropic".to_string()), model: None, ..Default::default() };
+    let selection = resolve_provider(args, Some("@ai-muse"), &config).unwrap().unwrap();
+    assert_eq!(selection.model.as_deref(), Some("claude-opus"));
+}
+
+#[test]
+fn test_local_model_beats_the_global_model() {
+    let config = config_from(r#"
+        provider = "local"
+        model = "mistral"
+
+        [local]
+        model = "llama3.1"
+    "#);
+
+    let selection = resolve_provider(ProviderArgs::default(), None, &config).unwrap().unwrap();
+    assert_eq!(selection.name.as_deref(), Some("local"));
+    assert_eq!(selection.model.as_deref(), Some("llama3.1"));
+}
+
+#[test]
+fn test_resolve_provider_rejects_unknown() {
+    let args = ProviderArgs { provider: Some("skynet".to_string()), model: None, ..Default::default() };
+    assert!(resolve_provider(args, None, &Config::default()).is_err());
+}
+
+#[test]
+fn test_resolve_fallbacks() {
+    use port42::common::providers::resolve_fallbacks;
+
+    let config = config_from(r#"
+        fallback = ["anthropic", "openai/gpt-4o", "local"]
+
+        [local]
+        model = "llama3.1"
+
+        [agents."@ai-muse"]
+        fallback = []
+    "#);
+
+    // The primary provider is skipped, models and local settings carried through
+    let primary = resolve_provider(ProviderArgs::default(), None, &config).unwrap();
+    let chain = resolve_fallbacks(None, &primary, &config).unwrap();
+    assert_eq!(chain.len(), 2);
+    assert_eq!(chain[0].name.as_deref(), Some("openai"));
+    assert_eq!(chain[0].model.as_deref(), Some("gpt-4o"));
+    assert_eq!(chain[1].model.as_deref(), Some("llama3.1"));
+    assert!(chain[1].base_url.is_some());
+
+    // An agent's own list replaces the global one
+    assert!(resolve_fallbacks(Some("@ai-muse"), &primary, &config).unwrap().is_empty());
+}
+
+#[test]
+fn test_set_keeps_comments_and_types() {
+    let original = "# my settings\nprovider = \"openai\"\n";
+    let updated = config::set_in(original, "timeouts.ai_secs", config::parse_value("600")).unwrap();
+    let updated = config::set_in(&updated, "agents.\"@ai-muse\".model", config::parse_value("gpt-4o")).unwrap();
+    assert!(updated.starts_with("# my settings"));
+
+    let config = config_from(&updated);
+    assert_eq!(config.timeouts.as_ref().unwrap().ai_secs, Some(600));
+    assert_eq!(config.agent_defaults("@ai-muse").unwrap().model.as_deref(), Some("gpt-4o"));
+    assert_eq!(config::get_in(&config_from(&updated), "provider"), Some(toml::Value::String("openai".into())));
+}
+
+#[test]
+fn test_set_rejects_bad_settings() {
+    assert!(config::set_in("", "no_such_setting", config::parse_value("1")).is_err());
+    assert!(config::set_in("", "color", config::parse_value("purple")).is_err());
+    assert!(config::set_in("", "port", config::parse_value("not-a-port")).is_err());
+    assert!(config::set_in("", "timeouts..ai_secs", config::parse_value("1")).is_err());
+    assert!(config::set_in("", "output", config::parse_value("yaml")).is_ok());
+}
+
+#[test]
+fn test_split_key() {
+    assert_eq!(config::split_key("timeouts.ai_secs").unwrap(), vec!["timeouts", "ai_secs"]);
+    assert_eq!(config::split_key("agents.\"@ai.x\".model").unwrap(), vec!["agents", "@ai.x", "model"]);
+    assert!(config::split_key("agents.\"@ai").is_err());
+}
+
+#[test]
+fn test_default_agent_leaves_named_agents_alone() {
+    let home = common::temp_home_with_config("config", "default-agent", "default_agent = \"@ai-muse\"\n");
+    let daemon = MockDaemon::start();
+    daemon.respond("swim", json!({"message": "Dolphins dream", "session_id": "cli-42", "agent": "@ai-muse"}));
+
+    // A known agent without its '@' is still the agent
+    let output = common::port42(&home, &daemon, &["swim", "ai-engineer", "write", "a", "haiku"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    // Anything else starts the message for the default agent
+    let output = common::port42(&home, &daemon, &["swim", "write", "a", "haiku"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let sent: Vec<(String, String)> = daemon.requests_of("swim").iter()
+        .map(|r| (r["payload"]["agent"].as_str().unwrap().to_string(), r["payload"]["message"].as_str().unwrap().to_string()))
+        .collect();
+    assert_eq!(sent, [
+        ("ai-engineer".to_string(), "write a haiku".to_string()),
+        ("@ai-muse".to_string(), "write a haiku".to_string()),
+    ]);
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use crossterm::event::KeyCode;
+use port42::context::detail::{self, Subject, format_millis};
+use port42::context::mouse::{self, Hint};
+use port42::context::formatters::{ContextFormatter, PrettyFormatter, TimelineFormatter};
+use port42::context::{ContextData, ContextSuggestion, HistoryQuery, TimelineKind, suggestions};
+use port42::testing::MockDaemon;
+use port42::protocol::{MemoryDetailResponse, RequestBuilder, ResponseParser};
+use port42::protocol::hooks::SuggestionFeedbackRequest;
+use ratatui::layout::Rect;
+use ratatui::text::Span;
+use serde_json::json;
+
+fn sample() -> ContextData {
+    serde_json::from_value(json!({
+        "active_session": null,
+        "recent_commands": [
+            { "command": "port42 ls /commands", "timestamp": "2024-06-01T10:05:00Z", "age_seconds": 0, "exit_code": 0 },
+            { "command": "git-haiku", "timestamp": "2024-06-01T09:00:00Z", "age_seconds": 0, "exit_code": 2 },
+        ],
+        "created_tools": [
+            { "name": "git-haiku", "type": "tool", "created_at": "2024-06-01T08:30:00Z" },
+        ],
+        "accessed_memories": [
+            { "path": "/memory/cli-1", "type": "memory", "access_count": 3, "last_accessed": "2024-06-01T10:00:00Z" },
+        ],
+        "suggestions": [],
+    })).unwrap()
+}
+
+#[test]
+fn test_timeline_interleaves_chronologically() {
+    let timeline = sample().timeline();
+    let kinds: Vec<TimelineKind> = timeline.iter().map(|e| e.kind).collect();
+    assert_eq!(kinds, vec![TimelineKind::Tool, TimelineKind::Command, TimelineKind::Memory, TimelineKind::Command]);
+    assert_eq!(timeline[0].text, "git-haiku");
+    assert_eq!(timeline[3].text, "port42 ls /commands");
+
+    let formatted = TimelineFormatter.format(&sample());
+    assert!(formatted.starts_with("🕰  Timeline (4 events)"));
+    assert!(formatted.contains("created git-haiku"));
+    assert!(formatted.contains("git-haiku (exit 2)"));
+    assert!(formatted.contains("accessed /memory/cli-1"));
+}
+
+#[test]
+fn test_history_window_and_limit() {
+    let since = Utc.with_ymd_and_hms(2024, 6, 1, 9, 0, 0).unwrap();
+    let windowed = sample().within(&HistoryQuery { since, limit: 200 });
+    assert_eq!(windowed.timeline().len(), 3);
+    assert!(windowed.created_tools.is_empty());
+
+    // The limit keeps the newest events of any kind
+    let limited = sample().within(&HistoryQuery { since: since - Duration::days(1), limit: 2 });
+    let texts: Vec<String> = limited.timeline().into_iter().map(|e| e.text).collect();
+    assert_eq!(texts, vec!["/memory/cli-1", "port42 ls /commands"]);
+
+    let payload = HistoryQuery::payload(Some(&HistoryQuery { since, limit: 50 }));
+    assert_eq!(payload["history"]["limit"], 50);
+    assert_eq!(payload["history"]["since"], "2024-06-01T09:00:00+00:00");
+    assert_eq!(HistoryQuery::payload(None), json!({}));
+}
+
+#[test]
+fn test_history_warns_when_the_daemon_sends_a_snapshot() {
+    let snapshot = json!({"recent_commands": [], "created_tools": [], "suggestions": []});
+    assert!(!HistoryQuery::answered(&snapshot));
+    let mut answered = snapshot.clone();
+    answered["history"] = json!({"since": "2024-06-01T09:00:00+00:00", "limit": 200});
+    assert!(HistoryQuery::answered(&answered));
+
+    let home = common::temp_home("context", "history");
+    let daemon = MockDaemon::start();
+    daemon.respond("context", snapshot);
+    let output = common::port42(&home, &daemon, &["context", "--history", "--since", "2h"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("keeps no history"), "{}", stderr);
+    assert_eq!(daemon.requests_of("context")[0]["payload"]["history"]["limit"], 200);
+
+    let daemon = MockDaemon::start();
+    daemon.respond("context", answered);
+    let output = common::port42(&home, &daemon, &["context", "--history", "--since", "2h"]);
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("keeps no history"));
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_suggestions_numbered_and_picked() {
+    let mut data = sample();
+    assert!(suggestions::pick(&data, 1).is_err());
+    data.suggestions = vec![
+        ContextSuggestion { command: "port42 ls /tools/".to_string(), reason: "Explore available tools".to_string(), confidence: 0.7 },
+        ContextSuggestion { command: "port42 swim @ai-engineer \"How can I help?\"".to_string(), reason: "Start a new AI session".to_string(), confidence: 0.8 },
+    ];
+
+    let pretty = PrettyFormatter.format(&data);
+    assert!(pretty.contains("   1. port42 ls /tools/  (Explore available tools)"));
+    assert!(pretty.contains("port42 context run <n>"));
+
+    assert_eq!(suggestions::pick(&data, 2).unwrap().reason, "Start a new AI session");
+    assert!(suggestions::pick(&data, 0).is_err());
+    assert!(suggestions::pick(&data, 3).is_err());
+
+    // Quoted words stay whole and port42 means this binary
+    let (program, args) = suggestions::command_line(&data.suggestions[1]).unwrap();
+    assert_ne!(program, "port42");
+    assert_eq!(args, vec!["swim", "@ai-engineer", "How can I help?"]);
+}
+
+#[test]
+fn test_suggestion_feedback_request() {
+    let request = SuggestionFeedbackRequest {
+        command: "port42 ls /tools/".to_string(),
+        reason: "Explore available tools".to_string(),
+        accepted: true,
+        exit_code: Some(0),
+    }.build_request("t".to_string()).unwrap();
+    assert_eq!(request.request_type, "suggestion_feedback");
+    assert_eq!(request.payload["accepted"], true);
+    assert_eq!(request.payload["exit_code"], 0);
+}
+
+#[test]
+fn test_detail_from_snapshot_and_daemon() {
+    let mut command = sample().recent_commands.remove(1);
+    command.duration_ms = Some(2_400);
+    command.session_id = Some("cli-1".to_string());
+    let mut detail = detail::summary(&Subject::Command(command));
+    let fields: Vec<(&str, &str)> = detail.fields.iter().map(|(l, v)| (l.as_str(), v.as_str())).collect();
+    assert_eq!(&fields[..3], &[("Command", "git-haiku"), ("Exit code", "2"), ("Duration", "2.4s")]);
+
+    // The daemon's answer fills in, replacing rather than repeating
+    let session = MemoryDetailResponse::parse_response(&json!({
+        "id": "cli-1", "agent": "@ai-engineer", "state": "completed",
+        "created_at": "2024-06-01T08:00:00Z", "last_activity": "2024-06-01T09:00:00Z",
+        "command_generated": { "name": "git-haiku", "description": null },
+        "messages": [{ "role": "user", "content": "hi", "timestamp": "2024-06-01T08:00:00Z" }],
+    })).unwrap();
+    detail.add_session(&session);
+    assert_eq!(detail.fields.iter().filter(|(l, _)| l == "Session").count(), 1);
+    assert!(detail.fields.contains(&("Messages".to_string(), "1".to_string())));
+    assert_eq!(detail.objects, vec!["/commands/git-haiku", "/memory/cli-1"]);
+
+    let mut tool = detail::summary(&Subject::Tool(sample().created_tools.remove(0)));
+    tool.add_metadata(&json!({
+        "type": "command", "description": "Commit haikus", "session": "cli-1",
+        "paths": ["/commands/git-haiku", "/tools/git-haiku"],
+        "relationships": { "generated_commands": ["/commands/git-haiku"], "child_artifacts": ["/artifacts/haiku.md"] },
+    }));
+    assert_eq!(tool.objects, vec!["/commands/git-haiku", "/tools/git-haiku", "/artifacts/haiku.md"]);
+    assert!(tool.fields.contains(&("Session".to_string(), "cli-1".to_string())));
+
+    assert_eq!(format_millis(850), "850ms");
+    assert_eq!(format_millis(185_000), "3m 05s");
+}
+
+#[test]
+fn test_mouse_hits_footer_hints_and_rows() {
+    let hints = vec![Hint::new("f", "filter", Some(KeyCode::Char('f'))), Hint::new("↑↓", "navigate", None)];
+    // "[f]filter [↑↓]navigate " is 23 wide, centred in 33 columns from x = 2
+    let footer = Rect::new(2, 20, 33, 3);
+    assert_eq!(mouse::hint_at(&hints, &[], footer, 7), Some(KeyCode::Char('f')));
+    assert_eq!(mouse::hint_at(&hints, &[], footer, 15), Some(KeyCode::Char('f')));
+    assert_eq!(mouse::hint_at(&hints, &[], footer, 16), None);
+    assert_eq!(mouse::hint_at(&hints, &[], footer, 18), None);
+    assert_eq!(mouse::hint_at(&hints, &[], footer, 6), None);
+
+    // Text before the hints pushes them right
+    let prefix = [Span::raw("Search: ")];
+    assert_eq!(mouse::hint_at(&hints, &prefix, footer, 7), None);
+    assert_eq!(mouse::hint_at(&hints, &prefix, footer, 11), Some(KeyCode::Char('f')));
+
+    let list = Rect::new(0, 3, 40, 10);
+    assert_eq!(mouse::row_at(list, 5, 3), Some(0));
+    assert_eq!(mouse::row_at(list, 5, 12), Some(9));
+    assert_eq!(mouse::row_at(list, 5, 13), None);
+    assert_eq!(mouse::row_at(list, 40, 5), None);
+}
@@ -0,0 +1,102 @@
+use chrono::{Local, TimeZone, Utc};
+use port42::common::daemon_log::{self, Level, LogFilter, LogLine};
+use regex::Regex;
+use std::io::Write;
+
+fn temp_log(name: &str, contents: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("port42-daemon-log-{}-{}.log", name, std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_parse_lines() {
+    let line = LogLine::parse("2024/06/01 12:34:56 ❌ Failed to spawn docs: boom", None);
+    assert_eq!(line.time, Local.with_ymd_and_hms(2024, 6, 1, 12, 34, 56).single());
+    assert_eq!(line.level, Level::Error);
+    assert_eq!(line.message, "❌ Failed to spawn docs: boom");
+
+    assert_eq!(LogLine::parse("2024/06/01 12:34:56 ⚠️ Rule processing failed", None).level, Level::Warn);
+    assert_eq!(LogLine::parse("2024/06/01 12:34:56 🔍 [DEBUG] handleMemoryShow", None).level, Level::Debug);
+    assert_eq!(LogLine::parse("2024/06/01 12:34:56 Error listing objects: denied", None).level, Level::Error);
+    assert_eq!(LogLine::parse("2024/06/01 12:34:56 🐬 Port 42 is open", None).level, Level::Info);
+
+    // The rest of a multi-line message belongs to its entry
+    let rest = LogLine::parse("  at main.go:42", Some(&line));
+    assert!(rest.continued);
+    assert_eq!((rest.time, rest.level), (line.time, Level::Error));
+}
+
+#[test]
+fn test_parse_json_lines() {
+    let line = LogLine::parse(r#"{"time":"2024-06-01T12:00:00Z","level":"WARN","msg":"slow provider","ms":950}"#, None);
+    assert_eq!(line.time.unwrap().with_timezone(&Utc), Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap());
+    assert_eq!(line.level, Level::Warn);
+    assert_eq!(line.message, "slow provider ms=950");
+
+    // As `port42 --log-json` writes them
+    let traced = LogLine::parse(
+        r#"{"timestamp":"2024-06-01T12:00:00.5Z","level":"DEBUG","fields":{"message":"request","id":"cli-1"},"target":"port42::client"}"#,
+        None,
+    );
+    assert_eq!(traced.level, Level::Debug);
+    assert_eq!(traced.message, "request id=cli-1 target=port42::client");
+}
+
+#[test]
+fn test_tail_with_filters() {
+    let path = temp_log("tail", "\
+2024/06/01 10:00:00 🐬 listening
+2024/06/01 10:00:01 ❌ Failed to spawn docs
+  stack line
+2024/06/01 11:00:00 ⚠️ Reference resolution failed
+2024/06/01 11:00:01 ✅ Claude API responded
+");
+
+    assert_eq!(daemon_log::tail(&path, 2, &LogFilter::default()).unwrap().len(), 2);
+
+    let at_least_warn = LogFilter { level: Some(Level::Warn), ..Default::default() };
+    let messages: Vec<String> = daemon_log::tail(&path, 50, &at_least_warn).unwrap()
+        .into_iter().map(|line| line.message).collect();
+    assert_eq!(messages, vec!["❌ Failed to spawn docs", "  stack line", "⚠️ Reference resolution failed"]);
+
+    let filter = LogFilter {
+        grep: Some(Regex::new("(?i)fail").unwrap()),
+        since: Some(Local.with_ymd_and_hms(2024, 6, 1, 10, 30, 0).unwrap().with_timezone(&Utc)),
+        ..Default::default()
+    };
+    let lines = daemon_log::tail(&path, 50, &filter).unwrap();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].message, "⚠️ Reference resolution failed");
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_follow_sees_new_lines_after_truncation() {
+    let path = temp_log("follow", "2024/06/01 10:00:00 old line\n");
+
+    let writer_path = path.clone();
+    let writer = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(400));
+        let mut file = std::fs::OpenOptions::new().append(true).open(&writer_path).unwrap();
+        // Written in two parts: nothing is shown until the newline arrives
+        write!(file, "2024/06/01 10:00:01 first").unwrap();
+        file.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(400));
+        writeln!(file, " half").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(400));
+        // A restarted daemon starts the file over
+        std::fs::write(&writer_path, "2024/06/01 10:00:02 after restart\n").unwrap();
+    });
+
+    let mut seen = Vec::new();
+    daemon_log::follow(&path, &LogFilter::default(), |line| {
+        seen.push(line.message.clone());
+        seen.len() < 2
+    }).unwrap();
+    writer.join().unwrap();
+
+    assert_eq!(seen, vec!["first half", "after restart"]);
+    std::fs::remove_file(&path).ok();
+}
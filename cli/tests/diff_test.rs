@@ -0,0 +1,78 @@
+use port42::display::diff::{Change, TextDiff, diff_lines, hunks, json_patch};
+use serde_json::{json, Value};
+
+#[test]
+fn test_diff_lines_keeps_common_lines() {
+    let changes = diff_lines("a\nb\nc\nd", "a\nc\nx\nd");
+    assert_eq!(changes, vec![
+        Change::Keep("a".into()),
+        Change::Remove("b".into()),
+        Change::Keep("c".into()),
+        Change::Add("x".into()),
+        Change::Keep("d".into()),
+    ]);
+    assert!(diff_lines("same\ntext", "same\ntext").iter().all(|c| matches!(c, Change::Keep(_))));
+}
+
+#[test]
+fn test_hunks_carry_context_and_merge() {
+    let old: Vec<String> = (1..=20).map(|n| n.to_string()).collect();
+    let mut new = old.clone();
+    new[1] = "two".into();
+    new[17] = "eighteen".into();
+    let changes = diff_lines(&old.join("\n"), &new.join("\n"));
+
+    // Far apart, the two edits get a hunk each with three lines of context
+    let far = hunks(&changes, 3);
+    assert_eq!(far.len(), 2);
+    assert_eq!(far[0].header(), "@@ -1,5 +1,5 @@");
+    assert_eq!(far[1].header(), "@@ -15,6 +15,6 @@");
+
+    // With enough context they touch and become one
+    let near = hunks(&changes, 8);
+    assert_eq!(near.len(), 1);
+    assert_eq!(near[0].header(), "@@ -1,20 +1,20 @@");
+}
+
+#[test]
+fn test_hunk_headers_for_pure_inserts() {
+    let changes = diff_lines("a\nb", "a\nnew\nb");
+    let zero = hunks(&changes, 0);
+    assert_eq!(zero[0].header(), "@@ -1,0 +2 @@");
+
+    let fresh = hunks(&diff_lines("", "first\nsecond"), 3);
+    assert_eq!(fresh[0].header(), "@@ -0,0 +1,2 @@");
+}
+
+#[test]
+fn test_text_diff_unified_output() {
+    colored::control::set_override(false);
+    let diff = TextDiff::new("/commands/old", "x\ny", "/commands/new", "x\nz", 3);
+    assert!(!diff.identical);
+    assert_eq!(diff.unified(), "--- /commands/old\n+++ /commands/new\n@@ -1,2 +1,2 @@\n x\n-y\n+z");
+    assert!(TextDiff::new("a", "same", "b", "same", 3).identical);
+
+    let json = serde_json::to_value(&diff).unwrap();
+    assert_eq!(json["hunks"][0]["changes"][1], serde_json::json!({"op": "remove", "line": "y"}));
+}
+
+#[test]
+fn test_json_patch_applies_to_the_old_lines() {
+    let (old, new) = ("a\nb\nc\nd\ne", "a\nc\nx\nd\ny\nz");
+    let patch = json_patch(&diff_lines(old, new));
+    assert_eq!(serde_json::to_value(&patch).unwrap(), json!([
+        {"op": "remove", "path": "/1"},
+        {"op": "add", "path": "/2", "value": "x"},
+        {"op": "remove", "path": "/4"},
+        {"op": "add", "path": "/4", "value": "y"},
+        {"op": "add", "path": "/5", "value": "z"},
+    ]));
+
+    let lines = |text: &str| Value::from(text.lines().collect::<Vec<_>>());
+    let mut doc = lines(old);
+    json_patch::patch(&mut doc, &patch).unwrap();
+    assert_eq!(doc, lines(new));
+
+    // Nothing to do is an empty patch
+    assert!(TextDiff::new("a", "same", "b", "same", 3).patch.0.is_empty());
+}
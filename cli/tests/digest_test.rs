@@ -0,0 +1,33 @@
+use chrono::{DateTime, Duration, Utc};
+use port42::common::digest::{self, Digest, DigestSession};
+use port42::protocol::hooks::CommandCount;
+
+#[test]
+fn test_digest_window_and_markdown() {
+    let until: DateTime<Utc> = "2025-03-02T08:00:00Z".parse().unwrap();
+    let since = until - Duration::hours(24);
+    assert!(digest::within("2025-03-01T12:00:00+01:00", since, until));
+    assert!(!digest::within("2025-02-28T12:00:00Z", since, until));
+    assert!(!digest::within("yesterday", since, until));
+
+    let mut digest = Digest {
+        since,
+        until,
+        sessions: vec![DigestSession { id: "cli-1".into(), agent: "@ai-engineer".into(), messages: 6, created_tool: true }],
+        tools: vec!["git-haiku".into()],
+        top_commands: Some(vec![CommandCount { command: "cargo test".into(), count: 12 }]),
+        narrative: None,
+    };
+    assert_eq!(digest.hours(), 24);
+    assert_eq!(digest.headline(), "1 sessions, 1 tools created, 12 commands run");
+    assert!(digest.facts().contains("- cli-1 with @ai-engineer (6 messages, created a tool)"));
+
+    digest.narrative = Some("A productive day.\n".into());
+    let md = digest.to_markdown();
+    assert!(md.starts_with("# Port 42 digest: 2025-03-01 08:00 to 2025-03-02 08:00 UTC\n\nA productive day.\n\n## Sessions (1)"));
+    assert!(md.contains("- `git-haiku`"));
+    assert!(md.contains("- `cargo test` (12x)"));
+
+    digest.top_commands = None;
+    assert!(!digest.to_markdown().contains("Top commands"));
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::{port42, temp_home};
+use base64::{engine::general_purpose, Engine as _};
+use port42::testing::{MockDaemon, Reply};
+use serde_json::json;
+
+#[test]
+fn test_declare_dry_run_writes_nothing() {
+    let home = temp_home("dry-run", "declare");
+    let daemon = MockDaemon::start();
+    daemon.respond("declare_relation", json!({
+        "relation_id": "rel-shiny", "type": "Tool", "materialized": false,
+        "physical_path": "/commands/shiny", "status": "preview", "content": "print('shiny')\n",
+        "metadata": {"type": "tool", "language": "python"}, "paths": ["/commands/shiny", "/tools/shiny"],
+    }));
+    let output = port42(&home, &daemon, &["declare", "tool", "shiny", "--dry-run"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("print('shiny')"), "{}", stdout);
+    assert!(stdout.contains("nothing was written"), "{}", stdout);
+
+    // Only the preview went out
+    let declared = daemon.requests_of("declare_relation");
+    assert_eq!(declared.len(), 1);
+    assert_eq!(declared[0]["payload"]["preview"], true);
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_declare_dry_run_needs_a_daemon_that_previews() {
+    let home = temp_home("dry-run", "legacy");
+    let daemon = MockDaemon::start();
+    daemon.on("hello", Reply::error("Unknown request type: hello"));
+    let output = port42(&home, &daemon, &["declare", "tool", "shiny", "--dry-run"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("nothing was sent"), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(daemon.requests_of("declare_relation").is_empty());
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_declare_dry_run_never_claims_a_write_was_a_preview() {
+    let home = temp_home("dry-run", "wrote");
+    let daemon = MockDaemon::start();
+    daemon.respond("declare_relation", json!({
+        "relation_id": "rel-shiny", "type": "Tool", "materialized": true,
+        "physical_path": "/commands/shiny", "status": "success",
+    }));
+    let output = port42(&home, &daemon, &["declare", "tool", "shiny", "--dry-run"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("instead of previewing"), "{}", stderr);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("nothing was written"));
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_evolve_dry_run_leaves_the_tool() {
+    let home = temp_home("dry-run", "evolve");
+    let daemon = MockDaemon::start();
+    daemon.respond("read_path", json!({
+        "path": "/commands/git-haiku",
+        "content": general_purpose::STANDARD.encode("#!/bin/sh\necho five seven five\n"),
+    }))
+    .respond("declare_relation", json!({
+        "relation_id": "rel-git-haiku", "type": "Tool", "materialized": false,
+        "physical_path": "/commands/git-haiku", "status": "preview",
+        "action": "update", "content": "#!/bin/sh\necho seven five seven\n",
+    }));
+    let output = port42(&home, &daemon, &["evolve", "git-haiku", "--dry-run"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("+echo seven five seven"), "{}", stdout);
+    assert!(stdout.contains("nothing was changed"), "{}", stdout);
+    assert!(daemon.requests_of("update_path").is_empty());
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_declare_phases_stay_off_stdout() {
+    let home = temp_home("dry-run", "phases");
+    let daemon = MockDaemon::start();
+    daemon.on("declare_relation", Reply::lines(vec![
+        json!({"status": {"phase": "prompting", "detail": "claude-sonnet-4"}}),
+        json!({"success": true, "data": {
+            "relation_id": "rel-shiny", "type": "Tool", "materialized": false,
+            "physical_path": "/commands/shiny", "status": "preview", "content": "print('shiny')\n",
+        }}),
+    ]));
+    let output = port42(&home, &daemon, &["declare", "tool", "shiny", "--dry-run"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Prompting AI"), "{}", stderr);
+    assert!(!stdout.contains("Prompting AI"), "{}", stdout);
+    assert!(stdout.contains("print('shiny')"), "{}", stdout);
+
+    std::fs::remove_dir_all(&home).ok();
+}
@@ -0,0 +1,59 @@
+use anyhow::Context;
+use port42::common::errors::{exit_code, ErrorReport, Port42Error};
+
+#[test]
+fn test_daemon_errors_are_classified_by_prefix() {
+    assert!(matches!(Port42Error::from_daemon("API_KEY_ERROR: missing key"), Port42Error::ApiKey(msg) if msg == "missing key"));
+    assert!(matches!(Port42Error::from_daemon("OPENAI_API_ERROR: 500"), Port42Error::ProviderApi(p, _) if p == "openai"));
+    assert!(matches!(Port42Error::from_daemon("path not found: /commands/x"), Port42Error::NotFound(_)));
+    assert!(matches!(Port42Error::from_daemon("database locked"), Port42Error::Daemon(_)));
+    let unsupported = Port42Error::from_daemon("Unknown request type: list_jobs");
+    assert!(matches!(unsupported, Port42Error::Unsupported(ref kind) if kind == "list_jobs"));
+    assert_eq!(unsupported.to_string(), "The daemon does not support list_jobs requests");
+    assert_eq!(unsupported.exit_code(), exit_code::INCOMPATIBLE);
+}
+
+#[test]
+fn test_lookup_failures_keep_their_kind() {
+    let lookup = |error: Option<&str>| Port42Error::from_lookup(error, "🔍 Nowhere", "Path '/x' does not exist");
+    assert!(matches!(lookup(Some("path not found: /x")), Port42Error::NotFound(msg) if msg.contains("does not exist")));
+    assert!(matches!(lookup(None), Port42Error::NotFound(_)));
+    // A daemon in trouble isn't a missing path
+    assert_eq!(lookup(Some("database locked")).exit_code(), exit_code::DAEMON);
+    assert_eq!(lookup(Some("NETWORK_ERROR: timed out")).exit_code(), exit_code::PROVIDER);
+}
+
+#[test]
+fn test_provider_outages_need_the_status_itself() {
+    use port42::swim::is_provider_outage_error;
+    assert!(is_provider_outage_error("upstream returned 529"));
+    assert!(is_provider_outage_error("status 529: try later"));
+    assert!(is_provider_outage_error("The model is overloaded"));
+    // Digits that merely contain 529 aren't a status
+    assert!(!is_provider_outage_error("session cli-15290 not found"));
+    assert!(!is_provider_outage_error("prompt is 45291 tokens, over the limit"));
+    assert!(!is_provider_outage_error("database locked on port 15293"));
+}
+
+#[test]
+fn test_report_uses_innermost_typed_error() {
+    let err: anyhow::Error = Err::<(), _>(Port42Error::ApiKey("no key".to_string()))
+        .context("Failed to swim")
+        .unwrap_err();
+    let report = ErrorReport::from_error(&err);
+    assert_eq!(report.code, exit_code::API_KEY);
+    assert_eq!(report.kind, "api_key");
+    assert_eq!(report.message, "Failed to swim: API key error: no key");
+}
+
+#[test]
+fn test_report_falls_back_on_untyped_errors() {
+    let refused = anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::ConnectionRefused));
+    assert_eq!(ErrorReport::from_error(&refused).code, exit_code::CONNECTION);
+
+    let missing = anyhow::anyhow!("Session abc does not exist");
+    assert_eq!(ErrorReport::from_error(&missing).kind, "not_found");
+
+    let other = anyhow::anyhow!("something broke");
+    assert_eq!(ErrorReport::from_error(&other).code, exit_code::FAILURE);
+}
@@ -0,0 +1,47 @@
+use port42::client::DaemonClient;
+use port42::protocol::RequestBuilder;
+use port42::protocol::events::{EventKind, SubscribeRequest};
+use port42::testing::{MockDaemon, Reply};
+use serde_json::json;
+
+fn subscribe_request() -> port42::protocol::DaemonRequest {
+    SubscribeRequest { types: vec![EventKind::ToolCreated, EventKind::RuleFired] }.build_request("sub".to_string()).unwrap()
+}
+
+#[test]
+fn test_subscribe_passes_pushed_events() {
+    let daemon = MockDaemon::start();
+    daemon.on("subscribe", Reply::lines(vec![
+        json!({"success": true}),
+        json!({"event": {"type": "tool_created", "timestamp": "2024-06-01T10:00:00Z", "subject": "git-haiku"}}),
+        json!({"keepalive": true}),
+        json!({"event": {"type": "daemon_reloaded", "timestamp": "2024-06-01T10:00:01Z", "subject": "x"}}),
+        json!({"event": {"type": "rule_fired", "timestamp": "2024-06-01T10:00:02Z", "subject": "on-tool", "data": {"rule": "r1"}}}),
+    ]));
+    let mut seen = Vec::new();
+    DaemonClient::new(daemon.port()).subscribe(subscribe_request(), &mut |event| {
+        seen.push(event);
+        seen.len() < 3
+    }).unwrap();
+
+    let requests = daemon.requests_of("subscribe");
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0]["payload"]["types"], json!(["tool_created", "rule_fired"]));
+
+    let kinds: Vec<EventKind> = seen.iter().map(|e| e.kind).collect();
+    assert_eq!(kinds, vec![EventKind::ToolCreated, EventKind::Unknown, EventKind::RuleFired]);
+    assert_eq!(seen[0].subject, "git-haiku");
+    assert_eq!(seen[2].data["rule"], "r1");
+}
+
+#[test]
+fn test_subscribe_refused_or_closed() {
+    // A daemon without subscribe
+    let daemon = MockDaemon::start();
+    let err = DaemonClient::new(daemon.port()).subscribe(subscribe_request(), &mut |_| true).unwrap_err();
+    assert!(err.to_string().contains("does not support subscribe"));
+
+    daemon.on("subscribe", Reply::lines(vec![json!({"success": true})]).then_hang_up());
+    let err = DaemonClient::new(daemon.port()).subscribe(subscribe_request(), &mut |_| true).unwrap_err();
+    assert!(err.to_string().contains("closed the event stream"));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{port42, temp_home};
+use base64::{engine::general_purpose, Engine as _};
+use port42::testing::{MockDaemon, Reply};
+use serde_json::json;
+
+/// A daemon holding git-haiku, ready to offer `evolved` as its next version
+fn daemon_with_tool(evolved: &str) -> MockDaemon {
+    let daemon = MockDaemon::start();
+    daemon.respond("read_path", json!({
+        "path": "/commands/git-haiku",
+        "content": general_purpose::STANDARD.encode("#!/bin/sh\necho five seven five\n"),
+    }))
+    .respond("declare_relation", json!({
+        "relation_id": "rel-git-haiku", "type": "Tool", "materialized": false,
+        "physical_path": "/commands/git-haiku", "status": "preview",
+        "action": "update", "content": evolved,
+    }))
+    .respond("update_path", json!({"path": "/commands/git-haiku", "version": 2}));
+    daemon
+}
+
+#[test]
+fn test_evolve_applies_as_update() {
+    let home = temp_home("evolve", "apply");
+    let daemon = daemon_with_tool("#!/bin/sh\necho five seven five seven seven\n");
+    let output = port42(&home, &daemon, &["evolve", "git-haiku", "more syllables"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("seven seven"));
+
+    // Generated as a preview of an update, then written over the old version
+    let declared = daemon.requests_of("declare_relation");
+    assert_eq!(declared[0]["payload"]["preview"], true);
+    assert_eq!(declared[0]["payload"]["relation"]["properties"]["evolves"], "/commands/git-haiku");
+    assert_eq!(declared[0]["user_prompt"], "more syllables");
+    let updated = daemon.requests_of("update_path");
+    assert_eq!(updated.len(), 1);
+    let content = general_purpose::STANDARD.decode(updated[0]["payload"]["content"].as_str().unwrap()).unwrap();
+    assert_eq!(String::from_utf8(content).unwrap(), "#!/bin/sh\necho five seven five seven seven\n");
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_evolve_needs_an_existing_tool() {
+    let home = temp_home("evolve", "missing");
+    let daemon = MockDaemon::start();
+    let output = port42(&home, &daemon, &["evolve", "git-haiku"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("port42 declare tool git-haiku"));
+    assert!(daemon.requests_of("declare_relation").is_empty());
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_evolve_on_a_daemon_without_previews_shows_what_it_wrote() {
+    let home = temp_home("evolve", "legacy");
+    let daemon = MockDaemon::start();
+    let read = |content: &str| Reply::ok(json!({
+        "path": "/commands/git-haiku",
+        "content": general_purpose::STANDARD.encode(content),
+    }));
+    daemon.on("hello", Reply::error("Unknown request type: hello"))
+        .on("read_path", read("#!/bin/sh\necho five seven five\n"))
+        .on("read_path", read("#!/bin/sh\necho seven five seven\n"))
+        .respond("declare_relation", json!({
+            "relation_id": "rel-git-haiku", "type": "Tool", "materialized": true,
+            "physical_path": "/commands/git-haiku", "status": "success",
+        }));
+
+    let output = port42(&home, &daemon, &["evolve", "git-haiku"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("+echo seven five seven"));
+
+    // The daemon wrote it; nothing is asked of it twice
+    let declared = daemon.requests_of("declare_relation");
+    assert_eq!(declared.len(), 1);
+    assert!(declared[0]["payload"].get("preview").is_none());
+    assert!(daemon.requests_of("update_path").is_empty());
+
+    let output = port42(&home, &daemon, &["evolve", "git-haiku", "--dry-run"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("nothing was sent"));
+    assert_eq!(daemon.requests_of("declare_relation").len(), 1);
+
+    std::fs::remove_dir_all(&home).ok();
+}
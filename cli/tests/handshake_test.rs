@@ -0,0 +1,162 @@
+use assert_cmd::Command;
+use port42::client::{self, DaemonClient};
+use port42::common::errors::exit_code;
+use port42::protocol::hello::{HelloResponse, PROTOCOL_REVISION, versions_compatible};
+use port42::protocol::DaemonRequest;
+use port42::testing::{MockDaemon, Reply};
+use serde_json::{json, Value};
+
+fn status_request() -> DaemonRequest {
+    DaemonRequest {
+        request_type: "status".to_string(),
+        id: "s1".to_string(),
+        payload: Value::Null,
+        references: None,
+        session_context: None,
+        user_prompt: None,
+        provider: None,
+    }
+}
+
+/// A daemon that has moved on to a protocol this CLI doesn't speak
+fn newer_daemon() -> MockDaemon {
+    let daemon = MockDaemon::start();
+    daemon.respond("hello", json!({"version": "9.0.0", "protocol": PROTOCOL_REVISION + 1, "min_protocol": PROTOCOL_REVISION + 1}))
+        .respond("status", json!({"port": daemon.port(), "uptime": "1m", "active_sessions": 0}));
+    daemon
+}
+
+fn run_status(daemon: &MockDaemon, strict: bool) -> std::process::Output {
+    let home = std::env::temp_dir().join(format!("port42-handshake-test-{}-{}", daemon.port(), std::process::id()));
+    let mut command = Command::cargo_bin("port42").unwrap();
+    command.env("HOME", &home).env_remove("PORT42_STRICT")
+        .args(["status", "--json", "--port", &daemon.port().to_string()]);
+    if strict {
+        command.arg("--strict");
+    }
+    let output = command.output().unwrap();
+    let _ = std::fs::remove_dir_all(&home);
+    output
+}
+
+#[test]
+fn test_incompatibility() {
+    assert_eq!(HelloResponse::legacy().incompatibility(), None);
+    let current = HelloResponse { version: Some("0.1.0".to_string()), protocol: PROTOCOL_REVISION, min_protocol: 0, features: Vec::new() };
+    assert_eq!(current.incompatibility(), None);
+
+    let newer = HelloResponse { version: Some("9.0.0".to_string()), protocol: PROTOCOL_REVISION + 1, min_protocol: PROTOCOL_REVISION + 1, features: Vec::new() };
+    let problem = newer.incompatibility().unwrap();
+    assert!(problem.reason.contains("v9.0.0"), "{}", problem.reason);
+    assert!(problem.fix.contains("port42 upgrade"));
+}
+
+#[test]
+fn test_handshake_once_per_daemon() {
+    let daemon = MockDaemon::start();
+    daemon.respond("status", json!({"port": daemon.port(), "uptime": "1m", "active_sessions": 0}));
+
+    let mut client = DaemonClient::new(daemon.port());
+    assert!(client.request(status_request()).unwrap().success);
+    client.disconnect();
+    assert!(client.request(status_request()).unwrap().success);
+
+    let hello = client::handshake(daemon.port()).unwrap();
+    assert_eq!(hello.protocol, PROTOCOL_REVISION);
+    assert_eq!(hello.version.as_deref(), Some(env!("PORT42_VERSION")));
+    // The handshake isn't one of the daemon's requests
+    assert_eq!(daemon.requests().len(), 2);
+}
+
+#[test]
+fn test_daemon_without_hello_is_legacy() {
+    let daemon = MockDaemon::start();
+    daemon.one_request_per_connection()
+        .on("hello", Reply::error("Unknown request type: hello"))
+        .respond("status", json!({"port": daemon.port(), "uptime": "1m", "active_sessions": 0}));
+
+    let mut client = DaemonClient::new(daemon.port());
+    assert!(client.request(status_request()).unwrap().success);
+    assert_eq!(client::handshake(daemon.port()), Some(HelloResponse::legacy()));
+}
+
+#[test]
+fn test_hello_leaves_the_request_a_connection() {
+    // The Go daemon hangs up after each answer, hello included
+    let daemon = MockDaemon::start();
+    daemon.one_request_per_connection()
+        .respond("status", json!({"port": daemon.port(), "uptime": "1m", "active_sessions": 0}));
+    let home = std::env::temp_dir().join(format!("port42-handshake-test-oneshot-{}", std::process::id()));
+    let output = Command::cargo_bin("port42").unwrap()
+        .env("HOME", &home)
+        .args(["status", "--json", "--retries", "0", "--port", &daemon.port().to_string()])
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&home);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("retrying"), "{}", stderr);
+    assert_eq!(daemon.requests_of("status").len(), 1);
+}
+
+#[test]
+fn test_incompatible_daemon_warns() {
+    let daemon = newer_daemon();
+    let output = run_status(&daemon, false);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("needs protocol"), "{}", stderr);
+    assert_eq!(stderr.matches("needs protocol").count(), 1, "{}", stderr);
+    assert_eq!(daemon.requests_of("status").len(), 1);
+}
+
+#[test]
+fn test_incompatible_daemon_refused_when_strict() {
+    let daemon = newer_daemon();
+    let output = run_status(&daemon, true);
+
+    assert_eq!(output.status.code(), Some(exit_code::INCOMPATIBLE));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("port42 upgrade"), "{}", stderr);
+    assert!(daemon.requests_of("status").is_empty());
+}
+
+/// A daemon from before `hello` whose status names its release
+fn legacy_daemon(version: &str) -> MockDaemon {
+    let daemon = MockDaemon::start();
+    daemon.one_request_per_connection()
+        .on("hello", Reply::error("Unknown request type: hello"))
+        .respond("status", json!({"port": daemon.port(), "uptime": "1m", "active_sessions": 0, "version": version}));
+    daemon
+}
+
+#[test]
+fn test_versions_compatible() {
+    assert!(versions_compatible("0.1.1", "v0.1.9"));
+    assert!(!versions_compatible("0.1.1", "0.2.0"));
+    assert!(!versions_compatible("1.0.0", "0.1.0"));
+}
+
+#[test]
+fn test_legacy_daemon_from_another_release_refused_when_strict() {
+    let other = if env!("PORT42_VERSION").starts_with("9.") { "8.0.0" } else { "9.0.0" };
+    let daemon = legacy_daemon(other);
+    let output = run_status(&daemon, true);
+    assert_eq!(output.status.code(), Some(exit_code::INCOMPATIBLE), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("port42 daemon restart"));
+
+    let daemon = legacy_daemon(other);
+    let output = run_status(&daemon, false);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("another release"));
+}
+
+#[test]
+fn test_legacy_daemon_from_this_release_is_fine() {
+    let daemon = legacy_daemon(env!("PORT42_VERSION"));
+    let output = run_status(&daemon, true);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("another release"));
+}
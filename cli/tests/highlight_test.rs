@@ -0,0 +1,51 @@
+use port42::display::highlight::{Highlighter, Language, Theme};
+
+#[test]
+fn test_language_detection() {
+    assert_eq!(Language::detect("/commands/x", "#!/usr/bin/env python3\nprint(1)", None), Some(Language::Python));
+    assert_eq!(Language::detect("/commands/x", "#!/bin/bash\necho hi", None), Some(Language::Shell));
+    assert_eq!(Language::detect("/artifacts/app.ts", "let x = 1", None), Some(Language::TypeScript));
+    // Metadata outranks the shebang and the extension
+    assert_eq!(Language::detect("/artifacts/run.py", "#!/bin/sh", Some("ruby")), Some(Language::Ruby));
+    assert_eq!(Language::detect("/artifacts/notes", "plain words", None), None);
+}
+
+#[test]
+fn test_tokens_are_painted() {
+    colored::control::set_override(true);
+    let mut highlighter = Highlighter::new(Language::Python, Theme::named("default").unwrap());
+    let line = highlighter.line("def f(): return 'a # b'  # note");
+    assert!(line.starts_with("\x1b[95mdef\x1b[0m f(): "));
+    // The # inside the string is not a comment
+    assert!(line.contains("\x1b[32m'a # b'\x1b[0m"));
+    assert!(line.ends_with("\x1b[2m# note\x1b[0m"));
+}
+
+#[test]
+fn test_block_comments_span_lines() {
+    colored::control::set_override(true);
+    let mut highlighter = Highlighter::new(Language::Rust, Theme::named("mono").unwrap());
+    assert_eq!(highlighter.line("let a = 1; /* open"), "\x1b[1mlet\x1b[0m a = 1; \x1b[2m/* open\x1b[0m");
+    assert_eq!(highlighter.line("still */ fn"), "\x1b[2mstill */\x1b[0m \x1b[1mfn\x1b[0m");
+}
+
+#[test]
+fn test_apostrophes_in_comments_and_heredocs_stay_put() {
+    colored::control::set_override(true);
+    let mut highlighter = Highlighter::new(Language::Shell, Theme::named("default").unwrap());
+    let lines: Vec<String> = ["#!/bin/sh", "# don't panic", "cat <<EOF", "It's fine", "EOF", "echo \"done\""]
+        .iter()
+        .map(|line| highlighter.line(line))
+        .collect();
+    assert_eq!(lines[1], "\x1b[2m# don't panic\x1b[0m");
+    // The heredoc closed, so the code after it is code again
+    assert!(!lines[5].starts_with("\x1b[32m"), "{:?}", lines[5]);
+    assert!(lines[5].contains("\x1b[32m\"done\"\x1b[0m"), "{:?}", lines[5]);
+}
+
+#[test]
+fn test_languages_without_a_grammar_stay_plain() {
+    use port42::display::highlight::highlight;
+    assert_eq!(highlight("/artifacts/app.ts", "let x: string = 'a'", None), None);
+    assert_eq!(highlight("/artifacts/Cargo.toml", "name = \"x\"", None), None);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::{port42, temp_home};
+use port42::common::errors::exit_code;
+use port42::protocol::{CatRequest, HistoryRequest, InfoRequest, RequestBuilder, ResponseParser, RollbackRequest, RollbackResponse, UpdatePathRequest, UpdatePathResponse, VersionHistory};
+use port42::testing::MockDaemon;
+use serde_json::json;
+
+#[test]
+fn test_version_only_sent_when_asked_for() {
+    let current = CatRequest { path: "/commands/git-haiku".to_string(), version: None }
+        .build_request("id".to_string())
+        .unwrap();
+    assert_eq!(current.payload, json!({"path": "/commands/git-haiku"}));
+
+    let earlier = InfoRequest { path: "/commands/git-haiku".to_string(), version: Some(2) }
+        .build_request("id".to_string())
+        .unwrap();
+    assert_eq!(earlier.request_type, "get_metadata");
+    assert_eq!(earlier.payload["version"], 2);
+}
+
+#[test]
+fn test_history_lists_newest_first() {
+    let request = HistoryRequest { path: "/commands/git-haiku".to_string() }
+        .build_request("id".to_string())
+        .unwrap();
+    assert_eq!(request.request_type, "list_versions");
+
+    let history = VersionHistory::parse_response(&json!({
+        "path": "/commands/git-haiku",
+        "versions": [
+            {"version": 1, "created": "2025-08-01T10:00:00Z", "session_id": "cli-1"},
+            {"version": 3, "current": true},
+            {"version": 2, "agent": "@ai-muse"}
+        ]
+    })).unwrap();
+    let order: Vec<u32> = history.versions.iter().map(|v| v.version).collect();
+    assert_eq!(order, vec![3, 2, 1]);
+    assert!(history.versions[0].current);
+    assert_eq!(history.versions[2].session_id.as_deref(), Some("cli-1"));
+    assert!(VersionHistory::parse_response(&json!({})).unwrap().versions.is_empty());
+}
+
+#[test]
+fn test_rollback_round_trip() {
+    let request = RollbackRequest { path: "/commands/git-haiku".to_string(), version: 1 }
+        .build_request("id".to_string())
+        .unwrap();
+    assert_eq!(request.request_type, "rollback_version");
+    assert_eq!(request.payload, json!({"path": "/commands/git-haiku", "version": 1}));
+
+    let result = RollbackResponse::parse_response(&json!({"path": "/commands/git-haiku", "restored_version": 1, "version": 3})).unwrap();
+    assert_eq!((result.restored_version, result.version), (1, Some(3)));
+    assert!(RollbackResponse::parse_response(&json!({"restored_version": 1})).unwrap().version.is_none());
+}
+
+#[test]
+fn test_update_round_trip() {
+    let request = UpdatePathRequest { path: "/commands/git-haiku".to_string(), content: b"#!/bin/sh\n".to_vec() }
+        .build_request("id".to_string())
+        .unwrap();
+    assert_eq!(request.request_type, "update_path");
+    assert_eq!(request.payload, json!({"path": "/commands/git-haiku", "content": "IyEvYmluL3NoCg=="}));
+
+    // The daemon answers with the new object id and where it lives
+    let result = UpdatePathResponse::parse_response(&json!({"id": "abc", "modified": "2026-01-01T00:00:00Z", "paths": ["/commands/git-haiku"]})).unwrap();
+    assert_eq!(result.id, "abc");
+    assert_eq!(result.paths, vec!["/commands/git-haiku"]);
+}
+
+#[test]
+fn test_cat_refuses_another_version_than_asked_for() {
+    let home = temp_home("history", "cat-version");
+    let daemon = MockDaemon::start();
+    // A daemon that ignores the version and answers with the current content
+    daemon.respond("read_path", json!({"path": "/commands/git-haiku", "content": "IyEvYmluL3NoCg=="}));
+    let output = port42(&home, &daemon, &["cat", "/commands/git-haiku", "--version", "2"]);
+    assert_eq!(output.status.code(), Some(exit_code::INCOMPATIBLE));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("may not keep versions"));
+    assert!(output.stdout.is_empty());
+
+    let daemon = MockDaemon::start();
+    daemon.respond("read_path", json!({"path": "/commands/git-haiku", "content": "IyEvYmluL3NoCg==", "version": 2}));
+    let output = port42(&home, &daemon, &["cat", "/commands/git-haiku", "--version", "2"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // Info finds it in the metadata too
+    daemon.respond("get_metadata", json!({"path": "/commands/git-haiku", "metadata": {"version": 3}}));
+    let output = port42(&home, &daemon, &["info", "/commands/git-haiku", "--version", "2"]);
+    assert_eq!(output.status.code(), Some(exit_code::INCOMPATIBLE));
+
+    std::fs::remove_dir_all(&home).ok();
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::{port42, temp_home};
+use port42::common::errors::exit_code;
+use port42::protocol::ResponseParser;
+use port42::protocol::jobs::{progress_bar, Job, JobList, JobState};
+use port42::testing::{MockDaemon, Reply};
+use serde_json::json;
+
+#[test]
+fn test_job_parses_bare_or_wrapped() {
+    let bare = json!({"job_id": "job-7", "kind": "declare", "status": "running", "progress": 40.0, "stage": "generating"});
+    let job = Job::parse_response(&bare).unwrap();
+    assert_eq!(job.id, "job-7");
+    assert_eq!(job.status, JobState::Running);
+    assert_eq!(job.progress_line(), "running   [########------------]  40%  generating");
+
+    let wrapped = json!({"job": {"id": "job-8", "kind": "swim", "status": "completed"}});
+    assert!(Job::parse_response(&wrapped).unwrap().status.is_finished());
+}
+
+#[test]
+fn test_unknown_job_states_are_tolerated() {
+    let list = JobList::parse_response(&json!({"jobs": [{"id": "a", "kind": "swim", "status": "paused"}]})).unwrap();
+    assert_eq!(list.jobs[0].status, JobState::Unknown);
+    assert!(!list.jobs[0].status.is_finished());
+    assert_eq!(JobList::parse_response(&json!({})).unwrap().jobs.len(), 0);
+}
+
+#[test]
+fn test_progress_bar_clamps() {
+    assert_eq!(progress_bar(0.0, 4), "[----]");
+    assert_eq!(progress_bar(50.0, 4), "[##--]");
+    assert_eq!(progress_bar(250.0, 4), "[####]");
+}
+
+#[test]
+fn test_detach_runs_here_on_daemons_that_cannot() {
+    let home = temp_home("jobs", "no-detach");
+    let daemon = MockDaemon::start();
+    daemon.on("hello", Reply::error("Unknown request type: hello"))
+        .respond("declare_relation", json!({
+            "relation_id": "rel-shiny", "type": "Tool", "materialized": true,
+            "physical_path": "/commands/shiny", "status": "success",
+        }));
+    let output = port42(&home, &daemon, &["declare", "tool", "shiny", "--detach"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("running this one here"));
+    let declared = daemon.requests_of("declare_relation");
+    assert_eq!(declared.len(), 1);
+    assert!(declared[0]["payload"].get("detach").is_none(), "{}", declared[0]);
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_jobs_on_a_daemon_without_them() {
+    let home = temp_home("jobs", "unsupported");
+    let daemon = MockDaemon::start();
+    let output = port42(&home, &daemon, &["jobs", "list"]);
+    assert_eq!(output.status.code(), Some(exit_code::INCOMPATIBLE));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("does not support list_jobs"), "{}", stderr);
+
+    std::fs::remove_dir_all(&home).ok();
+}
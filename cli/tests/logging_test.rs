@@ -0,0 +1,25 @@
+use port42::common::logging::{self, LogLevel};
+use std::collections::HashMap;
+
+fn directives(level: Option<LogLevel>, vars: &[(&str, &str)]) -> String {
+    let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+    logging::directives(level, |name| vars.get(name).map(|v| v.to_string()))
+}
+
+#[test]
+fn test_log_filter_precedence() {
+    assert_eq!(directives(None, &[]), "warn");
+    assert_eq!(directives(None, &[("PORT42_DEBUG", "1")]), "debug");
+    assert_eq!(
+        directives(None, &[("PORT42_VERBOSE", "1"), ("PORT42_DEBUG_KEYS", "1")]),
+        "warn,port42::requests=trace,port42::keys=trace"
+    );
+
+    // PORT42_LOG replaces the old switches, --log-level replaces everything
+    let env = [("PORT42_LOG", "info,port42::client=trace"), ("PORT42_DEBUG", "1")];
+    assert_eq!(directives(None, &env), "info,port42::client=trace");
+    assert_eq!(directives(Some(LogLevel::Error), &env), "error");
+
+    // An empty PORT42_LOG is as good as unset
+    assert_eq!(directives(None, &[("PORT42_LOG", " ")]), "warn");
+}
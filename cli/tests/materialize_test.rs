@@ -0,0 +1,26 @@
+use port42::common::materialize::{Change, compare, conflict_path, local_path};
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_compare_against_base() {
+    assert_eq!(compare("a", "a", "a"), Change::Unchanged);
+    assert_eq!(compare("a", "b", "a"), Change::Local);
+    assert_eq!(compare("a", "a", "b"), Change::Remote);
+    assert_eq!(compare("a", "b", "b"), Change::Converged);
+    assert_eq!(compare("a", "b", "c"), Change::Conflict);
+    // A file that was never synced has no base: differing sides conflict
+    assert_eq!(compare("", "b", "c"), Change::Conflict);
+}
+
+#[test]
+fn test_local_paths() {
+    let dest = Path::new("/work/docs");
+    assert_eq!(local_path("/artifacts/docs", "/artifacts/docs/deep/notes.txt", dest), PathBuf::from("/work/docs/deep/notes.txt"));
+    assert_eq!(local_path("/artifacts/docs/", "/artifacts/docs/api.md", dest), PathBuf::from("/work/docs/api.md"));
+    // A single object lands at the destination itself
+    assert_eq!(local_path("/commands/tool", "/commands/tool", Path::new("/work/tool")), PathBuf::from("/work/tool"));
+    // Nothing escapes the destination
+    assert_eq!(local_path("/a", "/a/../../etc/passwd", dest), PathBuf::from("/work/docs/etc/passwd"));
+
+    assert_eq!(conflict_path(Path::new("/work/docs/api.md")), PathBuf::from("/work/docs/api.md.vfs"));
+}
@@ -0,0 +1,99 @@
+use port42::context::memory_tui::fuzzy_score;
+use port42::protocol::{MemoryDetailResponse, MemoryForkRequest, MemoryListResponse, MemoryMergeRequest, MemoryRenameRequest, RequestBuilder, ResponseParser};
+use serde_json::json;
+
+#[test]
+fn test_memory_as_context_keeps_recent_messages() {
+    let detail = MemoryDetailResponse::parse_response(&json!({
+        "id": "cli-7",
+        "agent": "@ai-muse",
+        "state": "completed",
+        "created_at": "2025-01-01T00:00:00Z",
+        "last_activity": "2025-01-01T00:05:00Z",
+        "messages": [
+            {"role": "user", "content": "first question that is fairly long", "timestamp": "2025-01-01T00:00:00Z"},
+            {"role": "assistant", "content": "first answer", "timestamp": "2025-01-01T00:01:00Z"},
+            {"role": "user", "content": "second", "timestamp": "2025-01-01T00:02:00Z"}
+        ]
+    })).unwrap();
+
+    let full = detail.as_context(10_000);
+    assert!(full.starts_with("=== Reference: memory cli-7 (@ai-muse) ===\n\n"));
+    assert!(full.ends_with("user: first question that is fairly long\n\nassistant: first answer\n\nuser: second"));
+
+    let trimmed = detail.as_context(40);
+    assert!(trimmed.contains("[1 earlier messages omitted]"));
+    assert!(!trimmed.contains("first question"));
+    assert!(trimmed.ends_with("assistant: first answer\n\nuser: second"));
+}
+
+#[test]
+fn test_fork_lineage_round_trips() {
+    let request = MemoryForkRequest { session_id: "cli-7".to_string(), at: Some(4) }
+        .build_request("id".to_string())
+        .unwrap();
+    assert_eq!(request.request_type, "fork_session");
+    assert_eq!(request.payload["at_message"], 4);
+
+    let detail = MemoryDetailResponse::parse_response(&json!({
+        "id": "cli-8",
+        "messages": [],
+        "forked_from": {"session_id": "cli-7", "at_message": 4}
+    })).unwrap();
+    let origin = detail.forked_from.unwrap();
+    assert_eq!((origin.session_id.as_str(), origin.at_message), ("cli-7", 4));
+
+    let plain = MemoryDetailResponse::parse_response(&json!({"id": "cli-7", "forked_from": null})).unwrap();
+    assert!(plain.forked_from.is_none());
+}
+
+#[test]
+fn test_merge_request_and_provenance() {
+    let request = MemoryMergeRequest {
+        session_ids: vec!["a".to_string(), "b".to_string()],
+        name: None,
+        interleave: true,
+    }.build_request("id".to_string()).unwrap();
+    assert_eq!(request.payload["strategy"], "interleave");
+    assert_eq!(request.payload["session_ids"][1], "b");
+
+    let detail = MemoryDetailResponse::parse_response(&json!({
+        "id": "cli-9",
+        "merged_from": ["a", "b"],
+        "messages": [
+            {"role": "user", "content": "q", "timestamp": "2025-01-01T00:00:00Z", "source_session": "b"},
+            {"role": "user", "content": "r", "timestamp": "2025-01-01T00:01:00Z"}
+        ]
+    })).unwrap();
+    assert_eq!(detail.merged_from, vec!["a", "b"]);
+    assert_eq!(detail.messages[0].source_session.as_deref(), Some("b"));
+    assert!(detail.messages[1].source_session.is_none());
+}
+
+#[test]
+fn test_rename_request_and_session_names() {
+    let request = MemoryRenameRequest {
+        session_id: "cli-1".to_string(),
+        name: "haiku maker".to_string(),
+    }.build_request("id".to_string()).unwrap();
+    assert_eq!(request.request_type, "rename_session");
+    assert_eq!(request.payload["name"], "haiku maker");
+
+    let list = MemoryListResponse::parse_response(&json!({
+        "active_sessions": [{"id": "cli-1", "name": "haiku maker"}],
+        "recent_sessions": [{"id": "cli-2", "name": ""}, {"id": "cli-3"}]
+    })).unwrap();
+    assert_eq!(list.active_sessions[0].name.as_deref(), Some("haiku maker"));
+    assert!(list.recent_sessions.iter().all(|s| s.name.is_none()));
+}
+
+#[test]
+fn test_fuzzy_score_prefers_tight_matches() {
+    assert_eq!(fuzzy_score("", "anything"), Some(0));
+    assert!(fuzzy_score("ENG", "cli-1 @ai-engineer").is_some());
+    assert!(fuzzy_score("rgn", "engineer").is_none());
+
+    let tight = fuzzy_score("muse", "@ai-muse active").unwrap();
+    let loose = fuzzy_score("muse", "@ai-m u s e").unwrap();
+    assert!(tight < loose);
+}
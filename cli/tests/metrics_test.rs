@@ -0,0 +1,39 @@
+use port42::protocol::ResponseParser;
+use port42::protocol::metrics::{MetricKind, MetricsSnapshot};
+use serde_json::json;
+
+fn snapshot() -> MetricsSnapshot {
+    MetricsSnapshot::parse_response(&json!({"metrics": [
+        {"name": "port42_errors_total", "type": "counter", "help": "Failed requests", "samples": [
+            {"labels": {"type": "swim", "reason": "say \"no\""}, "value": 3}
+        ]},
+        {"name": "port42_tokens", "type": "summary", "samples": [{"value": 1.5}]},
+        {"name": "port42_latency_seconds", "type": "histogram", "samples": [
+            {"buckets": [{"le": 0.5, "count": 2}, {"le": 1, "count": 4}], "sum": 2.25, "count": 5}
+        ]}
+    ]})).unwrap()
+}
+
+#[test]
+fn test_unknown_metric_types_are_untyped() {
+    let metrics = snapshot();
+    assert_eq!(metrics.metrics[1].kind, MetricKind::Untyped);
+    assert!(MetricsSnapshot::parse_response(&json!({})).unwrap().metrics.is_empty());
+}
+
+#[test]
+fn test_prometheus_exposition() {
+    assert_eq!(snapshot().to_prometheus(), "\
+# HELP port42_errors_total Failed requests
+# TYPE port42_errors_total counter
+port42_errors_total{reason=\"say \\\"no\\\"\",type=\"swim\"} 3
+# TYPE port42_tokens untyped
+port42_tokens 1.5
+# TYPE port42_latency_seconds histogram
+port42_latency_seconds_bucket{le=\"0.5\"} 2
+port42_latency_seconds_bucket{le=\"1\"} 4
+port42_latency_seconds_bucket{le=\"+Inf\"} 5
+port42_latency_seconds_sum 2.25
+port42_latency_seconds_count 5
+");
+}
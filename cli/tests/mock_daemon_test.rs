@@ -0,0 +1,62 @@
+use assert_cmd::Command;
+use port42::client::DaemonClient;
+use port42::protocol::DaemonRequest;
+use port42::testing::{MockDaemon, Reply};
+use serde_json::{json, Value};
+
+fn request(kind: &str, id: &str) -> DaemonRequest {
+    DaemonRequest {
+        request_type: kind.to_string(),
+        id: id.to_string(),
+        payload: Value::Null,
+        references: None,
+        session_context: None,
+        user_prompt: None,
+        provider: None,
+    }
+}
+
+#[test]
+fn test_canned_replies_in_order() {
+    let daemon = MockDaemon::start();
+    daemon.on("search", Reply::error("index rebuilding"))
+        .respond("search", json!({"results": []}));
+
+    let mut client = DaemonClient::new(daemon.port());
+    let first = client.request(request("search", "s1")).unwrap();
+    assert!(!first.success);
+    assert_eq!(first.error.as_deref(), Some("index rebuilding"));
+    assert_eq!(first.id, "s1");
+
+    // The last reply keeps being given
+    for id in ["s2", "s3"] {
+        let response = client.request(request("search", id)).unwrap();
+        assert!(response.success);
+        assert_eq!(response.id, id);
+    }
+
+    let unknown = client.request(request("teleport", "t1")).unwrap();
+    assert_eq!(unknown.error.as_deref(), Some("Unknown request type: teleport"));
+
+    let ids: Vec<Value> = daemon.requests().iter().map(|r| r["id"].clone()).collect();
+    assert_eq!(ids, vec![json!("s1"), json!("s2"), json!("s3"), json!("t1")]);
+}
+
+#[test]
+fn test_binary_against_mock_daemon() {
+    let daemon = MockDaemon::start();
+    daemon.respond("status", json!({"port": daemon.port(), "uptime": "3h", "active_sessions": 2}));
+
+    let home = std::env::temp_dir().join(format!("port42-mock-daemon-test-{}", std::process::id()));
+    let output = Command::cargo_bin("port42").unwrap()
+        .env("HOME", &home)
+        .args(["status", "--json", "--port", &daemon.port().to_string()])
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&home);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let status: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(status["uptime"], "3h");
+    assert_eq!(daemon.requests_of("status").len(), 1);
+}
@@ -0,0 +1,30 @@
+use port42::protocol::ResponseParser;
+use port42::protocol::models::{ModelsReport, ModelsResponse};
+use serde_json::json;
+
+#[test]
+fn test_configured_models_are_validated() {
+    let data = json!({
+        "providers": [
+            {"name": "openai", "models": ["gpt-4o", "gpt-4o-mini"]},
+            {"name": "google", "models": [], "error": "no API key"}
+        ]
+    });
+    let response = ModelsResponse::parse_response(&data).unwrap();
+
+    let report = ModelsReport::new(response.providers, vec![
+        ("@ai-analyst".to_string(), "openai".to_string(), "gpt-4o".to_string()),
+        ("@ai-founder".to_string(), "openai".to_string(), "gpt-40".to_string()),
+        ("@ai-muse".to_string(), "google".to_string(), "gemini-1.5-pro".to_string()),
+    ]);
+
+    assert_eq!(report.configured[0].valid, Some(true));
+
+    // Typos are caught and a near match is suggested
+    assert_eq!(report.configured[1].valid, Some(false));
+    assert_eq!(report.configured[1].suggestion.as_deref(), Some("gpt-4o"));
+
+    // Unreachable providers can't confirm or reject a model
+    assert_eq!(report.configured[2].valid, None);
+    assert!(report.has_invalid());
+}
@@ -0,0 +1,19 @@
+use port42::protocol::{AddNoteRequest, NoteList, RequestBuilder, ResponseParser};
+use serde_json::json;
+
+#[test]
+fn test_add_note_request() {
+    let request = AddNoteRequest { path: "/commands/git-haiku".to_string(), text: "staged only".to_string() }
+        .build_request("id".to_string())
+        .unwrap();
+    assert_eq!(request.request_type, "add_note");
+    assert_eq!(request.payload["text"], "staged only");
+}
+
+#[test]
+fn test_note_list_tolerates_missing_fields() {
+    let list = NoteList::parse_response(&json!({"notes": [{"text": "a"}, {"text": "b", "created": "2025-01-01T00:00:00Z"}]})).unwrap();
+    assert_eq!(list.notes.len(), 2);
+    assert!(list.notes[0].created.is_none());
+    assert!(NoteList::parse_response(&json!({"path": "/x"})).unwrap().notes.is_empty());
+}
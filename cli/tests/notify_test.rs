@@ -0,0 +1,109 @@
+use port42::common::notify::{self, Notifier, NotifyEvent};
+use port42::config::{Config, NotifyHook};
+use port42::context::ContextData;
+use port42::protocol::events::{DaemonEvent, EventKind};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn test_notify_templates_and_payloads() {
+    let config: Config = toml::from_str(r#"
+        [notify]
+        long_session_minutes = 45
+
+        [notify.templates]
+        tool_crystallized = "New tool {{name}}"
+        session_completed = "Session with {{nobody}}"
+
+        [[notify.sinks]]
+        name = "team"
+        kind = "slack"
+        url = "https://hooks.slack.com/services/T/B/X"
+        events = ["tool_crystallized"]
+
+        [[notify.sinks]]
+        name = "ops"
+        kind = "webhook"
+        url = "https://example.com/hook"
+    "#).unwrap();
+    let notifier = Notifier::from_config(&config);
+    let vars = HashMap::from([
+        ("name".to_string(), "git-haiku".to_string()),
+        ("description".to_string(), "poems".to_string()),
+        ("agent".to_string(), "@ai-muse".to_string()),
+        ("session".to_string(), "cli-1".to_string()),
+        ("minutes".to_string(), "50".to_string()),
+        ("tools".to_string(), "2".to_string()),
+    ]);
+
+    assert_eq!(notifier.render(NotifyEvent::ToolCrystallized, &vars), "New tool git-haiku");
+    // A template naming an unknown variable falls back to the built-in one
+    assert_eq!(
+        notifier.render(NotifyEvent::SessionCompleted, &vars),
+        "🐬 @ai-muse session cli-1 ended after 50 minutes (2 tools created)"
+    );
+
+    let sinks = notifier.sinks();
+    assert_eq!(notify::payload(&sinks[0], NotifyEvent::Test, "hi", &vars)["text"], "hi");
+    let generic = notify::payload(&sinks[1], NotifyEvent::ToolCrystallized, "hi", &vars);
+    assert_eq!(generic["event"], "tool_crystallized");
+    assert_eq!(generic["data"]["name"], "git-haiku");
+
+    assert!(sinks.iter().all(|s| notify::validate_sink(s).is_ok()));
+    let mut bad = sinks[0].clone();
+    bad.events = vec!["tool_crystalised".to_string()];
+    assert!(notify::validate_sink(&bad).is_err());
+}
+
+#[test]
+fn test_hooks_run_on_snapshot_events() {
+    let config: Config = toml::from_str(r#"
+        [[notify.hooks]]
+        on = "tool_created"
+        exec = "cat > \"$OUT\"; echo \" $PORT42_EVENT $PORT42_SUBJECT\" >> \"$OUT\""
+    "#).unwrap();
+    let hooks = config.notify.unwrap().hooks;
+
+    let before: ContextData = serde_json::from_value(json!({
+        "active_session": {
+            "id": "cli-1", "agent": "@ai-engineer", "message_count": 2, "state": "active",
+            "start_time": "2024-06-01T08:00:00Z", "last_activity": "2024-06-01T08:05:00Z",
+        },
+        "recent_commands": [],
+        "created_tools": [],
+        "suggestions": [],
+    })).unwrap();
+    let mut after = before.clone();
+    after.active_session.as_mut().unwrap().message_count = 4;
+    after.created_tools = serde_json::from_value(json!([
+        { "name": "git-haiku", "type": "tool", "created_at": "2024-06-01T08:06:00Z" },
+    ])).unwrap();
+
+    let events = after.events_since(&before);
+    let kinds: Vec<EventKind> = events.iter().map(|e| e.kind).collect();
+    assert_eq!(kinds, vec![EventKind::Message, EventKind::ToolCreated]);
+    assert!(before.events_since(&before).is_empty());
+
+    // A different session means one ended and another started
+    let mut next = after.clone();
+    let session = next.active_session.as_mut().unwrap();
+    session.id = "cli-2".to_string();
+    session.start_time += chrono::Duration::minutes(30);
+    let kinds: Vec<EventKind> = next.events_since(&after).iter().map(|e| e.kind).collect();
+    assert_eq!(kinds, vec![EventKind::SessionEnded, EventKind::SessionStarted]);
+
+    let out = std::env::temp_dir().join(format!("port42-hook-{}", std::process::id()));
+    let mut hook = hooks[0].clone();
+    hook.exec = hook.exec.replace("$OUT", &out.display().to_string());
+    assert_eq!(hook.on, events[1].kind.label());
+    notify::run_hook(&hook, &events[1]).unwrap();
+    let written = std::fs::read_to_string(&out).unwrap();
+    std::fs::remove_file(&out).unwrap();
+    let (json, env) = written.split_once(' ').unwrap();
+    let event: DaemonEvent = serde_json::from_str(json).unwrap();
+    assert_eq!(event, events[1]);
+    assert_eq!(env.trim(), "tool_created git-haiku");
+
+    let failing = NotifyHook { on: "tool_created".to_string(), exec: "exit 3".to_string() };
+    assert!(notify::run_hook(&failing, &events[1]).unwrap_err().to_string().contains("code 3"));
+}
@@ -0,0 +1,269 @@
+mod common;
+
+use common::{port42_at, temp_home};
+use port42::common::outbox::Outbox;
+use port42::protocol::DaemonRequest;
+use port42::testing::{MockDaemon, Reply};
+use serde_json::{json, Value};
+
+fn request(id: &str) -> DaemonRequest {
+    DaemonRequest {
+        request_type: "swim".to_string(),
+        id: id.to_string(),
+        payload: json!({"agent": "@ai-engineer", "message": id}),
+        references: None,
+        session_context: None,
+        user_prompt: None,
+        provider: None,
+    }
+}
+
+/// A port nothing is listening on
+fn closed_port() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().port().to_string()
+}
+
+#[test]
+fn test_outbox_order_and_failures() {
+    let home = temp_home("outbox", "store");
+    let outbox = Outbox::at(home.join("outbox"));
+    assert!(outbox.pending().unwrap().is_empty());
+
+    outbox.push("possess @ai-engineer: first", request("first")).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    outbox.push("possess @ai-engineer: second", request("second")).unwrap();
+    let pending = outbox.pending().unwrap();
+    let ids: Vec<&str> = pending.iter().map(|item| item.request.id.as_str()).collect();
+    assert_eq!(ids, ["first", "second"]);
+
+    // Turned down requests are set aside with the daemon's reason
+    let first = pending.into_iter().next().unwrap();
+    let name = first.name();
+    outbox.fail(first, "unknown agent").unwrap();
+    assert_eq!(outbox.pending().unwrap().len(), 1);
+    let failed = outbox.failed().unwrap();
+    assert_eq!(failed[0].error.as_deref(), Some("unknown agent"));
+
+    outbox.retry(&name).unwrap();
+    assert!(outbox.failed().unwrap().is_empty());
+    let pending = outbox.pending().unwrap();
+    assert_eq!(pending[0].request.id, "first");
+    assert_eq!(pending[0].error, None);
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_each_request_is_claimed_once() {
+    let home = temp_home("outbox", "claim");
+    let outbox = Outbox::at(home.join("outbox"));
+    outbox.push("possess @ai-engineer: once", request("once")).unwrap();
+
+    // Two port42s read the same pending list; only the first claim wins
+    let seen_by_first = outbox.pending().unwrap();
+    let seen_by_second = outbox.pending().unwrap();
+    let claimed = outbox.claim(seen_by_first.into_iter().next().unwrap()).unwrap().unwrap();
+    assert!(outbox.claim(seen_by_second.into_iter().next().unwrap()).unwrap().is_none());
+    assert!(outbox.pending().unwrap().is_empty());
+    assert_eq!(outbox.sending().unwrap().len(), 1);
+
+    // Undelivered, it goes back in line under the same name
+    let name = claimed.name();
+    outbox.release(&claimed).unwrap();
+    assert_eq!(outbox.pending().unwrap()[0].name(), name);
+
+    // Turned down after a claim, it's set aside as any other
+    let claimed = outbox.claim(outbox.pending().unwrap().remove(0)).unwrap().unwrap();
+    outbox.fail(claimed, "unknown agent").unwrap();
+    assert!(outbox.sending().unwrap().is_empty());
+    assert_eq!(outbox.failed().unwrap()[0].name(), name);
+
+    // A claim left by a port42 that died can be put back by hand
+    outbox.retry(&name).unwrap();
+    let stuck = outbox.claim(outbox.pending().unwrap().remove(0)).unwrap().unwrap();
+    drop(stuck);
+    outbox.retry(&name).unwrap();
+    assert_eq!(outbox.pending().unwrap().len(), 1);
+    assert!(outbox.sending().unwrap().is_empty());
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_queue_then_flush() {
+    let home = temp_home("outbox", "flush");
+    let output = port42_at(&home, &["--port", &closed_port(), "possess", "@ai-engineer", "--queue", "write", "a", "haiku"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let outbox = Outbox::at(home.join(".port42/outbox"));
+    assert_eq!(outbox.pending().unwrap()[0].summary, "possess @ai-engineer: write a haiku");
+
+    let daemon = MockDaemon::start();
+    daemon.respond("swim", json!({"session_id": "cli-42", "message": "Dolphins dream"}));
+    let output = port42_at(&home, &["--port", &daemon.port().to_string(), "outbox", "flush"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("possess @ai-engineer: write a haiku"), "{}", stdout);
+    assert!(stdout.contains("cli-42"), "{}", stdout);
+
+    let sent = daemon.requests_of("swim");
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0]["payload"]["message"], "write a haiku");
+    assert_eq!(sent[0]["payload"]["detach"], Value::Null);
+    assert!(outbox.pending().unwrap().is_empty());
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_flushes_side_by_side_send_each_once() {
+    let home = temp_home("outbox", "race");
+    let outbox = Outbox::at(home.join(".port42/outbox"));
+    for n in 0..6 {
+        outbox.push(&format!("possess @ai-engineer: {}", n), request(&format!("race-{}", n))).unwrap();
+    }
+    let daemon = MockDaemon::start();
+    daemon.respond("swim", json!({"session_id": "cli-42", "message": "Dolphins dream"}));
+
+    let port = daemon.port().to_string();
+    let flushes: Vec<_> = (0..2)
+        .map(|_| {
+            std::process::Command::new(assert_cmd::cargo::cargo_bin("port42"))
+                .env("HOME", &home)
+                .env_remove("PORT42_PORT")
+                .args(["--port", &port, "outbox", "flush"])
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    for mut flush in flushes {
+        assert!(flush.wait().unwrap().success());
+    }
+
+    let mut ids: Vec<String> = daemon.requests_of("swim").iter().map(|r| r["id"].as_str().unwrap().to_string()).collect();
+    ids.sort();
+    assert_eq!(ids, (0..6).map(|n| format!("race-{}", n)).collect::<Vec<_>>());
+    assert!(outbox.pending().unwrap().is_empty());
+    assert!(outbox.sending().unwrap().is_empty());
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_delivered_as_jobs_on_next_connection() {
+    let home = temp_home("outbox", "auto");
+    let outbox = Outbox::at(home.join(".port42/outbox"));
+    outbox.push("possess @ai-engineer: accepted", request("accepted")).unwrap();
+
+    let daemon = MockDaemon::start();
+    daemon.respond("swim", json!({"job_id": "job-7", "status": "queued"}))
+        .respond("status", json!({"port": daemon.port(), "uptime": "1m", "active_sessions": 0}));
+    let output = port42_at(&home, &["--port", &daemon.port().to_string(), "status", "--json"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // The command's own output is left for machines to read
+    let status: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(status["uptime"], "1m");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("job-7"));
+
+    let sent = daemon.requests_of("swim");
+    assert_eq!(sent[0]["payload"]["detach"], true);
+    assert!(outbox.pending().unwrap().is_empty());
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_turned_down_requests_are_set_aside() {
+    let home = temp_home("outbox", "refused");
+    let outbox = Outbox::at(home.join(".port42/outbox"));
+    outbox.push("possess @ai-nobody: hello", request("refused")).unwrap();
+
+    let daemon = MockDaemon::start();
+    let output = port42_at(&home, &["--port", &daemon.port().to_string(), "outbox", "flush"]);
+    assert!(!output.status.success());
+    assert!(outbox.pending().unwrap().is_empty());
+    assert_eq!(outbox.failed().unwrap()[0].error.as_deref(), Some("Unknown request type: swim"));
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_only_delivered_in_background_by_daemons_that_detach() {
+    let home = temp_home("outbox", "no-detach");
+    let outbox = Outbox::at(home.join(".port42/outbox"));
+    outbox.push("possess @ai-engineer: later", request("later")).unwrap();
+
+    let daemon = MockDaemon::start();
+    daemon.on("hello", Reply::error("Unknown request type: hello"))
+        .respond("status", json!({"port": daemon.port(), "uptime": "1m", "active_sessions": 0}));
+    let output = port42_at(&home, &["--port", &daemon.port().to_string(), "status", "--json"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("port42 outbox flush"));
+
+    // Nothing was sent; it waits for an explicit flush
+    assert!(daemon.requests_of("swim").is_empty());
+    assert_eq!(outbox.pending().unwrap().len(), 1);
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_requests_that_cannot_detach_wait_for_a_flush() {
+    let home = temp_home("outbox", "held");
+    let outbox = Outbox::at(home.join(".port42/outbox"));
+    // Pushed in the same millisecond, they fall back on the ID to keep their order
+    outbox.push("possess @ai-engineer: first", request("1-first")).unwrap();
+    let mut store = request("2-store");
+    store.request_type = "store_path".to_string();
+    outbox.push("store notes", store).unwrap();
+    outbox.push("possess @ai-engineer: last", request("3-last")).unwrap();
+
+    let daemon = MockDaemon::start();
+    daemon.respond("swim", json!({"job_id": "job-1", "status": "queued"}))
+        .respond("status", json!({"port": daemon.port(), "uptime": "1m", "active_sessions": 0}));
+    let output = port42_at(&home, &["--port", &daemon.port().to_string(), "status", "--json"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("2 queued requests waiting"));
+
+    // Only the job ahead of it went; the rest keep their place in line
+    let sent = daemon.requests_of("swim");
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0]["payload"]["message"], "1-first");
+    assert!(daemon.requests_of("store_path").is_empty());
+    assert_eq!(outbox.pending().unwrap().len(), 2);
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_unanswered_requests_are_not_sent_twice() {
+    let home = temp_home("outbox", "unknown");
+    let outbox = Outbox::at(home.join(".port42/outbox"));
+    outbox.push("possess @ai-engineer: maybe", request("maybe")).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    outbox.push("possess @ai-engineer: next", request("next")).unwrap();
+
+    // The daemon takes the request and hangs up without answering
+    let daemon = MockDaemon::start();
+    daemon.on("swim", Reply::lines(vec![]).then_hang_up());
+    let output = port42_at(&home, &["--port", &daemon.port().to_string(), "--retries", "0", "outbox", "flush"]);
+    assert!(!output.status.success());
+    assert_eq!(daemon.requests_of("swim").len(), 1);
+
+    // Set aside rather than queued again, and the rest wait their turn
+    let unknown = outbox.unknown().unwrap();
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].request.id, "maybe");
+    assert_eq!(outbox.pending().unwrap()[0].request.id, "next");
+    let list = port42_at(&home, &["outbox", "list"]);
+    assert!(String::from_utf8_lossy(&list.stdout).contains("no answer came back"));
+
+    outbox.retry(&unknown[0].name()).unwrap();
+    assert!(outbox.unknown().unwrap().is_empty());
+    assert_eq!(outbox.pending().unwrap().len(), 2);
+
+    std::fs::remove_dir_all(&home).ok();
+}
@@ -0,0 +1,12 @@
+use port42::display::pager::screen_rows;
+
+#[test]
+fn test_screen_rows() {
+    assert_eq!(screen_rows("one\ntwo\n", 80), 2);
+    // Blank lines still take a row
+    assert_eq!(screen_rows("one\n\nthree", 80), 3);
+    // Long lines wrap
+    assert_eq!(screen_rows(&"x".repeat(170), 80), 3);
+    // Colour codes take no room
+    assert_eq!(screen_rows(&format!("\x1b[1;94m{}\x1b[0m", "x".repeat(80)), 80), 1);
+}
@@ -0,0 +1,18 @@
+use port42::common::platform;
+
+#[test]
+fn test_process_checks() {
+    assert!(platform::is_alive(std::process::id()));
+
+    let mut child = platform::shell("exit 3").spawn().unwrap();
+    let pid = child.id();
+    assert_eq!(child.wait().unwrap().code(), Some(3));
+    assert!(!platform::is_alive(pid));
+}
+
+#[test]
+fn test_paths() {
+    assert!(platform::pid_file().starts_with(platform::runtime_dir()));
+    assert!(platform::daemon_log_path().ends_with("daemon.log"));
+    assert!(platform::hostname().is_some_and(|name| !name.is_empty()));
+}
@@ -0,0 +1,261 @@
+mod common;
+
+use common::{port42_at, temp_home_with_config};
+use openssl::asn1::Asn1Time;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{PKey, Private};
+use openssl::rsa::Rsa;
+use openssl::ssl::{SslAcceptor, SslMethod};
+use openssl::x509::extension::SubjectAlternativeName;
+use openssl::x509::{X509Builder, X509NameBuilder, X509};
+use port42::common::endpoint::{normalize_fingerprint, Endpoint, SystemRoots};
+use port42::config::TlsConfig;
+use port42::common::errors::exit_code;
+use port42::config::{self, Config};
+use port42::testing::MockDaemon;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+const PIN: &str = "AB:CD:EF:01:23:45:67:89:AB:CD:EF:01:23:45:67:89:AB:CD:EF:01:23:45:67:89:AB:CD:EF:01:23:45:67:89";
+
+fn status_daemon() -> MockDaemon {
+    let daemon = MockDaemon::start();
+    daemon.respond("status", json!({"port": daemon.port(), "uptime": "1m", "active_sessions": 0}));
+    daemon
+}
+
+/// A self-signed certificate for `localhost`
+fn certificate() -> (X509, PKey<Private>) {
+    let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_text("CN", "localhost").unwrap();
+    let name = name.build();
+    let mut builder = X509Builder::new().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_subject_name(&name).unwrap();
+    builder.set_issuer_name(&name).unwrap();
+    builder.set_pubkey(&key).unwrap();
+    builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+    builder.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+    let san = SubjectAlternativeName::new().dns("localhost").build(&builder.x509v3_context(None, None)).unwrap();
+    builder.append_extension(san).unwrap();
+    builder.sign(&key, MessageDigest::sha256()).unwrap();
+    (builder.build(), key)
+}
+
+/// TLS in front of `daemon`, the way a remote daemon would be exposed.
+/// Returns the port it listens on.
+fn tls_proxy(daemon: &MockDaemon, cert: &X509, key: &PKey<Private>) -> u16 {
+    let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+    acceptor.set_certificate(cert).unwrap();
+    acceptor.set_private_key(key).unwrap();
+    let acceptor = acceptor.build();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let upstream = daemon.port();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let acceptor = acceptor.clone();
+            std::thread::spawn(move || {
+                let Ok(tls) = acceptor.accept(stream) else { return };
+                let mut tls = BufReader::new(tls);
+                let plain = TcpStream::connect(("127.0.0.1", upstream)).unwrap();
+                let mut replies = BufReader::new(plain.try_clone().unwrap());
+                let mut plain = plain;
+                let mut line = String::new();
+                while tls.read_line(&mut line).unwrap_or(0) > 0 {
+                    plain.write_all(line.as_bytes()).unwrap();
+                    line.clear();
+                    if replies.read_line(&mut line).unwrap_or(0) == 0 {
+                        return;
+                    }
+                    tls.get_mut().write_all(line.as_bytes()).unwrap();
+                    line.clear();
+                }
+            });
+        }
+    });
+    port
+}
+
+fn fingerprint(cert: &X509) -> String {
+    cert.digest(MessageDigest::sha256()).unwrap().iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":")
+}
+
+#[test]
+fn test_profiles_from_config() {
+    let config: Config = toml::from_str(&format!(r#"
+        profile = "work"
+
+        [profiles.work]
+        host = "port42.example.com"
+        port = 4242
+
+        [profiles.work.tls]
+        pin_sha256 = "{}"
+
+        [profiles.laptop]
+        port = 4343
+    "#, PIN)).unwrap();
+
+    let work = Endpoint::from_profile("work", config.profile("work").unwrap());
+    assert!(!work.is_local());
+    assert_eq!(work.describe(4242), "port42.example.com:4242 (TLS)");
+    assert_eq!(work.tls.unwrap().pin_sha256.as_deref(), Some(PIN));
+
+    // Without a host the daemon is still this machine's
+    let laptop = Endpoint::from_profile("laptop", config.profile("laptop").unwrap());
+    assert!(laptop.is_local());
+    assert_eq!(laptop.describe(4343), "127.0.0.1:4343");
+    assert_eq!(Endpoint::default().describe(42), "127.0.0.1:42");
+    assert_eq!(Endpoint { host: "::1".to_string(), ..Endpoint::default() }.describe(42), "[::1]:42");
+
+    let missing = config.profile("home").unwrap_err().to_string();
+    assert!(missing.contains("laptop, work"), "{}", missing);
+}
+
+#[test]
+fn test_fingerprints() {
+    let expected = "abcdef0123456789".repeat(4);
+    assert_eq!(normalize_fingerprint(PIN).as_deref(), Some(expected.as_str()));
+    assert_eq!(normalize_fingerprint(&expected).as_deref(), Some(expected.as_str()));
+    assert_eq!(normalize_fingerprint("AB:CD"), None);
+    assert_eq!(normalize_fingerprint(&"zz".repeat(32)), None);
+
+    assert!(config::validate("[profiles.work.tls]\npin_sha256 = \"AB:CD\"\n").is_err());
+    assert!(config::validate("profile = \"home\"\n[profiles.work]\nport = 1\n").is_err());
+    assert!(config::validate(&format!("profile = \"work\"\n[profiles.work.tls]\npin_sha256 = \"{}\"\n", PIN)).is_ok());
+}
+
+#[test]
+fn test_profile_picks_port() {
+    let daemon = status_daemon();
+    let home = temp_home_with_config("profile", "port", &format!("[profiles.local]\nhost = \"localhost\"\nport = {}\n", daemon.port()));
+
+    let output = port42_at(&home, &["--profile", "local", "status", "--json"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let status: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(status["uptime"], "1m");
+
+    let output = port42_at(&home, &["--profile", "nowhere", "status"]);
+    assert_eq!(output.status.code(), Some(exit_code::USAGE));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("local"));
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_remote_daemon_is_not_managed() {
+    let home = temp_home_with_config("profile", "remote", "[profiles.far]\nhost = \"192.0.2.1\"\nport = 4242\n");
+    let output = port42_at(&home, &["--profile", "far", "daemon", "stop"]);
+    assert_eq!(output.status.code(), Some(exit_code::USAGE));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("192.0.2.1:4242"));
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_tls_with_pinned_certificate() {
+    let daemon = status_daemon();
+    let (cert, key) = certificate();
+    let port = tls_proxy(&daemon, &cert, &key);
+
+    let home = temp_home_with_config("profile", "pinned", &format!(
+        "[profiles.tls]\nhost = \"127.0.0.1\"\nport = {}\n[profiles.tls.tls]\npin_sha256 = \"{}\"\n",
+        port, fingerprint(&cert)
+    ));
+    let output = port42_at(&home, &["--profile", "tls", "status", "--json"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(daemon.requests_of("status").len(), 1);
+
+    // Someone else's certificate is refused, with what was seen instead
+    std::fs::write(home.join(".port42/config.toml"), format!(
+        "[profiles.tls]\nport = {}\n[profiles.tls.tls]\npin_sha256 = \"{}\"\n", port, PIN
+    )).unwrap();
+    let output = port42_at(&home, &["--profile", "tls", "status", "--json"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&fingerprint(&cert).replace(':', "").to_lowercase()), "{}", stderr);
+    assert_eq!(daemon.requests_of("status").len(), 1);
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_tls_with_ca_file() {
+    let daemon = status_daemon();
+    let (cert, key) = certificate();
+    let port = tls_proxy(&daemon, &cert, &key);
+
+    let home = temp_home_with_config("profile", "ca", "");
+    let ca_file = home.join("daemon.pem");
+    std::fs::write(&ca_file, cert.to_pem().unwrap()).unwrap();
+    std::fs::write(home.join(".port42/config.toml"), format!(
+        "[profiles.tls]\nport = {}\n[profiles.tls.tls]\nca_file = {:?}\nserver_name = \"localhost\"\n",
+        port, ca_file
+    )).unwrap();
+    let output = port42_at(&home, &["--profile", "tls", "status", "--json"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // Without the CA the certificate is just self-signed
+    std::fs::write(home.join(".port42/config.toml"), format!(
+        "[profiles.tls]\nport = {}\n[profiles.tls.tls]\nserver_name = \"localhost\"\n", port
+    )).unwrap();
+    let output = port42_at(&home, &["--profile", "tls", "status", "--json"]);
+    assert!(!output.status.success());
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+/// Send a status request over `endpoint`, trusting only `roots`
+fn status_over(endpoint: &Endpoint, port: u16, roots: &SystemRoots) -> std::io::Result<Value> {
+    let mut transport = endpoint.connect_trusting(port, Duration::from_secs(5), roots)?;
+    transport.write_all(b"{\"type\":\"status\",\"id\":\"roots\"}\n")?;
+    let mut line = String::new();
+    BufReader::new(transport).read_line(&mut line)?;
+    Ok(serde_json::from_str(&line).unwrap())
+}
+
+#[test]
+fn test_tls_with_system_roots() {
+    let daemon = status_daemon();
+    let (cert, key) = certificate();
+    let port = tls_proxy(&daemon, &cert, &key);
+    let endpoint = Endpoint {
+        tls: Some(TlsConfig { server_name: Some("localhost".to_string()), ..Default::default() }),
+        ..Default::default()
+    };
+
+    let home = temp_home_with_config("profile", "roots", "");
+    let bundle = home.join("bundle.pem");
+    std::fs::write(&bundle, cert.to_pem().unwrap()).unwrap();
+    let roots = SystemRoots { file: Some(bundle), dirs: Vec::new() };
+    let reply = status_over(&endpoint, port, &roots).unwrap();
+    assert_eq!(reply["success"], json!(true), "{}", reply);
+
+    // A hashed directory, as most distributions keep them
+    let dir = home.join("certs");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(format!("{:08x}.0", cert.subject_name_hash())), cert.to_pem().unwrap()).unwrap();
+    let roots = SystemRoots { file: None, dirs: vec![dir] };
+    let reply = status_over(&endpoint, port, &roots).unwrap();
+    assert_eq!(reply["success"], json!(true), "{}", reply);
+
+    // Nothing is trusted that wasn't found
+    assert!(status_over(&endpoint, port, &SystemRoots::default()).is_err());
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_system_roots_are_found() {
+    // Only where the machine has any to find
+    if !std::path::Path::new("/etc/ssl/certs").is_dir() && !std::path::Path::new("/etc/pki/tls").is_dir() {
+        return;
+    }
+    let roots = SystemRoots::probe();
+    assert!(roots.file.is_some() || !roots.dirs.is_empty(), "{:?}", roots);
+    assert!(roots.file.iter().chain(&roots.dirs).all(|path| path.exists()), "{:?}", roots);
+}
@@ -0,0 +1,44 @@
+use port42::config::Config;
+use port42::project::Project;
+
+#[test]
+fn test_project_discovery_and_merge() {
+    let root = std::env::temp_dir().join(format!("port42-project-test-{}", std::process::id()));
+    let nested = root.join("src").join("deep");
+    std::fs::create_dir_all(&nested).unwrap();
+
+    let project = Project::init(&root, Some("demo".to_string()), false).unwrap();
+    assert_eq!(project.config.name, "demo");
+    assert!(Project::init(&root, None, false).is_err(), "init must not clobber an existing project");
+
+    std::fs::write(root.join(".port42/project.toml"), r#"
+        name = "demo"
+        refs = ["file:./README.md", "p42:/commands/lint"]
+        model = "gpt-4o"
+
+        [agents.ai-muse]
+        provider = "openai"
+    "#).unwrap();
+    std::fs::write(root.join(".port42/context.md"), "Uses PostgreSQL 16.").unwrap();
+
+    // Found from any subdirectory, with file refs anchored at the project root
+    let project = Project::discover_from(&nested).unwrap().unwrap();
+    assert_eq!(project.references()[0], format!("file:{}", root.join("README.md").display()));
+    assert_eq!(project.references()[1], "p42:/commands/lint");
+    assert!(project.guidance().unwrap().contains("PostgreSQL 16"));
+
+    // Project settings win over the user's, matching agents with or without '@'
+    let mut config: Config = toml::from_str(r#"
+        model = "claude-3-opus"
+        [agents."@ai-muse"]
+        model = "claude-3-7-sonnet"
+    "#).unwrap();
+    config.apply_project(&project.config);
+    assert_eq!(config.model.as_deref(), Some("gpt-4o"));
+    let muse = config.agent_defaults("@ai-muse").unwrap();
+    assert_eq!(muse.provider.as_deref(), Some("openai"));
+    assert_eq!(muse.model.as_deref(), Some("claude-3-7-sonnet"));
+    assert_eq!(config.agents.len(), 1);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -1,23 +1,29 @@
-use port42::protocol::{PossessRequest, PossessResponse, RequestBuilder, ResponseParser};
+use port42::protocol::{DeclareRelationRequest, DeclareRelationResponse, GenerationStatus, Relation, RequestBuilder, ResponseParser, SwimRequest, SwimResponse};
 use serde_json::json;
 
 #[test]
-fn test_possess_request_builder() {
-    let request = PossessRequest {
+fn test_swim_request_builder() {
+    let request = SwimRequest {
         agent: "@ai-engineer".to_string(),
         message: "test message".to_string(),
+        memory_context: None,
+        references: None,
+        approval_response: None,
+        provider: None,
+        guidance: None,
+        workspace: None,
     };
     
     let daemon_request = request.build_request("test-123".to_string()).unwrap();
     
-    assert_eq!(daemon_request.request_type, "possess");
+    assert_eq!(daemon_request.request_type, "swim");
     assert_eq!(daemon_request.id, "test-123");
     assert_eq!(daemon_request.payload["agent"], "@ai-engineer");
     assert_eq!(daemon_request.payload["message"], "test message");
 }
 
 #[test]
-fn test_possess_response_parser() {
+fn test_swim_response_parser() {
     // Test basic response
     let data = json!({
         "message": "Hello from AI",
@@ -26,7 +32,7 @@ fn test_possess_response_parser() {
         "command_generated": false
     });
     
-    let response = PossessResponse::parse_response(&data).unwrap();
+    let response = SwimResponse::parse_response(&data).unwrap();
     
     assert_eq!(response.message, "Hello from AI");
     assert_eq!(response.session_id, "session-123");
@@ -36,7 +42,7 @@ fn test_possess_response_parser() {
 }
 
 #[test]
-fn test_possess_response_with_command() {
+fn test_swim_response_with_command() {
     let data = json!({
         "message": "I created a command for you",
         "session_id": "session-456",
@@ -49,7 +55,7 @@ fn test_possess_response_with_command() {
         }
     });
     
-    let response = PossessResponse::parse_response(&data).unwrap();
+    let response = SwimResponse::parse_response(&data).unwrap();
     
     assert!(response.command_generated);
     assert!(response.command_spec.is_some());
@@ -61,7 +67,7 @@ fn test_possess_response_with_command() {
 }
 
 #[test]
-fn test_possess_response_with_artifact() {
+fn test_swim_response_with_artifact() {
     let data = json!({
         "message": "I created an artifact",
         "session_id": "session-789",
@@ -76,7 +82,7 @@ fn test_possess_response_with_artifact() {
         }
     });
     
-    let response = PossessResponse::parse_response(&data).unwrap();
+    let response = SwimResponse::parse_response(&data).unwrap();
     
     assert!(response.artifact_generated);
     assert!(response.artifact_spec.is_some());
@@ -85,4 +91,73 @@ fn test_possess_response_with_artifact() {
     assert_eq!(spec.name, "readme");
     assert_eq!(spec.artifact_type, "document");
     assert_eq!(spec.path, "/artifacts/document/readme.md");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_declare_progress_only_sent_when_asked_for() {
+    let mut request = DeclareRelationRequest {
+        relation: Relation::new_tool("git-haiku", vec!["git".to_string()]),
+        references: None,
+        user_prompt: None,
+        provider: None,
+        progress: false,
+        preview: false,
+    };
+    let quiet = request.build_request("id".to_string()).unwrap();
+    assert!(quiet.payload.get("progress").is_none());
+
+    request.progress = true;
+    let watched = request.build_request("id".to_string()).unwrap();
+    assert_eq!(watched.payload["progress"], true);
+}
+
+#[test]
+fn test_evolve_preview_round_trip() {
+    let request = DeclareRelationRequest {
+        relation: Relation::new_tool("git-haiku", Vec::new()).evolving("/commands/git-haiku"),
+        references: None,
+        user_prompt: Some("more syllables".to_string()),
+        provider: None,
+        progress: false,
+        preview: true,
+    };
+    let built = request.build_request("id".to_string()).unwrap();
+    assert_eq!(built.payload["preview"], true);
+    assert_eq!(built.payload["relation"]["properties"]["evolves"], "/commands/git-haiku");
+
+    let response = DeclareRelationResponse::parse_response(&json!({
+        "relation_id": "rel-git-haiku", "type": "Tool", "materialized": false,
+        "physical_path": "/commands/git-haiku", "status": "preview",
+        "action": "update", "content": "#!/bin/sh\n",
+    })).unwrap();
+    assert_eq!(response.action.as_deref(), Some("update"));
+    assert_eq!(response.content.as_deref(), Some("#!/bin/sh\n"));
+}
+
+#[test]
+fn test_declare_preview_then_write() {
+    let preview = DeclareRelationResponse::parse_response(&json!({
+        "relation_id": "rel-shiny", "type": "Tool", "materialized": false,
+        "physical_path": "/commands/shiny", "status": "preview", "content": "print('shiny')\n",
+        "metadata": {"type": "tool", "language": "python"}, "paths": ["/commands/shiny", "/tools/shiny"],
+    })).unwrap();
+    assert_eq!(preview.paths.len(), 2);
+    assert_eq!(preview.metadata.unwrap()["language"], "python");
+
+    // Confirming sends back exactly what was shown
+    let relation = Relation::new_tool("shiny", Vec::new()).with_generated(&preview.content.unwrap());
+    assert_eq!(relation.properties["generated"], "print('shiny')\n");
+}
+
+#[test]
+fn test_generation_status_labels() {
+    let status: GenerationStatus = serde_json::from_value(json!({"phase": "prompting", "detail": "claude-sonnet-4"})).unwrap();
+    assert_eq!(status.label(), "Prompting AI");
+    assert_eq!(status.step(), Some(2));
+    assert_eq!(status.detail.as_deref(), Some("claude-sonnet-4"));
+
+    // A phase this CLI doesn't know yet still reads sensibly, just without a step
+    let status: GenerationStatus = serde_json::from_value(json!({"phase": "linting_code"})).unwrap();
+    assert_eq!(status.label(), "linting code");
+    assert_eq!(status.step(), None);
+}
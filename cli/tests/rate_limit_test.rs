@@ -0,0 +1,37 @@
+use port42::common::rate_limit::{backoff_delay, is_rate_limited, retry_after, with_jitter};
+use std::time::Duration;
+
+#[test]
+fn test_rate_limit_detection_and_backoff() {
+    assert!(is_rate_limited("CLAUDE_API_ERROR: 429 Too Many Requests"));
+    assert!(is_rate_limited("OPENAI_API_ERROR: rate_limit_exceeded"));
+    assert!(!is_rate_limited("invalid x-api-key"));
+    // Overloads are outages for the fallback chain, not limits to wait out
+    assert!(!is_rate_limited("Anthropic API returned 529: Overloaded"));
+    // Digits that merely contain 429 aren't a status
+    assert!(!is_rate_limited("session cli-14290 not found"));
+    assert!(!is_rate_limited("prompt is 45291 tokens, over the limit"));
+    assert!(!is_rate_limited("database locked on port 15293"));
+
+    assert_eq!(retry_after("429: please retry after 17 seconds"), Some(Duration::from_secs(17)));
+    assert_eq!(retry_after("Retry-After: 3"), Some(Duration::from_secs(3)));
+    assert_eq!(retry_after("429 Too Many Requests"), None);
+
+    let max = Duration::from_secs(120);
+    // Exponential without a hint, the provider's hint otherwise, both capped
+    assert_eq!(backoff_delay(0, None, max), Duration::from_secs(5));
+    assert_eq!(backoff_delay(2, None, max), Duration::from_secs(20));
+    assert_eq!(backoff_delay(9, None, max), max);
+    assert_eq!(backoff_delay(0, Some(Duration::from_secs(42)), max), Duration::from_secs(42));
+}
+
+#[test]
+fn test_jitter_only_stretches_the_delay() {
+    let delay = Duration::from_secs(10);
+    assert_eq!(with_jitter(delay, 0.0), delay);
+    assert_eq!(with_jitter(delay, 0.5), Duration::from_secs(11));
+    assert_eq!(with_jitter(delay, 1.0), Duration::from_secs(12));
+    // Out-of-range draws are clamped rather than shortening the wait
+    assert_eq!(with_jitter(delay, -3.0), delay);
+    assert_eq!(with_jitter(delay, 7.0), Duration::from_secs(12));
+}
@@ -0,0 +1,35 @@
+use port42::protocol::{RealitySnapshot, SnapshotEntry};
+
+fn entry(sha: &str, agent: Option<&str>) -> SnapshotEntry {
+    SnapshotEntry { sha256: sha.to_string(), language: "bash".to_string(), description: None, agent: agent.map(String::from) }
+}
+
+fn snapshot(tools: &[(&str, SnapshotEntry)]) -> RealitySnapshot {
+    RealitySnapshot {
+        taken: "2025-08-01T10:00:00Z".to_string(),
+        tools: tools.iter().map(|(name, e)| (name.to_string(), e.clone())).collect(),
+    }
+}
+
+#[test]
+fn test_reality_diff() {
+    let before = snapshot(&[("kept", entry("a", None)), ("edited", entry("b", Some("@ai-muse"))), ("gone", entry("c", Some("@ai-muse")))]);
+    let after = snapshot(&[("kept", entry("a", None)), ("edited", entry("b2", Some("@ai-muse"))), ("new", entry("d", None))]);
+
+    let mut diff = after.diff_from(Some(&before));
+    assert_eq!(diff.since.as_deref(), Some("2025-08-01T10:00:00Z"));
+    let names = |changes: &Vec<port42::protocol::ToolChange>| changes.iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+    assert_eq!(names(&diff.added), vec!["new"]);
+    assert_eq!(names(&diff.removed), vec!["gone"]);
+    assert_eq!(names(&diff.modified), vec!["edited"]);
+    assert_eq!(diff.unchanged, 1);
+
+    diff.retain_agent("@ai-muse");
+    assert!(diff.added.is_empty());
+    assert_eq!(names(&diff.removed), vec!["gone"]);
+
+    // Without an earlier snapshot everything is new
+    let first = after.diff_from(None);
+    assert!(first.since.is_none());
+    assert_eq!(first.added.len(), 3);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use common::{port42, temp_home};
+use port42::testing::MockDaemon;
+use port42::common::recording::{Recorder, Recording};
+use port42::protocol::DaemonRequest;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+fn request(kind: &str, id: &str, payload: Value) -> DaemonRequest {
+    DaemonRequest {
+        request_type: kind.to_string(),
+        id: id.to_string(),
+        payload,
+        references: None,
+        session_context: None,
+        user_prompt: None,
+        provider: None,
+    }
+}
+
+#[test]
+fn test_record_and_answer() {
+    let dir = std::env::temp_dir().join(format!("port42-recording-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let recorder = Recorder::create(&dir).unwrap();
+    let first = recorder.record(
+        &request("cat", "a", json!({"path": "/tools/one"})),
+        "{\"id\":\"a\",\"success\":true,\"data\":{\"content\":\"one\"}}\n",
+        Duration::from_millis(12),
+    ).unwrap();
+    recorder.record(
+        &request("cat", "b", json!({"path": "/tools/two"})),
+        "{\"id\":\"b\",\"success\":true,\"data\":{\"content\":\"two\"}}\n",
+        Duration::from_millis(3),
+    ).unwrap();
+    assert!(first.ends_with("0001-cat.json"));
+
+    // A later command carries on the numbering
+    let path = Recorder::create(&dir).unwrap()
+        .record(&request("status", "c", Value::Null), "not json\n", Duration::ZERO)
+        .unwrap();
+    assert!(path.ends_with("0003-status.json"));
+
+    let mut recording = Recording::load(&dir).unwrap();
+    assert_eq!(recording.exchanges.len(), 3);
+    assert_eq!(recording.exchanges[2].1.response, json!("not json"));
+
+    // Matching payload first, whatever the order
+    let (source, response) = recording.answer(&json!({"type": "cat", "id": "x", "payload": {"path": "/tools/two"}})).unwrap();
+    assert_eq!(source, "0002-cat.json");
+    assert_eq!(response, &json!({"id": "b", "success": true, "data": {"content": "two"}}));
+
+    // Then the next unserved one of the type, then the last one again
+    let (source, _) = recording.answer(&json!({"type": "cat", "id": "y", "payload": {"path": "/tools/three"}})).unwrap();
+    assert_eq!(source, "0001-cat.json");
+    let (source, _) = recording.answer(&json!({"type": "cat", "id": "z", "payload": null})).unwrap();
+    assert_eq!(source, "0002-cat.json");
+
+    assert!(recording.answer(&json!({"type": "swim", "id": "w"})).is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_replay_resends_only_reads_unless_asked() {
+    let home = temp_home("recording", "replay");
+    let dir = home.join("rec");
+    let recorder = Recorder::create(&dir).unwrap();
+    recorder.record(&request("status", "a", Value::Null), "{\"id\":\"a\",\"success\":true,\"data\":{\"uptime\":\"1m\"}}\n", Duration::ZERO).unwrap();
+    recorder.record(&request("declare_relation", "b", json!({"relation": {}})), "{\"id\":\"b\",\"success\":true}\n", Duration::ZERO).unwrap();
+    let dir = dir.to_str().unwrap();
+
+    let daemon = MockDaemon::start();
+    daemon.respond("status", json!({"uptime": "1m"}))
+        .respond("declare_relation", Value::Null);
+
+    let output = port42(&home, &daemon, &["replay", dir]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("declare_relation"));
+    assert_eq!(daemon.requests_of("status").len(), 1);
+    assert!(daemon.requests_of("declare_relation").is_empty());
+
+    // Named, or everything with --yes
+    let output = port42(&home, &daemon, &["replay", dir, "--type", "declare_relation"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(daemon.requests_of("declare_relation").len(), 1);
+    assert_eq!(daemon.requests_of("status").len(), 1);
+
+    let output = port42(&home, &daemon, &["replay", dir, "--yes"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(daemon.requests_of("declare_relation").len(), 2);
+    assert_eq!(daemon.requests_of("status").len(), 2);
+
+    let _ = std::fs::remove_dir_all(&home);
+}
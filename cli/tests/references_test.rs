@@ -0,0 +1,101 @@
+use port42::common::references::{parse_references, truncate_context};
+use port42::common::file_ref::{Budget, expand, glob_match};
+use port42::common::url_ref::{UrlSpec, extract_article, parse_size};
+
+#[test]
+fn test_parse_references() {
+    let refs = parse_references(vec!["file:./spec.md".to_string(), "search:\"docker\"".to_string()], false).unwrap();
+    assert_eq!(refs[0].ref_type, "file");
+    assert_eq!(refs[0].target, "./spec.md");
+    assert_eq!(refs[1].ref_type, "search");
+    assert!(parse_references(vec!["no-colon".to_string()], false).is_err());
+}
+
+#[test]
+fn test_truncate_context() {
+    let (content, truncated) = truncate_context("short".to_string(), 10);
+    assert_eq!(content, "short");
+    assert!(!truncated);
+
+    // Never split a multi-byte character
+    let (content, truncated) = truncate_context("ab€cd".to_string(), 3);
+    assert_eq!(content, "ab");
+    assert!(truncated);
+}
+
+#[test]
+fn test_git_spec_parse() {
+    use port42::common::git_ref::GitSpec;
+
+    assert_eq!(GitSpec::parse("diff").unwrap(), GitSpec::Diff);
+    assert_eq!(GitSpec::parse("staged").unwrap(), GitSpec::Staged);
+    assert_eq!(GitSpec::parse("HEAD~3..HEAD").unwrap(), GitSpec::Range("HEAD~3..HEAD".to_string()));
+    assert_eq!(GitSpec::parse("abc123").unwrap(), GitSpec::Commit("abc123".to_string()));
+    assert_eq!(GitSpec::parse("main...feature").unwrap().args()[0], "log");
+
+    // Nothing that git would take as an option
+    assert!(GitSpec::parse("").is_err());
+    assert!(GitSpec::parse("--output=/tmp/x").is_err());
+    assert!(GitSpec::parse("HEAD --all").is_err());
+}
+
+#[test]
+fn test_url_options() {
+    let spec = UrlSpec::parse("https://example.com/docs!raw!max=20k").unwrap();
+    assert_eq!(spec, UrlSpec { url: "https://example.com/docs".to_string(), raw: true, max_bytes: Some(20 * 1024), fresh: false });
+    assert!(UrlSpec::parse("https://example.com!fresh").unwrap().fresh);
+
+    // A `!` that isn't an option belongs to the address
+    assert_eq!(UrlSpec::parse("https://example.com/#!/page").unwrap().url, "https://example.com/#!/page");
+
+    assert!(UrlSpec::parse("example.com").is_err());
+    assert!(UrlSpec::parse("https://example.com!max=lots").is_err());
+    assert_eq!(parse_size("1M").unwrap(), 1024 * 1024);
+    assert_eq!(parse_size("500").unwrap(), 500);
+    assert!(parse_size("0").is_err());
+}
+
+#[test]
+fn test_article_extraction() {
+    let html = r#"<!DOCTYPE html><html><head><title>Guide &amp; Notes</title>
+        <script>var x = "<p>hidden</p>";</script></head>
+        <body><nav><a href="/">Home</a></nav>
+        <article><p>First   line.</p><ul><li>one</li><li>two &#8212; &#x41;</li></ul><!-- gone --></article>
+        <footer>(c) 2026</footer></body></html>"#;
+    assert_eq!(extract_article(html), "# Guide & Notes\n\nFirst line.\n\n- one\n- two — A");
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("./src/**/*.rs", "./src/main.rs"));
+    assert!(glob_match("./src/**/*.rs", "src/ui/wave_spinner.rs"));
+    assert!(glob_match("src/**", "src/a/b/c.txt"));
+    assert!(glob_match("src/?.rs", "src/a.rs"));
+    assert!(!glob_match("src/*.rs", "src/ui/mod.rs"));
+    assert!(!glob_match("src/*.rs", "src/main.rs.bak"));
+}
+
+#[test]
+fn test_directory_expansion_budgets() {
+    let dir = std::env::temp_dir().join(format!("port42-dir-ref-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(dir.join("a.txt"), "alpha").unwrap();
+    std::fs::write(dir.join("nested/b.txt"), "b".repeat(100)).unwrap();
+    std::fs::write(dir.join("nested/c.txt"), "gamma").unwrap();
+    std::fs::write(dir.join("blob.bin"), [0u8, 1, 2]).unwrap();
+
+    let expansion = expand(&dir.to_string_lossy(), Budget { per_file: 40, total: 45 }).unwrap();
+    let included: Vec<(&str, usize, bool)> = expansion.included.iter()
+        .map(|f| (f.path.rsplit('/').next().unwrap(), f.content.len(), f.truncated))
+        .collect();
+    assert_eq!(included, vec![("a.txt", 5, false), ("b.txt", 40, true)]);
+    assert_eq!(expansion.bytes(), 45);
+    let skipped: Vec<&str> = expansion.skipped.iter().map(|f| f.reason.as_str()).collect();
+    assert_eq!(skipped, vec!["binary", "over the total budget"]);
+
+    let globbed = expand(&format!("{}/**/*.txt", dir.display()), Budget { per_file: 1000, total: 1000 }).unwrap();
+    assert_eq!(globbed.included.len(), 3);
+    assert!(expand(&format!("{}/*.md", dir.display()), Budget { per_file: 10, total: 10 }).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
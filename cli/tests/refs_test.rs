@@ -0,0 +1,33 @@
+use port42::protocol::refs::{ResolveReferencesRequest, ResolvedReferences};
+use port42::protocol::{Reference, RequestBuilder, ResponseParser};
+use serde_json::json;
+
+#[test]
+fn test_resolve_request_carries_local_context() {
+    let mut piped = Reference::from_string("stdin:").unwrap();
+    piped.context = Some("hello\n".to_string());
+    let references = vec![Reference::from_string("file:./notes.md").unwrap(), piped];
+
+    let request = ResolveReferencesRequest { references }.build_request("id".to_string()).unwrap();
+    assert_eq!(request.request_type, "resolve_references");
+    assert_eq!(request.payload["references"][0], json!({"type": "file", "target": "./notes.md", "context": null}));
+    assert_eq!(request.payload["references"][1]["context"], "hello\n");
+}
+
+#[test]
+fn test_resolved_references_parse() {
+    let resolved = ResolvedReferences::parse_response(&json!({
+        "references": [
+            {"type": "file", "target": "./big.log", "content": "abc", "size": 90000, "truncated": true},
+            {"type": "url", "target": "https://example.com", "error": "timed out"},
+            {"type": "search", "target": "nothing"}
+        ]
+    })).unwrap();
+    assert_eq!(resolved.references.len(), 3);
+    assert_eq!(resolved.references[0].spec(), "file:./big.log");
+    assert_eq!(resolved.references[0].injected(), 3);
+    assert!(resolved.references[0].truncated);
+    assert_eq!(resolved.references[2].injected(), 0);
+    assert_eq!(resolved.failed(), 1);
+    assert!(!resolved.full);
+}
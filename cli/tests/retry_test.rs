@@ -0,0 +1,96 @@
+use port42::client::{set_retries, DaemonClient, RetryPolicy};
+use port42::protocol::DaemonRequest;
+use port42::testing::{MockDaemon, Reply};
+use serde_json::json;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+fn request() -> DaemonRequest {
+    DaemonRequest {
+        request_type: "status".to_string(),
+        id: "retry-test".to_string(),
+        payload: serde_json::Value::Null,
+        references: None,
+        session_context: None,
+        user_prompt: None,
+        provider: None,
+    }
+}
+
+#[test]
+fn test_backoff_delay() {
+    let policy = RetryPolicy { max_retries: 5, base_delay: Duration::from_millis(100), max_delay: Duration::from_millis(350) };
+    assert_eq!(policy.delay(0), Duration::from_millis(100));
+    assert_eq!(policy.delay(1), Duration::from_millis(200));
+    assert_eq!(policy.delay(2), Duration::from_millis(350));
+    assert_eq!(policy.delay(40), Duration::from_millis(350));
+}
+
+#[test]
+fn test_request_survives_dropped_connection() {
+    set_retries(2);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let daemon = std::thread::spawn(move || {
+        for (n, stream) in listener.incoming().take(2).enumerate() {
+            let mut stream = stream.unwrap();
+            let mut line = String::new();
+            BufReader::new(stream.try_clone().unwrap()).read_line(&mut line).unwrap();
+            // The first connection hangs up without answering
+            if n == 1 {
+                stream.write_all(b"{\"id\":\"retry-test\",\"success\":true,\"data\":{\"ok\":true}}\n").unwrap();
+            }
+        }
+    });
+
+    let mut client = DaemonClient::new(port);
+    let response = client.request(request()).unwrap();
+    assert!(response.success);
+    daemon.join().unwrap();
+}
+
+fn request_of(request_type: &str) -> DaemonRequest {
+    DaemonRequest { request_type: request_type.to_string(), ..request() }
+}
+
+#[test]
+fn test_timed_out_generation_is_not_resent() {
+    set_retries(2);
+    let daemon = MockDaemon::start();
+    // No answer at all, as when the daemon is still generating
+    daemon.on("possess", Reply::lines(vec![]));
+
+    let mut client = DaemonClient::new(daemon.port());
+    assert!(client.request_timeout(request_of("possess"), Duration::from_millis(200)).is_err());
+    assert_eq!(daemon.requests_of("possess").len(), 1);
+}
+
+#[test]
+fn test_timed_out_read_is_resent() {
+    set_retries(2);
+    let daemon = MockDaemon::start();
+    daemon.on("read_path", Reply::lines(vec![]))
+        .respond("read_path", json!({"path": "/commands/a", "content": ""}));
+
+    let mut client = DaemonClient::new(daemon.port());
+    assert!(client.request_timeout(request_of("read_path"), Duration::from_millis(200)).unwrap().success);
+    assert_eq!(daemon.requests_of("read_path").len(), 2);
+}
+
+#[test]
+fn test_refused_connection_is_retried_for_any_request() {
+    set_retries(2);
+    // Nothing listens until shortly after the first attempt
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let starting = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(100));
+        MockDaemon::serve(port, Box::new(|_| Reply::ok(json!({"ok": true})))).unwrap()
+    });
+
+    let mut client = DaemonClient::new(port);
+    let response = client.request(request_of("possess")).unwrap();
+    assert!(response.success);
+    assert_eq!(starting.join().unwrap().requests_of("possess").len(), 1);
+}
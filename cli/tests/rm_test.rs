@@ -0,0 +1,48 @@
+mod common;
+
+use common::{port42, temp_home};
+use port42::common::utils::normalize_vfs_path;
+use port42::testing::{MockDaemon, Reply};
+use serde_json::json;
+
+#[test]
+fn test_vfs_paths_normalize() {
+    assert_eq!(normalize_vfs_path("//commands"), "/commands");
+    assert_eq!(normalize_vfs_path("/commands/"), "/commands");
+    assert_eq!(normalize_vfs_path("commands/./foo"), "/commands/foo");
+    assert_eq!(normalize_vfs_path("/tools/../commands"), "/commands");
+    assert_eq!(normalize_vfs_path("/.."), "/");
+    assert_eq!(normalize_vfs_path(""), "/");
+}
+
+#[test]
+fn test_rm_refuses_protected_paths_however_written() {
+    let home = temp_home("rm", "protected");
+    let daemon = MockDaemon::start();
+    for path in ["//commands", "/commands/", "/memory/./", "/tools/../trash", "//"] {
+        let output = port42(&home, &daemon, &["rm", path, "--force", "--yes"]);
+        assert!(!output.status.success(), "{} was accepted", path);
+    }
+    assert!(daemon.requests().is_empty());
+}
+
+#[test]
+fn test_rm_reports_each_path_when_one_fails() {
+    let home = temp_home("rm", "partial");
+    let daemon = MockDaemon::start();
+    // Answered in order: a goes, missing doesn't, b goes
+    daemon.respond("trash_path", json!({}))
+        .on("trash_path", Reply::error("path not found: /commands/missing"))
+        .respond("trash_path", json!({}));
+    let output = port42(&home, &daemon, &["--json", "rm", "/commands/a", "/commands/missing", "/commands/b"]);
+    assert!(!output.status.success());
+
+    // Every path was attempted, and the ones that went are on stdout
+    assert_eq!(daemon.requests_of("trash_path").len(), 3);
+    let removed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let sources: Vec<&str> = removed.as_array().unwrap().iter().map(|r| r["source"].as_str().unwrap()).collect();
+    assert_eq!(sources, ["/commands/a", "/commands/b"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Removed 2 of 3 paths; failed: /commands/missing"), "{}", stderr);
+}
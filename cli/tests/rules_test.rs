@@ -0,0 +1,27 @@
+use port42::protocol::ResponseParser;
+use port42::protocol::rules::{RuleEvent, RuleList};
+use serde_json::json;
+
+#[test]
+fn test_rules_parse_with_defaults() {
+    let list = RuleList::parse_response(&json!({"rules": [
+        {"id": "r1", "name": "log", "trigger": {"event": "tool_created", "pattern": "git-*"}, "action": {"command": "echo {name}"}},
+        {"id": "r2", "name": "new", "enabled": false, "trigger": {"event": "moon_phase"}, "action": {"command": "true"}, "fire_count": 2}
+    ]})).unwrap();
+    assert!(list.rules[0].enabled);
+    assert_eq!(list.rules[0].when(), "tool_created matching git-*");
+    assert_eq!(list.rules[1].trigger.event, RuleEvent::Unknown);
+    assert_eq!(list.rules[1].fire_count, 2);
+}
+
+#[test]
+fn test_new_rules_serialize_without_id() {
+    let mut list = RuleList::parse_response(&json!({"rules": [
+        {"name": "log", "trigger": {"event": "command_run"}, "action": {"command": "true"}}
+    ]})).unwrap();
+    let rule = list.rules.remove(0);
+    let value = serde_json::to_value(&rule).unwrap();
+    assert!(value.get("id").is_none());
+    assert!(value["trigger"].get("pattern").is_none());
+    assert_eq!(value["trigger"]["event"], "command_run");
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::{port42, temp_home};
+use base64::{engine::general_purpose, Engine as _};
+use port42::testing::{MockDaemon, Reply};
+use serde_json::json;
+
+fn daemon_with_tool(source: &str) -> MockDaemon {
+    let daemon = MockDaemon::start();
+    daemon.respond("read_path", json!({
+        "path": "/commands/greet",
+        "content": general_purpose::STANDARD.encode(source),
+    }))
+    .respond("track_command", json!({}));
+    daemon
+}
+
+#[test]
+#[cfg(unix)]
+fn test_run_executes_private_copy_and_cleans_up() {
+    let home = temp_home("run", "scratch");
+    let daemon = daemon_with_tool("#!/bin/sh\necho \"$0\"\nstat -c %a \"$(dirname \"$0\")\"\nexit 3\n");
+
+    let output = port42(&home, &daemon, &["run", "greet"]);
+
+    assert_eq!(output.status.code(), Some(3), "the tool's exit code passes through");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let scratch = std::path::PathBuf::from(lines.next().unwrap());
+    assert_eq!(lines.next(), Some("700"), "scratch dir is private: {}", stdout);
+    assert_ne!(scratch.parent(), Some(std::env::temp_dir().as_path()), "not directly in the shared temp dir");
+    assert!(!scratch.exists() && !scratch.parent().unwrap().exists(), "scratch copy is removed after the run");
+
+    let tracked = daemon.requests_of("track_command");
+    assert_eq!(tracked.len(), 1);
+    assert_eq!(tracked[0]["payload"]["exit_code"], 3);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_run_uses_matching_local_copy() {
+    let home = temp_home("run", "local");
+    let source = "#!/bin/sh\necho \"$0\"\n";
+    let local = home.join(".port42/commands/greet");
+    std::fs::create_dir_all(local.parent().unwrap()).unwrap();
+    std::fs::write(&local, source).unwrap();
+    port42::common::utils::make_executable(&local).unwrap();
+    let daemon = daemon_with_tool(source);
+
+    let output = port42(&home, &daemon, &["run", "greet"]);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), local.display().to_string());
+}
+
+#[test]
+fn test_run_missing_tool_is_not_found() {
+    let home = temp_home("run", "missing");
+    let daemon = MockDaemon::start();
+    daemon.on("read_path", Reply::error("Path /commands/nope not found"));
+
+    let output = port42(&home, &daemon, &["run", "nope"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No crystallized command at '/commands/nope'"));
+    assert!(daemon.requests_of("track_command").is_empty());
+}
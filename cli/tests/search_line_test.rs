@@ -0,0 +1,24 @@
+use port42::protocol::SearchLine;
+
+#[test]
+fn test_search_line_parse() {
+    let line = SearchLine::parse(r#"docker "compose file" --type tool --tag infra,ops --tag=ci --all -n 5"#).unwrap();
+    assert_eq!(line.query, "docker compose file");
+    assert_eq!(line.mode, "and");
+    assert_eq!(line.filters.type_filter.as_deref(), Some("tool"));
+    assert_eq!(line.filters.tags, Some(vec!["infra".to_string(), "ops".to_string(), "ci".to_string()]));
+    assert_eq!(line.filters.limit, Some(5));
+    assert!(!line.all_agents);
+
+    let line = SearchLine::parse("errors --after 2025-01-01T00:00:00Z --all-agents --exact").unwrap();
+    assert_eq!(line.mode, "phrase");
+    assert!(line.all_agents);
+    assert_eq!(line.filters.after.as_deref(), Some("2025-01-01T00:00:00+00:00"));
+    assert!(line.filters.agent.is_none());
+
+    assert!(SearchLine::parse("--type tool").is_err());
+    assert!(SearchLine::parse("docker --type").is_err());
+    assert!(SearchLine::parse("docker --bogus").is_err());
+    assert!(SearchLine::parse("docker --after yesterday").is_err());
+    assert!(SearchLine::parse(r#""unclosed"#).is_err());
+}
@@ -0,0 +1,31 @@
+use port42::common::semantic::{cosine_similarity, fuse_rankings};
+
+#[test]
+fn test_hybrid_ranking() {
+    assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-9);
+    assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-9);
+    // Mismatched dimensions never match
+    assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+
+    let semantic = vec!["/memory/rate-limits".to_string(), "/commands/backoff".to_string()];
+    let keyword = vec!["/commands/backoff".to_string(), "/commands/retry".to_string()];
+    let fused = fuse_rankings(&[semantic, keyword]);
+
+    // Found by both rankings beats top of only one
+    assert_eq!(fused[0].0, "/commands/backoff");
+    assert_eq!(fused.len(), 3);
+}
+
+#[test]
+fn test_semantic_search_rejects_filters_it_cannot_apply() {
+    // The index has no dates, agents or tags, so these can't quietly drop out
+    for filter in [["--after", "2025-01-01"], ["--agent", "@ai-muse"], ["--tag", "rust"]] {
+        let output = assert_cmd::Command::cargo_bin("port42").unwrap()
+            .args(["search", "haiku", "--semantic"])
+            .args(filter)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"), "{:?}", filter);
+    }
+}
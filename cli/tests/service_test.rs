@@ -0,0 +1,75 @@
+use port42::common::service::{Manager, Scope, Service, ServiceSpec, Step, UNIT_NAME};
+use std::path::PathBuf;
+
+fn spec() -> ServiceSpec {
+    ServiceSpec {
+        binary: PathBuf::from("/usr/local/bin/port42d"),
+        log: PathBuf::from("/home/echo/.port42/daemon.log"),
+        home: PathBuf::from("/home/echo"),
+        user: Some("echo".to_string()),
+        env: vec![
+            ("PATH".to_string(), "/usr/bin:/bin".to_string()),
+            ("ANTHROPIC_API_KEY".to_string(), r#"sk-"a&b"\c"#.to_string()),
+        ],
+    }
+}
+
+#[test]
+fn test_systemd_user_unit() {
+    let service = Service::new(Manager::Systemd, Scope::User);
+    let files = service.render(&spec());
+    assert_eq!(files.len(), 2);
+
+    let unit = &files[0];
+    assert!(unit.path.ends_with(format!("systemd/user/{}", UNIT_NAME)));
+    assert!(!unit.private);
+    assert!(unit.contents.contains("ExecStart=/usr/local/bin/port42d\n"));
+    assert!(unit.contents.contains("StandardOutput=append:/home/echo/.port42/daemon.log\n"));
+    assert!(unit.contents.contains("WantedBy=default.target\n"));
+    assert!(!unit.contents.contains("User="));
+    // Keys stay out of the unit
+    assert!(!unit.contents.contains("sk-"));
+
+    let env = &files[1];
+    assert!(env.private);
+    assert!(unit.contents.contains(&format!("EnvironmentFile=-{}\n", env.path.display())));
+    assert!(env.contents.contains(r#"ANTHROPIC_API_KEY="sk-\"a&b\"\\c""#));
+
+    assert_eq!(service.commands(Step::Load), vec![
+        vec!["systemctl", "--user", "daemon-reload"],
+        vec!["systemctl", "--user", "enable", "--now", UNIT_NAME],
+    ]);
+}
+
+#[test]
+fn test_systemd_system_unit() {
+    let service = Service::new(Manager::Systemd, Scope::System);
+    let unit = &service.render(&spec())[0];
+    assert_eq!(unit.path, PathBuf::from("/etc/systemd/system").join(UNIT_NAME));
+    assert!(unit.contents.contains("User=echo\n"));
+    assert!(unit.contents.contains("Environment=HOME=/home/echo\n"));
+    assert!(unit.contents.contains("WantedBy=multi-user.target\n"));
+    assert_eq!(service.commands(Step::Stop), vec![vec!["systemctl", "stop", UNIT_NAME]]);
+}
+
+#[test]
+fn test_launchd_plist() {
+    let service = Service::new(Manager::Launchd, Scope::User);
+    let files = service.render(&spec());
+    assert_eq!(files.len(), 1);
+
+    let plist = &files[0];
+    assert!(plist.path.ends_with("Library/LaunchAgents/com.port42.daemon.plist"));
+    assert!(plist.private);
+    assert!(plist.contents.contains("<string>/usr/local/bin/port42d</string>"));
+    assert!(plist.contents.contains("<key>HOME</key>\n        <string>/home/echo</string>"));
+    assert!(plist.contents.contains("<string>sk-&quot;a&amp;b&quot;\\c</string>"));
+    // Only system daemons name an account
+    assert!(!plist.contents.contains("UserName"));
+
+    let system = Service::new(Manager::Launchd, Scope::System);
+    assert!(system.render(&spec())[0].contents.contains("<key>UserName</key>\n    <string>echo</string>"));
+    assert_eq!(system.commands(Step::Restart), vec![
+        vec!["launchctl", "kickstart", "-k", "system/com.port42.daemon"],
+    ]);
+}
@@ -0,0 +1,32 @@
+use port42::common::shell_history::{dedup, recall, remember};
+
+fn entries(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|l| l.to_string()).collect()
+}
+
+#[test]
+fn test_dedup_keeps_latest_occurrence() {
+    let history = entries(&["ls /", "status", "ls /", "cat /x", "status"]);
+    assert_eq!(dedup(&history, 10), entries(&["ls /", "cat /x", "status"]));
+    // Past the size limit the oldest go first
+    assert_eq!(dedup(&history, 2), entries(&["cat /x", "status"]));
+}
+
+#[test]
+fn test_remember_moves_repeats_to_the_end() {
+    let history = entries(&["ls /", "status", "cat /x"]);
+    assert_eq!(remember(&history, "ls /", 10), entries(&["status", "cat /x", "ls /"]));
+    assert_eq!(remember(&history, "tree", 3), entries(&["status", "cat /x", "tree"]));
+}
+
+#[test]
+fn test_recall_by_index() {
+    let history = entries(&["ls /", "status"]);
+    assert_eq!(recall(&history, "history 2").unwrap().as_deref(), Some("status"));
+    // Anything but `history <N>` is left alone
+    assert!(recall(&history, "history").unwrap().is_none());
+    assert!(recall(&history, "history /commands/x").unwrap().is_none());
+    assert!(recall(&history, "ls /").unwrap().is_none());
+    assert!(recall(&history, "history 0").is_err());
+    assert!(recall(&history, "history 3").is_err());
+}
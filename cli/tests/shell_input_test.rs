@@ -0,0 +1,26 @@
+use port42::common::shell_input::split_commands;
+
+#[test]
+fn test_lines_become_commands() {
+    let input = split_commands("ls /commands\n\n# a comment\n  cat /commands/git-haiku  \n");
+    assert_eq!(input.commands, vec!["ls /commands", "cat /commands/git-haiku"]);
+    assert!(!input.incomplete);
+}
+
+#[test]
+fn test_backslash_continues_the_line() {
+    assert!(split_commands("swim @ai-muse \\").incomplete);
+    let input = split_commands("swim @ai-muse \\\nwrite a haiku");
+    assert_eq!(input.commands, vec!["swim @ai-muse write a haiku"]);
+    assert!(!input.incomplete);
+}
+
+#[test]
+fn test_quotes_hold_newlines() {
+    assert!(split_commands("swim @ai-muse \"first line").incomplete);
+    let input = split_commands("swim @ai-muse \"first line\nsecond line\"\nls");
+    assert_eq!(input.commands, vec!["swim @ai-muse \"first line\nsecond line\"", "ls"]);
+    assert!(!input.incomplete);
+    // An apostrophe inside a word doesn't open a quote
+    assert!(!split_commands("swim @ai-muse what's new").incomplete);
+}
@@ -0,0 +1,20 @@
+use port42::common::site::{self, SiteEntry};
+
+#[test]
+fn test_site_pages() {
+    assert_eq!(site::escape_html("<a href=\"x\">&'</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&#39;&lt;/a&gt;");
+    assert_eq!(site::page_name("/artifacts/docs/api.md"), "artifacts-docs-api-md.html");
+    assert_eq!(site::page_name("/memory/cli-1754170150"), "memory-cli-1754170150.html");
+
+    let index = site::index_page(&[("Tools", vec![SiteEntry {
+        title: "git-haiku".to_string(),
+        href: "tools/commands-git-haiku.html".to_string(),
+        description: "Commits <as> poems".to_string(),
+        keywords: "Python".to_string(),
+    }]), ("Memories", Vec::new())], "2025-01-01 10:00");
+
+    assert!(index.contains("Commits &lt;as&gt; poems"));
+    assert!(index.contains("data-search=\"git-haiku commits &lt;as&gt; poems python\""));
+    assert!(index.contains("Nothing here yet."));
+    assert!(index.contains("href=\"style.css\""));
+}
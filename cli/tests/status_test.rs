@@ -0,0 +1,55 @@
+use port42::protocol::{CheckState, DetailedStatusRequest, RequestBuilder, ResponseParser, StatusRequest, StatusResponse};
+use serde_json::json;
+
+#[test]
+fn test_only_detailed_status_asks_for_probes() {
+    let plain = StatusRequest.build_request("id".to_string()).unwrap();
+    assert!(plain.payload.is_null());
+    let detailed = DetailedStatusRequest.build_request("id".to_string()).unwrap();
+    assert_eq!(detailed.request_type, "status");
+    assert_eq!(detailed.payload["detailed"], true);
+}
+
+#[test]
+fn test_health_checks() {
+    let fresh = chrono::Utc::now().to_rfc3339();
+    let status = StatusResponse::parse_response(&json!({
+        "port": 42,
+        "uptime": "1h",
+        "active_sessions": 1,
+        "health": {
+            "providers": [
+                {"name": "anthropic", "reachable": true, "latency_ms": 212},
+                {"name": "openai", "reachable": false, "error": "no API key"}
+            ],
+            "avg_latency_ms": 84.4,
+            "object_count": 12,
+            "index_updated_at": fresh
+        }
+    })).unwrap();
+    let checks = status.health.unwrap().checks();
+    let summary: Vec<(&str, &str, CheckState)> = checks.iter()
+        .map(|c| (c.name.as_str(), c.value.as_str(), c.state))
+        .collect();
+    assert_eq!(summary[..4], [
+        ("Provider anthropic", "reachable (212ms)", CheckState::Ok),
+        ("Provider openai", "unreachable: no API key", CheckState::Fail),
+        ("Avg latency", "84ms", CheckState::Info),
+        ("Object store", "12 objects", CheckState::Info),
+    ]);
+    assert_eq!(checks[4].name, "Search index");
+    assert_eq!(checks[4].state, CheckState::Ok);
+}
+
+#[test]
+fn test_stale_index_warns_and_plain_status_has_no_health() {
+    let status = StatusResponse::parse_response(&json!({
+        "health": {"index_updated_at": "2020-01-01T00:00:00Z", "unindexed_objects": 3}
+    })).unwrap();
+    let checks = status.health.unwrap().checks();
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].state, CheckState::Warn);
+    assert!(checks[0].value.ends_with("3 objects waiting"));
+
+    assert!(StatusResponse::parse_response(&json!({"port": 42})).unwrap().health.is_none());
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::{port42, temp_home};
+use port42::common::template::{parse_vars, placeholders, render};
+use port42::testing::MockDaemon;
+use serde_json::json;
+
+#[test]
+fn test_render_prompt_template() {
+    let template = "Review PR #{{pr}} in {{ repo }}.\nFocus on {{focus}}, then summarize PR #{{pr}}.";
+    assert_eq!(placeholders(template), vec!["pr", "repo", "focus"]);
+
+    let vars = parse_vars(&[
+        "pr=123".to_string(),
+        "repo=port42".to_string(),
+        "focus=error handling = retries".to_string(),
+    ]).unwrap();
+    let rendered = render(template, &vars).unwrap();
+    assert_eq!(rendered, "Review PR #123 in port42.\nFocus on error handling = retries, then summarize PR #123.");
+
+    // Every placeholder must be filled
+    let partial = parse_vars(&["pr=123".to_string()]).unwrap();
+    let err = render(template, &partial).unwrap_err().to_string();
+    assert!(err.contains("repo") && err.contains("focus"));
+
+    assert!(parse_vars(&["no-equals".to_string()]).is_err());
+}
+
+#[test]
+fn test_declare_renders_a_saved_prompt() {
+    let home = temp_home("template", "declare");
+    let daemon = MockDaemon::start();
+    // "A {{lang}} tool that {{task}}\n"
+    daemon.respond("read_path", json!({"path": "/artifacts/prompts/cli-tool.md", "content": "QSB7e2xhbmd9fSB0b29sIHRoYXQge3t0YXNrfX0K"}))
+        .respond("declare_relation", json!({
+            "relation_id": "rel-counter", "type": "Tool", "materialized": true,
+            "physical_path": "/commands/counter", "status": "success",
+        }));
+    let output = port42(&home, &daemon, &[
+        "declare", "tool", "counter", "--template", "cli-tool",
+        "--var", "lang=rust", "--var", "task=counts", "--prompt", "Keep it small",
+    ]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(daemon.requests_of("read_path")[0]["payload"]["path"], "/artifacts/prompts/cli-tool.md");
+    let declared = daemon.requests_of("declare_relation");
+    assert_eq!(declared[0]["user_prompt"], "A rust tool that counts\n\nKeep it small");
+
+    std::fs::remove_dir_all(&home).ok();
+}
+
+#[test]
+fn test_one_template_flag_and_one_store() {
+    let home = temp_home("template", "flags");
+    let daemon = MockDaemon::start();
+    // The old spelling is an alias of --template, not a second flag
+    let output = port42(&home, &daemon, &["swim", "@ai-engineer", "--prompt-template", "review-pr"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Prompt template 'review-pr' not found"));
+    assert_eq!(daemon.requests_of("read_path")[0]["payload"]["path"], "/artifacts/prompts/review-pr.md");
+    let output = port42(&home, &daemon, &["swim", "@ai-engineer", "--template", "a", "--prompt-template", "b"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    // `templates` is the prompts store under another name
+    let output = port42(&home, &daemon, &["templates", "list"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(daemon.requests_of("list_path")[0]["payload"]["path"], "/artifacts/prompts");
+
+    std::fs::remove_dir_all(&home).ok();
+}
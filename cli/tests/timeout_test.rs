@@ -0,0 +1,90 @@
+use port42::client::{set_retries, DaemonClient, RequestKind, Timeouts};
+use port42::config::TimeoutConfig;
+use port42::protocol::DaemonRequest;
+use port42::ui::wave_spinner::format_countdown;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_request_kinds() {
+    assert_eq!(RequestKind::of("swim"), RequestKind::Ai);
+    assert_eq!(RequestKind::of("declare_relation"), RequestKind::Ai);
+    assert_eq!(RequestKind::of("read_path"), RequestKind::Vfs);
+    assert_eq!(RequestKind::of("search"), RequestKind::Search);
+    assert_eq!(RequestKind::of("status"), RequestKind::Other);
+}
+
+#[test]
+fn test_timeout_layers() {
+    let config = TimeoutConfig { ai_secs: Some(600), vfs_secs: Some(10), ..Default::default() };
+    let env = |name: &str| match name {
+        "PORT42_TIMEOUT" => Some("45".to_string()),
+        "PORT42_TIMEOUT_AI" => Some("900".to_string()),
+        _ => None,
+    };
+
+    // Config over defaults
+    let configured = Timeouts::default().layered(&config, |_| None);
+    assert_eq!(configured.ai, Duration::from_secs(600));
+    assert_eq!(configured.vfs, Duration::from_secs(10));
+    assert_eq!(configured.search, Duration::from_secs(60));
+
+    // PORT42_TIMEOUT over config, per-kind variables over both
+    let layered = Timeouts::default().layered(&config, env);
+    assert_eq!(layered.ai, Duration::from_secs(900));
+    assert_eq!(layered.vfs, Duration::from_secs(45));
+    assert_eq!(layered.other, Duration::from_secs(45));
+}
+
+#[test]
+fn test_countdown_format() {
+    assert_eq!(format_countdown(Duration::from_secs(300)), "5:00");
+    assert_eq!(format_countdown(Duration::from_secs(65)), "1:05");
+    assert_eq!(format_countdown(Duration::from_millis(900)), "0:00");
+}
+
+#[test]
+fn test_request_gives_up_after_timeout() {
+    set_retries(0);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    // Turns down hello like a daemon from before the handshake, answers the
+    // status asked for its version, then reads the request and never answers
+    let daemon = std::thread::spawn(move || {
+        let mut line = String::new();
+        for reply in [
+            r#"{"id":"hello","success":false,"error":"Unknown request type: hello"}"#,
+            r#"{"id":"status","success":true,"data":{"port":42,"uptime":"1m","active_sessions":0}}"#,
+        ] {
+            let (mut stream, _) = listener.accept().unwrap();
+            BufReader::new(stream.try_clone().unwrap()).read_line(&mut line).unwrap();
+            writeln!(stream, "{}", reply).unwrap();
+        }
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        let _ = reader.read_line(&mut line);
+    });
+
+    let request = DaemonRequest {
+        request_type: "status".to_string(),
+        id: "timeout-test".to_string(),
+        payload: serde_json::Value::Null,
+        references: None,
+        session_context: None,
+        user_prompt: None,
+        provider: None,
+    };
+    let started = Instant::now();
+    let mut client = DaemonClient::new(port);
+    let err = client.request_timeout(request, Duration::from_secs(1)).unwrap_err();
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(err.to_string().contains("No reply within 1s"), "{}", err);
+
+    drop(client);
+    daemon.join().unwrap();
+}
@@ -0,0 +1,62 @@
+use port42::common::tool_manifest::parse;
+
+#[test]
+fn test_manifest_formats() {
+    let yaml = r#"
+tools:
+  - name: git-haiku
+    transforms: [git, haiku]
+    prompt: |
+      Turn recent commits into haiku
+    refs:
+      - file:./README.md
+  - name: log-parser
+    transforms: logs, errors
+    provider: ollama
+"#;
+    let tools = parse(yaml, "yaml").unwrap();
+    assert_eq!(tools.len(), 2);
+    assert_eq!(tools[0].name, "git-haiku");
+    assert_eq!(tools[0].transforms, vec!["git", "haiku"]);
+    assert_eq!(tools[0].prompt.as_deref(), Some("Turn recent commits into haiku\n"));
+    assert_eq!(tools[0].refs, vec!["file:./README.md"]);
+    assert_eq!(tools[1].transforms, vec!["logs", "errors"]);
+    assert_eq!(tools[1].provider.as_deref(), Some("ollama"));
+
+    // A bare list works too
+    let list = parse("- name: a\n  transforms: [x]\n", "yml").unwrap();
+    assert_eq!(list[0].name, "a");
+
+    let json = parse(r#"{"tools": [{"name": "a", "prompt": "p", "references": ["search:x"]}]}"#, "json").unwrap();
+    assert_eq!(json[0].refs, vec!["search:x"]);
+
+    let toml = parse("[[tools]]\nname = \"a\"\ntransforms = [\"x\"]\n", "toml").unwrap();
+    assert_eq!(toml[0].transforms, vec!["x"]);
+}
+
+#[test]
+fn test_manifest_validation() {
+    assert!(parse("tools: []\n", "yaml").is_err());
+    assert!(parse("tools:\n  - name: bad name\n    prompt: p\n", "yaml").is_err());
+    assert!(parse("tools:\n  - name: a\n    prompt: p\n  - name: a\n    prompt: q\n", "yaml").is_err());
+    assert!(parse("tools:\n  - name: a\n", "yaml").is_err());
+    assert!(parse("tools:\n  - name: a\n    prompt: p\n    refs: [nocolon]\n", "yaml").is_err());
+    assert!(parse("name: a\n", "yaml").is_err());
+}
+
+#[test]
+fn test_manifest_scalars_stay_as_written() {
+    let tools = parse("tools:\n  - name: 2024\n    prompt: 1.0\n    model: 3.50\n", "yaml").unwrap();
+    assert_eq!(tools[0].name, "2024");
+    assert_eq!(tools[0].prompt.as_deref(), Some("1.0"));
+    assert_eq!(tools[0].model.as_deref(), Some("3.50"));
+}
+
+#[test]
+fn test_manifest_block_scalar_dedent_is_an_error() {
+    // A line less indented than the block's first, but still inside its key
+    let dedented = "tools:\n  - name: a\n    prompt: |\n        four\n      two\n";
+    assert!(parse(dedented, "yaml").is_err());
+    let tools = parse("tools:\n  - name: a\n    prompt: |\n        four\n          six\n", "yaml").unwrap();
+    assert_eq!(tools[0].prompt.as_deref(), Some("four\n  six\n"));
+}
@@ -0,0 +1,38 @@
+use port42::common::transcript::{is_json_path, Transcript, TranscriptEntry};
+use port42::protocol::swim::CommandSpec;
+use std::path::Path;
+
+#[test]
+fn test_transcript_rendering() {
+    let mut reply = TranscriptEntry::new("assistant", "Here is your tool\n");
+    reply.timestamp = "2025-08-02T10:00:05+00:00".to_string();
+    reply.command_spec = Some(CommandSpec {
+        name: "git-haiku".to_string(),
+        description: "Commits as poems".to_string(),
+        language: "python".to_string(),
+    });
+    let mut question = TranscriptEntry::new("user", "make git-haiku");
+    question.timestamp = "2025-08-02T10:00:00+00:00".to_string();
+
+    let transcript = Transcript {
+        session_id: "cli-1".to_string(),
+        agent: "@ai-muse".to_string(),
+        started: "2025-08-02T09:59:00+00:00".to_string(),
+        saved: "2025-08-02T10:01:00+00:00".to_string(),
+        entries: vec![question, reply],
+    };
+
+    let markdown = transcript.render(false);
+    assert!(markdown.starts_with("# Port 42 session cli-1\n"));
+    assert!(markdown.contains("- **Generated:** `git-haiku` (command)\n"));
+    assert!(markdown.contains("### You · 2025-08-02 10:00:00\n\nmake git-haiku\n"));
+    assert!(markdown.contains("### @ai-muse · 2025-08-02 10:00:05\n\nHere is your tool\n"));
+    assert!(markdown.contains("> 🔨 Crystallized command `git-haiku` (python): Commits as poems"));
+
+    let json: serde_json::Value = serde_json::from_str(&transcript.render(true)).unwrap();
+    assert_eq!(json["entries"][1]["command_spec"]["name"], "git-haiku");
+    assert!(json["entries"][0].get("command_spec").is_none());
+
+    assert!(is_json_path(Path::new("out.JSON")));
+    assert!(!is_json_path(Path::new("out.md")));
+}
@@ -0,0 +1,46 @@
+use port42::protocol::{TreeNode, VfsTree};
+
+fn node(path: &str, entry_type: &str, size: Option<i64>, children: Vec<TreeNode>) -> TreeNode {
+    TreeNode {
+        name: path.rsplit('/').next().unwrap_or("/").to_string(),
+        path: path.to_string(),
+        entry_type: entry_type.to_string(),
+        size,
+        executable: None,
+        children,
+        truncated: false,
+        error: None,
+    }
+}
+
+#[test]
+fn test_tree_counts_and_sizes() {
+    let root = node("/artifacts", "directory", None, vec![
+        node("/artifacts/docs", "directory", None, vec![
+            node("/artifacts/docs/api.md", "file", Some(2300), vec![]),
+            node("/artifacts/docs/deep", "directory", None, vec![
+                node("/artifacts/docs/deep/notes.txt", "file", Some(120), vec![]),
+            ]),
+        ]),
+        node("/artifacts/logo.png", "file", None, vec![]),
+    ]);
+    let tree = VfsTree::new(root, true);
+    assert_eq!(tree.directories, 2);
+    assert_eq!(tree.files, 3);
+    assert_eq!(tree.root.total_size(), 2420);
+}
+
+#[test]
+fn test_tree_json_is_nested() {
+    let mut docs = node("/docs", "directory", None, vec![]);
+    docs.truncated = true;
+    let tree = VfsTree::new(node("/", "directory", None, vec![docs]), false);
+    let json = serde_json::to_value(&tree).unwrap();
+    assert_eq!(json["path"], "/");
+    assert_eq!(json["children"][0]["type"], "directory");
+    assert_eq!(json["children"][0]["truncated"], true);
+    // Empty and unknown fields are left out rather than nulled
+    assert!(json["children"][0].get("children").is_none());
+    assert!(json["children"][0].get("size").is_none());
+    assert!(json.get("show_sizes").is_none());
+}
@@ -0,0 +1,129 @@
+use port42::common::upgrade::{self, Release, LAYOUT_VERSION};
+use port42::common::archive::sha256_hex;
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("port42-upgrade-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_versions_and_urls() {
+    assert!(upgrade::is_newer("0.1.10", "0.1.9"));
+    assert!(upgrade::is_newer("v1.0.0", "0.9.9"));
+    assert!(upgrade::is_newer("0.2", "0.1.9"));
+    assert!(!upgrade::is_newer("0.1.1", "0.1.1"));
+    assert!(!upgrade::is_newer("0.1.0\n", "0.1.1"));
+
+    let release = Release::new("https://example.com/port42", "v0.1.2\n", "linux-x86_64");
+    assert_eq!(release.version, "0.1.2");
+    assert_eq!(release.archive_url, "https://example.com/port42/releases/port42-linux-x86_64-v0.1.2.tar.gz");
+    assert_eq!(release.checksum_url(), format!("{}.sha256", release.archive_url));
+}
+
+#[test]
+fn test_checksums() {
+    // sha256("abc")
+    let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    upgrade::verify_checksum(b"abc", &format!("{}  port42.tar.gz\n", digest)).unwrap();
+    upgrade::verify_checksum(b"abc", &digest.to_uppercase()).unwrap();
+
+    let err = upgrade::verify_checksum(b"abd", digest).unwrap_err();
+    assert!(err.to_string().contains("Checksum mismatch"), "{}", err);
+    assert!(upgrade::verify_checksum(b"abc", "not a digest").is_err());
+}
+
+#[test]
+fn test_swap_keeps_backups() {
+    let dir = temp_dir("swap");
+    let (new_cli, new_daemon) = (dir.join("new-port42"), dir.join("new-port42d"));
+    let (cli, daemon) = (dir.join("bin/port42"), dir.join("bin/port42d"));
+    fs::create_dir_all(dir.join("bin")).unwrap();
+    fs::write(&new_cli, "new cli").unwrap();
+    fs::write(&new_daemon, "new daemon").unwrap();
+    fs::write(&cli, "old cli").unwrap();
+
+    let installed = upgrade::swap(&[(&new_cli, &cli), (&new_daemon, &daemon)], &dir.join("backup")).unwrap();
+    assert_eq!(installed, vec![
+        (cli.clone(), sha256_hex(b"new cli")),
+        (daemon.clone(), sha256_hex(b"new daemon")),
+    ]);
+    assert_eq!(fs::read_to_string(&cli).unwrap(), "new cli");
+    assert_eq!(fs::read_to_string(&daemon).unwrap(), "new daemon");
+    assert_eq!(fs::read_to_string(dir.join("backup/port42.bak")).unwrap(), "old cli");
+    // Nothing was installed there before, so there's nothing to back up
+    assert!(!dir.join("backup/port42d.bak").exists());
+    // No staged copies are left behind
+    assert_eq!(fs::read_dir(dir.join("bin")).unwrap().count(), 2);
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_swap_cleans_up_when_staging_fails() {
+    let dir = temp_dir("swap-fail");
+    let new_cli = dir.join("new-port42");
+    let cli = dir.join("bin/port42");
+    fs::create_dir_all(dir.join("bin")).unwrap();
+    fs::write(&new_cli, "new cli").unwrap();
+    fs::write(&cli, "old cli").unwrap();
+
+    // The daemon's new binary is missing, so staging stops after the CLI's
+    let err = upgrade::swap(&[(&new_cli, &cli), (&dir.join("missing"), &dir.join("bin/port42d"))], &dir.join("backup"));
+    assert!(err.is_err());
+    assert_eq!(fs::read_to_string(&cli).unwrap(), "old cli");
+    assert_eq!(fs::read_dir(dir.join("bin")).unwrap().count(), 1, "no staged copies are left behind");
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_unpack_reads_the_checked_bytes() {
+    let dir = temp_dir("unpack");
+    let tree = dir.join("tree");
+    fs::create_dir_all(tree.join("bin")).unwrap();
+    fs::create_dir_all(tree.join("daemon")).unwrap();
+    fs::write(tree.join("bin/port42"), "cli").unwrap();
+    fs::write(tree.join("bin/port42d"), "daemon").unwrap();
+    fs::write(tree.join("daemon/agents.json"), "{}").unwrap();
+    let status = std::process::Command::new("tar")
+        .arg("-czf").arg(dir.join("release.tar.gz"))
+        .arg("-C").arg(&tree).arg(".")
+        .status().unwrap();
+    assert!(status.success());
+    let archive = fs::read(dir.join("release.tar.gz")).unwrap();
+
+    let out = dir.join("out");
+    fs::create_dir_all(&out).unwrap();
+    let unpacked = upgrade::unpack(&archive, &out).unwrap();
+    assert_eq!(fs::read_to_string(&unpacked.cli).unwrap(), "cli");
+    assert_eq!(fs::read_to_string(&unpacked.daemon).unwrap(), "daemon");
+    assert_eq!(unpacked.data, vec![out.join("daemon/agents.json")]);
+
+    assert!(upgrade::unpack(b"not a tarball", &out).is_err());
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_layout_migration() {
+    let dir = temp_dir("layout");
+    assert_eq!(upgrade::layout_version(&dir), 1);
+    upgrade::migrate(&dir).unwrap();
+    assert_eq!(fs::read_to_string(dir.join("layout")).unwrap(), LAYOUT_VERSION.to_string());
+
+    // A directory a newer release has already moved on is left alone
+    fs::write(dir.join("layout"), (LAYOUT_VERSION + 1).to_string()).unwrap();
+    assert!(upgrade::migrate(&dir).is_err());
+    assert_eq!(upgrade::layout_version(&dir), LAYOUT_VERSION + 1);
+
+    // Nothing is created for an installation that doesn't exist yet
+    let missing = dir.join("missing");
+    assert!(upgrade::migrate(&missing).unwrap().is_empty());
+    assert!(!missing.exists());
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,129 @@
+use port42::common::utils::parse_since;
+use port42::protocol::ResponseParser;
+use port42::protocol::usage::{UsageReport, UsageRequest, UsageResponse, format_cost};
+use port42::protocol::{MemoryDetailResponse, ProviderSelection, RequestBuilder};
+use port42::protocol::swim::TokenUsage;
+use serde_json::json;
+
+#[test]
+fn test_parse_since_formats() {
+    let week = parse_since("7d").unwrap();
+    assert_eq!((chrono::Utc::now() - week).num_days(), 7);
+
+    let hours = parse_since("12h").unwrap();
+    assert_eq!((chrono::Utc::now() - hours).num_hours(), 12);
+
+    let date = parse_since("2024-06-01").unwrap();
+    assert_eq!(date.to_rfc3339(), "2024-06-01T00:00:00+00:00");
+
+    assert!(parse_since("yesterday").is_err());
+    assert!(parse_since("7y").is_err());
+}
+
+#[test]
+fn test_usage_report_aggregation() {
+    let data = json!({
+        "records": [
+            {"session_id": "cli-1", "agent": "@ai-muse", "provider": "anthropic", "model": "claude-opus-4",
+             "date": "2024-06-01", "input_tokens": 1000, "output_tokens": 500, "cost": 0.5},
+            {"session_id": "cli-2", "agent": "@ai-analyst", "provider": "openai",
+             "date": "2024-06-01", "input_tokens": 2000, "output_tokens": 1000},
+            {"session_id": "cli-3", "agent": "@ai-muse", "provider": "local",
+             "date": "2024-06-02", "input_tokens": 300, "output_tokens": 200},
+            {"agent": "@ai-muse", "note": "records without a session id are skipped"}
+        ]
+    });
+
+    let usage = UsageResponse::parse_response(&data).unwrap();
+    assert_eq!(usage.records.len(), 3);
+
+    let report = UsageReport::from_records(&usage.records, None);
+    assert_eq!(report.total.sessions, 3);
+    assert_eq!(report.total.input_tokens, 3300);
+    assert_eq!(report.by_agent["@ai-muse"].sessions, 2);
+    assert_eq!(report.by_day["2024-06-01"].sessions, 2);
+
+    // Daemon-reported cost wins; otherwise it is estimated, and local is free
+    assert_eq!(report.by_provider["anthropic"].cost, 0.5);
+    assert!(report.by_provider["openai"].cost > 0.0);
+    assert_eq!(report.by_provider["local"].cost, 0.0);
+}
+
+#[test]
+fn test_usage_request_date_range() {
+    let request = UsageRequest { since: Some("2024-06-01T00:00:00+00:00".to_string()), until: Some("2024-07-01T00:00:00+00:00".to_string()) }
+        .build_request("id".to_string())
+        .unwrap();
+    assert_eq!(request.payload["until"], "2024-07-01T00:00:00+00:00");
+}
+
+#[test]
+fn test_token_usage_cost() {
+    let mut usage = TokenUsage { input_tokens: 1_000_000, output_tokens: 0 };
+    usage.add(TokenUsage { input_tokens: 0, output_tokens: 1_000_000 });
+    assert_eq!(usage.total(), 2_000_000);
+
+    let opus = ProviderSelection { name: Some("anthropic".to_string()), model: Some("claude-opus-4".to_string()), base_url: None };
+    assert_eq!(usage.cost(Some(&opus)), 90.0);
+    let local = ProviderSelection { name: Some("ollama".to_string()), model: None, base_url: None };
+    assert_eq!(usage.cost(Some(&local)), 0.0);
+
+    assert_eq!(format_cost(0.0042), "$0.0042");
+    assert_eq!(format_cost(1.5), "$1.50");
+}
+
+#[test]
+fn test_memory_detail_usage() {
+    let reported = MemoryDetailResponse::parse_response(&json!({
+        "id": "cli-1",
+        "messages": [],
+        "usage": {"input_tokens": 1200, "output_tokens": 300, "provider": "anthropic", "cost": 0.25}
+    })).unwrap();
+    let usage = reported.usage.unwrap();
+    assert_eq!((usage.input_tokens, usage.output_tokens, usage.cost), (1200, 300, 0.25));
+
+    // Without a session total, the messages' own usage is summed
+    let summed = MemoryDetailResponse::parse_response(&json!({
+        "id": "cli-2",
+        "messages": [
+            {"role": "user", "content": "hi", "timestamp": "2024-06-01T10:00:00Z"},
+            {"role": "assistant", "content": "hello", "timestamp": "2024-06-01T10:00:02Z",
+             "usage": {"input_tokens": 100, "output_tokens": 40}},
+            {"role": "assistant", "content": "again", "timestamp": "2024-06-01T10:01:00Z",
+             "usage": {"input_tokens": 150, "output_tokens": 60}}
+        ]
+    })).unwrap();
+    let usage = summed.usage.as_ref().unwrap();
+    assert_eq!((usage.input_tokens, usage.output_tokens), (250, 100));
+    assert!(usage.cost > 0.0);
+    let json = serde_json::to_value(&summed).unwrap();
+    assert_eq!(json["usage"]["input_tokens"], 250);
+    assert_eq!(json["messages"][1]["usage"]["output_tokens"], 40);
+
+    let untracked = MemoryDetailResponse::parse_response(&json!({"id": "cli-3", "messages": []})).unwrap();
+    assert!(untracked.usage.is_none());
+    assert!(serde_json::to_value(&untracked).unwrap().get("usage").is_none());
+}
+
+#[test]
+fn test_usage_since_compares_timestamps() {
+    let data = json!({
+        "records": [
+            {"session_id": "cli-1", "agent": "@ai-muse", "date": "2024-06-01",
+             "timestamp": "2024-06-01T09:00:00Z", "input_tokens": 100, "output_tokens": 50},
+            {"session_id": "cli-2", "agent": "@ai-muse", "date": "2024-06-01",
+             "timestamp": "2024-06-01T15:00:00Z", "input_tokens": 100, "output_tokens": 50},
+            {"session_id": "cli-3", "agent": "@ai-muse", "date": "2024-06-01",
+             "input_tokens": 100, "output_tokens": 50}
+        ]
+    });
+    let usage = UsageResponse::parse_response(&data).unwrap();
+    let since = parse_since("2024-06-01T12:00:00Z").unwrap();
+
+    let kept: Vec<&str> = usage.records.iter()
+        .filter(|r| r.is_since(since))
+        .map(|r| r.session_id.as_str())
+        .collect();
+    // Without a timestamp only the day is known, so the record stays
+    assert_eq!(kept, ["cli-2", "cli-3"]);
+}
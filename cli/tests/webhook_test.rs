@@ -0,0 +1,34 @@
+use port42::client::RetryPolicy;
+use port42::common::webhook::{self, Forwarder};
+use port42::protocol::events::DaemonEvent;
+use serde_json::json;
+
+#[test]
+fn test_hmac_sha256_vectors() {
+    // RFC 4231 test cases 2 and 6 (a key longer than the block is hashed first)
+    assert_eq!(
+        webhook::sign(b"Jefe", b"what do ya want for nothing?"),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    assert_eq!(
+        webhook::sign(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First"),
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+    );
+}
+
+#[test]
+fn test_forward_headers() {
+    let event: DaemonEvent = serde_json::from_value(json!({
+        "type": "tool_created", "timestamp": "2024-06-01T10:00:00Z", "subject": "git-haiku",
+    })).unwrap();
+    let body = serde_json::to_string(&event).unwrap();
+    let mut forwarder = Forwarder { url: "https://hooks.example.com/p42".to_string(), secret: None, retry: RetryPolicy::default() };
+    let headers = forwarder.headers(&event, &body);
+    assert_eq!(headers[0], "X-Port42-Event: tool_created");
+    assert!(headers[1].starts_with("X-Port42-Delivery: "));
+    assert!(!headers.iter().any(|h| h.starts_with(webhook::SIGNATURE_HEADER)));
+
+    forwarder.secret = Some("s3cret".to_string());
+    let headers = forwarder.headers(&event, &body);
+    assert_eq!(headers[2], format!("X-Port42-Signature: sha256={}", webhook::sign(b"s3cret", body.as_bytes())));
+}
@@ -0,0 +1,58 @@
+use port42::common::workspace::{MAX_ENTRIES, collect, is_enabled};
+use port42::config::{Config, SessionConfig};
+use port42::protocol::RequestBuilder;
+use port42::protocol::swim::SwimRequest;
+use std::fs;
+
+#[test]
+fn test_workspace_listing() {
+    let dir = std::env::temp_dir().join(format!("port42-workspace-test-{}", std::process::id()));
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("Cargo.toml"), "").unwrap();
+    fs::write(dir.join(".env"), "SECRET=1").unwrap();
+
+    let context = collect(&dir);
+    assert_eq!(context.cwd, dir.display().to_string());
+    // Hidden entries stay out; directories are marked
+    assert_eq!(context.files, vec!["Cargo.toml", "src/"]);
+    assert_eq!(context.omitted, 0);
+
+    for i in 0..MAX_ENTRIES + 5 {
+        fs::write(dir.join(format!("f{:03}", i)), "").unwrap();
+    }
+    let context = collect(&dir);
+    assert_eq!(context.files.len(), MAX_ENTRIES);
+    assert_eq!(context.omitted, 7);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_workspace_opt_in() {
+    let mut config = Config::default();
+    assert!(!is_enabled(&config));
+    config.session = Some(SessionConfig { workspace_context: Some(true), ..Default::default() });
+    assert!(is_enabled(&config));
+}
+
+#[test]
+fn test_swim_request_carries_workspace() {
+    let mut request = SwimRequest {
+        agent: "@ai-engineer".to_string(),
+        message: "hi".to_string(),
+        memory_context: None,
+        references: None,
+        approval_response: None,
+        provider: None,
+        guidance: None,
+        workspace: None,
+    };
+    let built = serde_json::to_value(request.build_request("t".to_string()).unwrap()).unwrap();
+    assert!(built.get("session_context").is_none());
+
+    request.workspace = Some(collect(&std::env::temp_dir()));
+    let built = serde_json::to_value(request.build_request("t".to_string()).unwrap()).unwrap();
+    let workspace = &built["session_context"]["workspace"];
+    assert_eq!(workspace["cwd"], std::env::temp_dir().display().to_string());
+    assert!(workspace["files"].is_array());
+    assert!(built["session_context"].get("session_id").is_none());
+}
@@ -0,0 +1,60 @@
+use port42::display::yaml::to_yaml;
+use serde_json::json;
+
+#[test]
+fn test_yaml_rendering() {
+    let value = json!({
+        "path": "/commands/git-haiku",
+        "count": 3,
+        "active": true,
+        "missing": null,
+        "version": "1.0",
+        "note": "key: value",
+        "empty": "",
+        "tags": ["git", "poetry"],
+        "entries": [{"name": "a", "size": 1}, {"name": "b", "size": 2}],
+        "nested": {"inner": {"deep": "yes"}},
+        "none": [],
+        "content": "line one\nline two\n",
+    });
+
+    let expected = r#"active: true
+content: |
+  line one
+  line two
+count: 3
+empty: ""
+entries:
+- name: a
+  size: 1
+- name: b
+  size: 2
+missing: null
+nested:
+  inner:
+    deep: "yes"
+none: []
+note: "key: value"
+path: /commands/git-haiku
+tags:
+- git
+- poetry
+version: "1.0"
+"#;
+    assert_eq!(to_yaml(&value), expected);
+    assert_eq!(to_yaml(&json!("plain")), "plain\n");
+    assert_eq!(to_yaml(&json!(["2025-08-02T10:00:00Z", ".5", "-x"])), "- \"2025-08-02T10:00:00Z\"\n- \".5\"\n- \"-x\"\n");
+    assert_eq!(to_yaml(&json!({"s": "no newline\nat end"})), "s: |-\n  no newline\n  at end\n");
+}
+
+#[test]
+fn test_yaml_round_trip() {
+    let value = json!({
+        "content": "first\nsecond\n",
+        "entries": [{"name": "a", "tags": ["x", "y"]}, {"name": "2024-01-01"}],
+        "note": "key: value",
+        "empty": "",
+    });
+    // What we render, a real YAML parser reads back unchanged
+    assert_eq!(serde_yaml::from_str::<serde_json::Value>(&to_yaml(&value)).unwrap(), value);
+}
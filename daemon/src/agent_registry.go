@@ -0,0 +1,162 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"io/ioutil"
+	"log"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+	"sync"
+)
+
+// RegisteredAgent is a custom agent defined by a CLI and announced to the daemon
+type RegisteredAgent struct {
+	Name        string   `json:"name"`
+	Description string   `json:"description,omitempty"`
+	Persona     string   `json:"persona,omitempty"`
+	Guidance    string   `json:"guidance,omitempty"`
+	DefaultRefs []string `json:"default_refs,omitempty"`
+	Provider    string   `json:"provider,omitempty"`
+	Model       string   `json:"model,omitempty"`
+}
+
+// AgentRegistry persists registered agents to ~/.port42/registered_agents.json
+type AgentRegistry struct {
+	path   string
+	agents map[string]RegisteredAgent
+	mu     sync.RWMutex
+}
+
+var agentRegistry *AgentRegistry
+
+// normalizeAgentKey matches the key agents.json uses ("@ai-reviewer" -> "reviewer")
+func normalizeAgentKey(name string) string {
+	key := strings.TrimPrefix(name, "@ai-")
+	return strings.TrimPrefix(key, "@")
+}
+
+// LoadAgentRegistry loads previously registered agents from baseDir
+func LoadAgentRegistry(baseDir string) *AgentRegistry {
+	registry := &AgentRegistry{
+		path:   filepath.Join(baseDir, "registered_agents.json"),
+		agents: make(map[string]RegisteredAgent),
+	}
+
+	data, err := ioutil.ReadFile(registry.path)
+	if err == nil {
+		var agents []RegisteredAgent
+		if err := json.Unmarshal(data, &agents); err != nil {
+			log.Printf("⚠️ Failed to parse %s: %v", registry.path, err)
+		}
+		for _, agent := range agents {
+			registry.agents[normalizeAgentKey(agent.Name)] = agent
+		}
+	} else if !os.IsNotExist(err) {
+		log.Printf("⚠️ Failed to read %s: %v", registry.path, err)
+	}
+
+	log.Printf("✅ Loaded %d registered agents", len(registry.agents))
+	return registry
+}
+
+// Register adds or replaces an agent
+func (r *AgentRegistry) Register(agent RegisteredAgent) error {
+	if normalizeAgentKey(agent.Name) == "" {
+		return fmt.Errorf("agent name is required")
+	}
+
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	r.agents[normalizeAgentKey(agent.Name)] = agent
+	return r.save()
+}
+
+// Unregister removes an agent, reporting whether it was registered
+func (r *AgentRegistry) Unregister(name string) (bool, error) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+
+	key := normalizeAgentKey(name)
+	if _, exists := r.agents[key]; !exists {
+		return false, nil
+	}
+	delete(r.agents, key)
+	return true, r.save()
+}
+
+// Get looks an agent up by any of its spellings
+func (r *AgentRegistry) Get(name string) (RegisteredAgent, bool) {
+	if r == nil {
+		return RegisteredAgent{}, false
+	}
+	r.mu.RLock()
+	defer r.mu.RUnlock()
+	agent, exists := r.agents[normalizeAgentKey(name)]
+	return agent, exists
+}
+
+// List returns the registered agents sorted by name
+func (r *AgentRegistry) List() []RegisteredAgent {
+	r.mu.RLock()
+	defer r.mu.RUnlock()
+
+	agents := make([]RegisteredAgent, 0, len(r.agents))
+	for _, agent := range r.agents {
+		agents = append(agents, agent)
+	}
+	sort.Slice(agents, func(i, j int) bool { return agents[i].Name < agents[j].Name })
+	return agents
+}
+
+// save writes the registry; callers hold the lock
+func (r *AgentRegistry) save() error {
+	agents := make([]RegisteredAgent, 0, len(r.agents))
+	for _, agent := range r.agents {
+		agents = append(agents, agent)
+	}
+	data, err := json.MarshalIndent(agents, "", "  ")
+	if err != nil {
+		return fmt.Errorf("failed to encode agent registry: %w", err)
+	}
+	if err := ioutil.WriteFile(r.path, data, 0644); err != nil {
+		return fmt.Errorf("failed to write agent registry: %w", err)
+	}
+	return nil
+}
+
+// builtinAgent returns the agents.json entry for a name
+func builtinAgent(name string) (Agent, bool) {
+	if agentConfig == nil {
+		return Agent{}, false
+	}
+	agent, exists := agentConfig.Agents[normalizeAgentKey(name)]
+	return agent, exists
+}
+
+// lookupAgent resolves built-in agents first, then registered ones
+func lookupAgent(name string) (Agent, bool) {
+	if agent, exists := builtinAgent(name); exists {
+		return agent, true
+	}
+	registered, exists := agentRegistry.Get(name)
+	if !exists {
+		return Agent{}, false
+	}
+
+	agent := Agent{
+		Name:         registered.Name,
+		Description:  registered.Description,
+		Personality:  registered.Persona,
+		CustomPrompt: registered.Guidance,
+	}
+	// Only keys from agents.json are usable; anything else gets the default model
+	if agentConfig != nil {
+		if _, known := agentConfig.Models[registered.Model]; known {
+			agent.Model = registered.Model
+		}
+	}
+	return agent, true
+}
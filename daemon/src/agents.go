@@ -171,7 +171,7 @@ func GetAgentPrompt(agentName string) string {
 	cleanName := strings.TrimPrefix(agentName, "@ai-")
 	cleanName = strings.TrimPrefix(cleanName, "@")
 	
-	agent, exists := agentConfig.Agents[cleanName]
+	agent, exists := lookupAgent(cleanName)
 	if !exists {
 		// Default prompt for unknown agents
 		return fmt.Sprintf("You are %s, swimming in Port 42's stream. Help the user create new commands and features for their system.", agentName)
@@ -233,8 +233,8 @@ func GetModelForAgent(agentName string) (*ModelDefinition, error) {
 	cleanName = strings.Replace(cleanName, "ai-", "", 1)
 	log.Printf("🔍 Normalized agent name: %s -> %s", agentName, cleanName)
 	
-	// Find the agent
-	agent, exists := agentConfig.Agents[cleanName]
+	// Find the agent (built-in or registered by a CLI)
+	agent, exists := lookupAgent(cleanName)
 	if !exists {
 		log.Printf("❌ Agent %s not found in config", cleanName)
 		return nil, fmt.Errorf("agent %s not found", agentName)
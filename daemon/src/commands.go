@@ -0,0 +1,113 @@
+package main
+
+import (
+	"bufio"
+	"encoding/json"
+	"fmt"
+	"log"
+	"os"
+	"path/filepath"
+	"sort"
+	"sync"
+	"time"
+)
+
+// CommandEntry is one command the user ran, as the shell hook or 'port42 run' reports it
+type CommandEntry struct {
+	Timestamp  time.Time `json:"timestamp"`
+	Command    string    `json:"command"`
+	ExitCode   int       `json:"exit_code"`
+	Cwd        string    `json:"cwd,omitempty"`
+	DurationMs *uint64   `json:"duration_ms,omitempty"`
+	Tool       string    `json:"tool,omitempty"`
+}
+
+// CommandCount is how often one command ran, as 'port42 digest' reads it
+type CommandCount struct {
+	Command string `json:"command"`
+	Count   int    `json:"count"`
+}
+
+// CommandLog appends tracked commands to ~/.port42/commands.jsonl
+type CommandLog struct {
+	path string
+	mu   sync.Mutex
+}
+
+var commandLog *CommandLog
+
+// NewCommandLog creates a command log in baseDir
+func NewCommandLog(baseDir string) *CommandLog {
+	return &CommandLog{path: filepath.Join(baseDir, "commands.jsonl")}
+}
+
+// Record appends one command
+func (l *CommandLog) Record(entry CommandEntry) {
+	if l == nil {
+		return
+	}
+	line, err := json.Marshal(entry)
+	if err != nil {
+		log.Printf("⚠️ Failed to encode command: %v", err)
+		return
+	}
+
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	file, err := os.OpenFile(l.path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0644)
+	if err != nil {
+		log.Printf("⚠️ Failed to open command log: %v", err)
+		return
+	}
+	defer file.Close()
+	if _, err := file.Write(append(line, '\n')); err != nil {
+		log.Printf("⚠️ Failed to record command: %v", err)
+	}
+}
+
+// TopCommands counts the commands run since a time (zero for all of them),
+// most frequent first; limit 0 returns every command
+func (l *CommandLog) TopCommands(since time.Time, limit int) ([]CommandCount, error) {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+
+	file, err := os.Open(l.path)
+	if os.IsNotExist(err) {
+		return []CommandCount{}, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("failed to read command log: %w", err)
+	}
+	defer file.Close()
+
+	counts := make(map[string]int)
+	scanner := bufio.NewScanner(file)
+	for scanner.Scan() {
+		var entry CommandEntry
+		if err := json.Unmarshal(scanner.Bytes(), &entry); err != nil {
+			continue
+		}
+		if !since.IsZero() && entry.Timestamp.Before(since) {
+			continue
+		}
+		counts[entry.Command]++
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, fmt.Errorf("failed to read command log: %w", err)
+	}
+
+	commands := make([]CommandCount, 0, len(counts))
+	for command, count := range counts {
+		commands = append(commands, CommandCount{Command: command, Count: count})
+	}
+	sort.Slice(commands, func(i, j int) bool {
+		if commands[i].Count != commands[j].Count {
+			return commands[i].Count > commands[j].Count
+		}
+		return commands[i].Command < commands[j].Command
+	})
+	if limit > 0 && len(commands) > limit {
+		commands = commands[:limit]
+	}
+	return commands, nil
+}
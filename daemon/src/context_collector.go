@@ -278,6 +278,9 @@ func (cc *ContextCollector) generateSuggestions(data *ContextData) []ContextSugg
 		}
 	}
 	
+	// Learn from what users did with earlier suggestions
+	suggestions = suggestionFeedback.Apply(suggestions)
+	
 	// Limit to top 5 suggestions
 	if len(suggestions) > 5 {
 		suggestions = suggestions[:5]
@@ -0,0 +1,166 @@
+package main
+
+import (
+	"fmt"
+	"hash/fnv"
+	"math"
+	"net/http"
+	"strings"
+	"unicode"
+)
+
+// Claude has no embedding API, so its texts are embedded locally
+const (
+	hashEmbeddingModel = "port42-hash-256"
+	hashEmbeddingDims  = 256
+)
+
+// Embedding models used when a request names no model
+var embeddingModels = map[string]string{
+	"openai": "text-embedding-3-small",
+	"google": "text-embedding-004",
+	"local":  "nomic-embed-text",
+}
+
+// embedTexts returns one vector per text and the model that made them
+func embedTexts(selection *ProviderSelection, texts []string) (string, [][]float32, error) {
+	if selection == nil {
+		selection = &ProviderSelection{}
+	}
+	name := selection.Name
+	if name == "" || name == "anthropic" {
+		vectors := make([][]float32, len(texts))
+		for i, text := range texts {
+			vectors[i] = hashEmbed(text)
+		}
+		return hashEmbeddingModel, vectors, nil
+	}
+
+	spec, exists := findProviderSpec(name)
+	if !exists {
+		return "", nil, fmt.Errorf("Unknown provider '%s'", name)
+	}
+	model := selection.Model
+	if model == "" {
+		model = embeddingModels[name]
+	}
+	client := newProviderClient(spec, selection.BaseURL, model)
+	if !client.Configured() {
+		return "", nil, fmt.Errorf("%s", missingKeyError(name))
+	}
+
+	var vectors [][]float32
+	var err error
+	switch name {
+	case "openai":
+		vectors, err = client.embedOpenAI(texts)
+	case "google":
+		vectors, err = client.embedGemini(texts)
+	case "local":
+		vectors, err = client.embedOllama(texts)
+	}
+	if err != nil {
+		return "", nil, err
+	}
+	if len(vectors) != len(texts) {
+		return "", nil, fmt.Errorf("%s returned %d embeddings for %d texts", name, len(vectors), len(texts))
+	}
+	return model, vectors, nil
+}
+
+// embedOpenAI uses the OpenAI embeddings API
+func (c *providerClient) embedOpenAI(texts []string) ([][]float32, error) {
+	header := http.Header{}
+	header.Set("Authorization", "Bearer "+c.apiKey)
+	var reply struct {
+		Data []struct {
+			Index     int       `json:"index"`
+			Embedding []float32 `json:"embedding"`
+		} `json:"data"`
+	}
+	body := map[string]interface{}{"model": c.model, "input": texts}
+	if err := c.post(c.baseURL+"/embeddings", header, body, &reply); err != nil {
+		return nil, err
+	}
+
+	vectors := make([][]float32, len(reply.Data))
+	for _, d := range reply.Data {
+		if d.Index < 0 || d.Index >= len(vectors) {
+			return nil, fmt.Errorf("openai returned an embedding for unknown input %d", d.Index)
+		}
+		vectors[d.Index] = d.Embedding
+	}
+	return vectors, nil
+}
+
+// embedGemini uses Google's batchEmbedContents API
+func (c *providerClient) embedGemini(texts []string) ([][]float32, error) {
+	header := http.Header{}
+	header.Set("x-goog-api-key", c.apiKey)
+	requests := make([]map[string]interface{}, len(texts))
+	for i, text := range texts {
+		requests[i] = map[string]interface{}{
+			"model":   "models/" + c.model,
+			"content": map[string]interface{}{"parts": []map[string]string{{"text": text}}},
+		}
+	}
+	var reply struct {
+		Embeddings []struct {
+			Values []float32 `json:"values"`
+		} `json:"embeddings"`
+	}
+	url := fmt.Sprintf("%s/models/%s:batchEmbedContents", c.baseURL, c.model)
+	if err := c.post(url, header, map[string]interface{}{"requests": requests}, &reply); err != nil {
+		return nil, err
+	}
+
+	vectors := make([][]float32, len(reply.Embeddings))
+	for i, e := range reply.Embeddings {
+		vectors[i] = e.Values
+	}
+	return vectors, nil
+}
+
+// embedOllama uses Ollama's /api/embed
+func (c *providerClient) embedOllama(texts []string) ([][]float32, error) {
+	var reply struct {
+		Embeddings [][]float32 `json:"embeddings"`
+	}
+	body := map[string]interface{}{"model": c.model, "input": texts}
+	if err := c.post(c.baseURL+"/api/embed", http.Header{}, body, &reply); err != nil {
+		return nil, err
+	}
+	return reply.Embeddings, nil
+}
+
+// hashEmbed hashes a text's lowercased words into a fixed number of
+// buckets and normalises the counts, so texts sharing words land close
+func hashEmbed(text string) []float32 {
+	vector := make([]float32, hashEmbeddingDims)
+	words := strings.FieldsFunc(strings.ToLower(text), func(r rune) bool {
+		return !unicode.IsLetter(r) && !unicode.IsNumber(r)
+	})
+	for _, word := range words {
+		h := fnv.New32a()
+		h.Write([]byte(word))
+		sum := h.Sum32()
+		// The top bit picks a sign so unrelated words cancel rather than pile up
+		if sum&(1<<31) != 0 {
+			vector[sum%hashEmbeddingDims]--
+		} else {
+			vector[sum%hashEmbeddingDims]++
+		}
+	}
+
+	var norm float64
+	for _, v := range vector {
+		norm += float64(v) * float64(v)
+	}
+	if norm > 0 {
+		scale := float32(1 / math.Sqrt(norm))
+		for i := range vector {
+			vector[i] *= scale
+		}
+	}
+	return vector
+}
@@ -0,0 +1,114 @@
+package main
+
+import (
+	"net/http"
+	"os"
+	"path/filepath"
+	"sync"
+	"time"
+)
+
+// How many recent requests the average latency covers
+const latencyWindow = 100
+
+const probeTimeout = 5 * time.Second
+
+// HealthReport is the "health" part of a detailed status
+type HealthReport struct {
+	Providers        []ProviderProbe `json:"providers"`
+	AvgLatencyMs     *float64        `json:"avg_latency_ms,omitempty"`
+	ObjectCount      *int            `json:"object_count,omitempty"`
+	ObjectStoreBytes *int64          `json:"object_store_bytes,omitempty"`
+}
+
+// ProviderProbe says whether an AI provider answered
+type ProviderProbe struct {
+	Name      string `json:"name"`
+	Reachable bool   `json:"reachable"`
+	LatencyMs int64  `json:"latency_ms,omitempty"`
+	Error     string `json:"error,omitempty"`
+}
+
+// LatencyTracker keeps the durations of the most recent requests
+type LatencyTracker struct {
+	samples []time.Duration
+	next    int
+	mu      sync.Mutex
+}
+
+// Record adds one request's duration
+func (t *LatencyTracker) Record(d time.Duration) {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	if len(t.samples) < latencyWindow {
+		t.samples = append(t.samples, d)
+		return
+	}
+	t.samples[t.next] = d
+	t.next = (t.next + 1) % latencyWindow
+}
+
+// AverageMs returns the mean duration in milliseconds, or nil before any request
+func (t *LatencyTracker) AverageMs() *float64 {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	if len(t.samples) == 0 {
+		return nil
+	}
+	var total time.Duration
+	for _, d := range t.samples {
+		total += d
+	}
+	avg := float64(total.Microseconds()) / float64(len(t.samples)) / 1000
+	return &avg
+}
+
+// collectHealth probes the providers and measures the object store
+func (d *Daemon) collectHealth() *HealthReport {
+	report := &HealthReport{
+		Providers:    []ProviderProbe{probeProvider("anthropic", "https://api.anthropic.com")},
+		AvgLatencyMs: d.latency.AverageMs(),
+	}
+
+	if d.storage != nil {
+		count, size := directoryStats(d.storage.objectsDir)
+		report.ObjectCount = &count
+		report.ObjectStoreBytes = &size
+	}
+	return report
+}
+
+// probeProvider counts any HTTP answer as reachable; only the network can fail it
+func probeProvider(name, url string) ProviderProbe {
+	probe := ProviderProbe{Name: name}
+	client := &http.Client{Timeout: probeTimeout}
+
+	start := time.Now()
+	resp, err := client.Get(url)
+	if err != nil {
+		probe.Error = err.Error()
+		return probe
+	}
+	resp.Body.Close()
+
+	probe.Reachable = true
+	probe.LatencyMs = time.Since(start).Milliseconds()
+	return probe
+}
+
+// directoryStats counts the files under dir and their total size
+func directoryStats(dir string) (int, int64) {
+	count := 0
+	var size int64
+	filepath.Walk(dir, func(path string, info os.FileInfo, err error) error {
+		if err != nil {
+			return nil
+		}
+		if !info.IsDir() {
+			count++
+			size += info.Size()
+		}
+		return nil
+	})
+	return count, size
+}
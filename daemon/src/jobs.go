@@ -0,0 +1,191 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"log"
+	"sort"
+	"sync"
+	"time"
+)
+
+// How long finished jobs stay listed
+const jobRetention = 24 * time.Hour
+
+// Job is an AI request the daemon runs in the background for a detached CLI
+type Job struct {
+	ID          string          `json:"id"`
+	Kind        string          `json:"kind"`   // "swim" or "declare"
+	Status      string          `json:"status"` // queued, running, completed, failed, cancelled
+	Description string          `json:"description,omitempty"`
+	Stage       string          `json:"stage,omitempty"`
+	CreatedAt   time.Time       `json:"created_at"`
+	UpdatedAt   time.Time       `json:"updated_at"`
+	Result      json.RawMessage `json:"result,omitempty"`
+	Error       string          `json:"error,omitempty"`
+}
+
+// finished reports whether nothing more will happen to the job
+func (j *Job) finished() bool {
+	return j.Status == "completed" || j.Status == "failed" || j.Status == "cancelled"
+}
+
+// JobManager tracks detached requests in memory
+type JobManager struct {
+	jobs map[string]*Job
+	mu   sync.Mutex
+}
+
+// NewJobManager creates an empty job table
+func NewJobManager() *JobManager {
+	return &JobManager{jobs: make(map[string]*Job)}
+}
+
+// isDetached reports whether a request asked to run in the background
+func isDetached(req Request) bool {
+	if req.Type != "swim" && req.Type != "declare_relation" {
+		return false
+	}
+	var payload struct {
+		Detach bool `json:"detach"`
+	}
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return false
+	}
+	return payload.Detach
+}
+
+// describeJob summarises a request for job listings
+func describeJob(req Request) (string, string) {
+	if req.Type == "declare_relation" {
+		var payload struct {
+			Relation struct {
+				Type       string                 `json:"type"`
+				Properties map[string]interface{} `json:"properties"`
+			} `json:"relation"`
+		}
+		json.Unmarshal(req.Payload, &payload)
+		if name, ok := payload.Relation.Properties["name"].(string); ok {
+			return "declare", fmt.Sprintf("%s %s", payload.Relation.Type, name)
+		}
+		return "declare", payload.Relation.Type
+	}
+
+	var payload struct {
+		Agent   string `json:"agent"`
+		Message string `json:"message"`
+	}
+	json.Unmarshal(req.Payload, &payload)
+	message := payload.Message
+	if len(message) > 60 {
+		message = message[:60] + "..."
+	}
+	return "swim", fmt.Sprintf("%s: %s", payload.Agent, message)
+}
+
+// Submit records a job and runs it in the background with run
+func (m *JobManager) Submit(req Request, run func(Request) Response) *Job {
+	kind, description := describeJob(req)
+	now := time.Now()
+	job := &Job{
+		ID:          fmt.Sprintf("job-%d", now.UnixNano()),
+		Kind:        kind,
+		Status:      "queued",
+		Description: description,
+		CreatedAt:   now,
+		UpdatedAt:   now,
+	}
+
+	m.mu.Lock()
+	m.prune()
+	m.jobs[job.ID] = job
+	m.mu.Unlock()
+
+	go func() {
+		if !m.update(job.ID, func(j *Job) { j.Status = "running"; j.Stage = "generating" }) {
+			return
+		}
+		log.Printf("⏳ Job %s running (%s)", job.ID, description)
+
+		resp := run(req)
+		m.update(job.ID, func(j *Job) {
+			j.Stage = ""
+			if resp.Success {
+				j.Status = "completed"
+				j.Result = resp.Data
+			} else {
+				j.Status = "failed"
+				j.Error = resp.Error
+			}
+		})
+		log.Printf("⏳ Job %s finished, success: %v", job.ID, resp.Success)
+	}()
+
+	return job
+}
+
+// update applies change to a job that hasn't finished; false if it has (e.g. was cancelled)
+func (m *JobManager) update(id string, change func(*Job)) bool {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	job, exists := m.jobs[id]
+	if !exists || job.finished() {
+		return false
+	}
+	change(job)
+	job.UpdatedAt = time.Now()
+	return true
+}
+
+// Get returns a copy of a job
+func (m *JobManager) Get(id string) (Job, bool) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	job, exists := m.jobs[id]
+	if !exists {
+		return Job{}, false
+	}
+	return *job, true
+}
+
+// List returns copies of all jobs, newest first
+func (m *JobManager) List() []Job {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	m.prune()
+
+	jobs := make([]Job, 0, len(m.jobs))
+	for _, job := range m.jobs {
+		jobs = append(jobs, *job)
+	}
+	sort.Slice(jobs, func(i, j int) bool { return jobs[i].CreatedAt.After(jobs[j].CreatedAt) })
+	return jobs
+}
+
+// Cancel marks a job cancelled. A request already with the AI still runs to
+// the end, but its result is discarded.
+func (m *JobManager) Cancel(id string) error {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	job, exists := m.jobs[id]
+	if !exists {
+		return fmt.Errorf("Job not found: %s", id)
+	}
+	if job.finished() {
+		return fmt.Errorf("Job %s already %s", id, job.Status)
+	}
+	job.Status = "cancelled"
+	job.Stage = ""
+	job.UpdatedAt = time.Now()
+	return nil
+}
+
+// prune drops finished jobs past retention; callers hold the lock
+func (m *JobManager) prune() {
+	cutoff := time.Now().Add(-jobRetention)
+	for id, job := range m.jobs {
+		if job.finished() && job.UpdatedAt.Before(cutoff) {
+			delete(m.jobs, id)
+		}
+	}
+}
@@ -18,6 +18,10 @@ var (
 	daemon    *Daemon
 )
 
+// Version is the release this daemon was built from, set at build time with
+// -ldflags "-X main.Version=<version>"
+var Version = "dev"
+
 func main() {
 	var listener net.Listener
 	var err error
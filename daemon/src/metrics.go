@@ -0,0 +1,181 @@
+package main
+
+import (
+	"sort"
+	"sync"
+	"time"
+)
+
+// Upper bounds, in seconds, of the request duration histogram
+var durationBuckets = []float64{0.005, 0.01, 0.05, 0.1, 0.5, 1, 5, 30, 120}
+
+// MetricFamily is one metric as 'port42 metrics' reads it
+type MetricFamily struct {
+	Name    string         `json:"name"`
+	Type    string         `json:"type"` // counter, gauge or histogram
+	Help    string         `json:"help,omitempty"`
+	Samples []MetricSample `json:"samples"`
+}
+
+// MetricSample is one labelled series: a value, or buckets for a histogram
+type MetricSample struct {
+	Labels  map[string]string `json:"labels,omitempty"`
+	Value   *float64          `json:"value,omitempty"`
+	Buckets []MetricBucket    `json:"buckets,omitempty"`
+	Sum     *float64          `json:"sum,omitempty"`
+	Count   *uint64           `json:"count,omitempty"`
+}
+
+// MetricBucket counts observations at or below Le, cumulatively
+type MetricBucket struct {
+	Le    float64 `json:"le"`
+	Count uint64  `json:"count"`
+}
+
+// requestKey labels request counts
+type requestKey struct {
+	Type    string
+	Success bool
+}
+
+// durationHistogram tracks how long one request type takes
+type durationHistogram struct {
+	counts []uint64 // per bucket, not cumulative
+	sum    float64
+	count  uint64
+}
+
+// RequestMetrics counts the requests the daemon has answered since it started
+type RequestMetrics struct {
+	requests  map[requestKey]uint64
+	durations map[string]*durationHistogram
+	mu        sync.Mutex
+}
+
+// NewRequestMetrics creates empty request metrics
+func NewRequestMetrics() *RequestMetrics {
+	return &RequestMetrics{
+		requests:  make(map[requestKey]uint64),
+		durations: make(map[string]*durationHistogram),
+	}
+}
+
+// Observe records one answered request
+func (m *RequestMetrics) Observe(requestType string, success bool, elapsed time.Duration) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	m.requests[requestKey{requestType, success}]++
+
+	h, exists := m.durations[requestType]
+	if !exists {
+		h = &durationHistogram{counts: make([]uint64, len(durationBuckets))}
+		m.durations[requestType] = h
+	}
+	seconds := elapsed.Seconds()
+	for i, le := range durationBuckets {
+		if seconds <= le {
+			h.counts[i]++
+			break
+		}
+	}
+	h.sum += seconds
+	h.count++
+}
+
+// families snapshots the request counters and histograms
+func (m *RequestMetrics) families() []MetricFamily {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	requests := MetricFamily{
+		Name: "port42_requests_total",
+		Type: "counter",
+		Help: "Requests answered, by type and outcome",
+	}
+	for key, n := range m.requests {
+		value := float64(n)
+		success := "false"
+		if key.Success {
+			success = "true"
+		}
+		requests.Samples = append(requests.Samples, MetricSample{
+			Labels: map[string]string{"type": key.Type, "success": success},
+			Value:  &value,
+		})
+	}
+
+	durations := MetricFamily{
+		Name: "port42_request_duration_seconds",
+		Type: "histogram",
+		Help: "Time taken to answer requests, by type",
+	}
+	for requestType, h := range m.durations {
+		buckets := make([]MetricBucket, len(durationBuckets))
+		var cumulative uint64
+		for i, le := range durationBuckets {
+			cumulative += h.counts[i]
+			buckets[i] = MetricBucket{Le: le, Count: cumulative}
+		}
+		sum, count := h.sum, h.count
+		durations.Samples = append(durations.Samples, MetricSample{
+			Labels:  map[string]string{"type": requestType},
+			Buckets: buckets,
+			Sum:     &sum,
+			Count:   &count,
+		})
+	}
+
+	for _, family := range []MetricFamily{requests, durations} {
+		sort.Slice(family.Samples, func(i, j int) bool {
+			a, b := family.Samples[i].Labels, family.Samples[j].Labels
+			if a["type"] != b["type"] {
+				return a["type"] < b["type"]
+			}
+			return a["success"] < b["success"]
+		})
+	}
+	return []MetricFamily{requests, durations}
+}
+
+// gauge is a single unlabelled gauge family
+func gauge(name, help string, value float64) MetricFamily {
+	return MetricFamily{
+		Name:    name,
+		Type:    "gauge",
+		Help:    help,
+		Samples: []MetricSample{{Value: &value}},
+	}
+}
+
+// collectMetrics gathers the request metrics and the daemon's current state
+func (d *Daemon) collectMetrics() []MetricFamily {
+	d.mu.RLock()
+	activeSessions := len(d.sessions)
+	d.mu.RUnlock()
+
+	jobs := make(map[string]int)
+	for _, job := range d.jobs.List() {
+		jobs[job.Status]++
+	}
+	jobFamily := MetricFamily{
+		Name: "port42_jobs",
+		Type: "gauge",
+		Help: "Detached jobs, by status",
+	}
+	for _, status := range []string{"queued", "running", "completed", "failed", "cancelled"} {
+		value := float64(jobs[status])
+		jobFamily.Samples = append(jobFamily.Samples, MetricSample{
+			Labels: map[string]string{"status": status},
+			Value:  &value,
+		})
+	}
+
+	families := d.metrics.families()
+	families = append(families,
+		gauge("port42_uptime_seconds", "Seconds since the daemon started", time.Since(startTime).Seconds()),
+		gauge("port42_active_sessions", "Sessions loaded in memory", float64(activeSessions)),
+		jobFamily,
+	)
+	return families
+}
@@ -3,22 +3,46 @@ package main
 import (
 	"encoding/json"
 	"fmt"
+	"log"
 )
 
 // Request represents an incoming request from the CLI
 type Request struct {
-	Type           string          `json:"type"`
-	ID             string          `json:"id"`
-	Payload        json.RawMessage `json:"payload"`
-	SessionContext *SessionContext `json:"session_context,omitempty"` // Optional session info
-	References     []Reference     `json:"references,omitempty"`      // Universal references
-	UserPrompt     string          `json:"user_prompt,omitempty"`     // Universal user prompt
+	Type           string             `json:"type"`
+	ID             string             `json:"id"`
+	Payload        json.RawMessage    `json:"payload"`
+	SessionContext *SessionContext    `json:"session_context,omitempty"` // Optional session info
+	References     []Reference        `json:"references,omitempty"`      // Universal references
+	UserPrompt     string             `json:"user_prompt,omitempty"`     // Universal user prompt
+	Provider       *ProviderSelection `json:"provider,omitempty"`        // AI backend chosen with --provider/--model
+	
+	emit func(line interface{}) error // Writes a line ahead of the response; nil when nobody is listening
+}
+
+// Emit sends an intermediate line (a stream chunk, say) before the response
+func (r Request) Emit(line interface{}) {
+	if r.emit == nil {
+		return
+	}
+	if err := r.emit(line); err != nil {
+		log.Printf("⚠️ Failed to emit line for [%s]: %v", r.ID, err)
+	}
 }
 
 // SessionContext provides memory session information for relation tracking
 type SessionContext struct {
-	SessionID string `json:"session_id,omitempty"` // Memory session ID
-	Agent     string `json:"agent,omitempty"`      // AI agent name if from conversation
+	SessionID string            `json:"session_id,omitempty"` // Memory session ID
+	Agent     string            `json:"agent,omitempty"`      // AI agent name if from conversation
+	Workspace *WorkspaceContext `json:"workspace,omitempty"`  // Where the CLI is running, when it opts in
+}
+
+// WorkspaceContext describes the directory a swim session runs in.
+// Only names are sent, never file contents.
+type WorkspaceContext struct {
+	Cwd       string   `json:"cwd"`
+	GitBranch string   `json:"git_branch,omitempty"`
+	Files     []string `json:"files"`             // Top-level names; directories end in "/"
+	Omitted   int      `json:"omitted,omitempty"` // Entries left out of Files
 }
 
 // Reference represents a contextual reference to enhance tool generation
@@ -44,15 +68,40 @@ const (
 	RequestMemory  = "memory"
 	RequestWatch   = "watch"
 	RequestEnd     = "end"
+	RequestHello   = "hello"
 )
 
+// Protocol revision this daemon speaks, and the oldest CLI revision it serves
+const (
+	ProtocolRevision  = 1
+	MinClientRevision = 0
+)
+
+// Optional behaviour advertised in HelloData.Features
+const (
+	// AI requests with "detach": true are answered at once with a job id
+	FeatureDetach = "detach"
+)
+
+// HelloData answers the CLI's opening handshake. Features lists optional
+// behaviour the CLI may rely on, so it doesn't send requests an older
+// daemon would mishandle.
+type HelloData struct {
+	Version     string   `json:"version"`
+	Protocol    int      `json:"protocol"`
+	MinProtocol int      `json:"min_protocol"`
+	Features    []string `json:"features"`
+}
+
 // SwimPayload for swim requests
 type SwimPayload struct {
 	Agent            string            `json:"agent"`
 	Message          string            `json:"message"`
 	SessionID        string            `json:"session_id,omitempty"`
 	MemoryContext    []string          `json:"memory_context,omitempty"`
+	AgentGuidance    string            `json:"agent_guidance,omitempty"` // Persona and guidance from the CLI's agents.toml
 	ApprovalResponse *ApprovalResponse `json:"approval_response,omitempty"`
+	Stream           bool              `json:"stream,omitempty"` // Send the reply as chunk lines too
 }
 
 // ApprovalRequest sent from daemon to CLI when bash command needs approval
@@ -71,12 +120,14 @@ type ApprovalResponse struct {
 // StatusData for status responses
 type StatusData struct {
 	Status    string `json:"status"`
+	Version   string `json:"version,omitempty"`
 	Port      string `json:"port"`
 	Sessions  int    `json:"sessions"`
 	Uptime    string `json:"uptime"`
 	Dolphins  string `json:"dolphins"`
 	RuleCount int    `json:"rule_count,omitempty"`
 	Rules     string `json:"rules,omitempty"`
+	Health    *HealthReport `json:"health,omitempty"` // Only for a detailed status
 }
 
 // WatchPayload for watch requests
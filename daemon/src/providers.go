@@ -0,0 +1,483 @@
+package main
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"io"
+	"log"
+	"net/http"
+	"os"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+)
+
+const modelsTimeout = 10 * time.Second
+
+// providerSpec is what the daemon needs to reach one AI provider; the names
+// and variables match the CLI's KNOWN_PROVIDERS
+type providerSpec struct {
+	Name       string
+	KeyVars    []string // checked in order
+	BaseURL    string
+	BaseURLVar string // overrides BaseURL when set
+}
+
+var providerSpecs = []providerSpec{
+	{Name: "anthropic", KeyVars: []string{"PORT42_ANTHROPIC_API_KEY", "ANTHROPIC_API_KEY"}, BaseURL: "https://api.anthropic.com"},
+	{Name: "openai", KeyVars: []string{"PORT42_OPENAI_API_KEY", "OPENAI_API_KEY"}, BaseURL: "https://api.openai.com/v1", BaseURLVar: "PORT42_OPENAI_BASE_URL"},
+	{Name: "google", KeyVars: []string{"PORT42_GOOGLE_API_KEY", "GOOGLE_API_KEY", "GEMINI_API_KEY"}, BaseURL: "https://generativelanguage.googleapis.com/v1beta"},
+	{Name: "local", BaseURL: "http://localhost:11434", BaseURLVar: "PORT42_OLLAMA_BASE_URL"},
+}
+
+// findProviderSpec looks a provider up by name
+func findProviderSpec(name string) (providerSpec, bool) {
+	for _, spec := range providerSpecs {
+		if spec.Name == name {
+			return spec, true
+		}
+	}
+	return providerSpec{}, false
+}
+
+// apiKey returns the first key set in the environment
+func (p providerSpec) apiKey() string {
+	for _, v := range p.KeyVars {
+		if key := os.Getenv(v); key != "" {
+			return key
+		}
+	}
+	return ""
+}
+
+// baseURL returns the endpoint, honouring the override variable
+func (p providerSpec) baseURL() string {
+	if p.BaseURLVar != "" {
+		if url := os.Getenv(p.BaseURLVar); url != "" {
+			return strings.TrimSuffix(url, "/")
+		}
+	}
+	return p.BaseURL
+}
+
+// ProviderModels is one provider's answer to a models request
+type ProviderModels struct {
+	Name   string   `json:"name"`
+	Models []string `json:"models"`
+	Error  string   `json:"error,omitempty"`
+}
+
+// listModels asks the provider which models it offers
+func (p providerSpec) listModels() ([]string, error) {
+	key := p.apiKey()
+	if len(p.KeyVars) > 0 && key == "" {
+		return nil, fmt.Errorf("no API key; set %s", strings.Join(p.KeyVars, " or "))
+	}
+
+	var url string
+	header := http.Header{}
+	switch p.Name {
+	case "anthropic":
+		url = p.baseURL() + "/v1/models?limit=1000"
+		header.Set("x-api-key", key)
+		header.Set("anthropic-version", "2023-06-01")
+	case "openai":
+		url = p.baseURL() + "/models"
+		header.Set("Authorization", "Bearer "+key)
+	case "google":
+		url = p.baseURL() + "/models?pageSize=1000"
+		header.Set("x-goog-api-key", key)
+	case "local":
+		url = p.baseURL() + "/api/tags"
+	default:
+		return nil, fmt.Errorf("unknown provider: %s", p.Name)
+	}
+
+	httpReq, err := http.NewRequest("GET", url, nil)
+	if err != nil {
+		return nil, err
+	}
+	httpReq.Header = header
+	client := &http.Client{Timeout: modelsTimeout}
+	resp, err := client.Do(httpReq)
+	if err != nil {
+		return nil, fmt.Errorf("%s unreachable: %v", p.Name, err)
+	}
+	defer resp.Body.Close()
+	body, err := io.ReadAll(resp.Body)
+	if err != nil {
+		return nil, err
+	}
+	if resp.StatusCode != http.StatusOK {
+		return nil, fmt.Errorf("%s returned %d: %s", p.Name, resp.StatusCode, strings.TrimSpace(string(body)))
+	}
+
+	// Anthropic and OpenAI list {"data":[{"id"}]}; Google and Ollama {"models":[{"name"}]}
+	var listing struct {
+		Data []struct {
+			ID string `json:"id"`
+		} `json:"data"`
+		Models []struct {
+			Name string `json:"name"`
+		} `json:"models"`
+	}
+	if err := json.Unmarshal(body, &listing); err != nil {
+		return nil, fmt.Errorf("unexpected models listing from %s: %v", p.Name, err)
+	}
+	models := []string{}
+	for _, m := range listing.Data {
+		models = append(models, m.ID)
+	}
+	for _, m := range listing.Models {
+		models = append(models, strings.TrimPrefix(m.Name, "models/"))
+	}
+	sort.Strings(models)
+	return models, nil
+}
+
+// collectModels asks each provider, or only the one named, in parallel
+func collectModels(only string) ([]ProviderModels, error) {
+	specs := providerSpecs
+	if only != "" {
+		spec, exists := findProviderSpec(only)
+		if !exists {
+			return nil, fmt.Errorf("Unknown provider '%s'", only)
+		}
+		specs = []providerSpec{spec}
+	}
+
+	results := make([]ProviderModels, len(specs))
+	var wg sync.WaitGroup
+	for i, spec := range specs {
+		wg.Add(1)
+		go func(i int, spec providerSpec) {
+			defer wg.Done()
+			result := ProviderModels{Name: spec.Name, Models: []string{}}
+			if models, err := spec.listModels(); err != nil {
+				result.Error = err.Error()
+			} else {
+				result.Models = models
+			}
+			results[i] = result
+		}(i, spec)
+	}
+	wg.Wait()
+	return results, nil
+}
+
+// Models used when a request names a provider but no model
+var defaultProviderModels = map[string]string{
+	"openai": "gpt-4o",
+	"google": "gemini-2.0-flash",
+	"local":  "llama3.2",
+}
+
+// ProviderSelection is the provider and model a CLI chose for a request
+type ProviderSelection struct {
+	Name    string `json:"name,omitempty"`
+	Model   string `json:"model,omitempty"`
+	BaseURL string `json:"base_url,omitempty"`
+}
+
+// AIClient is a provider a swim can talk to. Replies come back in Claude's
+// shape whichever provider answered
+type AIClient interface {
+	Send(messages []Message, systemPrompt string, agentName string) (*AnthropicResponse, error)
+	SendWithoutTools(messages []Message, systemPrompt string, agentName string) (*AnthropicResponse, error)
+	Provider() string
+	Configured() bool
+}
+
+// NewAIClient builds the client for the provider a request chose; no
+// selection means Claude with the agent's model
+func NewAIClient(selection *ProviderSelection) (AIClient, error) {
+	if selection == nil {
+		selection = &ProviderSelection{}
+	}
+	name := selection.Name
+	if name == "" {
+		name = "anthropic"
+	}
+	spec, exists := findProviderSpec(name)
+	if !exists {
+		return nil, fmt.Errorf("Unknown provider '%s'", name)
+	}
+
+	if name == "anthropic" {
+		client := NewAnthropicClient()
+		client.model = selection.Model
+		// A key from agents.json ("opus") stands for its model ID
+		if agentConfig != nil {
+			if model, known := agentConfig.Models[selection.Model]; known {
+				client.model = model.ID
+			}
+		}
+		return client, nil
+	}
+
+	model := selection.Model
+	if model == "" {
+		model = defaultProviderModels[name]
+	}
+	return newProviderClient(spec, selection.BaseURL, model), nil
+}
+
+// newProviderClient builds a client for a provider other than Claude;
+// baseURL, when given, replaces the provider's own
+func newProviderClient(spec providerSpec, baseURL, model string) *providerClient {
+	if baseURL == "" {
+		baseURL = spec.baseURL()
+	}
+	return &providerClient{
+		spec:       spec,
+		apiKey:     spec.apiKey(),
+		baseURL:    strings.TrimSuffix(baseURL, "/"),
+		model:      model,
+		httpClient: &http.Client{Timeout: 300 * time.Second},
+	}
+}
+
+// missingKeyError is the swim error for a provider without an API key
+func missingKeyError(provider string) string {
+	spec, _ := findProviderSpec(provider)
+	return fmt.Sprintf("API_KEY_ERROR: No API key found for %s. Please set %s and restart the daemon",
+		provider, strings.Join(spec.KeyVars, " or "))
+}
+
+// providerClient talks to the providers other than Claude. Port 42's tools
+// are Claude tool definitions, so these providers answer with text only
+type providerClient struct {
+	spec       providerSpec
+	apiKey     string
+	baseURL    string
+	model      string
+	httpClient *http.Client
+}
+
+// Provider names the backend for usage records
+func (c *providerClient) Provider() string {
+	return c.spec.Name
+}
+
+// Configured reports whether the client has what it needs to make a call
+func (c *providerClient) Configured() bool {
+	return len(c.spec.KeyVars) == 0 || c.apiKey != ""
+}
+
+// Send answers without tools; see providerClient
+func (c *providerClient) Send(messages []Message, systemPrompt string, agentName string) (*AnthropicResponse, error) {
+	return c.SendWithoutTools(messages, systemPrompt, agentName)
+}
+
+// SendWithoutTools sends the conversation to the provider
+func (c *providerClient) SendWithoutTools(messages []Message, systemPrompt string, agentName string) (*AnthropicResponse, error) {
+	temperature := 0.7
+	if modelDef, err := GetModelForAgent(agentName); err == nil {
+		temperature = modelDef.Temperature
+	}
+	log.Printf("🔍 Sending to %s: model=%s, messages=%d", c.spec.Name, c.model, len(messages))
+
+	if c.spec.Name == "google" {
+		return c.sendGemini(messages, systemPrompt, temperature)
+	}
+	return c.sendChatCompletions(messages, systemPrompt, temperature)
+}
+
+// sendChatCompletions uses the OpenAI chat API, which Ollama also serves under /v1
+func (c *providerClient) sendChatCompletions(messages []Message, systemPrompt string, temperature float64) (*AnthropicResponse, error) {
+	type chatMessage struct {
+		Role    string `json:"role"`
+		Content string `json:"content"`
+	}
+	chat := []chatMessage{}
+	if systemPrompt != "" {
+		chat = append(chat, chatMessage{Role: "system", Content: systemPrompt})
+	}
+	for _, msg := range messages {
+		if msg.Role == "system" {
+			continue
+		}
+		chat = append(chat, chatMessage{Role: msg.Role, Content: msg.Content})
+	}
+
+	body := map[string]interface{}{
+		"model":       c.model,
+		"messages":    chat,
+		"max_tokens":  GetResponseConfig().MaxTokens,
+		"temperature": temperature,
+	}
+	url := c.baseURL + "/chat/completions"
+	if c.spec.Name == "local" {
+		url = c.baseURL + "/v1/chat/completions"
+	}
+	header := http.Header{}
+	if c.apiKey != "" {
+		header.Set("Authorization", "Bearer "+c.apiKey)
+	}
+
+	var reply struct {
+		Model   string `json:"model"`
+		Choices []struct {
+			Message struct {
+				Content string `json:"content"`
+			} `json:"message"`
+		} `json:"choices"`
+		Usage struct {
+			PromptTokens     int `json:"prompt_tokens"`
+			CompletionTokens int `json:"completion_tokens"`
+		} `json:"usage"`
+	}
+	if err := c.post(url, header, body, &reply); err != nil {
+		return nil, err
+	}
+	if len(reply.Choices) == 0 {
+		return nil, fmt.Errorf("%s returned no choices", c.spec.Name)
+	}
+
+	return &AnthropicResponse{
+		Content:    []AnthropicContent{{Type: "text", Text: reply.Choices[0].Message.Content}},
+		StopReason: "end_turn",
+		Model:      reply.Model,
+		Usage: AnthropicUsage{
+			InputTokens:  reply.Usage.PromptTokens,
+			OutputTokens: reply.Usage.CompletionTokens,
+		},
+	}, nil
+}
+
+// sendGemini uses Google's generateContent API
+func (c *providerClient) sendGemini(messages []Message, systemPrompt string, temperature float64) (*AnthropicResponse, error) {
+	type part struct {
+		Text string `json:"text"`
+	}
+	type content struct {
+		Role  string `json:"role,omitempty"`
+		Parts []part `json:"parts"`
+	}
+	contents := []content{}
+	for _, msg := range messages {
+		role := msg.Role
+		switch role {
+		case "system":
+			continue
+		case "assistant":
+			role = "model"
+		}
+		contents = append(contents, content{Role: role, Parts: []part{{Text: msg.Content}}})
+	}
+
+	body := map[string]interface{}{
+		"contents": contents,
+		"generationConfig": map[string]interface{}{
+			"maxOutputTokens": GetResponseConfig().MaxTokens,
+			"temperature":     temperature,
+		},
+	}
+	if systemPrompt != "" {
+		body["systemInstruction"] = content{Parts: []part{{Text: systemPrompt}}}
+	}
+	header := http.Header{}
+	header.Set("x-goog-api-key", c.apiKey)
+
+	var reply struct {
+		ModelVersion string `json:"modelVersion"`
+		Candidates   []struct {
+			Content content `json:"content"`
+		} `json:"candidates"`
+		UsageMetadata struct {
+			PromptTokenCount     int `json:"promptTokenCount"`
+			CandidatesTokenCount int `json:"candidatesTokenCount"`
+		} `json:"usageMetadata"`
+	}
+	url := fmt.Sprintf("%s/models/%s:generateContent", c.baseURL, c.model)
+	if err := c.post(url, header, body, &reply); err != nil {
+		return nil, err
+	}
+	if len(reply.Candidates) == 0 {
+		return nil, fmt.Errorf("google returned no candidates")
+	}
+
+	var text strings.Builder
+	for _, p := range reply.Candidates[0].Content.Parts {
+		text.WriteString(p.Text)
+	}
+	model := reply.ModelVersion
+	if model == "" {
+		model = c.model
+	}
+	return &AnthropicResponse{
+		Content:    []AnthropicContent{{Type: "text", Text: text.String()}},
+		StopReason: "end_turn",
+		Model:      model,
+		Usage: AnthropicUsage{
+			InputTokens:  reply.UsageMetadata.PromptTokenCount,
+			OutputTokens: reply.UsageMetadata.CandidatesTokenCount,
+		},
+	}, nil
+}
+
+// post sends body as JSON and decodes a 200 reply into out. Other statuses
+// become errors carrying the code, which the CLI reads to spot rate limits
+func (c *providerClient) post(url string, header http.Header, body interface{}, out interface{}) error {
+	data, err := json.Marshal(body)
+	if err != nil {
+		return err
+	}
+	httpReq, err := http.NewRequest("POST", url, bytes.NewBuffer(data))
+	if err != nil {
+		return err
+	}
+	httpReq.Header = header
+	httpReq.Header.Set("Content-Type", "application/json")
+
+	start := time.Now()
+	resp, err := c.httpClient.Do(httpReq)
+	if err != nil {
+		return fmt.Errorf("%s network error: %v", c.spec.Name, err)
+	}
+	defer resp.Body.Close()
+	log.Printf("✅ %s responded in %v with status %d", c.spec.Name, time.Since(start), resp.StatusCode)
+
+	reply, err := io.ReadAll(resp.Body)
+	if err != nil {
+		return err
+	}
+	if resp.StatusCode != http.StatusOK {
+		return fmt.Errorf("%s API returned %d: %s", c.spec.Name, resp.StatusCode, strings.TrimSpace(string(reply)))
+	}
+	if err := json.Unmarshal(reply, out); err != nil {
+		return fmt.Errorf("failed to parse %s response: %v", c.spec.Name, err)
+	}
+	return nil
+}
+
+// ProviderInfo describes one provider for list_providers
+type ProviderInfo struct {
+	Name         string `json:"name"`
+	Configured   bool   `json:"configured"`
+	DefaultModel string `json:"default_model,omitempty"`
+	BaseURL      string `json:"base_url,omitempty"`
+}
+
+// listProviders reports each provider the factory can build and whether it's usable
+func listProviders() []ProviderInfo {
+	providers := make([]ProviderInfo, 0, len(providerSpecs))
+	for _, spec := range providerSpecs {
+		info := ProviderInfo{
+			Name:         spec.Name,
+			Configured:   len(spec.KeyVars) == 0 || spec.apiKey() != "",
+			DefaultModel: defaultProviderModels[spec.Name],
+			BaseURL:      spec.baseURL(),
+		}
+		// Claude's model comes from the agent's entry in agents.json
+		if spec.Name == "anthropic" && agentConfig != nil {
+			if model, exists := agentConfig.Models[agentConfig.DefaultModel]; exists {
+				info.DefaultModel = model.ID
+			}
+		}
+		providers = append(providers, info)
+	}
+	return providers
+}
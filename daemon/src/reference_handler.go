@@ -117,4 +117,42 @@ func (rh *ReferenceHandler) FormatForSwim(resolvedText string) string {
 func (rh *ReferenceHandler) FormatForDeclare(resolvedText string) string {
 	// For declare mode, we store the raw resolved text
 	return resolvedText
-}
\ No newline at end of file
+}
+
+// ReferencePreview is one reference as resolve_references reports it
+type ReferencePreview struct {
+	Type      string `json:"type"`
+	Target    string `json:"target"`
+	Content   string `json:"content,omitempty"`
+	Size      int    `json:"size,omitempty"`
+	Truncated bool   `json:"truncated"`
+	Error     string `json:"error,omitempty"`
+}
+
+// PreviewReferences resolves references and reports what each one would
+// inject into a prompt, with the same limits swim and declare apply
+func (rh *ReferenceHandler) PreviewReferences(references []Reference) ([]ReferencePreview, error) {
+	result := rh.ResolveReferences(references, "preview")
+	// "no context resolved" still has per-reference errors worth showing
+	if result.Contexts == nil && result.Error != nil {
+		return nil, result.Error
+	}
+
+	injected := resolution.InjectedContent(result.Contexts)
+	previews := make([]ReferencePreview, 0, len(result.Contexts))
+	for i, ctx := range result.Contexts {
+		preview := ReferencePreview{
+			Type:   ctx.Type,
+			Target: ctx.Target,
+		}
+		if !ctx.Success {
+			preview.Error = ctx.Error
+		} else {
+			preview.Content = injected[i]
+			preview.Size = len(ctx.Content)
+			preview.Truncated = injected[i] != ctx.Content
+		}
+		previews = append(previews, preview)
+	}
+	return previews, nil
+}
@@ -145,57 +145,76 @@ func (s *service) resolveAll(references []Reference) []*ResolvedContext {
 	return results
 }
 
-// formatForAI formats resolved contexts for AI consumption
-func (s *service) formatForAI(contexts []*ResolvedContext) string {
-	var parts []string
-	
-	// Only include successful resolutions
-	var successful []*ResolvedContext
-	for _, ctx := range contexts {
-		if ctx.Success && len(ctx.Content) > 0 {
-			successful = append(successful, ctx)
-		}
-	}
-	
-	if len(successful) == 0 {
-		return ""
-	}
-	
-	parts = append(parts, "CONTEXTUAL INFORMATION:")
-	
-	// Apply simple size limiting (8KB total)
+// Limits on the reference content that reaches the prompt
+const (
+	maxContextSize      = 2000
+	maxTotalContextSize = 8 * 1024
+)
+
+// InjectedContent applies formatForAI's size limits, returning the content
+// each context contributes: "" for failures and for contexts past the limit
+func InjectedContent(contexts []*ResolvedContext) []string {
+	injected := make([]string, len(contexts))
 	totalSize := 0
-	maxSize := 8 * 1024
-	
-	for _, ctx := range successful {
+
+	for i, ctx := range contexts {
+		if !ctx.Success || len(ctx.Content) == 0 {
+			continue
+		}
 		content := ctx.Content
-		
+
 		// Limit individual context size
-		if len(content) > 2000 {
-			content = content[:2000] + "\n[Content truncated for size]"
+		if len(content) > maxContextSize {
+			content = content[:maxContextSize] + "\n[Content truncated for size]"
 		}
-		
-		if totalSize+len(content) > maxSize {
-			parts = append(parts, "\n[Additional references omitted due to size limit]")
+
+		blockSize := len(contextBlock(ctx, content))
+		if totalSize+blockSize > maxTotalContextSize {
 			break
 		}
-		
-		contextBlock := fmt.Sprintf("\n%s Reference (%s):\n%s\n", 
-			strings.Title(ctx.Type), ctx.Target, content)
-		
-		parts = append(parts, contextBlock)
-		totalSize += len(contextBlock)
+		injected[i] = content
+		totalSize += blockSize
 	}
-	
+
+	return injected
+}
+
+// contextBlock is one reference as it appears in the prompt
+func contextBlock(ctx *ResolvedContext, content string) string {
+	return fmt.Sprintf("\n%s Reference (%s):\n%s\n",
+		strings.Title(ctx.Type), ctx.Target, content)
+}
+
+// formatForAI formats resolved contexts for AI consumption
+func (s *service) formatForAI(contexts []*ResolvedContext) string {
+	parts := []string{"CONTEXTUAL INFORMATION:"}
+	successful, omitted := 0, false
+
+	for i, content := range InjectedContent(contexts) {
+		ctx := contexts[i]
+		if !ctx.Success || len(ctx.Content) == 0 {
+			continue
+		}
+		successful++
+		if content == "" {
+			omitted = true
+			continue
+		}
+		parts = append(parts, contextBlock(ctx, content))
+	}
+
 	if len(parts) == 1 {
 		return "" // Only header
 	}
-	
+	if omitted {
+		parts = append(parts, "\n[Additional references omitted due to size limit]")
+	}
+
 	parts = append(parts, "\nUse this contextual information to generate more relevant tools.\n")
-	
+
 	result := strings.Join(parts, "")
-	log.Printf("✨ AI context formatted: %d chars from %d successful references", 
-		len(result), len(successful))
-	
+	log.Printf("✨ AI context formatted: %d chars from %d successful references",
+		len(result), successful)
+
 	return result
 }
\ No newline at end of file
@@ -31,6 +31,9 @@ type Daemon struct {
 	validator       *validation.RequestValidator // Step 5: Request validation
 	referenceHandler *ReferenceHandler // Common reference resolution logic
 	contextCollector *ContextCollector // Step 2: Context tracking and suggestions
+	jobs            *JobManager       // Detached AI requests running in the background
+	latency         *LatencyTracker   // Recent request durations for the health report
+	metrics         *RequestMetrics   // Request counts and durations for 'port42 metrics'
 }
 
 // Session represents an active swim session
@@ -43,14 +46,19 @@ type Session struct {
 	Messages         []Message    `json:"messages"`
 	CommandGenerated *CommandSpec `json:"command_generated,omitempty"`
 	IdleTimeout      time.Duration `json:"idle_timeout"`
+	Name             string       `json:"name,omitempty"`
+	ForkedFrom       *ForkOrigin  `json:"forked_from,omitempty"`
+	MergedFrom       []string     `json:"merged_from,omitempty"`
+	Metadata         map[string]interface{} `json:"metadata,omitempty"`
 	mu               sync.Mutex
 }
 
 // Message represents a conversation message
 type Message struct {
-	Role      string    `json:"role"`      // "user" or "assistant"
-	Content   string    `json:"content"`
-	Timestamp time.Time `json:"timestamp"`
+	Role          string    `json:"role"`      // "user" or "assistant"
+	Content       string    `json:"content"`
+	Timestamp     time.Time `json:"timestamp"`
+	SourceSession string    `json:"source_session,omitempty"` // in merged sessions
 }
 
 
@@ -95,6 +103,9 @@ func NewDaemon(listener net.Listener, port string) *Daemon {
 		shutdownCh: make(chan struct{}),
 		storage:    storage,
 		baseDir:    baseDir,
+		jobs:       NewJobManager(),
+		latency:    &LatencyTracker{},
+		metrics:    NewRequestMetrics(),
 		config: Config{
 			Port:         port,
 			AIBackend:    "http://localhost:3000/api/ai", // Default, can be overridden
@@ -105,6 +116,21 @@ func NewDaemon(listener net.Listener, port string) *Daemon {
 		},
 	}
 	
+	// Custom agents registered by CLIs survive daemon restarts
+	agentRegistry = LoadAgentRegistry(baseDir)
+	
+	// Rules added with 'port42 rules add'
+	userRules = LoadUserRules(baseDir)
+	
+	// Token counts behind 'port42 usage'
+	usageLedger = NewUsageLedger(baseDir)
+	
+	// Commands reported by the shell hook and 'port42 run'
+	commandLog = NewCommandLog(baseDir)
+	
+	// What users did with context suggestions
+	suggestionFeedback = LoadSuggestionFeedback(baseDir)
+	
 	// Initialize Context Collector FIRST (before Reality Compiler needs it)
 	log.Printf("📊 Initializing Context Collector...")
 	daemon.contextCollector = NewContextCollector(daemon)
@@ -213,8 +239,15 @@ func (d *Daemon) handleConnection(conn net.Conn) {
 		log.Printf("◊ Request [%s] type: %s", req.ID, req.Type)
 	}
 	
+	// Handlers may stream lines ahead of the response on the same connection
+	req.emit = encoder.Encode
+	
 	// Process request
+	started := time.Now()
 	resp := d.handleRequest(req)
+	elapsed := time.Since(started)
+	d.latency.Record(elapsed)
+	d.metrics.Observe(req.Type, resp.Success, elapsed)
 	
 	// Debug: Check response size (skip for context)
 	var respJSON []byte
@@ -278,151 +311,894 @@ func (d *Daemon) handleRequest(req Request) Response {
 		// Note: We don't track internal operations like list_path, read_path, get_metadata, status, etc.
 		// They will just fall through without tracking
 		}
-		
-		if commandName != "" {
-			d.contextCollector.TrackCommand(commandName, 0)
+		
+		if commandName != "" {
+			d.contextCollector.TrackCommand(commandName, 0)
+			userRules.Fire("command_run", commandName, "")
+		}
+	}
+	
+	// Detached AI requests answer at once with a job to poll
+	if isDetached(req) {
+		req.emit = nil // The connection closes long before the job is done
+		job := d.jobs.Submit(req, d.handleRequestInternal)
+		log.Printf("🚀 Detached [%s] as job %s", req.ID, job.ID)
+		resp := NewResponse(req.ID, true)
+		resp.SetData(map[string]interface{}{
+			"job_id": job.ID,
+			"status": job.Status,
+		})
+		return resp
+	}
+	
+	// Now handle the request
+	return d.handleRequestInternal(req)
+}
+
+// handleRequestInternal actually processes the request
+func (d *Daemon) handleRequestInternal(req Request) Response {
+	switch req.Type {
+	case RequestHello:
+		return d.handleHello(req)
+	case RequestStatus:
+		return d.handleStatus(req)
+	case RequestSwim:
+		return d.handleSwim(req)
+	case RequestList:
+		return d.handleList(req)
+	case RequestMemory:
+		return d.handleMemory(req)
+	case RequestWatch:
+		return d.handleWatch(req)
+	case RequestEnd:
+		return d.handleEnd(req)
+	case "ping":
+		// Simple ping handler for connection checks
+		return NewResponse(req.ID, true)
+	case "store_path":
+		return d.handleStorePath(req)
+	case "update_path":
+		return d.handleUpdatePath(req)
+	case "delete_path":
+		return d.handleDeletePath(req)
+	case "trash_path":
+		return d.handleTrashPath(req)
+	case "restore_path":
+		return d.handleRestorePath(req)
+	case "copy_path":
+		return d.handleCopyPath(req)
+	case "move_path":
+		return d.handleMovePath(req)
+	case "import_object":
+		return d.handleImportObject(req)
+	case "list_versions":
+		return d.handleListVersions(req)
+	case "rollback_version":
+		return d.handleRollbackVersion(req)
+	case "add_note":
+		return d.handleAddNote(req)
+	case "list_notes":
+		return d.handleListNotes(req)
+	case "create_memory":
+		return d.handleCreateMemory(req)
+	case "fork_session":
+		return d.handleForkSession(req)
+	case "merge_sessions":
+		return d.handleMergeSessions(req)
+	case "rename_session":
+		return d.handleRenameSession(req)
+	case "list_path":
+		return d.handleListPath(req)
+	case "read_path":
+		return d.handleReadPath(req)
+	case "get_metadata":
+		return d.handleGetMetadata(req)
+	case "search":
+		return d.handleSearch(req)
+	case "get_last_session":
+		return d.handleGetLastSession(req)
+	case "declare_relation":
+		return d.handleDeclareRelation(req)
+	case "get_relation":
+		return d.handleGetRelation(req)
+	case "list_relations":
+		return d.handleListRelations(req)
+	case "delete_relation":
+		return d.handleDeleteRelation(req)
+	case "context":
+		return d.handleGetContext(req)
+	case "register_agent":
+		return d.handleRegisterAgent(req)
+	case "unregister_agent":
+		return d.handleUnregisterAgent(req)
+	case "list_agents":
+		return d.handleListAgents(req)
+	case "list_jobs":
+		return d.handleListJobs(req)
+	case "job_status":
+		return d.handleJobStatus(req)
+	case "cancel_job":
+		return d.handleCancelJob(req)
+	case "list_rules":
+		return d.handleListUserRules(req)
+	case "usage":
+		return d.handleUsage(req)
+	case "metrics":
+		return d.handleMetrics(req)
+	case "models":
+		return d.handleModels(req)
+	case "list_providers":
+		return d.handleListProviders(req)
+	case "embed":
+		return d.handleEmbed(req)
+	case "resolve_references":
+		return d.handleResolveReferences(req)
+	case "track_command":
+		return d.handleTrackCommand(req)
+	case "command_stats":
+		return d.handleCommandStats(req)
+	case "suggestion_feedback":
+		return d.handleSuggestionFeedback(req)
+	case "session_metadata":
+		return d.handleSessionMetadata(req)
+	case "add_rule":
+		return d.handleAddUserRule(req)
+	case "update_rule":
+		return d.handleUpdateUserRule(req)
+	case "delete_rule":
+		return d.handleDeleteUserRule(req)
+	default:
+		resp := NewResponse(req.ID, false)
+		resp.SetError(fmt.Sprintf("Unknown request type: %s", req.Type))
+		return resp
+	}
+}
+
+// Virtual filesystem handlers - thin wrappers that delegate to storage
+
+// handleStorePath stores content at a virtual path
+func (d *Daemon) handleStorePath(req Request) Response {
+	var payload struct {
+		Path     string                 `json:"path"`
+		Content  string                 `json:"content"` // base64 encoded
+		Metadata map[string]interface{} `json:"metadata,omitempty"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Decode content
+	content, err := base64.StdEncoding.DecodeString(payload.Content)
+	if err != nil {
+		return NewErrorResponse(req.ID, "Failed to decode content: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleStorePath(payload.Path, content, payload.Metadata)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleUpdatePath updates content at a virtual path
+func (d *Daemon) handleUpdatePath(req Request) Response {
+	var payload struct {
+		Path            string                 `json:"path"`
+		Content         string                 `json:"content,omitempty"` // base64, optional
+		MetadataUpdates map[string]interface{} `json:"metadata_updates,omitempty"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Decode content if provided
+	var content []byte
+	if payload.Content != "" {
+		var err error
+		content, err = base64.StdEncoding.DecodeString(payload.Content)
+		if err != nil {
+			return NewErrorResponse(req.ID, "Failed to decode content: "+err.Error())
+		}
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleUpdatePath(payload.Path, content, payload.MetadataUpdates)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleDeletePath removes a virtual path
+func (d *Daemon) handleDeletePath(req Request) Response {
+	var payload struct {
+		Path string `json:"path"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleDeletePath(payload.Path)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleTrashPath moves a virtual path under /trash
+func (d *Daemon) handleTrashPath(req Request) Response {
+	var payload struct {
+		Path string `json:"path"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleTrashPath(payload.Path)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleRestorePath brings a trashed path back
+func (d *Daemon) handleRestorePath(req Request) Response {
+	var payload struct {
+		Path        string `json:"path"`
+		Destination string `json:"destination,omitempty"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleRestorePath(payload.Path, payload.Destination)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleCopyPath makes an object reachable from another path as well
+func (d *Daemon) handleCopyPath(req Request) Response {
+	var payload struct {
+		Source      string `json:"source"`
+		Destination string `json:"destination"`
+		Overwrite   bool   `json:"overwrite"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleCopyPath(payload.Source, payload.Destination, payload.Overwrite)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleMovePath moves an object to another path
+func (d *Daemon) handleMovePath(req Request) Response {
+	var payload struct {
+		Source      string `json:"source"`
+		Destination string `json:"destination"`
+		Overwrite   bool   `json:"overwrite"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleMovePath(payload.Source, payload.Destination, payload.Overwrite)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleImportObject stores an object from an export archive
+func (d *Daemon) handleImportObject(req Request) Response {
+	var payload struct {
+		Path       string                 `json:"path"`
+		Type       string                 `json:"type"`
+		Content    string                 `json:"content"` // base64 encoded
+		Metadata   map[string]interface{} `json:"metadata,omitempty"`
+		OnConflict string                 `json:"on_conflict"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	content, err := base64.StdEncoding.DecodeString(payload.Content)
+	if err != nil {
+		return NewErrorResponse(req.ID, "Invalid content encoding: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleImportObject(payload.Path, content, payload.Metadata, payload.OnConflict)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleListVersions lists the contents a path has had
+func (d *Daemon) handleListVersions(req Request) Response {
+	var payload struct {
+		Path string `json:"path"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleListVersions(payload.Path)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleRollbackVersion makes an earlier version of a path current again
+func (d *Daemon) handleRollbackVersion(req Request) Response {
+	var payload struct {
+		Path    string `json:"path"`
+		Version int    `json:"version"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleRollbackVersion(payload.Path, payload.Version)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+	log.Printf("⏪ Rolled %s back to version %d", payload.Path, payload.Version)
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleAddNote attaches a note to a path's object
+func (d *Daemon) handleAddNote(req Request) Response {
+	var payload struct {
+		Path string `json:"path"`
+		Text string `json:"text"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleAddNote(payload.Path, payload.Text)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleListNotes returns the notes on a path's object
+func (d *Daemon) handleListNotes(req Request) Response {
+	var payload struct {
+		Path string `json:"path"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	// Delegate to storage
+	result, err := d.storage.HandleListNotes(payload.Path)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(result)
+	return resp
+}
+
+// handleRegisterAgent records a custom agent so swims can use its persona
+func (d *Daemon) handleRegisterAgent(req Request) Response {
+	var agent RegisteredAgent
+	if err := json.Unmarshal(req.Payload, &agent); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	if err := agentRegistry.Register(agent); err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+	log.Printf("🤖 Registered agent %s", agent.Name)
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"name":       agent.Name,
+		"registered": true,
+	})
+	return resp
+}
+
+// handleUnregisterAgent forgets a custom agent
+func (d *Daemon) handleUnregisterAgent(req Request) Response {
+	var payload struct {
+		Name string `json:"name"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	removed, err := agentRegistry.Unregister(payload.Name)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+	if !removed {
+		return NewErrorResponse(req.ID, fmt.Sprintf("Agent not registered: %s", payload.Name))
+	}
+	log.Printf("🤖 Unregistered agent %s", payload.Name)
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"name":    payload.Name,
+		"removed": true,
+	})
+	return resp
+}
+
+// handleListAgents returns the registered custom agents
+func (d *Daemon) handleListAgents(req Request) Response {
+	agents := []map[string]interface{}{}
+	for _, agent := range agentRegistry.List() {
+		agents = append(agents, map[string]interface{}{
+			"name":        agent.Name,
+			"description": agent.Description,
+		})
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"agents": agents,
+	})
+	return resp
+}
+
+// handleListJobs returns the background jobs the daemon knows about
+func (d *Daemon) handleListJobs(req Request) Response {
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"jobs": d.jobs.List(),
+	})
+	return resp
+}
+
+// handleJobStatus returns one background job, with its result once finished
+func (d *Daemon) handleJobStatus(req Request) Response {
+	var payload struct {
+		JobID string `json:"job_id"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	job, exists := d.jobs.Get(payload.JobID)
+	if !exists {
+		return NewErrorResponse(req.ID, fmt.Sprintf("Job not found: %s", payload.JobID))
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"job": job,
+	})
+	return resp
+}
+
+// handleCancelJob stops tracking a background job
+func (d *Daemon) handleCancelJob(req Request) Response {
+	var payload struct {
+		JobID string `json:"job_id"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	if err := d.jobs.Cancel(payload.JobID); err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+	log.Printf("🛑 Cancelled job %s", payload.JobID)
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"job_id": payload.JobID,
+		"status": "cancelled",
+	})
+	return resp
+}
+
+// handleListUserRules returns the rules added from the CLI
+func (d *Daemon) handleListUserRules(req Request) Response {
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"rules": userRules.List(),
+	})
+	return resp
+}
+
+// handleAddUserRule saves a new rule and returns it with its ID
+func (d *Daemon) handleAddUserRule(req Request) Response {
+	var payload struct {
+		Rule UserRule `json:"rule"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	rule, err := userRules.Add(payload.Rule)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+	log.Printf("📋 Added user rule %s [%s]", rule.Name, rule.ID)
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"rule": rule,
+	})
+	return resp
+}
+
+// handleUpdateUserRule switches a rule on or off
+func (d *Daemon) handleUpdateUserRule(req Request) Response {
+	var payload struct {
+		RuleID  string `json:"rule_id"`
+		Enabled bool   `json:"enabled"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	rule, err := userRules.SetEnabled(payload.RuleID, payload.Enabled)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"rule": rule,
+	})
+	return resp
+}
+
+// handleDeleteUserRule removes a rule
+func (d *Daemon) handleDeleteUserRule(req Request) Response {
+	var payload struct {
+		RuleID string `json:"rule_id"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	if err := userRules.Delete(payload.RuleID); err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+	log.Printf("🗑️ Deleted user rule %s", payload.RuleID)
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"rule_id": payload.RuleID,
+		"deleted": true,
+	})
+	return resp
+}
+
+// handleUsage returns token usage per session and day between two RFC 3339 times
+func (d *Daemon) handleUsage(req Request) Response {
+	var payload struct {
+		Since string `json:"since,omitempty"`
+		Until string `json:"until,omitempty"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	var since, until time.Time
+	var err error
+	if payload.Since != "" {
+		if since, err = time.Parse(time.RFC3339, payload.Since); err != nil {
+			return NewErrorResponse(req.ID, "Invalid since: "+err.Error())
+		}
+	}
+	if payload.Until != "" {
+		if until, err = time.Parse(time.RFC3339, payload.Until); err != nil {
+			return NewErrorResponse(req.ID, "Invalid until: "+err.Error())
 		}
 	}
-	
-	// Now handle the request
-	return d.handleRequestInternal(req)
+
+	records, err := usageLedger.Records(since, until)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"records": records,
+	})
+	return resp
 }
 
-// handleRequestInternal actually processes the request
-func (d *Daemon) handleRequestInternal(req Request) Response {
-	switch req.Type {
-	case RequestStatus:
-		return d.handleStatus(req)
-	case RequestSwim:
-		return d.handleSwim(req)
-	case RequestList:
-		return d.handleList(req)
-	case RequestMemory:
-		return d.handleMemory(req)
-	case RequestWatch:
-		return d.handleWatch(req)
-	case RequestEnd:
-		return d.handleEnd(req)
-	case "ping":
-		// Simple ping handler for connection checks
-		return NewResponse(req.ID, true)
-	case "store_path":
-		return d.handleStorePath(req)
-	case "update_path":
-		return d.handleUpdatePath(req)
-	case "delete_path":
-		return d.handleDeletePath(req)
-	case "create_memory":
-		return d.handleCreateMemory(req)
-	case "list_path":
-		return d.handleListPath(req)
-	case "read_path":
-		return d.handleReadPath(req)
-	case "get_metadata":
-		return d.handleGetMetadata(req)
-	case "search":
-		return d.handleSearch(req)
-	case "get_last_session":
-		return d.handleGetLastSession(req)
-	case "declare_relation":
-		return d.handleDeclareRelation(req)
-	case "get_relation":
-		return d.handleGetRelation(req)
-	case "list_relations":
-		return d.handleListRelations(req)
-	case "delete_relation":
-		return d.handleDeleteRelation(req)
-	case "context":
-		return d.handleGetContext(req)
-	default:
-		resp := NewResponse(req.ID, false)
-		resp.SetError(fmt.Sprintf("Unknown request type: %s", req.Type))
-		return resp
+// handleMetrics returns the daemon's metrics for 'port42 metrics'
+func (d *Daemon) handleMetrics(req Request) Response {
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"metrics": d.collectMetrics(),
+	})
+	return resp
+}
+
+// handleModels lists the models each provider offers
+func (d *Daemon) handleModels(req Request) Response {
+	var payload struct {
+		Provider string `json:"provider,omitempty"`
+	}
+
+	if len(req.Payload) > 0 {
+		if err := json.Unmarshal(req.Payload, &payload); err != nil {
+			return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+		}
+	}
+
+	providers, err := collectModels(payload.Provider)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
 	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"providers": providers,
+	})
+	return resp
 }
 
-// Virtual filesystem handlers - thin wrappers that delegate to storage
+// handleListProviders reports the providers swims can be sent to
+func (d *Daemon) handleListProviders(req Request) Response {
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"providers": listProviders(),
+	})
+	return resp
+}
 
-// handleStorePath stores content at a virtual path
-func (d *Daemon) handleStorePath(req Request) Response {
+// handleEmbed returns an embedding vector for each text, from the requested provider
+func (d *Daemon) handleEmbed(req Request) Response {
 	var payload struct {
-		Path     string                 `json:"path"`
-		Content  string                 `json:"content"` // base64 encoded
-		Metadata map[string]interface{} `json:"metadata,omitempty"`
+		Texts []string `json:"texts"`
 	}
 
 	if err := json.Unmarshal(req.Payload, &payload); err != nil {
 		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
 	}
+	if len(payload.Texts) == 0 {
+		return NewErrorResponse(req.ID, "No texts to embed")
+	}
 
-	// Decode content
-	content, err := base64.StdEncoding.DecodeString(payload.Content)
+	model, vectors, err := embedTexts(req.Provider, payload.Texts)
 	if err != nil {
-		return NewErrorResponse(req.ID, "Failed to decode content: "+err.Error())
+		return NewErrorResponse(req.ID, fmt.Sprintf("Failed to embed: %v", err))
 	}
 
-	// Delegate to storage
-	result, err := d.storage.HandleStorePath(payload.Path, content, payload.Metadata)
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"model":   model,
+		"vectors": vectors,
+	})
+	return resp
+}
+
+// handleResolveReferences shows what references would add to a prompt, without generating anything
+func (d *Daemon) handleResolveReferences(req Request) Response {
+	references := req.References
+	if len(references) == 0 && len(req.Payload) > 0 {
+		var payload struct {
+			References []Reference `json:"references"`
+		}
+		if err := json.Unmarshal(req.Payload, &payload); err != nil {
+			return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+		}
+		references = payload.References
+	}
+	if len(references) == 0 {
+		return NewErrorResponse(req.ID, "No references to resolve")
+	}
+	if d.referenceHandler == nil {
+		return NewErrorResponse(req.ID, "Reference resolution not available")
+	}
+
+	previews, err := d.referenceHandler.PreviewReferences(references)
 	if err != nil {
 		return NewErrorResponse(req.ID, err.Error())
 	}
 
 	resp := NewResponse(req.ID, true)
-	resp.SetData(result)
+	resp.SetData(map[string]interface{}{
+		"references": previews,
+	})
 	return resp
 }
 
-// handleUpdatePath updates content at a virtual path
-func (d *Daemon) handleUpdatePath(req Request) Response {
+// handleTrackCommand records a command the user ran outside the daemon
+func (d *Daemon) handleTrackCommand(req Request) Response {
 	var payload struct {
-		Path            string                 `json:"path"`
-		Content         string                 `json:"content,omitempty"` // base64, optional
-		MetadataUpdates map[string]interface{} `json:"metadata_updates,omitempty"`
+		Command    string  `json:"command"`
+		ExitCode   int     `json:"exit_code"`
+		Cwd        string  `json:"cwd,omitempty"`
+		DurationMs *uint64 `json:"duration_ms,omitempty"`
+		Tool       string  `json:"tool,omitempty"`
 	}
 
 	if err := json.Unmarshal(req.Payload, &payload); err != nil {
 		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
 	}
+	command := strings.TrimSpace(payload.Command)
+	if command == "" {
+		return NewErrorResponse(req.ID, "Command is required")
+	}
 
-	// Decode content if provided
-	var content []byte
-	if payload.Content != "" {
-		var err error
-		content, err = base64.StdEncoding.DecodeString(payload.Content)
+	commandLog.Record(CommandEntry{
+		Timestamp:  time.Now(),
+		Command:    command,
+		ExitCode:   payload.ExitCode,
+		Cwd:        payload.Cwd,
+		DurationMs: payload.DurationMs,
+		Tool:       payload.Tool,
+	})
+	if d.contextCollector != nil {
+		d.contextCollector.TrackCommand(command, payload.ExitCode)
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"tracked": true,
+	})
+	return resp
+}
+
+// handleCommandStats returns the most frequently run tracked commands
+func (d *Daemon) handleCommandStats(req Request) Response {
+	var payload struct {
+		Since string `json:"since,omitempty"` // RFC 3339
+		Limit int    `json:"limit,omitempty"`
+	}
+
+	if len(req.Payload) > 0 {
+		if err := json.Unmarshal(req.Payload, &payload); err != nil {
+			return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+		}
+	}
+
+	var since time.Time
+	if payload.Since != "" {
+		parsed, err := time.Parse(time.RFC3339, payload.Since)
 		if err != nil {
-			return NewErrorResponse(req.ID, "Failed to decode content: "+err.Error())
+			return NewErrorResponse(req.ID, "Invalid since: "+err.Error())
 		}
+		since = parsed
 	}
 
-	// Delegate to storage
-	result, err := d.storage.HandleUpdatePath(payload.Path, content, payload.MetadataUpdates)
+	commands, err := commandLog.TopCommands(since, payload.Limit)
 	if err != nil {
 		return NewErrorResponse(req.ID, err.Error())
 	}
 
 	resp := NewResponse(req.ID, true)
-	resp.SetData(result)
+	resp.SetData(map[string]interface{}{
+		"commands": commands,
+	})
 	return resp
 }
 
-// handleDeletePath removes a virtual path
-func (d *Daemon) handleDeletePath(req Request) Response {
+// handleSuggestionFeedback records whether a context suggestion was taken up
+func (d *Daemon) handleSuggestionFeedback(req Request) Response {
 	var payload struct {
-		Path string `json:"path"`
+		Command  string `json:"command"`
+		Reason   string `json:"reason"`
+		Accepted bool   `json:"accepted"`
+		ExitCode *int   `json:"exit_code,omitempty"`
 	}
 
 	if err := json.Unmarshal(req.Payload, &payload); err != nil {
 		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
 	}
+	if payload.Reason == "" {
+		return NewErrorResponse(req.ID, "Suggestion reason is required")
+	}
 
-	// Delegate to storage
-	result, err := d.storage.HandleDeletePath(payload.Path)
+	if err := suggestionFeedback.Record(payload.Reason, payload.Accepted, payload.ExitCode); err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+	log.Printf("💡 Suggestion feedback: %s (accepted: %v)", payload.Command, payload.Accepted)
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"recorded": true,
+	})
+	return resp
+}
+
+// handleSessionMetadata records changes made during a session, such as a provider switch
+func (d *Daemon) handleSessionMetadata(req Request) Response {
+	var payload struct {
+		SessionID string                 `json:"session_id"`
+		Metadata  map[string]interface{} `json:"metadata"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+	if payload.SessionID == "" {
+		return NewErrorResponse(req.ID, "Session ID is required")
+	}
+
+	metadata, err := d.updateSessionMetadata(payload.SessionID, payload.Metadata)
 	if err != nil {
 		return NewErrorResponse(req.ID, err.Error())
 	}
 
 	resp := NewResponse(req.ID, true)
-	resp.SetData(result)
+	resp.SetData(map[string]interface{}{
+		"session_id": payload.SessionID,
+		"metadata":   metadata,
+	})
 	return resp
 }
 
@@ -525,7 +1301,8 @@ func (d *Daemon) handleListPath(req Request) Response {
 // handleReadPath reads content from a virtual path
 func (d *Daemon) handleReadPath(req Request) Response {
 	var payload struct {
-		Path string `json:"path"`
+		Path    string `json:"path"`
+		Version int    `json:"version,omitempty"`
 	}
 
 	if err := json.Unmarshal(req.Payload, &payload); err != nil {
@@ -551,8 +1328,19 @@ func (d *Daemon) handleReadPath(req Request) Response {
 		return NewErrorResponse(req.ID, fmt.Sprintf("Path not found: %s", payload.Path))
 	}
 
+	// An earlier version is another object; the metadata stays the path's own
+	contentID := objID
+	version := 0
+	if payload.Version > 0 {
+		versionID, n, err := d.storage.VersionObject(payload.Path, payload.Version)
+		if err != nil {
+			return NewErrorResponse(req.ID, err.Error())
+		}
+		contentID, version = versionID, n
+	}
+
 	// Read content
-	content, err := d.storage.Read(objID)
+	content, err := d.storage.Read(contentID)
 	if err != nil {
 		return NewErrorResponse(req.ID, fmt.Sprintf("Failed to read content: %v", err))
 	}
@@ -582,6 +1370,12 @@ func (d *Daemon) handleReadPath(req Request) Response {
 			"title":       metadata.Title,
 			"description": metadata.Description,
 		}
+		if version == 0 {
+			version = metadata.CurrentVersion()
+		}
+	}
+	if version > 0 {
+		responseData["version"] = version
 	}
 
 	resp := NewResponse(req.ID, true)
@@ -592,7 +1386,8 @@ func (d *Daemon) handleReadPath(req Request) Response {
 // handleGetMetadata retrieves enriched metadata for a virtual path
 func (d *Daemon) handleGetMetadata(req Request) Response {
 	var payload struct {
-		Path string `json:"path"`
+		Path    string `json:"path"`
+		Version int    `json:"version,omitempty"`
 	}
 
 	if err := json.Unmarshal(req.Payload, &payload); err != nil {
@@ -635,8 +1430,19 @@ func (d *Daemon) handleGetMetadata(req Request) Response {
 		}
 	}
 
+	// Sizes and version describe an earlier version when one was asked for
+	contentID := objID
+	version := metadata.CurrentVersion()
+	if payload.Version > 0 {
+		versionID, n, err := d.storage.VersionObject(payload.Path, payload.Version)
+		if err != nil {
+			return NewErrorResponse(req.ID, err.Error())
+		}
+		contentID, version = versionID, n
+	}
+
 	// Get actual content size
-	content, err := d.storage.Read(objID)
+	content, err := d.storage.Read(contentID)
 	actualSize := int64(0)
 	if err == nil {
 		actualSize = int64(len(content))
@@ -653,6 +1459,7 @@ func (d *Daemon) handleGetMetadata(req Request) Response {
 		"modified":  metadata.Modified,
 		"accessed":  metadata.Accessed,
 		"size":      actualSize,
+		"version":   version,
 		
 		// Content info
 		"title":       metadata.Title,
@@ -669,6 +1476,7 @@ func (d *Daemon) handleGetMetadata(req Request) Response {
 		// Relationships
 		"paths":         metadata.Paths,
 		"relationships": metadata.Relationships,
+		"notes":         metadata.Notes,
 		
 		// Computed fields
 		"age_seconds":       time.Since(metadata.Created).Seconds(),
@@ -853,6 +1661,10 @@ func (d *Daemon) getOrCreateSession(sessionID, agent string) *Session {
 				Messages:         persistedSession.Messages,
 				CommandGenerated: nil,
 				IdleTimeout:      30 * time.Minute,
+				Name:             persistedSession.Name,
+				ForkedFrom:       persistedSession.ForkedFrom,
+				MergedFrom:       persistedSession.MergedFrom,
+				Metadata:         persistedSession.Metadata,
 			}
 			
 			// Convert command info if exists
@@ -943,6 +1755,10 @@ func (d *Daemon) loadRecentSessions() {
 				Messages:         ps.Messages,
 				CommandGenerated: nil,
 				IdleTimeout:      30 * time.Minute,
+				Name:             ps.Name,
+				ForkedFrom:       ps.ForkedFrom,
+				MergedFrom:       ps.MergedFrom,
+				Metadata:         ps.Metadata,
 			}
 			
 			// Convert command info if exists
@@ -1083,6 +1899,7 @@ func (d *Daemon) handleStatus(req Request) Response {
 
 	status := StatusData{
 		Status:    "swimming",
+		Version:   Version,
 		Port:      d.config.Port,
 		Sessions:  activeSessions,
 		Uptime:    uptime,
@@ -1091,10 +1908,33 @@ func (d *Daemon) handleStatus(req Request) Response {
 		Rules:     rulesStatus,
 	}
 	
+	// Probing providers takes a while, so only `status --detailed` asks for it
+	var payload struct {
+		Detailed bool `json:"detailed"`
+	}
+	if len(req.Payload) > 0 {
+		json.Unmarshal(req.Payload, &payload)
+	}
+	if payload.Detailed {
+		status.Health = d.collectHealth()
+	}
+	
 	resp.SetData(status)
 	return resp
 }
 
+// handleHello answers the CLI's handshake with this daemon's version and protocol
+func (d *Daemon) handleHello(req Request) Response {
+	resp := NewResponse(req.ID, true)
+	resp.SetData(HelloData{
+		Version:     Version,
+		Protocol:    ProtocolRevision,
+		MinProtocol: MinClientRevision,
+		Features:    []string{FeatureDetach},
+	})
+	return resp
+}
+
 // handleWatch handles watch requests for real-time monitoring
 func (d *Daemon) handleWatch(req Request) Response {
 	// Parse the watch payload
@@ -1227,6 +2067,7 @@ func (d *Daemon) handleMemory(req Request) Response {
 	for _, session := range d.sessions {
 		activeSummaries = append(activeSummaries, SessionSummary{
 			ID:           session.ID,
+			Name:         session.Name,
 			Agent:        session.Agent,
 			CreatedAt:    session.CreatedAt,
 			LastActivity: session.LastActivity,
@@ -1248,6 +2089,7 @@ func (d *Daemon) handleMemory(req Request) Response {
 			for _, ps := range sessions {
 				recentSummaries = append(recentSummaries, SessionSummary{
 					ID:           ps.ID,
+					Name:         ps.Name,
 					Agent:        ps.Agent,
 					CreatedAt:    ps.CreatedAt,
 					LastActivity: ps.LastActivity,
@@ -1278,6 +2120,81 @@ func (d *Daemon) handleMemory(req Request) Response {
 	return resp
 }
 
+// handleForkSession branches a session into a new one
+func (d *Daemon) handleForkSession(req Request) Response {
+	var payload struct {
+		SessionID string `json:"session_id"`
+		AtMessage int    `json:"at_message,omitempty"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	fork, err := d.forkSession(payload.SessionID, payload.AtMessage)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"session_id":  fork.ID,
+		"agent":       fork.Agent,
+		"forked_from": fork.ForkedFrom,
+	})
+	return resp
+}
+
+// handleMergeSessions combines sessions into a new one
+func (d *Daemon) handleMergeSessions(req Request) Response {
+	var payload struct {
+		SessionIDs []string `json:"session_ids"`
+		Name       string   `json:"name,omitempty"`
+		Strategy   string   `json:"strategy,omitempty"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	merged, err := d.mergeSessions(payload.SessionIDs, payload.Name, payload.Strategy)
+	if err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"session_id":    merged.ID,
+		"name":          merged.Name,
+		"merged_from":   merged.MergedFrom,
+		"message_count": len(merged.Messages),
+	})
+	return resp
+}
+
+// handleRenameSession gives a session a human label
+func (d *Daemon) handleRenameSession(req Request) Response {
+	var payload struct {
+		SessionID string `json:"session_id"`
+		Name      string `json:"name"`
+	}
+
+	if err := json.Unmarshal(req.Payload, &payload); err != nil {
+		return NewErrorResponse(req.ID, "Invalid payload: "+err.Error())
+	}
+
+	if err := d.renameSession(payload.SessionID, payload.Name); err != nil {
+		return NewErrorResponse(req.ID, err.Error())
+	}
+
+	resp := NewResponse(req.ID, true)
+	resp.SetData(map[string]interface{}{
+		"session_id": payload.SessionID,
+		"name":       payload.Name,
+	})
+	return resp
+}
+
 func (d *Daemon) handleEnd(req Request) Response {
 	resp := NewResponse(req.ID, true)
 	
@@ -1318,6 +2235,10 @@ func (d *Daemon) handleMemoryShow(req Request, sessionID string) Response {
 			"last_activity": session.LastActivity,
 			"messages":     session.Messages,
 			"command_generated": session.CommandGenerated,
+			"name":         session.Name,
+			"forked_from":  session.ForkedFrom,
+			"merged_from":  session.MergedFrom,
+			"metadata":     session.Metadata,
 		}
 		resp.SetData(data)
 		
@@ -1341,6 +2262,10 @@ func (d *Daemon) handleMemoryShow(req Request, sessionID string) Response {
 				"last_activity": session.LastActivity,
 				"messages":     session.Messages,
 				"command_generated": session.CommandGenerated,
+				"name":         session.Name,
+				"forked_from":  session.ForkedFrom,
+				"merged_from":  session.MergedFrom,
+				"metadata":     session.Metadata,
 			}
 			resp.SetData(data)
 			
@@ -2089,6 +3014,7 @@ func (d *Daemon) generateArtifact(spec *ArtifactSpec) error {
 	}
 	
 	log.Printf("🎨 Artifact generation completed: %s", spec.Name)
+	userRules.Fire("artifact_created", spec.Name, basePath)
 	return nil
 }
 
@@ -0,0 +1,206 @@
+package main
+
+import (
+	"fmt"
+	"log"
+	"sort"
+	"strings"
+	"time"
+)
+
+// newSessionID names a session the daemon creates itself, in the CLI's format
+func newSessionID() string {
+	return fmt.Sprintf("cli-%d", time.Now().UnixNano()/int64(time.Millisecond))
+}
+
+// findSession returns a live session, or the saved one when it isn't loaded
+func (d *Daemon) findSession(sessionID string) (*Session, error) {
+	if session, exists := d.getSession(sessionID); exists {
+		return session, nil
+	}
+	if d.storage == nil {
+		return nil, fmt.Errorf("Session '%s' not found", sessionID)
+	}
+	session, err := d.storage.LoadSession(sessionID)
+	if err != nil {
+		return nil, fmt.Errorf("Session '%s' not found", sessionID)
+	}
+	return session, nil
+}
+
+// snapshotMessages copies a session's messages
+func snapshotMessages(session *Session) []Message {
+	session.mu.Lock()
+	defer session.mu.Unlock()
+	return append([]Message{}, session.Messages...)
+}
+
+// forkSession saves a new session holding a copy of the parent's first
+// atMessage messages (all of them when 0); the parent is left untouched
+func (d *Daemon) forkSession(parentID string, atMessage int) (*Session, error) {
+	if d.storage == nil {
+		return nil, fmt.Errorf("storage not available")
+	}
+	parent, err := d.findSession(parentID)
+	if err != nil {
+		return nil, err
+	}
+
+	messages := snapshotMessages(parent)
+	if atMessage < 0 || atMessage > len(messages) {
+		return nil, fmt.Errorf("Session '%s' has %d messages; can't fork at message %d", parentID, len(messages), atMessage)
+	}
+	if atMessage > 0 {
+		messages = messages[:atMessage]
+	}
+
+	now := time.Now()
+	fork := &Session{
+		ID:           newSessionID(),
+		Agent:        parent.Agent,
+		CreatedAt:    now,
+		LastActivity: now,
+		State:        SessionIdle,
+		Messages:     messages,
+		IdleTimeout:  30 * time.Minute,
+		ForkedFrom: &ForkOrigin{
+			SessionID: parentID,
+			AtMessage: len(messages),
+		},
+	}
+	if err := d.storage.SaveSession(fork); err != nil {
+		return nil, fmt.Errorf("failed to save fork: %v", err)
+	}
+
+	log.Printf("🌿 Forked session %s into %s at message %d", parentID, fork.ID, len(messages))
+	return fork, nil
+}
+
+// mergeSessions saves a new session holding the messages of several others,
+// each tagged with the session it came from. strategy "interleave" orders
+// them by time; otherwise each session's messages follow the previous one's
+func (d *Daemon) mergeSessions(sessionIDs []string, name, strategy string) (*Session, error) {
+	if d.storage == nil {
+		return nil, fmt.Errorf("storage not available")
+	}
+	if strategy != "" && strategy != "concat" && strategy != "interleave" {
+		return nil, fmt.Errorf("unknown merge strategy %q: use concat or interleave", strategy)
+	}
+
+	seen := make(map[string]bool)
+	var sources []*Session
+	for _, id := range sessionIDs {
+		if seen[id] {
+			continue
+		}
+		seen[id] = true
+		session, err := d.findSession(id)
+		if err != nil {
+			return nil, err
+		}
+		sources = append(sources, session)
+	}
+	if len(sources) < 2 {
+		return nil, fmt.Errorf("merging needs at least two different sessions")
+	}
+
+	var messages []Message
+	mergedFrom := make([]string, 0, len(sources))
+	for _, source := range sources {
+		for _, msg := range snapshotMessages(source) {
+			// Messages from an earlier merge keep their original session
+			if msg.SourceSession == "" {
+				msg.SourceSession = source.ID
+			}
+			messages = append(messages, msg)
+		}
+		mergedFrom = append(mergedFrom, source.ID)
+	}
+	if strategy == "interleave" {
+		sort.SliceStable(messages, func(i, j int) bool {
+			return messages[i].Timestamp.Before(messages[j].Timestamp)
+		})
+	}
+
+	now := time.Now()
+	merged := &Session{
+		ID:           newSessionID(),
+		Agent:        sources[0].Agent,
+		Name:         name,
+		CreatedAt:    now,
+		LastActivity: now,
+		State:        SessionIdle,
+		Messages:     messages,
+		IdleTimeout:  30 * time.Minute,
+		MergedFrom:   mergedFrom,
+	}
+	if err := d.storage.SaveSession(merged); err != nil {
+		return nil, fmt.Errorf("failed to save merged session: %v", err)
+	}
+
+	log.Printf("🪢 Merged %d sessions into %s (%d messages)", len(sources), merged.ID, len(messages))
+	return merged, nil
+}
+
+// renameSession sets a session's label and saves it; the ID stays the same
+func (d *Daemon) renameSession(sessionID, name string) error {
+	name = strings.TrimSpace(name)
+	if name == "" {
+		return fmt.Errorf("session name is required")
+	}
+	if d.storage == nil {
+		return fmt.Errorf("storage not available")
+	}
+	session, err := d.findSession(sessionID)
+	if err != nil {
+		return err
+	}
+
+	session.mu.Lock()
+	session.Name = name
+	session.mu.Unlock()
+	if err := d.storage.SaveSession(session); err != nil {
+		return fmt.Errorf("failed to save session: %v", err)
+	}
+
+	log.Printf("✏️ Renamed session %s to '%s'", sessionID, name)
+	return nil
+}
+
+// updateSessionMetadata merges keys into a session's metadata and saves it;
+// a null value removes its key
+func (d *Daemon) updateSessionMetadata(sessionID string, metadata map[string]interface{}) (map[string]interface{}, error) {
+	if len(metadata) == 0 {
+		return nil, fmt.Errorf("no metadata to record")
+	}
+	if d.storage == nil {
+		return nil, fmt.Errorf("storage not available")
+	}
+	session, err := d.findSession(sessionID)
+	if err != nil {
+		return nil, err
+	}
+
+	// Replace rather than modify the map, so saves in flight keep a consistent copy
+	session.mu.Lock()
+	merged := make(map[string]interface{}, len(session.Metadata)+len(metadata))
+	for key, value := range session.Metadata {
+		merged[key] = value
+	}
+	for key, value := range metadata {
+		if value == nil {
+			delete(merged, key)
+		} else {
+			merged[key] = value
+		}
+	}
+	session.Metadata = merged
+	session.mu.Unlock()
+
+	if err := d.storage.SaveSession(session); err != nil {
+		return nil, fmt.Errorf("failed to save session: %v", err)
+	}
+
+	log.Printf("🏷️ Updated metadata of session %s", sessionID)
+	return merged, nil
+}
@@ -176,6 +176,9 @@ func NewStorage(baseDir string, relationStore RelationStore) (*Storage, error) {
 	return s, nil
 }
 
+// trashRoot holds soft-deleted paths until they are restored or deleted
+const trashRoot = "/trash"
+
 // ==================== Core Object Storage ====================
 
 // Store saves content and returns its hash ID
@@ -355,10 +358,18 @@ func (s *Storage) SaveSession(session *Session) error {
 		UpdatedAt:    time.Now(),
 		LastActivity: session.LastActivity,
 		Messages:     session.Messages,
+		Name:         session.Name,
+		ForkedFrom:   session.ForkedFrom,
+		MergedFrom:   session.MergedFrom,
 		Metadata: map[string]interface{}{
 			"agent": session.Agent,
 		},
 	}
+	for key, value := range session.Metadata {
+		if key != "agent" {
+			ps.Metadata[key] = value
+		}
+	}
 	
 	// Add command info if generated
 	if session.CommandGenerated != nil {
@@ -398,6 +409,13 @@ func (s *Storage) SaveSession(session *Session) error {
 		},
 	}
 	
+	// Notes belong to the session, not to one saved state of it
+	if existing, exists := s.sessionIndex.Sessions[session.ID]; exists {
+		if previous, err := s.LoadMetadata(existing.ObjectID); err == nil {
+			metadata.Notes = previous.Notes
+		}
+	}
+	
 	// Store in object store
 	objectID, err := s.StoreWithMetadata(data, metadata)
 	if err != nil {
@@ -468,6 +486,10 @@ func (s *Storage) LoadSession(sessionID string) (*Session, error) {
 		Messages:         ps.Messages,
 		CommandGenerated: nil,
 		IdleTimeout:      30 * time.Minute,
+		Name:             ps.Name,
+		ForkedFrom:       ps.ForkedFrom,
+		MergedFrom:       ps.MergedFrom,
+		Metadata:         ps.Metadata,
 	}
 	
 	// Convert command info if exists
@@ -1163,8 +1185,10 @@ func (s *Storage) HandleUpdatePath(path string, content []byte, metadataUpdates
 			return nil, fmt.Errorf("failed to store new content: %v", err)
 		}
 		
-		// Update metadata to point to new object
+		// Keep the content being replaced as a version, then point to the new object
+		s.recordVersion(meta, newID)
 		meta.ID = newID
+		meta.Size = int64(len(content))
 		meta.Modified = time.Now()
 		
 		// Update symlinks if it's a command
@@ -1200,14 +1224,212 @@ func (s *Storage) HandleUpdatePath(path string, content []byte, metadataUpdates
 	if err := s.SaveMetadata(meta); err != nil {
 		return nil, fmt.Errorf("failed to save metadata: %v", err)
 	}
+	s.retireMetadata(objID, meta.ID)
 	
 	return map[string]interface{}{
 		"id":       meta.ID,
 		"modified": meta.Modified,
 		"paths":    meta.Paths,
+		"version":  meta.CurrentVersion(),
+	}, nil
+}
+
+// recordVersion appends the object's present content to its versions
+// before meta moves on to newID; identical content isn't a new version
+func (s *Storage) recordVersion(meta *Metadata, newID string) {
+	if newID == meta.ID {
+		return
+	}
+	
+	size := meta.Size
+	if info, err := os.Stat(s.GetPath(meta.ID)); err == nil {
+		size = info.Size()
+	}
+	meta.Versions = append(meta.Versions, ObjectVersion{
+		Version:  meta.CurrentVersion(),
+		ObjectID: meta.ID,
+		Created:  meta.currentVersionCreated(),
+		Session:  meta.Session,
+		Agent:    meta.Agent,
+		Size:     size,
+	})
+	meta.VersionCreated = time.Now()
+}
+
+// retireMetadata drops the record the metadata was saved under before its
+// content changed; left behind, it would still resolve the same paths
+func (s *Storage) retireMetadata(oldID, newID string) {
+	if oldID == newID {
+		return
+	}
+	if err := os.Remove(filepath.Join(s.metadataDir, oldID+".json")); err != nil && !os.IsNotExist(err) {
+		log.Printf("Warning: Failed to remove old metadata for %s: %v", oldID, err)
+	}
+}
+
+// VersionObject resolves a path to the object holding one of its versions;
+// version 0 means the current one
+func (s *Storage) VersionObject(path string, version int) (string, int, error) {
+	objID := s.ResolvePath(path)
+	if objID == "" {
+		return "", 0, fmt.Errorf("path not found: %s", path)
+	}
+	if strings.HasPrefix(objID, "relation:") {
+		return "", 0, fmt.Errorf("%s is a tool definition, which keeps no versions", path)
+	}
+	
+	meta, err := s.LoadMetadata(objID)
+	if err != nil {
+		return "", 0, fmt.Errorf("failed to load metadata: %v", err)
+	}
+	if version == 0 || version == meta.CurrentVersion() {
+		return objID, meta.CurrentVersion(), nil
+	}
+	for _, v := range meta.Versions {
+		if v.Version == version {
+			return v.ObjectID, version, nil
+		}
+	}
+	return "", 0, fmt.Errorf("%s has no version %d (latest is %d)", path, version, meta.CurrentVersion())
+}
+
+// versionEntry is how list_versions reports one version
+func versionEntry(v ObjectVersion, current bool) map[string]interface{} {
+	entry := map[string]interface{}{
+		"version": v.Version,
+		"created": v.Created,
+		"size":    v.Size,
+		"current": current,
+	}
+	if v.Session != "" {
+		entry["session_id"] = v.Session
+	}
+	if v.Agent != "" {
+		entry["agent"] = v.Agent
+	}
+	return entry
+}
+
+// HandleListVersions processes list_versions requests
+func (s *Storage) HandleListVersions(path string) (map[string]interface{}, error) {
+	objID := s.ResolvePath(path)
+	if objID == "" {
+		return nil, fmt.Errorf("path not found: %s", path)
+	}
+	
+	versions := []map[string]interface{}{}
+	if strings.HasPrefix(objID, "relation:") {
+		// Tool definitions live in the relation store, which keeps no history
+		versions = append(versions, map[string]interface{}{"version": 1, "current": true})
+		return map[string]interface{}{"path": path, "versions": versions}, nil
+	}
+	
+	meta, err := s.LoadMetadata(objID)
+	if err != nil {
+		return nil, fmt.Errorf("failed to load metadata: %v", err)
+	}
+	for _, v := range meta.Versions {
+		versions = append(versions, versionEntry(v, false))
+	}
+	versions = append(versions, versionEntry(ObjectVersion{
+		Version:  meta.CurrentVersion(),
+		ObjectID: meta.ID,
+		Created:  meta.currentVersionCreated(),
+		Session:  meta.Session,
+		Agent:    meta.Agent,
+		Size:     meta.Size,
+	}, true))
+	
+	return map[string]interface{}{
+		"path":     path,
+		"versions": versions,
+	}, nil
+}
+
+// HandleRollbackVersion processes rollback_version requests. The old
+// content comes back as a new version, so the rollback can be undone too
+func (s *Storage) HandleRollbackVersion(path string, version int) (map[string]interface{}, error) {
+	if version < 1 {
+		return nil, fmt.Errorf("invalid version: %d", version)
+	}
+	versionID, _, err := s.VersionObject(path, version)
+	if err != nil {
+		return nil, err
+	}
+	if versionID == s.ResolvePath(path) {
+		return nil, fmt.Errorf("version %d of %s is already current", version, path)
+	}
+	
+	content, err := s.Read(versionID)
+	if err != nil {
+		return nil, fmt.Errorf("failed to read version %d: %v", version, err)
+	}
+	result, err := s.HandleUpdatePath(path, content, nil)
+	if err != nil {
+		return nil, err
+	}
+	
+	return map[string]interface{}{
+		"path":             path,
+		"restored_version": version,
+		"version":          result["version"],
 	}, nil
 }
 
+// HandleAddNote processes add_note requests
+func (s *Storage) HandleAddNote(path, text string) (map[string]interface{}, error) {
+	if strings.TrimSpace(text) == "" {
+		return nil, fmt.Errorf("note text is required")
+	}
+	meta, err := s.notableMetadata(path)
+	if err != nil {
+		return nil, err
+	}
+	
+	meta.Notes = append(meta.Notes, ObjectNote{Text: text, Created: time.Now()})
+	if err := s.SaveMetadata(meta); err != nil {
+		return nil, fmt.Errorf("failed to save note: %v", err)
+	}
+	
+	return map[string]interface{}{
+		"path":  path,
+		"notes": meta.Notes,
+	}, nil
+}
+
+// HandleListNotes processes list_notes requests
+func (s *Storage) HandleListNotes(path string) (map[string]interface{}, error) {
+	meta, err := s.notableMetadata(path)
+	if err != nil {
+		return nil, err
+	}
+	
+	notes := meta.Notes
+	if notes == nil {
+		notes = []ObjectNote{}
+	}
+	return map[string]interface{}{
+		"path":  path,
+		"notes": notes,
+	}, nil
+}
+
+// notableMetadata loads the metadata notes on path are kept in
+func (s *Storage) notableMetadata(path string) (*Metadata, error) {
+	objID := s.ResolvePath(path)
+	if objID == "" {
+		return nil, fmt.Errorf("path not found: %s", path)
+	}
+	if strings.HasPrefix(objID, "relation:") {
+		return nil, fmt.Errorf("%s is a tool definition, which can't hold notes", path)
+	}
+	meta, err := s.LoadMetadata(objID)
+	if err != nil {
+		return nil, fmt.Errorf("failed to load metadata: %v", err)
+	}
+	return meta, nil
+}
+
 // HandleDeletePath processes delete_path requests
 func (s *Storage) HandleDeletePath(path string) (map[string]interface{}, error) {
 	// Resolve path to object ID
@@ -1257,6 +1479,190 @@ func (s *Storage) HandleDeletePath(path string) (map[string]interface{}, error)
 	}, nil
 }
 
+// HandleTrashPath processes trash_path requests: the path moves under
+// /trash and the object is kept, so restore_path can bring it back
+func (s *Storage) HandleTrashPath(path string) (map[string]interface{}, error) {
+	if path == trashRoot || strings.HasPrefix(path, trashRoot+"/") {
+		return nil, fmt.Errorf("%s is already in the trash; delete it to remove it for good", path)
+	}
+	
+	// Trashing the same path again replaces what was trashed before
+	destination := trashRoot + path
+	objID, err := s.relocatePath(path, destination, false, true)
+	if err != nil {
+		return nil, err
+	}
+	
+	return map[string]interface{}{
+		"operation":   "trash",
+		"source":      path,
+		"destination": destination,
+		"object_id":   objID,
+	}, nil
+}
+
+// HandleRestorePath processes restore_path requests, returning a trashed
+// object to the path it was trashed from unless given another
+func (s *Storage) HandleRestorePath(path, destination string) (map[string]interface{}, error) {
+	if !strings.HasPrefix(path, trashRoot+"/") {
+		path = trashRoot + path
+	}
+	if destination == "" {
+		destination = strings.TrimPrefix(path, trashRoot)
+	}
+	
+	objID, err := s.relocatePath(path, destination, false, false)
+	if err != nil {
+		return nil, err
+	}
+	
+	return map[string]interface{}{
+		"operation":   "restore",
+		"source":      path,
+		"destination": destination,
+		"object_id":   objID,
+	}, nil
+}
+
+// HandleCopyPath processes copy_path requests. Objects are addressed by
+// content, so the copy is the same object reachable from one more path
+func (s *Storage) HandleCopyPath(source, destination string, overwrite bool) (map[string]interface{}, error) {
+	objID, err := s.relocatePath(source, destination, true, overwrite)
+	if err != nil {
+		return nil, err
+	}
+	
+	return map[string]interface{}{
+		"operation":   "copy",
+		"source":      source,
+		"destination": destination,
+		"object_id":   objID,
+	}, nil
+}
+
+// HandleMovePath processes move_path requests
+func (s *Storage) HandleMovePath(source, destination string, overwrite bool) (map[string]interface{}, error) {
+	objID, err := s.relocatePath(source, destination, false, overwrite)
+	if err != nil {
+		return nil, err
+	}
+	
+	return map[string]interface{}{
+		"operation":   "move",
+		"source":      source,
+		"destination": destination,
+		"object_id":   objID,
+	}, nil
+}
+
+// HandleImportObject processes import_object requests from 'port42 import'.
+// onConflict says what to do when the path is taken: skip, overwrite or rename
+func (s *Storage) HandleImportObject(path string, content []byte, metadata map[string]interface{}, onConflict string) (map[string]interface{}, error) {
+	status := "created"
+	if s.ResolvePath(path) != "" {
+		switch onConflict {
+		case "skip":
+			return map[string]interface{}{"path": path, "status": "skipped"}, nil
+		case "overwrite":
+			if _, err := s.HandleDeletePath(path); err != nil {
+				return nil, err
+			}
+			status = "overwritten"
+		case "rename":
+			path = s.freePath(path)
+			status = "renamed"
+		default:
+			return nil, fmt.Errorf("unknown on_conflict %q: use skip, overwrite or rename", onConflict)
+		}
+	}
+	
+	result, err := s.HandleStorePath(path, content, metadata)
+	if err != nil {
+		return nil, err
+	}
+	
+	// Keep what the export recorded beyond what store_path takes
+	if _, err := s.HandleUpdatePath(path, nil, metadata); err != nil {
+		log.Printf("Warning: Failed to restore metadata for %s: %v", path, err)
+	}
+	
+	return map[string]interface{}{
+		"path":   path,
+		"status": status,
+		"id":     result["id"],
+	}, nil
+}
+
+// freePath returns path with the first "-N" suffix (before any extension) not in use
+func (s *Storage) freePath(path string) string {
+	ext := filepath.Ext(path)
+	if strings.Contains(ext, "/") {
+		ext = ""
+	}
+	base := strings.TrimSuffix(path, ext)
+	for n := 1; ; n++ {
+		candidate := fmt.Sprintf("%s-%d%s", base, n, ext)
+		if s.ResolvePath(candidate) == "" {
+			return candidate
+		}
+	}
+}
+
+// relocatePath moves one of an object's virtual paths to another. Content
+// is addressed by hash, so nothing is copied; keepSource leaves the old
+// path in place as well
+func (s *Storage) relocatePath(source, destination string, keepSource, overwrite bool) (string, error) {
+	objID := s.ResolvePath(source)
+	if objID == "" {
+		return "", fmt.Errorf("path not found: %s", source)
+	}
+	if strings.HasPrefix(objID, "relation:") {
+		return "", fmt.Errorf("%s is a tool definition and can't be moved", source)
+	}
+	
+	if existing := s.ResolvePath(destination); existing != "" && existing != objID {
+		if !overwrite {
+			return "", fmt.Errorf("destination exists: %s", destination)
+		}
+		if _, err := s.HandleDeletePath(destination); err != nil {
+			return "", err
+		}
+	}
+	
+	meta, err := s.LoadMetadata(objID)
+	if err != nil {
+		return "", fmt.Errorf("failed to load metadata: %v", err)
+	}
+	
+	paths := []string{}
+	for _, p := range meta.Paths {
+		if p == destination || (p == source && !keepSource) {
+			continue
+		}
+		paths = append(paths, p)
+	}
+	meta.Paths = append(paths, destination)
+	if meta.Lifecycle == "deprecated" {
+		meta.Lifecycle = "active"
+	}
+	
+	if err := s.SaveMetadata(meta); err != nil {
+		return "", fmt.Errorf("failed to update metadata: %v", err)
+	}
+	
+	// Commands are symlinks on disk too, so they run under their new name only
+	if name, ok := commandName(source); ok && !keepSource {
+		s.removeCommandSymlink(name)
+	}
+	if name, ok := commandName(destination); ok {
+		if err := s.CreateCommandSymlink(objID, name); err != nil {
+			log.Printf("Warning: Failed to create symlink for command %s: %v", name, err)
+		}
+	}
+	
+	return objID, nil
+}
+
 // HandleCreateMemory processes create_memory requests
 func (s *Storage) HandleCreateMemory(agent, initialMessage string) (map[string]interface{}, error) {
 	// Generate memory ID
@@ -1390,6 +1796,15 @@ func (s *Storage) removeCommandSymlink(cmdName string) error {
 	return os.Remove(linkPath)
 }
 
+// commandName is the command a /commands/<name> path stands for
+func commandName(path string) (string, bool) {
+	name := strings.TrimPrefix(path, "/commands/")
+	if name == path || name == "" || strings.Contains(name, "/") {
+		return "", false
+	}
+	return name, true
+}
+
 func generateMemoryID() string {
 	return fmt.Sprintf("mem-%d", time.Now().Unix())
 }
@@ -0,0 +1,116 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"io/ioutil"
+	"log"
+	"os"
+	"path/filepath"
+	"sync"
+)
+
+// How far each piece of feedback moves a suggestion's confidence
+const feedbackStep = 0.05
+
+// Suggestions that fall below this confidence are no longer made
+const minSuggestionConfidence = 0.3
+
+// SuggestionTally is the feedback one kind of suggestion has had
+type SuggestionTally struct {
+	Accepted  int `json:"accepted"`
+	Declined  int `json:"declined"`
+	Succeeded int `json:"succeeded"`
+	Failed    int `json:"failed"`
+}
+
+// adjustment is how far the tally moves the suggestion's base confidence
+func (t *SuggestionTally) adjustment() float64 {
+	return feedbackStep * float64(t.Succeeded-t.Declined-t.Failed)
+}
+
+// SuggestionFeedback keeps suggestion feedback in ~/.port42/suggestion_feedback.json.
+// Suggestions are tallied by reason: commands carry session IDs and tool
+// names, but the reason names the kind of suggestion
+type SuggestionFeedback struct {
+	path    string
+	tallies map[string]*SuggestionTally
+	mu      sync.Mutex
+}
+
+var suggestionFeedback *SuggestionFeedback
+
+// LoadSuggestionFeedback reads the feedback kept in baseDir
+func LoadSuggestionFeedback(baseDir string) *SuggestionFeedback {
+	store := &SuggestionFeedback{
+		path:    filepath.Join(baseDir, "suggestion_feedback.json"),
+		tallies: make(map[string]*SuggestionTally),
+	}
+
+	data, err := ioutil.ReadFile(store.path)
+	if err == nil {
+		if err := json.Unmarshal(data, &store.tallies); err != nil {
+			log.Printf("⚠️ Failed to parse %s: %v", store.path, err)
+		}
+	} else if !os.IsNotExist(err) {
+		log.Printf("⚠️ Failed to read %s: %v", store.path, err)
+	}
+	return store
+}
+
+// Record counts one suggestion being taken up or passed over; exitCode is
+// set when the suggested command was run
+func (s *SuggestionFeedback) Record(reason string, accepted bool, exitCode *int) error {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	tally, exists := s.tallies[reason]
+	if !exists {
+		tally = &SuggestionTally{}
+		s.tallies[reason] = tally
+	}
+	if !accepted {
+		tally.Declined++
+	} else {
+		tally.Accepted++
+		if exitCode != nil && *exitCode != 0 {
+			tally.Failed++
+		} else {
+			tally.Succeeded++
+		}
+	}
+
+	data, err := json.MarshalIndent(s.tallies, "", "  ")
+	if err != nil {
+		return fmt.Errorf("failed to encode suggestion feedback: %w", err)
+	}
+	if err := ioutil.WriteFile(s.path, data, 0644); err != nil {
+		return fmt.Errorf("failed to write suggestion feedback: %w", err)
+	}
+	return nil
+}
+
+// Apply moves each suggestion's confidence by its feedback and drops the
+// ones users keep passing over or that keep failing
+func (s *SuggestionFeedback) Apply(suggestions []ContextSuggestion) []ContextSuggestion {
+	if s == nil {
+		return suggestions
+	}
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	kept := suggestions[:0]
+	for _, suggestion := range suggestions {
+		if tally, exists := s.tallies[suggestion.Reason]; exists {
+			suggestion.Confidence += tally.adjustment()
+			if suggestion.Confidence > 0.99 {
+				suggestion.Confidence = 0.99
+			}
+			if suggestion.Confidence < minSuggestionConfidence {
+				continue
+			}
+		}
+		kept = append(kept, suggestion)
+	}
+	return kept
+}
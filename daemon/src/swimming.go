@@ -57,6 +57,7 @@ func getMapKeys(m map[string]interface{}) []string {
 type AnthropicClient struct {
 	apiKey     string
 	apiURL     string
+	model      string // chosen with --model; empty uses the agent's model
 	httpClient *http.Client
 	lastRequest time.Time
 	requestMutex sync.Mutex
@@ -81,17 +82,29 @@ type AnthropicTool struct {
 	InputSchema map[string]interface{} `json:"input_schema"`
 }
 
-// AnthropicResponse represents Claude's response
+// AnthropicResponse represents Claude's response; other providers' replies
+// are converted to it
 type AnthropicResponse struct {
-	Content []struct {
-		Type  string          `json:"type"`
-		Text  string          `json:"text,omitempty"`
-		ID    string          `json:"id,omitempty"` // Tool use ID
-		Name  string          `json:"name,omitempty"`
-		Input json.RawMessage `json:"input,omitempty"`
-	} `json:"content"`
-	Error      *AnthropicError `json:"error,omitempty"`
-	StopReason string          `json:"stop_reason,omitempty"`
+	Content    []AnthropicContent `json:"content"`
+	Error      *AnthropicError    `json:"error,omitempty"`
+	StopReason string             `json:"stop_reason,omitempty"`
+	Model      string             `json:"model,omitempty"`
+	Usage      AnthropicUsage     `json:"usage"`
+}
+
+// AnthropicContent is one block of a reply: text or a tool call
+type AnthropicContent struct {
+	Type  string          `json:"type"`
+	Text  string          `json:"text,omitempty"`
+	ID    string          `json:"id,omitempty"` // Tool use ID
+	Name  string          `json:"name,omitempty"`
+	Input json.RawMessage `json:"input,omitempty"`
+}
+
+// AnthropicUsage is the token count Claude reports for one call
+type AnthropicUsage struct {
+	InputTokens  int `json:"input_tokens"`
+	OutputTokens int `json:"output_tokens"`
 }
 
 // AnthropicError for API errors
@@ -154,6 +167,24 @@ func NewAnthropicClient() *AnthropicClient {
 	}
 }
 
+// modelID is the model to ask for: the one chosen for the request, else the agent's
+func (c *AnthropicClient) modelID(modelDef *ModelDefinition) string {
+	if c.model != "" {
+		return c.model
+	}
+	return modelDef.ID
+}
+
+// Provider names the backend for usage records
+func (c *AnthropicClient) Provider() string {
+	return "anthropic"
+}
+
+// Configured reports whether the client has an API key
+func (c *AnthropicClient) Configured() bool {
+	return c.apiKey != ""
+}
+
 // AnthropicMessage is the format Anthropic expects
 type AnthropicMessage struct {
 	Role    string      `json:"role"`
@@ -223,7 +254,7 @@ func (c *AnthropicClient) SendWithoutTools(messages []Message, systemPrompt stri
 		agentName, modelDef.ID, modelDef.Name, modelDef.Temperature)
 	
 	req := AnthropicRequest{
-		Model:       modelDef.ID,
+		Model:       c.modelID(modelDef),
 		System:      systemPrompt,
 		Messages:    anthropicMessages,
 		MaxTokens:   responseConfig.MaxTokens,
@@ -404,7 +435,7 @@ func (c *AnthropicClient) Send(messages []Message, systemPrompt string, agentNam
 	log.Printf("🔍 Checking tools for agent: %s (clean: %s), config exists: %v", agentName, cleanName, agentConfig != nil)
 	
 	if agentConfig != nil {
-		if _, exists := agentConfig.Agents[cleanName]; exists {
+		if _, exists := lookupAgent(cleanName); exists {
 			log.Printf("🔍 Agent %s found", cleanName)
 			// All agents get the same tools - the guidance controls what they do with them
 			tools = []AnthropicTool{
@@ -424,7 +455,7 @@ func (c *AnthropicClient) Send(messages []Message, systemPrompt string, agentNam
 		agentName, modelDef.ID, modelDef.Name, modelDef.Temperature)
 	
 	req := AnthropicRequest{
-		Model:       modelDef.ID,
+		Model:       c.modelID(modelDef),
 		System:      systemPrompt,
 		Messages:    anthropicMessages,
 		MaxTokens:   responseConfig.MaxTokens,
@@ -646,6 +677,7 @@ func (d *Daemon) handleSwimWithAI(req Request) Response {
 		// New session (no messages yet)
 		memoryPath := fmt.Sprintf("/memory/%s", sessionID)
 		d.contextCollector.TrackMemoryAccess(memoryPath, "created")
+		userRules.Fire("memory_created", sessionID, memoryPath)
 	}
 	
 	log.Printf("🔍 Session loaded: ID=%s, MessageCount=%d", session.ID, len(session.Messages))
@@ -660,7 +692,7 @@ func (d *Daemon) handleSwimWithAI(req Request) Response {
 	session.LastActivity = time.Now()
 	
 	// Get agent prompt
-	agentPrompt := getAgentPrompt(payload.Agent)
+	agentPrompt := getAgentPrompt(payload.Agent) + formatAgentGuidance(payload.AgentGuidance)
 	
 	// Process references using common reference handler
 	if len(req.References) > 0 && d.referenceHandler != nil {
@@ -689,6 +721,11 @@ func (d *Daemon) handleSwimWithAI(req Request) Response {
 		agentPrompt = agentPrompt + memorySection
 	}
 	
+	// Tell the AI which project the user is in, if the CLI sent it
+	if req.SessionContext != nil && req.SessionContext.Workspace != nil {
+		agentPrompt = agentPrompt + formatWorkspace(req.SessionContext.Workspace)
+	}
+	
 	// Build conversation history (without system prompt)
 	messages := d.buildConversationContext(session, payload.Agent)
 	session.mu.Unlock()
@@ -700,24 +737,37 @@ func (d *Daemon) handleSwimWithAI(req Request) Response {
 		go d.storage.SaveSession(session)
 	}
 	
-	// Call Claude
-	aiClient := NewAnthropicClient()
-	log.Printf("🔍 AI client created, has API key: %v", aiClient.apiKey != "")
+	// Call the provider the CLI chose; Claude when it chose none
+	aiClient, err := NewAIClient(req.Provider)
+	if err != nil {
+		d.rollbackUserMessage(session, payload.Message)
+		resp.SetError(err.Error())
+		return resp
+	}
+	log.Printf("🔍 AI client created for %s, configured: %v", aiClient.Provider(), aiClient.Configured())
 	
-	if aiClient.apiKey == "" {
+	if !aiClient.Configured() {
 		// No API key - return error
 		log.Printf("❌ No API key available - cannot process AI request")
-		resp.SetError("API_KEY_ERROR: No API key found. Please set PORT42_ANTHROPIC_API_KEY or ANTHROPIC_API_KEY and restart the daemon")
+		d.rollbackUserMessage(session, payload.Message)
+		if aiClient.Provider() == "anthropic" {
+			resp.SetError("API_KEY_ERROR: No API key found. Please set PORT42_ANTHROPIC_API_KEY or ANTHROPIC_API_KEY and restart the daemon")
+		} else {
+			resp.SetError(missingKeyError(aiClient.Provider()))
+		}
 		return resp
 	}
 	
-	log.Printf("🤖 Using REAL AI handler with Claude")
+	log.Printf("🤖 Using REAL AI handler with %s", aiClient.Provider())
 	
 	log.Printf("🔍 Sending to AI with %d messages in context", len(messages))
 	aiResp, err := aiClient.Send(messages, agentPrompt, payload.Agent)
 	if err != nil {
 		log.Printf("AI error: %v", err)
 		
+		// The CLI resends the same message on retry or fallback
+		d.rollbackUserMessage(session, payload.Message)
+		
 		// Classify error by source for better user messaging
 		errorMsg := err.Error()
 		if strings.Contains(errorMsg, "api_error") || strings.Contains(errorMsg, "Overloaded") || strings.Contains(errorMsg, "rate_limit") {
@@ -732,12 +782,27 @@ func (d *Daemon) handleSwimWithAI(req Request) Response {
 		return resp
 	}
 	log.Printf("🔍 Got AI response")
+	usage := aiResp.Usage
 	
 	// Extract response text and check for tool calls
 	var responseText string
 	var artifactSpec *ArtifactSpec
 	var toolResults []map[string]interface{} // Track tool results for continuation
 	
+	// Stream whatever responseText has gained since the last flush, a line per chunk
+	streamed := 0
+	flushChunks := func() {
+		if !payload.Stream || len(responseText) <= streamed {
+			return
+		}
+		for _, line := range strings.SplitAfter(responseText[streamed:], "\n") {
+			if line != "" {
+				req.Emit(map[string]interface{}{"id": req.ID, "chunk": line})
+			}
+		}
+		streamed = len(responseText)
+	}
+	
 	// Log the full AI response structure for debugging
 	log.Printf("🔍 [DEBUG] AI Response Content Array Length: %d", len(aiResp.Content))
 	for i, content := range aiResp.Content {
@@ -837,6 +902,9 @@ func (d *Daemon) handleSwimWithAI(req Request) Response {
 		
 	}
 	
+	// Show the first answer while the continuation is generated
+	flushChunks()
+	
 	// If we have tool results, call Claude again for continuation
 	if len(toolResults) > 0 {
 		log.Printf("🔄 [CONTINUATION] Found %d tool results, calling Claude for continuation", len(toolResults))
@@ -894,6 +962,8 @@ func (d *Daemon) handleSwimWithAI(req Request) Response {
 			log.Printf("❌ [CONTINUATION] Failed to get continuation: %v", err)
 		} else {
 			log.Printf("✅ [CONTINUATION] Got continuation response")
+			usage.InputTokens += continuationResp.Usage.InputTokens
+			usage.OutputTokens += continuationResp.Usage.OutputTokens
 			
 			// Process continuation response
 			if len(continuationResp.Content) > 0 {
@@ -909,6 +979,8 @@ func (d *Daemon) handleSwimWithAI(req Request) Response {
 		}
 	}
 	
+	flushChunks()
+	
 	// Final response check
 	log.Printf("🔍 [DEBUG] Final responseText length: %d chars", len(responseText))
 	if len(responseText) > 500 {
@@ -954,11 +1026,22 @@ func (d *Daemon) handleSwimWithAI(req Request) Response {
 		go d.storage.SaveSession(session)
 	}
 	
+	usageLedger.Record(UsageEntry{
+		Timestamp:    time.Now(),
+		SessionID:    session.ID,
+		Agent:        payload.Agent,
+		Provider:     aiClient.Provider(),
+		Model:        aiResp.Model,
+		InputTokens:  usage.InputTokens,
+		OutputTokens: usage.OutputTokens,
+	})
+	
 	// Prepare response
 	data := map[string]interface{}{
 		"message":    responseText,
 		"agent":      payload.Agent,
 		"session_id": session.ID,
+		"usage":      usage, // Real token counts, so the CLI doesn't have to estimate
 	}
 	
 	
@@ -992,6 +1075,25 @@ func (d *Daemon) handleSwimWithAI(req Request) Response {
 	return resp
 }
 
+// rollbackUserMessage drops a user turn the AI never answered
+func (d *Daemon) rollbackUserMessage(session *Session, message string) {
+	session.mu.Lock()
+	last := len(session.Messages) - 1
+	if last < 0 || session.Messages[last].Role != "user" || session.Messages[last].Content != message {
+		session.mu.Unlock()
+		return
+	}
+	session.Messages = session.Messages[:last]
+	session.mu.Unlock()
+	
+	log.Printf("↩️ Rolled back unanswered message in session %s", session.ID)
+	if d.storage != nil {
+		if err := d.storage.SaveSession(session); err != nil {
+			log.Printf("⚠️ Failed to save session after rollback: %v", err)
+		}
+	}
+}
+
 // Build conversation context (without system prompt which is now separate)
 func (d *Daemon) buildConversationContext(session *Session, agent string) []Message {
 	messages := []Message{}
@@ -1414,4 +1516,32 @@ func init() {
 	} else {
 		log.Println("❌ NO API KEY - AI features disabled!")
 	}
-}
\ No newline at end of file
+}
+
+// formatAgentGuidance appends instructions the CLI defines locally for an agent
+func formatAgentGuidance(guidance string) string {
+	guidance = strings.TrimSpace(guidance)
+	if guidance == "" {
+		return ""
+	}
+	return "\n\n--- AGENT GUIDANCE ---\n" + guidance + "\n--- END AGENT GUIDANCE ---\n"
+}
+
+// formatWorkspace renders the CLI's workspace context for the system prompt
+func formatWorkspace(ws *WorkspaceContext) string {
+	section := "\n\n--- WORKSPACE ---\n"
+	section += fmt.Sprintf("The user is working in %s", ws.Cwd)
+	if ws.GitBranch != "" {
+		section += fmt.Sprintf(" (git branch %s)", ws.GitBranch)
+	}
+	section += ".\n"
+	if len(ws.Files) > 0 {
+		section += "Top-level entries: " + strings.Join(ws.Files, ", ")
+		if ws.Omitted > 0 {
+			section += fmt.Sprintf(" and %d more", ws.Omitted)
+		}
+		section += "\n"
+	}
+	section += "--- END WORKSPACE ---\n"
+	return section
+}
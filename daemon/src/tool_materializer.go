@@ -75,6 +75,7 @@ func (tm *ToolMaterializer) Materialize(relation Relation) (*MaterializedEntity,
 	} else {
 		log.Printf("⚠️ Context collector is nil, cannot track tool: %s", name)
 	}
+	userRules.Fire("tool_created", name, "/commands/"+name)
 	
 	// Get the canonical object ID for the executable
 	executableID, err := tm.storage.Store([]byte(code))
@@ -216,11 +217,18 @@ func (tm *ToolMaterializer) generateToolCode(name string, transforms []string, r
 		},
 	}
 	
-	// Get agent prompt for tool creation (reuse existing logic)
-	agentPrompt := getAgentPrompt("@ai-engineer")
+	// Get agent prompt for tool creation (reuse existing logic); declare --agent picks another agent
+	agent := "@ai-engineer"
+	if declared, ok := relation.Properties["agent"].(string); ok && declared != "" {
+		agent = declared
+	}
+	agentPrompt := getAgentPrompt(agent)
+	if guidance, ok := relation.Properties["agent_guidance"].(string); ok {
+		agentPrompt = agentPrompt + formatAgentGuidance(guidance)
+	}
 	
 	// Use SendWithoutTools for pure text generation (we want JSON, not tool execution)
-	response, err := tm.aiClient.SendWithoutTools(messages, agentPrompt, "@ai-engineer")
+	response, err := tm.aiClient.SendWithoutTools(messages, agentPrompt, agent)
 	if err != nil {
 		return nil, "", fmt.Errorf("AI code generation failed: %w", err)
 	}
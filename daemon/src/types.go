@@ -41,6 +41,13 @@ type Metadata struct {
 	Summary    string    `json:"summary,omitempty"`
 	Embeddings []float32 `json:"embeddings,omitempty"`
 	
+	// Versions: earlier contents, oldest first; the current one is len(Versions)+1
+	Versions       []ObjectVersion `json:"versions,omitempty"`
+	VersionCreated time.Time       `json:"version_created,omitempty"`
+	
+	// Notes: freeform annotations added with 'port42 note'
+	Notes []ObjectNote `json:"notes,omitempty"`
+	
 	// Relationships
 	Relationships struct {
 		Session           string   `json:"session,omitempty"`
@@ -51,6 +58,35 @@ type Metadata struct {
 	} `json:"relationships,omitempty"`
 }
 
+// ObjectVersion is content an object had before update_path replaced it
+type ObjectVersion struct {
+	Version  int       `json:"version"`
+	ObjectID string    `json:"object_id"`
+	Created  time.Time `json:"created"`
+	Session  string    `json:"session,omitempty"`
+	Agent    string    `json:"agent,omitempty"`
+	Size     int64     `json:"size,omitempty"`
+}
+
+// ObjectNote is one annotation on an object
+type ObjectNote struct {
+	Text    string    `json:"text"`
+	Created time.Time `json:"created"`
+}
+
+// CurrentVersion is the version number of the object's present content
+func (m *Metadata) CurrentVersion() int {
+	return len(m.Versions) + 1
+}
+
+// currentVersionCreated is when the present content was written
+func (m *Metadata) currentVersionCreated() time.Time {
+	if m.VersionCreated.IsZero() {
+		return m.Created
+	}
+	return m.VersionCreated
+}
+
 // SessionReference points to the current object for a session
 type SessionReference struct {
 	ObjectID         string    `json:"object_id"`
@@ -73,9 +109,18 @@ type PersistentSession struct {
 	LastActivity     time.Time              `json:"last_activity"`
 	Messages         []Message              `json:"messages"`
 	CommandGenerated *CommandGenerationInfo `json:"command_generated,omitempty"`
+	Name             string                 `json:"name,omitempty"`
+	ForkedFrom       *ForkOrigin            `json:"forked_from,omitempty"`
+	MergedFrom       []string               `json:"merged_from,omitempty"`
 	Metadata         map[string]interface{} `json:"metadata,omitempty"`
 }
 
+// ForkOrigin is the session and message count a fork was taken from
+type ForkOrigin struct {
+	SessionID string `json:"session_id"`
+	AtMessage int    `json:"at_message"`
+}
+
 // CommandGenerationInfo stores info about a generated command
 type CommandGenerationInfo struct {
 	Name      string    `json:"name"`
@@ -94,6 +139,7 @@ type MemoryStats struct {
 // SessionSummary provides a lightweight view of a session
 type SessionSummary struct {
 	ID           string    `json:"id"`
+	Name         string    `json:"name,omitempty"`
 	Agent        string    `json:"agent"`
 	CreatedAt    time.Time `json:"created_at"`
 	LastActivity time.Time `json:"last_activity"`
@@ -0,0 +1,131 @@
+package main
+
+import (
+	"bufio"
+	"encoding/json"
+	"fmt"
+	"log"
+	"os"
+	"path/filepath"
+	"sort"
+	"sync"
+	"time"
+)
+
+// UsageEntry is the token count of one AI exchange
+type UsageEntry struct {
+	Timestamp    time.Time `json:"timestamp"`
+	SessionID    string    `json:"session_id"`
+	Agent        string    `json:"agent"`
+	Provider     string    `json:"provider"`
+	Model        string    `json:"model,omitempty"`
+	InputTokens  int       `json:"input_tokens"`
+	OutputTokens int       `json:"output_tokens"`
+}
+
+// UsageRecord totals one session's entries for one day, as 'port42 usage' reads them
+type UsageRecord struct {
+	SessionID    string `json:"session_id"`
+	Agent        string `json:"agent"`
+	Provider     string `json:"provider"`
+	Model        string `json:"model,omitempty"`
+	Date         string `json:"date"`      // YYYY-MM-DD
+	Timestamp    string `json:"timestamp"` // latest exchange, RFC 3339
+	InputTokens  int    `json:"input_tokens"`
+	OutputTokens int    `json:"output_tokens"`
+}
+
+// UsageLedger appends usage entries to ~/.port42/usage.jsonl
+type UsageLedger struct {
+	path string
+	mu   sync.Mutex
+}
+
+var usageLedger *UsageLedger
+
+// NewUsageLedger creates a ledger in baseDir
+func NewUsageLedger(baseDir string) *UsageLedger {
+	return &UsageLedger{path: filepath.Join(baseDir, "usage.jsonl")}
+}
+
+// Record appends one exchange
+func (l *UsageLedger) Record(entry UsageEntry) {
+	if l == nil {
+		return
+	}
+	line, err := json.Marshal(entry)
+	if err != nil {
+		log.Printf("⚠️ Failed to encode usage: %v", err)
+		return
+	}
+
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	file, err := os.OpenFile(l.path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0644)
+	if err != nil {
+		log.Printf("⚠️ Failed to open usage ledger: %v", err)
+		return
+	}
+	defer file.Close()
+	if _, err := file.Write(append(line, '\n')); err != nil {
+		log.Printf("⚠️ Failed to record usage: %v", err)
+	}
+}
+
+// Records totals the entries in [since, until) by session and day; zero times are open bounds
+func (l *UsageLedger) Records(since, until time.Time) ([]UsageRecord, error) {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+
+	file, err := os.Open(l.path)
+	if os.IsNotExist(err) {
+		return []UsageRecord{}, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("failed to read usage ledger: %w", err)
+	}
+	defer file.Close()
+
+	totals := make(map[string]*UsageRecord)
+	latest := make(map[string]time.Time)
+	scanner := bufio.NewScanner(file)
+	for scanner.Scan() {
+		var entry UsageEntry
+		if err := json.Unmarshal(scanner.Bytes(), &entry); err != nil {
+			continue
+		}
+		if (!since.IsZero() && entry.Timestamp.Before(since)) || (!until.IsZero() && !entry.Timestamp.Before(until)) {
+			continue
+		}
+
+		date := entry.Timestamp.UTC().Format("2006-01-02")
+		key := entry.SessionID + "|" + date
+		record, exists := totals[key]
+		if !exists {
+			record = &UsageRecord{
+				SessionID: entry.SessionID,
+				Agent:     entry.Agent,
+				Provider:  entry.Provider,
+				Model:     entry.Model,
+				Date:      date,
+			}
+			totals[key] = record
+		}
+		record.InputTokens += entry.InputTokens
+		record.OutputTokens += entry.OutputTokens
+		if entry.Timestamp.After(latest[key]) {
+			latest[key] = entry.Timestamp
+			record.Timestamp = entry.Timestamp.UTC().Format(time.RFC3339)
+		}
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, fmt.Errorf("failed to read usage ledger: %w", err)
+	}
+
+	records := make([]UsageRecord, 0, len(totals))
+	for _, record := range totals {
+		records = append(records, *record)
+	}
+	sort.Slice(records, func(i, j int) bool { return records[i].Timestamp < records[j].Timestamp })
+	return records, nil
+}
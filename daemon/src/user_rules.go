@@ -0,0 +1,195 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"io/ioutil"
+	"log"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+)
+
+// Events a user rule can react to
+var userRuleEvents = map[string]bool{
+	"tool_created":     true,
+	"artifact_created": true,
+	"memory_created":   true,
+	"command_run":      true,
+}
+
+// UserRuleTrigger says which event a rule reacts to
+type UserRuleTrigger struct {
+	Event   string `json:"event"`
+	Pattern string `json:"pattern,omitempty"` // glob on the subject's name
+}
+
+// UserRuleAction is the shell command a rule runs; {name} and {path} name the subject
+type UserRuleAction struct {
+	Command string `json:"command"`
+}
+
+// UserRule is a rule added from the CLI, unlike the built-in relation rules in rules.go
+type UserRule struct {
+	ID          string          `json:"id"`
+	Name        string          `json:"name"`
+	Description string          `json:"description,omitempty"`
+	Enabled     bool            `json:"enabled"`
+	Trigger     UserRuleTrigger `json:"trigger"`
+	Action      UserRuleAction  `json:"action"`
+	FireCount   int64           `json:"fire_count"`
+	LastFired   string          `json:"last_fired,omitempty"`
+	CreatedAt   string          `json:"created_at,omitempty"`
+}
+
+// UserRuleStore persists user rules to ~/.port42/rules.json and fires them
+type UserRuleStore struct {
+	path  string
+	rules []UserRule
+	mu    sync.Mutex
+}
+
+var userRules *UserRuleStore
+
+// LoadUserRules loads the rules saved in baseDir
+func LoadUserRules(baseDir string) *UserRuleStore {
+	store := &UserRuleStore{path: filepath.Join(baseDir, "rules.json")}
+
+	data, err := ioutil.ReadFile(store.path)
+	if err == nil {
+		if err := json.Unmarshal(data, &store.rules); err != nil {
+			log.Printf("⚠️ Failed to parse %s: %v", store.path, err)
+		}
+	} else if !os.IsNotExist(err) {
+		log.Printf("⚠️ Failed to read %s: %v", store.path, err)
+	}
+
+	log.Printf("✅ Loaded %d user rules", len(store.rules))
+	return store
+}
+
+// Add validates a rule, assigns its ID and saves it
+func (s *UserRuleStore) Add(rule UserRule) (UserRule, error) {
+	if strings.TrimSpace(rule.Name) == "" {
+		return rule, fmt.Errorf("rule name is required")
+	}
+	if !userRuleEvents[rule.Trigger.Event] {
+		return rule, fmt.Errorf("unknown rule event: %s", rule.Trigger.Event)
+	}
+	if strings.TrimSpace(rule.Action.Command) == "" {
+		return rule, fmt.Errorf("rule action needs a command")
+	}
+	if rule.Trigger.Pattern != "" {
+		if _, err := filepath.Match(rule.Trigger.Pattern, ""); err != nil {
+			return rule, fmt.Errorf("invalid pattern %q: %v", rule.Trigger.Pattern, err)
+		}
+	}
+
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	for _, existing := range s.rules {
+		if existing.Name == rule.Name {
+			return rule, fmt.Errorf("a rule named %s already exists", rule.Name)
+		}
+	}
+
+	now := time.Now()
+	rule.ID = fmt.Sprintf("rule-%d", now.UnixNano())
+	rule.CreatedAt = now.Format(time.RFC3339)
+	rule.FireCount = 0
+	rule.LastFired = ""
+	s.rules = append(s.rules, rule)
+	return rule, s.save()
+}
+
+// SetEnabled switches a rule on or off
+func (s *UserRuleStore) SetEnabled(id string, enabled bool) (UserRule, error) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	for i := range s.rules {
+		if s.rules[i].ID == id {
+			s.rules[i].Enabled = enabled
+			return s.rules[i], s.save()
+		}
+	}
+	return UserRule{}, fmt.Errorf("rule not found: %s", id)
+}
+
+// Delete removes a rule
+func (s *UserRuleStore) Delete(id string) error {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	for i := range s.rules {
+		if s.rules[i].ID == id {
+			s.rules = append(s.rules[:i], s.rules[i+1:]...)
+			return s.save()
+		}
+	}
+	return fmt.Errorf("rule not found: %s", id)
+}
+
+// List returns copies of the rules sorted by name
+func (s *UserRuleStore) List() []UserRule {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	rules := append([]UserRule{}, s.rules...)
+	sort.Slice(rules, func(i, j int) bool { return rules[i].Name < rules[j].Name })
+	return rules
+}
+
+// Fire runs every enabled rule matching the event in the background
+func (s *UserRuleStore) Fire(event, name, path string) {
+	if s == nil {
+		return
+	}
+
+	s.mu.Lock()
+	var commands []string
+	for i := range s.rules {
+		rule := &s.rules[i]
+		if !rule.Enabled || rule.Trigger.Event != event {
+			continue
+		}
+		if rule.Trigger.Pattern != "" {
+			if matched, _ := filepath.Match(rule.Trigger.Pattern, name); !matched {
+				continue
+			}
+		}
+		rule.FireCount++
+		rule.LastFired = time.Now().Format(time.RFC3339)
+		command := strings.ReplaceAll(rule.Action.Command, "{name}", name)
+		commands = append(commands, strings.ReplaceAll(command, "{path}", path))
+		log.Printf("⚡ Rule '%s' fired on %s %s", rule.Name, event, name)
+	}
+	if len(commands) > 0 {
+		if err := s.save(); err != nil {
+			log.Printf("⚠️ %v", err)
+		}
+	}
+	s.mu.Unlock()
+
+	for _, command := range commands {
+		go func(command string) {
+			output, err := exec.Command("sh", "-c", command).CombinedOutput()
+			if err != nil {
+				log.Printf("❌ Rule command failed: %s: %v\n%s", command, err, output)
+			}
+		}(command)
+	}
+}
+
+// save writes the rules; callers hold the lock
+func (s *UserRuleStore) save() error {
+	data, err := json.MarshalIndent(s.rules, "", "  ")
+	if err != nil {
+		return fmt.Errorf("failed to encode rules: %w", err)
+	}
+	if err := ioutil.WriteFile(s.path, data, 0644); err != nil {
+		return fmt.Errorf("failed to write rules: %w", err)
+	}
+	return nil
+}
@@ -328,7 +328,7 @@ build_from_source() {
     cd daemon/src
     # Run go mod tidy first to ensure dependencies are up to date
     go mod tidy >/dev/null 2>&1 || true
-    go build -o "$temp_repo/bin/port42d" .
+    go build -ldflags "-X main.Version=$(cat "$temp_repo/version.txt" 2>/dev/null || echo dev)" -o "$temp_repo/bin/port42d" .
     cd ../..
     
     # Build CLI